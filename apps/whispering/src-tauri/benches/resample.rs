@@ -0,0 +1,61 @@
+//! Cost of the live capture path on a minute of audio, in the blocks a
+//! device callback delivers: `cargo bench --bench resample`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use whispering_lib::recorder::input::{InputChannel, InputConverter};
+use whispering_lib::recorder::resample::LinearResampler;
+
+/// Samples per callback at 48 kHz, a typical device buffer
+const BLOCK: usize = 480;
+
+fn signal(rate: u32, channels: usize) -> Vec<f32> {
+    (0..rate as usize * 60 * channels)
+        .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / rate as f32).sin())
+        .collect()
+}
+
+fn resample(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resample");
+    for source_rate in [44_100, 48_000] {
+        let input = signal(source_rate, 1);
+        group.throughput(Throughput::Elements(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("linear_to_16k", source_rate),
+            &input,
+            |b, input| {
+                let mut output = Vec::new();
+                b.iter(|| {
+                    let mut resampler = LinearResampler::new(source_rate, 16_000);
+                    for block in input.chunks(BLOCK) {
+                        output.clear();
+                        resampler.process_into(black_box(block), &mut output);
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn convert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convert");
+    let input = signal(48_000, 2);
+    group.throughput(Throughput::Elements(input.len() as u64));
+    for (name, channel) in [("mix", InputChannel::Mix), ("left", InputChannel::Left)] {
+        group.bench_with_input(BenchmarkId::new("stereo_48k_to_16k", name), &input, |b, input| {
+            let mut output = Vec::new();
+            b.iter(|| {
+                let mut converter = InputConverter::new(2, Some(channel), 48_000, 16_000)
+                    .expect("rates differ");
+                for block in input.chunks(BLOCK * 2) {
+                    output.clear();
+                    converter.process_into(black_box(block), &mut output);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, resample, convert);
+criterion_main!(benches);
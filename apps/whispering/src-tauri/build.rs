@@ -1,3 +1,66 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Where the frontend's event types are written, relative to this crate
+const EVENTS_TS: &str = "../src/lib/events/catalog.generated.ts";
+
+/// Reads `src/events/catalog.rs` for each event's name, TypeScript payload
+/// and doc comment; the Rust payload types are parsed but not needed here.
+/// Events behind a cargo feature are typed either way, since the frontend
+/// is the same for every build.
+macro_rules! catalog {
+    ($(
+        $(#[doc = $doc:literal])*
+        $(#[cfg($cfg:meta)])?
+        $event:ident = $name:literal => $payload:ty as $ts:literal;
+    )*) => {
+        &[$(($name, $ts, concat!($($doc),*))),*]
+    };
+}
+
+fn event_catalog() -> &'static [(&'static str, &'static str, &'static str)] {
+    include!("src/events/catalog.rs")
+}
+
+/// TypeScript definitions for `src/lib/events`, so `listen` is typed by the
+/// same catalog the backend emits from
+fn events_typescript() -> String {
+    let catalog = event_catalog();
+    let imports: BTreeSet<&str> = catalog
+        .iter()
+        .flat_map(|(_, ts, _)| ts.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_uppercase()))
+        .collect();
+
+    let mut out = String::from(
+        "// Generated by src-tauri/build.rs from src-tauri/src/events/catalog.rs.\n// Do not edit; add events to the catalog instead.\n\n",
+    );
+    out.push_str("import type {\n");
+    for name in &imports {
+        out.push_str(&format!("\t{},\n", name));
+    }
+    out.push_str("} from './payloads';\n\n");
+    out.push_str("/** Payload of each event the backend emits, by event name */\n");
+    out.push_str("export type EventPayloads = {\n");
+    for (name, ts, doc) in catalog {
+        out.push_str(&format!("\t/** {} */\n\t'{}': {};\n", doc.trim(), name, ts));
+    }
+    out.push_str("};\n\nexport type EventName = keyof EventPayloads;\n");
+    out
+}
+
 fn main() {
+    println!("cargo:rerun-if-changed=src/events/catalog.rs");
+    let path = Path::new(EVENTS_TS);
+    // Only when building from the full repository, not a packaged crate
+    if path.parent().is_some_and(Path::exists) {
+        let typescript = events_typescript();
+        // Leave the file alone when unchanged so the dev server doesn't reload
+        if fs::read_to_string(path).ok().as_deref() != Some(typescript.as_str()) {
+            fs::write(path, typescript).expect("Failed to write event types");
+        }
+    }
+
     tauri_build::build()
 }
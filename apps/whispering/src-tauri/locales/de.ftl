@@ -0,0 +1,56 @@
+## Tray menu
+
+tray-show-window = Fenster anzeigen
+tray-hide-window = Fenster ausblenden
+tray-settings = Einstellungen
+tray-close-to-tray = Beim Schließen in den Infobereich
+tray-start-minimized = Minimiert starten
+tray-sound-feedback = Töne
+tray-auto-paste = Automatisch einfügen
+tray-recording-preset = Aufnahmeprofil
+tray-quit = Beenden
+
+## Tray tooltips
+
+tooltip-idle = Whispering
+tooltip-recording = Whispering - Aufnahme läuft
+tooltip-processing = Whispering - Transkription läuft
+
+## Notifications
+
+notify-still-in-tray = Whispering läuft im Infobereich weiter
+notify-recording-queued = Aufnahme gespeichert; sie wird transkribiert, sobald Whispering geöffnet wird
+notify-processing-timeout = Die Transkription hat länger als { $seconds } Sekunden gedauert und wurde abgebrochen. Prüfe deine Internetverbindung oder wähle einen anderen Anbieter und nimm erneut auf.
+notify-keyword-heard = „{ $phrase }“ wurde erwähnt: { $text }
+notify-injection-fell-back = Die Transkription konnte nicht in { $app } eingefügt werden. Sie liegt zum Einfügen in der Zwischenablage.
+notify-injection-fell-back-unknown = Die Transkription konnte nicht in die aktive App eingefügt werden. Sie liegt zum Einfügen in der Zwischenablage.
+notify-elevated = { $app } läuft als Administrator, deshalb kann Whispering dort nicht tippen. Die Transkription liegt zum Einfügen in der Zwischenablage.
+notify-elevated-unknown = Die aktive App läuft als Administrator, deshalb kann Whispering dort nicht tippen. Die Transkription liegt zum Einfügen in der Zwischenablage.
+notify-disk-space-low = Nur noch { $available } frei auf dem Datenträger. Die Aufnahme ist pausiert, damit die Datei nicht abbricht; schaffe Platz und setze sie dann fort oder beende sie.
+
+## Screen reader announcements
+
+announce-recording = Aufnahme läuft
+announce-recording-stopped = Aufnahme beendet, wird transkribiert
+announce-recording-cancelled = Aufnahme abgebrochen
+announce-transcription-done = Transkription fertig
+announce-error = Fehler: { $message }
+announce-fell-back = Einfügen fehlgeschlagen, Transkription in die Zwischenablage kopiert
+announce-pasted-into = In { $app } eingefügt
+announce-pasted = Eingefügt
+
+## Error recovery hints
+
+hint-device-not-found = Schließe das Mikrofon erneut an oder wähle in den Einstellungen ein anderes Gerät.
+hint-device-busy = Schließe andere Apps, die das Mikrofon verwenden, oder wähle ein anderes Eingabegerät.
+hint-unsupported-device = Wähle ein anderes Eingabegerät oder eine andere Abtastrate in den Einstellungen.
+hint-no-session = Starte eine neue Aufnahme.
+hint-permission-denied = Erteile Whispering die nötige Berechtigung in den Systemeinstellungen.
+hint-not-running = Starte Whispering und versuche es erneut.
+hint-address-in-use = Wähle in den Einstellungen einen anderen Port.
+hint-invalid-api-key = Prüfe den API-Schlüssel in den Transkriptionseinstellungen.
+hint-network = Prüfe deine Internetverbindung und versuche es erneut.
+hint-unsupported = Installiere eine Whispering-Version, die diese Funktion enthält.
+hint-ffmpeg-not-found = Installiere FFmpeg oder nimm mit CPAL auf, das WAV-Dateien erzeugt.
+hint-model-load = Lade das Modell erneut herunter oder wähle in den Einstellungen ein anderes.
+hint-disk-full = Schaffe Platz auf dem Datenträger oder wähle in den Einstellungen einen anderen Aufnahmeordner.
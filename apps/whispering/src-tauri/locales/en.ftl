@@ -0,0 +1,59 @@
+# Strings shown by the Rust side: tray menus and tooltips, notifications,
+# screen reader announcements and error hints. See src/i18n.
+
+## Tray menu
+
+tray-show-window = Show Window
+tray-hide-window = Hide Window
+tray-settings = Settings
+tray-close-to-tray = Close to tray
+tray-start-minimized = Start minimized
+tray-sound-feedback = Sound feedback
+tray-auto-paste = Auto-paste
+tray-recording-preset = Recording preset
+tray-quit = Quit
+
+## Tray tooltips
+
+tooltip-idle = Whispering
+tooltip-recording = Whispering - Recording
+tooltip-processing = Whispering - Transcribing
+
+## Notifications
+
+notify-still-in-tray = Whispering is still running in the tray
+notify-recording-queued = Recording saved; it will be transcribed when Whispering opens
+notify-processing-timeout = Transcription took longer than { $seconds } seconds and was cancelled. Check your internet connection or try another provider, then record again.
+notify-keyword-heard = “{ $phrase }” was mentioned: { $text }
+notify-injection-fell-back = Couldn't insert the transcript into { $app }. It's on the clipboard to paste instead.
+notify-injection-fell-back-unknown = Couldn't insert the transcript into the focused app. It's on the clipboard to paste instead.
+notify-elevated = { $app } is running as administrator, so Whispering can't type into it. The transcript is on the clipboard to paste instead.
+notify-elevated-unknown = The focused app is running as administrator, so Whispering can't type into it. The transcript is on the clipboard to paste instead.
+notify-disk-space-low = Only { $available } left on the disk. Recording is paused so the file isn't cut off; free up space, then resume or stop.
+
+## Screen reader announcements
+
+announce-recording = Recording
+announce-recording-stopped = Recording stopped, transcribing
+announce-recording-cancelled = Recording cancelled
+announce-transcription-done = Transcription done
+announce-error = Error: { $message }
+announce-fell-back = Couldn't paste, transcript copied to the clipboard
+announce-pasted-into = Pasted into { $app }
+announce-pasted = Pasted
+
+## Error recovery hints
+
+hint-device-not-found = Reconnect the microphone or choose another device in settings.
+hint-device-busy = Close other apps using the microphone, or pick a different input device.
+hint-unsupported-device = Choose another input device, or a different sample rate in settings.
+hint-no-session = Start a new recording.
+hint-permission-denied = Grant Whispering the required permission in your system settings.
+hint-not-running = Launch Whispering and try again.
+hint-address-in-use = Choose a different port in settings.
+hint-invalid-api-key = Check the API key in transcription settings.
+hint-network = Check your internet connection and try again.
+hint-unsupported = Install a build of Whispering that includes this feature.
+hint-ffmpeg-not-found = Install FFmpeg, or record with CPAL which produces WAV files.
+hint-model-load = Re-download the model or select a different one in settings.
+hint-disk-full = Free up space on the disk or choose another recording folder in settings.
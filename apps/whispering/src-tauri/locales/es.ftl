@@ -0,0 +1,56 @@
+## Tray menu
+
+tray-show-window = Mostrar ventana
+tray-hide-window = Ocultar ventana
+tray-settings = Ajustes
+tray-close-to-tray = Cerrar a la bandeja
+tray-start-minimized = Iniciar minimizado
+tray-sound-feedback = Sonidos
+tray-auto-paste = Pegar automáticamente
+tray-recording-preset = Perfil de grabación
+tray-quit = Salir
+
+## Tray tooltips
+
+tooltip-idle = Whispering
+tooltip-recording = Whispering - Grabando
+tooltip-processing = Whispering - Transcribiendo
+
+## Notifications
+
+notify-still-in-tray = Whispering sigue ejecutándose en la bandeja
+notify-recording-queued = Grabación guardada; se transcribirá cuando abras Whispering
+notify-processing-timeout = La transcripción tardó más de { $seconds } segundos y se canceló. Comprueba tu conexión a internet o prueba otro proveedor y vuelve a grabar.
+notify-keyword-heard = Se mencionó «{ $phrase }»: { $text }
+notify-injection-fell-back = No se pudo insertar la transcripción en { $app }. Está en el portapapeles para que la pegues.
+notify-injection-fell-back-unknown = No se pudo insertar la transcripción en la aplicación activa. Está en el portapapeles para que la pegues.
+notify-elevated = { $app } se ejecuta como administrador, así que Whispering no puede escribir en ella. La transcripción está en el portapapeles para que la pegues.
+notify-elevated-unknown = La aplicación activa se ejecuta como administrador, así que Whispering no puede escribir en ella. La transcripción está en el portapapeles para que la pegues.
+notify-disk-space-low = Solo quedan { $available } libres en el disco. La grabación está en pausa para que el archivo no se corte; libera espacio y después reanúdala o detenla.
+
+## Screen reader announcements
+
+announce-recording = Grabando
+announce-recording-stopped = Grabación detenida, transcribiendo
+announce-recording-cancelled = Grabación cancelada
+announce-transcription-done = Transcripción lista
+announce-error = Error: { $message }
+announce-fell-back = No se pudo pegar, la transcripción se copió al portapapeles
+announce-pasted-into = Pegado en { $app }
+announce-pasted = Pegado
+
+## Error recovery hints
+
+hint-device-not-found = Vuelve a conectar el micrófono o elige otro dispositivo en los ajustes.
+hint-device-busy = Cierra otras aplicaciones que usen el micrófono o elige otro dispositivo de entrada.
+hint-unsupported-device = Elige otro dispositivo de entrada o una frecuencia de muestreo distinta en los ajustes.
+hint-no-session = Inicia una nueva grabación.
+hint-permission-denied = Concede a Whispering el permiso necesario en los ajustes del sistema.
+hint-not-running = Abre Whispering y vuelve a intentarlo.
+hint-address-in-use = Elige otro puerto en los ajustes.
+hint-invalid-api-key = Revisa la clave de API en los ajustes de transcripción.
+hint-network = Comprueba tu conexión a internet y vuelve a intentarlo.
+hint-unsupported = Instala una versión de Whispering que incluya esta función.
+hint-ffmpeg-not-found = Instala FFmpeg o graba con CPAL, que produce archivos WAV.
+hint-model-load = Vuelve a descargar el modelo o elige otro en los ajustes.
+hint-disk-full = Libera espacio en el disco o elige otra carpeta de grabaciones en los ajustes.
@@ -0,0 +1,56 @@
+## Tray menu
+
+tray-show-window = Afficher la fenêtre
+tray-hide-window = Masquer la fenêtre
+tray-settings = Réglages
+tray-close-to-tray = Fermer dans la zone de notification
+tray-start-minimized = Démarrer réduit
+tray-sound-feedback = Sons
+tray-auto-paste = Collage automatique
+tray-recording-preset = Profil d'enregistrement
+tray-quit = Quitter
+
+## Tray tooltips
+
+tooltip-idle = Whispering
+tooltip-recording = Whispering - Enregistrement
+tooltip-processing = Whispering - Transcription
+
+## Notifications
+
+notify-still-in-tray = Whispering continue de fonctionner dans la zone de notification
+notify-recording-queued = Enregistrement sauvegardé ; il sera transcrit à l'ouverture de Whispering
+notify-processing-timeout = La transcription a pris plus de { $seconds } secondes et a été annulée. Vérifiez votre connexion internet ou essayez un autre fournisseur, puis enregistrez à nouveau.
+notify-keyword-heard = « { $phrase } » a été mentionné : { $text }
+notify-injection-fell-back = Impossible d'insérer la transcription dans { $app }. Elle est dans le presse-papiers, prête à être collée.
+notify-injection-fell-back-unknown = Impossible d'insérer la transcription dans l'application active. Elle est dans le presse-papiers, prête à être collée.
+notify-elevated = { $app } s'exécute en tant qu'administrateur, Whispering ne peut donc pas y taper. La transcription est dans le presse-papiers, prête à être collée.
+notify-elevated-unknown = L'application active s'exécute en tant qu'administrateur, Whispering ne peut donc pas y taper. La transcription est dans le presse-papiers, prête à être collée.
+notify-disk-space-low = Il ne reste que { $available } sur le disque. L'enregistrement est en pause pour que le fichier ne soit pas tronqué ; libérez de l'espace, puis reprenez-le ou arrêtez-le.
+
+## Screen reader announcements
+
+announce-recording = Enregistrement
+announce-recording-stopped = Enregistrement arrêté, transcription en cours
+announce-recording-cancelled = Enregistrement annulé
+announce-transcription-done = Transcription terminée
+announce-error = Erreur : { $message }
+announce-fell-back = Collage impossible, transcription copiée dans le presse-papiers
+announce-pasted-into = Collé dans { $app }
+announce-pasted = Collé
+
+## Error recovery hints
+
+hint-device-not-found = Rebranchez le micro ou choisissez un autre appareil dans les réglages.
+hint-device-busy = Fermez les autres applications qui utilisent le micro, ou choisissez un autre périphérique d'entrée.
+hint-unsupported-device = Choisissez un autre périphérique d'entrée, ou une autre fréquence d'échantillonnage dans les paramètres.
+hint-no-session = Lancez un nouvel enregistrement.
+hint-permission-denied = Accordez à Whispering l'autorisation nécessaire dans les réglages du système.
+hint-not-running = Lancez Whispering et réessayez.
+hint-address-in-use = Choisissez un autre port dans les réglages.
+hint-invalid-api-key = Vérifiez la clé d'API dans les réglages de transcription.
+hint-network = Vérifiez votre connexion internet et réessayez.
+hint-unsupported = Installez une version de Whispering qui inclut cette fonctionnalité.
+hint-ffmpeg-not-found = Installez FFmpeg, ou enregistrez avec CPAL qui produit des fichiers WAV.
+hint-model-load = Téléchargez à nouveau le modèle ou choisissez-en un autre dans les réglages.
+hint-disk-full = Libérez de l'espace sur le disque ou choisissez un autre dossier d'enregistrement dans les réglages.
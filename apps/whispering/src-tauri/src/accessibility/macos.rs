@@ -0,0 +1,104 @@
+//! VoiceOver announcements through AppKit's `NSAccessibility` functions.
+//!
+//! The notification names and user info keys are `NSString`s and the user
+//! info an `NSDictionary`, both toll-free bridged with their Core Foundation
+//! counterparts, so no Objective-C bindings are needed beyond fetching
+//! `NSApp`.
+
+use crate::focus::cf_string;
+use core_foundation_sys::base::{CFRelease, CFTypeRef};
+use core_foundation_sys::dictionary::{
+    kCFTypeDictionaryKeyCallBacks, kCFTypeDictionaryValueCallBacks, CFDictionaryCreate,
+    CFDictionaryRef,
+};
+use core_foundation_sys::number::{kCFNumberSInt64Type, CFNumberCreate};
+use core_foundation_sys::string::CFStringRef;
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::ptr;
+
+/// `NSAccessibilityPriorityHigh`: interrupts whatever VoiceOver is saying
+const PRIORITY_HIGH: i64 = 90;
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    static NSAccessibilityAnnouncementRequestedNotification: CFStringRef;
+    static NSAccessibilityAnnouncementKey: CFStringRef;
+    static NSAccessibilityPriorityKey: CFStringRef;
+
+    fn NSAccessibilityPostNotificationWithUserInfo(
+        element: *mut c_void,
+        notification: CFStringRef,
+        user_info: CFDictionaryRef,
+    );
+}
+
+#[link(name = "objc")]
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> *mut c_void;
+    fn sel_registerName(name: *const c_char) -> *mut c_void;
+    fn objc_msgSend();
+}
+
+/// `[NSApplication sharedApplication]`, the element announcements come from
+///
+/// # Safety
+///
+/// Must be called on the main thread.
+unsafe fn shared_application() -> *mut c_void {
+    let send: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+        std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+    let class = objc_getClass(b"NSApplication\0".as_ptr() as *const c_char);
+    if class.is_null() {
+        return ptr::null_mut();
+    }
+    send(
+        class,
+        sel_registerName(b"sharedApplication\0".as_ptr() as *const c_char),
+    )
+}
+
+/// Ask VoiceOver to speak `message`; must be called on the main thread
+pub fn announce(message: &str) -> Result<(), String> {
+    unsafe {
+        let app = shared_application();
+        if app.is_null() {
+            return Err("NSApplication isn't available".to_string());
+        }
+        let text = cf_string(message);
+        if text.is_null() {
+            return Err("Failed to create the announcement string".to_string());
+        }
+        let priority = CFNumberCreate(
+            ptr::null(),
+            kCFNumberSInt64Type,
+            &PRIORITY_HIGH as *const i64 as *const c_void,
+        );
+        let keys = [
+            NSAccessibilityAnnouncementKey as *const c_void,
+            NSAccessibilityPriorityKey as *const c_void,
+        ];
+        let values = [text as *const c_void, priority as *const c_void];
+        let info = CFDictionaryCreate(
+            ptr::null(),
+            keys.as_ptr(),
+            values.as_ptr(),
+            if priority.is_null() { 1 } else { 2 },
+            &kCFTypeDictionaryKeyCallBacks,
+            &kCFTypeDictionaryValueCallBacks,
+        );
+        NSAccessibilityPostNotificationWithUserInfo(
+            app,
+            NSAccessibilityAnnouncementRequestedNotification,
+            info,
+        );
+        if !info.is_null() {
+            CFRelease(info as CFTypeRef);
+        }
+        if !priority.is_null() {
+            CFRelease(priority as CFTypeRef);
+        }
+        CFRelease(text as CFTypeRef);
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Screen reader announcements for what happens without looking at the
+//! screen: recording starting and stopping, transcription finishing and
+//! text going into the target app.
+//!
+//! Sounds say that something happened; announcements say what. They go
+//! through the platform accessibility API, which only speaks them when a
+//! screen reader is running:
+//!
+//! - macOS: an `NSAccessibility` announcement request, which VoiceOver
+//!   reads from background apps too
+//! - Windows: a UI Automation notification event on the main window, read
+//!   by Narrator and NVDA
+//! - Linux: an ARIA live region in the main window, which WebKitGTK exposes
+//!   to Orca over AT-SPI (sent as `accessibility://announce`)
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use crate::app_state::{AppState, AppStatus};
+use crate::history::InjectionOutcome;
+use crate::i18n;
+use crate::injection::InjectionReport;
+use crate::settings::SettingsStore;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+/// Announce changes of the recording state for as long as the app runs
+pub fn follow_app_state(app: AppHandle) {
+    let mut rx = app.state::<AppState>().subscribe();
+    tauri::async_runtime::spawn(async move {
+        let mut previous = rx.borrow().clone();
+        while rx.changed().await.is_ok() {
+            let status = rx.borrow_and_update().clone();
+            if let Some(message) = describe(&previous, &status) {
+                announce(&app, &message);
+            }
+            previous = status;
+        }
+    });
+}
+
+/// What to say about moving from `previous` to `status`, if anything
+///
+/// Finishing an injection is left to [`announce_injection`], which knows
+/// where the text went.
+fn describe(previous: &AppStatus, status: &AppStatus) -> Option<String> {
+    match (previous, status) {
+        (_, AppStatus::Recording { .. }) => Some(i18n::t("announce-recording")),
+        (AppStatus::Recording { .. }, AppStatus::Processing) => {
+            Some(i18n::t("announce-recording-stopped"))
+        }
+        (AppStatus::Recording { .. }, AppStatus::Idle) => {
+            Some(i18n::t("announce-recording-cancelled"))
+        }
+        (AppStatus::Processing, AppStatus::Idle) => Some(i18n::t("announce-transcription-done")),
+        (_, AppStatus::Error { message }) => {
+            Some(i18n::t_args("announce-error", &[("message", message)]))
+        }
+        _ => None,
+    }
+}
+
+/// Say where injected text ended up
+pub fn announce_injection(app: &AppHandle, report: &InjectionReport) {
+    let message = match (report.outcome, report.app.as_deref()) {
+        (InjectionOutcome::FellBack, _) => i18n::t("announce-fell-back"),
+        (_, Some(target)) => i18n::t_args("announce-pasted-into", &[("app", target)]),
+        (_, None) => i18n::t("announce-pasted"),
+    };
+    announce(app, &message);
+}
+
+/// Have the screen reader speak `message`, unless announcements are off
+pub fn announce(app: &AppHandle, message: &str) {
+    let enabled = app
+        .try_state::<SettingsStore>()
+        .is_some_and(|settings| settings.get().screen_reader_announcements);
+    if !enabled {
+        return;
+    }
+    debug!("Announcing {:?}", message);
+
+    #[cfg(target_os = "macos")]
+    {
+        let message = message.to_string();
+        let result = app.run_on_main_thread(move || {
+            if let Err(e) = macos::announce(&message) {
+                warn!("Failed to announce to VoiceOver: {}", e);
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to announce: {}", e);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let Some(window) = app.get_webview_window("main") else {
+            return;
+        };
+        let hwnd = match window.hwnd() {
+            Ok(hwnd) => hwnd.0 as isize,
+            Err(e) => {
+                warn!("Failed to announce: {}", e);
+                return;
+            }
+        };
+        let message = message.to_string();
+        let result = app.run_on_main_thread(move || {
+            if let Err(e) = windows::announce(hwnd as _, &message) {
+                warn!("Failed to raise a UI Automation notification: {}", e);
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to announce: {}", e);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::events::{Announcement, AppEvent};
+        if let Err(e) = Announcement::emit(app, message) {
+            warn!("Failed to announce: {}", e);
+        }
+    }
+}
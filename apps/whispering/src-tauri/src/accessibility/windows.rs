@@ -0,0 +1,56 @@
+//! UI Automation notification events, which Narrator and NVDA read out.
+//!
+//! Notifications have to come from an element, so they're raised on the
+//! host provider UI Automation keeps for the main window.
+
+use std::ffi::c_void;
+use windows_sys::Win32::Foundation::{SysAllocString, SysFreeString, HWND};
+use windows_sys::Win32::UI::Accessibility::{
+    NotificationKind_ActionCompleted, NotificationProcessing_ImportantMostRecent,
+    UiaHostProviderFromHwnd, UiaRaiseNotificationEvent,
+};
+
+/// Lets screen readers drop a stale announcement when a newer one arrives
+const ACTIVITY_ID: &str = "Whispering.Status";
+
+/// The start of every COM interface's vtable
+#[repr(C)]
+struct IUnknownVtbl {
+    query_interface: usize,
+    add_ref: usize,
+    release: unsafe extern "system" fn(*mut c_void) -> u32,
+}
+
+/// Raise a notification carrying `message` on `window`; must be called on
+/// the thread that owns it
+pub fn announce(window: HWND, message: &str) -> Result<(), String> {
+    unsafe {
+        let mut provider: *mut c_void = std::ptr::null_mut();
+        let hr = UiaHostProviderFromHwnd(window, &mut provider);
+        if hr < 0 || provider.is_null() {
+            return Err(format!("No provider for the window (HRESULT {:#x})", hr));
+        }
+        let display = SysAllocString(wide(message).as_ptr());
+        let activity = SysAllocString(wide(ACTIVITY_ID).as_ptr());
+        let hr = UiaRaiseNotificationEvent(
+            provider,
+            NotificationKind_ActionCompleted,
+            NotificationProcessing_ImportantMostRecent,
+            display,
+            activity,
+        );
+        SysFreeString(display);
+        SysFreeString(activity);
+        let vtable = *(provider as *const *const IUnknownVtbl);
+        ((*vtable).release)(provider);
+        if hr < 0 {
+            return Err(format!("HRESULT {:#x}", hr));
+        }
+    }
+    Ok(())
+}
+
+/// `text` as a null-terminated UTF-16 string
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
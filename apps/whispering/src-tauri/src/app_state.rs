@@ -0,0 +1,172 @@
+//! Central recording lifecycle state machine.
+//!
+//! Every subsystem that cares whether Whispering is recording, transcribing or
+//! typing reads the state from here instead of keeping its own flags, so the
+//! tray, IPC clients and the UI can never disagree.
+//!
+//! ```text
+//!   Idle ──RecordingStarted──▶ Recording ──RecordingStopped──▶ Processing
+//!    ▲                            │                               │
+//!    │◀──────RecordingCancelled───┘                InjectionStarted│
+//!    │                                                            ▼
+//!    │◀────────────────Completed──────────────────────────── Injecting
+//!    │
+//!    └──Reset── Error ◀──Failed── (any state)
+//! ```
+
+use crate::events::{AppEvent, StateChanged};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use thiserror::Error;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+/// Current stage of the dictation pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum AppStatus {
+    Idle,
+    Recording {
+        #[serde(rename = "recordingId")]
+        recording_id: Option<String>,
+    },
+    Processing,
+    Injecting,
+    Error {
+        message: String,
+    },
+}
+
+/// Something that happened in the pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StateEvent {
+    RecordingStarted {
+        #[serde(rename = "recordingId")]
+        recording_id: Option<String>,
+    },
+    RecordingStopped,
+    RecordingCancelled,
+    InjectionStarted,
+    Completed,
+    Failed {
+        message: String,
+    },
+    Reset,
+}
+
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "name")]
+pub enum StateError {
+    #[error("Invalid state transition: {event} while {from}")]
+    InvalidTransition { from: String, event: String },
+}
+
+impl AppStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            AppStatus::Idle => "idle",
+            AppStatus::Recording { .. } => "recording",
+            AppStatus::Processing => "processing",
+            AppStatus::Injecting => "injecting",
+            AppStatus::Error { .. } => "error",
+        }
+    }
+
+    /// Apply an event, returning the next state if the transition is allowed
+    pub fn next(&self, event: &StateEvent) -> Option<AppStatus> {
+        use AppStatus::*;
+        match (self, event) {
+            (_, StateEvent::Failed { message }) => Some(Error {
+                message: message.clone(),
+            }),
+            (_, StateEvent::Reset) => Some(Idle),
+            (Idle | Error { .. }, StateEvent::RecordingStarted { recording_id }) => Some(Recording {
+                recording_id: recording_id.clone(),
+            }),
+            (Recording { .. }, StateEvent::RecordingStopped) => Some(Processing),
+            (Recording { .. }, StateEvent::RecordingCancelled) => Some(Idle),
+            (Processing, StateEvent::InjectionStarted) => Some(Injecting),
+            (Processing | Injecting, StateEvent::Completed) => Some(Idle),
+            _ => None,
+        }
+    }
+}
+
+/// Owner of the current `AppStatus`, published through a watch channel
+pub struct AppState {
+    tx: watch::Sender<AppStatus>,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        let (tx, _rx) = watch::channel(AppStatus::Idle);
+        Self { tx }
+    }
+
+    pub fn current(&self) -> AppStatus {
+        self.tx.borrow().clone()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<AppStatus> {
+        self.tx.subscribe()
+    }
+
+    /// Apply an event atomically, rejecting transitions the machine doesn't allow
+    pub fn transition(&self, event: StateEvent) -> Result<AppStatus, StateError> {
+        let mut result = None;
+        self.tx.send_if_modified(|status| match status.next(&event) {
+            Some(next) => {
+                debug!("App state: {} -> {} ({:?})", status.label(), next.label(), event);
+                let changed = *status != next;
+                *status = next.clone();
+                result = Some(Ok(next));
+                changed
+            }
+            None => {
+                result = Some(Err(StateError::InvalidTransition {
+                    from: status.label().to_string(),
+                    event: format!("{:?}", event),
+                }));
+                false
+            }
+        });
+        result.expect("send_if_modified always runs the closure")
+    }
+
+    /// Like `transition`, but only logs rejected transitions
+    ///
+    /// Used from commands whose own work already succeeded, where a state
+    /// mismatch shouldn't turn into a user-facing failure.
+    pub fn record(&self, event: StateEvent) {
+        if let Err(e) = self.transition(event) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Mirror every state change to the frontend as a `state://changed` event
+    pub fn forward_to_frontend<R: Runtime>(&self, app: AppHandle<R>) {
+        let mut rx = self.subscribe();
+        tauri::async_runtime::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let status = rx.borrow_and_update().clone();
+                let _ = StateChanged::emit(&app, &status);
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub async fn get_app_state(state: tauri::State<'_, AppState>) -> crate::error::Result<AppStatus> {
+    Ok(state.current())
+}
+
+/// Lets the frontend report pipeline stages that happen outside Rust, such as
+/// cloud transcription finishing or failing
+#[tauri::command]
+pub async fn report_app_state(
+    event: StateEvent,
+    state: tauri::State<'_, AppState>,
+) -> crate::error::Result<AppStatus> {
+    Ok(state.transition(event)?)
+}
@@ -0,0 +1,85 @@
+//! Decoding and writing stored audio at its original quality.
+//!
+//! Transcription resamples everything to 16kHz mono; playback and editing
+//! want the original channels and sample rate, so they go through here.
+
+use crate::error::{AppError, Result};
+use crate::transcription::{decode_audio, DECODED_SAMPLE_RATE};
+use rodio::{Decoder, Source};
+use std::io::Cursor;
+use std::path::Path;
+use tracing::debug;
+
+/// Interleaved audio samples in the range -1.0..=1.0
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl DecodedAudio {
+    pub fn duration_seconds(&self) -> f64 {
+        self.samples.len() as f64 / (self.channels as f64 * self.sample_rate as f64)
+    }
+
+    /// Copy of the audio between two times, clamped to its length
+    pub fn slice(&self, start_seconds: f64, end_seconds: f64) -> DecodedAudio {
+        let frame = |seconds: f64| {
+            let frames = self.samples.len() / self.channels as usize;
+            ((seconds.max(0.0) * self.sample_rate as f64) as usize).min(frames)
+        };
+        let start = frame(start_seconds) * self.channels as usize;
+        let end = frame(end_seconds).max(frame(start_seconds)) * self.channels as usize;
+        DecodedAudio {
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            samples: self.samples[start..end].to_vec(),
+        }
+    }
+
+    /// Write as 16-bit PCM WAV
+    pub fn write_wav(&self, path: &Path) -> Result<()> {
+        let spec = hound::WavSpec {
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec)
+            .map_err(|e| AppError::Internal(format!("Failed to create {:?}: {}", path, e)))?;
+        for sample in &self.samples {
+            writer
+                .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                .map_err(|e| AppError::Internal(format!("Failed to write {:?}: {}", path, e)))?;
+        }
+        writer
+            .finalize()
+            .map_err(|e| AppError::Internal(format!("Failed to finalize {:?}: {}", path, e)))?;
+        Ok(())
+    }
+}
+
+/// Decode an audio file, falling back to the transcription decoder (FFmpeg)
+/// for formats rodio can't read such as Opus
+pub fn decode_file(path: &Path) -> Result<DecodedAudio> {
+    let audio = std::fs::read(path)?;
+    match Decoder::new(Cursor::new(audio.clone())) {
+        Ok(decoder) => Ok(DecodedAudio {
+            channels: decoder.channels(),
+            sample_rate: decoder.sample_rate(),
+            samples: decoder.convert_samples::<f32>().collect(),
+        }),
+        Err(e) => {
+            debug!(
+                "rodio can't decode {:?} ({}), converting with FFmpeg",
+                path, e
+            );
+            Ok(DecodedAudio {
+                channels: 1,
+                sample_rate: DECODED_SAMPLE_RATE,
+                samples: decode_audio(audio)?,
+            })
+        }
+    }
+}
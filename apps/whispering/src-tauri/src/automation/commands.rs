@@ -0,0 +1,32 @@
+use super::{Automation, AutomationConfig, AutomationInput, AutomationRun};
+use crate::error::Result;
+use crate::transforms::TransformContext;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_automation_config(automation: State<'_, Automation>) -> Result<AutomationConfig> {
+    Ok(automation.config())
+}
+
+/// Save the scripts, returning them with ids assigned to new ones
+#[tauri::command]
+pub async fn set_automation_config(
+    config: AutomationConfig,
+    automation: State<'_, Automation>,
+) -> Result<AutomationConfig> {
+    automation.set_config(config)
+}
+
+/// Run `source`, saved or not, on `text` and show what it would do;
+/// destinations it triggers are listed but not delivered to
+#[tauri::command]
+pub async fn test_automation_script(
+    source: String,
+    text: String,
+    context: Option<TransformContext>,
+    app: AppHandle,
+) -> Result<AutomationRun> {
+    let ast = super::compile(&source)?;
+    let input = AutomationInput::new(&app, text, &context.unwrap_or_default());
+    super::run(&ast, &input)
+}
@@ -0,0 +1,308 @@
+//! Automation scripts written in Rhai, for conditional tweaks that rules
+//! and script hooks can't express without leaving the app.
+//!
+//! Each enabled script runs on every new transcript, after script hooks and
+//! before profanity filtering, and sees:
+//!
+//! - `transcript`, `app` (the focused app, or `""`), `language` and
+//!   `profile` as constants
+//! - `setting(key)`: a native setting by its camelCase name, `()` if unknown
+//! - `set_output(text)`: replace the transcript
+//! - `trigger(id)`: also hand the result to the destination plugin `id`
+//! - `print(...)` and `debug(...)`, which go to the app log
+//!
+//! For example, to add emoji shortcodes when dictating into Slack:
+//!
+//! ```rhai
+//! if app == "slack" {
+//!     set_output(transcript.replace("thumbs up", ":+1:"));
+//! }
+//! ```
+//!
+//! Scripts can't read files, import modules or run programs, and are
+//! stopped after a fixed number of operations so a loop can't hang the
+//! pipeline.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::focus::focused_app;
+use crate::settings::SettingsStore;
+use crate::transforms::{TransformContext, TransformStage};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+pub const AUTOMATION_FILE: &str = "automation.json";
+
+/// Roughly how many steps a script may take per transcript
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_STRING_SIZE: usize = 1 << 20;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationScript {
+    /// Assigned when the script is saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub source: String,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutomationConfig {
+    /// In the order they run
+    pub scripts: Vec<AutomationScript>,
+}
+
+/// What a script sees of the transcript it runs on
+#[derive(Debug, Clone, Default)]
+pub struct AutomationInput {
+    pub transcript: String,
+    pub app: Option<String>,
+    pub language: Option<String>,
+    pub profile: Option<String>,
+    pub settings: serde_json::Value,
+}
+
+impl AutomationInput {
+    fn new(app: &AppHandle, transcript: String, context: &TransformContext) -> Self {
+        let settings = serde_json::to_value(app.state::<SettingsStore>().get())
+            .unwrap_or(serde_json::Value::Null);
+        Self {
+            transcript,
+            app: focused_app(),
+            language: context.language.clone(),
+            profile: context.profile.clone(),
+            settings,
+        }
+    }
+}
+
+/// What one run of a script did
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRun {
+    /// The transcript after the script, changed or not
+    pub output: String,
+    /// Destination plugins the script asked for, in order
+    pub triggered: Vec<String>,
+    /// Lines printed with `print` or `debug`
+    pub logs: Vec<String>,
+}
+
+/// Runs enabled scripts over transcripts, registered with `Transforms`
+struct ScriptStage {
+    app: AppHandle,
+    config: Arc<Mutex<AutomationConfig>>,
+}
+
+impl TransformStage for ScriptStage {
+    fn apply(&self, text: String, context: &TransformContext) -> String {
+        let scripts: Vec<AutomationScript> = self
+            .config
+            .lock()
+            .map(|config| {
+                config
+                    .scripts
+                    .iter()
+                    .filter(|script| script.enabled)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        if scripts.is_empty() {
+            return text;
+        }
+        let mut input = AutomationInput::new(&self.app, text, context);
+        for script in scripts {
+            let result = compile(&script.source).and_then(|ast| run(&ast, &input));
+            match result {
+                Ok(output) => {
+                    for line in &output.logs {
+                        info!("[automation {}] {}", script.name, line);
+                    }
+                    for destination in output.triggered {
+                        trigger(&self.app, destination, output.output.clone());
+                    }
+                    input.transcript = output.output;
+                }
+                Err(e) => warn!("Skipping automation {}: {}", script.name, e),
+            }
+        }
+        input.transcript
+    }
+}
+
+/// Hand `text` to the destination plugin `id` in the background
+fn trigger(app: &AppHandle, id: String, text: String) {
+    #[cfg(feature = "plugins")]
+    {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let plugins = app.state::<crate::plugins::Plugins>();
+            if let Err(e) = plugins.deliver_to(&app, &id, &text).await {
+                warn!("Automation couldn't deliver to {}: {}", id, e);
+            }
+        });
+    }
+    #[cfg(not(feature = "plugins"))]
+    {
+        let _ = (app, text);
+        warn!(
+            "Automation asked for destination {}, but plugins aren't in this build",
+            id
+        );
+    }
+}
+
+pub struct Automation {
+    path: PathBuf,
+    config: Arc<Mutex<AutomationConfig>>,
+}
+
+impl Automation {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                AutomationConfig::default()
+            }),
+            Err(_) => AutomationConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Arc::new(Mutex::new(config)),
+        }
+    }
+
+    /// The stage that runs enabled scripts, for `Transforms::add_stage`
+    pub fn transform_stage(&self, app: AppHandle) -> Arc<dyn TransformStage> {
+        Arc::new(ScriptStage {
+            app,
+            config: self.config.clone(),
+        })
+    }
+
+    pub fn config(&self) -> AutomationConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// Save the scripts, refusing any that don't compile
+    pub fn set_config(&self, mut config: AutomationConfig) -> Result<AutomationConfig> {
+        for script in &mut config.scripts {
+            if script.name.trim().is_empty() {
+                return Err(AppError::InvalidInput(
+                    "Automation name can't be empty".to_string(),
+                ));
+            }
+            compile(&script.source).map_err(|e| {
+                AppError::InvalidInput(format!("Automation {}: {}", script.name, e))
+            })?;
+            if script.id.is_empty() {
+                script.id = uuid::Uuid::new_v4().to_string();
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize automation scripts: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self.config.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock automation scripts: {}", e))
+        })? = config.clone();
+        debug!("Saved {} automation scripts", config.scripts.len());
+        Ok(config)
+    }
+}
+
+/// An engine with the sandbox's limits and nothing registered yet
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval");
+    engine
+}
+
+pub fn compile(source: &str) -> Result<AST> {
+    engine()
+        .compile(source)
+        .map_err(|e| AppError::InvalidInput(format!("Script doesn't compile: {}", e)))
+}
+
+/// Run a compiled script on `input`
+pub fn run(ast: &AST, input: &AutomationInput) -> Result<AutomationRun> {
+    let state = Arc::new(Mutex::new(AutomationRun {
+        output: input.transcript.clone(),
+        ..Default::default()
+    }));
+    let mut engine = engine();
+
+    let run = state.clone();
+    engine.register_fn("set_output", move |text: &str| {
+        if let Ok(mut run) = run.lock() {
+            run.output = text.to_string();
+        }
+    });
+    let run = state.clone();
+    engine.register_fn("trigger", move |destination: &str| {
+        if let Ok(mut run) = run.lock() {
+            run.triggered.push(destination.to_string());
+        }
+    });
+    let settings = input.settings.clone();
+    engine.register_fn("setting", move |key: &str| -> Dynamic {
+        settings
+            .get(key)
+            .and_then(|value| rhai::serde::to_dynamic(value).ok())
+            .unwrap_or(Dynamic::UNIT)
+    });
+    let run = state.clone();
+    engine.on_print(move |line| {
+        if let Ok(mut run) = run.lock() {
+            run.logs.push(line.to_string());
+        }
+    });
+    let run = state.clone();
+    engine.on_debug(move |line, _, position| {
+        if let Ok(mut run) = run.lock() {
+            run.logs.push(format!("{} ({})", line, position));
+        }
+    });
+
+    let mut scope = Scope::new();
+    scope
+        .push_constant("transcript", input.transcript.clone())
+        .push_constant("app", input.app.clone().unwrap_or_default())
+        .push_constant("language", input.language.clone().unwrap_or_default())
+        .push_constant("profile", input.profile.clone().unwrap_or_default());
+    engine
+        .run_ast_with_scope(&mut scope, ast)
+        .map_err(|e| AppError::InvalidInput(format!("Script failed: {}", e)))?;
+    drop(engine);
+
+    let run = state
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock automation run: {}", e)))?;
+    Ok(run.clone())
+}
@@ -0,0 +1,49 @@
+use super::{BackupConfig, BackupStatus, BackupSummary, Backups, RestoreSummary};
+use crate::error::Result;
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_backup_config(backups: State<'_, Backups>) -> Result<BackupConfig> {
+    Ok(backups.config())
+}
+
+#[tauri::command]
+pub async fn set_backup_config(config: BackupConfig, backups: State<'_, Backups>) -> Result<()> {
+    debug!(
+        "Updating backups: enabled={}, every {}h, audio={}",
+        config.enabled, config.interval_hours, config.include_audio
+    );
+    backups.set_config(config)
+}
+
+#[tauri::command]
+pub async fn get_backup_status(backups: State<'_, Backups>) -> Result<BackupStatus> {
+    Ok(backups.status())
+}
+
+/// Unlock the destination's backup key, creating it on first use
+#[tauri::command]
+pub async fn set_backup_passphrase(
+    passphrase: String,
+    backups: State<'_, Backups>,
+    app: AppHandle,
+) -> Result<()> {
+    backups.set_passphrase(&app, &passphrase).await
+}
+
+#[tauri::command]
+pub async fn run_backup_now(backups: State<'_, Backups>, app: AppHandle) -> Result<BackupSummary> {
+    backups.run(&app).await
+}
+
+/// Replace the history with the latest backup. Needs the passphrase, since
+/// only the public half of the key is kept on this machine.
+#[tauri::command]
+pub async fn restore_backup(
+    passphrase: String,
+    backups: State<'_, Backups>,
+    app: AppHandle,
+) -> Result<RestoreSummary> {
+    backups.restore(&app, &passphrase).await
+}
@@ -0,0 +1,80 @@
+//! Encrypting backups with age.
+//!
+//! Each destination gets its own random X25519 key. The private half is
+//! stored next to the backups, encrypted with the user's passphrase, and only
+//! the public half is kept locally. Scheduled backups therefore run without
+//! the passphrase, and nothing on this machine can read them back without it.
+
+use crate::error::{AppError, Result};
+use age::secrecy::{ExposeSecret, SecretString};
+use age::x25519::{Identity, Recipient};
+use std::io::{Read, Write};
+
+pub fn generate_key() -> Identity {
+    Identity::generate()
+}
+
+/// The public half of `key`, which is all that's needed to encrypt
+pub fn recipient_of(key: &Identity) -> String {
+    key.to_public().to_string()
+}
+
+/// Encrypt `plaintext` so only the holder of `recipient`'s key can read it
+pub fn encrypt(recipient: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let recipient: Recipient = recipient
+        .parse()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid backup key: {}", e)))?;
+    let encryptor =
+        age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))
+            .map_err(|e| AppError::Internal(format!("Failed to encrypt backup: {}", e)))?;
+    wrap(encryptor, plaintext)
+}
+
+pub fn decrypt(key: &Identity, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    unwrap(ciphertext, key)
+}
+
+/// Encrypt `key` with `passphrase`, for storing next to the backups
+pub fn lock_key(key: &Identity, passphrase: &str) -> Result<Vec<u8>> {
+    let encryptor =
+        age::Encryptor::with_user_passphrase(SecretString::from(passphrase.to_string()));
+    wrap(encryptor, key.to_string().expose_secret().as_bytes())
+}
+
+/// Decrypt a key stored by `lock_key`
+pub fn unlock_key(locked: &[u8], passphrase: &str) -> Result<Identity> {
+    let identity = age::scrypt::Identity::new(SecretString::from(passphrase.to_string()));
+    let plaintext = unwrap(locked, &identity)
+        .map_err(|_| AppError::InvalidInput("Wrong backup passphrase".to_string()))?;
+    String::from_utf8(plaintext)
+        .ok()
+        .and_then(|key| key.trim().parse().ok())
+        .ok_or_else(|| AppError::InvalidInput("The stored backup key is damaged".to_string()))
+}
+
+fn wrap(encryptor: age::Encryptor, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| AppError::Internal(format!("Failed to encrypt backup: {}", e)))?;
+    writer
+        .write_all(plaintext)
+        .map_err(|e| AppError::Internal(format!("Failed to encrypt backup: {}", e)))?;
+    writer
+        .finish()
+        .map_err(|e| AppError::Internal(format!("Failed to encrypt backup: {}", e)))?;
+    Ok(encrypted)
+}
+
+fn unwrap(ciphertext: &[u8], identity: &dyn age::Identity) -> Result<Vec<u8>> {
+    let decryptor = age::Decryptor::new(ciphertext)
+        .map_err(|e| AppError::Internal(format!("Failed to read backup: {}", e)))?;
+    let mut reader = decryptor
+        .decrypt(std::iter::once(identity))
+        .map_err(|e| AppError::Internal(format!("Failed to decrypt backup: {}", e)))?;
+    let mut plaintext = Vec::new();
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| AppError::Internal(format!("Failed to decrypt backup: {}", e)))?;
+    Ok(plaintext)
+}
@@ -0,0 +1,582 @@
+//! Encrypted backups of the history database and its audio.
+//!
+//! A backup run uploads a snapshot of the history database, every audio file
+//! not uploaded before, and a manifest saying where each audio file belongs.
+//! Audio is stored under a hash of its contents, so later runs only send
+//! files that are new or have changed since (trimmed, split, moved to another
+//! format), and a changed file never overwrites the copy an older backup
+//! points to. Everything is encrypted before it leaves the machine (see
+//! `crypto`).
+//!
+//! Runs happen on a schedule once a destination and passphrase are set up,
+//! or on demand with `run_backup_now`. `restore_backup` brings the database
+//! back and rewrites any audio files that are missing locally.
+
+pub mod commands;
+mod crypto;
+mod target;
+
+pub use target::{BackupTarget, S3Target, WebDavTarget};
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, BackupStatusChanged};
+use crate::history::HistoryStore;
+use crate::privacy;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+pub const BACKUP_FILE: &str = "backup.json";
+
+/// How often the scheduler checks whether a backup is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Uploads and downloads give up after this long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// Object keys at the destination
+const KEY_OBJECT: &str = "key.age";
+const DATABASE_OBJECT: &str = "history.db.age";
+const MANIFEST_OBJECT: &str = "manifest.json.age";
+const AUDIO_FOLDER: &str = "audio";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupConfig {
+    /// Back up on a schedule; `run_backup_now` works either way
+    pub enabled: bool,
+    pub interval_hours: u32,
+    /// Also back up audio, not only the database
+    pub include_audio: bool,
+    pub target: BackupTarget,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            include_audio: true,
+            target: BackupTarget::None,
+        }
+    }
+}
+
+/// Where backups stand, for settings and diagnostics
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupStatus {
+    /// A destination is set and a passphrase has been entered for it
+    pub ready: bool,
+    pub running: bool,
+    /// Milliseconds since the Unix epoch
+    pub last_run_at: Option<i64>,
+    pub last_success_at: Option<i64>,
+    /// Why the last run failed, if it did
+    pub last_error: Option<String>,
+    /// Audio files stored at the destination
+    pub audio_files: usize,
+}
+
+/// Result of a backup run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub database_bytes: usize,
+    pub audio_files: usize,
+    /// Audio files sent this run
+    pub uploaded_audio: usize,
+}
+
+/// Result of a restore
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    /// When the restored backup was made, in milliseconds since the Unix epoch
+    pub backed_up_at: i64,
+    pub restored_audio: usize,
+    /// Audio files left alone because they're still on disk
+    pub existing_audio: usize,
+    /// Audio files that couldn't be written back
+    pub failed_audio: usize,
+}
+
+/// Lists what a backup contains; stored encrypted next to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    created_at: i64,
+    audio: Vec<AudioObject>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioObject {
+    entry_id: String,
+    /// Key at the destination
+    object: String,
+    /// Where the file lives locally
+    path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredBackup {
+    config: BackupConfig,
+    /// Public half of the destination's key (see `crypto`)
+    recipient: Option<String>,
+    last_run_at: Option<i64>,
+    last_success_at: Option<i64>,
+    last_error: Option<String>,
+    /// Audio already at the destination, by entry id
+    audio_objects: BTreeMap<String, UploadedAudio>,
+}
+
+/// An entry's audio as it was when it was last uploaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadedAudio {
+    /// Key at the destination
+    object: String,
+    path: String,
+    /// `None` after a restore, when the file wasn't read yet
+    stamp: Option<FileStamp>,
+}
+
+/// Size and modification time of a file, to tell it changed without reading
+/// it again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStamp {
+    len: u64,
+    /// Milliseconds since the Unix epoch
+    modified: i64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_millis() as i64)
+            .unwrap_or_default();
+        Ok(Self {
+            len: metadata.len(),
+            modified,
+        })
+    }
+}
+
+pub struct Backups {
+    path: PathBuf,
+    state: Mutex<StoredBackup>,
+    running: AtomicBool,
+}
+
+/// Clears the running flag when a run ends, however it ends
+struct RunGuard<'a>(&'a AtomicBool);
+
+impl Drop for RunGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Backups {
+    pub fn open(path: &Path) -> Self {
+        let state = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                StoredBackup::default()
+            }),
+            Err(_) => StoredBackup::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(state),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    pub fn config(&self) -> BackupConfig {
+        self.state
+            .lock()
+            .map(|state| state.config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: BackupConfig) -> Result<()> {
+        config.target.validate()?;
+        if config.interval_hours == 0 {
+            return Err(AppError::InvalidInput(
+                "Backup interval must be at least an hour".to_string(),
+            ));
+        }
+        self.modify(|state| {
+            // A new destination needs its own key and a full upload
+            if config.target != state.config.target {
+                state.recipient = None;
+                state.audio_objects.clear();
+            }
+            state.config = config;
+        })
+    }
+
+    pub fn status(&self) -> BackupStatus {
+        let running = self.running.load(Ordering::SeqCst);
+        match self.state.lock() {
+            Ok(state) => BackupStatus {
+                ready: state.config.target.is_set() && state.recipient.is_some(),
+                running,
+                last_run_at: state.last_run_at,
+                last_success_at: state.last_success_at,
+                last_error: state.last_error.clone(),
+                audio_files: state.audio_objects.len(),
+            },
+            Err(_) => BackupStatus {
+                ready: false,
+                running,
+                last_run_at: None,
+                last_success_at: None,
+                last_error: None,
+                audio_files: 0,
+            },
+        }
+    }
+
+    /// Unlock the destination's key with `passphrase`, or create one locked
+    /// with it if the destination has none yet
+    pub async fn set_passphrase(&self, app: &AppHandle, passphrase: &str) -> Result<()> {
+        if passphrase.len() < 8 {
+            return Err(AppError::InvalidInput(
+                "Use a backup passphrase of at least 8 characters".to_string(),
+            ));
+        }
+        let target = self.config().target;
+        let client = connect(app, &target)?;
+        let key = match target.get(&client, KEY_OBJECT).await? {
+            Some(locked) => crypto::unlock_key(&locked, passphrase)?,
+            None => {
+                let key = crypto::generate_key();
+                target
+                    .put(&client, KEY_OBJECT, crypto::lock_key(&key, passphrase)?)
+                    .await?;
+                info!("Created a backup key at the destination");
+                key
+            }
+        };
+        self.modify(|state| state.recipient = Some(crypto::recipient_of(&key)))
+    }
+
+    /// Back up now, unless a backup or restore is already running
+    pub async fn run(&self, app: &AppHandle) -> Result<BackupSummary> {
+        let guard = self.begin(app)?;
+        let result = self.upload(app).await;
+        let now = Local::now().timestamp_millis();
+        let saved = self.modify(|state| {
+            state.last_run_at = Some(now);
+            match &result {
+                Ok(_) => {
+                    state.last_success_at = Some(now);
+                    state.last_error = None;
+                }
+                Err(e) => state.last_error = Some(e.to_string()),
+            }
+        });
+        drop(guard);
+        let _ = BackupStatusChanged::emit(app, &self.status());
+        saved?;
+        result
+    }
+
+    /// Replace the history with the latest backup and write back missing audio
+    pub async fn restore(&self, app: &AppHandle, passphrase: &str) -> Result<RestoreSummary> {
+        let guard = self.begin(app)?;
+        let result = self.download(app, passphrase).await;
+        drop(guard);
+        let _ = BackupStatusChanged::emit(app, &self.status());
+        result
+    }
+
+    fn begin(&self, app: &AppHandle) -> Result<RunGuard<'_>> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(AppError::InvalidInput(
+                "A backup or restore is already running".to_string(),
+            ));
+        }
+        let _ = BackupStatusChanged::emit(app, &self.status());
+        Ok(RunGuard(&self.running))
+    }
+
+    async fn upload(&self, app: &AppHandle) -> Result<BackupSummary> {
+        let (config, recipient, mut uploaded) = {
+            let state = self
+                .state
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock backup state: {}", e)))?;
+            (
+                state.config.clone(),
+                state.recipient.clone(),
+                state.audio_objects.clone(),
+            )
+        };
+        let recipient = recipient.ok_or_else(|| {
+            AppError::InvalidInput("Enter a backup passphrase before backing up".to_string())
+        })?;
+        let target = config.target;
+        let client = connect(app, &target)?;
+        target.prepare(&client, &[AUDIO_FOLDER]).await?;
+
+        let history = app.state::<HistoryStore>();
+        let snapshot = tempfile::NamedTempFile::new()?;
+        history.snapshot_to(snapshot.path())?;
+        let database = std::fs::read(snapshot.path())?;
+        target
+            .put(
+                &client,
+                DATABASE_OBJECT,
+                crypto::encrypt(&recipient, &database)?,
+            )
+            .await?;
+
+        let mut manifest = Manifest {
+            created_at: Local::now().timestamp_millis(),
+            audio: Vec::new(),
+        };
+        let mut uploaded_audio = 0;
+        if config.include_audio {
+            for (entry_id, path) in history.audio_paths()? {
+                let stamp = match FileStamp::of(Path::new(&path)) {
+                    Ok(stamp) => stamp,
+                    Err(e) => {
+                        warn!("Skipping audio {} in backup: {}", path, e);
+                        continue;
+                    }
+                };
+                let object = match uploaded.get(&entry_id) {
+                    Some(known) if known.path == path && known.stamp.as_ref() == Some(&stamp) => {
+                        known.object.clone()
+                    }
+                    // New, or trimmed, split or renamed since the last run
+                    _ => {
+                        let audio = match std::fs::read(&path) {
+                            Ok(audio) => audio,
+                            Err(e) => {
+                                warn!("Skipping audio {} in backup: {}", path, e);
+                                continue;
+                            }
+                        };
+                        let object = audio_object(&audio, Path::new(&path));
+                        if !uploaded.values().any(|known| known.object == object) {
+                            target
+                                .put(&client, &object, crypto::encrypt(&recipient, &audio)?)
+                                .await?;
+                            uploaded_audio += 1;
+                        }
+                        let known = UploadedAudio {
+                            object: object.clone(),
+                            path: path.clone(),
+                            stamp: Some(stamp),
+                        };
+                        self.modify(|state| {
+                            state.audio_objects.insert(entry_id.clone(), known.clone());
+                        })?;
+                        uploaded.insert(entry_id.clone(), known);
+                        object
+                    }
+                };
+                manifest.audio.push(AudioObject {
+                    entry_id,
+                    object,
+                    path,
+                });
+            }
+        }
+
+        let manifest_json = serde_json::to_vec(&manifest).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize backup manifest: {}", e))
+        })?;
+        target
+            .put(
+                &client,
+                MANIFEST_OBJECT,
+                crypto::encrypt(&recipient, &manifest_json)?,
+            )
+            .await?;
+
+        info!(
+            "Backed up history ({} bytes) and {} new audio files",
+            database.len(),
+            uploaded_audio
+        );
+        Ok(BackupSummary {
+            database_bytes: database.len(),
+            audio_files: manifest.audio.len(),
+            uploaded_audio,
+        })
+    }
+
+    async fn download(&self, app: &AppHandle, passphrase: &str) -> Result<RestoreSummary> {
+        let target = self.config().target;
+        let client = connect(app, &target)?;
+        let missing = || AppError::InvalidInput("No backup found at the destination".to_string());
+
+        let locked = target.get(&client, KEY_OBJECT).await?.ok_or_else(missing)?;
+        let key = crypto::unlock_key(&locked, passphrase)?;
+        let manifest = target
+            .get(&client, MANIFEST_OBJECT)
+            .await?
+            .ok_or_else(missing)?;
+        let manifest: Manifest = serde_json::from_slice(&crypto::decrypt(&key, &manifest)?)
+            .map_err(|e| AppError::InvalidInput(format!("Backup manifest is damaged: {}", e)))?;
+        let database = target
+            .get(&client, DATABASE_OBJECT)
+            .await?
+            .ok_or_else(missing)?;
+
+        let snapshot = tempfile::NamedTempFile::new()?;
+        std::fs::write(snapshot.path(), crypto::decrypt(&key, &database)?)?;
+        app.state::<HistoryStore>().restore_from(snapshot.path())?;
+        info!(
+            "Restored history from the backup of {}",
+            manifest.created_at
+        );
+
+        let mut summary = RestoreSummary {
+            backed_up_at: manifest.created_at,
+            restored_audio: 0,
+            existing_audio: 0,
+            failed_audio: 0,
+        };
+        for audio in &manifest.audio {
+            let path = Path::new(&audio.path);
+            if path.exists() {
+                summary.existing_audio += 1;
+                continue;
+            }
+            match restore_audio(&target, &client, &key, audio).await {
+                Ok(()) => summary.restored_audio += 1,
+                Err(e) => {
+                    warn!("Failed to restore audio {}: {}", audio.path, e);
+                    summary.failed_audio += 1;
+                }
+            }
+        }
+
+        // Keep backing up to the same destination without asking again
+        self.modify(|state| {
+            state.recipient = Some(crypto::recipient_of(&key));
+            state.audio_objects = manifest
+                .audio
+                .iter()
+                .map(|audio| {
+                    let known = UploadedAudio {
+                        object: audio.object.clone(),
+                        path: audio.path.clone(),
+                        stamp: None,
+                    };
+                    (audio.entry_id.clone(), known)
+                })
+                .collect();
+        })?;
+        Ok(summary)
+    }
+
+    fn is_due(&self, now: i64) -> bool {
+        let Ok(state) = self.state.lock() else {
+            return false;
+        };
+        if !state.config.enabled || !state.config.target.is_set() || state.recipient.is_none() {
+            return false;
+        }
+        let interval = i64::from(state.config.interval_hours) * 60 * 60 * 1000;
+        state
+            .last_run_at
+            .is_none_or(|last_run| now - last_run >= interval)
+    }
+
+    /// Check every few minutes whether a scheduled backup is due and run it
+    pub fn start(&self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                let backups = app.state::<Backups>();
+                if !backups.is_due(Local::now().timestamp_millis()) {
+                    continue;
+                }
+                match backups.run(&app).await {
+                    Ok(summary) => info!(
+                        "Scheduled backup finished: {} audio files uploaded",
+                        summary.uploaded_audio
+                    ),
+                    Err(e) => error!("Scheduled backup failed: {}", e),
+                }
+            }
+        });
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut StoredBackup)) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock backup state: {}", e)))?;
+        f(&mut state);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*state)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize backup state: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+/// An HTTP client for `target`, if it's configured and privacy mode allows it
+fn connect(app: &AppHandle, target: &BackupTarget) -> Result<reqwest::Client> {
+    if !target.is_set() {
+        return Err(AppError::InvalidInput(
+            "No backup destination is configured".to_string(),
+        ));
+    }
+    privacy::ensure_allowed(app, target.base_url())?;
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Key audio is stored under: a hash of its contents, keeping the file's
+/// extension
+fn audio_object(audio: &[u8], path: &Path) -> String {
+    let hash = format!("{:x}", Sha256::digest(audio));
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}/{}.{}.age", AUDIO_FOLDER, hash, ext),
+        None => format!("{}/{}.age", AUDIO_FOLDER, hash),
+    }
+}
+
+async fn restore_audio(
+    target: &BackupTarget,
+    client: &reqwest::Client,
+    key: &age::x25519::Identity,
+    audio: &AudioObject,
+) -> Result<()> {
+    let encrypted = target
+        .get(client, &audio.object)
+        .await?
+        .ok_or_else(|| AppError::InvalidInput("Audio is missing from the backup".to_string()))?;
+    let path = Path::new(&audio.path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, crypto::decrypt(key, &encrypted)?)?;
+    Ok(())
+}
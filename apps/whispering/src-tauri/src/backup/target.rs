@@ -0,0 +1,292 @@
+//! Where backups are stored: an S3-compatible bucket or a WebDAV folder.
+//!
+//! Both are plain HTTP object stores here. Objects are put and fetched by
+//! key, relative to the bucket prefix or the WebDAV folder. S3 requests are
+//! signed with AWS Signature Version 4, which MinIO, Backblaze B2, Wasabi
+//! and R2 accept as well. Path-style URLs keep custom endpoints working.
+
+use crate::error::{AppError, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BackupTarget {
+    #[default]
+    None,
+    S3(S3Target),
+    WebDav(WebDavTarget),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct S3Target {
+    /// e.g. `https://s3.eu-central-1.amazonaws.com` or a MinIO server
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// Folder inside the bucket, e.g. `whispering`
+    pub prefix: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebDavTarget {
+    /// Folder to back up into, e.g.
+    /// `https://cloud.example.com/remote.php/dav/files/me/Whispering`
+    pub url: String,
+    pub username: String,
+    /// An app password, for Nextcloud
+    pub password: String,
+}
+
+impl BackupTarget {
+    pub fn is_set(&self) -> bool {
+        !matches!(self, BackupTarget::None)
+    }
+
+    /// The server requests go to, for the privacy check
+    pub fn base_url(&self) -> &str {
+        match self {
+            BackupTarget::None => "",
+            BackupTarget::S3(s3) => &s3.endpoint,
+            BackupTarget::WebDav(webdav) => &webdav.url,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let (url, missing) = match self {
+            BackupTarget::None => return Ok(()),
+            BackupTarget::S3(s3) => (
+                &s3.endpoint,
+                s3.bucket.trim().is_empty() || s3.region.trim().is_empty(),
+            ),
+            BackupTarget::WebDav(webdav) => (&webdav.url, false),
+        };
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(AppError::InvalidInput(format!(
+                "Backup URL must start with https:// or http://, got {}",
+                url
+            )));
+        }
+        if missing {
+            return Err(AppError::InvalidInput(
+                "S3 backups need a bucket and a region".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Create the folders objects are put into, where the store has folders
+    pub async fn prepare(&self, client: &Client, folders: &[&str]) -> Result<()> {
+        let BackupTarget::WebDav(webdav) = self else {
+            return Ok(());
+        };
+        let mkcol = Method::from_bytes(b"MKCOL").expect("MKCOL is a valid method");
+        let mut urls = vec![format!("{}/", webdav.url.trim_end_matches('/'))];
+        urls.extend(
+            folders
+                .iter()
+                .map(|folder| format!("{}/", webdav.object_url(folder))),
+        );
+        for url in urls {
+            let response = client
+                .request(mkcol.clone(), &url)
+                .basic_auth(&webdav.username, Some(&webdav.password))
+                .send()
+                .await
+                .map_err(|e| AppError::Network(e.to_string()))?;
+            // 405 means the folder is already there
+            if !response.status().is_success()
+                && response.status() != StatusCode::METHOD_NOT_ALLOWED
+            {
+                check_status(response).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn put(&self, client: &Client, key: &str, body: Vec<u8>) -> Result<()> {
+        let request = match self {
+            BackupTarget::None => return Err(not_configured()),
+            BackupTarget::S3(s3) => s3.signed(client, Method::PUT, key, body)?,
+            BackupTarget::WebDav(webdav) => client
+                .put(webdav.object_url(key))
+                .basic_auth(&webdav.username, Some(&webdav.password))
+                .body(body),
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        check_status(response).await?;
+        Ok(())
+    }
+
+    /// Fetch an object; `None` if it doesn't exist
+    pub async fn get(&self, client: &Client, key: &str) -> Result<Option<Vec<u8>>> {
+        let request = match self {
+            BackupTarget::None => return Err(not_configured()),
+            BackupTarget::S3(s3) => s3.signed(client, Method::GET, key, Vec::new())?,
+            BackupTarget::WebDav(webdav) => client
+                .get(webdav.object_url(key))
+                .basic_auth(&webdav.username, Some(&webdav.password)),
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let bytes = check_status(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| AppError::Network(format!("Failed to download backup: {}", e)))?;
+        Ok(Some(bytes.to_vec()))
+    }
+}
+
+impl S3Target {
+    fn object_url(&self, key: &str) -> String {
+        let prefix = self.prefix.trim_matches('/');
+        let key = if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", prefix, key)
+        };
+        format!(
+            "{}/{}/{}",
+            self.endpoint.trim_end_matches('/'),
+            encode_path(&self.bucket),
+            encode_path(&key)
+        )
+    }
+
+    /// A request signed with AWS Signature Version 4
+    fn signed(
+        &self,
+        client: &Client,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<RequestBuilder> {
+        let url = reqwest::Url::parse(&self.object_url(key))
+            .map_err(|e| AppError::InvalidInput(format!("Invalid S3 endpoint: {}", e)))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => {
+                return Err(AppError::InvalidInput(
+                    "S3 endpoint has no host".to_string(),
+                ))
+            }
+        };
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = sha256_hex(&body);
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method.as_str(),
+            url.path(),
+            host,
+            payload_hash,
+            amz_date,
+            SIGNED_HEADERS,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region.trim());
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signing_key = [date.as_str(), self.region.trim(), "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_access_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part.as_bytes()),
+            );
+        let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        Ok(client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                    self.access_key_id, scope, SIGNED_HEADERS, signature
+                ),
+            )
+            .body(body))
+    }
+}
+
+impl WebDavTarget {
+    fn object_url(&self, key: &str) -> String {
+        format!("{}/{}", self.url.trim_end_matches('/'), encode_path(key))
+    }
+}
+
+fn not_configured() -> AppError {
+    AppError::InvalidInput("No backup destination is configured".to_string())
+}
+
+/// Turn a non-success response into the matching error
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("Backup server returned {}: {}", status, body.trim());
+    Err(match status.as_u16() {
+        401 | 403 => AppError::PermissionDenied(message),
+        _ => AppError::Network(message),
+    })
+}
+
+/// Percent-encode each segment of `path`, keeping the slashes
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            segment
+                .bytes()
+                .map(|b| match b {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                        (b as char).to_string()
+                    }
+                    _ => format!("%{:02X}", b),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,51 @@
+//! Which optional subsystems this build includes.
+//!
+//! Distro packagers and minimal builds can turn off the cargo features for
+//! subsystems with heavy native dependencies (onnxruntime and whisper.cpp
+//! for `local-inference`) or ones they don't want to ship. Modules that
+//! other code relies on keep their API and fail with
+//! [`AppError::Unsupported`]; the rest, and their commands, aren't compiled
+//! at all, so the frontend checks here before offering them.
+
+use crate::error::{AppError, Result};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildFeatures {
+    /// whisper.cpp and Parakeet transcription, and punctuation restoration
+    pub local_inference: bool,
+    /// The voice memo inbox phones upload to
+    pub http_api: bool,
+    /// OBS Studio and the journal file
+    pub integrations: bool,
+    /// Recording overlay and live captions windows
+    pub overlay: bool,
+    /// WebAssembly plugins
+    pub plugins: bool,
+}
+
+impl BuildFeatures {
+    pub const fn current() -> Self {
+        Self {
+            local_inference: cfg!(feature = "local-inference"),
+            http_api: cfg!(feature = "http-api"),
+            integrations: cfg!(feature = "integrations"),
+            overlay: cfg!(feature = "overlay"),
+            plugins: cfg!(feature = "plugins"),
+        }
+    }
+}
+
+/// The error for using `what` in a build without `feature`
+pub(crate) fn missing(feature: &str, what: &str) -> AppError {
+    AppError::Unsupported(format!(
+        "{} isn't included in this build (cargo feature `{}`)",
+        what, feature
+    ))
+}
+
+#[tauri::command]
+pub async fn get_build_features() -> Result<BuildFeatures> {
+    Ok(BuildFeatures::current())
+}
@@ -0,0 +1,168 @@
+//! A zip of everything the app keeps: transcripts as JSON and Markdown,
+//! the audio behind them, settings and usage statistics.
+//!
+//! Audio is copied into the zip one file at a time as the bulk job reaches
+//! each entry, so libraries of many gigabytes never have to fit in memory.
+//! The zip is written next to its destination as `.part` and only renamed
+//! once complete; an archive dropped before `finish` (a cancelled job, a
+//! failure) deletes its partial file.
+
+use crate::error::{AppError, Result};
+use crate::history::{now_millis, HistoryEntry, SCHEMA_VERSION};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Describes the archive; written last, as `manifest.json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest<'a> {
+    /// Milliseconds since the Unix epoch
+    exported_at: i64,
+    app_version: &'a str,
+    history_schema_version: u32,
+    entries: usize,
+    /// Where each entry's audio is in the archive, by entry id
+    audio: &'a BTreeMap<String, String>,
+}
+
+pub struct Archive {
+    zip: Mutex<Option<ZipWriter<BufWriter<File>>>>,
+    part: PathBuf,
+    path: PathBuf,
+    audio: Mutex<BTreeMap<String, String>>,
+}
+
+impl Archive {
+    /// Start writing the archive that will end up at `path`
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let part = path.with_extension("zip.part");
+        let file = File::create(&part)?;
+        Ok(Self {
+            zip: Mutex::new(Some(ZipWriter::new(BufWriter::new(file)))),
+            part,
+            path: path.to_path_buf(),
+            audio: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Copy an entry's audio into `audio/`, if it has any
+    pub fn add_audio(&self, entry: &HistoryEntry) -> Result<()> {
+        let Some(audio_path) = entry.audio_path.as_deref().map(Path::new) else {
+            return Ok(());
+        };
+        let name = match audio_path.extension() {
+            Some(ext) => format!("audio/{}.{}", entry.id, ext.to_string_lossy()),
+            None => format!("audio/{}", entry.id),
+        };
+        let mut source = File::open(audio_path).map_err(|e| {
+            AppError::Internal(format!("Failed to open {}: {}", audio_path.display(), e))
+        })?;
+        // Audio is compressed already, or WAV that can exceed 4 GiB
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .large_file(true);
+        self.write(&name, options, |zip| {
+            std::io::copy(&mut source, zip)?;
+            Ok(())
+        })?;
+        self.audio
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock archive: {}", e)))?
+            .insert(entry.id.clone(), name);
+        Ok(())
+    }
+
+    /// Add a text file, compressed
+    pub fn add_text(&self, name: &str, contents: &str) -> Result<()> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        self.write(name, options, |zip| Ok(zip.write_all(contents.as_bytes())?))
+    }
+
+    /// Add a value as pretty-printed JSON
+    pub fn add_json(&self, name: &str, value: &impl Serialize) -> Result<()> {
+        let contents = serde_json::to_string_pretty(value)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize {}: {}", name, e)))?;
+        self.add_text(name, &contents)
+    }
+
+    fn write(
+        &self,
+        name: &str,
+        options: SimpleFileOptions,
+        contents: impl FnOnce(&mut ZipWriter<BufWriter<File>>) -> Result<()>,
+    ) -> Result<()> {
+        let mut zip = self
+            .zip
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock archive: {}", e)))?;
+        let zip = zip
+            .as_mut()
+            .ok_or_else(|| AppError::Internal("The archive is already finished".to_string()))?;
+        zip.start_file(name, options).map_err(|e| {
+            AppError::Internal(format!("Failed to add {} to the archive: {}", name, e))
+        })?;
+        contents(zip)
+    }
+
+    /// Write the manifest, close the zip and move it to its destination,
+    /// returning that
+    pub fn finish(&self, app_version: &str, entries: usize) -> Result<PathBuf> {
+        let audio = self
+            .audio
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock archive: {}", e)))?
+            .clone();
+        self.add_json(
+            "manifest.json",
+            &Manifest {
+                exported_at: now_millis(),
+                app_version,
+                history_schema_version: SCHEMA_VERSION,
+                entries,
+                audio: &audio,
+            },
+        )?;
+
+        let zip = self
+            .zip
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock archive: {}", e)))?
+            .take()
+            .ok_or_else(|| AppError::Internal("The archive is already finished".to_string()))?;
+        let mut file = zip
+            .finish()
+            .map_err(|e| AppError::Internal(format!("Failed to finish the archive: {}", e)))?;
+        file.flush()?;
+        drop(file);
+        std::fs::rename(&self.part, &self.path)?;
+        info!(
+            "Exported {} entries and {} recordings to {:?}",
+            entries,
+            audio.len(),
+            self.path
+        );
+        Ok(self.path.clone())
+    }
+}
+
+impl Drop for Archive {
+    fn drop(&mut self) {
+        let unfinished = self.zip.get_mut().map(|zip| zip.take()).ok().flatten();
+        if let Some(zip) = unfinished {
+            drop(zip);
+            if let Err(e) = std::fs::remove_file(&self.part) {
+                warn!("Failed to remove unfinished {:?}: {}", self.part, e);
+            }
+        }
+    }
+}
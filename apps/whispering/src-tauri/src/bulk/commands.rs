@@ -0,0 +1,247 @@
+use super::export::{self, ExportedEntry};
+use super::{Archive, BulkJobKind, BulkJobs, ExportFormat};
+use crate::chapters;
+use crate::error::{AppError, Result};
+use crate::file_names::{self, FileNameContext};
+use crate::history::commands::retranscribe_entry;
+use crate::history::{now_millis, HistoryFilter, HistoryStore};
+use crate::punctuation::Punctuator;
+use crate::settings::SettingsStore;
+use crate::stats::{self, StatsRange};
+use crate::telemetry::{self, Feature, Telemetry};
+use crate::timestamps::Timestamps;
+use crate::transcription::{LocalEngine, ModelManager};
+use crate::transforms::Transforms;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tracing::warn;
+
+/// How `bulk_reprocess` re-transcribes each entry
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessPipeline {
+    pub provider: LocalEngine,
+    pub model: String,
+    pub language: Option<String>,
+}
+
+/// Delete every entry matching `filter` in the background, returning the job
+/// id. An empty filter matches the whole history, so it's refused unless
+/// `all` confirms that's what's meant.
+///
+/// Audio the app keeps in its data folder (inbox uploads, trimmed and split
+/// audio) goes with its entry. Files elsewhere, like the recordings folder
+/// or a watched folder, are the user's and stay.
+#[tauri::command]
+pub async fn bulk_delete(
+    filter: HistoryFilter,
+    all: Option<bool>,
+    jobs: State<'_, BulkJobs>,
+    app_handle: AppHandle,
+) -> Result<String> {
+    if filter.is_empty() && !all.unwrap_or(false) {
+        return Err(AppError::InvalidInput(
+            "Choose which entries to delete, or confirm deleting all of them".to_string(),
+        ));
+    }
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve app data directory: {}", e)))?;
+    telemetry::record(&app_handle, Feature::BulkDelete);
+    let app = app_handle.clone();
+    jobs.start(
+        &app_handle,
+        BulkJobKind::Delete,
+        &filter,
+        move |entry| {
+            app.state::<HistoryStore>().delete(&entry.id)?;
+            let owned = entry
+                .audio_path
+                .as_deref()
+                .map(Path::new)
+                .filter(|path| path.starts_with(&data_dir));
+            if let Some(path) = owned {
+                if let Err(e) = std::fs::remove_file(path) {
+                    warn!("Failed to delete audio {:?} of {}: {}", path, entry.id, e);
+                }
+            }
+            Ok(())
+        },
+        || Ok(()),
+    )
+}
+
+/// Write every entry matching `filter` to one file at `path`, or to a file
+/// in `path` named by the file name template if it's a folder
+#[tauri::command]
+pub async fn bulk_export(
+    filter: HistoryFilter,
+    format: ExportFormat,
+    path: String,
+    jobs: State<'_, BulkJobs>,
+    app_handle: AppHandle,
+) -> Result<String> {
+    // Times are written as the settings say when the export starts
+    let timestamps = Timestamps::current(&app_handle);
+    let mut path = PathBuf::from(path);
+    if path.is_dir() {
+        let context = FileNameContext {
+            created_at: now_millis(),
+            name: "history".to_string(),
+            ..Default::default()
+        };
+        let template = app_handle.state::<SettingsStore>().get().file_name_template;
+        let stem = file_names::render(&template, &context, &timestamps)?.unwrap_or_else(|| {
+            format!(
+                "whispering-history-{}",
+                timestamps.filename(context.created_at)
+            )
+        });
+        path = file_names::unique_path(&path, &stem, format.extension());
+    }
+
+    telemetry::record(&app_handle, Feature::BulkExport);
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let sink = collected.clone();
+    let app = app_handle.clone();
+    jobs.start(
+        &app_handle,
+        BulkJobKind::Export,
+        &filter,
+        move |entry| {
+            let chapters = chapters::chapters_for(&app.state::<HistoryStore>(), entry)?;
+            sink.lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock export buffer: {}", e)))?
+                .push(ExportedEntry {
+                    entry: entry.clone(),
+                    chapters,
+                });
+            Ok(())
+        },
+        move || {
+            let entries = collected
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock export buffer: {}", e)))?;
+            export::write(format, &entries, &timestamps, &path)
+        },
+    )
+}
+
+/// Write everything the app keeps to a zip at `path`, or to a new file in
+/// `path` if it's a folder: every transcript as JSON and Markdown, their
+/// audio, the settings and usage statistics. `app_settings` are the
+/// frontend's own settings, which Rust can't read. Returns the job id.
+#[tauri::command]
+pub async fn export_all_data(
+    path: String,
+    app_settings: Option<Value>,
+    jobs: State<'_, BulkJobs>,
+    app_handle: AppHandle,
+) -> Result<String> {
+    let timestamps = Timestamps::current(&app_handle);
+    let mut path = PathBuf::from(path);
+    if path.is_dir() {
+        let stem = format!("whispering-data-{}", timestamps.filename(now_millis()));
+        path = file_names::unique_path(&path, &stem, "zip");
+    }
+
+    let archive = Arc::new(Archive::create(&path)?);
+    let writer = archive.clone();
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let sink = collected.clone();
+    let app = app_handle.clone();
+    let history_app = app_handle.clone();
+    jobs.start(
+        &app_handle,
+        BulkJobKind::Archive,
+        &HistoryFilter::default(),
+        move |entry| {
+            let chapters = chapters::chapters_for(&history_app.state::<HistoryStore>(), entry)?;
+            sink.lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock export buffer: {}", e)))?
+                .push(ExportedEntry {
+                    entry: entry.clone(),
+                    chapters,
+                });
+            writer.add_audio(entry)
+        },
+        move || {
+            let entries = collected
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock export buffer: {}", e)))?;
+            for (format, name) in [
+                (ExportFormat::Json, "transcripts/history.json"),
+                (ExportFormat::Markdown, "transcripts/history.md"),
+            ] {
+                archive.add_text(name, &export::render(format, &entries, &timestamps)?)?;
+            }
+
+            let settings = app.state::<SettingsStore>();
+            if let Ok(contents) = std::fs::read_to_string(settings.path()) {
+                let name = settings
+                    .path()
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                archive.add_text(&format!("settings/{}", name), &contents)?;
+            }
+            if let Some(app_settings) = &app_settings {
+                archive.add_json("settings/app.json", app_settings)?;
+            }
+
+            let history = app.state::<HistoryStore>();
+            archive.add_json(
+                "usage/stats.json",
+                &stats::compute(&history, &StatsRange::default())?,
+            )?;
+            archive.add_json(
+                "usage/telemetry.json",
+                &app.state::<Telemetry>().pending(&app),
+            )?;
+            archive.finish(&app.package_info().version.to_string(), entries.len())?;
+            Ok(())
+        },
+    )
+}
+
+/// Re-transcribe every entry matching `filter`, each result becoming a new
+/// transcript version
+#[tauri::command]
+pub async fn bulk_reprocess(
+    filter: HistoryFilter,
+    pipeline: ReprocessPipeline,
+    jobs: State<'_, BulkJobs>,
+    app_handle: AppHandle,
+) -> Result<String> {
+    telemetry::record(&app_handle, Feature::BulkReprocess);
+    let app = app_handle.clone();
+    jobs.start(
+        &app_handle,
+        BulkJobKind::Reprocess,
+        &filter,
+        move |entry| {
+            retranscribe_entry(
+                &app.state::<HistoryStore>(),
+                &app.state::<ModelManager>(),
+                &app.state::<Punctuator>(),
+                &app.state::<Transforms>(),
+                entry,
+                pipeline.provider,
+                &pipeline.model,
+                pipeline.language.clone(),
+            )
+            .map(|_| ())
+        },
+        || Ok(()),
+    )
+}
+
+/// Stop a bulk job; returns false if it already finished
+#[tauri::command]
+pub async fn cancel_bulk_job(job_id: String, jobs: State<'_, BulkJobs>) -> Result<bool> {
+    jobs.cancel(&job_id)
+}
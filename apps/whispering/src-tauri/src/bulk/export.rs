@@ -0,0 +1,149 @@
+use crate::error::{AppError, Result};
+use crate::history::{Chapter, HistoryEntry};
+use crate::timestamps::Timestamps;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// File format for `bulk_export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// An entry as exported, with the chapters of long transcripts
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedEntry {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+}
+
+/// An entry in a JSON export, with its time readable next to the
+/// milliseconds
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonEntry<'a> {
+    #[serde(flatten)]
+    exported: &'a ExportedEntry,
+    /// RFC 3339 in the configured zone
+    created_at_local: String,
+}
+
+/// Write entries to a single file, oldest first, with times as
+/// `timestamps` formats them
+pub fn write(
+    format: ExportFormat,
+    entries: &[ExportedEntry],
+    timestamps: &Timestamps,
+    path: &Path,
+) -> Result<()> {
+    let contents = render(format, entries, timestamps)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// The contents `write` would write
+pub fn render(
+    format: ExportFormat,
+    entries: &[ExportedEntry],
+    timestamps: &Timestamps,
+) -> Result<String> {
+    let mut entries: Vec<&ExportedEntry> = entries.iter().collect();
+    entries.sort_by_key(|exported| exported.entry.created_at);
+
+    let contents = match format {
+        ExportFormat::Json => {
+            let entries: Vec<JsonEntry> = entries
+                .iter()
+                .map(|&exported| JsonEntry {
+                    exported,
+                    created_at_local: timestamps.rfc3339(exported.entry.created_at),
+                })
+                .collect();
+            serde_json::to_string_pretty(&entries)
+                .map_err(|e| AppError::Internal(format!("Failed to serialize history: {}", e)))?
+        }
+        ExportFormat::Csv => to_csv(&entries, timestamps),
+        ExportFormat::Markdown => to_markdown(&entries, timestamps),
+    };
+    Ok(contents)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(entries: &[&ExportedEntry], timestamps: &Timestamps) -> String {
+    let mut csv = String::from("id,created_at,source,duration_seconds,favorite,tags,transcript\n");
+    for ExportedEntry { entry, .. } in entries {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            entry.id,
+            csv_field(&timestamps.display(entry.created_at)),
+            entry.source.as_str(),
+            entry.duration_seconds,
+            entry.favorite,
+            csv_field(&entry.tags.join(";")),
+            csv_field(&entry.transcript),
+        );
+    }
+    csv
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on
+fn timestamp(seconds: f32) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn to_markdown(entries: &[&ExportedEntry], timestamps: &Timestamps) -> String {
+    let mut markdown = String::from("# Whispering history\n");
+    for ExportedEntry { entry, chapters } in entries {
+        let _ = write!(
+            markdown,
+            "\n## {}\n\n",
+            timestamps.display(entry.created_at)
+        );
+        if !entry.tags.is_empty() {
+            let _ = writeln!(markdown, "Tags: {}\n", entry.tags.join(", "));
+        }
+        if !chapters.is_empty() {
+            let _ = writeln!(markdown, "Chapters:\n");
+            for chapter in chapters {
+                let _ = writeln!(markdown, "- {} {}", timestamp(chapter.start), chapter.title);
+            }
+            let _ = writeln!(markdown);
+        }
+        let _ = writeln!(markdown, "{}", entry.transcript.trim());
+    }
+    markdown
+}
@@ -0,0 +1,166 @@
+pub mod archive;
+pub mod commands;
+mod export;
+
+pub use archive::Archive;
+pub use export::ExportFormat;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, BulkJobFinished, BulkJobProgress};
+use crate::history::{HistoryEntry, HistoryFilter, HistoryStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// What a bulk job does to each entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkJobKind {
+    Delete,
+    Export,
+    Reprocess,
+    /// `export_all_data`
+    Archive,
+}
+
+/// Payload of the `bulk://progress` event, emitted after each entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkProgress {
+    pub job_id: String,
+    pub kind: BulkJobKind,
+    pub done: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+/// Payload of the `bulk://finished` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkFinished {
+    pub job_id: String,
+    pub kind: BulkJobKind,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cancelled: bool,
+    /// Set when the job as a whole failed (e.g. the export file couldn't be written)
+    pub error: Option<String>,
+}
+
+/// Runs history batch operations on background threads
+///
+/// Jobs report progress through `bulk://progress` and finish with
+/// `bulk://finished`, so the history page stays responsive while thousands
+/// of entries are deleted or re-transcribed.
+pub struct BulkJobs {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl BulkJobs {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start a job over the entries matching `filter`, returning its id
+    ///
+    /// `work` runs once per entry; a failure is counted and the job moves on
+    /// to the next entry. `finish` runs once after the last entry unless the
+    /// job was cancelled.
+    pub fn start<W, F>(
+        &self,
+        app: &AppHandle,
+        kind: BulkJobKind,
+        filter: &HistoryFilter,
+        mut work: W,
+        finish: F,
+    ) -> Result<String>
+    where
+        W: FnMut(&HistoryEntry) -> Result<()> + Send + 'static,
+        F: FnOnce() -> Result<()> + Send + 'static,
+    {
+        let entries = app.state::<HistoryStore>().list_filtered(filter, None)?;
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock bulk jobs: {}", e)))?
+            .insert(job_id.clone(), cancelled.clone());
+        info!(
+            "Starting bulk {:?} job {} over {} entries",
+            kind,
+            job_id,
+            entries.len()
+        );
+
+        let app = app.clone();
+        let id = job_id.clone();
+        thread::spawn(move || {
+            let total = entries.len();
+            let mut attempted = 0;
+            let mut failed = 0;
+            for entry in &entries {
+                if cancelled.load(Ordering::SeqCst) {
+                    break;
+                }
+                attempted += 1;
+                if let Err(e) = work(entry) {
+                    warn!("Bulk {:?} job {} failed on {}: {}", kind, id, entry.id, e);
+                    failed += 1;
+                }
+                let _ = BulkJobProgress::emit(
+                    &app,
+                    &BulkProgress {
+                        job_id: id.clone(),
+                        kind,
+                        done: attempted,
+                        failed,
+                        total,
+                    },
+                );
+            }
+
+            let was_cancelled = cancelled.load(Ordering::SeqCst);
+            let error = if was_cancelled {
+                None
+            } else {
+                finish().err().map(|e| e.to_string())
+            };
+            let finished = BulkFinished {
+                job_id: id.clone(),
+                kind,
+                succeeded: attempted - failed,
+                failed,
+                cancelled: was_cancelled,
+                error,
+            };
+            info!("Bulk {:?} job {} finished: {:?}", kind, id, finished);
+            let _ = BulkJobFinished::emit(&app, &finished);
+            if let Ok(mut running) = app.state::<BulkJobs>().running.lock() {
+                running.remove(&id);
+            }
+        });
+
+        Ok(job_id)
+    }
+
+    /// Stop a running job after the entry it's working on
+    pub fn cancel(&self, job_id: &str) -> Result<bool> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock bulk jobs: {}", e)))?;
+        match running.get(job_id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::SeqCst);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use super::{Calendar, CalendarConfig, CalendarEvent};
+use crate::error::Result;
+use crate::history::{HistoryEntry, HistoryStore};
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_calendar_config(calendar: State<'_, Calendar>) -> Result<CalendarConfig> {
+    Ok(calendar.config())
+}
+
+#[tauri::command]
+pub async fn set_calendar_config(
+    config: CalendarConfig,
+    calendar: State<'_, Calendar>,
+) -> Result<()> {
+    debug!("Updating calendar settings: enabled={}", config.enabled);
+    calendar.set_config(config)
+}
+
+/// The event happening right now, e.g. to show while recording
+#[tauri::command]
+pub async fn get_current_calendar_event(
+    calendar: State<'_, Calendar>,
+    app_handle: AppHandle,
+) -> Result<Option<CalendarEvent>> {
+    let now = crate::history::now_millis();
+    calendar.event_between(&app_handle, now, now + 1).await
+}
+
+/// Title an entry after the calendar event it was recorded during. Call right
+/// after the entry is created; returns `None` if no event matches.
+#[tauri::command]
+pub async fn label_from_calendar(
+    id: String,
+    calendar: State<'_, Calendar>,
+    history: State<'_, HistoryStore>,
+    app_handle: AppHandle,
+) -> Result<Option<HistoryEntry>> {
+    calendar.label_entry(&app_handle, &history, &id).await
+}
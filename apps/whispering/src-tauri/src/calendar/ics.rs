@@ -0,0 +1,341 @@
+//! Just enough iCalendar (RFC 5545) parsing to find the meeting happening at
+//! a given time.
+//!
+//! Supported: timed `VEVENT`s with `SUMMARY`, `DTSTART`, `DTEND` or
+//! `DURATION`, `ATTENDEE` and daily/weekly `RRULE`s. All-day and cancelled
+//! events are skipped. `TZID` times are read as local time, which is right
+//! for the common case of a calendar in the user's own time zone.
+
+use super::CalendarEvent;
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: i64,
+    until: Option<DateTime<Local>>,
+    /// Days a weekly event repeats on; empty means the day it started
+    weekdays: Vec<Weekday>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IcsEvent {
+    title: String,
+    attendees: Vec<String>,
+    start: DateTime<Local>,
+    duration: Duration,
+    recurrence: Option<Recurrence>,
+}
+
+impl IcsEvent {
+    /// The occurrence of this event overlapping `from..to`, if there is one
+    pub fn occurrence_between(
+        &self,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Option<CalendarEvent> {
+        let overlaps = |start: DateTime<Local>| start < to && start + self.duration > from;
+        let Some(rule) = &self.recurrence else {
+            return overlaps(self.start).then(|| self.at(self.start));
+        };
+
+        // Occurrences that could overlap start at most one event length earlier
+        let first_day = (from - self.duration)
+            .date_naive()
+            .max(self.start.date_naive());
+        let mut day = first_day;
+        while day <= to.date_naive() {
+            if self.repeats_on(rule, day) {
+                let start = Local
+                    .from_local_datetime(&day.and_time(self.start.time()))
+                    .earliest()?;
+                if rule.until.map_or(true, |until| start <= until) && overlaps(start) {
+                    return Some(self.at(start));
+                }
+            }
+            day = day.succ_opt()?;
+        }
+        None
+    }
+
+    fn repeats_on(&self, rule: &Recurrence, day: NaiveDate) -> bool {
+        let first = self.start.date_naive();
+        match rule.frequency {
+            Frequency::Daily => (day - first).num_days() % rule.interval == 0,
+            Frequency::Weekly => {
+                let weekdays = if rule.weekdays.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    rule.weekdays.clone()
+                };
+                let week = |date: NaiveDate| {
+                    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+                };
+                let weeks = (week(day) - week(first)).num_days() / 7;
+                weekdays.contains(&day.weekday()) && weeks % rule.interval == 0
+            }
+        }
+    }
+
+    fn at(&self, start: DateTime<Local>) -> CalendarEvent {
+        CalendarEvent {
+            title: self.title.clone(),
+            start: start.timestamp_millis(),
+            end: (start + self.duration).timestamp_millis(),
+            attendees: self.attendees.clone(),
+        }
+    }
+}
+
+/// A content line split into name, parameters and value
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, String)>,
+    value: &'a str,
+}
+
+impl Property<'_> {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub fn parse(ics: &str) -> Vec<IcsEvent> {
+    let lines = unfold(ics);
+    let mut events = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+
+    for line in &lines {
+        let Some(property) = parse_line(line) else {
+            continue;
+        };
+        match (property.name.as_str(), property.value) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(event) = current.take().and_then(|props| build_event(&props)) {
+                    events.push(event);
+                }
+            }
+            _ => {
+                if let Some(props) = current.as_mut() {
+                    props.push(property);
+                }
+            }
+        }
+    }
+    events
+}
+
+fn build_event(props: &[Property]) -> Option<IcsEvent> {
+    let find = |name: &str| props.iter().find(|p| p.name == name);
+
+    if find("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED")) {
+        return None;
+    }
+    let start = find("DTSTART").and_then(parse_time)?;
+    let duration = match find("DTEND").and_then(parse_time) {
+        Some(end) => end - start,
+        None => find("DURATION")
+            .and_then(|p| parse_duration(p.value))
+            .unwrap_or_else(Duration::zero),
+    };
+    let attendees = props
+        .iter()
+        .filter(|p| p.name == "ATTENDEE")
+        .map(|p| match p.param("CN") {
+            Some(name) => name.to_string(),
+            None => strip_mailto(p.value).to_string(),
+        })
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    Some(IcsEvent {
+        title: find("SUMMARY")
+            .map(|p| unescape(p.value))
+            .unwrap_or_default(),
+        attendees,
+        start,
+        duration,
+        recurrence: find("RRULE").and_then(|p| parse_rule(p.value)),
+    })
+}
+
+/// Join continuation lines (starting with a space or tab) onto the previous line
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=value;PARAM="quoted:value":VALUE`
+fn parse_line(line: &str) -> Option<Property<'_>> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.trim_matches('"').to_string()))
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value,
+    })
+}
+
+/// Timed start or end; dates without a time (all-day events) give `None`
+fn parse_time(property: &Property) -> Option<DateTime<Local>> {
+    if property
+        .param("VALUE")
+        .is_some_and(|value| value.eq_ignore_ascii_case("DATE"))
+    {
+        return None;
+    }
+    let value = property.value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&time).with_timezone(&Local));
+    }
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local.from_local_datetime(&time).earliest()
+}
+
+/// `P1W`, `PT1H30M`, `P1DT2H`, ...
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    'S' => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+/// `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20250101T000000Z`
+fn parse_rule(value: &str) -> Option<Recurrence> {
+    let mut rule = Recurrence {
+        frequency: Frequency::Daily,
+        interval: 1,
+        until: None,
+        weekdays: Vec::new(),
+    };
+    let mut frequency = None;
+    for (key, value) in value.split(';').filter_map(|part| part.split_once('=')) {
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    // Monthly and yearly meetings only match their first occurrence
+                    _ => None,
+                }
+            }
+            "INTERVAL" => rule.interval = value.parse::<i64>().ok()?.max(1),
+            "UNTIL" => {
+                rule.until = parse_time(&Property {
+                    name: "UNTIL".to_string(),
+                    params: Vec::new(),
+                    value,
+                })
+                .or_else(|| {
+                    // A date-only UNTIL includes that whole day
+                    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+                    Local
+                        .from_local_datetime(&date.and_hms_opt(23, 59, 59)?)
+                        .earliest()
+                })
+            }
+            "BYDAY" => {
+                rule.weekdays = value
+                    .split(',')
+                    // Drop ordinals such as the 2 in 2MO, which only apply to monthly rules
+                    .map(|day| {
+                        day.trim_start_matches(|c: char| c == '-' || c == '+' || c.is_ascii_digit())
+                    })
+                    .filter_map(parse_weekday)
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+    rule.frequency = frequency?;
+    Some(rule)
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    Some(match day.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn strip_mailto(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map_or(value, |_| &value[7..])
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
@@ -0,0 +1,55 @@
+//! Events from the calendars configured on this Mac.
+//!
+//! Queried through the Calendar app's scripting interface, which reads the
+//! same EventKit store, so no framework bindings are needed. The first query
+//! triggers macOS's calendar access prompt.
+
+use super::CalendarEvent;
+use crate::error::{AppError, Result};
+use std::process::Command;
+
+const SCRIPT: &str = r#"
+function run(argv) {
+    const from = new Date(Number(argv[0]));
+    const to = new Date(Number(argv[1]));
+    const events = [];
+    for (const calendar of Application('Calendar').calendars()) {
+        const matches = calendar.events.whose({
+            _and: [{ startDate: { _lessThan: to } }, { endDate: { _greaterThan: from } }],
+        })();
+        for (const event of matches) {
+            if (event.alldayEvent()) continue;
+            events.push({
+                title: event.summary(),
+                start: event.startDate().getTime(),
+                end: event.endDate().getTime(),
+                attendees: event.attendees().map((a) => a.displayName() || a.email()),
+            });
+        }
+    }
+    return JSON.stringify(events);
+}
+"#;
+
+pub fn events_between(from: i64, to: i64) -> Result<Vec<CalendarEvent>> {
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", SCRIPT])
+        .arg(from.to_string())
+        .arg(to.to_string())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(
+            if stderr.contains("-1743") || stderr.contains("Not authorized") {
+                AppError::PermissionDenied(
+                    "Allow Whispering to access Calendar in System Settings → Privacy & Security"
+                        .to_string(),
+                )
+            } else {
+                AppError::Internal(format!("Failed to read calendars: {}", stderr.trim()))
+            },
+        );
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| AppError::Internal(format!("Unexpected calendar output: {}", e)))
+}
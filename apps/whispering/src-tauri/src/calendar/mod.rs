@@ -0,0 +1,245 @@
+//! Labelling recordings with the calendar event they were made during.
+//!
+//! Events come from an ICS feed (the "secret address" every calendar service
+//! offers) or, on macOS without a feed, from the calendars set up in the
+//! Calendar app. Entries recorded during an event get its name as their
+//! title and its attendees in their metadata.
+
+pub mod commands;
+mod ics;
+#[cfg(target_os = "macos")]
+mod macos;
+
+use crate::error::{AppError, Result};
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::privacy;
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+
+pub const CALENDAR_FILE: &str = "calendar.json";
+
+/// How long a downloaded ICS feed is reused before fetching it again
+const FEED_TTL: Duration = Duration::from_secs(15 * 60);
+
+const FEED_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CalendarConfig {
+    pub enabled: bool,
+    /// ICS feed to read events from; on macOS, the system calendars are used if empty
+    pub ics_url: String,
+}
+
+/// A single occurrence of a calendar event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub title: String,
+    /// Milliseconds since the Unix epoch
+    pub start: i64,
+    pub end: i64,
+    pub attendees: Vec<String>,
+}
+
+struct CachedFeed {
+    url: String,
+    fetched: Instant,
+    events: Vec<ics::IcsEvent>,
+}
+
+pub struct Calendar {
+    path: PathBuf,
+    config: Mutex<CalendarConfig>,
+    feed: Mutex<Option<CachedFeed>>,
+}
+
+impl Calendar {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                CalendarConfig::default()
+            }),
+            Err(_) => CalendarConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            feed: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> CalendarConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: CalendarConfig) -> Result<()> {
+        let url = config.ics_url.trim();
+        if !url.is_empty()
+            && !["http://", "https://", "webcal://"]
+                .iter()
+                .any(|scheme| url.starts_with(scheme))
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Calendar feed must be an http(s) or webcal URL, got {}",
+                url
+            )));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize calendar settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self.config.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock calendar settings: {}", e))
+        })? = config;
+        if let Ok(mut feed) = self.feed.lock() {
+            *feed = None;
+        }
+        Ok(())
+    }
+
+    /// The event overlapping `from..to` (milliseconds) the most, if any
+    pub async fn event_between(
+        &self,
+        app: &AppHandle,
+        from: i64,
+        to: i64,
+    ) -> Result<Option<CalendarEvent>> {
+        let config = self.config();
+        let url = config.ics_url.trim();
+        let events = if !url.is_empty() {
+            self.feed_events_between(app, url, from, to).await?
+        } else {
+            system_events_between(from, to).await?
+        };
+
+        let overlap = |event: &CalendarEvent| event.end.min(to) - event.start.max(from);
+        Ok(events.into_iter().max_by_key(overlap))
+    }
+
+    async fn feed_events_between(
+        &self,
+        app: &AppHandle,
+        url: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<CalendarEvent>> {
+        let fresh = self.feed.lock().ok().is_some_and(|feed| {
+            feed.as_ref()
+                .is_some_and(|feed| feed.url == url && feed.fetched.elapsed() < FEED_TTL)
+        });
+        if !fresh {
+            let events = ics::parse(&fetch_feed(app, url).await?);
+            debug!("Loaded {} events from the calendar feed", events.len());
+            if let Ok(mut feed) = self.feed.lock() {
+                *feed = Some(CachedFeed {
+                    url: url.to_string(),
+                    fetched: Instant::now(),
+                    events,
+                });
+            }
+        }
+
+        let (Some(from), Some(to)) = (
+            Local.timestamp_millis_opt(from).single(),
+            Local.timestamp_millis_opt(to).single(),
+        ) else {
+            return Ok(Vec::new());
+        };
+        let feed = self
+            .feed
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock calendar feed: {}", e)))?;
+        Ok(feed
+            .iter()
+            .flat_map(|feed| feed.events.iter())
+            .filter_map(|event| event.occurrence_between(from, to))
+            .collect())
+    }
+
+    /// Title an entry after the event it was recorded during
+    ///
+    /// Returns the updated entry, or `None` if calendar labelling is off or
+    /// no event overlaps the recording.
+    pub async fn label_entry(
+        &self,
+        app: &AppHandle,
+        history: &HistoryStore,
+        id: &str,
+    ) -> Result<Option<HistoryEntry>> {
+        if !self.config().enabled {
+            return Ok(None);
+        }
+        let entry = history.get(id)?;
+        let end = entry.created_at + (entry.duration_seconds as f64 * 1000.0) as i64;
+        let Some(event) = self
+            .event_between(app, entry.created_at, end.max(entry.created_at + 1))
+            .await?
+        else {
+            return Ok(None);
+        };
+        info!("Labelling {} with calendar event {:?}", id, event.title);
+        Ok(Some(history.set_meeting(
+            id,
+            &event.title,
+            &event.attendees,
+        )?))
+    }
+}
+
+async fn fetch_feed(app: &AppHandle, url: &str) -> Result<String> {
+    // webcal:// is just a hint to open the feed in a calendar app
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.to_string(),
+    };
+    privacy::ensure_allowed(app, &url)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(FEED_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::Network(format!(
+            "Calendar feed returned {}",
+            status
+        )));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to read calendar feed: {}", e)))
+}
+
+#[cfg(target_os = "macos")]
+async fn system_events_between(from: i64, to: i64) -> Result<Vec<CalendarEvent>> {
+    tokio::task::spawn_blocking(move || macos::events_between(from, to))
+        .await
+        .map_err(|e| AppError::Internal(format!("Calendar lookup panicked: {}", e)))?
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn system_events_between(_from: i64, _to: i64) -> Result<Vec<CalendarEvent>> {
+    Err(AppError::InvalidInput(
+        "Set a calendar feed (ICS URL) to label recordings on this platform".to_string(),
+    ))
+}
@@ -0,0 +1,30 @@
+use crate::captions::{CaptionsStyle, CaptionsWindow};
+use crate::error::Result;
+use tauri::State;
+use tracing::info;
+
+/// Show the live captions window, optionally changing how it looks
+#[tauri::command]
+pub async fn show_captions(
+    style: Option<CaptionsStyle>,
+    captions: State<'_, CaptionsWindow>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Showing captions: {:?}", style);
+    captions.show(&app_handle, style)
+}
+
+#[tauri::command]
+pub async fn hide_captions(
+    captions: State<'_, CaptionsWindow>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Hiding captions");
+    captions.hide(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_captions_style(captions: State<'_, CaptionsWindow>) -> Result<CaptionsStyle> {
+    Ok(captions.style())
+}
@@ -0,0 +1,171 @@
+//! Live captions window.
+//!
+//! A resizable, always-on-top window showing streaming transcription results
+//! as they arrive. The page is static (`captions.html`), so results and style
+//! changes are pushed into it by calling its `window.whispering*` functions.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, StreamingFinal, StreamingInterim};
+use crate::monitors::active_monitor;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Event, Listener, Manager, PhysicalPosition, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+use tracing::{debug, warn};
+
+const CAPTIONS_LABEL: &str = "captions";
+
+/// Initial logical size; the user can resize the window afterwards
+const CAPTIONS_WIDTH: f64 = 720.0;
+const CAPTIONS_HEIGHT: f64 = 140.0;
+
+/// Distance from the bottom of the screen when first shown, in logical pixels
+const CAPTIONS_MARGIN: f64 = 80.0;
+
+/// How captions are rendered - provided by the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionsStyle {
+    /// Font size in CSS pixels
+    pub font_size: u32,
+    /// Opacity of the background behind the text, 0.0 to 1.0
+    pub background_opacity: f64,
+}
+
+impl Default for CaptionsStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 28,
+            background_opacity: 0.75,
+        }
+    }
+}
+
+impl CaptionsStyle {
+    fn clamped(self) -> Self {
+        Self {
+            font_size: self.font_size.clamp(10, 96),
+            background_opacity: self.background_opacity.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Owns the captions window and forwards streaming results to it
+pub struct CaptionsWindow {
+    style: Mutex<CaptionsStyle>,
+}
+
+impl CaptionsWindow {
+    pub fn new() -> Self {
+        Self {
+            style: Mutex::new(CaptionsStyle::default()),
+        }
+    }
+
+    pub fn style(&self) -> CaptionsStyle {
+        self.style
+            .lock()
+            .map(|style| style.clone())
+            .unwrap_or_default()
+    }
+
+    /// Forward streaming results to the window while it is open
+    pub fn follow_streaming(&self, app: AppHandle) {
+        for (event, kind) in [
+            (StreamingInterim::NAME, "interim"),
+            (StreamingFinal::NAME, "final"),
+        ] {
+            let handle = app.clone();
+            app.listen_any(event, move |event: Event| {
+                if let Some(window) = handle.get_webview_window(CAPTIONS_LABEL) {
+                    call(
+                        &window,
+                        "whisperingCaption",
+                        &format!("'{}', {}", kind, event.payload()),
+                    );
+                }
+            });
+        }
+    }
+
+    /// Open the captions window, or restyle it if it is already open
+    pub fn show(&self, app: &AppHandle, style: Option<CaptionsStyle>) -> Result<()> {
+        if let Some(style) = style {
+            *self.style.lock().map_err(|e| {
+                AppError::Internal(format!("Failed to lock captions style: {}", e))
+            })? = style.clamped();
+        }
+        let style = self.style();
+
+        if let Some(window) = app.get_webview_window(CAPTIONS_LABEL) {
+            let style = serde_json::to_string(&style).map_err(|e| {
+                AppError::Internal(format!("Failed to serialize captions style: {}", e))
+            })?;
+            call(&window, "whisperingStyle", &style);
+            return window
+                .show()
+                .map_err(|e| AppError::Internal(format!("Failed to show captions window: {}", e)));
+        }
+
+        // The style travels in the URL so it applies before the first paint
+        let url = format!(
+            "captions.html?fontSize={}&backgroundOpacity={}",
+            style.font_size, style.background_opacity
+        );
+        let window = WebviewWindowBuilder::new(app, CAPTIONS_LABEL, WebviewUrl::App(url.into()))
+            .title("Whispering Captions")
+            .inner_size(CAPTIONS_WIDTH, CAPTIONS_HEIGHT)
+            .min_inner_size(240.0, 60.0)
+            .transparent(true)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .resizable(true)
+            .focused(false)
+            .visible(false)
+            .visible_on_all_workspaces(true)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create captions window: {}", e)))?;
+
+        if let Some(position) = initial_position(app, &window) {
+            debug!("Placing captions window at {:?}", position);
+            let _ = window.set_position(position);
+        }
+        window
+            .show()
+            .map_err(|e| AppError::Internal(format!("Failed to show captions window: {}", e)))
+    }
+
+    pub fn hide(&self, app: &AppHandle) {
+        if let Some(window) = app.get_webview_window(CAPTIONS_LABEL) {
+            if let Err(e) = window.close() {
+                warn!("Failed to close captions window: {}", e);
+            }
+        }
+    }
+}
+
+/// Call a function defined by `captions.html` with already-encoded arguments
+fn call(window: &WebviewWindow, function: &str, args: &str) {
+    // The page may still be loading, in which case there is nothing to update yet
+    let script = format!("window.{0} && window.{0}({1})", function, args);
+    if let Err(e) = window.eval(&script) {
+        warn!("Failed to update captions window: {}", e);
+    }
+}
+
+/// Bottom center of the display the user is working on
+fn initial_position(app: &AppHandle, window: &WebviewWindow) -> Option<PhysicalPosition<i32>> {
+    let monitor = active_monitor(app).ok().flatten()?;
+    let size = window.outer_size().ok()?;
+    let origin = monitor.position();
+    let screen = monitor.size();
+    let margin = (CAPTIONS_MARGIN * monitor.scale_factor()).round() as i32;
+    Some(PhysicalPosition::new(
+        origin.x + (screen.width as i32 - size.width as i32) / 2,
+        origin.y + screen.height as i32 - size.height as i32 - margin,
+    ))
+}
@@ -0,0 +1,11 @@
+use crate::error::Result;
+use crate::history::{Chapter, HistoryStore};
+use tauri::State;
+
+/// Chapters of a long transcript, detected on first request; empty for
+/// transcripts too short to split
+#[tauri::command]
+pub async fn get_chapters(id: String, history: State<'_, HistoryStore>) -> Result<Vec<Chapter>> {
+    let entry = history.get(&id)?;
+    super::chapters_for(&history, &entry)
+}
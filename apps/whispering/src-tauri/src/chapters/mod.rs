@@ -0,0 +1,228 @@
+//! Splitting long transcripts into chapters by topic.
+//!
+//! Detection is a lightweight take on TextTiling: the transcript is cut into
+//! sentences, and at each sentence boundary the vocabulary of the sentences
+//! before is compared with the sentences after. Chapters start where the
+//! two sides share the fewest words, as long as every chapter stays long
+//! enough to be worth navigating to. Each chapter is titled with its most
+//! frequent distinctive words.
+//!
+//! History doesn't keep segment timings, so chapter times are estimated
+//! from word positions, assuming an even pace of speech. Stopwords are only
+//! filtered for English; other languages still work, with noisier titles.
+
+pub mod commands;
+
+use crate::error::Result;
+use crate::history::{Chapter, HistoryEntry, HistoryStore};
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
+
+/// Transcripts shorter than this aren't split (about seven minutes of speech)
+const MIN_WORDS: usize = 1000;
+
+/// No chapter is shorter than this
+const MIN_CHAPTER_WORDS: usize = 250;
+
+/// Roughly one chapter per this many words, before similarity decides
+const WORDS_PER_CHAPTER: usize = 600;
+
+/// Sentences compared on each side of a candidate boundary
+const WINDOW_SENTENCES: usize = 5;
+
+/// Unpunctuated transcripts are cut into pseudo-sentences this long
+const FALLBACK_SENTENCE_WORDS: usize = 20;
+
+/// Words in a chapter title
+const TITLE_WORDS: usize = 3;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "again", "also", "and", "any", "are", "because", "been", "before", "being",
+    "but", "can", "could", "did", "does", "doing", "don't", "down", "each", "even", "for", "from",
+    "get", "going", "gonna", "got", "had", "has", "have", "her", "here", "him", "his", "how",
+    "i'm", "into", "it's", "its", "just", "know", "like", "let's", "make", "maybe", "more",
+    "most", "much", "need", "not", "now", "okay", "one", "only", "other", "our", "out", "over",
+    "really", "right", "said", "say", "see", "she", "should", "some", "something", "such",
+    "than", "that", "that's", "the", "their", "them", "then", "there", "these", "they", "thing",
+    "things", "think", "this", "those", "through", "too", "under", "very", "want", "was", "way",
+    "well", "were", "what", "when", "where", "which", "while", "who", "why", "will", "with",
+    "would", "yeah", "yes", "you", "your",
+];
+
+/// A sentence and where it starts, counted in words
+struct Sentence {
+    first_word: usize,
+    words: usize,
+    terms: Vec<String>,
+}
+
+fn term(word: &str, stopwords: &HashSet<&str>) -> Option<String> {
+    let term = word
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    (term.chars().count() > 2 && !stopwords.contains(term.as_str())).then_some(term)
+}
+
+fn sentences(text: &str) -> Vec<Sentence> {
+    let stopwords: HashSet<&str> = STOPWORDS.iter().copied().collect();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let punctuated = words
+        .iter()
+        .any(|word| word.ends_with(['.', '!', '?']));
+
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, word) in words.iter().enumerate() {
+        let ends = if punctuated {
+            word.ends_with(['.', '!', '?'])
+        } else {
+            i + 1 - start >= FALLBACK_SENTENCE_WORDS
+        };
+        if ends || i + 1 == words.len() {
+            sentences.push(Sentence {
+                first_word: start,
+                words: i + 1 - start,
+                terms: words[start..=i]
+                    .iter()
+                    .filter_map(|word| term(word, &stopwords))
+                    .collect(),
+            });
+            start = i + 1;
+        }
+    }
+    sentences
+}
+
+fn counts<'a>(sentences: impl Iterator<Item = &'a Sentence>) -> HashMap<&'a str, f32> {
+    let mut counts = HashMap::new();
+    for sentence in sentences {
+        for term in &sentence.terms {
+            *counts.entry(term.as_str()).or_insert(0.0) += 1.0;
+        }
+    }
+    counts
+}
+
+fn cosine(a: &HashMap<&str, f32>, b: &HashMap<&str, f32>) -> f32 {
+    let dot: f32 = a
+        .iter()
+        .filter_map(|(term, x)| b.get(term).map(|y| x * y))
+        .sum();
+    let norm = |v: &HashMap<&str, f32>| v.values().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// The most frequent terms of `sentences`, weighted against how common they
+/// are in the whole transcript
+fn title(sentences: &[Sentence], overall: &HashMap<&str, f32>) -> String {
+    let local = counts(sentences.iter());
+    let mut order: Vec<&str> = Vec::new();
+    for sentence in sentences {
+        for term in &sentence.terms {
+            if !order.contains(&term.as_str()) {
+                order.push(term);
+            }
+        }
+    }
+    let score = |term: &str| {
+        let count = local.get(term).copied().unwrap_or(0.0);
+        count * count / overall.get(term).copied().unwrap_or(1.0)
+    };
+    // Stable sort keeps first appearance as the tie-breaker
+    order.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    order
+        .into_iter()
+        .take(TITLE_WORDS)
+        .map(|term| {
+            let mut chars = term.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Split `text`, spoken over `duration_seconds`, into chapters. Returns
+/// nothing for transcripts too short to be worth splitting.
+pub fn detect(text: &str, duration_seconds: f32) -> Vec<Chapter> {
+    let sentences = sentences(text);
+    let total_words: usize = sentences.iter().map(|s| s.words).sum();
+    if total_words < MIN_WORDS {
+        return Vec::new();
+    }
+
+    // Similarity across each boundary; boundary `b` is before sentence `b`
+    let mut candidates: Vec<(usize, f32)> = (1..sentences.len())
+        .map(|b| {
+            let before = counts(sentences[b.saturating_sub(WINDOW_SENTENCES)..b].iter());
+            let after =
+                counts(sentences[b..(b + WINDOW_SENTENCES).min(sentences.len())].iter());
+            (b, cosine(&before, &after))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    // Take the least similar boundaries that keep every chapter long enough
+    let wanted = (total_words / WORDS_PER_CHAPTER).max(2);
+    let mut boundaries: Vec<usize> = Vec::new();
+    for (b, _) in candidates {
+        if boundaries.len() + 1 >= wanted {
+            break;
+        }
+        let at = sentences[b].first_word;
+        let mut edges: Vec<usize> = boundaries
+            .iter()
+            .map(|&existing| sentences[existing].first_word)
+            .chain([0, at, total_words])
+            .collect();
+        edges.sort_unstable();
+        if edges.windows(2).all(|pair| pair[1] - pair[0] >= MIN_CHAPTER_WORDS) {
+            boundaries.push(b);
+        }
+    }
+    boundaries.sort_unstable();
+    debug!(
+        "Found {} chapters in {} words",
+        boundaries.len() + 1,
+        total_words
+    );
+
+    let overall = counts(sentences.iter());
+    let seconds_per_word = duration_seconds / total_words as f32;
+    let starts: Vec<usize> = [0].into_iter().chain(boundaries).collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &first)| {
+            let last = starts.get(i + 1).copied().unwrap_or(sentences.len());
+            let end_word = sentences
+                .get(last)
+                .map_or(total_words, |sentence| sentence.first_word);
+            Chapter {
+                start: sentences[first].first_word as f32 * seconds_per_word,
+                end: end_word as f32 * seconds_per_word,
+                title: title(&sentences[first..last], &overall),
+            }
+        })
+        .collect()
+}
+
+/// Chapters of an entry, detected and stored the first time they're asked
+/// for after its transcript changed
+pub fn chapters_for(history: &HistoryStore, entry: &HistoryEntry) -> Result<Vec<Chapter>> {
+    if let Some(chapters) = history.chapters(&entry.id)? {
+        return Ok(chapters);
+    }
+    let chapters = detect(&entry.transcript, entry.duration_seconds);
+    if !chapters.is_empty() {
+        history.set_chapters(&entry.id, &chapters)?;
+    }
+    Ok(chapters)
+}
@@ -0,0 +1,142 @@
+//! Launcher-friendly subcommands (`whispering toggle`, `whispering status`,
+//! `whispering last-transcript`, `whispering transcribe <file>`,
+//! `whispering ui`) that talk to the running instance over the IPC socket
+//! and print machine-readable output, plus the GUI's startup flags.
+
+use crate::error::AppError;
+use crate::ipc::{IpcClient, IpcCommand, IpcMessage};
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: whispering <toggle|start|stop|status [--json]|last-transcript|ui>
+       whispering transcribe <audio file>
+       whispering [--daemon] [--config <path>]";
+
+/// Run a CLI subcommand if one was given, returning the process exit code
+///
+/// Returns `None` when the arguments don't name a subcommand so the caller can
+/// launch the GUI as usual.
+pub fn run(args: &[String]) -> Option<i32> {
+    let subcommand = args.get(1)?.as_str();
+    let json = args.iter().skip(2).any(|arg| arg == "--json");
+
+    let command = match subcommand {
+        "toggle" => IpcCommand::ToggleRecording,
+        "start" => IpcCommand::StartRecording,
+        "stop" => IpcCommand::StopRecording,
+        "status" => IpcCommand::Status,
+        "last-transcript" => IpcCommand::LastTranscript,
+        "ui" => IpcCommand::ShowUi,
+        "transcribe" => {
+            attach_console();
+            match transcribe_path(args.get(2)) {
+                Ok(path) => IpcCommand::TranscribeFile { path },
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return Some(1);
+                }
+            }
+        }
+        "help" | "--help" | "-h" => {
+            attach_console();
+            println!("{}", USAGE);
+            return Some(0);
+        }
+        _ => return None,
+    };
+
+    attach_console();
+
+    let response = IpcClient::connect().and_then(|mut client| client.request(command));
+    Some(print_response(response, json))
+}
+
+/// Absolute path of the file to transcribe, since the app resolves paths
+/// from its own working directory rather than the caller's
+fn transcribe_path(arg: Option<&String>) -> Result<String, String> {
+    let arg = arg.ok_or("Missing the audio file to transcribe")?;
+    std::fs::canonicalize(arg)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Can't open {}: {}", arg, e))
+}
+
+/// Settings file given with `--config <path>` or `--config=<path>`
+pub fn config_path(args: &[String]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+fn print_response(response: Result<IpcMessage, AppError>, json: bool) -> i32 {
+    let message = match response {
+        Ok(message) => message,
+        Err(e) => IpcMessage::Error {
+            message: e.to_string(),
+        },
+    };
+
+    if json {
+        match serde_json::to_string(&message) {
+            Ok(output) => println!("{}", output),
+            Err(e) => eprintln!("Failed to serialize response: {}", e),
+        }
+    }
+
+    match message {
+        IpcMessage::Ok => {
+            if !json {
+                println!("ok");
+            }
+            0
+        }
+        IpcMessage::Status {
+            recording,
+            recording_id,
+        } => {
+            if !json {
+                match recording_id {
+                    Some(id) if recording => println!("recording {}", id),
+                    _ => println!("idle"),
+                }
+            }
+            0
+        }
+        IpcMessage::Transcript { text } => match text {
+            Some(text) => {
+                if !json {
+                    println!("{}", text);
+                }
+                0
+            }
+            // No transcript yet is a distinct, scriptable outcome
+            None => 2,
+        },
+        IpcMessage::Error { message } => {
+            if !json {
+                eprintln!("{}", message);
+            }
+            1
+        }
+    }
+}
+
+/// Release builds use the Windows GUI subsystem, which has no console; attach
+/// to the parent's so output shows up in the calling terminal
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {
+    // Console is always attached on non-Windows platforms
+}
@@ -0,0 +1,9 @@
+use super::clipboard_audio_path;
+use crate::error::Result;
+use tauri::AppHandle;
+
+/// Path of the audio file named on the clipboard
+#[tauri::command]
+pub async fn get_clipboard_audio_path(app: AppHandle) -> Result<String> {
+    Ok(clipboard_audio_path(&app)?.to_string_lossy().to_string())
+}
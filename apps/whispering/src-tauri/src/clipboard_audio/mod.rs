@@ -0,0 +1,96 @@
+//! Transcribing an audio file named on the clipboard.
+//!
+//! A quick path for voice messages saved from chat apps: copy the file (or
+//! its path) and press the hotkey, or run `whispering transcribe <file>`
+//! from a file manager action. The frontend runs the file through the usual
+//! transcription pipeline and puts the result on the clipboard.
+//!
+//! File managers put copied files on the clipboard differently: as a plain
+//! path (Windows "Copy as path", quoted), as `file://` URIs (most Linux file
+//! managers, one per line, sometimes after a `copy` header), or both.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::watcher::is_audio_file;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Payload of `clipboard-audio://transcribe`, emitted when `whispering
+/// transcribe` asks the running app to transcribe a file
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscribeFilePayload {
+    pub path: String,
+}
+
+/// The first audio file named on the clipboard
+pub fn clipboard_audio_path(app: &AppHandle) -> Result<PathBuf> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|_| AppError::InvalidInput("The clipboard doesn't hold a file path".into()))?;
+    text.lines()
+        .filter_map(parse_path)
+        .find(|path| path.is_file())
+        .ok_or_else(|| AppError::InvalidInput("The clipboard doesn't name an existing file".into()))
+        .and_then(|path| check_audio_file(&path).map(|_| path))
+}
+
+/// Fail unless `path` is an existing file with an audio or video extension
+pub fn check_audio_file(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Err(AppError::InvalidInput(format!("{:?} isn't a file", path)));
+    }
+    if !is_audio_file(path) {
+        return Err(AppError::InvalidInput(format!(
+            "{:?} isn't an audio file",
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// A path from one clipboard line: bare, quoted, or a `file://` URI
+fn parse_path(line: &str) -> Option<PathBuf> {
+    let line = line.trim().trim_matches('"').trim_matches('\'');
+    if line.is_empty() {
+        return None;
+    }
+    match line.strip_prefix("file://") {
+        // `file:///C:/...` on Windows drops the slash before the drive
+        Some(uri) if cfg!(target_os = "windows") => {
+            Some(PathBuf::from(percent_decode(uri.trim_start_matches('/'))))
+        }
+        // Skip the host, which is empty or `localhost`
+        Some(uri) => uri
+            .find('/')
+            .map(|start| PathBuf::from(percent_decode(&uri[start..]))),
+        None => Some(PathBuf::from(line)),
+    }
+}
+
+/// Decode `%XX` escapes in a URI path, leaving malformed ones as they are
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,3 +1,4 @@
+use crate::error::{AppError, Result};
 use std::process::{Command, Stdio};
 use serde::{Deserialize, Serialize};
 
@@ -6,7 +7,7 @@ use std::os::windows::process::CommandExt;
 
 // Windows process creation flag to prevent console window from appearing
 #[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+pub(crate) const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -57,11 +58,11 @@ fn parse_command(command: &str) -> (String, Vec<String>) {
 /// execute_command("ffmpeg -i input.wav output.mp3".to_string())
 /// ```
 #[tauri::command]
-pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
+pub async fn execute_command(command: String) -> Result<CommandOutput> {
     let (program, args) = parse_command(&command);
 
     if program.is_empty() {
-        return Err("Empty command".to_string());
+        return Err(AppError::InvalidInput("Empty command".to_string()));
     }
 
     println!("[Rust] execute_command: program='{}', args={:?}", program, args);
@@ -90,7 +91,7 @@ pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
         Err(e) => {
             let error_msg = format!("Command execution failed: {}", e);
             println!("[Rust] execute_command: error - {}", error_msg);
-            Err(error_msg)
+            Err(AppError::Internal(error_msg))
         }
     }
 }
@@ -118,11 +119,11 @@ pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
 /// spawn_command("ffmpeg -f avfoundation -i :0 output.wav".to_string())
 /// ```
 #[tauri::command]
-pub async fn spawn_command(command: String) -> Result<u32, String> {
+pub async fn spawn_command(command: String) -> Result<u32> {
     let (program, args) = parse_command(&command);
 
     if program.is_empty() {
-        return Err("Empty command".to_string());
+        return Err(AppError::InvalidInput("Empty command".to_string()));
     }
 
     println!("[Rust] spawn_command: program='{}', args={:?}", program, args);
@@ -145,7 +146,7 @@ pub async fn spawn_command(command: String) -> Result<u32, String> {
         Err(e) => {
             let error_msg = format!("Failed to spawn process: {}", e);
             println!("[Rust] spawn_command: error - {}", error_msg);
-            Err(error_msg)
+            Err(AppError::Internal(error_msg))
         }
     }
 }
@@ -0,0 +1,55 @@
+use super::{CorrectionSuggestion, Corrections};
+use crate::error::Result;
+use crate::events::{AppEvent, CorrectionSuggested};
+use crate::history::HistoryStore;
+use crate::telemetry::{Feature, Telemetry};
+use crate::transforms::Transforms;
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+/// Save a user's correction of a transcript and learn from the words they
+/// replaced. Replacements that keep coming up are announced on
+/// `corrections://suggested`.
+#[tauri::command]
+pub async fn record_correction(
+    id: String,
+    corrected_text: String,
+    corrections: State<'_, Corrections>,
+    history: State<'_, HistoryStore>,
+    telemetry: State<'_, Telemetry>,
+    app: AppHandle,
+) -> Result<()> {
+    debug!("Recording correction for {}", id);
+    telemetry.record(Feature::TranscriptEdit);
+    for suggestion in corrections.record(&history, &id, &corrected_text)? {
+        let _ = CorrectionSuggested::emit(&app, &suggestion);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_correction_suggestions(
+    corrections: State<'_, Corrections>,
+) -> Result<Vec<CorrectionSuggestion>> {
+    Ok(corrections.pending())
+}
+
+/// Accept a suggestion; returns it with the rule's new id, or with the term
+/// to add to the transcription prompt
+#[tauri::command]
+pub async fn accept_correction_suggestion(
+    id: String,
+    corrections: State<'_, Corrections>,
+    transforms: State<'_, Transforms>,
+) -> Result<CorrectionSuggestion> {
+    debug!("Accepting correction suggestion {}", id);
+    corrections.accept(&id, &transforms)
+}
+
+#[tauri::command]
+pub async fn dismiss_correction_suggestion(
+    id: String,
+    corrections: State<'_, Corrections>,
+) -> Result<()> {
+    corrections.dismiss(&id)
+}
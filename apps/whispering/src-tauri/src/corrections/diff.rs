@@ -0,0 +1,94 @@
+//! Finding the words a user replaced when correcting a transcript.
+
+/// Longest phrase, in words, counted as one substitution. Longer rewrites are
+/// usually rephrasing rather than a misheard word.
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// One replaced phrase, e.g. "get hub" → "GitHub"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    pub from: String,
+    pub to: String,
+}
+
+/// Strip the punctuation a transcriber attaches to words, so "hub," and "hub"
+/// line up
+fn word(token: &str) -> &str {
+    token.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// Whether `to` only differs from `from` by a capital first letter, as at the
+/// start of a sentence
+fn only_capitalized(from: &str, to: &str) -> bool {
+    let mut from_chars = from.chars();
+    let mut to_chars = to.chars();
+    match (from_chars.next(), to_chars.next()) {
+        (Some(f), Some(t)) => {
+            f.to_lowercase().eq(t.to_lowercase()) && from_chars.as_str() == to_chars.as_str()
+        }
+        _ => false,
+    }
+}
+
+/// The phrases replaced between `original` and `corrected`, found by aligning
+/// their words. Words only added or only removed aren't substitutions and are
+/// left out, as are changes to punctuation or sentence capitalization.
+pub fn substitutions(original: &str, corrected: &str) -> Vec<Substitution> {
+    let before: Vec<&str> = original
+        .split_whitespace()
+        .map(word)
+        .filter(|w| !w.is_empty())
+        .collect();
+    let after: Vec<&str> = corrected
+        .split_whitespace()
+        .map(word)
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    // Longest common subsequence, filled from the end so the walk below can
+    // go forwards
+    let mut lcs = vec![vec![0u32; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            lcs[i][j] = if before[i] == after[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut found = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let mut flush = |removed: &mut Vec<&str>, added: &mut Vec<&str>| {
+        if !removed.is_empty()
+            && !added.is_empty()
+            && removed.len() <= MAX_PHRASE_WORDS
+            && added.len() <= MAX_PHRASE_WORDS
+        {
+            let from = removed.join(" ");
+            let to = added.join(" ");
+            if !only_capitalized(&from, &to) {
+                found.push(Substitution { from, to });
+            }
+        }
+        removed.clear();
+        added.clear();
+    };
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            flush(&mut removed, &mut added);
+            i += 1;
+            j += 1;
+        } else if j == after.len() || (i < before.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(before[i]);
+            i += 1;
+        } else {
+            added.push(after[j]);
+            j += 1;
+        }
+    }
+    flush(&mut removed, &mut added);
+    found
+}
@@ -0,0 +1,242 @@
+//! Learning from the corrections users make to their transcripts.
+//!
+//! `record_correction` saves an edit like `edit_transcript` does, then lines
+//! the old and new text up word by word and counts each phrase that was
+//! replaced. Once the same replacement has been made in enough different
+//! transcripts it's offered on `corrections://suggested`: as a vocabulary
+//! term for names and jargon the transcriber keeps mishearing, which the
+//! frontend adds to the transcription prompt, or otherwise as a replacement
+//! rule for `transforms`. Dismissed suggestions aren't offered again.
+
+pub mod commands;
+mod diff;
+
+
+use crate::error::{AppError, Result};
+use crate::history::HistoryStore;
+use crate::transforms::{ReplacementRule, Transforms};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+pub const CORRECTIONS_FILE: &str = "corrections.json";
+
+/// Number of different transcripts a replacement has to be made in before
+/// it's suggested
+const SUGGEST_AFTER: usize = 3;
+
+/// What accepting a suggestion would add
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum SuggestionKind {
+    /// A term for the transcription prompt, so the model gets it right itself
+    Vocabulary { term: String },
+    /// A replacement rule that fixes it after transcription
+    Rule { rule: ReplacementRule },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestionStatus {
+    /// Still being counted
+    #[default]
+    Learning,
+    Suggested,
+    Accepted,
+    Dismissed,
+}
+
+/// A replacement seen in the user's corrections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectionSuggestion {
+    /// The replaced phrase in lower case, then the replacement
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    /// History entries the replacement was made in
+    pub entry_ids: Vec<String>,
+    /// Milliseconds since the Unix epoch
+    pub last_seen: i64,
+    pub status: SuggestionStatus,
+    pub kind: SuggestionKind,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredCorrections {
+    suggestions: Vec<CorrectionSuggestion>,
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Whether `to` reads like a name, acronym or product term: a capital or a
+/// digit after the first letter of a word ("GitHub", "Kubernetes", "gpt4"),
+/// or a capitalized word replacing a lower-case one
+fn looks_like_term(from: &str, to: &str) -> bool {
+    let inner_capital = to
+        .split_whitespace()
+        .any(|w| w.chars().skip(1).any(|c| c.is_uppercase() || c.is_ascii_digit()));
+    let proper_noun = to.chars().next().is_some_and(char::is_uppercase)
+        && from.chars().next().is_some_and(char::is_lowercase);
+    inner_capital || proper_noun
+}
+
+fn suggest(from: &str, to: &str) -> SuggestionKind {
+    if looks_like_term(from, to) {
+        return SuggestionKind::Vocabulary {
+            term: to.to_string(),
+        };
+    }
+    let pattern = from
+        .split_whitespace()
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(r"\s+");
+    SuggestionKind::Rule {
+        rule: ReplacementRule {
+            id: String::new(),
+            pattern: format!(r"\b{}\b", pattern),
+            replacement: to.replace('$', "$$"),
+            case_insensitive: true,
+            enabled: true,
+            tests: vec![from.to_string()],
+        },
+    }
+}
+
+pub struct Corrections {
+    path: PathBuf,
+    stored: Mutex<StoredCorrections>,
+}
+
+impl Corrections {
+    pub fn open(path: &Path) -> Self {
+        let stored = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                StoredCorrections::default()
+            }),
+            Err(_) => StoredCorrections::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            stored: Mutex::new(stored),
+        }
+    }
+
+    fn save(&self, stored: &StoredCorrections) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(stored)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize corrections: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+
+    /// Save `corrected` as the entry's transcript and count the replacements
+    /// it makes. Returns the suggestions that became ready with this edit.
+    pub fn record(
+        &self,
+        history: &HistoryStore,
+        entry_id: &str,
+        corrected: &str,
+    ) -> Result<Vec<CorrectionSuggestion>> {
+        let original = history.get(entry_id)?.transcript;
+        history.edit_transcript(entry_id, corrected)?;
+
+        let mut stored = self
+            .stored
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock corrections: {}", e)))?;
+        let mut ready = Vec::new();
+        for substitution in diff::substitutions(&original, corrected) {
+            let id = format!("{}\u{2192}{}", substitution.from.to_lowercase(), substitution.to);
+            let index = match stored.suggestions.iter().position(|s| s.id == id) {
+                Some(index) => index,
+                None => {
+                    stored.suggestions.push(CorrectionSuggestion {
+                        kind: suggest(&substitution.from, &substitution.to),
+                        id,
+                        from: substitution.from,
+                        to: substitution.to,
+                        entry_ids: Vec::new(),
+                        last_seen: 0,
+                        status: SuggestionStatus::Learning,
+                    });
+                    stored.suggestions.len() - 1
+                }
+            };
+            let suggestion = &mut stored.suggestions[index];
+            suggestion.last_seen = now_millis();
+            if !suggestion.entry_ids.iter().any(|id| id == entry_id) {
+                suggestion.entry_ids.push(entry_id.to_string());
+            }
+            if suggestion.status == SuggestionStatus::Learning
+                && suggestion.entry_ids.len() >= SUGGEST_AFTER
+            {
+                info!("Suggesting {:?} -> {:?}", suggestion.from, suggestion.to);
+                suggestion.status = SuggestionStatus::Suggested;
+                ready.push(suggestion.clone());
+            }
+        }
+        self.save(&stored)?;
+        Ok(ready)
+    }
+
+    /// Suggestions waiting for the user to accept or dismiss them
+    pub fn pending(&self) -> Vec<CorrectionSuggestion> {
+        self.stored
+            .lock()
+            .map(|stored| {
+                stored
+                    .suggestions
+                    .iter()
+                    .filter(|s| s.status == SuggestionStatus::Suggested)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn resolve(&self, id: &str, status: SuggestionStatus) -> Result<CorrectionSuggestion> {
+        let mut stored = self
+            .stored
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock corrections: {}", e)))?;
+        let suggestion = stored
+            .suggestions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| AppError::InvalidInput(format!("No suggestion with id {}", id)))?;
+        suggestion.status = status;
+        let suggestion = suggestion.clone();
+        self.save(&stored)?;
+        Ok(suggestion)
+    }
+
+    /// Accept a suggestion. A rule is added to `transforms` here; a
+    /// vocabulary term is returned for the frontend to add to the prompt.
+    pub fn accept(&self, id: &str, transforms: &Transforms) -> Result<CorrectionSuggestion> {
+        let mut suggestion = self.resolve(id, SuggestionStatus::Accepted)?;
+        if let SuggestionKind::Rule { rule } = &suggestion.kind {
+            let rule = transforms.add_rule(rule.clone())?;
+            suggestion.kind = SuggestionKind::Rule { rule };
+        }
+        Ok(suggestion)
+    }
+
+    /// Stop suggesting `id`; the replacement is still counted but never
+    /// offered again
+    pub fn dismiss(&self, id: &str) -> Result<()> {
+        self.resolve(id, SuggestionStatus::Dismissed).map(|_| ())
+    }
+}
@@ -0,0 +1,26 @@
+use super::{AssetCheck, SoundSlot};
+use crate::error::{AppError, Result};
+use crate::settings::SettingsStore;
+use std::path::Path;
+use tauri::ipc::Response;
+use tauri::State;
+
+/// Check the custom icons and sounds in the settings, listing each with why
+/// it can't be used, if it can't
+#[tauri::command]
+pub async fn check_custom_assets(settings: State<'_, SettingsStore>) -> Result<Vec<AssetCheck>> {
+    Ok(super::check(&settings.get()))
+}
+
+/// The custom sound for `sound` as raw bytes, once it passes the checks
+#[tauri::command]
+pub async fn load_custom_sound(
+    sound: SoundSlot,
+    settings: State<'_, SettingsStore>,
+) -> Result<Response> {
+    let settings = settings.get();
+    let path = sound
+        .path(&settings)
+        .ok_or_else(|| AppError::InvalidInput(format!("No custom {:?} sound is set", sound)))?;
+    Ok(Response::new(super::load_sound(Path::new(path))?))
+}
@@ -0,0 +1,195 @@
+//! Tray icons and sounds the user supplies in place of the built-in ones.
+//!
+//! The paths are native settings, so only files the user picked are ever
+//! read. Each file is checked before it's used: icons must be PNGs between
+//! 16 and 1024 pixels a side, sounds MP3, WAV, Ogg or FLAC, and neither may
+//! be large enough to slow the app down. A file that's missing or fails a
+//! check is skipped in favour of the built-in asset, and
+//! `check_custom_assets` says what was wrong so the settings page can show
+//! it.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::settings::NativeSettings;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+const MAX_ICON_BYTES: u64 = 1 << 20;
+const MIN_ICON_SIZE: u32 = 16;
+const MAX_ICON_SIZE: u32 = 1024;
+/// Icons larger than this are scaled down before they're drawn
+const ICON_RENDER_SIZE: u32 = 256;
+const MAX_SOUND_BYTES: u64 = 5 << 20;
+
+/// Which built-in sound a custom one replaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SoundSlot {
+    /// Starting a manual or voice-activated recording
+    Start,
+    /// Stopping one
+    Stop,
+}
+
+impl SoundSlot {
+    /// The custom file for this sound, if one is set
+    pub fn path(self, settings: &NativeSettings) -> Option<&str> {
+        let path = match self {
+            SoundSlot::Start => &settings.custom_start_sound,
+            SoundSlot::Stop => &settings.custom_stop_sound,
+        };
+        Some(path.trim()).filter(|path| !path.is_empty())
+    }
+}
+
+/// The outcome of checking one configured file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetCheck {
+    /// The native setting holding the path, e.g. `customStartSound`
+    pub setting: &'static str,
+    pub path: String,
+    /// Why the built-in asset is used instead, if it is
+    pub error: Option<String>,
+}
+
+/// Load and check a custom tray icon, scaling down large ones
+pub fn load_icon(path: &Path) -> Result<RgbaImage> {
+    let bytes = read_limited(path, MAX_ICON_BYTES)?;
+    if image::guess_format(&bytes).ok() != Some(ImageFormat::Png) {
+        return Err(AppError::InvalidInput(format!(
+            "{} isn't a PNG image",
+            path.display()
+        )));
+    }
+    let icon = image::load_from_memory_with_format(&bytes, ImageFormat::Png)
+        .map_err(|e| AppError::InvalidInput(format!("Can't read {}: {}", path.display(), e)))?
+        .to_rgba8();
+
+    let (width, height) = icon.dimensions();
+    if width.min(height) < MIN_ICON_SIZE || width.max(height) > MAX_ICON_SIZE {
+        return Err(AppError::InvalidInput(format!(
+            "{} is {}x{}; tray icons must be {}-{} pixels a side",
+            path.display(),
+            width,
+            height,
+            MIN_ICON_SIZE,
+            MAX_ICON_SIZE
+        )));
+    }
+    if width.max(height) <= ICON_RENDER_SIZE {
+        return Ok(icon);
+    }
+    let scale = ICON_RENDER_SIZE as f32 / width.max(height) as f32;
+    Ok(imageops::resize(
+        &icon,
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1),
+        FilterType::Lanczos3,
+    ))
+}
+
+/// Load and check a custom sound, returning the file's contents
+pub fn load_sound(path: &Path) -> Result<Vec<u8>> {
+    let bytes = read_limited(path, MAX_SOUND_BYTES)?;
+    if !is_supported_sound(&bytes) {
+        return Err(AppError::InvalidInput(format!(
+            "{} isn't an MP3, WAV, Ogg or FLAC file",
+            path.display()
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Check every custom file set in `settings`
+pub fn check(settings: &NativeSettings) -> Vec<AssetCheck> {
+    let icons = [
+        ("customTrayIconIdle", &settings.custom_tray_icon_idle),
+        (
+            "customTrayIconRecording",
+            &settings.custom_tray_icon_recording,
+        ),
+        (
+            "customTrayIconProcessing",
+            &settings.custom_tray_icon_processing,
+        ),
+    ];
+    let sounds = [
+        ("customStartSound", &settings.custom_start_sound),
+        ("customStopSound", &settings.custom_stop_sound),
+    ];
+
+    let mut checks = Vec::new();
+    for (is_icon, (setting, path)) in icons
+        .into_iter()
+        .map(|icon| (true, icon))
+        .chain(sounds.into_iter().map(|sound| (false, sound)))
+    {
+        let path = path.trim();
+        if path.is_empty() {
+            continue;
+        }
+        let result = if is_icon {
+            load_icon(Path::new(path)).map(|_| ())
+        } else {
+            load_sound(Path::new(path)).map(|_| ())
+        };
+        checks.push(AssetCheck {
+            setting,
+            path: path.to_string(),
+            error: result.err().map(|e| match e {
+                AppError::InvalidInput(message) => message,
+                e => e.to_string(),
+            }),
+        });
+    }
+    checks
+}
+
+/// Read a regular file of at most `max` bytes
+fn read_limited(path: &Path, max: u64) -> Result<Vec<u8>> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| AppError::InvalidInput(format!("Can't open {}: {}", path.display(), e)))?;
+    if !metadata.is_file() {
+        return Err(AppError::InvalidInput(format!(
+            "{} isn't a file",
+            path.display()
+        )));
+    }
+    if metadata.len() > max {
+        return Err(AppError::InvalidInput(format!(
+            "{} is larger than {} KiB",
+            path.display(),
+            max / 1024
+        )));
+    }
+    // The file may have grown since the size check
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    std::fs::File::open(path)?
+        .take(max + 1)
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > max {
+        return Err(AppError::InvalidInput(format!(
+            "{} is larger than {} KiB",
+            path.display(),
+            max / 1024
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Whether `bytes` start like a sound format webviews can play
+fn is_supported_sound(bytes: &[u8]) -> bool {
+    let wav = bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(&b"WAVE"[..]);
+    // An MP3 without ID3 tags starts straight with a frame, whose header
+    // begins with 11 set bits
+    let mpeg_frame = matches!(bytes, [0xFF, second, ..] if second & 0xE0 == 0xE0);
+    wav || mpeg_frame
+        || [&b"ID3"[..], b"OggS", b"fLaC"]
+            .iter()
+            .any(|magic| bytes.starts_with(magic))
+}
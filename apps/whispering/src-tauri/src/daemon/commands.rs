@@ -0,0 +1,9 @@
+use super::Daemon;
+use crate::error::Result;
+use tauri::State;
+
+/// Recordings the daemon made while no UI was open, for the UI to transcribe
+#[tauri::command]
+pub async fn take_daemon_recordings(daemon: State<'_, Daemon>) -> Result<Vec<String>> {
+    Ok(daemon.take_recordings())
+}
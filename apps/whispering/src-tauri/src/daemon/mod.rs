@@ -0,0 +1,188 @@
+//! Running as a resident daemon with the UI as a separate, disposable part.
+//!
+//! `whispering --daemon` starts the engine (recorder, tray, IPC, hotkeys and
+//! the other Rust services) without creating the webview, which is most of
+//! the idle memory. The UI is created when asked for (`whispering ui`, the tray's
+//! Show, or launching Whispering again) and destroyed rather than hidden
+//! when closed. Recording runs in Rust, so closing the UI, or its webview
+//! crashing, doesn't interrupt a recording in progress; the next UI picks
+//! it up from the recorder.
+//!
+//! While no UI is open, recording commands from the CLI are handled here:
+//! audio is recorded from the preferred device and queued, and the next UI
+//! transcribes the queue (see `take_daemon_recordings`). The queue is also
+//! kept on disk with the other pending transcriptions, so a restart doesn't
+//! lose it.
+
+pub mod commands;
+
+use crate::app_state::{AppState, StateEvent};
+use crate::error::{AppError, Result};
+use crate::i18n;
+use crate::jobs::pending::{PendingJobs, PendingSource};
+use crate::notifications::notify;
+use crate::recorder::commands::apply_priority;
+use crate::recorder::input::InputConfigs;
+use crate::recorder::priority::DevicePriorities;
+use crate::recorder::AppData;
+use crate::settings::SettingsStore;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow, WebviewWindowBuilder};
+use tracing::{info, warn};
+
+/// Flag that starts Whispering as a daemon
+pub const DAEMON_FLAG: &str = "--daemon";
+
+/// Label of the UI window, as created from tauri.conf.json
+pub const MAIN_WINDOW: &str = "main";
+
+/// Folder in the app data directory for recordings made without a UI
+const RECORDINGS_DIR: &str = "daemon-recordings";
+
+/// Whether the process was launched with `--daemon`
+pub fn is_daemon_launch(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| arg == DAEMON_FLAG)
+}
+
+/// Whether the UI window currently exists, hidden or not
+pub fn has_ui<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.get_webview_window(MAIN_WINDOW).is_some()
+}
+
+/// The UI window, creating its webview if it doesn't exist yet
+///
+/// tauri.conf.json doesn't create the window at startup, so launching to
+/// the tray or as a daemon never pays for a webview that isn't shown.
+pub fn create_ui<R: Runtime>(app: &AppHandle<R>) -> Result<WebviewWindow<R>> {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        return Ok(window);
+    }
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == MAIN_WINDOW)
+        .cloned()
+        .ok_or_else(|| AppError::Internal("No main window in the app config".to_string()))?;
+    info!("Creating the UI window");
+    Ok(WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.build())
+        .map_err(|e| AppError::Internal(format!("Failed to create the UI window: {}", e)))?)
+}
+
+/// Show the UI window, creating its webview first if needed
+pub fn show_ui<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let window = create_ui(app)?;
+    window
+        .show()
+        .and_then(|_| window.set_focus())
+        .map_err(|e| AppError::Internal(format!("Failed to show the UI window: {}", e)))?;
+    Ok(())
+}
+
+pub struct Daemon {
+    enabled: bool,
+    /// Recordings waiting for a UI to transcribe them
+    queued: Mutex<Vec<String>>,
+}
+
+impl Daemon {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            queued: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Handle a recording command from the CLI while no UI is open
+    pub fn record<R: Runtime>(&self, app: &AppHandle<R>, command: &str) -> Result<()> {
+        let recording = app
+            .state::<AppData>()
+            .recorder
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?
+            .get_current_recording_id()
+            .is_some();
+        match (command, recording) {
+            ("startRecording" | "toggleRecording", false) => self.start(app),
+            ("stopRecording" | "toggleRecording", true) => self.stop(app),
+            _ => Ok(()),
+        }
+    }
+
+    fn start<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| {
+                AppError::Internal(format!("Failed to resolve app data directory: {}", e))
+            })?
+            .join(RECORDINGS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let settings = app.state::<SettingsStore>();
+        let data = app.state::<AppData>();
+        let mut recorder = data
+            .recorder
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+        recorder.set_backend(settings.get().audio_backend);
+        let device = apply_priority(
+            &recorder,
+            &app.state::<DevicePriorities>(),
+            "default".to_string(),
+        );
+        let input = app.state::<InputConfigs>().get(&device);
+        let recording_id = uuid::Uuid::new_v4().simple().to_string();
+        info!("Daemon recording {} from {}", recording_id, device);
+        recorder.init_session(device, dir, recording_id.clone(), None, input)?;
+        recorder.start_recording()?;
+        app.state::<AppState>().record(StateEvent::RecordingStarted {
+            recording_id: Some(recording_id),
+        });
+        Ok(())
+    }
+
+    fn stop<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let data = app.state::<AppData>();
+        let recording = {
+            let mut recorder = data
+                .recorder
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+            let recording = recorder.stop_recording()?;
+            recorder.close_session()?;
+            recording
+        };
+        let app_state = app.state::<AppState>();
+        app_state.record(StateEvent::RecordingStopped);
+        // Nothing to transcribe with until a UI opens
+        app_state.record(StateEvent::Completed);
+        if let Some(path) = recording.file_path {
+            if let Err(e) = app
+                .state::<PendingJobs>()
+                .enqueue(&path, PendingSource::Daemon)
+            {
+                warn!("Failed to queue {} for transcription: {}", path, e);
+            }
+            self.queued
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock daemon queue: {}", e)))?
+                .push(path);
+            notify(app, &i18n::t("notify-recording-queued"));
+        }
+        Ok(())
+    }
+
+    /// Recordings made without a UI, emptying the queue
+    pub fn take_recordings(&self) -> Vec<String> {
+        self.queued
+            .lock()
+            .map(|mut queued| std::mem::take(&mut *queued))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,82 @@
+//! Advertising servers on the local network over mDNS (Bonjour), so phones
+//! and companion tools find the desktop without typing in an address.
+//!
+//! Every server is advertised under [`SERVICE_TYPE`], told apart by its TXT
+//! record:
+//!
+//! - `caps`: what it serves, `inbox` or `remote`
+//! - `fp`: the SHA-256 fingerprint of its certificate, to pin
+//! - `v`: the version of its API
+
+use crate::error::{AppError, Result};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Service type Whispering's servers are advertised under
+pub const SERVICE_TYPE: &str = "_whispering._tcp.local.";
+
+/// A service being advertised, withdrawn when dropped
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Advertisement {
+    /// Advertise `instance` on `port`, with `txt` as its TXT record
+    pub fn start(instance: &str, port: u16, txt: &[(&str, &str)]) -> Result<Self> {
+        let daemon = ServiceDaemon::new()
+            .map_err(|e| AppError::Internal(format!("Failed to start mDNS responder: {}", e)))?;
+        let service = service_info(instance, port, txt)?;
+        let fullname = service.get_fullname().to_string();
+        daemon
+            .register(service)
+            .map_err(|e| AppError::Internal(format!("Failed to advertise {}: {}", fullname, e)))?;
+        info!("Advertising {} on port {}", fullname, port);
+        Ok(Self { daemon, fullname })
+    }
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        if let Err(e) = self.daemon.unregister(&self.fullname) {
+            warn!("Failed to withdraw {}: {}", self.fullname, e);
+        }
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// The service record advertising `instance` on `port` from this machine
+pub fn service_info(instance: &str, port: u16, txt: &[(&str, &str)]) -> Result<ServiceInfo> {
+    let host = format!("{}.local.", host_label());
+    let properties: HashMap<String, String> = txt
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let service = ServiceInfo::new(SERVICE_TYPE, instance, &host, "", port, properties)
+        .map_err(|e| AppError::Internal(format!("Invalid mDNS service: {}", e)))?
+        .enable_addr_auto();
+    Ok(service)
+}
+
+/// This machine's name as a DNS label, e.g. `alices-laptop`
+pub fn host_label() -> String {
+    let name = sysinfo::System::host_name().unwrap_or_default();
+    let label: String = name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    match label.trim_matches('-') {
+        "" => "whispering".to_string(),
+        label => label.to_string(),
+    }
+}
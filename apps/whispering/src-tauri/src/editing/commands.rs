@@ -0,0 +1,27 @@
+use crate::editing;
+use crate::error::Result;
+use crate::history::{HistoryEntry, HistoryStore};
+use tauri::State;
+
+/// Cut a stored recording down to `start`..`end` seconds
+#[tauri::command]
+pub async fn trim_recording(
+    id: String,
+    start: f64,
+    end: f64,
+    history: State<'_, HistoryStore>,
+    app_handle: tauri::AppHandle,
+) -> Result<HistoryEntry> {
+    editing::trim_recording(&app_handle, &history, &id, start, end)
+}
+
+/// Split a stored recording at `at` seconds into two history entries
+#[tauri::command]
+pub async fn split_recording(
+    id: String,
+    at: f64,
+    history: State<'_, HistoryStore>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<HistoryEntry>> {
+    editing::split_recording(&app_handle, &history, &id, at)
+}
@@ -0,0 +1,125 @@
+pub mod commands;
+
+use crate::audio::decode_file;
+use crate::error::{AppError, Result};
+use crate::history::{HistoryEntry, HistoryStore};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+/// Edited audio is written to the app data directory rather than next to the
+/// original, which may be a watched folder or a file the user owns
+fn edited_audio_dir(app: &AppHandle) -> Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve app data directory: {}", e)))?
+        .join("edited");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn audio_path(entry: &HistoryEntry) -> Result<&str> {
+    entry
+        .audio_path
+        .as_deref()
+        .ok_or_else(|| AppError::InvalidInput(format!("History entry {} has no audio", entry.id)))
+}
+
+/// Keep only the audio between `start` and `end` seconds
+///
+/// The original file is left untouched; the entry is pointed at a trimmed
+/// copy. Returns the updated entry.
+pub fn trim_recording(
+    app: &AppHandle,
+    history: &HistoryStore,
+    id: &str,
+    start: f64,
+    end: f64,
+) -> Result<HistoryEntry> {
+    if start < 0.0 || end <= start {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid trim range {:.2}s - {:.2}s",
+            start, end
+        )));
+    }
+
+    let entry = history.get(id)?;
+    let audio = decode_file(Path::new(audio_path(&entry)?))?;
+    let trimmed = audio.slice(start, end);
+    if trimmed.samples.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "Trim range is outside the recording ({:.2}s long)",
+            audio.duration_seconds()
+        )));
+    }
+
+    let path = edited_audio_dir(app)?.join(format!("{}-{}.wav", id, uuid::Uuid::new_v4()));
+    trimmed.write_wav(&path)?;
+    let path = path.to_string_lossy().to_string();
+    history.update_audio(id, &path, trimmed.duration_seconds() as f32)?;
+    info!(
+        "Trimmed {} to {:.2}s - {:.2}s ({:.2}s)",
+        id,
+        start,
+        end,
+        trimmed.duration_seconds()
+    );
+
+    Ok(history.get(id)?)
+}
+
+/// Split a recording in two at `at` seconds
+///
+/// The entry keeps the first part and a new entry is created for the rest.
+/// Returns both entries, in order.
+pub fn split_recording(
+    app: &AppHandle,
+    history: &HistoryStore,
+    id: &str,
+    at: f64,
+) -> Result<Vec<HistoryEntry>> {
+    let entry = history.get(id)?;
+    let audio = decode_file(Path::new(audio_path(&entry)?))?;
+    let duration = audio.duration_seconds();
+    if at <= 0.0 || at >= duration {
+        return Err(AppError::InvalidInput(format!(
+            "Split point {:.2}s is outside the recording ({:.2}s long)",
+            at, duration
+        )));
+    }
+
+    let dir = edited_audio_dir(app)?;
+    let first = audio.slice(0.0, at);
+    let second = audio.slice(at, duration);
+
+    let first_path = dir.join(format!("{}-{}.wav", id, uuid::Uuid::new_v4()));
+    first.write_wav(&first_path)?;
+
+    let mut rest = HistoryEntry::new(entry.source, String::new());
+    // Keep the second half next to the first in chronological listings
+    rest.created_at = entry.created_at + 1;
+    rest.source_path = entry.source_path.clone();
+    rest.tags = entry.tags.clone();
+    rest.favorite = entry.favorite;
+    rest.provider = entry.provider.clone();
+    rest.target_app = entry.target_app.clone();
+    rest.title = entry.title.clone();
+    rest.attendees = entry.attendees.clone();
+    rest.language = entry.language.clone();
+    rest.language_confidence = entry.language_confidence;
+    let second_path = dir.join(format!("{}.wav", rest.id));
+    second.write_wav(&second_path)?;
+    rest.audio_path = Some(second_path.to_string_lossy().to_string());
+    rest.duration_seconds = second.duration_seconds() as f32;
+
+    history.update_audio(
+        id,
+        &first_path.to_string_lossy(),
+        first.duration_seconds() as f32,
+    )?;
+    history.insert(&rest)?;
+    info!("Split {} at {:.2}s into {} and {}", id, at, id, rest.id);
+
+    Ok(vec![history.get(id)?, rest])
+}
@@ -0,0 +1,191 @@
+//! Crate-wide error type returned by Tauri commands.
+//!
+//! Errors reach the frontend as structured objects:
+//!
+//! ```json
+//! { "name": "DeviceBusy", "code": "DEVICE_BUSY", "message": "...", "recoveryHint": "..." }
+//! ```
+//!
+//! `name` matches the variant (or, for wrapped module errors, the inner
+//! variant) so existing frontend `switch (error.name)` handling keeps working,
+//! while `code` and `recoveryHint` let the UI show actionable messages.
+
+use crate::app_state::StateError;
+use crate::history::HistoryError;
+use crate::transcription::TranscriptionError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Recording device not found: {0}")]
+    DeviceNotFound(String),
+
+    #[error("Microphone is busy or unavailable: {0}")]
+    DeviceBusy(String),
+
+    #[error("Recording device can't be used: {0}")]
+    UnsupportedDevice(String),
+
+    #[error("No recording session: {0}")]
+    NoSession(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("Whispering is not running: {0}")]
+    NotRunning(String),
+
+    #[error("Address already in use: {0}")]
+    AddressInUse(String),
+
+    #[error("Invalid API key: {0}")]
+    InvalidApiKey(String),
+
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Not available in this build: {0}")]
+    Unsupported(String),
+
+    #[error("Not enough disk space: {0}")]
+    DiskFull(String),
+
+    #[error("File system error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Transcription(#[from] TranscriptionError),
+
+    #[error(transparent)]
+    History(#[from] HistoryError),
+
+    #[error(transparent)]
+    State(#[from] StateError),
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    /// Stable machine-readable code for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::DeviceNotFound(_) => "DEVICE_NOT_FOUND",
+            AppError::DeviceBusy(_) => "DEVICE_BUSY",
+            AppError::UnsupportedDevice(_) => "UNSUPPORTED_DEVICE",
+            AppError::NoSession(_) => "NO_SESSION",
+            AppError::PermissionDenied(_) => "PERMISSION_DENIED",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::NotRunning(_) => "NOT_RUNNING",
+            AppError::AddressInUse(_) => "ADDRESS_IN_USE",
+            AppError::InvalidApiKey(_) => "INVALID_API_KEY",
+            AppError::Network(_) => "NETWORK",
+            AppError::Unsupported(_) => "UNSUPPORTED",
+            AppError::DiskFull(_) => "DISK_FULL",
+            AppError::Io(_) => "IO",
+            AppError::Transcription(e) => match e {
+                TranscriptionError::AudioReadError { .. } => "AUDIO_READ",
+                TranscriptionError::FfmpegNotFoundError { .. } => "FFMPEG_NOT_FOUND",
+                TranscriptionError::GpuError { .. } => "GPU",
+                TranscriptionError::ModelLoadError { .. } => "MODEL_LOAD",
+                TranscriptionError::TranscriptionError { .. } => "TRANSCRIPTION",
+                TranscriptionError::Cancelled { .. } => "CANCELLED",
+            },
+            AppError::History(e) => match e {
+                HistoryError::DatabaseError { .. } => "DATABASE",
+                HistoryError::NotFoundError { .. } => "NOT_FOUND",
+            },
+            AppError::State(_) => "INVALID_STATE",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// Suggestion shown next to the error message, if there is a useful
+    /// one, in the language of the tray and notifications
+    pub fn recovery_hint(&self) -> Option<String> {
+        let id = match self {
+            AppError::DeviceNotFound(_) => "hint-device-not-found",
+            AppError::DeviceBusy(_) => "hint-device-busy",
+            AppError::UnsupportedDevice(_) => "hint-unsupported-device",
+            AppError::NoSession(_) => "hint-no-session",
+            AppError::PermissionDenied(_) => "hint-permission-denied",
+            AppError::NotRunning(_) => "hint-not-running",
+            AppError::AddressInUse(_) => "hint-address-in-use",
+            AppError::InvalidApiKey(_) => "hint-invalid-api-key",
+            AppError::Network(_) => "hint-network",
+            AppError::Unsupported(_) => "hint-unsupported",
+            AppError::DiskFull(_) => "hint-disk-full",
+            AppError::Transcription(TranscriptionError::FfmpegNotFoundError { .. }) => {
+                "hint-ffmpeg-not-found"
+            }
+            AppError::Transcription(TranscriptionError::ModelLoadError { .. }) => "hint-model-load",
+            _ => return None,
+        };
+        Some(crate::i18n::t(id))
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AppError::DeviceNotFound(_) => "DeviceNotFound",
+            AppError::DeviceBusy(_) => "DeviceBusy",
+            AppError::UnsupportedDevice(_) => "UnsupportedDevice",
+            AppError::NoSession(_) => "NoSession",
+            AppError::PermissionDenied(_) => "PermissionDenied",
+            AppError::InvalidInput(_) => "InvalidInput",
+            AppError::NotRunning(_) => "NotRunning",
+            AppError::AddressInUse(_) => "AddressInUse",
+            AppError::InvalidApiKey(_) => "InvalidApiKey",
+            AppError::Network(_) => "Network",
+            AppError::Unsupported(_) => "Unsupported",
+            AppError::DiskFull(_) => "DiskFull",
+            AppError::Io(_) => "Io",
+            AppError::Transcription(e) => match e {
+                TranscriptionError::AudioReadError { .. } => "AudioReadError",
+                TranscriptionError::FfmpegNotFoundError { .. } => "FfmpegNotFoundError",
+                TranscriptionError::GpuError { .. } => "GpuError",
+                TranscriptionError::ModelLoadError { .. } => "ModelLoadError",
+                TranscriptionError::TranscriptionError { .. } => "TranscriptionError",
+                TranscriptionError::Cancelled { .. } => "Cancelled",
+            },
+            AppError::History(e) => match e {
+                HistoryError::DatabaseError { .. } => "DatabaseError",
+                HistoryError::NotFoundError { .. } => "NotFoundError",
+            },
+            AppError::State(_) => "InvalidTransition",
+            AppError::Internal(_) => "Internal",
+        }
+    }
+
+    /// Message without the category prefix, for wrapped module errors
+    fn detail(&self) -> String {
+        match self {
+            AppError::Transcription(
+                TranscriptionError::AudioReadError { message }
+                | TranscriptionError::FfmpegNotFoundError { message }
+                | TranscriptionError::GpuError { message }
+                | TranscriptionError::ModelLoadError { message }
+                | TranscriptionError::TranscriptionError { message }
+                | TranscriptionError::Cancelled { message },
+            ) => message.clone(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("name", self.name())?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.detail())?;
+        state.serialize_field("recoveryHint", &self.recovery_hint())?;
+        state.end()
+    }
+}
@@ -0,0 +1,113 @@
+// The event catalog, read by both `events/mod.rs` and `build.rs`.
+//
+// Each line is `Marker = "name" => RustPayload as "TypeScriptPayload";`.
+// Payload paths must be absolute (`crate::...`), and TypeScript types
+// other than primitives must be exported from src/lib/events/payloads.ts.
+// Events of an optional subsystem take its `#[cfg(feature = "...")]` after
+// their doc comment.
+
+catalog! {
+    /// The app state machine moved to a new status
+    StateChanged = "state://changed" => crate::app_state::AppStatus as "AppStatus";
+    /// Text for the main window's live region to have the screen reader
+    /// speak, on platforms without a native announcement API
+    Announcement = "accessibility://announce" => str as "string";
+    /// A backup started, finished or failed
+    BackupStatusChanged = "backup://status" => crate::backup::BackupStatus as "BackupStatus";
+    /// A bulk job finished another entry
+    BulkJobProgress = "bulk://progress" => crate::bulk::BulkProgress as "BulkProgress";
+    /// A bulk job ran to completion or was cancelled
+    BulkJobFinished = "bulk://finished" => crate::bulk::BulkFinished as "BulkFinished";
+    /// An audio file should be transcribed to the clipboard
+    TranscribeFileRequested = "clipboard-audio://transcribe"
+        => crate::clipboard_audio::TranscribeFilePayload as "TranscribeFile";
+    /// The same correction was made often enough to suggest automating it
+    CorrectionSuggested = "corrections://suggested"
+        => crate::corrections::CorrectionSuggestion as "CorrectionSuggestion";
+    /// A double-tap, key sequence, mouse button or pedal trigger fired
+    HotkeyTriggered = "hotkeys://trigger" => crate::hotkeys::TriggerFired as "TriggerFired";
+    /// A phone finished pairing with the inbox
+    #[cfg(feature = "http-api")]
+    InboxDevicePaired = "inbox://paired" => crate::inbox::PairedDeviceInfo as "PairedDevice";
+    /// A paired device uploaded a recording
+    #[cfg(feature = "http-api")]
+    InboxReceived = "inbox://received" => crate::history::HistoryEntry as "HistoryEntry";
+    /// An uploaded recording was transcribed
+    #[cfg(feature = "http-api")]
+    InboxTranscribed = "inbox://transcribed" => crate::history::HistoryEntry as "HistoryEntry";
+    /// An uploaded recording couldn't be transcribed and waits in the inbox
+    #[cfg(feature = "http-api")]
+    InboxTranscriptionFailed = "inbox://transcription-failed"
+        => crate::inbox::InboxFailure as "InboxFailure";
+    /// The target app runs as administrator; carries its name if known
+    InjectionElevated = "injection://elevated" => Option<String> as "string | null";
+    /// Text went into the target app, or fell back to the clipboard
+    InjectionResult = "injection://result"
+        => crate::injection::InjectionReport as "InjectionReport";
+    /// The CLI or another instance asked for a frontend command
+    IpcCommandReceived = "ipc://command" => crate::ipc::server::IpcCommandPayload as "IpcCommand";
+    /// Stage timings were stored for an entry
+    LatencyRecorded = "latency://recorded"
+        => crate::history::LatencyBreakdown as "LatencyBreakdown";
+    /// The language of tray menus, tooltips and notifications changed
+    LocaleChanged = "locale://changed" => str as "string";
+    /// The user agreed to transcribe a detected meeting
+    MeetingStarted = "meetings://start" => crate::meetings::MeetingStart as "MeetingStart";
+    /// A meeting summary moved to its next stage
+    MeetingSummaryProgress = "meetings://summary-progress"
+        => crate::meetings::summary::SummaryProgress as "SummaryProgress";
+    /// Playback stopped or reached the end
+    PlaybackEnded = "playback://ended" => crate::playback::PlaybackStatus as "PlaybackStatus";
+    /// Periodic position update while playing
+    PlaybackPosition = "playback://position"
+        => crate::playback::PlaybackStatus as "PlaybackStatus";
+    /// A recording preset was applied from the tray, CLI or settings
+    PresetApplied = "presets://applied" => crate::presets::RecordingPreset as "RecordingPreset";
+    /// Presets were saved or deleted
+    PresetsChanged = "presets://changed" => () as "null";
+    /// A recording's file is missing audio the disk couldn't keep up with
+    AudioDropped = "recorder://audio-dropped"
+        => crate::recorder::recorder::DroppedAudio as "DroppedAudio";
+    /// The selected microphone belongs to a Bluetooth headset
+    BluetoothHeadsetSelected = "recorder://bluetooth-headset"
+        => crate::recorder::bluetooth::BluetoothHeadset as "BluetoothHeadset";
+    /// A recording was paused because its disk is nearly full
+    DiskSpaceLow = "recorder://disk-space-low"
+        => crate::recorder::disk_space::DiskSpace as "DiskSpace";
+    /// The device priority list now prefers another device
+    PreferredDeviceChanged = "recorder://preferred-device"
+        => crate::recorder::priority::PreferredDevice as "PreferredDevice";
+    /// A phone finished pairing as a remote control
+    #[cfg(feature = "http-api")]
+    RemoteDevicePaired = "remote://paired" => crate::inbox::PairedDeviceInfo as "PairedDevice";
+    /// The login session became, or stopped being, the one at the screen
+    SessionChanged = "session://changed" => crate::session::SessionChange as "SessionChange";
+    /// Native settings changed, from the frontend, tray or settings file
+    SettingsChanged = "settings://changed" => crate::settings::NativeSettings as "NativeSettings";
+    /// The settings file was edited outside the app and reloaded
+    SettingsReloaded = "settings://reloaded" => crate::settings::NativeSettings as "NativeSettings";
+    /// Live transcription's best guess so far, replaced by later results
+    StreamingInterim = "streaming://interim"
+        => crate::transcription::streaming::StreamingTranscript as "StreamingTranscript";
+    /// Live transcription text the provider won't revise
+    StreamingFinal = "streaming://final"
+        => crate::transcription::streaming::StreamingTranscript as "StreamingTranscript";
+    /// A watched phrase was heard during live transcription
+    KeywordHeard = "streaming://keyword"
+        => crate::transcription::streaming::KeywordMatch as "KeywordMatch";
+    /// The live transcription connection changed state
+    StreamingStatusChanged = "streaming://status"
+        => crate::transcription::streaming::StreamingStatus as "StreamingStatus";
+    /// Syncing overwrote a change made on this device or another
+    SyncConflicted = "sync://conflict" => crate::sync::SyncConflict as "SyncConflict";
+    /// Changes from other devices were merged
+    Synced = "sync://synced" => crate::sync::SyncSummary as "SyncSummary";
+    /// The tray asked the main window to open a page
+    TrayNavigate = "tray://navigate" => str as "string";
+    /// The watchdog cancelled a stuck job; carries the timeout in seconds
+    WatchdogTimeout = "watchdog://timeout" => u64 as "number";
+    /// A file in a watched folder couldn't be transcribed
+    WatcherFailed = "watcher://error" => crate::watcher::WatcherErrorPayload as "WatcherError";
+    /// A file in a watched folder was transcribed
+    WatcherTranscribed = "watcher://transcribed" => crate::history::HistoryEntry as "HistoryEntry";
+}
@@ -0,0 +1,49 @@
+//! Every event the backend emits, with its payload.
+//!
+//! Event names used to be string literals in whichever module emitted them,
+//! repeated by hand in the frontend's `listen` calls along with a guess at
+//! the payload shape, so renaming either side broke the other silently.
+//! `catalog.rs` now lists each event once. The crate turns it into a marker
+//! type per event, emitted with [`AppEvent::emit`], and `build.rs` reads the
+//! same file to write `src/lib/events/catalog.generated.ts`, which types the
+//! frontend's `listen`.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// An event the backend emits
+pub trait AppEvent {
+    /// Name as passed to `emit` and `listen`
+    const NAME: &'static str;
+    type Payload: Serialize + ?Sized;
+
+    /// Send to every window and Rust listener
+    fn emit<R: Runtime>(app: &AppHandle<R>, payload: &Self::Payload) -> tauri::Result<()> {
+        app.emit(Self::NAME, payload)
+    }
+}
+
+macro_rules! catalog {
+    ($(
+        $(#[doc = $doc:literal])*
+        $(#[cfg($cfg:meta)])?
+        $event:ident = $name:literal => $payload:ty as $ts:literal;
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            pub struct $event;
+
+            $(#[cfg($cfg)])?
+            impl AppEvent for $event {
+                const NAME: &'static str = $name;
+                type Payload = $payload;
+            }
+        )*
+
+        /// Names of every event in the catalog this build can emit
+        pub const NAMES: &[&str] = &[$($(#[cfg($cfg)])? $name),*];
+    };
+}
+
+include!("catalog.rs");
@@ -0,0 +1,54 @@
+use super::FileNameContext;
+use crate::error::{AppError, Result};
+use crate::history::now_millis;
+use crate::recorder::commands::AppData;
+use crate::settings::SettingsStore;
+use crate::timestamps::Timestamps;
+use tauri::{AppHandle, State};
+
+/// The name `template` would give a sample recording, to show next to the
+/// setting; an error if it uses an unknown placeholder
+#[tauri::command]
+pub async fn preview_file_name(template: String, app: AppHandle) -> Result<Option<String>> {
+    let context = FileNameContext {
+        created_at: now_millis(),
+        app: Some("Slack".to_string()),
+        text: "Notes from the planning meeting about next quarter".to_string(),
+        id: "k3Jd9x".to_string(),
+        name: "interview".to_string(),
+    };
+    super::render(&template, &context, &Timestamps::current(&app))
+}
+
+/// Name the last recording saved to a file after its transcript, returning
+/// its new path. Does nothing without a template, or once it's been named.
+#[tauri::command]
+pub async fn name_saved_recording(
+    text: String,
+    data: State<'_, AppData>,
+    settings: State<'_, SettingsStore>,
+    app: AppHandle,
+) -> Result<Option<String>> {
+    let saved = data
+        .last_saved
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock saved recording: {}", e)))?
+        .take();
+    let Some(saved) = saved else {
+        return Ok(None);
+    };
+    if !saved.path.is_file() {
+        return Ok(None);
+    }
+    let context = FileNameContext {
+        text,
+        ..saved.context
+    };
+    let path = super::rename(
+        &saved.path,
+        &settings.get().file_name_template,
+        &context,
+        &Timestamps::current(&app),
+    )?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
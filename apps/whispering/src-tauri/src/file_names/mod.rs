@@ -0,0 +1,228 @@
+//! Naming saved files from a template.
+//!
+//! The `fileNameTemplate` setting, e.g. `{{date}}_{{app}}_{{first_words}}`,
+//! names saved recordings, history exports and the transcripts written next
+//! to watched-folder audio. The extension is added by whoever saves the
+//! file. An empty template keeps the built-in names.
+//!
+//! Placeholder values are cleaned of characters no file system allows, and
+//! a name already taken gets ` (2)`, ` (3)`, ... so nothing is overwritten.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::timestamps::Timestamps;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// What a template can refer to
+pub const PLACEHOLDERS: &[&str] = &[
+    "date",
+    "time",
+    "datetime",
+    "app",
+    "first_words",
+    "id",
+    "name",
+];
+
+/// How many words `{{first_words}}` takes from the transcript
+const FIRST_WORDS: usize = 5;
+/// Longest stem a template may produce, leaving room for ` (n)` and an
+/// extension within the usual 255-byte limit
+const MAX_STEM_BYTES: usize = 200;
+
+/// What a file is named after
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileNameContext {
+    /// When the recording was made, in UTC milliseconds
+    pub created_at: i64,
+    /// The app that was focused, if known
+    pub app: Option<String>,
+    /// The transcript
+    pub text: String,
+    /// The history entry or recording id
+    pub id: String,
+    /// The file's existing name without extension, e.g. the watched file's
+    pub name: String,
+}
+
+/// A recording saved under its id, waiting for its transcript to be named
+/// after
+#[derive(Debug, Clone)]
+pub struct SavedRecording {
+    pub path: PathBuf,
+    pub context: FileNameContext,
+}
+
+/// Check that `template` only uses known placeholders
+pub fn validate(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "Unclosed {{{{ in file name template {:?}",
+                template
+            ))
+        })?;
+        let placeholder = after[..end].trim();
+        if !PLACEHOLDERS.contains(&placeholder) {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown placeholder {{{{{}}}}} in file name template; use one of {}",
+                placeholder,
+                PLACEHOLDERS.join(", ")
+            )));
+        }
+        rest = &after[end + 2..];
+    }
+    Ok(())
+}
+
+/// The file stem `template` gives for `context`, or `None` for an empty
+/// template
+pub fn render(
+    template: &str,
+    context: &FileNameContext,
+    timestamps: &Timestamps,
+) -> Result<Option<String>> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Ok(None);
+    }
+    validate(template)?;
+
+    let mut stem = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        stem.push_str(&clean(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after.find("}}").unwrap_or(after.len());
+        stem.push_str(&clean(&value(after[..end].trim(), context, timestamps)));
+        rest = after.get(end + 2..).unwrap_or_default();
+    }
+    stem.push_str(&clean(rest));
+
+    let stem = stem.split_whitespace().collect::<Vec<_>>().join(" ");
+    let stem = truncate(stem.trim_matches(['.', '_', '-', ' ']), MAX_STEM_BYTES);
+    if stem.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "File name template {:?} gives an empty name",
+            template
+        )));
+    }
+    Ok(Some(stem.to_string()))
+}
+
+fn value(placeholder: &str, context: &FileNameContext, timestamps: &Timestamps) -> String {
+    let formatted = |pattern| {
+        timestamps
+            .format(context.created_at, pattern)
+            .unwrap_or_default()
+    };
+    match placeholder {
+        "date" => formatted("%Y-%m-%d"),
+        "time" => formatted("%H-%M-%S"),
+        "datetime" => timestamps.filename(context.created_at),
+        "app" => context
+            .app
+            .clone()
+            .filter(|app| !app.trim().is_empty())
+            .unwrap_or_else(|| "unknown".to_string()),
+        "first_words" => first_words(&context.text, FIRST_WORDS),
+        "id" => context.id.clone(),
+        "name" => context.name.clone(),
+        _ => String::new(),
+    }
+}
+
+/// The first `count` words of `text` joined with `-`, without punctuation
+pub fn first_words(text: &str, count: usize) -> String {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .take(count)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `value` with characters some file system doesn't allow replaced by
+/// spaces
+fn clean(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+fn truncate(value: &str, max_bytes: usize) -> &str {
+    if value.len() <= max_bytes {
+        return value;
+    }
+    let mut end = max_bytes;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    value[..end].trim_end()
+}
+
+/// A stem in `dir` that none of `extensions` is taken for: `stem`, or
+/// `stem (2)`, `stem (3)`, ... if it is
+pub fn unique_stem(dir: &Path, stem: &str, extensions: &[&str]) -> String {
+    let taken = |candidate: &str| {
+        extensions
+            .iter()
+            .any(|extension| dir.join(file_name(candidate, extension)).exists())
+    };
+    if !taken(stem) {
+        return stem.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} ({})", stem, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| stem.to_string())
+}
+
+/// `dir/stem.extension`, numbered if that's taken
+pub fn unique_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    dir.join(file_name(&unique_stem(dir, stem, &[extension]), extension))
+}
+
+fn file_name(stem: &str, extension: &str) -> String {
+    if extension.is_empty() {
+        stem.to_string()
+    } else {
+        format!("{}.{}", stem, extension)
+    }
+}
+
+/// Rename the file at `path` as `template` names it for `context`, keeping
+/// its folder and extension, and return where it is now. An empty template
+/// leaves it alone.
+pub fn rename(
+    path: &Path,
+    template: &str,
+    context: &FileNameContext,
+    timestamps: &Timestamps,
+) -> Result<PathBuf> {
+    let Some(stem) = render(template, context, timestamps)? else {
+        return Ok(path.to_path_buf());
+    };
+    if path.file_stem().and_then(|stem| stem.to_str()) == Some(stem.as_str()) {
+        return Ok(path.to_path_buf());
+    }
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let target = unique_path(dir, &stem, extension);
+    std::fs::rename(path, &target)
+        .map_err(|e| AppError::Internal(format!("Failed to rename {}: {}", path.display(), e)))?;
+    Ok(target)
+}
@@ -0,0 +1,131 @@
+//! The application the user is working in, used to tag new history entries
+//! and pick per-app injection formatting.
+
+/// Name of the application owning the foreground window (the executable name
+/// without extension, e.g. `Code` or `slack`)
+///
+/// On macOS this is the app's name as shown in the menu bar (e.g. `Code`,
+/// `Slack`) and needs the Accessibility permission, which pasting already
+/// requires. Wayland doesn't expose other apps' windows, so Linux returns
+/// `None` and rules that match on the app don't apply there.
+#[cfg(target_os = "windows")]
+pub fn focused_app() -> Option<String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut pid);
+        if pid == 0 {
+            return None;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let ok =
+            QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        if ok == 0 {
+            return None;
+        }
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn focused_app() -> Option<String> {
+    use accessibility_sys::{AXUIElementCreateSystemWide, AXUIElementRef};
+    use core_foundation_sys::base::{CFGetTypeID, CFRelease};
+    use core_foundation_sys::string::{CFStringGetTypeID, CFStringRef};
+
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        let app = copy_ax_attribute(system, "AXFocusedApplication");
+        CFRelease(system as _);
+        let app = app? as AXUIElementRef;
+        let title = copy_ax_attribute(app, "AXTitle");
+        CFRelease(app as _);
+        let title = title?;
+        let name = (CFGetTypeID(title) == CFStringGetTypeID())
+            .then(|| cf_string_to_string(title as CFStringRef));
+        CFRelease(title);
+        name.filter(|name| !name.is_empty())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn focused_app() -> Option<String> {
+    None
+}
+
+/// Copy the Accessibility attribute `name` of `element`, or `None` if it
+/// isn't set. The caller releases the result.
+///
+/// # Safety
+///
+/// `element` must be a valid `AXUIElementRef`.
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn copy_ax_attribute(
+    element: accessibility_sys::AXUIElementRef,
+    name: &str,
+) -> Option<core_foundation_sys::base::CFTypeRef> {
+    use accessibility_sys::{kAXErrorSuccess, AXUIElementCopyAttributeValue};
+    use core_foundation_sys::base::{CFRelease, CFTypeRef};
+
+    let attribute = cf_string(name);
+    let mut value: CFTypeRef = std::ptr::null();
+    let error = AXUIElementCopyAttributeValue(element, attribute, &mut value);
+    CFRelease(attribute as _);
+    (error == kAXErrorSuccess && !value.is_null()).then_some(value)
+}
+
+/// A new `CFString` holding `text`; the caller releases it
+///
+/// # Safety
+///
+/// Calls into Core Foundation; the result must be released exactly once.
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn cf_string(text: &str) -> core_foundation_sys::string::CFStringRef {
+    use core_foundation_sys::base::{kCFAllocatorDefault, CFIndex};
+    use core_foundation_sys::string::{kCFStringEncodingUTF8, CFStringCreateWithBytes};
+
+    CFStringCreateWithBytes(
+        kCFAllocatorDefault,
+        text.as_ptr(),
+        text.len() as CFIndex,
+        kCFStringEncodingUTF8,
+        0,
+    )
+}
+
+/// # Safety
+///
+/// `string` must be a valid `CFStringRef`.
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn cf_string_to_string(
+    string: core_foundation_sys::string::CFStringRef,
+) -> String {
+    use core_foundation_sys::base::CFRange;
+    use core_foundation_sys::string::{CFStringGetCharacters, CFStringGetLength};
+
+    let length = CFStringGetLength(string);
+    let mut buffer = vec![0u16; length as usize];
+    CFStringGetCharacters(string, CFRange::init(0, length), buffer.as_mut_ptr());
+    String::from_utf16_lossy(&buffer)
+}
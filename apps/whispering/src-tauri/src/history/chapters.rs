@@ -0,0 +1,69 @@
+use super::{HistoryStore, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS chapters (
+        entry_id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        start    REAL NOT NULL,
+        end      REAL NOT NULL,
+        title    TEXT NOT NULL,
+        PRIMARY KEY (entry_id, position)
+    );";
+
+/// A section of a long transcript about one topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    /// Seconds from the start of the recording
+    pub start: f32,
+    pub end: f32,
+    pub title: String,
+}
+
+/// Drop an entry's chapters, e.g. because its transcript changed
+pub(super) fn clear(conn: &Connection, entry_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM chapters WHERE entry_id = ?1", params![entry_id])?;
+    Ok(())
+}
+
+impl HistoryStore {
+    /// Stored chapters of an entry in order, or `None` if none were stored
+    /// since its transcript last changed
+    pub fn chapters(&self, entry_id: &str) -> Result<Option<Vec<Chapter>>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT start, end, title FROM chapters WHERE entry_id = ?1 ORDER BY position ASC",
+        )?;
+        let chapters = stmt
+            .query_map(params![entry_id], |row| {
+                Ok(Chapter {
+                    start: row.get(0)?,
+                    end: row.get(1)?,
+                    title: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(if chapters.is_empty() {
+            None
+        } else {
+            Some(chapters)
+        })
+    }
+
+    /// Replace an entry's chapters
+    pub fn set_chapters(&self, entry_id: &str, chapters: &[Chapter]) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        clear(&tx, entry_id)?;
+        for (position, chapter) in chapters.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO chapters (entry_id, position, start, end, title) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![entry_id, position as i64, chapter.start, chapter.end, chapter.title],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
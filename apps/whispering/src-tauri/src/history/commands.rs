@@ -0,0 +1,344 @@
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, LatencyRecorded};
+use crate::focus::focused_app;
+use crate::history::{
+    AutoTagRule, DbInfo, HistoryEntry, HistoryFilter, HistorySource, HistoryStore, InjectionRecord,
+    LatencyBreakdown, StageTiming, TagContext, TranscriptVersion,
+};
+use crate::punctuation::Punctuator;
+use crate::telemetry::{Feature, Telemetry};
+use crate::transcription::language::is_auto;
+use crate::transcription::{transcribe_local, LocalEngine, ModelManager};
+use crate::transforms::{TransformContext, Transforms};
+use serde::Deserialize;
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
+use tracing::{debug, info};
+
+#[tauri::command]
+pub async fn list_history_entries(
+    limit: Option<u32>,
+    filter: Option<HistoryFilter>,
+    history: State<'_, HistoryStore>,
+) -> Result<Vec<HistoryEntry>> {
+    debug!("Listing history entries: limit={:?}, filter={:?}", limit, filter);
+    Ok(history.list_filtered(&filter.unwrap_or_default(), limit)?)
+}
+
+/// A transcript made in the app, mirrored from the frontend's recordings
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingTranscript {
+    /// The recording's id in the frontend's database
+    pub id: String,
+    /// Milliseconds since the Unix epoch
+    pub created_at: i64,
+    pub transcript: String,
+    /// The file the desktop recorder saved, or the file transcribed
+    pub audio_path: Option<String>,
+    /// Transcription service that produced the transcript
+    pub provider: Option<String>,
+}
+
+/// Keep a transcript made in the app in history, so search, tags, bulk
+/// actions and stats cover every dictation and not only what Rust
+/// transcribed. Called again as the recording changes.
+///
+/// The first save happens as transcription finishes, while the user is still
+/// in the app being dictated into: that app becomes the entry's target app
+/// and the auto-tag rules are applied.
+#[tauri::command]
+pub async fn save_recording_to_history(
+    recording: RecordingTranscript,
+    history: State<'_, HistoryStore>,
+) -> Result<HistoryEntry> {
+    let mut entry = HistoryEntry::new(HistorySource::Recording, recording.transcript);
+    entry.id = recording.id;
+    entry.created_at = recording.created_at;
+    if let Some(path) = &recording.audio_path {
+        entry.duration_seconds = wav_duration(Path::new(path)).unwrap_or_default();
+    }
+    entry.audio_path = recording.audio_path;
+    entry.provider = recording.provider;
+    if !history.save_recording(&entry)? {
+        return Ok(history.get(&entry.id)?);
+    }
+    let context = TagContext {
+        app: focused_app(),
+        profile: None,
+    };
+    debug!("Auto-tagging new recording {} with {:?}", entry.id, context);
+    Ok(history.apply_auto_tags(&entry.id, &context)?)
+}
+
+/// Length of a WAV file from its header; other formats aren't measured
+fn wav_duration(path: &Path) -> Option<f32> {
+    let reader = hound::WavReader::open(path).ok()?;
+    let spec = reader.spec();
+    Some(reader.duration() as f32 / spec.sample_rate as f32)
+}
+
+#[tauri::command]
+pub async fn get_history_entry(id: String, history: State<'_, HistoryStore>) -> Result<HistoryEntry> {
+    Ok(history.get(&id)?)
+}
+
+/// Schema version, size and row counts of the history database, for
+/// diagnostics
+#[tauri::command]
+pub async fn get_db_info(history: State<'_, HistoryStore>) -> Result<DbInfo> {
+    Ok(history.info()?)
+}
+
+#[tauri::command]
+pub async fn delete_history_entry(id: String, history: State<'_, HistoryStore>) -> Result<()> {
+    debug!("Deleting history entry {}", id);
+    Ok(history.delete(&id)?)
+}
+
+/// Options for `retranscribe`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetranscribeOptions {
+    pub language: Option<String>,
+}
+
+/// Transcribe an entry's stored audio again, e.g. after downloading a better
+/// model. The result becomes the current transcript and a new version; the
+/// previous transcripts stay available through `list_versions`.
+///
+/// Only the local engines run in Rust; cloud providers are called from the
+/// frontend, which can store their results the same way.
+#[tauri::command]
+pub async fn retranscribe(
+    id: String,
+    provider: LocalEngine,
+    model: String,
+    options: Option<RetranscribeOptions>,
+    history: State<'_, HistoryStore>,
+    telemetry: State<'_, Telemetry>,
+    app_handle: AppHandle,
+) -> Result<TranscriptVersion> {
+    let entry = history.get(&id)?;
+    telemetry.record(Feature::Retranscription);
+    let language = options.unwrap_or_default().language;
+    retranscribe_in_background(app_handle, entry, provider, model, language).await
+}
+
+/// `retranscribe_entry` on a blocking thread, since transcribing and the
+/// transforms (script hooks among them) take seconds
+async fn retranscribe_in_background(
+    app: AppHandle,
+    entry: HistoryEntry,
+    provider: LocalEngine,
+    model: String,
+    language: Option<String>,
+) -> Result<TranscriptVersion> {
+    tauri::async_runtime::spawn_blocking(move || {
+        retranscribe_entry(
+            &app.state::<HistoryStore>(),
+            &app.state::<ModelManager>(),
+            &app.state::<Punctuator>(),
+            &app.state::<Transforms>(),
+            &entry,
+            provider,
+            &model,
+            language,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Re-transcription task failed: {}", e)))?
+}
+
+/// Transcribe an entry's audio with a local engine and store the result as a
+/// new version. Shared by `retranscribe` and bulk reprocessing.
+pub fn retranscribe_entry(
+    history: &HistoryStore,
+    model_manager: &ModelManager,
+    punctuator: &Punctuator,
+    transforms: &Transforms,
+    entry: &HistoryEntry,
+    provider: LocalEngine,
+    model: &str,
+    language: Option<String>,
+) -> Result<TranscriptVersion> {
+    let audio_path = entry.audio_path.as_deref().ok_or_else(|| {
+        AppError::InvalidInput(format!("History entry {} has no audio", entry.id))
+    })?;
+    info!("Re-transcribing {} with {:?} ({})", entry.id, provider, model);
+
+    let audio = std::fs::read(audio_path)?;
+    let context = TransformContext {
+        language: language.clone(),
+        profile: None,
+    };
+    let transcript = transcribe_local(model_manager, provider, audio, model, language.clone())?;
+    if let (Some(code), confidence) = transcript.language_for_history(language.as_deref()) {
+        history.set_language(&entry.id, &code, confidence)?;
+    }
+    let text = transforms.apply(&punctuator.apply(transcript.text), &context);
+
+    Ok(history.add_retranscription(&entry.id, provider.as_str(), model, &text)?)
+}
+
+/// Transcribe an entry again in `language`, for when auto-detection picked
+/// the wrong one (most often on short recordings). Uses the engine and model
+/// of the entry's last re-transcription unless others are given.
+#[tauri::command]
+pub async fn retranscribe_with_language(
+    id: String,
+    language: String,
+    provider: Option<LocalEngine>,
+    model: Option<String>,
+    history: State<'_, HistoryStore>,
+    telemetry: State<'_, Telemetry>,
+    app_handle: AppHandle,
+) -> Result<TranscriptVersion> {
+    if is_auto(Some(&language)) {
+        return Err(AppError::InvalidInput(
+            "Choose a language to re-transcribe in".to_string(),
+        ));
+    }
+    let entry = history.get(&id)?;
+    let previous = history
+        .list_versions(&id)?
+        .into_iter()
+        .rev()
+        .find(|version| version.model.is_some());
+    let provider = provider
+        .or_else(|| LocalEngine::parse(previous.as_ref()?.provider.as_deref()?))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("Choose an engine to re-transcribe {} with", id))
+        })?;
+    let model = model
+        .or_else(|| previous.and_then(|version| version.model))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("Choose a model to re-transcribe {} with", id))
+        })?;
+    info!("Re-transcribing {} in {}", id, language);
+    telemetry.record(Feature::Retranscription);
+    retranscribe_in_background(app_handle, entry, provider, model, Some(language)).await
+}
+
+/// Every transcript an entry has had, oldest first, for comparing versions
+#[tauri::command]
+pub async fn list_versions(
+    id: String,
+    history: State<'_, HistoryStore>,
+) -> Result<Vec<TranscriptVersion>> {
+    Ok(history.list_versions(&id)?)
+}
+
+/// Save a manual edit of an entry's transcript as a new version
+#[tauri::command]
+pub async fn edit_transcript(
+    id: String,
+    transcript: String,
+    history: State<'_, HistoryStore>,
+    telemetry: State<'_, Telemetry>,
+) -> Result<TranscriptVersion> {
+    debug!("Saving edited transcript for {}", id);
+    telemetry.record(Feature::TranscriptEdit);
+    Ok(history.edit_transcript(&id, &transcript)?)
+}
+
+/// Make an earlier transcript version current again
+#[tauri::command]
+pub async fn restore_version(
+    id: String,
+    version: u32,
+    history: State<'_, HistoryStore>,
+) -> Result<TranscriptVersion> {
+    info!("Restoring version {} of {}", version, id);
+    Ok(history.restore_version(&id, version)?)
+}
+
+/// Replace an entry's tags
+#[tauri::command]
+pub async fn set_tags(
+    id: String,
+    tags: Vec<String>,
+    history: State<'_, HistoryStore>,
+    telemetry: State<'_, Telemetry>,
+) -> Result<HistoryEntry> {
+    telemetry.record(Feature::Tags);
+    Ok(history.set_tags(&id, &tags)?)
+}
+
+/// Flip an entry's favorite flag, returning the new value
+#[tauri::command]
+pub async fn toggle_favorite(id: String, history: State<'_, HistoryStore>) -> Result<bool> {
+    Ok(history.toggle_favorite(&id)?)
+}
+
+/// Every tag in use, for filter suggestions
+#[tauri::command]
+pub async fn list_tags(history: State<'_, HistoryStore>) -> Result<Vec<String>> {
+    Ok(history.all_tags()?)
+}
+
+#[tauri::command]
+pub async fn get_auto_tag_rules(history: State<'_, HistoryStore>) -> Result<Vec<AutoTagRule>> {
+    Ok(history.auto_tag_rules()?)
+}
+
+#[tauri::command]
+pub async fn set_auto_tag_rules(
+    rules: Vec<AutoTagRule>,
+    history: State<'_, HistoryStore>,
+) -> Result<()> {
+    info!("Saving {} auto-tag rules", rules.len());
+    Ok(history.set_auto_tag_rules(&rules)?)
+}
+
+/// Tag a new entry using the auto-tag rules, matched against the currently
+/// focused app and the given profile. Call right after the entry is created.
+#[tauri::command]
+pub async fn apply_auto_tags(
+    id: String,
+    profile: Option<String>,
+    history: State<'_, HistoryStore>,
+) -> Result<HistoryEntry> {
+    let context = TagContext {
+        app: focused_app(),
+        profile,
+    };
+    debug!("Auto-tagging {} with {:?}", id, context);
+    Ok(history.apply_auto_tags(&id, &context)?)
+}
+
+/// Store how long pipeline stages took for an entry, as measured by the
+/// frontend, and emit the updated breakdown as `latency://recorded` for the
+/// performance HUD
+#[tauri::command]
+pub async fn record_latency(
+    id: String,
+    stages: Vec<StageTiming>,
+    app_handle: AppHandle,
+    history: State<'_, HistoryStore>,
+) -> Result<LatencyBreakdown> {
+    debug!("Recording latency for {}: {:?}", id, stages);
+    let breakdown = history.record_latency(&id, &stages)?;
+    let _ = LatencyRecorded::emit(&app_handle, &breakdown);
+    Ok(breakdown)
+}
+
+/// Per-stage timings for an entry, from stopping the recording to the text
+/// arriving in the target app
+#[tauri::command]
+pub async fn get_latency_breakdown(
+    id: String,
+    history: State<'_, HistoryStore>,
+) -> Result<LatencyBreakdown> {
+    Ok(history.latency_breakdown(&id)?)
+}
+
+/// How an entry's text was put into the target app and whether it landed;
+/// `None` if it was never injected
+#[tauri::command]
+pub async fn get_injection_record(
+    id: String,
+    history: State<'_, HistoryStore>,
+) -> Result<Option<InjectionRecord>> {
+    Ok(history.injection_record(&id)?)
+}
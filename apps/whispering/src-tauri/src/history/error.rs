@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, Serialize, Deserialize)]
+#[serde(tag = "name")]
+pub enum HistoryError {
+    #[error("Database error: {message}")]
+    DatabaseError { message: String },
+
+    #[error("History entry not found: {id}")]
+    NotFoundError { id: String },
+}
+
+impl From<rusqlite::Error> for HistoryError {
+    fn from(e: rusqlite::Error) -> Self {
+        HistoryError::DatabaseError {
+            message: e.to_string(),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use super::{now_millis, HistoryStore, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS injections (
+        entry_id    TEXT PRIMARY KEY,
+        app         TEXT,
+        method      TEXT NOT NULL,
+        outcome     TEXT NOT NULL,
+        injected_at INTEGER NOT NULL
+    );";
+
+/// Whether a transcript made it into the target app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InjectionOutcome {
+    /// The text was read back from the target app after going in
+    Verified,
+    /// The text was sent, but the target app doesn't let it be read back
+    Unverified,
+    /// The target app rejected the text, so it was left on the clipboard
+    FellBack,
+}
+
+impl InjectionOutcome {
+    const ALL: [InjectionOutcome; 3] = [
+        InjectionOutcome::Verified,
+        InjectionOutcome::Unverified,
+        InjectionOutcome::FellBack,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            InjectionOutcome::Verified => "verified",
+            InjectionOutcome::Unverified => "unverified",
+            InjectionOutcome::FellBack => "fellBack",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|outcome| outcome.as_str() == value)
+    }
+}
+
+/// How an entry's text was put into the target app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectionRecord {
+    pub entry_id: String,
+    /// The focused app, where it could be named
+    pub app: Option<String>,
+    /// The method actually used, after any fallbacks
+    pub method: String,
+    pub outcome: InjectionOutcome,
+    pub injected_at: i64,
+}
+
+impl HistoryStore {
+    /// Store how an entry's text was injected, replacing any earlier record
+    /// for it. As with latency, the id isn't checked against this database.
+    pub fn record_injection(
+        &self,
+        entry_id: &str,
+        app: Option<&str>,
+        method: &str,
+        outcome: InjectionOutcome,
+    ) -> Result<InjectionRecord> {
+        let injected_at = now_millis();
+        self.conn()?.execute(
+            "INSERT OR REPLACE INTO injections (entry_id, app, method, outcome, injected_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![entry_id, app, method, outcome.as_str(), injected_at],
+        )?;
+        Ok(InjectionRecord {
+            entry_id: entry_id.to_string(),
+            app: app.map(str::to_string),
+            method: method.to_string(),
+            outcome,
+            injected_at,
+        })
+    }
+
+    /// How an entry's text was injected; `None` if it never was
+    pub fn injection_record(&self, entry_id: &str) -> Result<Option<InjectionRecord>> {
+        let conn = self.conn()?;
+        let row = conn
+            .query_row(
+                "SELECT app, method, outcome, injected_at FROM injections WHERE entry_id = ?1",
+                params![entry_id],
+                |row| {
+                    Ok((
+                        row.get::<_, Option<String>>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+        Ok(row.and_then(|(app, method, outcome, injected_at)| {
+            Some(InjectionRecord {
+                entry_id: entry_id.to_string(),
+                app,
+                method,
+                outcome: InjectionOutcome::parse(&outcome)?,
+                injected_at,
+            })
+        }))
+    }
+}
@@ -0,0 +1,149 @@
+use super::{now_millis, HistoryStore, Result};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS latency (
+        entry_id    TEXT NOT NULL,
+        stage       TEXT NOT NULL,
+        ms          INTEGER NOT NULL,
+        recorded_at INTEGER NOT NULL,
+        PRIMARY KEY (entry_id, stage)
+    );";
+
+/// A step between the end of a recording and the text arriving in the
+/// target app, in pipeline order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LatencyStage {
+    /// Stopping the recorder and finalizing the audio
+    CaptureStop,
+    /// Converting or compressing the audio for the provider
+    Encode,
+    /// Sending the audio to a cloud provider
+    Upload,
+    /// Waiting for the engine or service to return a transcript
+    Provider,
+    /// Transformations and other text processing
+    PostProcess,
+    /// Copying or typing the result into the target app
+    Inject,
+}
+
+impl LatencyStage {
+    pub const ALL: [LatencyStage; 6] = [
+        LatencyStage::CaptureStop,
+        LatencyStage::Encode,
+        LatencyStage::Upload,
+        LatencyStage::Provider,
+        LatencyStage::PostProcess,
+        LatencyStage::Inject,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            LatencyStage::CaptureStop => "captureStop",
+            LatencyStage::Encode => "encode",
+            LatencyStage::Upload => "upload",
+            LatencyStage::Provider => "provider",
+            LatencyStage::PostProcess => "postProcess",
+            LatencyStage::Inject => "inject",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.as_str() == value)
+    }
+}
+
+/// How long one stage took
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTiming {
+    pub stage: LatencyStage,
+    pub ms: u64,
+}
+
+impl StageTiming {
+    /// Time a stage that started at `started` and just finished
+    pub fn since(stage: LatencyStage, started: Instant) -> Self {
+        Self {
+            stage,
+            ms: started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Where the time went between stopping a recording and getting its text
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyBreakdown {
+    pub entry_id: String,
+    /// Measured stages in pipeline order; stages that didn't run are absent
+    pub stages: Vec<StageTiming>,
+    pub total_ms: u64,
+    /// Stage taking the largest share of the total, if any were measured
+    pub slowest: Option<LatencyStage>,
+}
+
+impl LatencyBreakdown {
+    fn new(entry_id: &str, mut stages: Vec<StageTiming>) -> Self {
+        stages.sort_by_key(|timing| timing.stage);
+        Self {
+            entry_id: entry_id.to_string(),
+            total_ms: stages.iter().map(|timing| timing.ms).sum(),
+            slowest: stages
+                .iter()
+                .max_by_key(|timing| timing.ms)
+                .map(|timing| timing.stage),
+            stages,
+        }
+    }
+}
+
+impl HistoryStore {
+    /// Store stage timings for an entry. Stages can be reported separately as
+    /// they finish; reporting a stage again replaces its earlier timing.
+    ///
+    /// Entries only need to exist in the frontend's history, so the id isn't
+    /// checked against this database.
+    pub fn record_latency(
+        &self,
+        entry_id: &str,
+        stages: &[StageTiming],
+    ) -> Result<LatencyBreakdown> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let recorded_at = now_millis();
+        for timing in stages {
+            tx.execute(
+                "INSERT OR REPLACE INTO latency (entry_id, stage, ms, recorded_at) VALUES (?1, ?2, ?3, ?4)",
+                params![entry_id, timing.stage.as_str(), timing.ms as i64, recorded_at],
+            )?;
+        }
+        tx.commit()?;
+        drop(conn);
+        self.latency_breakdown(entry_id)
+    }
+
+    /// Stage timings recorded for an entry; empty if none were
+    pub fn latency_breakdown(&self, entry_id: &str) -> Result<LatencyBreakdown> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT stage, ms FROM latency WHERE entry_id = ?1")?;
+        let stages = stmt
+            .query_map(params![entry_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .filter_map(|(stage, ms)| {
+                Some(StageTiming {
+                    stage: LatencyStage::parse(&stage)?,
+                    ms: ms.max(0) as u64,
+                })
+            })
+            .collect();
+        Ok(LatencyBreakdown::new(entry_id, stages))
+    }
+}
@@ -0,0 +1,270 @@
+//! Versioned schema upgrades for the history database.
+//!
+//! The schema version is kept in SQLite's `user_version`. Each migration
+//! runs in its own transaction and bumps it, so an upgrade interrupted
+//! halfway resumes where it stopped. Before upgrading a database that holds
+//! data, a copy is written next to it in `history-backups/`, so a migration
+//! gone wrong can be undone by hand; the last few copies are kept.
+
+use super::{
+    chapters, injections, latency, now_millis, summaries, sync, tags, versions, HistoryError,
+    HistoryStore, Result,
+};
+use rusqlite::{params, Connection, DatabaseName};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Folder next to the database holding copies made before upgrades
+pub const BACKUP_DIR: &str = "history-backups";
+
+/// Copies kept in `BACKUP_DIR`; older ones are removed
+const BACKUPS_KEPT: usize = 5;
+
+struct Migration {
+    description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Every schema change, oldest first; migration `i` upgrades to version
+/// `i + 1`. Only ever append to this list.
+const MIGRATIONS: &[Migration] = &[Migration {
+    description: "Schema before versioned migrations",
+    apply: unversioned_schema,
+}];
+
+/// The version this build's schema is at
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// What `get_db_info` reports about the database
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbInfo {
+    /// `None` when history is only kept in memory
+    pub path: Option<String>,
+    pub schema_version: u32,
+    /// The version this build upgrades to
+    pub latest_version: u32,
+    /// The database file and its write-ahead log
+    pub size_bytes: u64,
+    pub tables: Vec<TableInfo>,
+    /// Copies made before upgrades, newest first
+    pub backups: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableInfo {
+    pub name: String,
+    pub rows: u64,
+}
+
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Bring the schema of `conn` up to `SCHEMA_VERSION`, first copying a
+/// database that holds data to `BACKUP_DIR` beside `path`, if given
+pub(super) fn migrate(conn: &Connection, path: Option<&Path>) -> Result<()> {
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        // Opened by an older build after a newer one; its additions are
+        // left alone and this build uses what it knows
+        warn!(
+            "History database is at schema version {}, newer than {}",
+            current, SCHEMA_VERSION
+        );
+        return Ok(());
+    }
+    if current == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    if let Some(path) = path.filter(|_| has_tables(conn).unwrap_or(true)) {
+        let backup = backup(conn, path, current)?;
+        info!(
+            "Backed up history database to {:?} before upgrading",
+            backup
+        );
+    }
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = version as u32 + 1;
+        info!(
+            "Upgrading history database to version {}: {}",
+            version, migration.description
+        );
+        conn.execute_batch("BEGIN IMMEDIATE")?;
+        let applied = (migration.apply)(conn).and_then(|()| {
+            conn.pragma_update(None, "user_version", version)?;
+            Ok(())
+        });
+        match applied {
+            Ok(()) => conn.execute_batch("COMMIT")?,
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                return Err(HistoryError::DatabaseError {
+                    message: format!("Failed to upgrade history to version {}: {}", version, e),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn has_tables(conn: &Connection) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table')",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Copy the database at version `from` into `BACKUP_DIR`, pruning old copies
+fn backup(conn: &Connection, path: &Path, from: u32) -> Result<PathBuf> {
+    let dir = backup_dir(path);
+    std::fs::create_dir_all(&dir).map_err(|e| HistoryError::DatabaseError {
+        message: format!("Failed to create {:?}: {}", dir, e),
+    })?;
+    let backup = dir.join(format!("history-v{}-{}.db", from, now_millis()));
+    conn.backup(DatabaseName::Main, &backup, None)?;
+
+    for old in list_backups(path).into_iter().skip(BACKUPS_KEPT) {
+        if let Err(e) = std::fs::remove_file(&old) {
+            warn!("Failed to remove old history backup {:?}: {}", old, e);
+        }
+    }
+    Ok(backup)
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(Path::new(".")).join(BACKUP_DIR)
+}
+
+/// Copies in `BACKUP_DIR` beside `path`, newest first
+fn list_backups(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(backup_dir(path)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<(i64, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            let (_, made_at) = stem.strip_prefix("history-v")?.split_once('-')?;
+            Some((made_at.parse().ok()?, path))
+        })
+        .collect();
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    backups.into_iter().map(|(_, path)| path).collect()
+}
+
+impl HistoryStore {
+    /// Schema version, size and row counts, for diagnostics
+    pub fn info(&self) -> Result<DbInfo> {
+        let conn = self.conn()?;
+        let mut statement = conn.prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+             ORDER BY name",
+        )?;
+        let names = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let rows = conn.query_row(
+                &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+                [],
+                |row| row.get(0),
+            )?;
+            tables.push(TableInfo { name, rows });
+        }
+
+        let path = self.path.as_deref();
+        let size_bytes = path
+            .map(|path| {
+                let wal = path.with_extension("db-wal");
+                [path, wal.as_path()]
+                    .iter()
+                    .filter_map(|file| std::fs::metadata(file).ok())
+                    .map(|metadata| metadata.len())
+                    .sum()
+            })
+            .unwrap_or_default();
+        Ok(DbInfo {
+            path: path.map(|path| path.display().to_string()),
+            schema_version: schema_version(&conn)?,
+            latest_version: SCHEMA_VERSION,
+            size_bytes,
+            tables,
+            backups: path
+                .map(list_backups)
+                .unwrap_or_default()
+                .iter()
+                .map(|backup| backup.display().to_string())
+                .collect(),
+        })
+    }
+}
+
+/// Version 1: the tables as they were built up before migrations were
+/// versioned. Each step checks what's there, so databases from any earlier
+/// release end up the same.
+fn unversioned_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS history (
+            id               TEXT PRIMARY KEY,
+            created_at       INTEGER NOT NULL,
+            source           TEXT NOT NULL,
+            source_path      TEXT,
+            audio_path       TEXT,
+            transcript       TEXT NOT NULL,
+            duration_seconds REAL NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS history_created_at ON history (created_at);
+        CREATE INDEX IF NOT EXISTS history_source_path ON history (source_path);
+        CREATE TABLE IF NOT EXISTS waveforms (
+            id    TEXT PRIMARY KEY,
+            peaks BLOB NOT NULL
+        );",
+    )?;
+    add_column_if_missing(conn, "history", "tags", "TEXT NOT NULL DEFAULT '[]'")?;
+    add_column_if_missing(conn, "history", "favorite", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "history", "provider", "TEXT")?;
+    add_column_if_missing(conn, "history", "target_app", "TEXT")?;
+    add_column_if_missing(conn, "history", "title", "TEXT")?;
+    add_column_if_missing(conn, "history", "attendees", "TEXT NOT NULL DEFAULT '[]'")?;
+    add_column_if_missing(conn, "history", "updated_at", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "history", "language", "TEXT")?;
+    add_column_if_missing(conn, "history", "language_confidence", "REAL")?;
+    conn.execute_batch(versions::SCHEMA)?;
+    conn.execute_batch(tags::SCHEMA)?;
+    conn.execute_batch(latency::SCHEMA)?;
+    conn.execute_batch(injections::SCHEMA)?;
+    conn.execute_batch(sync::SCHEMA)?;
+    conn.execute_batch(summaries::SCHEMA)?;
+    conn.execute_batch(chapters::SCHEMA)?;
+    Ok(())
+}
+
+/// Add a column to a table created by an older version of the app
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ))?
+        .exists(params![column])?;
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,468 @@
+pub mod commands;
+mod chapters;
+mod error;
+mod injections;
+mod latency;
+mod migrations;
+mod snapshot;
+mod summaries;
+mod sync;
+mod tags;
+mod versions;
+
+pub use chapters::Chapter;
+pub use error::HistoryError;
+pub use injections::{InjectionOutcome, InjectionRecord};
+pub use latency::{LatencyBreakdown, LatencyStage, StageTiming};
+pub use migrations::{schema_version, DbInfo, TableInfo, BACKUP_DIR, SCHEMA_VERSION};
+pub use summaries::{ActionItem, MeetingSummary};
+pub use sync::{LocalCopy, SyncedEntry, Tombstone};
+pub use tags::{AutoTagRule, TagContext};
+pub use versions::{TranscriptVersion, VersionSource};
+
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+pub type Result<T> = std::result::Result<T, HistoryError>;
+
+/// File name of the history database inside the app data directory
+pub const HISTORY_DB_FILE: &str = "history.db";
+
+/// Where a history entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistorySource {
+    Recording,
+    WatchedFolder,
+    Inbox,
+    /// Brought over from another dictation tool (see `import`)
+    Import,
+}
+
+impl HistorySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistorySource::Recording => "recording",
+            HistorySource::WatchedFolder => "watchedFolder",
+            HistorySource::Inbox => "inbox",
+            HistorySource::Import => "import",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "watchedFolder" => HistorySource::WatchedFolder,
+            "inbox" => HistorySource::Inbox,
+            "import" => HistorySource::Import,
+            _ => HistorySource::Recording,
+        }
+    }
+}
+
+/// A transcript produced on the Rust side, returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: String,
+    /// Milliseconds since the Unix epoch (UTC)
+    pub created_at: i64,
+    pub source: HistorySource,
+    /// Original file the audio was read from, if any
+    pub source_path: Option<String>,
+    /// Audio kept for this entry, if any
+    pub audio_path: Option<String>,
+    pub transcript: String,
+    pub duration_seconds: f32,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    /// Engine or service that produced the current transcript
+    pub provider: Option<String>,
+    /// Application the transcript was dictated into, if known
+    pub target_app: Option<String>,
+    /// Name of the calendar event the recording was made during, if any
+    pub title: Option<String>,
+    /// Attendees of that event
+    pub attendees: Vec<String>,
+    /// Language code of the transcript, detected or chosen by the user
+    pub language: Option<String>,
+    /// How sure auto-detection was of `language`, from 0 to 1; `None` when
+    /// the language was chosen rather than detected
+    pub language_confidence: Option<f32>,
+}
+
+impl HistoryEntry {
+    /// Create an entry with a fresh id and the current timestamp
+    pub fn new(source: HistorySource, transcript: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: now_millis(),
+            source,
+            source_path: None,
+            audio_path: None,
+            transcript,
+            duration_seconds: 0.0,
+            tags: Vec::new(),
+            favorite: false,
+            provider: None,
+            target_app: None,
+            title: None,
+            attendees: Vec::new(),
+            language: None,
+            language_confidence: None,
+        }
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let source: String = row.get("source")?;
+        let tags: String = row.get("tags")?;
+        let attendees: String = row.get("attendees")?;
+        Ok(Self {
+            id: row.get("id")?,
+            created_at: row.get("created_at")?,
+            source: HistorySource::parse(&source),
+            source_path: row.get("source_path")?,
+            audio_path: row.get("audio_path")?,
+            transcript: row.get("transcript")?,
+            duration_seconds: row.get("duration_seconds")?,
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+            favorite: row.get("favorite")?,
+            provider: row.get("provider")?,
+            target_app: row.get("target_app")?,
+            title: row.get("title")?,
+            attendees: serde_json::from_str(&attendees).unwrap_or_default(),
+            language: row.get("language")?,
+            language_confidence: row.get("language_confidence")?,
+        })
+    }
+}
+
+/// Which entries to list
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryFilter {
+    /// Only these entries (an explicit selection)
+    pub ids: Option<Vec<String>>,
+    /// Created at or after this time, in milliseconds since the Unix epoch
+    pub from: Option<i64>,
+    /// Created before this time, in milliseconds since the Unix epoch
+    pub to: Option<i64>,
+    /// Only entries carrying all of these tags
+    pub tags: Vec<String>,
+    pub favorites_only: bool,
+}
+
+impl HistoryFilter {
+    /// Whether this matches every entry
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_none()
+            && self.from.is_none()
+            && self.to.is_none()
+            && self.tags.is_empty()
+            && !self.favorites_only
+    }
+}
+
+/// SQLite-backed store for transcripts created by Rust-side subsystems
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+    /// The database file, unless history is only kept in memory
+    path: Option<PathBuf>,
+}
+
+impl HistoryStore {
+    /// Open (or create) the history database at the given path
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| HistoryError::DatabaseError {
+                message: format!("Failed to create history directory: {}", e),
+            })?;
+        }
+
+        let conn = Connection::open(path)?;
+        info!("History database opened at {:?}", path);
+        Self::init(conn, Some(path))
+    }
+
+    /// A history that lives only as long as the app, for when storing
+    /// history is disabled by policy
+    pub fn open_in_memory() -> Result<Self> {
+        info!("History storage disabled, keeping history in memory");
+        Self::init(Connection::open_in_memory()?, None)
+    }
+
+    fn init(conn: Connection, path: Option<&Path>) -> Result<Self> {
+        migrations::migrate(&conn, path)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            path: path.map(Path::to_path_buf),
+        })
+    }
+
+    fn conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn.lock().map_err(|e| HistoryError::DatabaseError {
+            message: format!("Failed to lock history database: {}", e),
+        })
+    }
+
+    pub fn insert(&self, entry: &HistoryEntry) -> Result<()> {
+        self.conn()?.execute(
+            "INSERT INTO history (id, created_at, source, source_path, audio_path, transcript, duration_seconds, tags, favorite, provider, target_app, title, attendees, language, language_confidence)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                entry.id,
+                entry.created_at,
+                entry.source.as_str(),
+                entry.source_path,
+                entry.audio_path,
+                entry.transcript,
+                entry.duration_seconds,
+                tags::to_json(&entry.tags),
+                entry.favorite,
+                entry.provider,
+                entry.target_app,
+                entry.title,
+                serde_json::to_string(&entry.attendees).unwrap_or_else(|_| "[]".to_string()),
+                entry.language,
+                entry.language_confidence,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Add a transcript made in the app, or update it once it's transcribed
+    /// again, edited or its audio is known. Tags, versions and the rest
+    /// added since are kept, and so is the first provider recorded. Returns
+    /// whether the entry is new.
+    pub fn save_recording(&self, entry: &HistoryEntry) -> Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE history SET transcript = ?2,
+                audio_path = COALESCE(?3, audio_path),
+                duration_seconds = CASE WHEN ?4 > 0 THEN ?4 ELSE duration_seconds END,
+                provider = COALESCE(provider, ?5)
+             WHERE id = ?1",
+            params![
+                entry.id,
+                entry.transcript,
+                entry.audio_path,
+                entry.duration_seconds,
+                entry.provider
+            ],
+        )?;
+        drop(conn);
+        if updated > 0 {
+            return Ok(false);
+        }
+        self.insert(entry)?;
+        Ok(true)
+    }
+
+    pub fn get(&self, id: &str) -> Result<HistoryEntry> {
+        self.conn()?
+            .query_row(
+                "SELECT * FROM history WHERE id = ?1",
+                params![id],
+                HistoryEntry::from_row,
+            )
+            .optional()?
+            .ok_or_else(|| HistoryError::NotFoundError { id: id.to_string() })
+    }
+
+    /// List entries, newest first
+    pub fn list(&self, limit: Option<u32>) -> Result<Vec<HistoryEntry>> {
+        self.list_filtered(&HistoryFilter::default(), limit)
+    }
+
+    /// List entries matching a filter, newest first
+    pub fn list_filtered(&self, filter: &HistoryFilter, limit: Option<u32>) -> Result<Vec<HistoryEntry>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(ids) = &filter.ids {
+            let mut placeholders = Vec::new();
+            for id in ids {
+                values.push(Value::Text(id.clone()));
+                placeholders.push(format!("?{}", values.len()));
+            }
+            conditions.push(format!("id IN ({})", placeholders.join(", ")));
+        }
+        if let Some(from) = filter.from {
+            values.push(Value::Integer(from));
+            conditions.push(format!("created_at >= ?{}", values.len()));
+        }
+        if let Some(to) = filter.to {
+            values.push(Value::Integer(to));
+            conditions.push(format!("created_at < ?{}", values.len()));
+        }
+        for tag in &filter.tags {
+            values.push(Value::Text(tag.clone()));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM json_each(history.tags) WHERE value = ?{})",
+                values.len()
+            ));
+        }
+        if filter.favorites_only {
+            conditions.push("favorite = 1".to_string());
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        values.push(Value::Integer(limit.map(i64::from).unwrap_or(-1)));
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM history {} ORDER BY created_at DESC LIMIT ?{}",
+            where_clause,
+            values.len()
+        ))?;
+        let entries = stmt
+            .query_map(params_from_iter(values), HistoryEntry::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// List entries from a single source, newest first
+    pub fn list_by_source(&self, source: HistorySource) -> Result<Vec<HistoryEntry>> {
+        let conn = self.conn()?;
+        let mut stmt =
+            conn.prepare("SELECT * FROM history WHERE source = ?1 ORDER BY created_at DESC")?;
+        let entries = stmt
+            .query_map(params![source.as_str()], HistoryEntry::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    pub fn update_transcript(&self, id: &str, transcript: &str, duration_seconds: f32) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE history SET transcript = ?2, duration_seconds = ?3 WHERE id = ?1",
+            params![id, transcript, duration_seconds],
+        )?;
+        if updated == 0 {
+            return Err(HistoryError::NotFoundError { id: id.to_string() });
+        }
+        chapters::clear(&conn, id)?;
+        Ok(())
+    }
+
+    /// Record the language of an entry's transcript; `confidence` is `None`
+    /// when the user chose the language
+    pub fn set_language(&self, id: &str, language: &str, confidence: Option<f32>) -> Result<()> {
+        let updated = self.conn()?.execute(
+            "UPDATE history SET language = ?2, language_confidence = ?3 WHERE id = ?1",
+            params![id, language, confidence],
+        )?;
+        if updated == 0 {
+            return Err(HistoryError::NotFoundError { id: id.to_string() });
+        }
+        Ok(())
+    }
+
+    /// Label an entry with the meeting it was recorded during
+    pub fn set_meeting(&self, id: &str, title: &str, attendees: &[String]) -> Result<HistoryEntry> {
+        let updated = self.conn()?.execute(
+            "UPDATE history SET title = ?2, attendees = ?3 WHERE id = ?1",
+            params![
+                id,
+                title,
+                serde_json::to_string(attendees).unwrap_or_else(|_| "[]".to_string())
+            ],
+        )?;
+        if updated == 0 {
+            return Err(HistoryError::NotFoundError { id: id.to_string() });
+        }
+        self.get(id)
+    }
+
+    /// Point an entry at new audio (e.g. after trimming), dropping derived data
+    pub fn update_audio(&self, id: &str, audio_path: &str, duration_seconds: f32) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE history SET audio_path = ?2, duration_seconds = ?3 WHERE id = ?1",
+            params![id, audio_path, duration_seconds],
+        )?;
+        if updated == 0 {
+            return Err(HistoryError::NotFoundError { id: id.to_string() });
+        }
+        conn.execute("DELETE FROM waveforms WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    pub fn delete(&self, id: &str) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if !delete_with_derived(&tx, id)? {
+            return Err(HistoryError::NotFoundError { id: id.to_string() });
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Cached waveform peaks for an entry, if they've been computed
+    pub fn waveform_peaks(&self, id: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .conn()?
+            .query_row(
+                "SELECT peaks FROM waveforms WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_waveform_peaks(&self, id: &str, peaks: &[u8]) -> Result<()> {
+        self.conn()?.execute(
+            "INSERT OR REPLACE INTO waveforms (id, peaks) VALUES (?1, ?2)",
+            params![id, peaks],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a file has already been imported, so watchers don't transcribe it twice
+    pub fn contains_source_path(&self, source_path: &str) -> Result<bool> {
+        let found = self
+            .conn()?
+            .query_row(
+                "SELECT 1 FROM history WHERE source_path = ?1 LIMIT 1",
+                params![source_path],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+}
+
+/// Delete an entry and every row derived from it, returning whether it
+/// existed. Run inside a transaction, so a failure part way can't leave rows
+/// behind for an entry that's gone; shared by deleting here and applying a
+/// deletion from another device.
+fn delete_with_derived(conn: &Connection, id: &str) -> Result<bool> {
+    let deleted = conn.execute("DELETE FROM history WHERE id = ?1", params![id])?;
+    conn.execute("DELETE FROM waveforms WHERE id = ?1", params![id])?;
+    conn.execute(
+        "DELETE FROM transcript_versions WHERE entry_id = ?1",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM latency WHERE entry_id = ?1", params![id])?;
+    conn.execute(
+        "DELETE FROM meeting_summaries WHERE entry_id = ?1",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM injections WHERE entry_id = ?1", params![id])?;
+    chapters::clear(conn, id)?;
+    Ok(deleted > 0)
+}
+
+/// Current time in milliseconds since the Unix epoch
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,36 @@
+use super::{migrations, HistoryStore, Result};
+use rusqlite::DatabaseName;
+use std::path::Path;
+
+impl HistoryStore {
+    /// Write a consistent copy of the database to `path`, e.g. for a backup
+    pub fn snapshot_to(&self, path: &Path) -> Result<()> {
+        self.conn()?.backup(DatabaseName::Main, path, None)?;
+        Ok(())
+    }
+
+    /// Replace the whole database with the copy at `path`, upgrading it if it
+    /// was written by an older version. The copy is itself a backup, so none
+    /// is made first.
+    pub fn restore_from(&self, path: &Path) -> Result<()> {
+        let mut conn = self.conn()?;
+        conn.restore(
+            DatabaseName::Main,
+            path,
+            None::<fn(rusqlite::backup::Progress)>,
+        )?;
+        migrations::migrate(&conn, None)
+    }
+
+    /// Entries that keep audio, as `(entry id, audio path)`
+    pub fn audio_paths(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn()?;
+        let mut statement = conn.prepare(
+            "SELECT id, audio_path FROM history WHERE audio_path IS NOT NULL ORDER BY created_at",
+        )?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,81 @@
+use super::{HistoryStore, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meeting_summaries (
+        entry_id     TEXT PRIMARY KEY,
+        created_at   INTEGER NOT NULL,
+        model        TEXT,
+        summary      TEXT NOT NULL,
+        decisions    TEXT NOT NULL DEFAULT '[]',
+        action_items TEXT NOT NULL DEFAULT '[]'
+    );";
+
+/// Something someone agreed to do in a meeting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionItem {
+    pub task: String,
+    /// Who took it on, as named in the meeting
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// When it's due, in the meeting's own words ("by Friday")
+    #[serde(default)]
+    pub due: Option<String>,
+}
+
+/// What an LLM made of a meeting transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingSummary {
+    pub entry_id: String,
+    /// Milliseconds since the Unix epoch (UTC)
+    pub created_at: i64,
+    /// Model that wrote the summary
+    pub model: Option<String>,
+    pub summary: String,
+    pub decisions: Vec<String>,
+    pub action_items: Vec<ActionItem>,
+}
+
+impl HistoryStore {
+    /// Store the summary of an entry, replacing any earlier one
+    pub fn set_meeting_summary(&self, summary: &MeetingSummary) -> Result<()> {
+        self.conn()?.execute(
+            "INSERT OR REPLACE INTO meeting_summaries (entry_id, created_at, model, summary, decisions, action_items)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                summary.entry_id,
+                summary.created_at,
+                summary.model,
+                summary.summary,
+                serde_json::to_string(&summary.decisions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&summary.action_items).unwrap_or_else(|_| "[]".to_string()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn meeting_summary(&self, entry_id: &str) -> Result<Option<MeetingSummary>> {
+        Ok(self
+            .conn()?
+            .query_row(
+                "SELECT * FROM meeting_summaries WHERE entry_id = ?1",
+                params![entry_id],
+                |row| {
+                    let decisions: String = row.get("decisions")?;
+                    let action_items: String = row.get("action_items")?;
+                    Ok(MeetingSummary {
+                        entry_id: row.get("entry_id")?,
+                        created_at: row.get("created_at")?,
+                        model: row.get("model")?,
+                        summary: row.get("summary")?,
+                        decisions: serde_json::from_str(&decisions).unwrap_or_default(),
+                        action_items: serde_json::from_str(&action_items).unwrap_or_default(),
+                    })
+                },
+            )
+            .optional()?)
+    }
+}
@@ -0,0 +1,172 @@
+use super::{chapters, delete_with_derived, tags, HistoryEntry, HistoryStore, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Triggers stamp every change with `updated_at` and remember deletions, so
+/// the sync module can tell which copy of an entry is newer without every
+/// write path having to know about it. Changes applied from another device
+/// carry their own `updated_at`, which the triggers leave alone.
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS history_tombstones (
+        id         TEXT PRIMARY KEY,
+        deleted_at INTEGER NOT NULL
+    );
+    UPDATE history SET updated_at = created_at WHERE updated_at = 0;
+    CREATE TRIGGER IF NOT EXISTS history_sync_insert AFTER INSERT ON history
+    WHEN NEW.updated_at = 0
+    BEGIN
+        UPDATE history
+        SET updated_at = CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER)
+        WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_sync_update AFTER UPDATE ON history
+    WHEN NEW.updated_at IS OLD.updated_at
+    BEGIN
+        UPDATE history
+        SET updated_at = CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER)
+        WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_sync_delete AFTER DELETE ON history
+    BEGIN
+        INSERT OR REPLACE INTO history_tombstones (id, deleted_at)
+        VALUES (OLD.id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+    END;";
+
+/// An entry with the time it last changed, as exchanged between devices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedEntry {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    /// Milliseconds since the Unix epoch
+    pub updated_at: i64,
+}
+
+/// A deleted entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tombstone {
+    pub id: String,
+    /// Milliseconds since the Unix epoch
+    pub deleted_at: i64,
+}
+
+/// What this device holds for an entry id
+#[derive(Debug, Clone)]
+pub enum LocalCopy {
+    Missing,
+    Present(SyncedEntry),
+    Deleted(i64),
+}
+
+impl HistoryStore {
+    /// Every entry and deletion, for sending to other devices
+    pub fn sync_snapshot(&self) -> Result<(Vec<SyncedEntry>, Vec<Tombstone>)> {
+        let conn = self.conn()?;
+        let entries = conn
+            .prepare("SELECT * FROM history ORDER BY created_at")?
+            .query_map([], |row| {
+                Ok(SyncedEntry {
+                    entry: HistoryEntry::from_row(row)?,
+                    updated_at: row.get("updated_at")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let tombstones = conn
+            .prepare("SELECT id, deleted_at FROM history_tombstones")?
+            .query_map([], |row| {
+                Ok(Tombstone {
+                    id: row.get(0)?,
+                    deleted_at: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok((entries, tombstones))
+    }
+
+    pub fn local_copy(&self, id: &str) -> Result<LocalCopy> {
+        let conn = self.conn()?;
+        let entry = conn
+            .query_row("SELECT * FROM history WHERE id = ?1", params![id], |row| {
+                Ok(SyncedEntry {
+                    entry: HistoryEntry::from_row(row)?,
+                    updated_at: row.get("updated_at")?,
+                })
+            })
+            .optional()?;
+        if let Some(entry) = entry {
+            return Ok(LocalCopy::Present(entry));
+        }
+        let deleted_at = conn
+            .query_row(
+                "SELECT deleted_at FROM history_tombstones WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(deleted_at.map_or(LocalCopy::Missing, LocalCopy::Deleted))
+    }
+
+    /// Store another device's copy of an entry, keeping its `updated_at`
+    pub fn apply_synced_entry(&self, synced: &SyncedEntry) -> Result<()> {
+        let entry = &synced.entry;
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO history (id, created_at, source, source_path, audio_path, transcript, duration_seconds, tags, favorite, provider, target_app, title, attendees, language, language_confidence, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+             ON CONFLICT (id) DO UPDATE SET
+                created_at = excluded.created_at,
+                source = excluded.source,
+                source_path = excluded.source_path,
+                audio_path = excluded.audio_path,
+                transcript = excluded.transcript,
+                duration_seconds = excluded.duration_seconds,
+                tags = excluded.tags,
+                favorite = excluded.favorite,
+                provider = excluded.provider,
+                target_app = excluded.target_app,
+                title = excluded.title,
+                attendees = excluded.attendees,
+                language = excluded.language,
+                language_confidence = excluded.language_confidence,
+                updated_at = excluded.updated_at",
+            params![
+                entry.id,
+                entry.created_at,
+                entry.source.as_str(),
+                entry.source_path,
+                entry.audio_path,
+                entry.transcript,
+                entry.duration_seconds,
+                tags::to_json(&entry.tags),
+                entry.favorite,
+                entry.provider,
+                entry.target_app,
+                entry.title,
+                serde_json::to_string(&entry.attendees).unwrap_or_else(|_| "[]".to_string()),
+                entry.language,
+                entry.language_confidence,
+                synced.updated_at,
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM history_tombstones WHERE id = ?1",
+            params![entry.id],
+        )?;
+        chapters::clear(&conn, &entry.id)?;
+        Ok(())
+    }
+
+    /// Apply another device's deletion, keeping its time
+    pub fn apply_tombstone(&self, tombstone: &Tombstone) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        delete_with_derived(&tx, &tombstone.id)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO history_tombstones (id, deleted_at) VALUES (?1, ?2)",
+            params![tombstone.id, tombstone.deleted_at],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use super::{HistoryEntry, HistoryError, HistoryStore, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS auto_tag_rules (
+        position INTEGER PRIMARY KEY,
+        app      TEXT,
+        profile  TEXT,
+        tags     TEXT NOT NULL
+    );";
+
+/// Tags `tags` onto new entries created while `app` is focused and/or
+/// `profile` is active. A rule without either condition never matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoTagRule {
+    /// Application name or executable, compared case-insensitively
+    pub app: Option<String>,
+    pub profile: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl AutoTagRule {
+    fn matches(&self, context: &TagContext) -> bool {
+        fn same(condition: &Option<String>, value: &Option<String>) -> bool {
+            match (condition, value) {
+                (None, _) => true,
+                (Some(condition), Some(value)) => condition.eq_ignore_ascii_case(value),
+                (Some(_), None) => false,
+            }
+        }
+        (self.app.is_some() || self.profile.is_some())
+            && same(&self.app, &context.app)
+            && same(&self.profile, &context.profile)
+    }
+}
+
+/// What the user was doing when an entry was created
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagContext {
+    pub app: Option<String>,
+    pub profile: Option<String>,
+}
+
+/// Trim tags, dropping empty ones and duplicates while keeping their order
+fn normalize(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+pub(super) fn to_json(tags: &[String]) -> String {
+    serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string())
+}
+
+impl HistoryStore {
+    /// Replace an entry's tags
+    pub fn set_tags(&self, id: &str, tags: &[String]) -> Result<HistoryEntry> {
+        let updated = self.conn()?.execute(
+            "UPDATE history SET tags = ?2 WHERE id = ?1",
+            params![id, to_json(&normalize(tags))],
+        )?;
+        if updated == 0 {
+            return Err(HistoryError::NotFoundError { id: id.to_string() });
+        }
+        self.get(id)
+    }
+
+    /// Flip an entry's favorite flag, returning the new value
+    pub fn toggle_favorite(&self, id: &str) -> Result<bool> {
+        self.conn()?
+            .query_row(
+                "UPDATE history SET favorite = NOT favorite WHERE id = ?1 RETURNING favorite",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| HistoryError::NotFoundError { id: id.to_string() })
+    }
+
+    /// Every tag in use, sorted
+    pub fn all_tags(&self) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT tag.value FROM history, json_each(history.tags) AS tag ORDER BY tag.value",
+        )?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tags)
+    }
+
+    pub fn auto_tag_rules(&self) -> Result<Vec<AutoTagRule>> {
+        let conn = self.conn()?;
+        let mut stmt =
+            conn.prepare("SELECT app, profile, tags FROM auto_tag_rules ORDER BY position")?;
+        let rules = stmt
+            .query_map([], |row| {
+                let tags: String = row.get("tags")?;
+                Ok(AutoTagRule {
+                    app: row.get("app")?,
+                    profile: row.get("profile")?,
+                    tags: serde_json::from_str(&tags).unwrap_or_default(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rules)
+    }
+
+    pub fn set_auto_tag_rules(&self, rules: &[AutoTagRule]) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM auto_tag_rules", [])?;
+        for (position, rule) in rules.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO auto_tag_rules (position, app, profile, tags) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    position as i64,
+                    rule.app,
+                    rule.profile,
+                    to_json(&normalize(&rule.tags))
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Add the tags of every rule matching `context` to an entry, and
+    /// remember the app it was dictated into
+    pub fn apply_auto_tags(&self, id: &str, context: &TagContext) -> Result<HistoryEntry> {
+        if let Some(app) = &context.app {
+            self.conn()?.execute(
+                "UPDATE history SET target_app = ?2 WHERE id = ?1",
+                params![id, app],
+            )?;
+        }
+        let entry = self.get(id)?;
+        let mut tags = entry.tags.clone();
+        for rule in self.auto_tag_rules()?.iter().filter(|r| r.matches(context)) {
+            tags.extend(rule.tags.iter().cloned());
+        }
+        let tags = normalize(&tags);
+        if tags == entry.tags {
+            return Ok(entry);
+        }
+        self.set_tags(id, &tags)
+    }
+}
@@ -0,0 +1,232 @@
+use super::{chapters, now_millis, HistoryEntry, HistoryError, HistoryStore, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+pub(super) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS transcript_versions (
+        entry_id   TEXT NOT NULL,
+        version    INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        source     TEXT NOT NULL,
+        provider   TEXT,
+        model      TEXT,
+        transcript TEXT NOT NULL,
+        PRIMARY KEY (entry_id, version)
+    );";
+
+/// What produced a transcript version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VersionSource {
+    /// The transcript the entry had before its first new version
+    Original,
+    Retranscription,
+    /// Edited by hand
+    UserEdit,
+    /// Copy of an earlier version, made current again
+    Restored,
+}
+
+impl VersionSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            VersionSource::Original => "original",
+            VersionSource::Retranscription => "retranscription",
+            VersionSource::UserEdit => "userEdit",
+            VersionSource::Restored => "restored",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "retranscription" => VersionSource::Retranscription,
+            "userEdit" => VersionSource::UserEdit,
+            "restored" => VersionSource::Restored,
+            _ => VersionSource::Original,
+        }
+    }
+}
+
+/// One transcript of a history entry's audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptVersion {
+    pub entry_id: String,
+    /// 1-based, increasing with each new version of the entry
+    pub version: u32,
+    /// Milliseconds since the Unix epoch (UTC)
+    pub created_at: i64,
+    pub source: VersionSource,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub transcript: String,
+}
+
+impl TranscriptVersion {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let source: String = row.get("source")?;
+        Ok(Self {
+            entry_id: row.get("entry_id")?,
+            version: row.get("version")?,
+            created_at: row.get("created_at")?,
+            source: VersionSource::parse(&source),
+            provider: row.get("provider")?,
+            model: row.get("model")?,
+            transcript: row.get("transcript")?,
+        })
+    }
+}
+
+fn insert_version(
+    conn: &Connection,
+    entry_id: &str,
+    created_at: i64,
+    source: VersionSource,
+    provider: Option<&str>,
+    model: Option<&str>,
+    transcript: &str,
+) -> Result<TranscriptVersion> {
+    let version: u32 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) + 1 FROM transcript_versions WHERE entry_id = ?1",
+        params![entry_id],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "INSERT INTO transcript_versions (entry_id, version, created_at, source, provider, model, transcript)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![entry_id, version, created_at, source.as_str(), provider, model, transcript],
+    )?;
+    Ok(TranscriptVersion {
+        entry_id: entry_id.to_string(),
+        version,
+        created_at,
+        source,
+        provider: provider.map(str::to_string),
+        model: model.map(str::to_string),
+        transcript: transcript.to_string(),
+    })
+}
+
+impl HistoryStore {
+    /// Add a version and make it the entry's current transcript
+    ///
+    /// The first time an entry gets a new version its existing transcript is
+    /// kept as version 1, so it can always be compared against and restored.
+    fn push_version(
+        &self,
+        entry_id: &str,
+        source: VersionSource,
+        provider: Option<&str>,
+        model: Option<&str>,
+        transcript: &str,
+    ) -> Result<TranscriptVersion> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        let has_versions: bool = tx.query_row(
+            "SELECT EXISTS (SELECT 1 FROM transcript_versions WHERE entry_id = ?1)",
+            params![entry_id],
+            |row| row.get(0),
+        )?;
+        if !has_versions {
+            let entry = tx
+                .query_row(
+                    "SELECT * FROM history WHERE id = ?1",
+                    params![entry_id],
+                    HistoryEntry::from_row,
+                )
+                .optional()?
+                .ok_or_else(|| HistoryError::NotFoundError {
+                    id: entry_id.to_string(),
+                })?;
+            insert_version(
+                &tx,
+                entry_id,
+                entry.created_at,
+                VersionSource::Original,
+                None,
+                None,
+                &entry.transcript,
+            )?;
+        }
+
+        let version = insert_version(
+            &tx,
+            entry_id,
+            now_millis(),
+            source,
+            provider,
+            model,
+            transcript,
+        )?;
+        let updated = tx.execute(
+            "UPDATE history SET transcript = ?2, provider = COALESCE(?3, provider) WHERE id = ?1",
+            params![entry_id, transcript, provider],
+        )?;
+        if updated == 0 {
+            return Err(HistoryError::NotFoundError {
+                id: entry_id.to_string(),
+            });
+        }
+        chapters::clear(&tx, entry_id)?;
+        tx.commit()?;
+        Ok(version)
+    }
+
+    /// Record a re-transcription and make it the current transcript
+    pub fn add_retranscription(
+        &self,
+        entry_id: &str,
+        provider: &str,
+        model: &str,
+        transcript: &str,
+    ) -> Result<TranscriptVersion> {
+        self.push_version(
+            entry_id,
+            VersionSource::Retranscription,
+            Some(provider),
+            Some(model),
+            transcript,
+        )
+    }
+
+    /// Record a manual edit and make it the current transcript
+    pub fn edit_transcript(&self, entry_id: &str, transcript: &str) -> Result<TranscriptVersion> {
+        self.push_version(entry_id, VersionSource::UserEdit, None, None, transcript)
+    }
+
+    /// Make an earlier version current again, recorded as a new version so
+    /// the history of changes stays linear
+    pub fn restore_version(&self, entry_id: &str, version: u32) -> Result<TranscriptVersion> {
+        let restored = self
+            .conn()?
+            .query_row(
+                "SELECT * FROM transcript_versions WHERE entry_id = ?1 AND version = ?2",
+                params![entry_id, version],
+                TranscriptVersion::from_row,
+            )
+            .optional()?
+            .ok_or_else(|| HistoryError::NotFoundError {
+                id: format!("{} (version {})", entry_id, version),
+            })?;
+        self.push_version(
+            entry_id,
+            VersionSource::Restored,
+            restored.provider.as_deref(),
+            restored.model.as_deref(),
+            &restored.transcript,
+        )
+    }
+
+    /// All versions of an entry's transcript, oldest first
+    pub fn list_versions(&self, entry_id: &str) -> Result<Vec<TranscriptVersion>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM transcript_versions WHERE entry_id = ?1 ORDER BY version ASC",
+        )?;
+        let versions = stmt
+            .query_map(params![entry_id], TranscriptVersion::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(versions)
+    }
+}
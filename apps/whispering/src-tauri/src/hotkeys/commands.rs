@@ -0,0 +1,16 @@
+use super::{HotkeyConfig, Hotkeys};
+use crate::error::Result;
+use tauri::State;
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_hotkey_config(hotkeys: State<'_, Hotkeys>) -> Result<HotkeyConfig> {
+    Ok(hotkeys.config())
+}
+
+/// Replace the double-tap and sequence triggers and their timing
+#[tauri::command]
+pub async fn set_hotkey_config(config: HotkeyConfig, hotkeys: State<'_, Hotkeys>) -> Result<()> {
+    debug!("Saving {} hotkey triggers", config.triggers.len());
+    hotkeys.set_config(config)
+}
@@ -0,0 +1,365 @@
+//! Triggers the global shortcut plugin can't express.
+//!
+//! global-hotkey only fires on a single key combination, so double-tapping a
+//! modifier (e.g. Right Ctrl twice), typing a sequence of keys, or holding an
+//! extra mouse button or foot pedal (see `raw_input`) is matched here from
+//! the raw events of the `shortcut_capture` listener. A matched trigger emits
+//! `hotkeys://trigger` with the id of the command to run, which the frontend
+//! dispatches like any other shortcut.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, HotkeyTriggered};
+use crate::raw_input::{self, PEDAL_PREFIX};
+use crate::shortcut_capture::{self, KeyEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+
+pub const HOTKEYS_FILE: &str = "hotkeys.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TriggerKind {
+    /// The same key pressed twice in quick succession, with nothing in between
+    DoubleTap { key: String },
+    /// Keys pressed one after another, e.g. `["ControlRight", "KeyW"]`
+    Sequence { keys: Vec<String> },
+    /// A key, mouse button (`Mouse4`) or pedal (`Pedal1`) held down; fires
+    /// on press and again on release, so push-to-talk works
+    Button { button: String },
+}
+
+impl TriggerKind {
+    fn inputs(&self) -> impl Iterator<Item = &String> {
+        match self {
+            TriggerKind::DoubleTap { key } => std::slice::from_ref(key).iter(),
+            TriggerKind::Sequence { keys } => keys.iter(),
+            TriggerKind::Button { button } => std::slice::from_ref(button).iter(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trigger {
+    /// Frontend command id, e.g. `toggleManualRecording`
+    pub command: String,
+    #[serde(flatten)]
+    pub kind: TriggerKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeyConfig {
+    pub triggers: Vec<Trigger>,
+    /// Longest gap between the two presses of a double-tap
+    pub double_tap_ms: u64,
+    /// Longest gap between consecutive keys of a sequence
+    pub sequence_timeout_ms: u64,
+    /// Presses this soon after the same key was released are treated as
+    /// contact bounce and ignored
+    pub debounce_ms: u64,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            triggers: Vec::new(),
+            double_tap_ms: 300,
+            sequence_timeout_ms: 1000,
+            debounce_ms: 30,
+        }
+    }
+}
+
+impl HotkeyConfig {
+    fn validate(&self) -> Result<()> {
+        if !(100..=1000).contains(&self.double_tap_ms) {
+            return Err(AppError::InvalidInput(
+                "Double-tap interval must be between 100 and 1000 ms".to_string(),
+            ));
+        }
+        if !(200..=5000).contains(&self.sequence_timeout_ms) {
+            return Err(AppError::InvalidInput(
+                "Sequence timeout must be between 200 and 5000 ms".to_string(),
+            ));
+        }
+        if self.debounce_ms >= self.double_tap_ms {
+            return Err(AppError::InvalidInput(
+                "Debounce must be shorter than the double-tap interval".to_string(),
+            ));
+        }
+        for trigger in &self.triggers {
+            if trigger.command.is_empty() {
+                return Err(AppError::InvalidInput(
+                    "Every trigger needs a command".to_string(),
+                ));
+            }
+            match &trigger.kind {
+                TriggerKind::DoubleTap { key } if key.is_empty() => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Double-tap trigger for {} needs a key",
+                        trigger.command
+                    )));
+                }
+                TriggerKind::Button { button } if button.is_empty() => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Button trigger for {} needs a button",
+                        trigger.command
+                    )));
+                }
+                TriggerKind::Sequence { keys } if keys.len() < 2 => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Sequence for {} needs at least two keys",
+                        trigger.command
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Payload of `hotkeys://trigger`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerFired {
+    pub command: String,
+    pub state: TriggerState,
+}
+
+/// Matches the frontend's shortcut trigger states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TriggerState {
+    Pressed,
+    Released,
+}
+
+pub struct Hotkeys {
+    path: PathBuf,
+    config: Arc<Mutex<HotkeyConfig>>,
+    started: Once,
+}
+
+impl Hotkeys {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                HotkeyConfig::default()
+            }),
+            Err(_) => HotkeyConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Arc::new(Mutex::new(config)),
+            started: Once::new(),
+        }
+    }
+
+    pub fn config(&self) -> HotkeyConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: HotkeyConfig) -> Result<()> {
+        config.validate()?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize hotkeys: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+
+        start_listeners(&config);
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock hotkeys: {}", e)))? = config;
+        Ok(())
+    }
+
+    /// Start matching triggers against key events
+    pub fn start(&self, app: AppHandle) {
+        self.started.call_once(|| {
+            let events = shortcut_capture::subscribe_keys();
+            let config = self.config.clone();
+            std::thread::spawn(move || {
+                let mut matcher = Matcher::default();
+                for event in events {
+                    let Ok(config) = config.lock().map(|config| config.clone()) else {
+                        continue;
+                    };
+                    for fired in matcher.feed(&config, &event) {
+                        debug!("Hotkey trigger fired: {:?}", fired);
+                        if let Err(e) = HotkeyTriggered::emit(&app, &fired) {
+                            warn!("Failed to emit hotkey trigger: {}", e);
+                        }
+                    }
+                }
+            });
+        });
+        start_listeners(&self.config());
+    }
+}
+
+/// Start the listeners `config`'s triggers need. Listening for every key
+/// needs accessibility access on macOS, so nothing starts until there's a
+/// trigger to match.
+fn start_listeners(config: &HotkeyConfig) {
+    let inputs: Vec<&String> = config
+        .triggers
+        .iter()
+        .flat_map(|trigger| trigger.kind.inputs())
+        .collect();
+    if inputs.iter().any(|input| !input.starts_with(PEDAL_PREFIX)) {
+        info!("Listening for hotkey triggers");
+        shortcut_capture::ensure_listener();
+    }
+    if inputs.iter().any(|input| input.starts_with(PEDAL_PREFIX)) {
+        raw_input::ensure_pedal_listener();
+    }
+    if inputs.iter().any(|input| raw_input::is_media_code(input)) {
+        raw_input::ensure_media_listener();
+    }
+}
+
+/// A completed press and release of a single key, the first half of a double-tap
+struct Tap {
+    code: String,
+    released: Instant,
+}
+
+#[derive(Default)]
+struct Matcher {
+    held: HashSet<String>,
+    last_release: Option<(String, Instant)>,
+    /// Set when a key was pressed and released with no other key involved
+    tap: Option<Tap>,
+    /// Whether another key went down while the current tap candidate was held
+    interrupted: bool,
+    sequence: Vec<(String, Instant)>,
+}
+
+impl Matcher {
+    /// Commands whose trigger `event` completes
+    fn feed(&mut self, config: &HotkeyConfig, event: &KeyEvent) -> Vec<TriggerFired> {
+        if !event.pressed {
+            if !self.held.remove(&event.code) {
+                return Vec::new();
+            }
+            self.last_release = Some((event.code.clone(), event.at));
+            self.tap = (!self.interrupted && self.held.is_empty()).then(|| Tap {
+                code: event.code.clone(),
+                released: event.at,
+            });
+            return buttons(config, &event.code, TriggerState::Released);
+        }
+
+        // Auto-repeat while held, or the key bouncing right after release
+        if self.held.contains(&event.code) {
+            return Vec::new();
+        }
+        let debounce = Duration::from_millis(config.debounce_ms);
+        if let Some((code, released)) = &self.last_release {
+            if *code == event.code && event.at.duration_since(*released) < debounce {
+                return Vec::new();
+            }
+        }
+        self.held.insert(event.code.clone());
+        self.interrupted = self.held.len() > 1;
+
+        let mut fired = buttons(config, &event.code, TriggerState::Pressed);
+
+        let double_tap = Duration::from_millis(config.double_tap_ms);
+        let tapped_twice = self.tap.take().is_some_and(|tap| {
+            tap.code == event.code
+                && !self.interrupted
+                && event.at.duration_since(tap.released) <= double_tap
+        });
+        if tapped_twice {
+            let double_tapped: Vec<TriggerFired> = config
+                .triggers
+                .iter()
+                .filter(|trigger| matches!(&trigger.kind, TriggerKind::DoubleTap { key } if *key == event.code))
+                .map(|trigger| TriggerFired {
+                    command: trigger.command.clone(),
+                    state: TriggerState::Pressed,
+                })
+                .collect();
+            if !double_tapped.is_empty() {
+                // A third tap starts over rather than firing again
+                self.interrupted = true;
+            }
+            fired.extend(double_tapped);
+        }
+
+        let timeout = Duration::from_millis(config.sequence_timeout_ms);
+        if self
+            .sequence
+            .last()
+            .is_some_and(|(_, at)| event.at.duration_since(*at) > timeout)
+        {
+            self.sequence.clear();
+        }
+        self.sequence.push((event.code.clone(), event.at));
+        let mut sequence_fired = false;
+        for trigger in &config.triggers {
+            if let TriggerKind::Sequence { keys } = &trigger.kind {
+                let matched = self
+                    .sequence
+                    .len()
+                    .checked_sub(keys.len())
+                    .is_some_and(|start| {
+                        self.sequence[start..].iter().map(|(code, _)| code).eq(keys)
+                    });
+                if matched {
+                    fired.push(TriggerFired {
+                        command: trigger.command.clone(),
+                        state: TriggerState::Pressed,
+                    });
+                    sequence_fired = true;
+                }
+            }
+        }
+        if sequence_fired {
+            self.sequence.clear();
+        }
+        let longest = config
+            .triggers
+            .iter()
+            .filter_map(|trigger| match &trigger.kind {
+                TriggerKind::Sequence { keys } => Some(keys.len()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        if self.sequence.len() > longest {
+            self.sequence.drain(..self.sequence.len() - longest);
+        }
+
+        fired
+    }
+}
+
+/// Button triggers bound to `code`
+fn buttons(config: &HotkeyConfig, code: &str, state: TriggerState) -> Vec<TriggerFired> {
+    config
+        .triggers
+        .iter()
+        .filter(|trigger| matches!(&trigger.kind, TriggerKind::Button { button } if button == code))
+        .map(|trigger| TriggerFired {
+            command: trigger.command.clone(),
+            state,
+        })
+        .collect()
+}
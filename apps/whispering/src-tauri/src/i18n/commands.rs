@@ -0,0 +1,59 @@
+use crate::error::{AppError, Result};
+use crate::settings::SettingsStore;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    /// The locale in use, e.g. `de`
+    pub locale: &'static str,
+    /// Locales with translations
+    pub supported: Vec<&'static str>,
+}
+
+fn info(locale: &'static str) -> LocaleInfo {
+    LocaleInfo {
+        locale,
+        supported: super::SUPPORTED_LOCALES
+            .iter()
+            .map(|(locale, _)| *locale)
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_locale() -> Result<LocaleInfo> {
+    Ok(info(super::locale()))
+}
+
+/// Set the language of tray menus, tooltips and notifications, e.g. `fr`
+/// or `pt-BR`; an empty `lang` follows the system's
+///
+/// Saved as the `language` setting, whose change relabels the tray and
+/// emits `locale://changed`.
+#[tauri::command]
+pub async fn set_locale(
+    lang: String,
+    settings: State<'_, SettingsStore>,
+    app: AppHandle,
+) -> Result<LocaleInfo> {
+    let lang = lang.trim().to_string();
+    if !lang.is_empty() && super::negotiate(&lang).is_none() {
+        return Err(AppError::InvalidInput(format!(
+            "No translation for {:?}",
+            lang
+        )));
+    }
+    let locale = super::resolve(&lang);
+    settings.update(&app, |settings| settings.language = lang)?;
+    Ok(info(locale))
+}
+
+/// The tray menu labels in the current language, by message id (e.g.
+/// `tray-quit`), for the tray the frontend builds
+#[tauri::command]
+pub async fn get_tray_labels() -> Result<HashMap<String, String>> {
+    Ok(super::messages("tray-").into_iter().collect())
+}
@@ -0,0 +1,183 @@
+//! Translations for what the Rust side shows on its own: tray menus and
+//! tooltips, notifications, screen reader announcements and error hints.
+//!
+//! Messages are Fluent files in `locales/`, compiled into the binary. The
+//! language follows the `language` native setting, or the system's when
+//! that's empty; a language without a file, or a message missing from one,
+//! falls back to English.
+//!
+//! The current locale is global rather than managed state so that errors,
+//! which are serialized without an `AppHandle`, can be translated too.
+
+pub mod commands;
+
+use crate::events::{AppEvent, LocaleChanged, SettingsChanged};
+use crate::settings::{NativeSettings, SettingsStore};
+use crate::tray::TrayManager;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::RwLock;
+use tauri::{AppHandle, Listener, Manager};
+use tracing::{error, info, warn};
+use unic_langid::LanguageIdentifier;
+
+/// Used when nothing better matches, and for messages a translation lacks
+pub const FALLBACK_LOCALE: &str = "en";
+
+/// Languages with a message file, by their primary language subtag
+pub const SUPPORTED_LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../../locales/en.ftl")),
+    ("de", include_str!("../../locales/de.ftl")),
+    ("es", include_str!("../../locales/es.ftl")),
+    ("fr", include_str!("../../locales/fr.ftl")),
+];
+
+type Bundle = FluentBundle<FluentResource>;
+
+lazy_static::lazy_static! {
+    static ref BUNDLES: Vec<(&'static str, Bundle)> = SUPPORTED_LOCALES
+        .iter()
+        .map(|(locale, source)| (*locale, bundle(locale, source)))
+        .collect();
+    static ref CURRENT_LOCALE: RwLock<&'static str> = RwLock::new(FALLBACK_LOCALE);
+}
+
+fn bundle(locale: &str, source: &str) -> Bundle {
+    let id: LanguageIdentifier = locale.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Notifications and menus are plain text, where the bidi isolation
+    // marks around arguments would show up as stray characters
+    bundle.set_use_isolating(false);
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+            error!("Errors in the {} messages: {:?}", locale, errors);
+            resource
+        });
+    if let Err(errors) = bundle.add_resource(resource) {
+        error!("Duplicate {} messages: {:?}", locale, errors);
+    }
+    bundle
+}
+
+/// The supported locale closest to `requested` (e.g. `de` for `de-AT`), or
+/// `None` if its language has no translation
+pub fn negotiate(requested: &str) -> Option<&'static str> {
+    let language = requested
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    SUPPORTED_LOCALES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| *locale == language)
+}
+
+/// The locale for a `language` setting: that language, or the system's
+/// when it's empty
+pub fn resolve(language: &str) -> &'static str {
+    let requested = match language.trim() {
+        "" => tauri_plugin_os::locale().unwrap_or_default(),
+        language => language.to_string(),
+    };
+    negotiate(&requested).unwrap_or(FALLBACK_LOCALE)
+}
+
+pub fn locale() -> &'static str {
+    CURRENT_LOCALE
+        .read()
+        .map(|locale| *locale)
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+/// Switch to `locale`, returning whether it changed
+fn set_locale(locale: &'static str) -> bool {
+    match CURRENT_LOCALE.write() {
+        Ok(mut current) if *current != locale => {
+            *current = locale;
+            true
+        }
+        Ok(_) => false,
+        Err(e) => {
+            warn!("Failed to switch locale: {}", e);
+            false
+        }
+    }
+}
+
+/// The message `id` in the current language
+pub fn t(id: &str) -> String {
+    t_args(id, &[])
+}
+
+/// The message `id` in the current language, with its `{ $name }`
+/// placeholders filled in from `args`
+pub fn t_args(id: &str, args: &[(&str, &str)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, *value);
+    }
+    [locale(), FALLBACK_LOCALE]
+        .iter()
+        .find_map(|locale| format_message(locale, id, &fluent_args))
+        .unwrap_or_else(|| {
+            warn!("No message {:?}", id);
+            id.to_string()
+        })
+}
+
+fn format_message(locale: &str, id: &str, args: &FluentArgs) -> Option<String> {
+    let (_, bundle) = BUNDLES.iter().find(|(l, _)| *l == locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+    if !errors.is_empty() {
+        warn!("Errors formatting {:?} in {}: {:?}", id, locale, errors);
+    }
+    Some(text.into_owned())
+}
+
+/// Every message whose id starts with `prefix`, in the current language
+pub fn messages(prefix: &str) -> Vec<(String, String)> {
+    // English has every message, so its file lists them all
+    let (_, source) = SUPPORTED_LOCALES[0];
+    source
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, _)| id.trim())
+        .filter(|id| id.starts_with(prefix) && !id.contains(char::is_whitespace))
+        .map(|id| (id.to_string(), t(id)))
+        .collect()
+}
+
+/// Apply the language setting now and whenever it changes, relabelling the
+/// tray and telling the frontend with `locale://changed`
+pub fn follow_settings(app: AppHandle) {
+    let language = app
+        .try_state::<SettingsStore>()
+        .map(|settings| settings.get().language)
+        .unwrap_or_default();
+    apply(&app, &language);
+
+    let handle = app.clone();
+    app.listen_any(SettingsChanged::NAME, move |event| {
+        let Ok(settings) = serde_json::from_str::<NativeSettings>(event.payload()) else {
+            return;
+        };
+        apply(&handle, &settings.language);
+    });
+}
+
+fn apply(app: &AppHandle, language: &str) {
+    let locale = resolve(language);
+    if !set_locale(locale) {
+        return;
+    }
+    info!("Using locale {}", locale);
+    if let Err(e) = app.state::<TrayManager>().relabel(app) {
+        warn!("Failed to relabel tray: {}", e);
+    }
+    if let Err(e) = LocaleChanged::emit(app, locale) {
+        warn!("Failed to announce locale change: {}", e);
+    }
+}
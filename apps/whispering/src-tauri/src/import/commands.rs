@@ -0,0 +1,17 @@
+use super::{ImportFormat, ImportReport};
+use crate::error::Result;
+use crate::history::HistoryStore;
+use std::path::Path;
+use tauri::State;
+
+/// Import transcripts from another tool's export at `path`. With `dry_run`
+/// nothing is stored, and the report says what would be.
+#[tauri::command]
+pub async fn import_history(
+    path: String,
+    format: ImportFormat,
+    dry_run: Option<bool>,
+    history: State<'_, HistoryStore>,
+) -> Result<ImportReport> {
+    super::import(&history, Path::new(&path), format, dry_run.unwrap_or(false))
+}
@@ -0,0 +1,165 @@
+//! Bringing transcripts over from other dictation tools.
+//!
+//! Each supported export is read into the same `Record`s and stored as
+//! history entries with the `import` source. An entry's `source_path` says
+//! where in the export it came from (the file, or the file and row), so
+//! importing the same export again only adds what's new. A dry run reads
+//! everything and reports what would be imported without storing it.
+
+pub mod commands;
+mod parse;
+
+use crate::error::Result;
+use crate::history::{HistoryEntry, HistorySource, HistoryStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::info;
+
+/// Entries included in a report's `preview`
+const PREVIEW_LEN: usize = 10;
+
+/// What `import_history` reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+    /// superwhisper's recordings folder, with a `meta.json` per recording
+    Superwhisper,
+    /// Transcripts exported as text, SRT, WebVTT or JSON files
+    MacWhisper,
+    /// Transcripts exported as text, SRT or WebVTT files
+    Aiko,
+    /// A table with a header row, e.g. `date,text,duration`
+    Csv,
+    /// An array of objects with fields like `text` and `createdAt`
+    Json,
+}
+
+impl ImportFormat {
+    /// Name of the tool, recorded as the provider of what it transcribed
+    fn tool(self) -> &'static str {
+        match self {
+            ImportFormat::Superwhisper => "superwhisper",
+            ImportFormat::MacWhisper => "MacWhisper",
+            ImportFormat::Aiko => "Aiko",
+            ImportFormat::Csv => "CSV",
+            ImportFormat::Json => "JSON",
+        }
+    }
+}
+
+/// An entry as read from an export, before it's stored
+#[derive(Debug, Clone, Default)]
+struct Record {
+    /// Where in the export the entry is, becoming its `source_path`
+    key: String,
+    created_at: Option<i64>,
+    transcript: String,
+    duration_seconds: Option<f32>,
+    title: Option<String>,
+    tags: Vec<String>,
+    favorite: bool,
+    audio_path: Option<String>,
+    provider: Option<String>,
+    language: Option<String>,
+}
+
+/// An entry that couldn't be imported
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProblem {
+    /// The file, or file and row, it came from
+    pub source: String,
+    pub error: String,
+}
+
+/// What an import did, or would do for a dry run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Entries found in the export
+    pub found: usize,
+    /// Entries stored, or that would be
+    pub imported: usize,
+    /// Entries already imported before, left alone
+    pub duplicates: usize,
+    pub problems: Vec<ImportProblem>,
+    /// The first few entries as they're stored
+    pub preview: Vec<HistoryEntry>,
+}
+
+/// Import the export at `path`, or only report on it if `dry_run`
+pub fn import(
+    history: &HistoryStore,
+    path: &Path,
+    format: ImportFormat,
+    dry_run: bool,
+) -> Result<ImportReport> {
+    let parsed = parse::parse(path, format)?;
+    let mut report = ImportReport {
+        dry_run,
+        found: parsed.len(),
+        imported: 0,
+        duplicates: 0,
+        problems: Vec::new(),
+        preview: Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    for record in parsed {
+        let record = match record {
+            Ok(record) if record.transcript.trim().is_empty() => {
+                report.problems.push(ImportProblem {
+                    source: record.key,
+                    error: "No transcript".to_string(),
+                });
+                continue;
+            }
+            Ok(record) => record,
+            Err(problem) => {
+                report.problems.push(problem);
+                continue;
+            }
+        };
+        if !seen.insert(record.key.clone()) || history.contains_source_path(&record.key)? {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let entry = to_entry(record, format);
+        if !dry_run {
+            history.insert(&entry)?;
+        }
+        report.imported += 1;
+        if report.preview.len() < PREVIEW_LEN {
+            report.preview.push(entry);
+        }
+    }
+
+    info!(
+        "{} {} of {} {:?} entries from {:?}",
+        if dry_run { "Would import" } else { "Imported" },
+        report.imported,
+        report.found,
+        format,
+        path
+    );
+    Ok(report)
+}
+
+fn to_entry(record: Record, format: ImportFormat) -> HistoryEntry {
+    let mut entry = HistoryEntry::new(HistorySource::Import, record.transcript.trim().to_string());
+    if let Some(created_at) = record.created_at {
+        entry.created_at = created_at;
+    }
+    entry.source_path = Some(record.key);
+    entry.audio_path = record.audio_path;
+    entry.duration_seconds = record.duration_seconds.unwrap_or_default().max(0.0);
+    entry.title = record.title;
+    entry.tags = record.tags;
+    entry.favorite = record.favorite;
+    entry.provider = record.provider.or_else(|| Some(format.tool().to_string()));
+    entry.language = record.language;
+    entry
+}
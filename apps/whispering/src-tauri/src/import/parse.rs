@@ -0,0 +1,403 @@
+//! Reading each supported format into `Record`s.
+
+use super::{ImportFormat, ImportProblem, Record};
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "wav", "m4a", "mp3", "aac", "ogg", "opus", "flac", "webm", "mp4",
+];
+const TRANSCRIPT_EXTENSIONS: &[&str] = &["txt", "srt", "vtt", "json"];
+
+/// Formats tried, in order, for dates without a zone; the last ones are how
+/// Whispering's own CSV exports write them
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%d.%m.%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+pub(super) type Parsed = std::result::Result<Record, ImportProblem>;
+
+pub(super) fn parse(path: &Path, format: ImportFormat) -> Result<Vec<Parsed>> {
+    if !path.exists() {
+        return Err(AppError::InvalidInput(format!(
+            "{} doesn't exist",
+            path.display()
+        )));
+    }
+    match format {
+        ImportFormat::Superwhisper => superwhisper(path),
+        ImportFormat::MacWhisper | ImportFormat::Aiko => transcript_files(path, format),
+        ImportFormat::Csv => table(path),
+        ImportFormat::Json => json(path),
+    }
+}
+
+/// superwhisper keeps a folder per recording, holding `meta.json` and the
+/// audio as `output.wav`. `path` is the folder of those, or one of them.
+fn superwhisper(path: &Path) -> Result<Vec<Parsed>> {
+    let dirs = if path.join("meta.json").is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        sorted_entries(path)?
+            .into_iter()
+            .filter(|dir| dir.join("meta.json").is_file())
+            .collect()
+    };
+
+    Ok(dirs
+        .into_iter()
+        .map(|dir| {
+            let key = dir.display().to_string();
+            let meta = read_json(&dir.join("meta.json")).map_err(|e| problem(&key, e))?;
+            let transcript = ["result", "llmResult", "rawResult"]
+                .iter()
+                .filter_map(|field| meta.get(*field).and_then(text))
+                .find(|text| !text.trim().is_empty())
+                .unwrap_or_default();
+            Ok(Record {
+                created_at: meta
+                    .get("datetime")
+                    .and_then(timestamp)
+                    .or_else(|| modified(&dir)),
+                transcript,
+                // In milliseconds
+                duration_seconds: meta
+                    .get("duration")
+                    .and_then(number)
+                    .map(|ms| (ms / 1000.0) as f32),
+                provider: meta.get("modelName").and_then(text),
+                language: meta.get("languageSelected").and_then(text),
+                audio_path: Some(dir.join("output.wav"))
+                    .filter(|audio| audio.is_file())
+                    .map(|audio| audio.display().to_string()),
+                key,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// MacWhisper and Aiko export each transcript as its own file: plain text,
+/// SRT or WebVTT subtitles, or MacWhisper's JSON. `path` is one such file
+/// or a folder of them; audio with the same name next to one is kept too.
+fn transcript_files(path: &Path, format: ImportFormat) -> Result<Vec<Parsed>> {
+    let files = if path.is_dir() {
+        sorted_entries(path)?
+            .into_iter()
+            .filter(|file| {
+                extension(file).is_some_and(|ext| TRANSCRIPT_EXTENSIONS.contains(&ext.as_str()))
+            })
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            let key = file.display().to_string();
+            let contents = std::fs::read_to_string(&file).map_err(|e| problem(&key, e))?;
+            let (transcript, duration_seconds) = match extension(&file).as_deref() {
+                Some("srt") | Some("vtt") => subtitles(&contents),
+                Some("json") => {
+                    let value: Value =
+                        serde_json::from_str(&contents).map_err(|e| problem(&key, e))?;
+                    segments(&value)
+                }
+                _ => (contents.trim().to_string(), None),
+            };
+            Ok(Record {
+                created_at: modified(&file),
+                transcript,
+                duration_seconds,
+                title: file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string()),
+                provider: Some(format.tool().to_string()),
+                audio_path: sibling_audio(&file).map(|audio| audio.display().to_string()),
+                key,
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// A table with a header row naming its columns
+fn table(path: &Path) -> Result<Vec<Parsed>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| AppError::InvalidInput(format!("Can't read {}: {}", path.display(), e)))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| AppError::InvalidInput(format!("Can't read {}: {}", path.display(), e)))?
+        .clone();
+
+    Ok(reader
+        .records()
+        .enumerate()
+        .map(|(row, record)| {
+            let key = format!("{}#{}", path.display(), row + 1);
+            let record = record.map_err(|e| problem(&key, e))?;
+            let object: Map<String, Value> = headers
+                .iter()
+                .zip(record.iter())
+                .map(|(name, value)| (name.to_string(), Value::String(value.to_string())))
+                .collect();
+            from_object(path, &key, &object)
+        })
+        .collect())
+}
+
+/// An array of objects, bare or under a key like `entries`
+fn json(path: &Path) -> Result<Vec<Parsed>> {
+    let value = read_json(path)
+        .map_err(|e| AppError::InvalidInput(format!("Can't read {}: {}", path.display(), e)))?;
+    let items = match &value {
+        Value::Array(items) => items,
+        Value::Object(object) => ["entries", "history", "items", "recordings", "transcripts"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_array))
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("{} holds no list of entries", path.display()))
+            })?,
+        _ => {
+            return Err(AppError::InvalidInput(format!(
+                "{} holds no list of entries",
+                path.display()
+            )))
+        }
+    };
+
+    Ok(items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let id = item.get("id").and_then(text);
+            let key = format!(
+                "{}#{}",
+                path.display(),
+                id.unwrap_or_else(|| (index + 1).to_string())
+            );
+            let object = item
+                .as_object()
+                .ok_or_else(|| problem(&key, "not an object"))?;
+            from_object(path, &key, object)
+        })
+        .collect())
+}
+
+/// A record from fields named the way most tools name them, ignoring case
+/// and separators (`created_at`, `createdAt` and `Created At` are the same)
+fn from_object(source: &Path, key: &str, object: &Map<String, Value>) -> Parsed {
+    let field = |names: &[&str]| {
+        object
+            .iter()
+            .find(|(name, value)| {
+                let name: String = name
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .flat_map(char::to_lowercase)
+                    .collect();
+                names.contains(&name.as_str()) && !value.is_null()
+            })
+            .map(|(_, value)| value)
+    };
+
+    let transcript = field(&["transcript", "text", "transcription", "content", "result"])
+        .and_then(text)
+        .ok_or_else(|| problem(key, "no transcript column"))?;
+    let tags = match field(&["tags", "labels"]) {
+        Some(Value::Array(tags)) => tags.iter().filter_map(text).collect(),
+        Some(tags) => text(tags)
+            .unwrap_or_default()
+            .split([';', ','])
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        None => Vec::new(),
+    };
+    let audio_path = field(&["audiopath", "audio", "audiofile", "file"])
+        .and_then(text)
+        .map(|audio| match source.parent() {
+            Some(dir) => dir.join(audio),
+            None => PathBuf::from(audio),
+        })
+        .filter(|audio| audio.is_file())
+        .map(|audio| audio.display().to_string());
+
+    Ok(Record {
+        key: key.to_string(),
+        created_at: field(&[
+            "createdat",
+            "created",
+            "date",
+            "datetime",
+            "timestamp",
+            "time",
+        ])
+        .and_then(timestamp),
+        transcript,
+        duration_seconds: field(&["durationseconds", "duration"])
+            .and_then(number)
+            .map(|seconds| seconds as f32),
+        title: field(&["title", "name"]).and_then(text),
+        tags,
+        favorite: field(&["favorite", "starred"])
+            .and_then(text)
+            .is_some_and(|favorite| {
+                matches!(favorite.to_lowercase().as_str(), "true" | "1" | "yes")
+            }),
+        audio_path,
+        provider: field(&["provider", "model", "service"]).and_then(text),
+        language: field(&["language", "lang"]).and_then(text),
+    })
+}
+
+/// The text of SRT or WebVTT cues joined up, and when the last one ends
+fn subtitles(contents: &str) -> (String, Option<f32>) {
+    let mut lines = Vec::new();
+    let mut end = None;
+    for line in contents.lines().map(str::trim) {
+        if let Some((_, cue_end)) = line.split_once("-->") {
+            end = cue_end.split_whitespace().next().and_then(cue_time).or(end);
+            continue;
+        }
+        let is_index = !line.is_empty() && line.chars().all(|c| c.is_ascii_digit());
+        if line.is_empty() || is_index || line.starts_with("WEBVTT") || line.starts_with("NOTE") {
+            continue;
+        }
+        lines.push(line);
+    }
+    (lines.join(" "), end)
+}
+
+/// `01:02:03,456`, `01:02:03.456` or `02:03.456` in seconds
+fn cue_time(value: &str) -> Option<f32> {
+    value
+        .replace(',', ".")
+        .split(':')
+        .try_fold(0.0, |total, part| {
+            Some(total * 60.0 + part.parse::<f32>().ok()?)
+        })
+}
+
+/// The transcript of a MacWhisper JSON export: its `text`, or its segments'
+fn segments(value: &Value) -> (String, Option<f32>) {
+    let segments = value
+        .get("segments")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let end = segments
+        .last()
+        .and_then(|segment| segment.get("end"))
+        .and_then(number)
+        .map(|end| end as f32);
+    let transcript = value.get("text").and_then(text).unwrap_or_else(|| {
+        segments
+            .iter()
+            .filter_map(|segment| segment.get("text").and_then(text))
+            .map(|text| text.trim().to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    (transcript, end)
+}
+
+/// A string, or a number written out
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Milliseconds since the epoch from a Unix time in seconds or
+/// milliseconds, or a date; dates without a zone are taken as local time
+pub(super) fn timestamp(value: &Value) -> Option<i64> {
+    if let Some(number) = number(value) {
+        // Seconds until the year 5138, milliseconds after
+        return Some(if number.abs() < 1e11 {
+            (number * 1000.0) as i64
+        } else {
+            number as i64
+        });
+    }
+    let text = text(value)?;
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.timestamp_millis());
+    }
+    let naive = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.timestamp_millis())
+}
+
+fn read_json(path: &Path) -> std::result::Result<Value, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| e.to_string())
+}
+
+/// Entries of a folder, sorted by name so imports run in a stable order
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    entries.sort();
+    Ok(entries)
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+/// An audio file next to `file` with the same name
+fn sibling_audio(file: &Path) -> Option<PathBuf> {
+    AUDIO_EXTENSIONS
+        .iter()
+        .map(|ext| file.with_extension(ext))
+        .find(|audio| audio.is_file())
+}
+
+fn modified(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64)
+}
+
+fn problem(source: &str, error: impl std::fmt::Display) -> ImportProblem {
+    ImportProblem {
+        source: source.to_string(),
+        error: error.to_string(),
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::Result;
+use crate::history::{HistoryEntry, HistorySource, HistoryStore};
+use crate::inbox::{InboxConfig, InboxServer, InboxStatus, PairedDeviceInfo, PairingInfo};
+use tauri::State;
+use tracing::info;
+
+#[tauri::command]
+pub async fn start_inbox_server(
+    config: InboxConfig,
+    inbox: State<'_, InboxServer>,
+    app_handle: tauri::AppHandle,
+) -> Result<InboxStatus> {
+    info!("Starting inbox server on port {}", config.port);
+    inbox.start(app_handle, config)
+}
+
+#[tauri::command]
+pub async fn stop_inbox_server(inbox: State<'_, InboxServer>) -> Result<()> {
+    info!("Stopping inbox server");
+    inbox.stop()
+}
+
+#[tauri::command]
+pub async fn get_inbox_status(inbox: State<'_, InboxServer>) -> Result<InboxStatus> {
+    inbox.status()
+}
+
+#[tauri::command]
+pub async fn start_inbox_pairing(inbox: State<'_, InboxServer>) -> Result<PairingInfo> {
+    info!("Starting inbox device pairing");
+    inbox.begin_pairing()
+}
+
+#[tauri::command]
+pub async fn list_paired_devices(
+    inbox: State<'_, InboxServer>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<PairedDeviceInfo>> {
+    inbox.paired_devices(&app_handle)
+}
+
+#[tauri::command]
+pub async fn revoke_paired_device(
+    device_id: String,
+    inbox: State<'_, InboxServer>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Revoking paired device {}", device_id);
+    inbox.revoke_device(&app_handle, &device_id)
+}
+
+#[tauri::command]
+pub async fn list_inbox_entries(history: State<'_, HistoryStore>) -> Result<Vec<HistoryEntry>> {
+    Ok(history.list_by_source(HistorySource::Inbox)?)
+}
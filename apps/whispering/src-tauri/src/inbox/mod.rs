@@ -0,0 +1,533 @@
+pub mod commands;
+pub(crate) mod pairing;
+
+pub use pairing::PairedDeviceInfo;
+
+use crate::discovery::{self, Advertisement};
+use crate::error::AppError;
+use crate::events::{
+    AppEvent, InboxDevicePaired, InboxReceived, InboxTranscribed, InboxTranscriptionFailed,
+};
+use crate::history::{HistoryEntry, HistorySource, HistoryStore, LatencyStage, StageTiming};
+use crate::punctuation::Punctuator;
+use crate::telemetry::{self, Feature};
+use crate::transcription::{transcribe_local, LocalEngine, ModelManager};
+use crate::transforms::{TransformContext, Transforms};
+use pairing::{load_or_create_identity, DeviceRegistry};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server, SslConfig};
+use tracing::{error, info, warn};
+
+pub use crate::error::Result;
+
+/// Default port for the inbox server
+pub const DEFAULT_INBOX_PORT: u16 = 47821;
+
+/// Largest upload accepted from a device (500 MB)
+const MAX_UPLOAD_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Largest JSON body accepted on the pairing endpoint
+const MAX_JSON_BYTES: u64 = 64 * 1024;
+
+/// Local transcription applied to received files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxTranscription {
+    pub engine: LocalEngine,
+    pub model_path: String,
+    pub language: Option<String>,
+}
+
+/// Inbox server settings - provided by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxConfig {
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Transcribe files as they arrive; when absent they wait in the inbox
+    pub transcription: Option<InboxTranscription>,
+    /// Advertise the server over mDNS
+    #[serde(default = "default_discoverable")]
+    pub discoverable: bool,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_INBOX_PORT
+}
+
+fn default_discoverable() -> bool {
+    true
+}
+
+/// Payload of the `inbox://transcription-failed` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxFailure {
+    pub entry_id: String,
+    pub message: String,
+}
+
+/// Current state of the inbox server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub address: Option<String>,
+    pub fingerprint: Option<String>,
+    /// Whether phones and companion tools can find it over mDNS
+    pub discoverable: bool,
+}
+
+/// Information encoded into the pairing QR code
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingInfo {
+    pub uri: String,
+    pub code: String,
+    pub expires_at: i64,
+    /// QR code of `uri` rendered as an SVG document
+    pub qr_svg: String,
+}
+
+struct RunningServer {
+    server: Arc<Server>,
+    handle: JoinHandle<()>,
+    port: u16,
+    fingerprint: String,
+    devices: Arc<Mutex<DeviceRegistry>>,
+    advertisement: Option<Advertisement>,
+}
+
+/// Local HTTPS endpoint that lets paired phones push audio for transcription
+pub struct InboxServer {
+    running: Mutex<Option<RunningServer>>,
+}
+
+impl InboxServer {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+
+    pub fn start(&self, app: AppHandle, config: InboxConfig) -> Result<InboxStatus> {
+        // Restart with the new configuration if already running
+        self.stop()?;
+
+        let inbox_dir = inbox_dir(&app)?;
+        let identity = load_or_create_identity(&inbox_dir)?;
+        let devices = Arc::new(Mutex::new(DeviceRegistry::load(&inbox_dir)?));
+
+        let server = Server::https(
+            ("0.0.0.0", config.port),
+            SslConfig {
+                certificate: identity.cert_pem,
+                private_key: identity.key_pem,
+            },
+        )
+        .map_err(|e| {
+            AppError::AddressInUse(format!("Failed to start inbox server on port {}: {}", config.port, e))
+        })?;
+        let server = Arc::new(server);
+        let port = config.port;
+
+        let advertisement = if config.discoverable {
+            let (instance, txt) = advertised_as(&identity.fingerprint);
+            Advertisement::start(&instance, port, &txt)
+                .map_err(|e| warn!("Inbox won't be discoverable: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        let handle = {
+            let server = server.clone();
+            let devices = devices.clone();
+            let inbox_dir = inbox_dir.clone();
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    // Uploads can take minutes on a slow network, so each
+                    // request gets a thread and pings aren't held up
+                    let app = app.clone();
+                    let config = config.clone();
+                    let devices = devices.clone();
+                    let inbox_dir = inbox_dir.clone();
+                    thread::spawn(move || {
+                        handle_request(&app, &config, &devices, &inbox_dir, request)
+                    });
+                }
+                info!("Inbox server stopped");
+            })
+        };
+
+        let mut running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock inbox server: {}", e)))?;
+        *running = Some(RunningServer {
+            server,
+            handle,
+            port,
+            fingerprint: identity.fingerprint,
+            devices,
+            advertisement,
+        });
+
+        info!("Inbox server listening on port {}", port);
+        Ok(status_of(running.as_ref()))
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock inbox server: {}", e)))?
+            .take();
+
+        if let Some(running) = running {
+            drop(running.advertisement);
+            running.server.unblock();
+            let _ = running.handle.join();
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<InboxStatus> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock inbox server: {}", e)))?;
+        Ok(status_of(running.as_ref()))
+    }
+
+    /// Open a pairing window and return the QR code for the UI
+    pub fn begin_pairing(&self) -> Result<PairingInfo> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock inbox server: {}", e)))?;
+        let running = running
+            .as_ref()
+            .ok_or_else(|| AppError::NotRunning("Inbox server is not running".to_string()))?;
+
+        let address = local_ip()
+            .map(|ip| ip.to_string())
+            .ok_or_else(|| AppError::Network("No local network address available".to_string()))?;
+
+        let mut devices = running
+            .devices
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock paired devices: {}", e)))?;
+        let pending = devices.begin_pairing();
+
+        let uri = format!(
+            "whispering://pair?host={}&port={}&code={}&fp={}",
+            address, running.port, pending.code, running.fingerprint
+        );
+        let qr_svg = qrcode::QrCode::new(uri.as_bytes())
+            .map_err(|e| AppError::Internal(format!("Failed to create QR code: {}", e)))?
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(256, 256)
+            .build();
+
+        Ok(PairingInfo {
+            uri,
+            code: pending.code.clone(),
+            expires_at: pending.expires_at,
+            qr_svg,
+        })
+    }
+
+    pub fn paired_devices(&self, app: &AppHandle) -> Result<Vec<PairedDeviceInfo>> {
+        self.with_registry(app, |registry| Ok(registry.devices()))
+    }
+
+    pub fn revoke_device(&self, app: &AppHandle, device_id: &str) -> Result<()> {
+        self.with_registry(app, |registry| registry.revoke(device_id))
+    }
+
+    /// Run against the live registry when serving, otherwise against the file on disk
+    fn with_registry<T>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut DeviceRegistry) -> Result<T>,
+    ) -> Result<T> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock inbox server: {}", e)))?;
+        match running.as_ref() {
+            Some(running) => {
+                let mut registry = running.devices.lock().map_err(|e| {
+                    AppError::Internal(format!("Failed to lock paired devices: {}", e))
+                })?;
+                f(&mut registry)
+            }
+            None => f(&mut DeviceRegistry::load(&inbox_dir(app)?)?),
+        }
+    }
+}
+
+/// Instance name and TXT record the inbox is advertised with over mDNS
+pub fn advertised_as(fingerprint: &str) -> (String, [(&'static str, &str); 3]) {
+    let instance = format!("{} inbox", discovery::host_label());
+    let txt = [("caps", "inbox"), ("fp", fingerprint), ("v", "1")];
+    (instance, txt)
+}
+
+fn status_of(running: Option<&RunningServer>) -> InboxStatus {
+    match running {
+        Some(running) => InboxStatus {
+            running: true,
+            port: Some(running.port),
+            address: local_ip().map(|ip| ip.to_string()),
+            fingerprint: Some(running.fingerprint.clone()),
+            discoverable: running.advertisement.is_some(),
+        },
+        None => InboxStatus {
+            running: false,
+            port: None,
+            address: None,
+            fingerprint: None,
+            discoverable: false,
+        },
+    }
+}
+
+fn inbox_dir(app: &AppHandle) -> Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve app data directory: {}", e)))?
+        .join("inbox");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Internal(format!("Failed to create inbox directory: {}", e)))?;
+    Ok(dir)
+}
+
+/// Address of the interface used for outbound traffic (no packets are sent)
+pub(crate) fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairRequest {
+    code: String,
+    device_name: String,
+}
+
+fn handle_request(
+    app: &AppHandle,
+    config: &InboxConfig,
+    devices: &Mutex<DeviceRegistry>,
+    inbox_dir: &Path,
+    mut request: Request,
+) {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+
+    let result = match (&method, url.as_str()) {
+        (Method::Get, "/ping") => authenticate(devices, &request)
+            .map(|_| (200, serde_json::json!({ "name": "Whispering" }))),
+        (Method::Post, "/pair") => {
+            let mut body = Vec::new();
+            request
+                .as_reader()
+                .take(MAX_JSON_BYTES)
+                .read_to_end(&mut body)
+                .map_err(|e| (400, format!("Failed to read request: {}", e)))
+                .and_then(|_| {
+                    serde_json::from_slice::<PairRequest>(&body)
+                        .map_err(|e| (400, format!("Invalid pairing request: {}", e)))
+                })
+                .and_then(|pair| {
+                    let mut registry = devices.lock().map_err(|e| (500, e.to_string()))?;
+                    registry
+                        .complete_pairing(&pair.code, &pair.device_name)
+                        .map_err(|e| (403, e.to_string()))
+                })
+                .map(|(device, token)| {
+                    let _ = InboxDevicePaired::emit(app, &device);
+                    (200, serde_json::json!({ "deviceId": device.id, "token": token }))
+                })
+        }
+        (Method::Post, "/upload") => authenticate(devices, &request)
+            .and_then(|device| receive_upload(app, config, inbox_dir, &device, &mut request)),
+        _ => Err((404, "Not found".to_string())),
+    };
+
+    let (status, body) = match result {
+        Ok((status, body)) => (status, body),
+        Err((status, message)) => {
+            warn!("Inbox request {} {} failed: {}", method, url, message);
+            (status, serde_json::json!({ "error": message }))
+        }
+    };
+
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+    if let Err(e) = request.respond(response) {
+        error!("Failed to respond to inbox request: {}", e);
+    }
+}
+
+fn header_value(request: &Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str().to_string())
+}
+
+fn authenticate(
+    devices: &Mutex<DeviceRegistry>,
+    request: &Request,
+) -> std::result::Result<PairedDeviceInfo, (u16, String)> {
+    let token = header_value(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer ").map(str::to_string))
+        .ok_or_else(|| (401, "Missing bearer token".to_string()))?;
+
+    devices
+        .lock()
+        .map_err(|e| (500, e.to_string()))?
+        .authenticate(&token)
+        .ok_or_else(|| (401, "Unknown device".to_string()))
+}
+
+fn receive_upload(
+    app: &AppHandle,
+    config: &InboxConfig,
+    inbox_dir: &Path,
+    device: &PairedDeviceInfo,
+    request: &mut Request,
+) -> std::result::Result<(u16, serde_json::Value), (u16, String)> {
+    // Keep the original extension so FFmpeg can sniff compressed formats
+    let extension = header_value(request, "X-Filename")
+        .and_then(|name| {
+            Path::new(&name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_lowercase())
+        })
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_else(|| "m4a".to_string());
+
+    // Stream to a partial file so a large upload never sits in memory and
+    // an interrupted one never looks like a finished recording
+    let mut entry = HistoryEntry::new(HistorySource::Inbox, String::new());
+    let audio_path = inbox_dir.join(format!("{}.{}", entry.id, extension));
+    let partial_path = inbox_dir.join(format!("{}.{}.part", entry.id, extension));
+    let received = store_upload(request, &partial_path).and_then(|len| {
+        std::fs::rename(&partial_path, &audio_path)
+            .map(|_| len)
+            .map_err(|e| (500, format!("Failed to store upload: {}", e)))
+    });
+    let received = match received {
+        Ok(len) => len,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+
+    entry.audio_path = Some(audio_path.to_string_lossy().to_string());
+    entry.provider = config
+        .transcription
+        .as_ref()
+        .map(|t| t.engine.as_str().to_string());
+    let history = app.state::<HistoryStore>();
+    history.insert(&entry).map_err(|e| (500, e.to_string()))?;
+    telemetry::record(app, Feature::Inbox);
+
+    info!("Received {} bytes from '{}'", received, device.name);
+    let _ = InboxReceived::emit(app, &entry);
+
+    if let Some(transcription) = config.transcription.clone() {
+        let app = app.clone();
+        let entry_id = entry.id.clone();
+        thread::spawn(move || {
+            if let Err(e) = transcribe_received(&app, &transcription, &entry_id, &audio_path) {
+                error!("Failed to transcribe inbox entry {}: {}", entry_id, e);
+                let _ = InboxTranscriptionFailed::emit(
+                    &app,
+                    &InboxFailure {
+                        entry_id,
+                        message: e.to_string(),
+                    },
+                );
+            }
+        });
+    }
+
+    Ok((201, serde_json::json!({ "id": entry.id })))
+}
+
+/// Copy the request body to `path`, returning its length
+fn store_upload(request: &mut Request, path: &Path) -> std::result::Result<u64, (u16, String)> {
+    let mut body = request.as_reader().take(MAX_UPLOAD_BYTES + 1);
+    let mut file =
+        std::fs::File::create(path).map_err(|e| (500, format!("Failed to store upload: {}", e)))?;
+    let len = io::copy(&mut body, &mut file)
+        .map_err(|e| (400, format!("Failed to read upload: {}", e)))?;
+
+    if len == 0 {
+        return Err((400, "Empty upload".to_string()));
+    }
+    if len > MAX_UPLOAD_BYTES {
+        return Err((413, "Upload too large".to_string()));
+    }
+    file.sync_all()
+        .map_err(|e| (500, format!("Failed to store upload: {}", e)))?;
+    Ok(len)
+}
+
+fn transcribe_received(
+    app: &AppHandle,
+    transcription: &InboxTranscription,
+    entry_id: &str,
+    audio_path: &Path,
+) -> Result<()> {
+    let audio_data = std::fs::read(audio_path)?;
+    let model_manager = app.state::<ModelManager>();
+    let started = Instant::now();
+    let mut transcript = transcribe_local(
+        &model_manager,
+        transcription.engine,
+        audio_data,
+        &transcription.model_path,
+        transcription.language.clone(),
+    )?;
+
+    let timing = StageTiming::since(LatencyStage::Provider, started);
+    transcript.text = app.state::<Punctuator>().apply(transcript.text);
+    transcript.text = app.state::<Transforms>().apply(
+        &transcript.text,
+        &TransformContext {
+            language: transcription.language.clone(),
+            profile: None,
+        },
+    );
+
+    let history = app.state::<HistoryStore>();
+    let (language, confidence) = transcript.language_for_history(transcription.language.as_deref());
+    history.update_transcript(entry_id, &transcript.text, transcript.duration_seconds)?;
+    if let Some(language) = &language {
+        history.set_language(entry_id, language, confidence)?;
+    }
+    history.record_latency(entry_id, &[timing])?;
+    let entry = history.get(entry_id)?;
+    let _ = InboxTranscribed::emit(app, &entry);
+    Ok(())
+}
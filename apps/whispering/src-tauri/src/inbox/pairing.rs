@@ -0,0 +1,267 @@
+use crate::error::AppError;
+use crate::history::now_millis;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use tracing::info;
+
+use super::Result;
+
+/// How long a pairing code shown in the UI stays valid
+const PAIRING_CODE_TTL_MS: i64 = 5 * 60 * 1000;
+
+/// Wrong guesses allowed before a pairing code is invalidated
+const MAX_PAIRING_ATTEMPTS: u32 = 5;
+
+const CERT_FILE: &str = "cert.pem";
+const KEY_FILE: &str = "key.pem";
+const FINGERPRINT_FILE: &str = "cert.sha256";
+const DEVICES_FILE: &str = "paired_devices.json";
+
+/// Self-signed TLS identity for the inbox or remote control server
+pub struct TlsIdentity {
+    pub cert_pem: Vec<u8>,
+    pub key_pem: Vec<u8>,
+    /// SHA-256 fingerprint of the certificate, pinned by the phone during pairing
+    pub fingerprint: String,
+}
+
+/// Load the TLS identity from disk, generating one on first use
+///
+/// The identity is persisted so the fingerprint pinned by already paired
+/// devices stays valid across restarts.
+pub fn load_or_create_identity(dir: &Path) -> Result<TlsIdentity> {
+    let cert_path = dir.join(CERT_FILE);
+    let key_path = dir.join(KEY_FILE);
+    let fingerprint_path = dir.join(FINGERPRINT_FILE);
+
+    if cert_path.exists() && key_path.exists() && fingerprint_path.exists() {
+        let read = |path: &Path| {
+            std::fs::read(path)
+                .map_err(|e| AppError::Internal(format!("Failed to read {:?}: {}", path, e)))
+        };
+        // Keys written before they were created private
+        #[cfg(unix)]
+        let _ = std::fs::set_permissions(
+            &key_path,
+            std::os::unix::fs::PermissionsExt::from_mode(0o600),
+        );
+        return Ok(TlsIdentity {
+            cert_pem: read(&cert_path)?,
+            key_pem: read(&key_path)?,
+            fingerprint: String::from_utf8_lossy(&read(&fingerprint_path)?).trim().to_string(),
+        });
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| AppError::Internal(format!("Failed to create {:?}: {}", dir, e)))?;
+
+    let rcgen::CertifiedKey { cert, key_pair } = rcgen::generate_simple_self_signed(vec![
+        "whispering.local".to_string(),
+        "localhost".to_string(),
+    ])
+    .map_err(|e| AppError::Internal(format!("Failed to generate TLS certificate: {}", e)))?;
+
+    let identity = TlsIdentity {
+        cert_pem: cert.pem().into_bytes(),
+        key_pem: key_pair.serialize_pem().into_bytes(),
+        fingerprint: fingerprint(cert.der()),
+    };
+
+    let write = |path: &Path, data: &[u8]| {
+        std::fs::write(path, data)
+            .map_err(|e| AppError::Internal(format!("Failed to write {:?}: {}", path, e)))
+    };
+    write(&cert_path, &identity.cert_pem)?;
+    write_private(&key_path, &identity.key_pem)
+        .map_err(|e| AppError::Internal(format!("Failed to write {:?}: {}", key_path, e)))?;
+    write(&fingerprint_path, identity.fingerprint.as_bytes())?;
+
+    info!(
+        "Generated TLS certificate {} in {:?}",
+        identity.fingerprint, dir
+    );
+    Ok(identity)
+}
+
+/// Write a file holding secrets (the private key, device tokens) so only the
+/// user can read it. A file an older version left readable is narrowed
+/// before anything new goes in.
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(data)
+}
+
+/// Colon separated uppercase SHA-256, the format most TLS pinning APIs expect
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn sha256_hex(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A phone or Shortcut allowed to push audio to the inbox, or to control
+/// recording through the remote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedDevice {
+    pub id: String,
+    pub name: String,
+    pub paired_at: i64,
+    /// Only the hash of the bearer token is kept on disk
+    token_hash: String,
+}
+
+/// Device info returned to the frontend (without the token hash)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedDeviceInfo {
+    pub id: String,
+    pub name: String,
+    pub paired_at: i64,
+}
+
+impl From<&PairedDevice> for PairedDeviceInfo {
+    fn from(device: &PairedDevice) -> Self {
+        Self {
+            id: device.id.clone(),
+            name: device.name.clone(),
+            paired_at: device.paired_at,
+        }
+    }
+}
+
+/// Pairing code currently displayed as a QR code
+pub struct PendingPairing {
+    pub code: String,
+    pub expires_at: i64,
+    attempts: u32,
+}
+
+impl PendingPairing {
+    pub fn new() -> Self {
+        Self {
+            code: format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000),
+            expires_at: now_millis() + PAIRING_CODE_TTL_MS,
+            attempts: 0,
+        }
+    }
+}
+
+/// Paired devices plus the pending pairing code, persisted in the server's directory
+pub struct DeviceRegistry {
+    devices: Vec<PairedDevice>,
+    pending: Option<PendingPairing>,
+    file_path: std::path::PathBuf,
+}
+
+impl DeviceRegistry {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let file_path = dir.join(DEVICES_FILE);
+        let devices = match std::fs::read(&file_path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| {
+                AppError::Internal(format!("Failed to parse paired devices: {}", e))
+            })?,
+            Err(_) => Vec::new(),
+        };
+        Ok(Self {
+            devices,
+            pending: None,
+            file_path,
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        let data = serde_json::to_vec_pretty(&self.devices).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize paired devices: {}", e))
+        })?;
+        write_private(&self.file_path, &data)
+            .map_err(|e| AppError::Internal(format!("Failed to save paired devices: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn devices(&self) -> Vec<PairedDeviceInfo> {
+        self.devices.iter().map(PairedDeviceInfo::from).collect()
+    }
+
+    /// Start a new pairing window, replacing any previous code
+    pub fn begin_pairing(&mut self) -> &PendingPairing {
+        self.pending.insert(PendingPairing::new())
+    }
+
+    /// Exchange a pairing code for a bearer token
+    ///
+    /// Codes are single use and are dropped after too many wrong guesses.
+    pub fn complete_pairing(&mut self, code: &str, device_name: &str) -> Result<(PairedDeviceInfo, String)> {
+        let pending = self
+            .pending
+            .as_mut()
+            .ok_or_else(|| AppError::PermissionDenied("No pairing in progress".to_string()))?;
+
+        if now_millis() > pending.expires_at {
+            self.pending = None;
+            return Err(AppError::PermissionDenied("Pairing code expired".to_string()));
+        }
+
+        if pending.code != code {
+            pending.attempts += 1;
+            if pending.attempts >= MAX_PAIRING_ATTEMPTS {
+                self.pending = None;
+            }
+            return Err(AppError::PermissionDenied("Invalid pairing code".to_string()));
+        }
+
+        self.pending = None;
+
+        let token = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let device = PairedDevice {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: device_name.to_string(),
+            paired_at: now_millis(),
+            token_hash: sha256_hex(&token),
+        };
+        let info = PairedDeviceInfo::from(&device);
+        self.devices.push(device);
+        self.save()?;
+
+        info!("Paired device '{}'", device_name);
+        Ok((info, token))
+    }
+
+    /// Resolve a bearer token to the device it was issued to
+    pub fn authenticate(&self, token: &str) -> Option<PairedDeviceInfo> {
+        let hash = sha256_hex(token);
+        self.devices
+            .iter()
+            .find(|device| device.token_hash == hash)
+            .map(PairedDeviceInfo::from)
+    }
+
+    pub fn revoke(&mut self, device_id: &str) -> Result<()> {
+        let before = self.devices.len();
+        self.devices.retain(|device| device.id != device_id);
+        if self.devices.len() == before {
+            return Err(AppError::InvalidInput(format!("Paired device not found: {}", device_id)));
+        }
+        self.save()
+    }
+}
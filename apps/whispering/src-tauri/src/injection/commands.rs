@@ -0,0 +1,88 @@
+use super::{foreground_elevated, InjectionConfig, InjectionReport, Injector};
+use crate::accessibility;
+use crate::app_state::{AppState, StateEvent};
+use crate::error::Result;
+use crate::events::{AppEvent, InjectionResult};
+use crate::focus::focused_app;
+use crate::history::HistoryStore;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use tracing::{debug, warn};
+
+/// Put `text` into the focused app, formatted for it, with the method
+/// configured for that app, and check it landed; see
+/// `Injector::inject_verified`.
+/// With `entry_id`, the outcome is stored with that history entry.
+#[tauri::command]
+pub async fn write_text(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    injector: State<'_, Injector>,
+    history: State<'_, HistoryStore>,
+    text: String,
+    entry_id: Option<String>,
+) -> Result<InjectionReport> {
+    app_state.record(StateEvent::InjectionStarted);
+    let result = injector
+        .inject_verified(&app, injector.prepare(&text))
+        .await;
+    app_state.record(match &result {
+        Ok(_) => StateEvent::Completed,
+        Err(e) => StateEvent::Failed {
+            message: e.to_string(),
+        },
+    });
+    let report = result?;
+    if let Some(entry_id) = entry_id {
+        if let Err(e) = history.record_injection(
+            &entry_id,
+            report.app.as_deref(),
+            report.method.as_str(),
+            report.outcome,
+        ) {
+            warn!("Failed to record injection for {}: {}", entry_id, e);
+        }
+    }
+    let _ = InjectionResult::emit(&app, &report);
+    accessibility::announce_injection(&app, &report);
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_injection_config(injector: State<'_, Injector>) -> Result<InjectionConfig> {
+    Ok(injector.config())
+}
+
+#[tauri::command]
+pub async fn set_injection_config(
+    config: InjectionConfig,
+    injector: State<'_, Injector>,
+) -> Result<()> {
+    debug!("Saving injection settings for {} apps", config.apps.len());
+    injector.set_config(config)
+}
+
+/// Name of the app in the foreground, for setting up per-app formatting
+#[tauri::command]
+pub async fn get_focused_app() -> Result<Option<String>> {
+    Ok(focused_app())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElevationStatus {
+    /// The foreground window runs as administrator, so text can't be typed
+    /// or pasted into it without the helper
+    pub foreground_elevated: bool,
+    pub helper_running: bool,
+}
+
+/// Whether the foreground window is out of reach of simulated input, and
+/// whether the elevated helper is up to reach it anyway (Windows only)
+#[tauri::command]
+pub async fn get_elevation_status(injector: State<'_, Injector>) -> Result<ElevationStatus> {
+    Ok(ElevationStatus {
+        foreground_elevated: foreground_elevated(),
+        helper_running: injector.helper_running(),
+    })
+}
@@ -0,0 +1,105 @@
+//! Reading the text around the caret in the focused app.
+//!
+//! Only macOS exposes this, through the Accessibility API the app already
+//! needs for pasting. Elsewhere, and in apps that don't report their text
+//! (many terminals, some Electron apps), this returns `None`.
+
+/// How much text before the caret to read for formatting
+const BEFORE_UNITS: isize = 8;
+
+/// Text next to the caret, where the transcript will land
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaretContext {
+    /// A few characters before the caret (or selection), empty at the start
+    /// of the field
+    pub before: String,
+    /// The character after the caret (or selection), `None` at the end
+    pub after: Option<char>,
+}
+
+pub fn caret_context() -> Option<CaretContext> {
+    read_caret(BEFORE_UNITS)
+}
+
+/// Up to `units` UTF-16 code units before the caret, to check what an
+/// injection left there
+pub fn text_before_caret(units: usize) -> Option<String> {
+    read_caret(units as isize).map(|context| context.before)
+}
+
+#[cfg(target_os = "macos")]
+fn read_caret(before_units: isize) -> Option<CaretContext> {
+    use crate::focus::copy_ax_attribute;
+    use accessibility_sys::{
+        kAXFocusedUIElementAttribute, kAXSelectedTextRangeAttribute, kAXValueAttribute,
+        kAXValueTypeCFRange, AXUIElementCreateSystemWide, AXUIElementRef, AXValueGetValue,
+        AXValueRef,
+    };
+    use core_foundation_sys::base::{CFGetTypeID, CFIndex, CFRange, CFRelease};
+    use core_foundation_sys::string::{
+        CFStringGetCharacters, CFStringGetLength, CFStringGetTypeID, CFStringRef,
+    };
+
+    unsafe fn utf16(string: CFStringRef, start: CFIndex, end: CFIndex) -> Vec<u16> {
+        let start = start.max(0);
+        let end = end.min(CFStringGetLength(string));
+        if end <= start {
+            return Vec::new();
+        }
+        let mut buffer = vec![0u16; (end - start) as usize];
+        CFStringGetCharacters(
+            string,
+            CFRange::init(start, end - start),
+            buffer.as_mut_ptr(),
+        );
+        buffer
+    }
+
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        let focused = copy_ax_attribute(system, kAXFocusedUIElementAttribute);
+        CFRelease(system as _);
+        let focused = focused? as AXUIElementRef;
+
+        let selection = copy_ax_attribute(focused, kAXSelectedTextRangeAttribute);
+        let value = copy_ax_attribute(focused, kAXValueAttribute);
+        CFRelease(focused as _);
+
+        let mut range = CFRange::init(0, 0);
+        let has_range = selection.is_some_and(|selection| {
+            AXValueGetValue(
+                selection as AXValueRef,
+                kAXValueTypeCFRange,
+                &mut range as *mut CFRange as *mut _,
+            )
+        });
+        let context = match value {
+            Some(value) if has_range && CFGetTypeID(value) == CFStringGetTypeID() => {
+                let text = value as CFStringRef;
+                let before = utf16(text, range.location - before_units, range.location);
+                let end = range.location + range.length;
+                let after = utf16(text, end, end + 2);
+                Some(CaretContext {
+                    // Cutting into the middle of a surrogate pair leaves a
+                    // replacement character at the start, which is harmless
+                    before: String::from_utf16_lossy(&before),
+                    after: char::decode_utf16(after).next().and_then(Result::ok),
+                })
+            }
+            _ => None,
+        };
+
+        if let Some(selection) = selection {
+            CFRelease(selection);
+        }
+        if let Some(value) = value {
+            CFRelease(value);
+        }
+        context
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_caret(_before_units: isize) -> Option<CaretContext> {
+    None
+}
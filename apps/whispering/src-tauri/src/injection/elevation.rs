@@ -0,0 +1,98 @@
+//! Detecting elevated target windows on Windows.
+//!
+//! User Interface Privilege Isolation (UIPI) silently drops simulated input
+//! sent to a window whose process runs at a higher integrity level than
+//! ours, such as a terminal started with "Run as administrator". Neither
+//! typing nor Ctrl+V gets through, and nothing reports an error, so the
+//! target has to be checked before injecting.
+
+/// Whether the foreground window belongs to a process at a higher integrity
+/// level than this one
+#[cfg(target_os = "windows")]
+pub fn foreground_elevated() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ACCESS_DENIED};
+    use windows_sys::Win32::System::SystemServices::SECURITY_MANDATORY_HIGH_RID;
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let Some(own) = integrity_level(GetCurrentProcess()) else {
+            return false;
+        };
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return false;
+        }
+        let mut pid = 0;
+        GetWindowThreadProcessId(window, &mut pid);
+        if pid == 0 {
+            return false;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return false;
+        }
+        let target = integrity_level(process);
+        let denied = GetLastError() == ERROR_ACCESS_DENIED;
+        CloseHandle(process);
+        match target {
+            Some(target) => target > own,
+            // The tokens of elevated processes can't be read from a
+            // non-elevated one, which is itself the tell
+            None => denied && own < SECURITY_MANDATORY_HIGH_RID as u32,
+        }
+    }
+}
+
+/// The mandatory integrity level (RID) of `process`'s token
+#[cfg(target_os = "windows")]
+unsafe fn integrity_level(process: windows_sys::Win32::Foundation::HANDLE) -> Option<u32> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::Security::{
+        GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation, TokenIntegrityLevel,
+        TOKEN_MANDATORY_LABEL, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::OpenProcessToken;
+
+    let mut token = std::ptr::null_mut();
+    if OpenProcessToken(process, TOKEN_QUERY, &mut token) == 0 {
+        return None;
+    }
+    let mut size = 0;
+    GetTokenInformation(
+        token,
+        TokenIntegrityLevel,
+        std::ptr::null_mut(),
+        0,
+        &mut size,
+    );
+    // u64s keep the label's pointers aligned
+    let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+    let read = GetTokenInformation(
+        token,
+        TokenIntegrityLevel,
+        buffer.as_mut_ptr() as _,
+        size,
+        &mut size,
+    );
+    CloseHandle(token);
+    if read == 0 {
+        return None;
+    }
+    let label = &*(buffer.as_ptr() as *const TOKEN_MANDATORY_LABEL);
+    let count = *GetSidSubAuthorityCount(label.Label.Sid);
+    if count == 0 {
+        return None;
+    }
+    Some(*GetSidSubAuthority(label.Label.Sid, count as u32 - 1))
+}
+
+/// Other platforms don't block input by privilege this way
+#[cfg(not(target_os = "windows"))]
+pub fn foreground_elevated() -> bool {
+    false
+}
@@ -0,0 +1,84 @@
+//! Fitting a transcript into the text around the caret.
+
+use super::context::CaretContext;
+use serde::{Deserialize, Serialize};
+
+/// Punctuation that ends a sentence, so the next word is capitalized
+const SENTENCE_END: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+
+/// Characters that hug the text after them, so no space is added
+const OPENERS: &[char] = &[
+    '(', '[', '{', '"', '\'', '“', '‘', '«', '¿', '¡', '/', '@', '#',
+];
+
+/// Characters that hug the text before them
+const CLOSERS: &[char] = &[
+    '.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\'', '”', '’', '»', '…',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatOptions {
+    /// Capitalize the first letter when the transcript starts a sentence
+    pub auto_capitalize: bool,
+    /// Add a space before or after the transcript when it would otherwise
+    /// run into the neighbouring text
+    pub smart_spacing: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            auto_capitalize: true,
+            smart_spacing: true,
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Adjust `text` for where it's going. Without `context` (the app doesn't
+/// expose its text) the transcript is left as it is, since guessing wrong
+/// is worse than doing nothing.
+pub fn format(text: &str, options: FormatOptions, context: Option<&CaretContext>) -> String {
+    let Some(context) = context else {
+        return text.to_string();
+    };
+    let mut text = text.trim().to_string();
+    if text.is_empty() {
+        return text;
+    }
+
+    let previous = context.before.trim_end().chars().last();
+    if options.auto_capitalize && previous.is_none_or(|c| SENTENCE_END.contains(&c)) {
+        text = capitalize(&text);
+    }
+
+    if options.smart_spacing {
+        let first = text.chars().next();
+        let last = text.chars().last();
+        let needs_leading = context
+            .before
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_whitespace() && !OPENERS.contains(&c))
+            && first.is_some_and(|c| !CLOSERS.contains(&c));
+        let needs_trailing = context
+            .after
+            .is_some_and(|c| !c.is_whitespace() && !CLOSERS.contains(&c))
+            && last.is_some_and(|c| !OPENERS.contains(&c));
+        if needs_leading {
+            text.insert(0, ' ');
+        }
+        if needs_trailing {
+            text.push(' ');
+        }
+    }
+    text
+}
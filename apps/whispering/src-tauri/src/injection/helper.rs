@@ -0,0 +1,303 @@
+//! Opt-in elevated helper for typing into elevated windows on Windows.
+//!
+//! UIPI blocks our simulated input to windows running as administrator (see
+//! `elevation`). Users who turn the helper on get a second copy of the
+//! executable, started elevated with `--injection-helper` behind one UAC
+//! prompt, that types on our behalf. It connects back to a loopback port
+//! with a per-launch token, types each transcript it's sent, and exits when
+//! the connection closes, so it never outlives the app.
+
+use crate::error::{AppError, Result};
+use enigo::{Enigo, Keyboard, Settings};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+/// First argument the helper process is launched with, followed by the port
+/// and token to connect back with
+pub const HELPER_ARG: &str = "--injection-helper";
+
+/// How long to wait for the user to answer the UAC prompt
+#[cfg(target_os = "windows")]
+const LAUNCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long a connecting helper has to say hello
+#[cfg(target_os = "windows")]
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the helper to type a transcript: a base allowance
+/// plus a generous time per character, since it types one key at a time
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+const REPLY_TIMEOUT_PER_CHAR: Duration = Duration::from_millis(20);
+
+/// Sent by the helper when it connects
+#[derive(Debug, Serialize, Deserialize)]
+struct HelperHello {
+    token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TypeRequest {
+    text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum HelperReply {
+    Ok,
+    Error { message: String },
+}
+
+/// Whether the process was launched as the elevated injection helper
+pub fn is_helper_launch(args: &[String]) -> bool {
+    args.get(1).is_some_and(|arg| arg == HELPER_ARG)
+}
+
+/// Connect back to the app and type what it sends until it disconnects,
+/// returning the exit code
+pub fn run_helper(args: &[String]) -> i32 {
+    let (Some(port), Some(token)) = (
+        args.get(2).and_then(|port| port.parse::<u16>().ok()),
+        args.get(3),
+    ) else {
+        return 2;
+    };
+    let Ok(mut writer) = TcpStream::connect(("127.0.0.1", port)) else {
+        return 1;
+    };
+    let Ok(reader) = writer.try_clone() else {
+        return 1;
+    };
+    if write_line(
+        &mut writer,
+        &HelperHello {
+            token: token.clone(),
+        },
+    )
+    .is_err()
+    {
+        return 1;
+    }
+    let Ok(mut enigo) = Enigo::new(&Settings::default()) else {
+        return 1;
+    };
+
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else { return 0 };
+        let reply = match serde_json::from_str::<TypeRequest>(&line) {
+            Ok(request) => match enigo.text(&request.text) {
+                Ok(()) => HelperReply::Ok,
+                Err(e) => HelperReply::Error {
+                    message: format!("Failed to type text: {}", e),
+                },
+            },
+            Err(e) => HelperReply::Error {
+                message: format!("Invalid request: {}", e),
+            },
+        };
+        if write_line(&mut writer, &reply).is_err() {
+            return 1;
+        }
+    }
+    0
+}
+
+fn write_line<T: Serialize>(stream: &mut TcpStream, message: &T) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stream.write_all(&line)
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+/// Handle to the helper process, started on first use
+#[derive(Default)]
+pub struct ElevatedHelper {
+    connection: Mutex<Option<Connection>>,
+}
+
+impl ElevatedHelper {
+    pub fn is_running(&self) -> bool {
+        self.connection
+            .lock()
+            .map(|connection| connection.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Type `text` through the helper, starting it first if needed. Blocks
+    /// while the UAC prompt is up.
+    pub fn type_text(&self, text: &str) -> Result<()> {
+        let mut connection = self
+            .connection
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock injection helper: {}", e)))?;
+        if connection.is_none() {
+            *connection = Some(launch()?);
+        }
+        let result = connection
+            .as_mut()
+            .map(|connection| send(connection, text))
+            .unwrap_or(Ok(()));
+        if result.is_err() {
+            // Start afresh next time rather than reuse a broken connection
+            *connection = None;
+        }
+        result
+    }
+
+    /// Close the connection, which ends the helper process
+    pub fn stop(&self) {
+        if let Ok(mut connection) = self.connection.lock() {
+            if connection.take().is_some() {
+                info!("Stopped the elevated injection helper");
+            }
+        }
+    }
+}
+
+fn send(connection: &mut Connection, text: &str) -> Result<()> {
+    // A helper that stopped answering is dropped like a closed one
+    let chars = u32::try_from(text.chars().count()).unwrap_or(u32::MAX);
+    let timeout = REPLY_TIMEOUT.saturating_add(REPLY_TIMEOUT_PER_CHAR.saturating_mul(chars));
+    connection
+        .reader
+        .get_ref()
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| {
+            AppError::Internal(format!(
+                "Failed to configure injection helper stream: {}",
+                e
+            ))
+        })?;
+    write_line(
+        &mut connection.writer,
+        &TypeRequest {
+            text: text.to_string(),
+        },
+    )
+    .map_err(|e| AppError::Internal(format!("Failed to reach injection helper: {}", e)))?;
+    let mut line = String::new();
+    connection
+        .reader
+        .read_line(&mut line)
+        .map_err(|e| AppError::Internal(format!("Failed to reach injection helper: {}", e)))?;
+    match serde_json::from_str(&line) {
+        Ok(HelperReply::Ok) => Ok(()),
+        Ok(HelperReply::Error { message }) => Err(AppError::Internal(message)),
+        Err(_) => Err(AppError::Internal(
+            "Injection helper closed the connection".to_string(),
+        )),
+    }
+}
+
+/// Start the helper elevated and wait for it to connect back
+#[cfg(target_os = "windows")]
+fn launch() -> Result<Connection> {
+    use std::net::TcpListener;
+    use std::os::windows::ffi::OsStrExt;
+    use std::time::Instant;
+    use tracing::warn;
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+    fn wide(value: &std::ffi::OsStr) -> Vec<u16> {
+        value.encode_wide().chain(std::iter::once(0)).collect()
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| AppError::Internal(format!("Failed to open injection helper port: {}", e)))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| AppError::Internal(format!("Failed to read injection helper port: {}", e)))?
+        .port();
+    let token = uuid::Uuid::new_v4().to_string();
+    let exe = std::env::current_exe().map_err(|e| {
+        AppError::Internal(format!("Failed to locate the Whispering executable: {}", e))
+    })?;
+    let parameters = format!("{} {} {}", HELPER_ARG, port, token);
+
+    info!("Starting the elevated injection helper");
+    let launched = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            wide("runas".as_ref()).as_ptr(),
+            wide(exe.as_os_str()).as_ptr(),
+            wide(parameters.as_ref()).as_ptr(),
+            std::ptr::null(),
+            SW_HIDE,
+        )
+    };
+    // Values of 32 and below are errors, including the user declining
+    if launched as isize <= 32 {
+        return Err(AppError::PermissionDenied(
+            "The elevated injection helper wasn't allowed to start".to_string(),
+        ));
+    }
+
+    listener.set_nonblocking(true).map_err(|e| {
+        AppError::Internal(format!("Failed to configure injection helper port: {}", e))
+    })?;
+    let deadline = Instant::now() + LAUNCH_TIMEOUT;
+    while Instant::now() < deadline {
+        match listener.accept() {
+            Ok((stream, _)) => match accept(stream, &token) {
+                Ok(connection) => return Ok(connection),
+                Err(e) => warn!("Rejected injection helper connection: {}", e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            Err(e) => {
+                return Err(AppError::Internal(format!(
+                    "Failed to accept injection helper: {}",
+                    e
+                )))
+            }
+        }
+    }
+    Err(AppError::Internal(
+        "The elevated injection helper didn't start in time".to_string(),
+    ))
+}
+
+/// Check a connecting helper's token
+#[cfg(target_os = "windows")]
+fn accept(stream: TcpStream, token: &str) -> Result<Connection> {
+    stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_read_timeout(Some(HELLO_TIMEOUT)))
+        .map_err(|e| {
+            AppError::Internal(format!(
+                "Failed to configure injection helper stream: {}",
+                e
+            ))
+        })?;
+    let writer = stream.try_clone().map_err(|e| {
+        AppError::Internal(format!("Failed to clone injection helper stream: {}", e))
+    })?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| AppError::Internal(format!("Failed to read injection helper hello: {}", e)))?;
+    match serde_json::from_str::<HelperHello>(&line) {
+        Ok(hello) if hello.token == token => Ok(Connection { reader, writer }),
+        _ => Err(AppError::Internal(
+            "Wrong injection helper token".to_string(),
+        )),
+    }
+}
+
+/// Only Windows isolates input by privilege, so there's nothing to start
+#[cfg(not(target_os = "windows"))]
+fn launch() -> Result<Connection> {
+    Err(AppError::Unsupported(
+        "The elevated injection helper is only needed on Windows".to_string(),
+    ))
+}
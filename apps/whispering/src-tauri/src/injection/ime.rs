@@ -0,0 +1,120 @@
+//! Keeping input methods (IMEs) from mangling injected text.
+//!
+//! Simulated keystrokes go through whatever input method is active, which
+//! treats them as composition input: with pinyin on, typed Latin letters
+//! turn into candidates, and typed CJK characters can be dropped or
+//! recomposed. Text in those scripts, or any text while an IME is on, goes
+//! in through the clipboard or the platform's text insertion API instead,
+//! neither of which passes through composition.
+
+/// Whether typing `text` key by key could be altered by an input method
+pub fn needs_ime_safe_path(text: &str) -> bool {
+    uses_complex_script(text) || ime_active()
+}
+
+/// Scripts that are normally entered through an IME or rely on shaping that
+/// per-character key events break
+fn uses_complex_script(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(
+            c as u32,
+            // Arabic and Syriac
+            0x0600..=0x08FF
+            // Indic scripts, Thai, Lao, Tibetan, Myanmar
+            | 0x0900..=0x109F
+            // Hangul Jamo
+            | 0x1100..=0x11FF
+            // Khmer
+            | 0x1780..=0x17FF
+            // CJK radicals, punctuation, kana, Bopomofo, Hangul
+            // compatibility Jamo, CJK ideographs
+            | 0x2E80..=0x9FFF
+            // Hangul syllables
+            | 0xAC00..=0xD7AF
+            // CJK compatibility ideographs
+            | 0xF900..=0xFAFF
+            // Half- and full-width forms
+            | 0xFF00..=0xFFEF
+            // CJK extensions
+            | 0x20000..=0x3FFFF
+        )
+    })
+}
+
+/// Whether the foreground window has an IME turned on
+#[cfg(target_os = "windows")]
+fn ime_active() -> bool {
+    use windows_sys::Win32::UI::Input::Ime::ImmGetDefaultIMEWnd;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, SendMessageTimeoutW, SMTO_ABORTIFHUNG,
+    };
+
+    const WM_IME_CONTROL: u32 = 0x0283;
+    const IMC_GETOPENSTATUS: usize = 0x0005;
+
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return false;
+        }
+        // The IME window belongs to the other process, so ask it rather than
+        // its input context, which only works for our own windows
+        let ime_window = ImmGetDefaultIMEWnd(window);
+        if ime_window.is_null() {
+            return false;
+        }
+        let mut open = 0usize;
+        let sent = SendMessageTimeoutW(
+            ime_window,
+            WM_IME_CONTROL,
+            IMC_GETOPENSTATUS,
+            0,
+            SMTO_ABORTIFHUNG,
+            50,
+            &mut open,
+        );
+        sent != 0 && open != 0
+    }
+}
+
+/// Other platforms don't expose another app's IME state; script detection
+/// covers the common cases
+#[cfg(not(target_os = "windows"))]
+fn ime_active() -> bool {
+    false
+}
+
+/// Insert `text` at the caret of the focused element through the
+/// Accessibility API, replacing any selection. Returns false if the element
+/// doesn't accept it.
+#[cfg(target_os = "macos")]
+pub fn insert_native(text: &str) -> bool {
+    use crate::focus::{cf_string, copy_ax_attribute};
+    use accessibility_sys::{
+        kAXErrorSuccess, kAXFocusedUIElementAttribute, kAXSelectedTextAttribute,
+        AXUIElementCreateSystemWide, AXUIElementRef, AXUIElementSetAttributeValue,
+    };
+    use core_foundation_sys::base::CFRelease;
+
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        let focused = copy_ax_attribute(system, kAXFocusedUIElementAttribute);
+        CFRelease(system as _);
+        let Some(focused) = focused else {
+            return false;
+        };
+        let focused = focused as AXUIElementRef;
+        let attribute = cf_string(kAXSelectedTextAttribute);
+        let value = cf_string(text);
+        let error = AXUIElementSetAttributeValue(focused, attribute, value as _);
+        CFRelease(value as _);
+        CFRelease(attribute as _);
+        CFRelease(focused as _);
+        error == kAXErrorSuccess
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn insert_native(_text: &str) -> bool {
+    false
+}
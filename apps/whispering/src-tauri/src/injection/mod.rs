@@ -0,0 +1,442 @@
+//! Putting transcripts into the app the user is working in.
+//!
+//! By default text goes in through the clipboard: the transcript is pasted
+//! with a simulated Cmd+V / Ctrl+V and the user's clipboard is restored
+//! after. Apps that block pasting can be typed into instead, and on macOS
+//! text can be inserted through the Accessibility API. Before going in it's
+//! fitted to the surrounding text (capitalization, spacing), with options
+//! that can differ per app.
+//!
+//! Where the text around the caret can be read back, injected text is
+//! checked afterwards. If the target app rejected it, the transcript is
+//! left on the clipboard and the user is told to paste it themselves.
+//! Windows blocks input to elevated windows outright, so those skip straight
+//! to the clipboard unless the user has opted into the elevated helper.
+
+pub mod commands;
+mod context;
+mod elevation;
+mod format;
+pub mod helper;
+mod ime;
+
+pub use context::{caret_context, text_before_caret, CaretContext};
+pub use elevation::foreground_elevated;
+pub use format::FormatOptions;
+pub use helper::ElevatedHelper;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, InjectionElevated};
+use crate::focus::focused_app;
+use crate::history::InjectionOutcome;
+use crate::i18n;
+use crate::notifications::notify;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing::{debug, info, warn};
+
+pub const INJECTION_FILE: &str = "injection.json";
+
+/// How text gets into the focused app
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InjectionMethod {
+    /// Paste through the clipboard, restoring it after
+    #[default]
+    Paste,
+    /// Simulate a keystroke per character, for apps that block pasting.
+    /// Falls back to pasting for text an input method would alter.
+    Type,
+    /// Insert through the platform's text API (macOS Accessibility), which
+    /// leaves the clipboard and input methods alone. Falls back to pasting
+    /// where unavailable.
+    Native,
+}
+
+impl InjectionMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InjectionMethod::Paste => "paste",
+            InjectionMethod::Type => "type",
+            InjectionMethod::Native => "native",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InjectionConfig {
+    pub method: InjectionMethod,
+    /// Formatting for apps without their own entry
+    pub formatting: FormatOptions,
+    /// Formatting by app name, as shown by `get_focused_app`; names match
+    /// case-insensitively
+    pub apps: BTreeMap<String, FormatOptions>,
+    /// Method by app name, for apps that need a different one
+    pub methods: BTreeMap<String, InjectionMethod>,
+    /// Type into elevated windows through a helper process started as
+    /// administrator (Windows only, asks for UAC consent once per session)
+    pub elevated_helper: bool,
+}
+
+/// The entry in `entries` for `app`, matching names case-insensitively
+fn for_app<T: Copy>(entries: &BTreeMap<String, T>, app: Option<&str>) -> Option<T> {
+    let app = app?;
+    entries
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(app))
+        .map(|(_, value)| *value)
+}
+
+impl InjectionConfig {
+    pub fn formatting_for(&self, app: Option<&str>) -> FormatOptions {
+        for_app(&self.apps, app).unwrap_or(self.formatting)
+    }
+
+    pub fn method_for(&self, app: Option<&str>) -> InjectionMethod {
+        for_app(&self.methods, app).unwrap_or(self.method)
+    }
+}
+
+/// A transcript formatted for the focused app, ready to go in
+#[derive(Debug, Clone)]
+pub struct Prepared {
+    /// The focused app, where it could be named
+    pub app: Option<String>,
+    pub text: String,
+    pub method: InjectionMethod,
+    /// The focused window runs as administrator, out of reach of simulated
+    /// input (Windows only)
+    pub elevated: bool,
+}
+
+/// What happened to an injected transcript, emitted as `injection://result`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectionReport {
+    pub app: Option<String>,
+    /// The method actually used, after any fallbacks
+    pub method: InjectionMethod,
+    pub outcome: InjectionOutcome,
+    /// The target ran as administrator
+    pub elevated: bool,
+}
+
+pub struct Injector {
+    path: PathBuf,
+    config: Mutex<InjectionConfig>,
+    helper: Arc<ElevatedHelper>,
+}
+
+impl Injector {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                InjectionConfig::default()
+            }),
+            Err(_) => InjectionConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            helper: Arc::new(ElevatedHelper::default()),
+        }
+    }
+
+    pub fn config(&self) -> InjectionConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: InjectionConfig) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize injection settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        if !config.elevated_helper {
+            self.helper.stop();
+        }
+        *self.config.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock injection settings: {}", e))
+        })? = config;
+        Ok(())
+    }
+
+    /// `text` formatted for the focused app and the text around the caret,
+    /// and the method to put it there with
+    pub fn prepare(&self, text: &str) -> Prepared {
+        let app = focused_app();
+        let config = self.config();
+        let options = config.formatting_for(app.as_deref());
+        let context = caret_context();
+        debug!(
+            "Formatting for {:?} with {:?}, caret context {:?}",
+            app, options, context
+        );
+        Prepared {
+            text: format::format(text, options, context.as_ref()),
+            method: config.method_for(app.as_deref()),
+            elevated: foreground_elevated(),
+            app,
+        }
+    }
+
+    /// `inject` a prepared transcript, then check it landed. If the target
+    /// rejected it (the text before the caret didn't change, or injecting
+    /// failed outright) the transcript is left on the clipboard and a
+    /// notification tells the user to paste it.
+    pub async fn inject_verified(
+        &self,
+        app: &AppHandle,
+        prepared: Prepared,
+    ) -> Result<InjectionReport> {
+        let Prepared {
+            app: target,
+            text,
+            method,
+            elevated,
+        } = prepared;
+        if elevated {
+            return self.inject_elevated(app, target, text, method).await;
+        }
+
+        let units = text.encode_utf16().count() + VERIFY_MARGIN_UNITS;
+        let baseline = text_before_caret(units);
+        let (method, landed) = match inject(app, &text, method).await {
+            Ok(used) => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(VERIFY_DELAY_MS)).await;
+                (used, landed(&text, baseline, text_before_caret(units)))
+            }
+            Err(e) => {
+                warn!("Injecting into {:?} failed: {}", target, e);
+                (method, Some(false))
+            }
+        };
+
+        let outcome = match landed {
+            Some(true) => InjectionOutcome::Verified,
+            None => InjectionOutcome::Unverified,
+            Some(false) => {
+                leave_on_clipboard(
+                    app,
+                    &text,
+                    &match target.as_deref() {
+                        Some(target) => {
+                            i18n::t_args("notify-injection-fell-back", &[("app", target)])
+                        }
+                        None => i18n::t("notify-injection-fell-back-unknown"),
+                    },
+                )?;
+                InjectionOutcome::FellBack
+            }
+        };
+        debug!(
+            "Injected into {:?} with {:?}: {:?}",
+            target, method, outcome
+        );
+        Ok(InjectionReport {
+            app: target,
+            method,
+            outcome,
+            elevated: false,
+        })
+    }
+
+    /// Our input can't reach an elevated window, so type through the helper
+    /// if the user opted in, and otherwise go straight to the clipboard
+    async fn inject_elevated(
+        &self,
+        app: &AppHandle,
+        target: Option<String>,
+        text: String,
+        method: InjectionMethod,
+    ) -> Result<InjectionReport> {
+        info!("{:?} is running as administrator", target);
+        let _ = InjectionElevated::emit(app, &target);
+
+        let typed = if self.config().elevated_helper {
+            let helper = self.helper.clone();
+            let request = text.clone();
+            match tokio::task::spawn_blocking(move || helper.type_text(&request)).await {
+                Ok(Ok(())) => true,
+                Ok(Err(e)) => {
+                    warn!("Elevated injection helper failed: {}", e);
+                    false
+                }
+                Err(e) => {
+                    warn!("Elevated injection helper task failed: {}", e);
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+        let (method, outcome) = if typed {
+            // Elevated apps can't be read back either
+            (InjectionMethod::Type, InjectionOutcome::Unverified)
+        } else {
+            leave_on_clipboard(
+                app,
+                &text,
+                &match target.as_deref() {
+                    Some(target) => i18n::t_args("notify-elevated", &[("app", target)]),
+                    None => i18n::t("notify-elevated-unknown"),
+                },
+            )?;
+            (method, InjectionOutcome::FellBack)
+        };
+        Ok(InjectionReport {
+            app: target,
+            method,
+            outcome,
+            elevated: true,
+        })
+    }
+
+    /// Whether the elevated helper process is running
+    pub fn helper_running(&self) -> bool {
+        self.helper.is_running()
+    }
+}
+
+/// Put `text` into the focused app with `method`, or the safest fallback
+/// for it. Returns the method actually used.
+pub async fn inject(
+    app: &tauri::AppHandle,
+    text: &str,
+    method: InjectionMethod,
+) -> Result<InjectionMethod> {
+    match method {
+        InjectionMethod::Type if ime::needs_ime_safe_path(text) => {
+            debug!("Pasting instead of typing so the input method can't alter the text");
+        }
+        InjectionMethod::Type => {
+            type_text(text)?;
+            return Ok(InjectionMethod::Type);
+        }
+        InjectionMethod::Native => {
+            if ime::insert_native(text) {
+                return Ok(InjectionMethod::Native);
+            }
+            debug!("Native text insertion unavailable, pasting instead");
+        }
+        InjectionMethod::Paste => {}
+    }
+    paste_text(app, text).await?;
+    Ok(InjectionMethod::Paste)
+}
+
+/// Extra text to read before the caret beyond the transcript itself, so a
+/// field that didn't change can be told apart from one that did
+const VERIFY_MARGIN_UNITS: usize = 16;
+
+/// Time for the target app to take the text in before reading it back
+const VERIFY_DELAY_MS: u64 = 50;
+
+/// Whether `text` landed, from the text before the caret read before and
+/// after injecting: `Some(false)` if nothing changed, `None` if it can't be
+/// told (unreadable, or something changed but not as expected)
+fn landed(text: &str, before: Option<String>, after: Option<String>) -> Option<bool> {
+    let after = after?;
+    if after.trim_end().ends_with(text.trim()) {
+        Some(true)
+    } else if before.as_ref() == Some(&after) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Put `text` on the clipboard, where it stays, and tell the user why
+fn leave_on_clipboard(app: &AppHandle, text: &str, message: &str) -> Result<()> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| AppError::Internal(format!("Failed to write to clipboard: {}", e)))?;
+    notify(app, message);
+    Ok(())
+}
+
+/// Type `text` as simulated key events
+fn type_text(text: &str) -> Result<()> {
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| AppError::Internal(e.to_string()))?;
+    enigo
+        .text(text)
+        .map_err(|e| AppError::Internal(format!("Failed to type text: {}", e)))?;
+    Ok(())
+}
+
+/// Writes text at the cursor position using the clipboard sandwich technique
+///
+/// This method preserves the user's existing clipboard content by:
+/// 1. Saving the current clipboard content
+/// 2. Writing the new text to clipboard
+/// 3. Simulating a paste operation (Cmd+V on macOS, Ctrl+V elsewhere)
+/// 4. Restoring the original clipboard content
+///
+/// This approach is faster than typing character-by-character and preserves
+/// the user's clipboard, making it ideal for inserting transcribed text.
+pub async fn paste_text(app: &tauri::AppHandle, text: &str) -> Result<()> {
+    // 1. Save current clipboard content
+    let original_clipboard = app.clipboard().read_text().ok();
+
+    // 2. Write new text to clipboard
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| AppError::Internal(format!("Failed to write to clipboard: {}", e)))?;
+
+    // Small delay to ensure clipboard is updated
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    // 3. Simulate paste operation using virtual key codes (layout-independent)
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Use virtual key codes for V to work with any keyboard layout
+    #[cfg(target_os = "macos")]
+    let (modifier, v_key) = (Key::Meta, Key::Other(9)); // Virtual key code for V on macOS
+    #[cfg(target_os = "windows")]
+    let (modifier, v_key) = (Key::Control, Key::Other(0x56)); // VK_V on Windows
+    #[cfg(target_os = "linux")]
+    let (modifier, v_key) = (Key::Control, Key::Unicode('v')); // Fallback for Linux
+
+    // Press modifier + V
+    enigo
+        .key(modifier, Direction::Press)
+        .map_err(|e| AppError::Internal(format!("Failed to press modifier key: {}", e)))?;
+    enigo
+        .key(v_key, Direction::Press)
+        .map_err(|e| AppError::Internal(format!("Failed to press V key: {}", e)))?;
+
+    // Release V + modifier (in reverse order for proper cleanup)
+    enigo
+        .key(v_key, Direction::Release)
+        .map_err(|e| AppError::Internal(format!("Failed to release V key: {}", e)))?;
+    enigo
+        .key(modifier, Direction::Release)
+        .map_err(|e| AppError::Internal(format!("Failed to release modifier key: {}", e)))?;
+
+    // Small delay to ensure paste completes
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // 4. Restore original clipboard content
+    if let Some(content) = original_clipboard {
+        app.clipboard()
+            .write_text(&content)
+            .map_err(|e| AppError::Internal(format!("Failed to restore clipboard: {}", e)))?;
+    }
+
+    Ok(())
+}
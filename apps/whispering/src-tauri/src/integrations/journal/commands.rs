@@ -0,0 +1,32 @@
+use crate::error::{AppError, Result};
+use tracing::debug;
+
+/// Append a transcript to the journal `path_pattern` currently names,
+/// returning the file it went into
+#[tauri::command]
+pub async fn append_to_journal(path_pattern: String, text: String) -> Result<String> {
+    let path = tokio::task::spawn_blocking(move || super::append(&path_pattern, &text))
+        .await
+        .map_err(|e| AppError::Internal(format!("Journal task failed: {}", e)))??;
+    debug!("Appended transcript to journal {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Commit the journal file at `path` to git, with a message from
+/// `message_template`, returning the commit id
+#[tauri::command]
+pub async fn commit_journal(
+    path: String,
+    text: String,
+    message_template: String,
+) -> Result<String> {
+    let id = tokio::task::spawn_blocking(move || {
+        let path = std::path::PathBuf::from(path);
+        let message = super::git::render_message(&message_template, &path, &text);
+        super::git::commit_file(&path, &message)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Journal task failed: {}", e)))??;
+    debug!("Committed journal as {}", id);
+    Ok(id)
+}
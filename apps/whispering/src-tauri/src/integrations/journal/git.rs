@@ -0,0 +1,89 @@
+//! Committing journal files to git.
+//!
+//! The journal's folder becomes a repository the first time it's needed, or
+//! the enclosing one is used if the journal already lives in one. Only the
+//! journal file is staged, so other work in the repository is left alone.
+
+use crate::error::{AppError, Result};
+use chrono::Local;
+use git2::{Repository, Signature};
+use std::path::Path;
+
+/// Used when the repository has no `user.name`/`user.email` configured
+const FALLBACK_NAME: &str = "Whispering";
+const FALLBACK_EMAIL: &str = "whispering@localhost";
+
+/// Longest preview of the transcript put in a commit message
+const PREVIEW_CHARS: usize = 60;
+
+/// Fill in a commit message template. Supports `{date}`, `{time}`, `{file}`
+/// and `{preview}`, the transcript's first line shortened.
+pub fn render_message(template: &str, path: &Path, text: &str) -> String {
+    let now = Local::now();
+    let first_line = text.trim().lines().next().unwrap_or_default();
+    let mut preview: String = first_line.chars().take(PREVIEW_CHARS).collect();
+    if first_line.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    let file = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{file}", &file)
+        .replace("{preview}", &preview)
+}
+
+/// Commit the current contents of `path`, returning the new commit's id
+pub fn commit_file(path: &Path, message: &str) -> Result<String> {
+    let path = path.canonicalize().map_err(|e| {
+        AppError::InvalidInput(format!("Failed to find journal {}: {}", path.display(), e))
+    })?;
+    let folder = path
+        .parent()
+        .ok_or_else(|| AppError::InvalidInput(format!("Not a file: {}", path.display())))?;
+    let repo = match Repository::discover(folder) {
+        Ok(repo) => repo,
+        Err(_) => Repository::init(folder)
+            .map_err(|e| AppError::Internal(format!("Failed to create git repository: {}", e)))?,
+    };
+    let workdir = repo
+        .workdir()
+        .and_then(|workdir| workdir.canonicalize().ok())
+        .ok_or_else(|| {
+            AppError::InvalidInput("Journal repository has no working folder".to_string())
+        })?;
+    let relative = path.strip_prefix(&workdir).map_err(|_| {
+        AppError::InvalidInput(format!(
+            "Journal {} is outside its repository {}",
+            path.display(),
+            workdir.display()
+        ))
+    })?;
+
+    let git = |e: git2::Error| AppError::Internal(format!("Failed to commit journal: {}", e));
+    let mut index = repo.index().map_err(git)?;
+    index.add_path(relative).map_err(git)?;
+    index.write().map_err(git)?;
+    let tree = repo
+        .find_tree(index.write_tree().map_err(git)?)
+        .map_err(git)?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now(FALLBACK_NAME, FALLBACK_EMAIL))
+        .map_err(git)?;
+    let id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parent.iter().collect::<Vec<_>>(),
+        )
+        .map_err(git)?;
+    Ok(id.to_string())
+}
@@ -0,0 +1,82 @@
+//! Appending transcripts to a journal file.
+//!
+//! The path is a strftime pattern, so `journal-%Y-%m.md` starts a new file
+//! each month and `journal-%Y-%m-%d.md` one each day. Each transcript goes in
+//! under a timestamp heading while the file is held with an exclusive lock,
+//! so a second window or the CLI never interleaves with a half-written entry.
+//! Journals can optionally be kept under version control (see `git`).
+
+pub mod commands;
+pub mod git;
+
+use crate::error::{AppError, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Heading written above each entry
+const HEADER_FORMAT: &str = "## %Y-%m-%d %H:%M";
+
+/// The file `pattern` names at `now`
+pub fn resolve_path(pattern: &str, now: &DateTime<Local>) -> Result<PathBuf> {
+    let items: Vec<Item> = StrftimeItems::new(pattern.trim()).collect();
+    if items.is_empty() || items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid journal path pattern: {}",
+            pattern
+        )));
+    }
+    let path = PathBuf::from(now.format_with_items(items.into_iter()).to_string());
+    if !path.is_absolute() {
+        return Err(AppError::InvalidInput(format!(
+            "Journal path must be absolute, got {}",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// Append `text` to the file `pattern` currently names, returning its path
+pub fn append(pattern: &str, text: &str) -> Result<PathBuf> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Nothing to add to the journal".to_string(),
+        ));
+    }
+
+    let now = Local::now();
+    let path = resolve_path(pattern, &now)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::Internal(format!("Failed to create journal folder: {}", e)))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| {
+            AppError::Internal(format!("Failed to open journal {}: {}", path.display(), e))
+        })?;
+    file.lock().map_err(|e| {
+        AppError::Internal(format!("Failed to lock journal {}: {}", path.display(), e))
+    })?;
+
+    // The length is only trustworthy once the lock is held
+    let separator = match file.metadata() {
+        Ok(metadata) if metadata.len() > 0 => "\n",
+        _ => "",
+    };
+    let entry = format!("{}{}\n\n{}\n", separator, now.format(HEADER_FORMAT), text);
+    let written = file
+        .write_all(entry.as_bytes())
+        .and_then(|()| file.sync_data());
+    let _ = file.unlock();
+    written.map_err(|e| {
+        AppError::Internal(format!("Failed to write journal {}: {}", path.display(), e))
+    })?;
+
+    Ok(path)
+}
@@ -0,0 +1,4 @@
+//! Pushing Whispering's output into other applications and files.
+
+pub mod journal;
+pub mod obs;
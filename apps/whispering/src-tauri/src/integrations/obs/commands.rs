@@ -0,0 +1,24 @@
+use super::{ObsConfig, ObsIntegration, ObsVersion};
+use crate::error::Result;
+use tauri::State;
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_obs_config(obs: State<'_, ObsIntegration>) -> Result<ObsConfig> {
+    Ok(obs.config())
+}
+
+#[tauri::command]
+pub async fn set_obs_config(config: ObsConfig, obs: State<'_, ObsIntegration>) -> Result<()> {
+    debug!(
+        "Updating OBS integration: enabled={}, url={}",
+        config.enabled, config.url
+    );
+    obs.set_config(config)
+}
+
+/// Connect to OBS with the saved settings and report its version
+#[tauri::command]
+pub async fn test_obs_connection(obs: State<'_, ObsIntegration>) -> Result<ObsVersion> {
+    obs.test_connection().await
+}
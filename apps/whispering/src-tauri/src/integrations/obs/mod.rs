@@ -0,0 +1,431 @@
+//! OBS Studio integration over obs-websocket (protocol v5, built into OBS 28+).
+//!
+//! Streaming transcription results are written into a text source with
+//! `SetInputSettings`, and a scene item (e.g. a "recording" badge) in the
+//! current program scene is shown while recording and hidden otherwise.
+//!
+//! A single task owns the WebSocket. It connects on first use and again after
+//! a failure, so OBS can be started or restarted at any time.
+
+pub mod commands;
+
+use crate::app_state::{AppState, AppStatus};
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, StreamingFinal, StreamingInterim};
+use crate::privacy;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Event, Listener, Manager};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn};
+
+pub const OBS_FILE: &str = "obs.json";
+
+/// Connecting and each request give up after this long
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const RPC_VERSION: u64 = 1;
+
+// obs-websocket message opcodes
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ObsConfig {
+    pub enabled: bool,
+    /// obs-websocket server, from OBS's Tools → WebSocket Server Settings
+    pub url: String,
+    /// Empty if authentication is disabled in OBS
+    pub password: String,
+    /// Text source that shows captions; empty to not push captions
+    pub caption_source: String,
+    /// Also show interim results, not only finished sentences
+    pub show_interim: bool,
+    /// Scene item shown while recording; empty to leave scenes alone
+    pub recording_source: String,
+}
+
+impl Default for ObsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "ws://127.0.0.1:4455".to_string(),
+            password: String::new(),
+            caption_source: String::new(),
+            show_interim: true,
+            recording_source: String::new(),
+        }
+    }
+}
+
+/// Result of `test_obs_connection`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsVersion {
+    pub obs_version: String,
+    pub websocket_version: String,
+}
+
+struct ObsRequest {
+    request_type: &'static str,
+    data: Value,
+    reply: oneshot::Sender<Result<Value>>,
+}
+
+enum ObsCommand {
+    Request(ObsRequest),
+    /// Drop the connection so the next request uses the current settings
+    Disconnect,
+}
+
+pub struct ObsIntegration {
+    path: PathBuf,
+    config: Mutex<ObsConfig>,
+    tx: Mutex<Option<mpsc::UnboundedSender<ObsCommand>>>,
+}
+
+impl ObsIntegration {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                ObsConfig::default()
+            }),
+            Err(_) => ObsConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            tx: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> ObsConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: ObsConfig) -> Result<()> {
+        if !config.url.starts_with("ws://") && !config.url.starts_with("wss://") {
+            return Err(AppError::InvalidInput(format!(
+                "OBS WebSocket URL must start with ws:// or wss://, got {}",
+                config.url
+            )));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize OBS settings: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock OBS settings: {}", e)))? =
+            config;
+
+        self.send(ObsCommand::Disconnect);
+        Ok(())
+    }
+
+    /// Start the connection task and follow captions and recording state
+    pub fn start(&self, app: AppHandle) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if let Ok(mut slot) = self.tx.lock() {
+            *slot = Some(tx);
+        }
+        tauri::async_runtime::spawn(run(app.clone(), rx));
+
+        for (event, is_final) in [(StreamingInterim::NAME, false), (StreamingFinal::NAME, true)] {
+            let handle = app.clone();
+            app.listen_any(event, move |event: Event| {
+                handle
+                    .state::<ObsIntegration>()
+                    .push_caption(event, is_final);
+            });
+        }
+
+        let mut rx = app.state::<AppState>().subscribe();
+        tauri::async_runtime::spawn(async move {
+            let mut was_recording = false;
+            while rx.changed().await.is_ok() {
+                let recording = matches!(*rx.borrow_and_update(), AppStatus::Recording { .. });
+                if recording == was_recording {
+                    continue;
+                }
+                was_recording = recording;
+                let obs = app.state::<ObsIntegration>();
+                if let Err(e) = obs.set_recording_indicator(recording).await {
+                    warn!("Failed to update OBS recording indicator: {}", e);
+                }
+            }
+        });
+    }
+
+    fn send(&self, command: ObsCommand) -> bool {
+        self.tx
+            .lock()
+            .ok()
+            .and_then(|tx| tx.as_ref().map(|tx| tx.send(command).is_ok()))
+            .unwrap_or(false)
+    }
+
+    /// Send a request to OBS and wait for its response data
+    async fn request(&self, request_type: &'static str, data: Value) -> Result<Value> {
+        let (reply, response) = oneshot::channel();
+        let sent = self.send(ObsCommand::Request(ObsRequest {
+            request_type,
+            data,
+            reply,
+        }));
+        if !sent {
+            return Err(AppError::NotRunning(
+                "The OBS integration has not started".to_string(),
+            ));
+        }
+        response
+            .await
+            .map_err(|_| AppError::Internal("OBS connection task stopped".to_string()))?
+    }
+
+    /// Connect to OBS and report its version
+    pub async fn test_connection(&self) -> Result<ObsVersion> {
+        let version = self.request("GetVersion", json!({})).await?;
+        let field = |name: &str| version[name].as_str().unwrap_or_default().to_string();
+        Ok(ObsVersion {
+            obs_version: field("obsVersion"),
+            websocket_version: field("obsWebSocketVersion"),
+        })
+    }
+
+    fn push_caption(&self, event: Event, is_final: bool) {
+        let config = self.config();
+        if !config.enabled || config.caption_source.is_empty() || !(is_final || config.show_interim)
+        {
+            return;
+        }
+        let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+        let text = payload["text"].as_str().unwrap_or_default();
+
+        // Fire and forget: a newer caption will replace this one anyway
+        let (reply, _) = oneshot::channel();
+        self.send(ObsCommand::Request(ObsRequest {
+            request_type: "SetInputSettings",
+            data: json!({
+                "inputName": config.caption_source,
+                "inputSettings": { "text": text },
+            }),
+            reply,
+        }));
+    }
+
+    /// Show or hide the recording scene item in the current program scene
+    async fn set_recording_indicator(&self, visible: bool) -> Result<()> {
+        let config = self.config();
+        if !config.enabled || config.recording_source.is_empty() {
+            return Ok(());
+        }
+
+        let scene = self.request("GetCurrentProgramScene", json!({})).await?;
+        let scene_name = scene["currentProgramSceneName"]
+            .as_str()
+            .or_else(|| scene["sceneName"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        let item = self
+            .request(
+                "GetSceneItemId",
+                json!({ "sceneName": scene_name, "sourceName": config.recording_source }),
+            )
+            .await?;
+        self.request(
+            "SetSceneItemEnabled",
+            json!({
+                "sceneName": scene_name,
+                "sceneItemId": item["sceneItemId"],
+                "sceneItemEnabled": visible,
+            }),
+        )
+        .await?;
+        debug!(
+            "{} {} in OBS scene {}",
+            if visible { "Showed" } else { "Hid" },
+            config.recording_source,
+            scene_name
+        );
+        Ok(())
+    }
+}
+
+/// Owns the WebSocket and serves requests one at a time
+async fn run(app: AppHandle, mut rx: mpsc::UnboundedReceiver<ObsCommand>) {
+    let mut socket: Option<Socket> = None;
+    let mut next_id: u64 = 0;
+
+    while let Some(command) = rx.recv().await {
+        let request = match command {
+            ObsCommand::Request(request) => request,
+            ObsCommand::Disconnect => {
+                if let Some(mut socket) = socket.take() {
+                    let _ = socket.close(None).await;
+                }
+                continue;
+            }
+        };
+
+        let ws = match socket.as_mut() {
+            Some(ws) => ws,
+            None => match connect(&app).await {
+                Ok(ws) => socket.insert(ws),
+                Err(e) => {
+                    let _ = request.reply.send(Err(e));
+                    continue;
+                }
+            },
+        };
+
+        next_id += 1;
+        let result = tokio::time::timeout(
+            TIMEOUT,
+            send_request(ws, next_id, request.request_type, request.data),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(AppError::Network(format!(
+                "OBS didn't answer {} in time",
+                request.request_type
+            )))
+        });
+        if matches!(result, Err(AppError::Network(_))) {
+            // Reconnect on the next request
+            socket = None;
+        }
+        let _ = request.reply.send(result);
+    }
+}
+
+async fn connect(app: &AppHandle) -> Result<Socket> {
+    let config = app.state::<ObsIntegration>().config();
+    privacy::ensure_allowed(app, &config.url)?;
+    debug!("Connecting to OBS at {}", config.url);
+
+    let (mut ws, _) = tokio::time::timeout(
+        TIMEOUT,
+        tokio_tungstenite::connect_async(config.url.as_str()),
+    )
+    .await
+    .map_err(|_| AppError::Network(format!("Timed out connecting to OBS at {}", config.url)))?
+    .map_err(|e| AppError::Network(format!("Failed to connect to OBS: {}", e)))?;
+
+    let hello = tokio::time::timeout(TIMEOUT, receive(&mut ws, OP_HELLO))
+        .await
+        .map_err(|_| AppError::Network("OBS didn't say hello".to_string()))??;
+
+    let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+    if let Some(auth) = hello.get("authentication") {
+        if config.password.is_empty() {
+            return Err(AppError::InvalidApiKey(
+                "OBS requires a WebSocket password".to_string(),
+            ));
+        }
+        identify["authentication"] = json!(authentication(
+            &config.password,
+            auth["salt"].as_str().unwrap_or_default(),
+            auth["challenge"].as_str().unwrap_or_default(),
+        ));
+    }
+    send(&mut ws, OP_IDENTIFY, identify).await?;
+
+    // OBS closes the connection instead of identifying if the password is wrong
+    match tokio::time::timeout(TIMEOUT, receive(&mut ws, OP_IDENTIFIED)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(AppError::Network(_))) => {
+            return Err(AppError::InvalidApiKey(
+                "OBS rejected the WebSocket password".to_string(),
+            ))
+        }
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return Err(AppError::Network("OBS didn't identify us".to_string())),
+    }
+
+    info!("Connected to OBS at {}", config.url);
+    Ok(ws)
+}
+
+/// `base64(sha256(base64(sha256(password + salt)) + challenge))`
+fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+async fn send(ws: &mut Socket, op: u64, data: Value) -> Result<()> {
+    let message = json!({ "op": op, "d": data });
+    ws.send(Message::Text(message.to_string()))
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to send to OBS: {}", e)))
+}
+
+/// Wait for the next message with opcode `op`, skipping anything else
+async fn receive(ws: &mut Socket, op: u64) -> Result<Value> {
+    while let Some(message) = ws.next().await {
+        let message = message.map_err(|e| AppError::Network(e.to_string()))?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let Ok(mut message) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        if message["op"].as_u64() == Some(op) {
+            return Ok(message["d"].take());
+        }
+    }
+    Err(AppError::Network("OBS closed the connection".to_string()))
+}
+
+async fn send_request(ws: &mut Socket, id: u64, request_type: &str, data: Value) -> Result<Value> {
+    let id = id.to_string();
+    send(
+        ws,
+        OP_REQUEST,
+        json!({ "requestType": request_type, "requestId": id, "requestData": data }),
+    )
+    .await?;
+
+    loop {
+        let mut response = receive(ws, OP_REQUEST_RESPONSE).await?;
+        // Responses to fire-and-forget requests that timed out can still arrive
+        if response["requestId"].as_str() != Some(id.as_str()) {
+            continue;
+        }
+        let status = &response["requestStatus"];
+        if status["result"].as_bool() != Some(true) {
+            return Err(AppError::InvalidInput(format!(
+                "OBS rejected {}: {}",
+                request_type,
+                status["comment"].as_str().unwrap_or("no reason given")
+            )));
+        }
+        return Ok(response["responseData"].take());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::error::AppError;
+use crate::ipc::{endpoint_file, IpcCommand, IpcEndpoint, IpcMessage, IpcRequest, Result};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// How long to wait on the running instance before taking it for gone. It
+/// answers from a socket thread, so a stuck one is hung or not Whispering.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connection to the running instance, used by helper processes
+pub struct IpcClient {
+    token: String,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl IpcClient {
+    /// Connect using the endpoint file written by the running instance
+    pub fn connect() -> Result<Self> {
+        let path = endpoint_file()?;
+        let data = std::fs::read(&path)
+            .map_err(|_| AppError::NotRunning("No IPC endpoint published".to_string()))?;
+        let endpoint: IpcEndpoint = serde_json::from_slice(&data)
+            .map_err(|e| AppError::Internal(format!("Invalid IPC endpoint file: {}", e)))?;
+
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+        let stream = TcpStream::connect_timeout(&address, TIMEOUT)
+            .map_err(|e| AppError::NotRunning(format!("Failed to connect: {}", e)))?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+            .map_err(|e| AppError::Internal(format!("Failed to set IPC timeouts: {}", e)))?;
+        let writer = stream
+            .try_clone()
+            .map_err(|e| AppError::Internal(format!("Failed to clone IPC stream: {}", e)))?;
+
+        Ok(Self {
+            token: endpoint.token,
+            reader: BufReader::new(stream),
+            writer,
+        })
+    }
+
+    /// Send a command and wait for its response
+    pub fn request(&mut self, command: IpcCommand) -> Result<IpcMessage> {
+        let request = IpcRequest {
+            token: self.token.clone(),
+            command,
+        };
+        let mut line = serde_json::to_vec(&request)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize request: {}", e)))?;
+        line.push(b'\n');
+        self.writer
+            .write_all(&line)
+            .map_err(|e| io_error("Failed to send request", e))?;
+        self.read_message()
+    }
+
+    /// Wait for pushed messages for as long as the connection lasts, rather
+    /// than giving up after the request timeout; for `Subscribe`
+    pub fn keep_listening(&self) -> Result<()> {
+        self.reader
+            .get_ref()
+            .set_read_timeout(None)
+            .map_err(|e| AppError::Internal(format!("Failed to clear IPC timeout: {}", e)))
+    }
+
+    /// Block until the next message arrives
+    pub fn read_message(&mut self) -> Result<IpcMessage> {
+        let mut line = String::new();
+        let read = self
+            .reader
+            .read_line(&mut line)
+            .map_err(|e| io_error("Failed to read response", e))?;
+        if read == 0 {
+            return Err(AppError::NotRunning("Connection closed by Whispering".to_string()));
+        }
+        Ok(serde_json::from_str(&line)
+            .map_err(|e| AppError::Internal(format!("Invalid response: {}", e)))?)
+    }
+}
+
+/// A timeout means the instance isn't answering, which callers treat like
+/// there being none
+fn io_error(context: &str, e: io::Error) -> AppError {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            AppError::NotRunning("Whispering isn't responding".to_string())
+        }
+        _ => format!("{}: {}", context, e).into(),
+    }
+}
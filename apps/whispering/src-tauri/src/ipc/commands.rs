@@ -0,0 +1,26 @@
+use crate::error::Result;
+use crate::ipc::IpcServer;
+use tauri::State;
+use tracing::debug;
+
+/// Called by the frontend after each transcription so helper processes
+/// (browser extension, launcher scripts) can receive it
+#[tauri::command]
+pub async fn publish_transcript(text: String, ipc: State<'_, IpcServer>) -> Result<()> {
+    ipc.publish_transcript(text);
+    Ok(())
+}
+
+/// Called by the frontend once it has run a relayed recording command, with
+/// the error if it failed, so the helper that sent it gets the outcome
+#[tauri::command]
+pub async fn finish_ipc_command(
+    id: u64,
+    error: Option<String>,
+    ipc: State<'_, IpcServer>,
+) -> Result<()> {
+    if !ipc.finish_command(id, error.map_or(Ok(()), Err)) {
+        debug!("IPC command {} finished after its client gave up", id);
+    }
+    Ok(())
+}
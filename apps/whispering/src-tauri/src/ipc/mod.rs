@@ -0,0 +1,80 @@
+//! Local socket used by helper processes (native messaging host, CLI) to talk
+//! to the running Whispering instance.
+//!
+//! The server listens on a random loopback port and writes the port together
+//! with a per-launch token to an endpoint file in the login session's own
+//! directory (see `session`). Clients read that file, connect, and exchange
+//! newline-delimited JSON messages.
+
+pub mod client;
+pub mod commands;
+pub mod server;
+
+pub use client::IpcClient;
+pub use server::IpcServer;
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub use crate::error::Result;
+
+/// Must match `identifier` in tauri.conf.json
+pub const APP_IDENTIFIER: &str = "com.bradenwong.whispering";
+
+const ENDPOINT_FILE: &str = "ipc.json";
+
+/// Connection details published by the running instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcEndpoint {
+    pub port: u16,
+    pub token: String,
+}
+
+/// Path of the endpoint file, resolvable without a Tauri app handle
+///
+/// Each login session has its own, so helpers reach the instance in theirs.
+pub fn endpoint_file() -> Result<PathBuf> {
+    Ok(crate::session::runtime_dir()?.join(ENDPOINT_FILE))
+}
+
+/// A request sent by a client, authenticated with the endpoint token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcRequest {
+    pub token: String,
+    #[serde(flatten)]
+    pub command: IpcCommand,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IpcCommand {
+    StartRecording,
+    StopRecording,
+    ToggleRecording,
+    Status,
+    LastTranscript,
+    /// Keep the connection open and receive pushed `IpcMessage`s
+    Subscribe,
+    /// Transcribe an audio file and put the result on the clipboard
+    TranscribeFile { path: String },
+    /// Open the UI, creating it if the daemon is running without one
+    ShowUi,
+}
+
+/// Messages written back to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IpcMessage {
+    Ok,
+    Status {
+        recording: bool,
+        #[serde(rename = "recordingId")]
+        recording_id: Option<String>,
+    },
+    Transcript {
+        text: Option<String>,
+    },
+    Error {
+        message: String,
+    },
+}
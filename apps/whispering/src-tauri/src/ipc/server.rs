@@ -0,0 +1,307 @@
+use crate::clipboard_audio::{check_audio_file, TranscribeFilePayload};
+use crate::daemon::{self, Daemon};
+use crate::error::AppError;
+use crate::events::{AppEvent, IpcCommandReceived, TranscribeFileRequested};
+use crate::ipc::{endpoint_file, IpcCommand, IpcEndpoint, IpcMessage, IpcRequest, Result};
+use crate::jobs::pending::{PendingJobs, PendingSource};
+use crate::recorder::AppData;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::{debug, error, info, warn};
+
+/// Longest a relayed command may take in the frontend. Stopping waits for
+/// the transcript, so `whispering stop && whispering last-transcript` prints
+/// the recording just made.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Payload of the `ipc://command` event, handled by the frontend recorder
+///
+/// The frontend answers with `finish_ipc_command` and the same `id`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcCommandPayload {
+    pub id: u64,
+    pub command: &'static str,
+}
+
+/// How a relayed command ended: `Err` carries the frontend's message
+type CommandReply = std::result::Result<(), String>;
+
+/// Loopback server relaying helper process requests into the app
+pub struct IpcServer {
+    token: String,
+    subscribers: Mutex<Vec<mpsc::Sender<IpcMessage>>>,
+    last_transcript: Mutex<Option<String>>,
+    next_command: AtomicU64,
+    /// Relayed commands waiting for the frontend to finish them
+    replies: Mutex<HashMap<u64, mpsc::Sender<CommandReply>>>,
+}
+
+impl IpcServer {
+    pub fn new() -> Self {
+        Self {
+            token: uuid::Uuid::new_v4().simple().to_string(),
+            subscribers: Mutex::new(Vec::new()),
+            last_transcript: Mutex::new(None),
+            next_command: AtomicU64::new(1),
+            replies: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Bind the socket and publish the endpoint file
+    pub fn start<R: Runtime>(&self, app: AppHandle<R>) -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| AppError::Internal(format!("Failed to bind IPC socket: {}", e)))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| AppError::Internal(format!("Failed to read IPC socket address: {}", e)))?
+            .port();
+
+        let endpoint = IpcEndpoint {
+            port,
+            token: self.token.clone(),
+        };
+        let path = endpoint_file()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::Internal(format!("Failed to create IPC directory: {}", e))
+            })?;
+        }
+        let data = serde_json::to_vec(&endpoint)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize IPC endpoint: {}", e)))?;
+        std::fs::write(&path, data)
+            .map_err(|e| AppError::Internal(format!("Failed to write IPC endpoint: {}", e)))?;
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let app = app.clone();
+                        thread::spawn(move || handle_connection(app, stream));
+                    }
+                    Err(e) => warn!("IPC connection failed: {}", e),
+                }
+            }
+        });
+
+        info!("IPC server listening on 127.0.0.1:{}", port);
+        Ok(())
+    }
+
+    /// Remove the endpoint file so clients don't connect to a stale port
+    pub fn shutdown(&self) {
+        if let Ok(path) = endpoint_file() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Remember the latest transcript and push it to subscribers
+    pub fn publish_transcript(&self, text: String) {
+        if let Ok(mut last) = self.last_transcript.lock() {
+            *last = Some(text.clone());
+        }
+        self.broadcast(IpcMessage::Transcript { text: Some(text) });
+    }
+
+    pub fn broadcast(&self, message: IpcMessage) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            // Drop subscribers whose connection has gone away
+            subscribers.retain(|tx| tx.send(message.clone()).is_ok());
+        }
+    }
+
+    pub(crate) fn last_transcript(&self) -> Option<String> {
+        self.last_transcript.lock().ok().and_then(|last| last.clone())
+    }
+
+    pub(crate) fn subscribe(&self) -> mpsc::Receiver<IpcMessage> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    /// Pass on how a relayed command ended, returning whether it was waited for
+    pub fn finish_command(&self, id: u64, reply: CommandReply) -> bool {
+        let tx = self
+            .replies
+            .lock()
+            .ok()
+            .and_then(|mut replies| replies.remove(&id));
+        tx.is_some_and(|tx| tx.send(reply).is_ok())
+    }
+
+    /// A new command id, with the channel its reply arrives on
+    fn expect_reply(&self) -> (u64, mpsc::Receiver<CommandReply>) {
+        let id = self.next_command.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut replies) = self.replies.lock() {
+            replies.insert(id, tx);
+        }
+        (id, rx)
+    }
+
+    fn forget_reply(&self, id: u64) {
+        if let Ok(mut replies) = self.replies.lock() {
+            replies.remove(&id);
+        }
+    }
+}
+
+fn write_message(stream: &mut TcpStream, message: &IpcMessage) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stream.write_all(&line)
+}
+
+fn handle_connection<R: Runtime>(app: AppHandle<R>, stream: TcpStream) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            error!("Failed to clone IPC stream: {}", e);
+            return;
+        }
+    };
+    let ipc = app.state::<IpcServer>();
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<IpcRequest>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = write_message(
+                    &mut writer,
+                    &IpcMessage::Error {
+                        message: format!("Invalid request: {}", e),
+                    },
+                );
+                continue;
+            }
+        };
+
+        if request.token != ipc.token {
+            let _ = write_message(
+                &mut writer,
+                &IpcMessage::Error {
+                    message: "Invalid token".to_string(),
+                },
+            );
+            break;
+        }
+
+        debug!("IPC request: {:?}", request.command);
+        let response = match request.command {
+            IpcCommand::StartRecording => forward(&app, "startRecording"),
+            IpcCommand::StopRecording => forward(&app, "stopRecording"),
+            IpcCommand::ToggleRecording => forward(&app, "toggleRecording"),
+            IpcCommand::Status => status(&app),
+            IpcCommand::LastTranscript => IpcMessage::Transcript {
+                text: ipc.last_transcript(),
+            },
+            IpcCommand::TranscribeFile { path } => transcribe_file(&app, path),
+            IpcCommand::ShowUi => match daemon::show_ui(&app) {
+                Ok(()) => IpcMessage::Ok,
+                Err(e) => IpcMessage::Error {
+                    message: e.to_string(),
+                },
+            },
+            IpcCommand::Subscribe => {
+                let _ = write_message(&mut writer, &IpcMessage::Ok);
+                for message in ipc.subscribe() {
+                    if write_message(&mut writer, &message).is_err() {
+                        break;
+                    }
+                }
+                return;
+            }
+        };
+
+        if write_message(&mut writer, &response).is_err() {
+            break;
+        }
+    }
+}
+
+/// Recording is driven by the frontend, so commands are relayed as events
+/// and answered once the frontend has run them; a daemon without a UI
+/// records by itself
+pub(crate) fn forward<R: Runtime>(app: &AppHandle<R>, command: &'static str) -> IpcMessage {
+    if !daemon::has_ui(app) {
+        return match app.state::<Daemon>().record(app, command) {
+            Ok(()) => IpcMessage::Ok,
+            Err(e) => IpcMessage::Error {
+                message: e.to_string(),
+            },
+        };
+    }
+
+    let ipc = app.state::<IpcServer>();
+    let (id, reply) = ipc.expect_reply();
+    if let Err(e) = IpcCommandReceived::emit(app, &IpcCommandPayload { id, command }) {
+        ipc.forget_reply(id);
+        return IpcMessage::Error {
+            message: format!("Failed to forward command: {}", e),
+        };
+    }
+    match reply.recv_timeout(COMMAND_TIMEOUT) {
+        Ok(Ok(())) => IpcMessage::Ok,
+        Ok(Err(message)) => IpcMessage::Error { message },
+        Err(_) => {
+            ipc.forget_reply(id);
+            IpcMessage::Error {
+                message: format!("Whispering didn't finish {} in time", command),
+            }
+        }
+    }
+}
+
+/// Transcription runs in the frontend too; the file is checked here so the
+/// CLI can report a bad path
+fn transcribe_file<R: Runtime>(app: &AppHandle<R>, path: String) -> IpcMessage {
+    if let Err(e) = check_audio_file(Path::new(&path)) {
+        return IpcMessage::Error {
+            message: e.to_string(),
+        };
+    }
+    if let Err(e) = app
+        .state::<PendingJobs>()
+        .enqueue(&path, PendingSource::File)
+    {
+        warn!("Failed to queue {} for transcription: {}", path, e);
+    }
+    match TranscribeFileRequested::emit(app, &TranscribeFilePayload { path }) {
+        Ok(()) => IpcMessage::Ok,
+        Err(e) => IpcMessage::Error {
+            message: format!("Failed to forward command: {}", e),
+        },
+    }
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> IpcMessage {
+    let state = app.state::<AppData>();
+    let recording_id = match state.recorder.lock() {
+        Ok(recorder) => recorder.get_current_recording_id(),
+        Err(e) => {
+            return IpcMessage::Error {
+                message: format!("Failed to lock recorder: {}", e),
+            }
+        }
+    };
+    IpcMessage::Status {
+        recording: recording_id.is_some(),
+        recording_id,
+    }
+}
@@ -0,0 +1,42 @@
+use super::pending::{InterruptedJobs, PendingJobs};
+use super::Jobs;
+use crate::error::{AppError, Result};
+use crate::recorder::commands::AppData;
+use tauri::State;
+
+/// Cancel a transcription started with `job_id`, returning whether it was still running
+#[tauri::command]
+pub async fn cancel_job(job_id: String, jobs: State<'_, Jobs>) -> Result<bool> {
+    jobs.cancel(&job_id)
+}
+
+/// Transcriptions a previous run didn't finish, to resume or offer for
+/// retry; empty after the first call
+#[tauri::command]
+pub async fn take_interrupted_jobs(pending: State<'_, PendingJobs>) -> Result<InterruptedJobs> {
+    pending.take_interrupted()
+}
+
+/// Take a file off the transcription queue once its transcript is saved or
+/// has failed; without a path, the recording the desktop recorder saved
+/// last. Returns whether it was queued.
+#[tauri::command]
+pub async fn finish_pending_job(
+    path: Option<String>,
+    pending: State<'_, PendingJobs>,
+    data: State<'_, AppData>,
+) -> Result<bool> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let saved = data.last_saved.lock().map_err(|e| {
+                AppError::Internal(format!("Failed to lock saved recording: {}", e))
+            })?;
+            match saved.as_ref() {
+                Some(saved) => saved.path.to_string_lossy().to_string(),
+                None => return Ok(false),
+            }
+        }
+    };
+    pending.finish(&path)
+}
@@ -0,0 +1,160 @@
+//! Cancellable transcription jobs.
+//!
+//! Transcription commands take an optional job id. While the command runs,
+//! `cancel_job(id)` trips its `CancellationToken`, and the command returns
+//! `Cancelled` straight away:
+//!
+//! - HTTP requests are dropped mid-flight, which closes the connection and
+//!   aborts the upload or the wait for the provider's response.
+//! - Local transcription checks the token between stages (decoding the
+//!   audio, inference, punctuation), so nothing after the current stage runs.
+//!   transcribe-rs doesn't expose whisper.cpp's abort callback, so a decode
+//!   that has already started finishes on its blocking thread and its result
+//!   is thrown away.
+//!
+//! Files waiting to be transcribed are also queued on disk (see [`pending`]),
+//! so those a crash interrupts are resumed on the next launch.
+
+pub mod commands;
+pub mod pending;
+
+use crate::error::{AppError, Result};
+use crate::transcription::TranscriptionError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// Tokens of the jobs currently running, by job id
+pub struct Jobs {
+    /// Token and start number, so a guard only removes its own entry
+    running: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    started: AtomicU64,
+}
+
+impl Jobs {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(HashMap::new()),
+            started: AtomicU64::new(0),
+        }
+    }
+
+    /// Register a job, cancellable until the returned guard is dropped
+    ///
+    /// Jobs without an id still get a token, so the watchdog's
+    /// [`cancel_all`](Self::cancel_all) reaches them too.
+    pub fn start(&self, id: Option<String>) -> JobGuard<'_> {
+        let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let token = CancellationToken::new();
+        let number = self.started.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut running) = self.running.lock() {
+            if let Some((_, previous)) = running.insert(id.clone(), (number, token.clone())) {
+                // A reused id belongs to a job the caller has given up on
+                previous.cancel();
+            }
+        }
+        JobGuard {
+            jobs: self,
+            id,
+            number,
+            token,
+        }
+    }
+
+    /// Cancel a running job, returning whether there was one with this id
+    pub fn cancel(&self, id: &str) -> Result<bool> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock jobs: {}", e)))?;
+        match running.get(id) {
+            Some((_, token)) => {
+                info!("Cancelling job {}", id);
+                token.cancel();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Cancel every running job, returning how many there were
+    pub fn cancel_all(&self) -> usize {
+        let Ok(running) = self.running.lock() else {
+            return 0;
+        };
+        for (_, token) in running.values() {
+            token.cancel();
+        }
+        running.len()
+    }
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A running job, deregistered when dropped
+pub struct JobGuard<'a> {
+    jobs: &'a Jobs,
+    id: String,
+    number: u64,
+    token: CancellationToken,
+}
+
+impl JobGuard<'_> {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Run `future` until it finishes or the job is cancelled
+    pub async fn run<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        cancellable(&self.token, future).await
+    }
+}
+
+impl Drop for JobGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut running) = self.jobs.running.lock() {
+            // A reused id may have replaced our entry with a newer job's
+            if running.get(&self.id).is_some_and(|(number, _)| *number == self.number) {
+                running.remove(&self.id);
+            }
+        }
+    }
+}
+
+/// The error a cancelled job returns
+pub fn cancelled() -> TranscriptionError {
+    TranscriptionError::Cancelled {
+        message: "Transcription was cancelled".to_string(),
+    }
+}
+
+/// Fail with [`cancelled`] if `token` has been cancelled
+pub fn check(token: &CancellationToken) -> std::result::Result<(), TranscriptionError> {
+    if token.is_cancelled() {
+        Err(cancelled())
+    } else {
+        Ok(())
+    }
+}
+
+/// Run `future` unless `token` is cancelled first
+///
+/// On cancellation the future is dropped, which for reqwest closes the
+/// connection.
+pub async fn cancellable<T>(
+    token: &CancellationToken,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(cancelled().into()),
+        result = future => result,
+    }
+}
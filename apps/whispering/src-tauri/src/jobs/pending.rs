@@ -0,0 +1,168 @@
+//! Audio files waiting to be transcribed, kept on disk so a crash doesn't
+//! lose them.
+//!
+//! A file is queued when it's saved for transcription (a desktop recording
+//! stopping, the daemon queueing one, or `whispering transcribe <file>`) and
+//! finished once the frontend has its transcript or has reported failing to
+//! get one. Whatever is still queued when the app next starts was
+//! interrupted: it's handed to the frontend once, to resume straight away,
+//! or to offer for retry if resuming has already failed to finish a few
+//! times, which usually means the file itself brings the app down.
+
+use crate::error::{AppError, Result};
+use crate::history::now_millis;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+pub const PENDING_FILE: &str = "pending-jobs.json";
+
+/// Launches that may resume a job before it's only offered for retry
+pub const MAX_RESUMES: u32 = 2;
+
+/// Where a queued file came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingSource {
+    /// The desktop recorder, with a window open
+    Recording,
+    /// The daemon, waiting for a window to open
+    Daemon,
+    /// A file handed over by the CLI
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingJob {
+    pub path: String,
+    pub source: PendingSource,
+    /// Unix milliseconds
+    pub queued_at: i64,
+    /// Launches that have tried to resume it
+    #[serde(default)]
+    pub resumes: u32,
+}
+
+/// The jobs a previous run left unfinished
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedJobs {
+    /// To transcribe again now
+    pub resume: Vec<PendingJob>,
+    /// Resumed [`MAX_RESUMES`] times without finishing; only on request
+    pub retry: Vec<PendingJob>,
+}
+
+pub struct PendingJobs {
+    path: PathBuf,
+    jobs: Mutex<Vec<PendingJob>>,
+    /// Paths queued before this launch, until they're handed out
+    interrupted: Mutex<Option<Vec<String>>>,
+}
+
+impl PendingJobs {
+    pub fn open(path: &Path) -> Self {
+        let jobs: Vec<PendingJob> = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        if !jobs.is_empty() {
+            info!("{} transcriptions were interrupted", jobs.len());
+        }
+        let interrupted = jobs.iter().map(|job| job.path.clone()).collect();
+        Self {
+            path: path.to_path_buf(),
+            jobs: Mutex::new(jobs),
+            interrupted: Mutex::new(Some(interrupted)),
+        }
+    }
+
+    pub fn list(&self) -> Vec<PendingJob> {
+        self.jobs
+            .lock()
+            .map(|jobs| jobs.clone())
+            .unwrap_or_default()
+    }
+
+    /// Queue `path`; queueing it again keeps the original entry
+    pub fn enqueue(&self, path: &str, source: PendingSource) -> Result<()> {
+        self.modify(|jobs| {
+            if !jobs.iter().any(|job| job.path == path) {
+                jobs.push(PendingJob {
+                    path: path.to_string(),
+                    source,
+                    queued_at: now_millis(),
+                    resumes: 0,
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Take `path` off the queue, returning whether it was on it
+    pub fn finish(&self, path: &str) -> Result<bool> {
+        self.modify(|jobs| {
+            let before = jobs.len();
+            jobs.retain(|job| job.path != path);
+            Ok(jobs.len() < before)
+        })
+    }
+
+    /// The jobs queued before this launch, the first time it's called
+    ///
+    /// Jobs whose file has gone are dropped. Those handed out to resume
+    /// count the attempt, so one that keeps crashing the app is only
+    /// offered for retry after [`MAX_RESUMES`] launches.
+    pub fn take_interrupted(&self) -> Result<InterruptedJobs> {
+        let Some(paths) = self
+            .interrupted
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock pending jobs: {}", e)))?
+            .take()
+        else {
+            return Ok(InterruptedJobs::default());
+        };
+        self.modify(|jobs| {
+            jobs.retain(|job| {
+                let exists = Path::new(&job.path).is_file();
+                if !exists && paths.contains(&job.path) {
+                    warn!("Interrupted transcription of {} lost its file", job.path);
+                }
+                exists || !paths.contains(&job.path)
+            });
+            let mut interrupted = InterruptedJobs::default();
+            for job in jobs.iter_mut().filter(|job| paths.contains(&job.path)) {
+                if job.resumes < MAX_RESUMES {
+                    job.resumes += 1;
+                    interrupted.resume.push(job.clone());
+                } else {
+                    interrupted.retry.push(job.clone());
+                }
+            }
+            Ok(interrupted)
+        })
+    }
+
+    fn modify<T>(&self, f: impl FnOnce(&mut Vec<PendingJob>) -> Result<T>) -> Result<T> {
+        let mut jobs = self
+            .jobs
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock pending jobs: {}", e)))?;
+        let value = f(&mut jobs)?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*jobs)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize pending jobs: {}", e)))?;
+        // Written aside and renamed, so a crash mid-write can't lose the queue
+        let part = self.path.with_extension("json.part");
+        std::fs::write(&part, contents)?;
+        std::fs::rename(&part, &self.path)?;
+        Ok(value)
+    }
+}
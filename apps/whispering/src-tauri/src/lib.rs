@@ -4,12 +4,30 @@ use tauri_plugin_aptabase::EventTracker;
 
 pub mod recorder;
 use recorder::commands::{
-    cancel_recording, close_recording_session, enumerate_recording_devices,
-    get_current_recording_id, init_recording_session, start_recording, stop_recording, AppData,
+    cancel_recording, close_recording_session, enumerate_recording_devices, get_capture_memory,
+    get_current_recording_id, get_device_priority, get_input_config, init_recording_session,
+    list_audio_backends, prewarm_recording_device, resume_recording, set_device_priority,
+    set_input_config, start_recording, stop_recording, AppData,
 };
+use recorder::input::{InputConfigs, INPUT_FILE};
+use recorder::priority::{DevicePriorities, DEVICE_PRIORITY_FILE};
+
+pub mod presets;
+use presets::commands::{apply_preset, delete_preset, list_presets, save_preset};
+use presets::{Presets, PRESETS_FILE};
 
 pub mod transcription;
 use transcription::{transcribe_audio_whisper, transcribe_audio_parakeet, ModelManager};
+use transcription::benchmark::benchmark_providers;
+use transcription::compare::compare_transcripts;
+use transcription::language::detect_language;
+use transcription::remote::{
+    list_remote_models, measure_remote_latency, test_remote_server, transcribe_audio_remote,
+};
+use transcription::streaming::commands::{
+    get_keyword_alerts, set_keyword_alerts, start_streaming, stop_streaming,
+};
+use transcription::streaming::{KeywordAlerts, StreamingTranscriber, KEYWORDS_FILE};
 
 pub mod windows_path;
 use windows_path::fix_windows_path;
@@ -20,6 +38,258 @@ use graceful_shutdown::send_sigint;
 pub mod command;
 use command::{execute_command, spawn_command};
 
+pub mod history;
+use history::commands::{
+    apply_auto_tags, delete_history_entry, edit_transcript, get_auto_tag_rules, get_db_info,
+    get_history_entry, get_injection_record, get_latency_breakdown, list_history_entries,
+    list_tags, list_versions, record_latency, restore_version, retranscribe,
+    retranscribe_with_language, save_recording_to_history, set_auto_tag_rules, set_tags,
+    toggle_favorite,
+};
+use history::{HistoryStore, HISTORY_DB_FILE};
+
+pub mod watcher;
+use watcher::commands::{get_watched_folders, set_watched_folders};
+use watcher::{FolderWatcher, WATCHED_FOLDERS_FILE};
+
+#[cfg(feature = "http-api")]
+pub mod inbox;
+#[cfg(feature = "http-api")]
+use inbox::commands::{
+    get_inbox_status, list_inbox_entries, list_paired_devices, revoke_paired_device,
+    start_inbox_pairing, start_inbox_server, stop_inbox_server,
+};
+#[cfg(feature = "http-api")]
+use inbox::InboxServer;
+
+#[cfg(feature = "http-api")]
+pub mod discovery;
+#[cfg(feature = "http-api")]
+pub mod remote;
+#[cfg(feature = "http-api")]
+use remote::commands::{
+    get_remote_status, list_remote_devices, revoke_remote_device, start_remote_pairing,
+    start_remote_server, stop_remote_server,
+};
+#[cfg(feature = "http-api")]
+use remote::RemoteServer;
+
+pub mod ipc;
+use ipc::commands::{finish_ipc_command, publish_transcript};
+use ipc::IpcServer;
+
+pub mod session;
+
+pub mod native_messaging;
+use native_messaging::commands::install_native_messaging_host;
+
+pub mod cli;
+
+pub mod daemon;
+use daemon::commands::take_daemon_recordings;
+use daemon::Daemon;
+
+pub mod error;
+
+pub mod build_features;
+use build_features::get_build_features;
+
+pub mod events;
+
+pub mod app_state;
+use app_state::{get_app_state, report_app_state, AppState};
+
+pub mod tray;
+use tray::commands::{
+    get_tray_backend, get_tray_info, is_tray_supported, set_tray_badge, set_tray_icon,
+};
+use tray::TrayManager;
+
+pub mod i18n;
+use i18n::commands::{get_locale, get_tray_labels, set_locale};
+
+pub mod custom_assets;
+use custom_assets::commands::{check_custom_assets, load_custom_sound};
+
+pub mod settings;
+use settings::commands::{get_native_settings, get_policy, set_native_settings};
+use settings::{SettingsStore, SETTINGS_FILE};
+
+pub mod shortcut_capture;
+use shortcut_capture::capture_next_shortcut;
+
+pub mod raw_input;
+use raw_input::commands::{capture_media_button, list_pedals};
+
+pub mod hotkeys;
+use hotkeys::commands::{get_hotkey_config, set_hotkey_config};
+use hotkeys::{Hotkeys, HOTKEYS_FILE};
+
+pub mod monitors;
+use monitors::commands::list_monitors;
+#[cfg(feature = "overlay")]
+use monitors::commands::{get_overlay_placement, set_overlay_placement};
+#[cfg(feature = "overlay")]
+use monitors::overlay::OverlayManager;
+
+#[cfg(feature = "overlay")]
+pub mod captions;
+#[cfg(feature = "overlay")]
+use captions::commands::{get_captions_style, hide_captions, show_captions};
+#[cfg(feature = "overlay")]
+use captions::CaptionsWindow;
+
+pub mod waveform;
+use waveform::commands::get_waveform;
+
+pub mod playback;
+use playback::commands::{
+    pause_playback, play_recording, resume_playback, seek_playback, stop_playback,
+};
+use playback::PlaybackEngine;
+
+pub mod audio;
+
+pub mod editing;
+use editing::commands::{split_recording, trim_recording};
+
+pub mod focus;
+
+pub mod accessibility;
+
+pub mod jobs;
+use jobs::commands::{cancel_job, finish_pending_job, take_interrupted_jobs};
+use jobs::pending::{PendingJobs, PENDING_FILE};
+use jobs::Jobs;
+
+pub mod bulk;
+use bulk::commands::{bulk_delete, bulk_export, bulk_reprocess, cancel_bulk_job, export_all_data};
+use bulk::BulkJobs;
+
+pub mod timestamps;
+
+pub mod file_names;
+use file_names::commands::{name_saved_recording, preview_file_name};
+
+pub mod repair;
+use repair::commands::{check_recordings, repair_recording};
+
+pub mod import;
+use import::commands::import_history;
+
+pub mod chapters;
+use chapters::commands::get_chapters;
+
+pub mod stats;
+use stats::commands::get_stats;
+
+pub mod recap;
+use recap::commands::{generate_recap, get_recap_config, set_recap_config};
+use recap::{RecapScheduler, RECAP_FILE};
+
+pub mod notifications;
+
+pub mod privacy;
+
+pub mod watchdog;
+use privacy::check_network_request;
+
+pub mod reminders;
+use reminders::commands::{get_reminder_config, set_reminder_config};
+use reminders::{Reminders, REMINDERS_FILE};
+
+pub mod calendar;
+use calendar::commands::{
+    get_calendar_config, get_current_calendar_event, label_from_calendar, set_calendar_config,
+};
+use calendar::{Calendar, CALENDAR_FILE};
+
+pub mod punctuation;
+use punctuation::commands::{
+    get_punctuation_restoration, restore_punctuation, set_punctuation_restoration,
+};
+use punctuation::{Punctuator, PUNCTUATION_FILE, PUNCTUATION_MODEL_DIR};
+
+pub mod transforms;
+use transforms::commands::{
+    add_rule, apply_transforms, get_transform_config, preview_filler_removal, remove_rule,
+    reorder_rules, set_transform_config, test_rule, update_rule,
+};
+use transforms::{Transforms, TRANSFORMS_FILE};
+
+#[cfg(feature = "plugins")]
+pub mod plugins;
+#[cfg(feature = "plugins")]
+use plugins::commands::{
+    deliver_to_plugins, disable_plugin, enable_plugin, install_plugin, list_plugins,
+};
+#[cfg(feature = "plugins")]
+use plugins::{Plugins, PLUGINS_DIR, PLUGINS_FILE};
+
+pub mod scripts;
+use scripts::commands::{get_script_config, set_script_config, test_script_hook};
+use scripts::{Scripts, SCRIPTS_FILE};
+
+pub mod automation;
+use automation::commands::{get_automation_config, set_automation_config, test_automation_script};
+use automation::{Automation, AUTOMATION_FILE};
+
+pub mod corrections;
+use corrections::commands::{
+    accept_correction_suggestion, dismiss_correction_suggestion, list_correction_suggestions,
+    record_correction,
+};
+use corrections::{Corrections, CORRECTIONS_FILE};
+
+pub mod injection;
+use injection::commands::{
+    get_elevation_status, get_focused_app, get_injection_config, set_injection_config,
+    write_text,
+};
+use injection::{Injector, INJECTION_FILE};
+
+pub mod meetings;
+use meetings::commands::{
+    get_active_meetings, get_meeting_summary, list_meeting_apps, summarize_meeting,
+};
+use meetings::MeetingDetector;
+
+#[cfg(feature = "integrations")]
+pub mod integrations;
+#[cfg(feature = "integrations")]
+use integrations::journal::commands::{append_to_journal, commit_journal};
+#[cfg(feature = "integrations")]
+use integrations::obs::commands::{get_obs_config, set_obs_config, test_obs_connection};
+#[cfg(feature = "integrations")]
+use integrations::obs::{ObsIntegration, OBS_FILE};
+
+pub mod telemetry;
+use telemetry::commands::{get_pending_telemetry, record_feature_usage, set_telemetry_enabled};
+use telemetry::{Telemetry, TELEMETRY_FILE};
+
+pub mod clipboard_audio;
+use clipboard_audio::commands::get_clipboard_audio_path;
+
+// `crate::` because the module shares its name with the `tts` crate
+pub mod tts;
+use crate::tts::commands::{list_voices, speak, stop_speaking};
+use crate::tts::Speaker;
+
+pub mod backup;
+use backup::commands::{
+    get_backup_config, get_backup_status, restore_backup, run_backup_now, set_backup_config,
+    set_backup_passphrase,
+};
+use backup::{Backups, BACKUP_FILE};
+
+pub mod sync;
+use sync::commands::{get_sync_config, set_sync_config, sync_now};
+use sync::{DeviceSync, SYNC_FILE};
+
+pub mod share;
+use share::commands::{create_share, get_share_config, set_share_config};
+use share::{Sharing, SHARE_FILE};
+
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 #[tokio::main]
@@ -32,6 +302,19 @@ pub async fn run() {
     // This ensures child processes can find ffmpeg on Windows
     fix_windows_path();
     
+    let args = std::env::args().collect::<Vec<_>>();
+    let config_path = cli::config_path(&args);
+    let daemon = daemon::is_daemon_launch(&args);
+
+    // Launching again shows the instance in this login session; one in
+    // another session is left to its user
+    #[cfg(desktop)]
+    {
+        if session::hand_off() {
+            return;
+        }
+    }
+
     let mut builder = tauri::Builder::default();
 
     // Try to get APTABASE_KEY from environment, use empty string if not found
@@ -59,35 +342,433 @@ pub async fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .manage(AppData::new())
-        .manage(ModelManager::new());
+        .manage(ModelManager::new())
+        .manage(IpcServer::new())
+        .manage(AppState::new())
+        .manage(TrayManager::new())
+        .manage(PlaybackEngine::new())
+        .manage(Speaker::new())
+        .manage(BulkJobs::new())
+        .manage(Jobs::new())
+        .manage(StreamingTranscriber::new())
+        .manage(MeetingDetector::new())
+        .manage(Daemon::new(daemon))
+        .setup(move |app| {
+            app.state::<AppState>().forward_to_frontend(app.handle().clone());
+            watchdog::start(app.handle().clone());
+            #[cfg(desktop)]
+            session::watch(app.handle().clone());
+            accessibility::follow_app_state(app.handle().clone());
+            let tray = app.state::<TrayManager>();
+            if let Err(e) = tray.start(app.handle()) {
+                eprintln!("Failed to start tray: {}", e);
+            }
+            tray.follow_app_state(app.handle().clone());
+            #[cfg(feature = "overlay")]
+            {
+                app.state::<OverlayManager>().follow_app_state(app.handle().clone());
+                app.state::<CaptionsWindow>().follow_streaming(app.handle().clone());
+            }
+            app.state::<AppData>().follow_settings(app.handle().clone());
 
-    #[cfg(desktop)]
+            let data_dir = app.path().app_data_dir()?;
+            let policy = settings::policy::load();
+            let history = if policy.disable_history {
+                HistoryStore::open_in_memory()?
+            } else {
+                HistoryStore::open(&data_dir.join(HISTORY_DB_FILE))?
+            };
+            app.manage(history);
+            repair::check_on_startup(app.handle().clone());
+            app.manage(InputConfigs::open(&data_dir.join(INPUT_FILE)));
+            app.manage(DevicePriorities::open(&data_dir.join(DEVICE_PRIORITY_FILE)));
+            DevicePriorities::watch(app.handle().clone());
+            app.manage(Presets::open(&data_dir.join(PRESETS_FILE)));
+            app.manage(PendingJobs::open(&data_dir.join(PENDING_FILE)));
+            let settings_path = config_path.unwrap_or_else(|| data_dir.join(SETTINGS_FILE));
+            let settings = SettingsStore::open(&settings_path, policy);
+            // Tray-first startup: the webview is only created when first shown
+            let defer_ui = daemon || (settings.get().start_minimized && tray.capability().supported);
+            app.manage(settings);
+            tray.follow_settings(app.handle().clone());
+            i18n::follow_settings(app.handle().clone());
+            if defer_ui {
+                if let Err(e) = tray.create_native(app.handle()) {
+                    eprintln!("Failed to create tray: {}", e);
+                }
+            } else {
+                daemon::create_ui(app.handle())?;
+            }
+            if let Err(e) = app.state::<SettingsStore>().watch(app.handle().clone()) {
+                eprintln!("Failed to watch settings file: {}", e);
+            }
+            app.manage(RecapScheduler::open(&data_dir.join(RECAP_FILE)));
+            app.state::<RecapScheduler>().start(app.handle().clone());
+            app.manage(Reminders::open(&data_dir.join(REMINDERS_FILE)));
+            app.state::<Reminders>().start(app.handle().clone());
+            app.manage(Telemetry::open(&data_dir.join(TELEMETRY_FILE)));
+            app.state::<Telemetry>().start(app.handle().clone(), !aptabase_key.is_empty());
+            app.state::<MeetingDetector>().start(app.handle().clone());
+            app.manage(Hotkeys::open(&data_dir.join(HOTKEYS_FILE)));
+            app.state::<Hotkeys>().start(app.handle().clone());
+            app.manage(Calendar::open(&data_dir.join(CALENDAR_FILE)));
+            app.manage(Punctuator::open(
+                &data_dir.join(PUNCTUATION_FILE),
+                &data_dir.join(PUNCTUATION_MODEL_DIR),
+            ));
+            app.manage(Transforms::open(
+                &data_dir.join(TRANSFORMS_FILE),
+                app.state::<SettingsStore>().policy().profanity,
+            ));
+            #[cfg(feature = "plugins")]
+            {
+                app.manage(Plugins::open(
+                    &data_dir.join(PLUGINS_FILE),
+                    &data_dir.join(PLUGINS_DIR),
+                ));
+                let stage = app.state::<Plugins>().transform_stage();
+                app.state::<Transforms>().add_stage(stage);
+            }
+            app.manage(Scripts::open(&data_dir.join(SCRIPTS_FILE)));
+            let stage = app.state::<Scripts>().transform_stage();
+            app.state::<Transforms>().add_stage(stage);
+            app.manage(Automation::open(&data_dir.join(AUTOMATION_FILE)));
+            let stage = app
+                .state::<Automation>()
+                .transform_stage(app.handle().clone());
+            app.state::<Transforms>().add_stage(stage);
+            app.manage(Corrections::open(&data_dir.join(CORRECTIONS_FILE)));
+            app.manage(KeywordAlerts::open(&data_dir.join(KEYWORDS_FILE)));
+            app.manage(Injector::open(&data_dir.join(INJECTION_FILE)));
+            #[cfg(feature = "integrations")]
+            {
+                app.manage(ObsIntegration::open(&data_dir.join(OBS_FILE)));
+                app.state::<ObsIntegration>().start(app.handle().clone());
+            }
+            app.manage(Backups::open(&data_dir.join(BACKUP_FILE)));
+            app.state::<Backups>().start(app.handle().clone());
+            app.manage(DeviceSync::open(&data_dir.join(SYNC_FILE)));
+            app.state::<DeviceSync>().start(app.handle().clone());
+            app.manage(Sharing::open(&data_dir.join(SHARE_FILE)));
+            // After the transcription stages it runs files through
+            app.manage(FolderWatcher::open(&data_dir.join(WATCHED_FOLDERS_FILE)));
+            if let Err(e) = app.state::<FolderWatcher>().start(app.handle().clone()) {
+                eprintln!("Failed to watch folders: {}", e);
+            }
+
+            // Helper processes are optional, so a failed bind shouldn't stop startup
+            if let Err(e) = app.state::<IpcServer>().start(app.handle().clone()) {
+                eprintln!("Failed to start IPC server: {}", e);
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                // A daemon gives the webview's memory back instead of hiding it
+                if window.state::<Daemon>().is_enabled() {
+                    return;
+                }
+                if window.state::<TrayManager>().hide_on_close(window) {
+                    api.prevent_close();
+                }
+            }
+        });
+
+    #[cfg(feature = "http-api")]
+    {
+        builder = builder
+            .manage(InboxServer::new())
+            .manage(RemoteServer::new());
+    }
+
+    #[cfg(feature = "overlay")]
+    {
+        builder = builder
+            .manage(OverlayManager::new())
+            .manage(CaptionsWindow::new());
+    }
+
+    // Linux's lock is shared by all of a user's sessions, so there the hand-off
+    // above is the only one
+    #[cfg(all(desktop, not(target_os = "linux")))]
     {
         builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            let _ = app
-                .get_webview_window("main")
-                .expect("no main window")
-                .set_focus();
+            if let Err(e) = daemon::show_ui(app) {
+                eprintln!("Failed to show window: {}", e);
+            }
         }));
     }
 
     // Register command handlers (same for all platforms now)
     let builder = builder.invoke_handler(tauri::generate_handler![
+        // Optional subsystems compiled into this build
+        get_build_features,
+        // Text injection
         write_text,
+        get_injection_config,
+        set_injection_config,
+        get_focused_app,
+        get_elevation_status,
+        // Transcribing audio files from the clipboard or `whispering transcribe`
+        get_clipboard_audio_path,
+        // Text-to-speech
+        speak,
+        stop_speaking,
+        list_voices,
         // Audio recorder commands
         get_current_recording_id,
         enumerate_recording_devices,
         init_recording_session,
+        prewarm_recording_device,
         close_recording_session,
         start_recording,
+        resume_recording,
         stop_recording,
         cancel_recording,
+        get_capture_memory,
+        get_input_config,
+        set_input_config,
+        list_audio_backends,
+        get_device_priority,
+        set_device_priority,
+        // Recording presets
+        list_presets,
+        save_preset,
+        delete_preset,
+        apply_preset,
         transcribe_audio_whisper,
         transcribe_audio_parakeet,
         send_sigint,
+        // OpenAI-compatible self-hosted servers
+        transcribe_audio_remote,
+        list_remote_models,
+        test_remote_server,
+        measure_remote_latency,
+        benchmark_providers,
+        compare_transcripts,
+        detect_language,
+        // Punctuation restoration for local transcripts
+        get_punctuation_restoration,
+        set_punctuation_restoration,
+        restore_punctuation,
+        // Built-in transcript transforms
+        get_transform_config,
+        set_transform_config,
+        apply_transforms,
+        preview_filler_removal,
+        add_rule,
+        update_rule,
+        remove_rule,
+        test_rule,
+        reorder_rules,
+        // Third-party WebAssembly plugins
+        #[cfg(feature = "plugins")]
+        list_plugins,
+        #[cfg(feature = "plugins")]
+        install_plugin,
+        #[cfg(feature = "plugins")]
+        enable_plugin,
+        #[cfg(feature = "plugins")]
+        disable_plugin,
+        #[cfg(feature = "plugins")]
+        deliver_to_plugins,
+        // User scripts run on each transcript
+        get_script_config,
+        set_script_config,
+        test_script_hook,
+        // Rhai automation scripts
+        get_automation_config,
+        set_automation_config,
+        test_automation_script,
+        // Learning from transcript corrections
+        record_correction,
+        list_correction_suggestions,
+        accept_correction_suggestion,
+        dismiss_correction_suggestion,
+        // Live streaming transcription
+        start_streaming,
+        stop_streaming,
+        get_keyword_alerts,
+        set_keyword_alerts,
         // Command execution (prevents console window flash on Windows)
         execute_command,
         spawn_command,
+        // History
+        list_history_entries,
+        get_history_entry,
+        save_recording_to_history,
+        delete_history_entry,
+        get_db_info,
+        get_waveform,
+        retranscribe,
+        retranscribe_with_language,
+        list_versions,
+        edit_transcript,
+        restore_version,
+        set_tags,
+        toggle_favorite,
+        list_tags,
+        get_auto_tag_rules,
+        set_auto_tag_rules,
+        apply_auto_tags,
+        record_latency,
+        get_latency_breakdown,
+        get_injection_record,
+        get_chapters,
+        // Batch history operations
+        bulk_delete,
+        bulk_export,
+        bulk_reprocess,
+        cancel_bulk_job,
+        export_all_data,
+        cancel_job,
+        take_interrupted_jobs,
+        finish_pending_job,
+        // File name templates
+        preview_file_name,
+        name_saved_recording,
+        // Damaged recordings
+        check_recordings,
+        repair_recording,
+        // Importing from other dictation tools
+        import_history,
+        // Usage statistics and recaps
+        get_stats,
+        get_recap_config,
+        set_recap_config,
+        generate_recap,
+        // Break and daily reminders
+        get_reminder_config,
+        set_reminder_config,
+        // Opt-in usage telemetry
+        get_pending_telemetry,
+        set_telemetry_enabled,
+        record_feature_usage,
+        // Playback of stored recordings
+        play_recording,
+        pause_playback,
+        resume_playback,
+        seek_playback,
+        stop_playback,
+        // Editing stored recordings
+        trim_recording,
+        split_recording,
+        // Watched folder transcription
+        set_watched_folders,
+        get_watched_folders,
+        // Mobile voice memo inbox
+        #[cfg(feature = "http-api")]
+        start_inbox_server,
+        #[cfg(feature = "http-api")]
+        stop_inbox_server,
+        #[cfg(feature = "http-api")]
+        get_inbox_status,
+        #[cfg(feature = "http-api")]
+        start_inbox_pairing,
+        #[cfg(feature = "http-api")]
+        list_paired_devices,
+        #[cfg(feature = "http-api")]
+        revoke_paired_device,
+        #[cfg(feature = "http-api")]
+        list_inbox_entries,
+        // Remote control from a phone
+        #[cfg(feature = "http-api")]
+        start_remote_server,
+        #[cfg(feature = "http-api")]
+        stop_remote_server,
+        #[cfg(feature = "http-api")]
+        get_remote_status,
+        #[cfg(feature = "http-api")]
+        start_remote_pairing,
+        #[cfg(feature = "http-api")]
+        list_remote_devices,
+        #[cfg(feature = "http-api")]
+        revoke_remote_device,
+        // Helper process integration (browser extension, launchers)
+        publish_transcript,
+        finish_ipc_command,
+        install_native_messaging_host,
+        // Resident daemon without a UI
+        take_daemon_recordings,
+        // Recording lifecycle state
+        get_app_state,
+        report_app_state,
+        // Tray
+        set_tray_badge,
+        set_tray_icon,
+        is_tray_supported,
+        get_tray_backend,
+        get_tray_info,
+        // User-supplied tray icons and sounds
+        check_custom_assets,
+        load_custom_sound,
+        // Tray and notification language
+        get_locale,
+        set_locale,
+        get_tray_labels,
+        // Settings mirrored from the frontend
+        get_native_settings,
+        set_native_settings,
+        get_policy,
+        check_network_request,
+        // Shortcut capture
+        capture_next_shortcut,
+        // Double-tap and sequence hotkeys
+        get_hotkey_config,
+        set_hotkey_config,
+        list_pedals,
+        capture_media_button,
+        // Displays and recording overlay
+        list_monitors,
+        #[cfg(feature = "overlay")]
+        get_overlay_placement,
+        #[cfg(feature = "overlay")]
+        set_overlay_placement,
+        // Live captions window
+        #[cfg(feature = "overlay")]
+        show_captions,
+        #[cfg(feature = "overlay")]
+        hide_captions,
+        #[cfg(feature = "overlay")]
+        get_captions_style,
+        // Meeting detection
+        list_meeting_apps,
+        get_active_meetings,
+        // Meeting summaries
+        summarize_meeting,
+        get_meeting_summary,
+        // Calendar labels for recordings
+        get_calendar_config,
+        set_calendar_config,
+        get_current_calendar_event,
+        label_from_calendar,
+        // OBS Studio integration
+        #[cfg(feature = "integrations")]
+        get_obs_config,
+        #[cfg(feature = "integrations")]
+        set_obs_config,
+        #[cfg(feature = "integrations")]
+        test_obs_connection,
+        // Journal file, optionally kept in git
+        #[cfg(feature = "integrations")]
+        append_to_journal,
+        #[cfg(feature = "integrations")]
+        commit_journal,
+        // Encrypted backups to S3 or WebDAV
+        get_backup_config,
+        set_backup_config,
+        get_backup_status,
+        set_backup_passphrase,
+        run_backup_now,
+        restore_backup,
+        // Syncing history and settings between desktops
+        get_sync_config,
+        set_sync_config,
+        sync_now,
+        // Encrypted share links
+        get_share_config,
+        set_share_config,
+        create_share,
     ]);
 
     let app = builder
@@ -95,6 +776,18 @@ pub async fn run() {
         .expect("error while building tauri application");
 
     app.run(|handler, event| {
+        // Closing the last window leaves a daemon running; quitting still exits
+        if let tauri::RunEvent::ExitRequested { api, code: None, .. } = &event {
+            if handler.state::<Daemon>().is_enabled() {
+                api.prevent_exit();
+            }
+        }
+
+        if let tauri::RunEvent::Exit { .. } = event {
+            handler.state::<IpcServer>().shutdown();
+            handler.state::<TrayManager>().shutdown();
+        }
+
         // Only track events if Aptabase is enabled (key is not empty)
         if !aptabase_key.is_empty() {
             match event {
@@ -110,70 +803,3 @@ pub async fn run() {
         }
     });
 }
-
-use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-use tauri_plugin_clipboard_manager::ClipboardExt;
-
-/// Writes text at the cursor position using the clipboard sandwich technique
-///
-/// This method preserves the user's existing clipboard content by:
-/// 1. Saving the current clipboard content
-/// 2. Writing the new text to clipboard
-/// 3. Simulating a paste operation (Cmd+V on macOS, Ctrl+V elsewhere)
-/// 4. Restoring the original clipboard content
-///
-/// This approach is faster than typing character-by-character and preserves
-/// the user's clipboard, making it ideal for inserting transcribed text.
-#[tauri::command]
-async fn write_text(app: tauri::AppHandle, text: String) -> Result<(), String> {
-    // 1. Save current clipboard content
-    let original_clipboard = app.clipboard().read_text().ok();
-
-    // 2. Write new text to clipboard
-    app.clipboard()
-        .write_text(&text)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
-
-    // Small delay to ensure clipboard is updated
-    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-
-    // 3. Simulate paste operation using virtual key codes (layout-independent)
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
-    
-    // Use virtual key codes for V to work with any keyboard layout
-    #[cfg(target_os = "macos")]
-    let (modifier, v_key) = (Key::Meta, Key::Other(9)); // Virtual key code for V on macOS
-    #[cfg(target_os = "windows")]
-    let (modifier, v_key) = (Key::Control, Key::Other(0x56)); // VK_V on Windows
-    #[cfg(target_os = "linux")]
-    let (modifier, v_key) = (Key::Control, Key::Unicode('v')); // Fallback for Linux
-
-    // Press modifier + V
-    enigo
-        .key(modifier, Direction::Press)
-        .map_err(|e| format!("Failed to press modifier key: {}", e))?;
-    enigo
-        .key(v_key, Direction::Press)
-        .map_err(|e| format!("Failed to press V key: {}", e))?;
-    
-    // Release V + modifier (in reverse order for proper cleanup)
-    enigo
-        .key(v_key, Direction::Release)
-        .map_err(|e| format!("Failed to release V key: {}", e))?;
-    enigo
-        .key(modifier, Direction::Release)
-        .map_err(|e| format!("Failed to release modifier key: {}", e))?;
-
-    // Small delay to ensure paste completes
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-    // 4. Restore original clipboard content
-    if let Some(content) = original_clipboard {
-        app.clipboard()
-            .write_text(&content)
-            .map_err(|e| format!("Failed to restore clipboard: {}", e))?;
-    }
-
-    Ok(())
-}
-
@@ -2,5 +2,21 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Browsers launch this executable directly as the native messaging host
+    let args: Vec<String> = std::env::args().collect();
+    if whispering_lib::native_messaging::is_host_launch(&args) {
+        std::process::exit(whispering_lib::native_messaging::run_host());
+    }
+
+    // The elevated injection helper is this executable started as administrator
+    if whispering_lib::injection::helper::is_helper_launch(&args) {
+        std::process::exit(whispering_lib::injection::helper::run_helper(&args));
+    }
+
+    // Launcher subcommands talk to the running instance and exit
+    if let Some(code) = whispering_lib::cli::run(&args) {
+        std::process::exit(code);
+    }
+
     whispering_lib::run()
 }
@@ -0,0 +1,40 @@
+use super::summary::{self, SummarizeOptions, SummarizedMeeting};
+use super::{MeetingDetector, MEETING_APPS};
+use crate::error::Result;
+use crate::history::{HistoryStore, MeetingSummary};
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+/// Names of the meeting apps that can be detected, for the allow/deny lists
+#[tauri::command]
+pub async fn list_meeting_apps() -> Result<Vec<&'static str>> {
+    Ok(MEETING_APPS.iter().map(|app| app.name).collect())
+}
+
+/// Meeting apps that looked like they were in a meeting on the last check
+#[tauri::command]
+pub async fn get_active_meetings(
+    detector: State<'_, MeetingDetector>,
+) -> Result<Vec<&'static str>> {
+    Ok(detector.active())
+}
+
+/// Transcribe a meeting recording, summarize it with a chat model and store
+/// both in history
+#[tauri::command]
+pub async fn summarize_meeting(
+    audio_path: String,
+    options: SummarizeOptions,
+    app: AppHandle,
+) -> Result<SummarizedMeeting> {
+    summary::summarize_meeting(&app, &PathBuf::from(audio_path), &options).await
+}
+
+/// The stored summary of a history entry, if it has one
+#[tauri::command]
+pub async fn get_meeting_summary(
+    id: String,
+    history: State<'_, HistoryStore>,
+) -> Result<Option<MeetingSummary>> {
+    Ok(history.meeting_summary(&id)?)
+}
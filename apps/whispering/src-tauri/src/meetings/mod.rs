@@ -0,0 +1,253 @@
+//! Detecting conferencing apps in a meeting and offering to transcribe it.
+//!
+//! Meetings are recognised by heuristics: processes that only run during a
+//! call (Zoom's `CptHost`) and, on Windows, window titles. Browser-based
+//! meetings can only be seen through window titles, so on macOS and Linux
+//! only the native apps are detected.
+//!
+//! When a meeting starts, apps in `meetingAppsDenied` are ignored, apps in
+//! `meetingAppsAllowed` are transcribed straight away and the rest get a
+//! "Transcribe this meeting?" prompt. Accepting emits `meetings://start`,
+//! which the frontend answers by recording system audio. Once the meeting
+//! is recorded, `summary` can turn it into notes and action items.
+
+pub mod commands;
+pub mod summary;
+
+use crate::app_state::{AppState, AppStatus};
+use crate::events::{AppEvent, MeetingStarted};
+use crate::recorder::recorder::LOOPBACK_DEVICE;
+use crate::settings::{NativeSettings, SettingsStore};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+use tracing::{debug, info, warn};
+
+/// How often running apps are checked
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A conferencing app and how to tell it's in a meeting
+pub struct MeetingApp {
+    pub name: &'static str,
+    /// Process names (without `.exe`) that only run during a meeting
+    processes: &'static [&'static str],
+    /// Window title fragments shown during a meeting
+    window_titles: &'static [&'static str],
+}
+
+pub const MEETING_APPS: &[MeetingApp] = &[
+    MeetingApp {
+        name: "Zoom",
+        processes: &["CptHost"],
+        window_titles: &["Zoom Meeting", "Zoom Webinar"],
+    },
+    MeetingApp {
+        name: "Microsoft Teams",
+        processes: &[],
+        window_titles: &["(Meeting) | Microsoft Teams", "Meeting with"],
+    },
+    MeetingApp {
+        name: "Google Meet",
+        processes: &[],
+        window_titles: &["Meet - "],
+    },
+    MeetingApp {
+        name: "Webex",
+        processes: &["atmgr", "webexmta"],
+        window_titles: &["Webex Meeting"],
+    },
+    MeetingApp {
+        name: "Slack",
+        processes: &[],
+        window_titles: &["Huddle"],
+    },
+];
+
+/// Payload of `meetings://start`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingStart {
+    pub app: &'static str,
+    /// Device to record the meeting from, if the platform can capture system audio
+    pub device_identifier: Option<&'static str>,
+}
+
+/// What to do when `app` starts a meeting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MeetingRule {
+    Allow,
+    Deny,
+    Ask,
+}
+
+fn rule_for(settings: &NativeSettings, app: &str) -> MeetingRule {
+    let listed = |apps: &[String]| apps.iter().any(|a| a.trim().eq_ignore_ascii_case(app));
+    if listed(&settings.meeting_apps_denied) {
+        MeetingRule::Deny
+    } else if listed(&settings.meeting_apps_allowed) {
+        MeetingRule::Allow
+    } else {
+        MeetingRule::Ask
+    }
+}
+
+/// Watches for meetings and remembers which ones were already handled
+pub struct MeetingDetector {
+    active: Mutex<BTreeSet<&'static str>>,
+}
+
+impl MeetingDetector {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Poll for meetings in the background while detection is enabled
+    pub fn start(&self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                let settings = app.state::<SettingsStore>().get();
+                let detected = if settings.detect_meetings {
+                    tokio::task::spawn_blocking(active_meetings)
+                        .await
+                        .unwrap_or_default()
+                } else {
+                    BTreeSet::new()
+                };
+
+                let started: Vec<&'static str> = {
+                    let detector = app.state::<MeetingDetector>();
+                    let Ok(mut active) = detector.active.lock() else {
+                        continue;
+                    };
+                    let started = detected.difference(&active).copied().collect();
+                    *active = detected;
+                    started
+                };
+                for name in started {
+                    on_meeting_started(&app, &settings, name);
+                }
+            }
+        });
+    }
+
+    /// Meeting apps detected on the last check
+    pub fn active(&self) -> Vec<&'static str> {
+        self.active
+            .lock()
+            .map(|active| active.iter().copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn on_meeting_started(app: &AppHandle, settings: &NativeSettings, name: &'static str) {
+    let rule = rule_for(settings, name);
+    info!("{} meeting detected ({:?})", name, rule);
+    if rule == MeetingRule::Deny {
+        return;
+    }
+    // Don't interrupt a dictation in progress
+    if !matches!(app.state::<AppState>().current(), AppStatus::Idle) {
+        debug!("Busy, not offering to transcribe the {} meeting", name);
+        return;
+    }
+    if rule == MeetingRule::Allow {
+        start_transcribing(app, name);
+        return;
+    }
+
+    let handle = app.clone();
+    app.dialog()
+        .message(format!(
+            "{} meeting detected. Transcribe this meeting?",
+            name
+        ))
+        .title("Whispering")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Transcribe".to_string(),
+            "Not now".to_string(),
+        ))
+        .show(move |accepted| {
+            if accepted {
+                start_transcribing(&handle, name);
+            }
+        });
+}
+
+fn start_transcribing(app: &AppHandle, name: &'static str) {
+    let start = MeetingStart {
+        app: name,
+        device_identifier: cfg!(target_os = "windows").then_some(LOOPBACK_DEVICE),
+    };
+    if let Err(e) = MeetingStarted::emit(app, &start) {
+        warn!("Failed to start transcribing the {} meeting: {}", name, e);
+    }
+}
+
+/// Names of the meeting apps that currently look like they're in a meeting
+fn active_meetings() -> BTreeSet<&'static str> {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let processes: Vec<String> = system
+        .processes()
+        .values()
+        .map(|process| {
+            let name = process.name().to_string_lossy();
+            let name = name.strip_suffix(".exe").unwrap_or(&name);
+            name.to_ascii_lowercase()
+        })
+        .collect();
+    let titles = window_titles();
+
+    MEETING_APPS
+        .iter()
+        .filter(|app| {
+            app.processes
+                .iter()
+                .any(|p| processes.iter().any(|name| name.eq_ignore_ascii_case(p)))
+                || app
+                    .window_titles
+                    .iter()
+                    .any(|fragment| titles.iter().any(|title| title.contains(fragment)))
+        })
+        .map(|app| app.name)
+        .collect()
+}
+
+/// Titles of visible top-level windows
+#[cfg(target_os = "windows")]
+fn window_titles() -> Vec<String> {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowTextW, IsWindowVisible,
+    };
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let titles = &mut *(lparam as *mut Vec<String>);
+        if IsWindowVisible(hwnd) != 0 {
+            let mut buffer = [0u16; 512];
+            let len = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+            if len > 0 {
+                titles.push(String::from_utf16_lossy(&buffer[..len as usize]));
+            }
+        }
+        1
+    }
+
+    let mut titles: Vec<String> = Vec::new();
+    unsafe {
+        EnumWindows(Some(collect), &mut titles as *mut Vec<String> as LPARAM);
+    }
+    titles
+}
+
+#[cfg(not(target_os = "windows"))]
+fn window_titles() -> Vec<String> {
+    Vec::new()
+}
@@ -0,0 +1,335 @@
+//! Turning a meeting recording into a summary, decisions and action items.
+//!
+//! `summarize_meeting` runs the whole chain in one call: the audio is
+//! transcribed (with speaker labels when the transcriber can tell speakers
+//! apart), the transcript is sent to an OpenAI-compatible chat model, and
+//! both end up in history, the summary next to the entry it belongs to.
+//! Progress is reported on `meetings://summary-progress`.
+//!
+//! Local engines can't separate speakers, so their transcripts are
+//! summarized without speaker labels. Very long meetings may exceed the chat
+//! model's context; the model's error is passed on as is.
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, MeetingSummaryProgress};
+use crate::history::{
+    now_millis, ActionItem, HistoryEntry, HistorySource, HistoryStore, MeetingSummary,
+};
+use crate::privacy;
+use crate::transcription::remote::{
+    self, transcribe_remote, transcribe_remote_diarized, DiarizedSegment, RemoteServer,
+};
+use crate::transcription::{transcribe_local, LocalEngine, ModelManager};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const SYSTEM_PROMPT: &str = "You summarize meeting transcripts. Reply with a JSON object with \
+    these keys: \"summary\" (a few sentences), \"decisions\" (an array of strings, one per \
+    decision made) and \"actionItems\" (an array of objects with \"task\", and \"owner\" and \
+    \"due\" when the transcript says who and when, otherwise null). Only include decisions and \
+    action items that were actually agreed on. Write in the language of the transcript.";
+
+/// What transcribes the meeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MeetingTranscriber {
+    #[serde(rename_all = "camelCase")]
+    Local {
+        engine: LocalEngine,
+        model_path: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Remote {
+        server: RemoteServer,
+        model: String,
+        #[serde(default)]
+        language: Option<String>,
+        /// Ask for speaker labels; needs a diarizing model
+        #[serde(default)]
+        diarize: bool,
+    },
+}
+
+/// The chat model that writes the summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summarizer {
+    pub server: RemoteServer,
+    pub model: String,
+    /// Added to the instructions, e.g. "Our team is called Platform"
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarizeOptions {
+    pub transcriber: MeetingTranscriber,
+    pub summarizer: Summarizer,
+    /// Stored as the entry's title
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub attendees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SummaryStage {
+    Transcribing,
+    Summarizing,
+    Saving,
+}
+
+/// Payload of `meetings://summary-progress`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryProgress {
+    pub audio_path: String,
+    pub stage: SummaryStage,
+}
+
+/// The stored entry and its summary
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarizedMeeting {
+    pub entry: HistoryEntry,
+    pub summary: MeetingSummary,
+}
+
+/// A transcript ready to summarize
+struct MeetingTranscript {
+    text: String,
+    duration_seconds: f32,
+    provider: String,
+}
+
+/// The JSON object the chat model is asked for
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryReply {
+    summary: String,
+    #[serde(default)]
+    decisions: Vec<String>,
+    #[serde(default)]
+    action_items: Vec<ActionItem>,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+fn progress(app: &AppHandle, audio_path: &Path, stage: SummaryStage) {
+    let _ = MeetingSummaryProgress::emit(
+        app,
+        &SummaryProgress {
+            audio_path: audio_path.to_string_lossy().to_string(),
+            stage,
+        },
+    );
+}
+
+/// Speaker-labelled lines, one per turn, joining consecutive segments of the
+/// same speaker
+fn label_speakers(segments: &[DiarizedSegment]) -> String {
+    let mut turns: Vec<(String, String)> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((speaker, turn)) if *speaker == segment.speaker => {
+                turn.push(' ');
+                turn.push_str(text);
+            }
+            _ => turns.push((segment.speaker.clone(), text.to_string())),
+        }
+    }
+    turns
+        .iter()
+        .map(|(speaker, turn)| format!("Speaker {}: {}", speaker, turn))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn transcribe(
+    app: &AppHandle,
+    transcriber: &MeetingTranscriber,
+    audio_data: Vec<u8>,
+    file_name: &str,
+) -> Result<MeetingTranscript> {
+    match transcriber.clone() {
+        MeetingTranscriber::Local {
+            engine,
+            model_path,
+            language,
+        } => {
+            let app = app.clone();
+            let transcript = tokio::task::spawn_blocking(move || {
+                let model_manager = app.state::<ModelManager>();
+                transcribe_local(&model_manager, engine, audio_data, &model_path, language)
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("Meeting transcription panicked: {}", e)))??;
+            Ok(MeetingTranscript {
+                text: transcript.text,
+                duration_seconds: transcript.duration_seconds,
+                provider: engine.as_str().to_string(),
+            })
+        }
+        MeetingTranscriber::Remote {
+            server,
+            model,
+            language,
+            diarize: true,
+        } => {
+            let segments =
+                transcribe_remote_diarized(app, &server, audio_data, file_name, &model, language)
+                    .await?;
+            Ok(MeetingTranscript {
+                text: label_speakers(&segments),
+                duration_seconds: segments.last().map_or(0.0, |segment| segment.end),
+                provider: model,
+            })
+        }
+        MeetingTranscriber::Remote {
+            server,
+            model,
+            language,
+            diarize: false,
+        } => {
+            let text =
+                transcribe_remote(app, &server, audio_data, file_name, &model, language).await?;
+            Ok(MeetingTranscript {
+                text,
+                duration_seconds: 0.0,
+                provider: model,
+            })
+        }
+    }
+}
+
+/// Ask the chat model for a summary of `transcript`
+async fn summarize(
+    app: &AppHandle,
+    summarizer: &Summarizer,
+    transcript: &str,
+) -> Result<SummaryReply> {
+    let url = summarizer.server.url("chat/completions");
+    privacy::ensure_allowed(app, &url)?;
+    let system = match summarizer.instructions.as_deref().filter(|i| !i.is_empty()) {
+        Some(instructions) => format!("{}\n\n{}", SYSTEM_PROMPT, instructions),
+        None => SYSTEM_PROMPT.to_string(),
+    };
+    let body = serde_json::json!({
+        "model": summarizer.model,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": transcript },
+        ],
+        "response_format": { "type": "json_object" },
+    });
+
+    let response = summarizer
+        .server
+        .request(
+            &remote::client(remote::REQUEST_TIMEOUT)?,
+            reqwest::Method::POST,
+            &url,
+        )
+        .json(&body)
+        .send()
+        .await
+        .map_err(remote::network_error)?;
+    let chat: ChatResponse = remote::check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Unexpected chat response: {}", e)))?;
+    let content = chat
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content)
+        .ok_or_else(|| AppError::Network("The model returned no reply".to_string()))?;
+
+    // Some servers wrap JSON replies in a Markdown code block regardless
+    let json = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```");
+    serde_json::from_str(json).map_err(|e| {
+        warn!("Unreadable summary reply: {}", content);
+        AppError::Network(format!("The model's summary wasn't in the expected format: {}", e))
+    })
+}
+
+/// Transcribe and summarize the meeting recorded at `audio_path`, storing
+/// both in history
+pub async fn summarize_meeting(
+    app: &AppHandle,
+    audio_path: &Path,
+    options: &SummarizeOptions,
+) -> Result<SummarizedMeeting> {
+    let audio_data = std::fs::read(audio_path)?;
+    let file_name = audio_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "meeting.wav".to_string());
+    info!("Summarizing meeting {:?}", audio_path);
+
+    progress(app, audio_path, SummaryStage::Transcribing);
+    let transcript = transcribe(app, &options.transcriber, audio_data, &file_name).await?;
+    if transcript.text.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "No speech was found in the recording".to_string(),
+        ));
+    }
+
+    progress(app, audio_path, SummaryStage::Summarizing);
+    let reply = summarize(app, &options.summarizer, &transcript.text).await?;
+
+    progress(app, audio_path, SummaryStage::Saving);
+    let source_path = audio_path.to_string_lossy().to_string();
+    let mut entry = HistoryEntry::new(HistorySource::Recording, transcript.text);
+    entry.source_path = Some(source_path.clone());
+    entry.audio_path = Some(source_path);
+    entry.duration_seconds = transcript.duration_seconds;
+    entry.provider = Some(transcript.provider);
+    entry.title = options.title.clone();
+    entry.attendees = options.attendees.clone();
+    let summary = MeetingSummary {
+        entry_id: entry.id.clone(),
+        created_at: now_millis(),
+        model: Some(options.summarizer.model.clone()),
+        summary: reply.summary,
+        decisions: reply.decisions,
+        action_items: reply.action_items,
+    };
+    let history = app.state::<HistoryStore>();
+    history.insert(&entry)?;
+    history.set_meeting_summary(&summary)?;
+    info!(
+        "Summarized meeting {} with {} action items",
+        entry.id,
+        summary.action_items.len()
+    );
+    Ok(SummarizedMeeting { entry, summary })
+}
@@ -0,0 +1,30 @@
+use crate::error::Result;
+#[cfg(feature = "overlay")]
+use crate::monitors::overlay::{OverlayManager, OverlayPlacement};
+use crate::monitors::MonitorInfo;
+#[cfg(feature = "overlay")]
+use tauri::State;
+#[cfg(feature = "overlay")]
+use tracing::info;
+
+#[tauri::command]
+pub async fn list_monitors(app_handle: tauri::AppHandle) -> Result<Vec<MonitorInfo>> {
+    crate::monitors::list_monitors(&app_handle)
+}
+
+#[cfg(feature = "overlay")]
+#[tauri::command]
+pub async fn get_overlay_placement(overlay: State<'_, OverlayManager>) -> Result<OverlayPlacement> {
+    Ok(overlay.placement())
+}
+
+#[cfg(feature = "overlay")]
+#[tauri::command]
+pub async fn set_overlay_placement(
+    placement: OverlayPlacement,
+    overlay: State<'_, OverlayManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Setting overlay placement: {:?}", placement);
+    overlay.set_placement(&app_handle, placement)
+}
@@ -0,0 +1,106 @@
+pub mod commands;
+#[cfg(feature = "overlay")]
+pub mod overlay;
+
+use crate::error::{AppError, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Monitor};
+
+/// A connected display, in physical pixels
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    fn from_monitor(monitor: &Monitor, primary: Option<&Monitor>) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        Self {
+            name: monitor.name().cloned(),
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+            scale_factor: monitor.scale_factor(),
+            is_primary: primary.is_some_and(|p| same_monitor(p, monitor)),
+        }
+    }
+}
+
+fn same_monitor(a: &Monitor, b: &Monitor) -> bool {
+    a.position() == b.position() && a.size() == b.size()
+}
+
+pub fn list_monitors(app: &AppHandle) -> Result<Vec<MonitorInfo>> {
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| AppError::Internal(format!("Failed to get primary monitor: {}", e)))?;
+    let monitors = app
+        .available_monitors()
+        .map_err(|e| AppError::Internal(format!("Failed to list monitors: {}", e)))?;
+    Ok(monitors
+        .iter()
+        .map(|m| MonitorInfo::from_monitor(m, primary.as_ref()))
+        .collect())
+}
+
+/// The display the user is working on
+///
+/// On Windows this is the display holding the foreground window. Other
+/// platforms don't expose another app's window to us without extra
+/// permissions, so the display under the mouse cursor is used instead.
+pub fn active_monitor(app: &AppHandle) -> Result<Option<Monitor>> {
+    let (x, y) = match foreground_window_center() {
+        Some(point) => point,
+        None => {
+            let cursor = app
+                .cursor_position()
+                .map_err(|e| AppError::Internal(format!("Failed to get cursor position: {}", e)))?;
+            (cursor.x, cursor.y)
+        }
+    };
+
+    let monitor = app.monitor_from_point(x, y).map_err(|e| {
+        AppError::Internal(format!("Failed to find monitor at ({}, {}): {}", x, y, e))
+    })?;
+    match monitor {
+        Some(monitor) => Ok(Some(monitor)),
+        None => app
+            .primary_monitor()
+            .map_err(|e| AppError::Internal(format!("Failed to get primary monitor: {}", e))),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn foreground_window_center() -> Option<(f64, f64)> {
+    use windows_sys::Win32::Foundation::RECT;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        let mut rect: RECT = std::mem::zeroed();
+        if GetWindowRect(hwnd, &mut rect) == 0 {
+            return None;
+        }
+        Some((
+            (rect.left as f64 + rect.right as f64) / 2.0,
+            (rect.top as f64 + rect.bottom as f64) / 2.0,
+        ))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn foreground_window_center() -> Option<(f64, f64)> {
+    None
+}
@@ -0,0 +1,217 @@
+use super::active_monitor;
+use crate::app_state::{AppState, AppStatus};
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder,
+};
+use tracing::{debug, warn};
+
+/// Logical size of the recording indicator window
+const OVERLAY_WIDTH: f64 = 160.0;
+const OVERLAY_HEIGHT: f64 = 40.0;
+
+const OVERLAY_LABEL_PREFIX: &str = "overlay-";
+
+/// Which displays show the recording overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayDisplay {
+    /// The display with the focused window (or the cursor, see `active_monitor`)
+    Active,
+    Primary,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+/// Where the recording overlay appears - provided by the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayPlacement {
+    pub enabled: bool,
+    pub display: OverlayDisplay,
+    pub anchor: OverlayAnchor,
+    /// Distance from the screen edge, in logical pixels
+    pub margin: u32,
+}
+
+impl Default for OverlayPlacement {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            display: OverlayDisplay::Active,
+            anchor: OverlayAnchor::TopCenter,
+            margin: 24,
+        }
+    }
+}
+
+/// Shows a small always-on-top recording indicator while recording
+pub struct OverlayManager {
+    placement: Mutex<OverlayPlacement>,
+}
+
+impl OverlayManager {
+    pub fn new() -> Self {
+        Self {
+            placement: Mutex::new(OverlayPlacement::default()),
+        }
+    }
+
+    pub fn placement(&self) -> OverlayPlacement {
+        self.placement
+            .lock()
+            .map(|placement| placement.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_placement(&self, app: &AppHandle, placement: OverlayPlacement) -> Result<()> {
+        *self.placement.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock overlay placement: {}", e))
+        })? = placement;
+
+        // Re-place any visible overlay so changes apply immediately
+        if has_overlays(app) {
+            self.hide(app);
+            self.show(app)?;
+        }
+        Ok(())
+    }
+
+    /// Show the overlay while recording and hide it otherwise
+    pub fn follow_app_state(&self, app: AppHandle) {
+        let mut rx = app.state::<AppState>().subscribe();
+        tauri::async_runtime::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let recording = matches!(*rx.borrow_and_update(), AppStatus::Recording { .. });
+                let overlay = app.state::<OverlayManager>();
+                if !recording {
+                    overlay.hide(&app);
+                } else if let Err(e) = overlay.show(&app) {
+                    warn!("Failed to show recording overlay: {}", e);
+                }
+            }
+        });
+    }
+
+    pub fn show(&self, app: &AppHandle) -> Result<()> {
+        let placement = self.placement();
+        if !placement.enabled {
+            return Ok(());
+        }
+
+        for (index, monitor) in target_monitors(app, placement.display)?.iter().enumerate() {
+            let label = format!("{}{}", OVERLAY_LABEL_PREFIX, index);
+            let window = match app.get_webview_window(&label) {
+                Some(window) => window,
+                None => {
+                    WebviewWindowBuilder::new(app, &label, WebviewUrl::App("overlay.html".into()))
+                        .title("Whispering")
+                        .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+                        .decorations(false)
+                        .transparent(true)
+                        .shadow(false)
+                        .always_on_top(true)
+                        .skip_taskbar(true)
+                        .resizable(false)
+                        .focused(false)
+                        .visible(false)
+                        .visible_on_all_workspaces(true)
+                        .build()
+                        .map_err(|e| {
+                            AppError::Internal(format!("Failed to create overlay window: {}", e))
+                        })?
+                }
+            };
+
+            let scale = monitor.scale_factor();
+            let size = PhysicalSize::new(
+                (OVERLAY_WIDTH * scale).round() as u32,
+                (OVERLAY_HEIGHT * scale).round() as u32,
+            );
+            let position = overlay_position(monitor, size, &placement);
+            debug!(
+                "Placing {} at {:?} on {:?}",
+                label,
+                position,
+                monitor.name()
+            );
+
+            window
+                .set_size(size)
+                .and_then(|_| window.set_position(position))
+                .and_then(|_| window.set_ignore_cursor_events(true))
+                .and_then(|_| window.show())
+                .map_err(|e| {
+                    AppError::Internal(format!("Failed to place overlay window: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    pub fn hide(&self, app: &AppHandle) {
+        for (label, window) in app.webview_windows() {
+            if label.starts_with(OVERLAY_LABEL_PREFIX) {
+                if let Err(e) = window.close() {
+                    warn!("Failed to close {}: {}", label, e);
+                }
+            }
+        }
+    }
+}
+
+fn has_overlays(app: &AppHandle) -> bool {
+    app.webview_windows()
+        .keys()
+        .any(|label| label.starts_with(OVERLAY_LABEL_PREFIX))
+}
+
+fn target_monitors(app: &AppHandle, display: OverlayDisplay) -> Result<Vec<Monitor>> {
+    let monitor = match display {
+        OverlayDisplay::All => {
+            return Ok(app
+                .available_monitors()
+                .map_err(|e| AppError::Internal(format!("Failed to list monitors: {}", e)))?)
+        }
+        OverlayDisplay::Active => active_monitor(app)?,
+        OverlayDisplay::Primary => app
+            .primary_monitor()
+            .map_err(|e| AppError::Internal(format!("Failed to get primary monitor: {}", e)))?,
+    };
+    Ok(monitor.into_iter().collect())
+}
+
+/// Top-left corner of the overlay on `monitor`, in physical pixels
+fn overlay_position(
+    monitor: &Monitor,
+    size: PhysicalSize<u32>,
+    placement: &OverlayPlacement,
+) -> PhysicalPosition<i32> {
+    let origin = monitor.position();
+    let screen = monitor.size();
+    let margin = (placement.margin as f64 * monitor.scale_factor()).round() as i32;
+    let free_x = screen.width as i32 - size.width as i32;
+    let free_y = screen.height as i32 - size.height as i32;
+
+    let x = match placement.anchor {
+        OverlayAnchor::TopLeft | OverlayAnchor::BottomLeft => margin,
+        OverlayAnchor::TopCenter | OverlayAnchor::BottomCenter => free_x / 2,
+        OverlayAnchor::TopRight | OverlayAnchor::BottomRight => free_x - margin,
+    };
+    let y = match placement.anchor {
+        OverlayAnchor::TopLeft | OverlayAnchor::TopCenter | OverlayAnchor::TopRight => margin,
+        _ => free_y - margin,
+    };
+    PhysicalPosition::new(origin.x + x, origin.y + y)
+}
@@ -0,0 +1,14 @@
+use crate::error::Result;
+use crate::native_messaging::{install_manifest, Browser};
+use tracing::info;
+
+/// Install the native messaging host manifest so the companion extension can
+/// reach Whispering. Returns the path of the written manifest.
+#[tauri::command]
+pub async fn install_native_messaging_host(
+    browser: Browser,
+    extension_id: Option<String>,
+) -> Result<String> {
+    info!("Installing native messaging host for {:?}", browser);
+    install_manifest(browser, extension_id).map(|path| path.to_string_lossy().to_string())
+}
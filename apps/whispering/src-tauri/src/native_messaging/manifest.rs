@@ -0,0 +1,167 @@
+use super::HOST_NAME;
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::info;
+
+/// Browsers the host manifest can be installed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Edge,
+    Brave,
+    Firefox,
+}
+
+impl Browser {
+    fn is_firefox(&self) -> bool {
+        matches!(self, Browser::Firefox)
+    }
+
+    /// Directory the browser scans for host manifests
+    #[cfg(target_os = "macos")]
+    fn manifest_dir(&self) -> Option<PathBuf> {
+        let base = dirs::home_dir()?.join("Library/Application Support");
+        Some(match self {
+            Browser::Chrome => base.join("Google/Chrome/NativeMessagingHosts"),
+            Browser::Chromium => base.join("Chromium/NativeMessagingHosts"),
+            Browser::Edge => base.join("Microsoft Edge/NativeMessagingHosts"),
+            Browser::Brave => base.join("BraveSoftware/Brave-Browser/NativeMessagingHosts"),
+            Browser::Firefox => base.join("Mozilla/NativeMessagingHosts"),
+        })
+    }
+
+    /// Directory the browser scans for host manifests
+    #[cfg(target_os = "linux")]
+    fn manifest_dir(&self) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        Some(match self {
+            Browser::Chrome => home.join(".config/google-chrome/NativeMessagingHosts"),
+            Browser::Chromium => home.join(".config/chromium/NativeMessagingHosts"),
+            Browser::Edge => home.join(".config/microsoft-edge/NativeMessagingHosts"),
+            Browser::Brave => home.join(".config/BraveSoftware/Brave-Browser/NativeMessagingHosts"),
+            Browser::Firefox => home.join(".mozilla/native-messaging-hosts"),
+        })
+    }
+
+    /// On Windows the manifest can live anywhere; the registry points to it
+    #[cfg(target_os = "windows")]
+    fn manifest_dir(&self) -> Option<PathBuf> {
+        let name = serde_json::to_value(self).ok()?.as_str()?.to_string();
+        Some(
+            dirs::data_local_dir()?
+                .join(crate::ipc::APP_IDENTIFIER)
+                .join("native-messaging")
+                .join(name),
+        )
+    }
+
+    #[cfg(target_os = "windows")]
+    fn registry_key(&self) -> String {
+        let base = match self {
+            Browser::Chrome => r"Software\Google\Chrome",
+            Browser::Chromium => r"Software\Chromium",
+            Browser::Edge => r"Software\Microsoft\Edge",
+            Browser::Brave => r"Software\BraveSoftware\Brave-Browser",
+            Browser::Firefox => r"Software\Mozilla",
+        };
+        format!(r"HKCU\{}\NativeMessagingHosts\{}", base, HOST_NAME)
+    }
+}
+
+/// Host manifest as defined by the Chrome and Firefox native messaging docs
+#[derive(Serialize)]
+struct HostManifest {
+    name: &'static str,
+    description: &'static str,
+    path: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_origins: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_extensions: Option<Vec<String>>,
+}
+
+/// Write the host manifest for a browser, returning where it was written
+///
+/// The extension id defaults to the published companion extension, baked in
+/// at build time like the analytics key.
+pub fn install_manifest(browser: Browser, extension_id: Option<String>) -> Result<PathBuf> {
+    let default_id = if browser.is_firefox() {
+        option_env!("WHISPERING_FIREFOX_EXTENSION_ID")
+    } else {
+        option_env!("WHISPERING_CHROME_EXTENSION_ID")
+    };
+    let extension_id = extension_id
+        .or_else(|| default_id.map(str::to_string))
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| AppError::InvalidInput("No extension id configured for this build".to_string()))?;
+
+    let exe = std::env::current_exe()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve executable path: {}", e)))?;
+
+    let manifest = HostManifest {
+        name: HOST_NAME,
+        description: "Whispering dictation bridge",
+        path: exe.to_string_lossy().to_string(),
+        kind: "stdio",
+        allowed_origins: (!browser.is_firefox())
+            .then(|| vec![format!("chrome-extension://{}/", extension_id)]),
+        allowed_extensions: browser.is_firefox().then(|| vec![extension_id.clone()]),
+    };
+
+    let dir = browser.manifest_dir().ok_or_else(|| {
+        AppError::Internal("Failed to resolve native messaging directory".to_string())
+    })?;
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        AppError::Internal(format!(
+            "Failed to create native messaging directory: {}",
+            e
+        ))
+    })?;
+
+    let manifest_path = dir.join(format!("{}.json", HOST_NAME));
+    let data = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize host manifest: {}", e)))?;
+    std::fs::write(&manifest_path, data)
+        .map_err(|e| AppError::Internal(format!("Failed to write host manifest: {}", e)))?;
+
+    #[cfg(target_os = "windows")]
+    register_manifest(browser, &manifest_path)?;
+
+    info!("Installed native messaging host for {:?} at {:?}", browser, manifest_path);
+    Ok(manifest_path)
+}
+
+#[cfg(target_os = "windows")]
+fn register_manifest(browser: Browser, manifest_path: &std::path::Path) -> Result<()> {
+    use crate::command::CREATE_NO_WINDOW;
+    use std::os::windows::process::CommandExt;
+
+    let manifest_path = manifest_path.to_string_lossy().to_string();
+    let output = std::process::Command::new("reg")
+        .args([
+            "add",
+            &browser.registry_key(),
+            "/ve",
+            "/t",
+            "REG_SZ",
+            "/d",
+            &manifest_path,
+            "/f",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| AppError::Internal(format!("Failed to run reg: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::PermissionDenied(format!(
+            "Failed to register native messaging host: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Native messaging host for the companion browser extension.
+//!
+//! Browsers launch the Whispering executable directly as the host process and
+//! talk to it over stdin/stdout using length-prefixed JSON. The host does no
+//! recording itself; it relays requests to the running app over the IPC
+//! socket and streams transcripts back to the extension.
+
+pub mod commands;
+mod manifest;
+
+pub use manifest::{install_manifest, Browser};
+
+use crate::ipc::{IpcClient, IpcCommand, IpcMessage};
+use serde::Deserialize;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Name the extension uses with `chrome.runtime.connectNative`
+pub const HOST_NAME: &str = "com.bradenwong.whispering";
+
+/// Chrome limits messages sent to the host to 4 GB, but anything beyond a
+/// few KB from the extension is a bug
+const MAX_INCOMING_MESSAGE_BYTES: usize = 1024 * 1024;
+
+/// Message sent by the extension
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ExtensionRequest {
+    /// Start dictating into the focused text field
+    StartDictation,
+    StopDictation,
+    ToggleDictation,
+    Status,
+}
+
+/// Whether the process was launched by a browser as a native messaging host
+///
+/// Chrome passes the calling extension's origin as the first argument, while
+/// Firefox passes the path of the host manifest followed by the extension id.
+pub fn is_host_launch(args: &[String]) -> bool {
+    match args.get(1) {
+        Some(arg) if arg.starts_with("chrome-extension://") => true,
+        Some(arg) => arg.ends_with(&format!("{}.json", HOST_NAME)),
+        None => false,
+    }
+}
+
+/// Run the host loop until the browser closes stdin, returning the exit code
+pub fn run_host() -> i32 {
+    let stdout = Arc::new(Mutex::new(io::stdout()));
+
+    // Forward transcripts pushed by the app for as long as the host lives
+    {
+        let stdout = stdout.clone();
+        thread::spawn(move || {
+            let Ok(mut client) = IpcClient::connect() else { return };
+            if client.request(IpcCommand::Subscribe).is_err() || client.keep_listening().is_err() {
+                return;
+            }
+            while let Ok(message) = client.read_message() {
+                if send_message(&stdout, &message).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let mut stdin = io::stdin();
+    loop {
+        let request = match read_message(&mut stdin) {
+            Ok(Some(request)) => request,
+            Ok(None) => return 0,
+            Err(e) => {
+                let _ = send_message(&stdout, &IpcMessage::Error { message: e });
+                return 1;
+            }
+        };
+
+        let response = match serde_json::from_slice::<ExtensionRequest>(&request) {
+            Ok(request) => relay(request),
+            Err(e) => IpcMessage::Error {
+                message: format!("Invalid message: {}", e),
+            },
+        };
+
+        if send_message(&stdout, &response).is_err() {
+            return 1;
+        }
+    }
+}
+
+fn relay(request: ExtensionRequest) -> IpcMessage {
+    let command = match request {
+        ExtensionRequest::StartDictation => IpcCommand::StartRecording,
+        ExtensionRequest::StopDictation => IpcCommand::StopRecording,
+        ExtensionRequest::ToggleDictation => IpcCommand::ToggleRecording,
+        ExtensionRequest::Status => IpcCommand::Status,
+    };
+
+    IpcClient::connect()
+        .and_then(|mut client| client.request(command))
+        .unwrap_or_else(|e| IpcMessage::Error {
+            message: e.to_string(),
+        })
+}
+
+/// Read one length-prefixed message, returning `None` once stdin is closed
+fn read_message(stdin: &mut impl Read) -> Result<Option<Vec<u8>>, String> {
+    let mut len_bytes = [0u8; 4];
+    match stdin.read_exact(&mut len_bytes) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(format!("Failed to read message length: {}", e)),
+    }
+
+    // Native messaging uses native byte order for the length prefix
+    let len = u32::from_ne_bytes(len_bytes) as usize;
+    if len > MAX_INCOMING_MESSAGE_BYTES {
+        return Err(format!("Message too large: {} bytes", len));
+    }
+
+    let mut message = vec![0u8; len];
+    stdin
+        .read_exact(&mut message)
+        .map_err(|e| format!("Failed to read message: {}", e))?;
+    Ok(Some(message))
+}
+
+fn send_message(stdout: &Mutex<io::Stdout>, message: &IpcMessage) -> io::Result<()> {
+    let data = serde_json::to_vec(message)?;
+    let mut stdout = stdout
+        .lock()
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "stdout lock poisoned"))?;
+    stdout.write_all(&(data.len() as u32).to_ne_bytes())?;
+    stdout.write_all(&data)?;
+    stdout.flush()
+}
@@ -0,0 +1,18 @@
+//! Native notifications shown by Rust-side features (tray, recaps, reminders).
+
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_notification::NotificationExt;
+use tracing::warn;
+
+/// Show a native notification titled "Whispering"
+pub fn notify<R: Runtime>(app: &AppHandle<R>, body: &str) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Whispering")
+        .body(body)
+        .show()
+    {
+        warn!("Failed to show notification: {}", e);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::error::Result;
+use crate::history::HistoryStore;
+use crate::playback::PlaybackEngine;
+use tauri::State;
+
+/// Play a history entry's audio, optionally starting part way through.
+/// Progress is reported through `playback://position` events.
+#[tauri::command]
+pub async fn play_recording(
+    id: String,
+    from_seconds: Option<f64>,
+    playback: State<'_, PlaybackEngine>,
+    history: State<'_, HistoryStore>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    playback.play(&app_handle, &history, &id, from_seconds.unwrap_or(0.0))
+}
+
+#[tauri::command]
+pub async fn pause_playback(
+    playback: State<'_, PlaybackEngine>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    playback.pause(&app_handle)
+}
+
+#[tauri::command]
+pub async fn resume_playback(
+    playback: State<'_, PlaybackEngine>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    playback.resume(&app_handle)
+}
+
+#[tauri::command]
+pub async fn seek_playback(
+    seconds: f64,
+    playback: State<'_, PlaybackEngine>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    playback.seek(&app_handle, seconds)
+}
+
+#[tauri::command]
+pub async fn stop_playback(
+    playback: State<'_, PlaybackEngine>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    playback.stop(&app_handle)
+}
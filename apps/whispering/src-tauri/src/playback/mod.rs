@@ -0,0 +1,238 @@
+pub mod commands;
+
+use crate::audio::{decode_file, DecodedAudio};
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, PlaybackEnded, PlaybackPosition};
+use crate::history::HistoryStore;
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, Sink};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{error, info, warn};
+
+/// How often `playback://position` is emitted while playing
+const POSITION_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Payload of the `playback://position` and `playback://ended` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackStatus {
+    pub id: String,
+    pub position_seconds: f64,
+    pub duration_seconds: f64,
+    pub playing: bool,
+}
+
+/// Decoded audio ready to hand to the output device
+struct Track {
+    id: String,
+    audio: DecodedAudio,
+}
+
+enum PlaybackCommand {
+    Play { track: Track, from_seconds: f64 },
+    Pause,
+    Resume,
+    Seek(f64),
+    Stop,
+}
+
+/// Plays history audio through the default output device
+///
+/// rodio's output stream can't move between threads, so a dedicated thread
+/// owns it and is driven through a channel.
+pub struct PlaybackEngine {
+    tx: Mutex<Option<Sender<PlaybackCommand>>>,
+}
+
+impl PlaybackEngine {
+    pub fn new() -> Self {
+        Self {
+            tx: Mutex::new(None),
+        }
+    }
+
+    /// Decode a history entry's audio and start playing it
+    pub fn play(
+        &self,
+        app: &AppHandle,
+        history: &HistoryStore,
+        id: &str,
+        from_seconds: f64,
+    ) -> Result<()> {
+        let entry = history.get(id)?;
+        let audio_path = entry
+            .audio_path
+            .ok_or_else(|| AppError::InvalidInput(format!("History entry {} has no audio", id)))?;
+        let track = Track {
+            id: id.to_string(),
+            audio: decode_file(Path::new(&audio_path))?,
+        };
+        info!(
+            "Playing {} from {:.1}s ({:.1}s long)",
+            id,
+            from_seconds,
+            track.audio.duration_seconds()
+        );
+        self.send(
+            app,
+            PlaybackCommand::Play {
+                track,
+                from_seconds,
+            },
+        )
+    }
+
+    pub fn pause(&self, app: &AppHandle) -> Result<()> {
+        self.send(app, PlaybackCommand::Pause)
+    }
+
+    pub fn resume(&self, app: &AppHandle) -> Result<()> {
+        self.send(app, PlaybackCommand::Resume)
+    }
+
+    pub fn seek(&self, app: &AppHandle, seconds: f64) -> Result<()> {
+        self.send(app, PlaybackCommand::Seek(seconds))
+    }
+
+    pub fn stop(&self, app: &AppHandle) -> Result<()> {
+        self.send(app, PlaybackCommand::Stop)
+    }
+
+    fn send(&self, app: &AppHandle, command: PlaybackCommand) -> Result<()> {
+        let mut tx = self
+            .tx
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock playback engine: {}", e)))?;
+        if tx.is_none() {
+            *tx = Some(spawn_player(app.clone())?);
+        }
+        let sender = tx.as_ref().expect("player thread was just started");
+        if let Err(mpsc::SendError(command)) = sender.send(command) {
+            // The player thread exited (e.g. the device disappeared); start a new one
+            warn!("Playback thread stopped, restarting");
+            let sender = spawn_player(app.clone())?;
+            sender
+                .send(command)
+                .map_err(|_| AppError::Internal("Playback thread is not running".to_string()))?;
+            *tx = Some(sender);
+        }
+        Ok(())
+    }
+}
+
+fn spawn_player(app: AppHandle) -> Result<Sender<PlaybackCommand>> {
+    let (tx, rx) = mpsc::channel::<PlaybackCommand>();
+    let (ready_tx, ready_rx) = mpsc::channel::<std::result::Result<(), String>>();
+
+    thread::spawn(move || {
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => {
+                let _ = ready_tx.send(Ok(()));
+                output
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(format!("No audio output device: {}", e)));
+                return;
+            }
+        };
+
+        let mut current: Option<(Sink, String, f64)> = None;
+        loop {
+            let command = match rx.recv_timeout(POSITION_INTERVAL) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            match command {
+                Some(PlaybackCommand::Play {
+                    track,
+                    from_seconds,
+                }) => {
+                    let sink = match Sink::try_new(&handle) {
+                        Ok(sink) => sink,
+                        Err(e) => {
+                            error!("Failed to open playback sink: {}", e);
+                            continue;
+                        }
+                    };
+                    let duration = track.audio.duration_seconds();
+                    sink.append(SamplesBuffer::new(
+                        track.audio.channels,
+                        track.audio.sample_rate,
+                        track.audio.samples,
+                    ));
+                    if from_seconds > 0.0 {
+                        if let Err(e) = sink.try_seek(Duration::from_secs_f64(from_seconds)) {
+                            warn!("Failed to seek to {:.1}s: {}", from_seconds, e);
+                        }
+                    }
+                    // Dropping the previous sink stops it
+                    current = Some((sink, track.id, duration));
+                }
+                Some(PlaybackCommand::Pause) => {
+                    if let Some((sink, ..)) = &current {
+                        sink.pause();
+                    }
+                }
+                Some(PlaybackCommand::Resume) => {
+                    if let Some((sink, ..)) = &current {
+                        sink.play();
+                    }
+                }
+                Some(PlaybackCommand::Seek(seconds)) => {
+                    if let Some((sink, ..)) = &current {
+                        if let Err(e) = sink.try_seek(Duration::from_secs_f64(seconds.max(0.0))) {
+                            warn!("Failed to seek to {:.1}s: {}", seconds, e);
+                        }
+                    }
+                }
+                Some(PlaybackCommand::Stop) => {
+                    if let Some((sink, id, duration)) = current.take() {
+                        let status = status(&sink, id, duration);
+                        let _ = PlaybackEnded::emit(&app, &status);
+                    }
+                }
+                None => {}
+            }
+
+            if let Some((sink, id, duration)) = &current {
+                if sink.empty() {
+                    let ended = PlaybackStatus {
+                        id: id.clone(),
+                        position_seconds: *duration,
+                        duration_seconds: *duration,
+                        playing: false,
+                    };
+                    let _ = PlaybackEnded::emit(&app, &ended);
+                    current = None;
+                } else {
+                    let _ = PlaybackPosition::emit(&app, &status(sink, id.clone(), *duration));
+                }
+            }
+        }
+    });
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(tx),
+        Ok(Err(e)) => Err(AppError::DeviceNotFound(e)),
+        Err(_) => Err(AppError::Internal(
+            "Playback thread exited during startup".to_string(),
+        )),
+    }
+}
+
+fn status(sink: &Sink, id: String, duration: f64) -> PlaybackStatus {
+    PlaybackStatus {
+        id,
+        position_seconds: sink.get_pos().as_secs_f64().min(duration),
+        duration_seconds: duration,
+        playing: !sink.is_paused() && !sink.empty(),
+    }
+}
@@ -0,0 +1,36 @@
+use super::{Delivery, PluginInfo, Plugins};
+use crate::error::Result;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn list_plugins(plugins: State<'_, Plugins>) -> Result<Vec<PluginInfo>> {
+    Ok(plugins.list())
+}
+
+/// Install the plugin at `path`, a folder or its `plugin.toml`
+#[tauri::command]
+pub async fn install_plugin(path: String, plugins: State<'_, Plugins>) -> Result<PluginInfo> {
+    plugins.install(&PathBuf::from(path))
+}
+
+#[tauri::command]
+pub async fn enable_plugin(id: String, plugins: State<'_, Plugins>) -> Result<PluginInfo> {
+    plugins.set_enabled(&id, true)
+}
+
+#[tauri::command]
+pub async fn disable_plugin(id: String, plugins: State<'_, Plugins>) -> Result<PluginInfo> {
+    plugins.set_enabled(&id, false)
+}
+
+/// Hand a delivered transcript to the enabled destination plugins
+#[tauri::command]
+pub async fn deliver_to_plugins(
+    text: String,
+    entry_id: Option<String>,
+    plugins: State<'_, Plugins>,
+    app: AppHandle,
+) -> Result<Vec<Delivery>> {
+    plugins.deliver(&app, &text, entry_id.as_deref()).await
+}
@@ -0,0 +1,356 @@
+//! Runs plugin modules with wasmtime.
+//!
+//! A plugin may import these from the `whispering` module, and nothing else:
+//!
+//! - `log(level, ptr, len)`: write to the app log, level 0 (debug) to 3
+//!   (error)
+//! - `set_error(ptr, len)`: the message to report when `deliver` fails
+//! - `http_post(url_ptr, url_len, body_ptr, body_len) -> i32`: POST a JSON
+//!   body to a host listed in the manifest's `capabilities.http`, returning
+//!   the status code, or -1 if the request was refused or failed. Only
+//!   destinations get to send anything; during `transform`, which runs
+//!   inline with the pipeline, it always returns -1. Privacy mode refuses
+//!   every host but this machine.
+
+use super::{PluginKind, PluginManifest};
+use crate::error::{AppError, Result};
+use crate::privacy;
+use std::path::Path;
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{debug, error, info, warn};
+use wasmtime::{
+    Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, Trap,
+};
+
+const IMPORT_MODULE: &str = "whispering";
+
+/// Linear memory a plugin instance may grow to
+const MEMORY_LIMIT: usize = 64 << 20;
+
+/// Fuel per call, roughly the number of wasm instructions it may execute
+const TRANSFORM_FUEL: u64 = 1_000_000_000;
+const DELIVER_FUEL: u64 = 2_000_000_000;
+
+/// Largest string passed across the boundary either way
+const MAX_IO: usize = 4 << 20;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Lets `http_post` reach the hosts a destination was granted
+struct Http {
+    app: AppHandle,
+    hosts: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl Http {
+    fn post(&self, url: &str, body: Vec<u8>) -> Result<i32> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid URL {}: {}", url, e)))?;
+        let host = parsed.host_str().unwrap_or_default();
+        if !self
+            .hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+        {
+            return Err(AppError::PermissionDenied(format!(
+                "{} isn't one of the plugin's hosts",
+                host
+            )));
+        }
+        if parsed.scheme() != "https" && !privacy::is_local_url(url) {
+            return Err(AppError::PermissionDenied(format!(
+                "Plugins may only use HTTPS to reach {}",
+                host
+            )));
+        }
+        privacy::ensure_allowed(&self.app, url)?;
+
+        let request = self
+            .client
+            .post(parsed)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .timeout(HTTP_TIMEOUT)
+            .body(body)
+            .send();
+        // Plugin calls run on blocking threads, where waiting is fine
+        let response = tauri::async_runtime::block_on(request)
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        Ok(i32::from(response.status().as_u16()))
+    }
+}
+
+struct HostState {
+    limits: StoreLimits,
+    plugin: String,
+    /// Set by the plugin through `set_error`
+    error: Option<String>,
+    http: Option<Http>,
+}
+
+pub struct Runtime {
+    engine: Engine,
+}
+
+impl Runtime {
+    pub fn new() -> Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to start the plugin runtime: {}", e))
+        })?;
+        Ok(Self { engine })
+    }
+
+    /// Compile the module at `path` and check it against `manifest`,
+    /// returning which interfaces it implements
+    pub fn load(
+        &self,
+        path: &Path,
+        manifest: &PluginManifest,
+    ) -> Result<(Module, Vec<PluginKind>)> {
+        let module = Module::from_file(&self.engine, path)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid plugin module: {:#}", e)))?;
+
+        for import in module.imports() {
+            let granted = match (import.module(), import.name()) {
+                (IMPORT_MODULE, "log" | "set_error") => true,
+                (IMPORT_MODULE, "http_post") => !manifest.capabilities.http.is_empty(),
+                _ => false,
+            };
+            if !granted {
+                return Err(AppError::PermissionDenied(format!(
+                    "The module imports {}::{}, which the manifest's capabilities don't grant",
+                    import.module(),
+                    import.name()
+                )));
+            }
+        }
+
+        let exports: Vec<&str> = module.exports().map(|export| export.name()).collect();
+        for required in ["memory", "alloc"] {
+            if !exports.contains(&required) {
+                return Err(AppError::InvalidInput(format!(
+                    "The module doesn't export `{}`",
+                    required
+                )));
+            }
+        }
+        let mut kinds = Vec::new();
+        if exports.contains(&"transform") {
+            kinds.push(PluginKind::Transform);
+        }
+        if exports.contains(&"deliver") {
+            kinds.push(PluginKind::Destination);
+        }
+        if kinds.is_empty() {
+            return Err(AppError::InvalidInput(
+                "The module exports neither `transform` nor `deliver`".to_string(),
+            ));
+        }
+        Ok((module, kinds))
+    }
+
+    pub fn transform(&self, module: &Module, plugin: &str, text: &str) -> Result<String> {
+        let (mut store, instance) = self.instantiate(module, plugin, None, TRANSFORM_FUEL)?;
+        let (memory, ptr, len) = write_input(&mut store, &instance, text.as_bytes())?;
+        let transform = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "transform")
+            .map_err(|e| plugin_error(plugin, e))?;
+        let packed = transform
+            .call(&mut store, (ptr, len))
+            .map_err(|e| plugin_error(plugin, e))?;
+
+        let ptr = (packed >> 32) as u32 as usize;
+        let len = packed as u32 as usize;
+        if len > MAX_IO {
+            return Err(AppError::Internal(format!(
+                "Plugin {} returned {} bytes, more than allowed",
+                plugin, len
+            )));
+        }
+        let bytes = memory.data(&store).get(ptr..ptr + len).ok_or_else(|| {
+            AppError::Internal(format!("Plugin {} returned text out of bounds", plugin))
+        })?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| AppError::Internal(format!("Plugin {} returned invalid UTF-8", plugin)))
+    }
+
+    pub fn deliver(
+        &self,
+        app: &AppHandle,
+        module: &Module,
+        manifest: &PluginManifest,
+        payload: &str,
+    ) -> Result<()> {
+        let plugin = manifest.id.as_str();
+        let http = if manifest.capabilities.http.is_empty() {
+            None
+        } else {
+            // Hosts are checked on the URL the plugin asked for, so a
+            // redirect mustn't take the request anywhere else
+            let client = reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+            Some(Http {
+                app: app.clone(),
+                hosts: manifest.capabilities.http.clone(),
+                client,
+            })
+        };
+        let (mut store, instance) = self.instantiate(module, plugin, http, DELIVER_FUEL)?;
+        let (_, ptr, len) = write_input(&mut store, &instance, payload.as_bytes())?;
+        let deliver = instance
+            .get_typed_func::<(i32, i32), i32>(&mut store, "deliver")
+            .map_err(|e| plugin_error(plugin, e))?;
+        let status = deliver
+            .call(&mut store, (ptr, len))
+            .map_err(|e| plugin_error(plugin, e))?;
+        if status != 0 {
+            let message = store
+                .data_mut()
+                .error
+                .take()
+                .unwrap_or_else(|| format!("Plugin {} failed with status {}", plugin, status));
+            return Err(AppError::Internal(message));
+        }
+        Ok(())
+    }
+
+    /// A fresh instance with its memory and fuel capped
+    fn instantiate(
+        &self,
+        module: &Module,
+        plugin: &str,
+        http: Option<Http>,
+        fuel: u64,
+    ) -> Result<(Store<HostState>, Instance)> {
+        let state = HostState {
+            limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+            plugin: plugin.to_string(),
+            error: None,
+            http,
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(fuel).map_err(|e| plugin_error(plugin, e))?;
+
+        let mut linker = Linker::new(&self.engine);
+        linker
+            .func_wrap(
+                IMPORT_MODULE,
+                "log",
+                |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| {
+                    let message = read_string(&mut caller, ptr, len)?;
+                    let plugin = &caller.data().plugin;
+                    match level {
+                        0 => debug!("[plugin {}] {}", plugin, message),
+                        1 => info!("[plugin {}] {}", plugin, message),
+                        2 => warn!("[plugin {}] {}", plugin, message),
+                        _ => error!("[plugin {}] {}", plugin, message),
+                    }
+                    Ok(())
+                },
+            )
+            .map_err(|e| plugin_error(plugin, e))?;
+        linker
+            .func_wrap(
+                IMPORT_MODULE,
+                "set_error",
+                |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                    let message = read_string(&mut caller, ptr, len)?;
+                    caller.data_mut().error = Some(message);
+                    Ok(())
+                },
+            )
+            .map_err(|e| plugin_error(plugin, e))?;
+        linker
+            .func_wrap(
+                IMPORT_MODULE,
+                "http_post",
+                |mut caller: Caller<'_, HostState>,
+                 url_ptr: i32,
+                 url_len: i32,
+                 body_ptr: i32,
+                 body_len: i32| {
+                    let url = read_string(&mut caller, url_ptr, url_len)?;
+                    let body = read_bytes(&mut caller, body_ptr, body_len)?;
+                    let state = caller.data();
+                    let Some(http) = &state.http else {
+                        warn!(
+                            "Plugin {} can't send requests while transforming",
+                            state.plugin
+                        );
+                        return Ok(-1);
+                    };
+                    Ok(http.post(&url, body).unwrap_or_else(|e| {
+                        warn!("Plugin {} request to {} failed: {}", state.plugin, url, e);
+                        -1
+                    }))
+                },
+            )
+            .map_err(|e| plugin_error(plugin, e))?;
+
+        let instance = linker
+            .instantiate(&mut store, module)
+            .map_err(|e| plugin_error(plugin, e))?;
+        Ok((store, instance))
+    }
+}
+
+/// Copy `input` into memory the plugin allocated for it
+fn write_input(
+    store: &mut Store<HostState>,
+    instance: &Instance,
+    input: &[u8],
+) -> Result<(Memory, i32, i32)> {
+    let plugin = store.data().plugin.clone();
+    if input.len() > MAX_IO {
+        return Err(AppError::InvalidInput(format!(
+            "{} bytes is too much to pass to plugin {}",
+            input.len(),
+            plugin
+        )));
+    }
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| AppError::Internal(format!("Plugin {} has no memory", plugin)))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut *store, "alloc")
+        .map_err(|e| plugin_error(&plugin, e))?;
+    let len = input.len() as i32;
+    let ptr = alloc
+        .call(&mut *store, len)
+        .map_err(|e| plugin_error(&plugin, e))?;
+    memory
+        .write(&mut *store, ptr as u32 as usize, input)
+        .map_err(|e| AppError::Internal(format!("Plugin {} gave a bad buffer: {}", plugin, e)))?;
+    Ok((memory, ptr, len))
+}
+
+fn read_bytes(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> wasmtime::Result<Vec<u8>> {
+    let len = len as u32 as usize;
+    if len > MAX_IO {
+        return Err(wasmtime::Error::msg("buffer too large"));
+    }
+    let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+        return Err(wasmtime::Error::msg("plugin doesn't export its memory"));
+    };
+    let mut bytes = vec![0; len];
+    memory.read(&*caller, ptr as u32 as usize, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    Ok(String::from_utf8_lossy(&read_bytes(caller, ptr, len)?).into_owned())
+}
+
+fn plugin_error(plugin: &str, e: wasmtime::Error) -> AppError {
+    if let Some(Trap::OutOfFuel) = e.downcast_ref::<Trap>() {
+        return AppError::Internal(format!("Plugin {} ran for too long", plugin));
+    }
+    AppError::Internal(format!("Plugin {} failed: {:#}", plugin, e))
+}
@@ -0,0 +1,455 @@
+//! Third-party transforms and destinations, as WebAssembly plugins.
+//!
+//! A plugin is a folder holding a `plugin.toml` manifest and a `plugin.wasm`
+//! core module:
+//!
+//! ```toml
+//! id = "slack"
+//! name = "Post to Slack"
+//! version = "0.1.0"
+//! description = "Sends each transcript to a Slack channel"
+//!
+//! [capabilities]
+//! # Hosts `http_post` may reach; without any, the plugin can't use it
+//! http = ["hooks.slack.com"]
+//! ```
+//!
+//! The module exports `memory` and `alloc(len) -> ptr`, plus one or both of:
+//!
+//! - `transform(ptr, len) -> i64`: takes a transcript as UTF-8 and returns
+//!   the new text as `ptr << 32 | len`. Runs after the built-in cleanups and
+//!   before profanity filtering, wherever transforms are applied.
+//! - `deliver(ptr, len) -> i32`: takes `{"text": ..., "entryId": ...}` as
+//!   JSON once a transcript has been delivered, and returns 0 on success.
+//!
+//! Plugins reach nothing but the host functions their capabilities grant
+//! (see `host`). Every call gets a fresh instance with capped memory and
+//! fuel, so a plugin keeps no state between calls and can't stall the
+//! pipeline. Installed plugins start disabled.
+
+pub mod commands;
+mod host;
+
+use crate::error::{AppError, Result};
+use crate::transforms::{TransformContext, TransformStage};
+use host::Runtime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use wasmtime::Module;
+
+pub const PLUGINS_FILE: &str = "plugins.json";
+
+/// Installed plugins, one folder each, inside the app data directory
+pub const PLUGINS_DIR: &str = "plugins";
+
+const MANIFEST_FILE: &str = "plugin.toml";
+const MODULE_FILE: &str = "plugin.wasm";
+
+/// What a plugin may do besides computing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Capabilities {
+    /// Hosts destinations may send HTTP requests to
+    pub http: Vec<String>,
+}
+
+/// Contents of `plugin.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    /// Letters, digits, `-` and `_`; also the name of its folder
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub capabilities: Capabilities,
+}
+
+/// An interface a plugin implements, worked out from its exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    Transform,
+    Destination,
+}
+
+/// Returned by `list_plugins` and the commands that change a plugin
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    #[serde(flatten)]
+    pub manifest: PluginManifest,
+    pub kinds: Vec<PluginKind>,
+    pub enabled: bool,
+    /// Why the plugin can't run, e.g. a missing export or an import its
+    /// capabilities don't grant
+    pub error: Option<String>,
+}
+
+/// Outcome of handing a transcript to one destination plugin
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub id: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PluginsConfig {
+    enabled: BTreeSet<String>,
+}
+
+struct Installed {
+    manifest: PluginManifest,
+    kinds: Vec<PluginKind>,
+    /// `Err` if the module failed to load or doesn't match the ABI
+    module: std::result::Result<Module, String>,
+}
+
+impl Installed {
+    fn implements(&self, kind: PluginKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+}
+
+/// State shared with the transform stage registered in `Transforms`
+struct Shared {
+    runtime: Option<Runtime>,
+    config: Mutex<PluginsConfig>,
+    /// By id, so plugins always run in the same order
+    installed: Mutex<BTreeMap<String, Installed>>,
+}
+
+impl Shared {
+    /// Enabled plugins implementing `kind`, with their modules
+    fn enabled(&self, kind: PluginKind) -> Vec<(PluginManifest, Module)> {
+        let enabled = self
+            .config
+            .lock()
+            .map(|config| config.enabled.clone())
+            .unwrap_or_default();
+        let Ok(installed) = self.installed.lock() else {
+            return Vec::new();
+        };
+        installed
+            .values()
+            .filter(|plugin| plugin.implements(kind) && enabled.contains(&plugin.manifest.id))
+            .filter_map(|plugin| {
+                let module = plugin.module.as_ref().ok()?;
+                Some((plugin.manifest.clone(), module.clone()))
+            })
+            .collect()
+    }
+}
+
+impl TransformStage for Shared {
+    fn apply(&self, mut text: String, _context: &TransformContext) -> String {
+        let Some(runtime) = &self.runtime else {
+            return text;
+        };
+        for (manifest, module) in self.enabled(PluginKind::Transform) {
+            match runtime.transform(&module, &manifest.id, &text) {
+                Ok(transformed) => text = transformed,
+                Err(e) => warn!("Skipping transform plugin {}: {}", manifest.id, e),
+            }
+        }
+        text
+    }
+}
+
+pub struct Plugins {
+    path: PathBuf,
+    dir: PathBuf,
+    shared: Arc<Shared>,
+}
+
+impl Plugins {
+    /// Load the settings at `path` and every plugin installed in `dir`
+    pub fn open(path: &Path, dir: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                PluginsConfig::default()
+            }),
+            Err(_) => PluginsConfig::default(),
+        };
+        let runtime = Runtime::new()
+            .map_err(|e| warn!("Plugins are unavailable: {}", e))
+            .ok();
+        let plugins = Self {
+            path: path.to_path_buf(),
+            dir: dir.to_path_buf(),
+            shared: Arc::new(Shared {
+                runtime,
+                config: Mutex::new(config),
+                installed: Mutex::new(BTreeMap::new()),
+            }),
+        };
+        plugins.scan();
+        plugins
+    }
+
+    /// The stage that runs enabled transform plugins, for `Transforms::add_stage`
+    pub fn transform_stage(&self) -> Arc<dyn TransformStage> {
+        self.shared.clone()
+    }
+
+    fn scan(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let folder = entry.path();
+            if !folder.join(MANIFEST_FILE).is_file() {
+                continue;
+            }
+            match self.load(&folder) {
+                Ok(plugin) => {
+                    if plugin.manifest.id != entry.file_name().to_string_lossy() {
+                        warn!(
+                            "Ignoring plugin in {:?}: its id doesn't match the folder",
+                            folder
+                        );
+                        continue;
+                    }
+                    if let Ok(mut installed) = self.shared.installed.lock() {
+                        installed.insert(plugin.manifest.id.clone(), plugin);
+                    }
+                }
+                Err(e) => warn!("Ignoring plugin in {:?}: {}", folder, e),
+            }
+        }
+    }
+
+    /// Read the manifest in `folder` and compile its module
+    ///
+    /// Only an unreadable manifest is an error; a module that can't be used
+    /// is recorded on the plugin so `list_plugins` can say why.
+    fn load(&self, folder: &Path) -> Result<Installed> {
+        let contents = std::fs::read_to_string(folder.join(MANIFEST_FILE))?;
+        let manifest: PluginManifest = toml::from_str(&contents)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid {}: {}", MANIFEST_FILE, e)))?;
+        validate_id(&manifest.id)?;
+        let loaded = match &self.shared.runtime {
+            Some(runtime) => runtime
+                .load(&folder.join(MODULE_FILE), &manifest)
+                .map_err(|e| e.to_string()),
+            None => Err("The plugin runtime failed to start".to_string()),
+        };
+        let (module, kinds) = match loaded {
+            Ok((module, kinds)) => (Ok(module), kinds),
+            Err(e) => (Err(e), Vec::new()),
+        };
+        Ok(Installed {
+            manifest,
+            kinds,
+            module,
+        })
+    }
+
+    fn info(&self, plugin: &Installed) -> PluginInfo {
+        let enabled = self
+            .shared
+            .config
+            .lock()
+            .is_ok_and(|config| config.enabled.contains(&plugin.manifest.id));
+        PluginInfo {
+            manifest: plugin.manifest.clone(),
+            kinds: plugin.kinds.clone(),
+            enabled,
+            error: plugin.module.as_ref().err().cloned(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<PluginInfo> {
+        let Ok(installed) = self.shared.installed.lock() else {
+            return Vec::new();
+        };
+        installed.values().map(|plugin| self.info(plugin)).collect()
+    }
+
+    fn get(&self, id: &str) -> Result<PluginInfo> {
+        let installed = self
+            .shared
+            .installed
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock plugins: {}", e)))?;
+        installed
+            .get(id)
+            .map(|plugin| self.info(plugin))
+            .ok_or_else(|| AppError::InvalidInput(format!("No plugin with id {}", id)))
+    }
+
+    /// Copy the plugin in `source` (its folder or its `plugin.toml`) into
+    /// the plugins folder, replacing an older version
+    ///
+    /// An upgrade that asks for new capabilities is disabled until the user
+    /// enables it again.
+    pub fn install(&self, source: &Path) -> Result<PluginInfo> {
+        let folder = if source.is_dir() {
+            source
+        } else {
+            source
+                .parent()
+                .ok_or_else(|| AppError::InvalidInput(format!("{:?} isn't a plugin", source)))?
+        };
+        let plugin = self.load(folder)?;
+        if let Err(e) = &plugin.module {
+            return Err(AppError::InvalidInput(format!(
+                "Can't install {}: {}",
+                plugin.manifest.id, e
+            )));
+        }
+
+        let id = plugin.manifest.id.clone();
+        let target = self.dir.join(&id);
+        std::fs::create_dir_all(&target)?;
+        for file in [MANIFEST_FILE, MODULE_FILE] {
+            if folder.join(file) != target.join(file) {
+                std::fs::copy(folder.join(file), target.join(file))?;
+            }
+        }
+
+        let mut installed = self
+            .shared
+            .installed
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock plugins: {}", e)))?;
+        let escalated = installed
+            .get(&id)
+            .is_some_and(|previous| previous.manifest.capabilities != plugin.manifest.capabilities);
+        installed.insert(id.clone(), plugin);
+        drop(installed);
+        if escalated {
+            info!("Plugin {} changed its capabilities, disabling it", id);
+            self.update_enabled(&id, false)?;
+        }
+        info!("Installed plugin {}", id);
+        self.get(&id)
+    }
+
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<PluginInfo> {
+        let plugin = self.get(id)?;
+        if enabled {
+            if let Some(error) = plugin.error {
+                return Err(AppError::InvalidInput(format!(
+                    "Plugin {} can't be enabled: {}",
+                    id, error
+                )));
+            }
+        }
+        self.update_enabled(id, enabled)?;
+        info!(
+            "Plugin {} {}",
+            id,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.get(id)
+    }
+
+    fn update_enabled(&self, id: &str, enabled: bool) -> Result<()> {
+        let mut config =
+            self.shared.config.lock().map_err(|e| {
+                AppError::Internal(format!("Failed to lock plugin settings: {}", e))
+            })?;
+        let mut updated = config.clone();
+        if enabled {
+            updated.enabled.insert(id.to_string());
+        } else {
+            updated.enabled.remove(id);
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&updated).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize plugin settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *config = updated;
+        Ok(())
+    }
+
+    /// Hand a delivered transcript to every enabled destination plugin
+    ///
+    /// One plugin failing doesn't stop the others; each reports separately.
+    pub async fn deliver(
+        &self,
+        app: &AppHandle,
+        text: &str,
+        entry_id: Option<&str>,
+    ) -> Result<Vec<Delivery>> {
+        let payload = serde_json::json!({ "text": text, "entryId": entry_id }).to_string();
+        let mut deliveries = Vec::new();
+        for (manifest, module) in self.shared.enabled(PluginKind::Destination) {
+            let id = manifest.id.clone();
+            let result = self.deliver_one(app, manifest, module, &payload).await;
+            if let Err(e) = &result {
+                warn!("Plugin {} failed to deliver: {}", id, e);
+            }
+            deliveries.push(Delivery {
+                id,
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        Ok(deliveries)
+    }
+
+    /// Hand `text` to the destination plugin `id` alone, which must be enabled
+    pub async fn deliver_to(&self, app: &AppHandle, id: &str, text: &str) -> Result<()> {
+        let (manifest, module) = self
+            .shared
+            .enabled(PluginKind::Destination)
+            .into_iter()
+            .find(|(manifest, _)| manifest.id == id)
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("No enabled destination plugin {}", id))
+            })?;
+        let payload = serde_json::json!({ "text": text, "entryId": null }).to_string();
+        self.deliver_one(app, manifest, module, &payload).await
+    }
+
+    async fn deliver_one(
+        &self,
+        app: &AppHandle,
+        manifest: PluginManifest,
+        module: Module,
+        payload: &str,
+    ) -> Result<()> {
+        let shared = self.shared.clone();
+        let app = app.clone();
+        let payload = payload.to_string();
+        tauri::async_runtime::spawn_blocking(move || {
+            let runtime = shared
+                .runtime
+                .as_ref()
+                .ok_or_else(|| AppError::Internal("The plugin runtime isn't running".into()))?;
+            runtime.deliver(&app, &module, &manifest, &payload)
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Plugin task failed: {}", e)))?
+    }
+}
+
+/// Ids name folders, so keep them to characters that are safe in paths
+fn validate_id(id: &str) -> Result<()> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Plugin id {:?} must be letters, digits, - and _",
+            id
+        )))
+    }
+}
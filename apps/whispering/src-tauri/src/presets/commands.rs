@@ -0,0 +1,42 @@
+use super::{PresetList, Presets, RecordingPreset};
+use crate::error::Result;
+use crate::events::{AppEvent, PresetsChanged};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn list_presets(presets: State<'_, Presets>) -> Result<PresetList> {
+    Ok(presets.list())
+}
+
+/// Add a preset, or replace the one with the same name
+#[tauri::command]
+pub async fn save_preset(
+    preset: RecordingPreset,
+    presets: State<'_, Presets>,
+    app: AppHandle,
+) -> Result<()> {
+    presets.save(preset)?;
+    let _ = PresetsChanged::emit(&app, &());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_preset(
+    name: String,
+    presets: State<'_, Presets>,
+    app: AppHandle,
+) -> Result<()> {
+    presets.delete(&name)?;
+    let _ = PresetsChanged::emit(&app, &());
+    Ok(())
+}
+
+/// Switch recording settings to the preset called `name`
+#[tauri::command]
+pub async fn apply_preset(
+    name: String,
+    presets: State<'_, Presets>,
+    app: AppHandle,
+) -> Result<RecordingPreset> {
+    presets.apply(&app, &name)
+}
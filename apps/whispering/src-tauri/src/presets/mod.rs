@@ -0,0 +1,197 @@
+//! Named recording presets, e.g. "Quick note" and "High-quality interview".
+//!
+//! A preset bundles what's recorded (sample rate), how it's stored
+//! (uncompressed WAV or compressed before transcription), how it's captured
+//! (pre-roll) and whether voice activity detection starts and stops it.
+//! Applying one updates the native settings directly and announces the
+//! preset on `presets://applied` for the frontend to apply the rest, so it
+//! works the same from the tray with the window closed.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, PresetApplied};
+use crate::settings::SettingsStore;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+pub const PRESETS_FILE: &str = "presets.json";
+
+/// Sample rates offered in recording settings
+pub const SAMPLE_RATES: [u32; 3] = [16_000, 44_100, 48_000];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingEncoding {
+    /// Uncompressed WAV, as recorded
+    Wav,
+    /// Compressed with FFmpeg before transcription, for smaller uploads
+    Compressed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingPreset {
+    pub name: String,
+    pub sample_rate: u32,
+    pub encoding: RecordingEncoding,
+    /// FFmpeg options when compressed; the app's defaults if unset
+    #[serde(default)]
+    pub compression_options: Option<String>,
+    /// Audio kept from just before the recording starts (0 for none)
+    #[serde(default)]
+    pub pre_roll_ms: u32,
+    /// Record with voice activity detection instead of start and stop
+    #[serde(default)]
+    pub vad: bool,
+}
+
+impl RecordingPreset {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "Preset name can't be empty".to_string(),
+            ));
+        }
+        if !SAMPLE_RATES.contains(&self.sample_rate) {
+            return Err(AppError::InvalidInput(format!(
+                "Sample rate must be one of {:?}",
+                SAMPLE_RATES
+            )));
+        }
+        // The choices offered in recording settings
+        if self.pre_roll_ms > 2000 || self.pre_roll_ms % 500 != 0 {
+            return Err(AppError::InvalidInput(
+                "Pre-roll must be 0, 500, 1000, 1500 or 2000 ms".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetList {
+    pub presets: Vec<RecordingPreset>,
+    /// Name of the preset applied last
+    pub active: Option<String>,
+}
+
+impl Default for PresetList {
+    fn default() -> Self {
+        Self {
+            presets: vec![
+                RecordingPreset {
+                    name: "Quick note".to_string(),
+                    sample_rate: 16_000,
+                    encoding: RecordingEncoding::Compressed,
+                    compression_options: None,
+                    pre_roll_ms: 0,
+                    vad: false,
+                },
+                RecordingPreset {
+                    name: "High-quality interview".to_string(),
+                    sample_rate: 48_000,
+                    encoding: RecordingEncoding::Wav,
+                    compression_options: None,
+                    pre_roll_ms: 1000,
+                    vad: false,
+                },
+            ],
+            active: None,
+        }
+    }
+}
+
+pub struct Presets {
+    path: PathBuf,
+    list: Mutex<PresetList>,
+}
+
+impl Presets {
+    pub fn open(path: &Path) -> Self {
+        let list = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                PresetList::default()
+            }),
+            Err(_) => PresetList::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            list: Mutex::new(list),
+        }
+    }
+
+    pub fn list(&self) -> PresetList {
+        self.list.lock().map(|list| list.clone()).unwrap_or_default()
+    }
+
+    fn modify<T>(&self, f: impl FnOnce(&mut PresetList) -> Result<T>) -> Result<T> {
+        let mut list = self
+            .list
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock presets: {}", e)))?;
+        let value = f(&mut list)?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*list)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize presets: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        Ok(value)
+    }
+
+    /// Add a preset, or replace the one with the same name
+    pub fn save(&self, preset: RecordingPreset) -> Result<()> {
+        preset.validate()?;
+        self.modify(|list| {
+            match list.presets.iter_mut().find(|p| p.name == preset.name) {
+                Some(existing) => *existing = preset,
+                None => list.presets.push(preset),
+            }
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Result<()> {
+        self.modify(|list| {
+            list.presets.retain(|preset| preset.name != name);
+            if list.active.as_deref() == Some(name) {
+                list.active = None;
+            }
+            Ok(())
+        })
+    }
+
+    /// Switch to the preset called `name`
+    pub fn apply(&self, app: &AppHandle, name: &str) -> Result<RecordingPreset> {
+        let preset = self.modify(|list| {
+            let preset = list
+                .presets
+                .iter()
+                .find(|preset| preset.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::InvalidInput(format!("No preset called {:?}", name))
+                })?;
+            list.active = Some(preset.name.clone());
+            Ok(preset)
+        })?;
+        info!("Applying recording preset {:?}", preset.name);
+        if let Some(settings) = app.try_state::<SettingsStore>() {
+            // Pre-roll needs a parked stream to fill
+            settings.update(app, |settings| {
+                settings.pre_roll_ms = preset.pre_roll_ms;
+                if preset.pre_roll_ms > 0 {
+                    settings.warm_start = true;
+                }
+            })?;
+        }
+        let _ = PresetApplied::emit(app, &preset);
+        Ok(preset)
+    }
+}
@@ -0,0 +1,68 @@
+//! Local-only privacy mode.
+//!
+//! When `privacyMode` is on (or forced by a `local_only` policy), every
+//! outbound request made by the transcription and LLM layers must go through
+//! `ensure_allowed` and is refused unless it targets this machine. The
+//! frontend's HTTP client asks `check_network_request` before each request.
+
+use crate::error::{AppError, Result};
+use crate::settings::SettingsStore;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// Whether requests to `url` stay on this machine
+///
+/// Only loopback hosts count. Even a LAN server is another machine, so
+/// self-hosted providers must run locally to be used in privacy mode.
+pub fn is_local_url(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        // Relative URLs resolve against the app itself
+        return true;
+    };
+    if !matches!(
+        scheme.to_ascii_lowercase().as_str(),
+        "http" | "https" | "ws" | "wss"
+    ) {
+        return true;
+    }
+
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if let Some(bracketed) = host_port.strip_prefix('[') {
+        bracketed.split(']').next().unwrap_or_default()
+    } else {
+        host_port.split(':').next().unwrap_or_default()
+    };
+    let host = host.to_ascii_lowercase();
+
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.try_state::<SettingsStore>()
+        .is_some_and(|settings| settings.get().privacy_mode)
+}
+
+/// Refuse a request to `url` if privacy mode is on and it would leave the machine
+pub fn ensure_allowed(app: &AppHandle, url: &str) -> Result<()> {
+    if is_enabled(app) && !is_local_url(url) {
+        warn!("Privacy mode blocked a request to {}", url);
+        return Err(AppError::PermissionDenied(format!(
+            "Privacy mode is on; requests to {} are blocked",
+            url
+        )));
+    }
+    Ok(())
+}
+
+/// Called by the frontend HTTP client before every request
+#[tauri::command]
+pub async fn check_network_request(url: String, app_handle: AppHandle) -> Result<()> {
+    ensure_allowed(&app_handle, &url)
+}
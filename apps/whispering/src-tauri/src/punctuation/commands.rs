@@ -0,0 +1,32 @@
+use super::{PunctuationStatus, Punctuator};
+use crate::error::Result;
+use tauri::State;
+use tracing::info;
+
+#[tauri::command]
+pub async fn get_punctuation_restoration(
+    punctuator: State<'_, Punctuator>,
+) -> Result<PunctuationStatus> {
+    Ok(punctuator.status())
+}
+
+/// Turn punctuation restoration for local transcripts on or off. Fails if
+/// turning it on before the model is installed.
+#[tauri::command]
+pub async fn set_punctuation_restoration(
+    enabled: bool,
+    punctuator: State<'_, Punctuator>,
+) -> Result<PunctuationStatus> {
+    info!("Setting punctuation restoration: {}", enabled);
+    punctuator.set_enabled(enabled)
+}
+
+/// Punctuate arbitrary text with the model, e.g. a cloud transcript or one
+/// from before restoration was turned on
+#[tauri::command]
+pub async fn restore_punctuation(
+    text: String,
+    punctuator: State<'_, Punctuator>,
+) -> Result<String> {
+    punctuator.restore(&text)
+}
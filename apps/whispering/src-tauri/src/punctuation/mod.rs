@@ -0,0 +1,321 @@
+//! Punctuation and capitalization for local transcripts.
+//!
+//! whisper.cpp sometimes returns long unpunctuated runs, and some local
+//! models never punctuate at all. When enabled, local transcripts go through
+//! a token-classification model (e.g. an ONNX export of
+//! `fullstop-punctuation-multilang`) before reaching transformations. The
+//! model directory holds:
+//!
+//! - `model.onnx`: takes `input_ids` and `attention_mask`, returns `logits`
+//! - `tokenizer.json`: the matching Hugging Face tokenizer
+//! - `config.json`: its `id2label`, mapping classes to the punctuation to
+//!   put after a word (`0` or `O` for none)
+//!
+//! The model only places punctuation; words after sentence-ending
+//! punctuation are capitalized by rule.
+//!
+//! Without the `local-inference` feature there's no ONNX runtime: the stage
+//! stays off and restoring punctuation fails as unsupported.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+#[cfg(feature = "local-inference")]
+use crate::transcription::TranscriptionError;
+#[cfg(feature = "local-inference")]
+use ort::{session::Session, value::Tensor};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "local-inference")]
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+#[cfg(feature = "local-inference")]
+use tokenizers::Tokenizer;
+use tracing::{debug, info, warn};
+
+pub const PUNCTUATION_FILE: &str = "punctuation.json";
+
+/// Model location inside the app data directory
+pub const PUNCTUATION_MODEL_DIR: &str = "models/punctuation";
+
+/// Words per model run; keeps inputs well under the usual 512-token limit
+const CHUNK_WORDS: usize = 150;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PunctuationConfig {
+    enabled: bool,
+}
+
+/// Returned by `get_punctuation_restoration`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunctuationStatus {
+    pub enabled: bool,
+    /// Where the model files go
+    pub model_dir: String,
+    pub model_installed: bool,
+}
+
+#[cfg(feature = "local-inference")]
+#[derive(Deserialize)]
+struct ModelConfig {
+    id2label: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "local-inference")]
+struct Model {
+    session: Session,
+    tokenizer: Tokenizer,
+    /// Punctuation to append for each class, indexed by class id
+    labels: Vec<String>,
+}
+
+#[cfg(feature = "local-inference")]
+impl Model {
+    fn load(dir: &Path) -> Result<Self> {
+        let fail = |what: &str, e: String| {
+            AppError::Transcription(TranscriptionError::ModelLoadError {
+                message: format!("Failed to load punctuation {}: {}", what, e),
+            })
+        };
+        let session = Session::builder()
+            .and_then(|builder| builder.commit_from_file(dir.join("model.onnx")))
+            .map_err(|e| fail("model", e.to_string()))?;
+        let tokenizer = Tokenizer::from_file(dir.join("tokenizer.json"))
+            .map_err(|e| fail("tokenizer", e.to_string()))?;
+        let config: ModelConfig = std::fs::read_to_string(dir.join("config.json"))
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()))
+            .map_err(|e| fail("labels", e))?;
+        let mut labels = Vec::new();
+        for (id, label) in config.id2label {
+            let id: usize = id
+                .parse()
+                .map_err(|_| fail("labels", format!("bad id {}", id)))?;
+            if labels.len() <= id {
+                labels.resize(id + 1, String::new());
+            }
+            labels[id] = match label.as_str() {
+                "0" | "O" => String::new(),
+                _ => label,
+            };
+        }
+        info!("Loaded punctuation model from {:?}", dir);
+        Ok(Self {
+            session,
+            tokenizer,
+            labels,
+        })
+    }
+
+    /// Punctuation to put after each word
+    fn classify(&mut self, words: &[&str]) -> Result<Vec<String>> {
+        let encoding = self
+            .tokenizer
+            .encode(words.to_vec(), true)
+            .map_err(|e| AppError::Internal(format!("Failed to tokenize transcript: {}", e)))?;
+        let ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
+        let mask: Vec<i64> = encoding
+            .get_attention_mask()
+            .iter()
+            .map(|&m| m as i64)
+            .collect();
+        let shape = [1usize, ids.len()];
+
+        let run = |session: &mut Session| -> ort::Result<Vec<usize>> {
+            let outputs = session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, ids))?,
+                "attention_mask" => Tensor::from_array((shape, mask))?,
+            ])?;
+            let (logits_shape, logits) = outputs["logits"].try_extract_tensor::<f32>()?;
+            let classes = logits_shape[2] as usize;
+            Ok(logits
+                .chunks_exact(classes)
+                .map(|scores| {
+                    scores
+                        .iter()
+                        .enumerate()
+                        .max_by(|a, b| a.1.total_cmp(b.1))
+                        .map(|(class, _)| class)
+                        .unwrap_or(0)
+                })
+                .collect())
+        };
+        let predicted = run(&mut self.session)
+            .map_err(|e| AppError::Internal(format!("Punctuation model failed: {}", e)))?;
+
+        // A word split into several tokens takes the last token's class,
+        // since the punctuation follows the end of the word
+        let mut punctuation = vec![String::new(); words.len()];
+        for (token, word) in encoding.get_word_ids().iter().enumerate() {
+            if let (Some(word), Some(&class)) = (word, predicted.get(token)) {
+                if let Some(slot) = punctuation.get_mut(*word as usize) {
+                    *slot = self.labels.get(class).cloned().unwrap_or_default();
+                }
+            }
+        }
+        Ok(punctuation)
+    }
+}
+
+/// No model can be loaded without an ONNX runtime
+#[cfg(not(feature = "local-inference"))]
+enum Model {}
+
+#[cfg(not(feature = "local-inference"))]
+impl Model {
+    fn load(_dir: &Path) -> Result<Self> {
+        Err(crate::build_features::missing(
+            "local-inference",
+            "Punctuation restoration",
+        ))
+    }
+
+    fn classify(&mut self, _words: &[&str]) -> Result<Vec<String>> {
+        match *self {}
+    }
+}
+
+pub struct Punctuator {
+    path: PathBuf,
+    model_dir: PathBuf,
+    config: Mutex<PunctuationConfig>,
+    /// Loaded on first use
+    model: Mutex<Option<Model>>,
+}
+
+impl Punctuator {
+    pub fn open(path: &Path, model_dir: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                PunctuationConfig::default()
+            }),
+            Err(_) => PunctuationConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            model_dir: model_dir.to_path_buf(),
+            config: Mutex::new(config),
+            model: Mutex::new(None),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        // A settings file from a full build may have it turned on
+        cfg!(feature = "local-inference") && self.config.lock().is_ok_and(|config| config.enabled)
+    }
+
+    fn model_installed(&self) -> bool {
+        ["model.onnx", "tokenizer.json", "config.json"]
+            .iter()
+            .all(|file| self.model_dir.join(file).is_file())
+    }
+
+    pub fn status(&self) -> PunctuationStatus {
+        PunctuationStatus {
+            enabled: self.enabled(),
+            model_dir: self.model_dir.to_string_lossy().to_string(),
+            model_installed: self.model_installed(),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<PunctuationStatus> {
+        if enabled && !cfg!(feature = "local-inference") {
+            return Err(crate::build_features::missing(
+                "local-inference",
+                "Punctuation restoration",
+            ));
+        }
+        if enabled && !self.model_installed() {
+            return Err(AppError::InvalidInput(format!(
+                "Put the punctuation model (model.onnx, tokenizer.json, config.json) in {:?} first",
+                self.model_dir
+            )));
+        }
+        let config = PunctuationConfig { enabled };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize punctuation settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self.config.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock punctuation settings: {}", e))
+        })? = config;
+        if !enabled {
+            // Free the model's memory until it's turned back on
+            if let Ok(mut model) = self.model.lock() {
+                *model = None;
+            }
+        }
+        Ok(self.status())
+    }
+
+    /// Punctuate and capitalize `text` with the model, whether or not the
+    /// stage is enabled
+    pub fn restore(&self, text: &str) -> Result<String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.is_empty() {
+            return Ok(String::new());
+        }
+        let mut model = self
+            .model
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock punctuation model: {}", e)))?;
+        if model.is_none() {
+            *model = Some(Model::load(&self.model_dir)?);
+        }
+        let model = model.as_mut().expect("model was just loaded");
+
+        let mut punctuation = Vec::with_capacity(words.len());
+        for chunk in words.chunks(CHUNK_WORDS) {
+            punctuation.extend(model.classify(chunk)?);
+        }
+        debug!("Restored punctuation for {} words", words.len());
+        Ok(rebuild(&words, &punctuation))
+    }
+
+    /// The restoration stage for a local transcript: returns `text` restored
+    /// if enabled, or unchanged if disabled or the model fails
+    pub fn apply(&self, text: String) -> String {
+        if !self.enabled() {
+            return text;
+        }
+        match self.restore(&text) {
+            Ok(restored) => restored,
+            Err(e) => {
+                warn!("Skipping punctuation restoration: {}", e);
+                text
+            }
+        }
+    }
+}
+
+/// Join words back up with the predicted punctuation, replacing whatever the
+/// engine put at the end of each word, and capitalize sentence starts
+fn rebuild(words: &[&str], punctuation: &[String]) -> String {
+    let mut text = String::new();
+    let mut sentence_start = true;
+    for (word, mark) in words.iter().zip(punctuation) {
+        let bare = word.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '\'');
+        let bare = if bare.is_empty() { *word } else { bare };
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        if sentence_start {
+            let mut chars = bare.chars();
+            if let Some(first) = chars.next() {
+                text.extend(first.to_uppercase());
+                text.push_str(chars.as_str());
+            }
+        } else {
+            text.push_str(bare);
+        }
+        text.push_str(mark);
+        sentence_start = matches!(mark.chars().last(), Some('.' | '?' | '!'));
+    }
+    text
+}
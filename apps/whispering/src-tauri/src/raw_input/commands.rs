@@ -0,0 +1,47 @@
+use super::PedalDevice;
+use crate::error::{AppError, Result};
+use crate::shortcut_capture;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+const DEFAULT_CAPTURE_TIMEOUT_MS: u64 = 15_000;
+
+/// Supported foot pedals that are plugged in
+#[tauri::command]
+pub async fn list_pedals() -> Result<Vec<PedalDevice>> {
+    Ok(tokio::task::spawn_blocking(super::connected_pedals)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to list pedals: {}", e)))?)
+}
+
+/// Wait for the next media button press, to learn which code the user's
+/// headset sends for the button they want to bind (usually `MediaPlayPause`)
+///
+/// Returns `None` if nothing was pressed before the timeout.
+#[tauri::command]
+pub async fn capture_media_button(timeout_ms: Option<u64>) -> Result<Option<String>> {
+    shortcut_capture::ensure_listener();
+    super::ensure_media_listener();
+    let events = shortcut_capture::subscribe_keys();
+
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_CAPTURE_TIMEOUT_MS));
+    let code = tokio::task::spawn_blocking(move || {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match events.recv_timeout(remaining) {
+                Ok(event) if event.pressed && super::is_media_code(&event.code) => {
+                    return Some(event.code)
+                }
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Media button capture failed: {}", e)))?;
+
+    debug!("Captured media button {:?}", code);
+    Ok(code)
+}
@@ -0,0 +1,175 @@
+//! Media buttons on headsets and keyboards.
+//!
+//! A Bluetooth headset's play/pause button reaches the computer over AVRCP,
+//! and the OS turns it into a media key press. On Windows and Linux the global
+//! key listener sees those as platform key codes, named here. USB and some
+//! Bluetooth headsets instead send HID consumer control reports, which are
+//! read directly; that's also the only way to see them on macOS. Reading
+//! doesn't stop the OS from acting on the button, so a media player may still
+//! pause or resume.
+
+use crate::shortcut_capture::{self, KeyEvent};
+use hidapi::HidApi;
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info};
+
+const USAGE_PAGE_CONSUMER: u16 = 0x0c;
+const USAGE_CONSUMER_CONTROL: u16 = 0x01;
+
+/// How often to look for newly connected devices
+const SCAN_INTERVAL: Duration = Duration::from_secs(3);
+
+const READ_TIMEOUT_MS: i32 = 500;
+
+/// Name for a consumer control usage (HID usage tables, page 0x0C), matching
+/// the W3C `KeyboardEvent.code` where there is one
+pub fn usage_code(usage: u16) -> String {
+    let name = match usage {
+        0xb0 => "MediaPlay",
+        0xb1 => "MediaPause",
+        0xb5 => "MediaTrackNext",
+        0xb6 => "MediaTrackPrevious",
+        0xb7 => "MediaStop",
+        0xcd => "MediaPlayPause",
+        0xe2 => "AudioVolumeMute",
+        0xe9 => "AudioVolumeUp",
+        0xea => "AudioVolumeDown",
+        _ => return format!("Consumer{:04X}", usage),
+    };
+    name.to_string()
+}
+
+/// Name for a media key reported by the global key listener
+pub fn media_key_code(key: rdev::Key) -> Option<&'static str> {
+    let rdev::Key::Unknown(raw) = key else {
+        return None;
+    };
+    // Virtual-key codes on Windows, X11 keycodes on Linux
+    let windows = cfg!(target_os = "windows");
+    let linux = cfg!(target_os = "linux");
+    Some(match raw {
+        0xb3 if windows => "MediaPlayPause",
+        0xb0 if windows => "MediaTrackNext",
+        0xb1 if windows => "MediaTrackPrevious",
+        0xb2 if windows => "MediaStop",
+        0xad if windows => "AudioVolumeMute",
+        172 if linux => "MediaPlayPause",
+        171 if linux => "MediaTrackNext",
+        173 if linux => "MediaTrackPrevious",
+        174 if linux => "MediaStop",
+        121 if linux => "AudioVolumeMute",
+        _ => return None,
+    })
+}
+
+/// Whether `code` names a media button rather than a regular key
+pub fn is_media_code(code: &str) -> bool {
+    code.starts_with("Media") || code.starts_with("AudioVolume") || code.starts_with("Consumer")
+}
+
+static START_LISTENER: Once = Once::new();
+
+/// Start reading consumer control devices, once something needs them
+pub fn ensure_media_listener() {
+    START_LISTENER.call_once(|| {
+        std::thread::spawn(|| {
+            let mut api = match HidApi::new() {
+                Ok(api) => api,
+                Err(e) => {
+                    error!("Can't read media buttons: {}", e);
+                    return;
+                }
+            };
+            info!("Listening for media buttons");
+            let open = Arc::new(Mutex::new(HashSet::new()));
+            loop {
+                scan(&mut api, &open);
+                std::thread::sleep(SCAN_INTERVAL);
+            }
+        });
+    });
+}
+
+/// Start a reader for every consumer control collection not read yet
+fn scan(api: &mut HidApi, open: &Arc<Mutex<HashSet<CString>>>) {
+    if let Err(e) = api.refresh_devices() {
+        debug!("Failed to refresh HID devices: {}", e);
+        return;
+    }
+    for info in api.device_list() {
+        if info.usage_page() != USAGE_PAGE_CONSUMER || info.usage() != USAGE_CONSUMER_CONTROL {
+            continue;
+        }
+        let path = info.path().to_owned();
+        let Ok(mut reading) = open.lock() else {
+            return;
+        };
+        if reading.contains(&path) {
+            continue;
+        }
+        let device = match info.open_device(api) {
+            Ok(device) => device,
+            Err(e) => {
+                // Some systems keep these to themselves; remember and move on
+                debug!("Can't open consumer control {:?}: {}", path, e);
+                reading.insert(path);
+                continue;
+            }
+        };
+        debug!(
+            "Reading media buttons from {}",
+            info.product_string().unwrap_or("unknown device")
+        );
+        reading.insert(path.clone());
+        let open = open.clone();
+        std::thread::spawn(move || {
+            let mut held: HashSet<u16> = HashSet::new();
+            let mut report = [0u8; 16];
+            loop {
+                let len = match device.read_timeout(&mut report, READ_TIMEOUT_MS) {
+                    Ok(0) => continue,
+                    Ok(len) => len,
+                    Err(e) => {
+                        debug!("Consumer control {:?} went away: {}", path, e);
+                        break;
+                    }
+                };
+                let pressed = usages(&report[..len]);
+                publish_changes(&held, &pressed);
+                held = pressed;
+            }
+            publish_changes(&held, &HashSet::new());
+            if let Ok(mut reading) = open.lock() {
+                reading.remove(&path);
+            }
+        });
+    }
+}
+
+/// Usages held in a report: a report ID followed by 16-bit usages, the layout
+/// virtually every consumer control uses. An all-zero report releases them.
+fn usages(report: &[u8]) -> HashSet<u16> {
+    report
+        .get(1..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .filter(|usage| *usage != 0)
+        .collect()
+}
+
+fn publish_changes(before: &HashSet<u16>, after: &HashSet<u16>) {
+    let now = Instant::now();
+    let released = before.difference(after).map(|usage| (*usage, false));
+    let pressed = after.difference(before).map(|usage| (*usage, true));
+    for (usage, pressed) in released.chain(pressed) {
+        shortcut_capture::publish(KeyEvent {
+            code: usage_code(usage),
+            pressed,
+            at: now,
+        });
+    }
+}
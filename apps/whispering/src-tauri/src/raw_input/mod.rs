@@ -0,0 +1,44 @@
+//! Inputs beyond the keyboard that can trigger commands.
+//!
+//! Extra mouse buttons arrive through the global `shortcut_capture` listener
+//! and are named here. HID foot pedals, common for dictation and medical
+//! transcription, aren't keyboards or mice at all: their reports are read
+//! directly and each pedal becomes `Pedal1`, `Pedal2`, … Headset media
+//! buttons come from either source (see `media`). All of them end up in the
+//! same stream of key events the `hotkeys` triggers are matched against.
+
+pub mod commands;
+mod media;
+mod pedal;
+
+pub use media::{ensure_media_listener, is_media_code, media_key_code};
+pub use pedal::{connected_pedals, ensure_pedal_listener, PedalDevice};
+
+use rdev::Button;
+
+/// Prefix of the codes pedals are reported with
+pub const PEDAL_PREFIX: &str = "Pedal";
+
+/// Code for a mouse button that can be bound, e.g. `Mouse4`
+///
+/// The primary and secondary buttons are left alone so binding can't take
+/// over ordinary clicking. rdev passes other buttons through with the
+/// platform's own numbering.
+pub fn button_code(button: Button) -> Option<&'static str> {
+    match button {
+        Button::Middle => Some("MouseMiddle"),
+        #[cfg(target_os = "windows")]
+        Button::Unknown(1) => Some("Mouse4"),
+        #[cfg(target_os = "windows")]
+        Button::Unknown(2) => Some("Mouse5"),
+        #[cfg(target_os = "linux")]
+        Button::Unknown(8) => Some("Mouse4"),
+        #[cfg(target_os = "linux")]
+        Button::Unknown(9) => Some("Mouse5"),
+        #[cfg(target_os = "macos")]
+        Button::Unknown(3) => Some("Mouse4"),
+        #[cfg(target_os = "macos")]
+        Button::Unknown(4) => Some("Mouse5"),
+        _ => None,
+    }
+}
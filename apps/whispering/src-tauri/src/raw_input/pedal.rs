@@ -0,0 +1,149 @@
+//! HID foot pedals.
+//!
+//! Dictation pedals report a bitmask of the pedals held down, one bit per
+//! pedal, which is what the devices in [`KNOWN_PEDALS`] send. Pedals that
+//! identify as a keyboard instead need no support here: bind the key they
+//! send.
+
+use super::PEDAL_PREFIX;
+use crate::shortcut_capture::{self, KeyEvent};
+use hidapi::{HidApi, HidDevice};
+use serde::{Deserialize, Serialize};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// How often to look for a pedal while none is connected
+const SCAN_INTERVAL: Duration = Duration::from_secs(3);
+
+const READ_TIMEOUT_MS: i32 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PedalDevice {
+    pub name: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+}
+
+/// Pedals known to send a plain bitmask report
+pub const KNOWN_PEDALS: &[(&str, u16, u16)] = &[
+    // Infinity IN-USB-1/2/3: left, center and right are bits 0, 1 and 2
+    ("Infinity IN-USB", 0x05f3, 0x00ff),
+];
+
+fn is_pedal(vendor_id: u16, product_id: u16) -> Option<&'static str> {
+    KNOWN_PEDALS
+        .iter()
+        .find(|(_, vendor, product)| *vendor == vendor_id && *product == product_id)
+        .map(|(name, _, _)| *name)
+}
+
+/// Pedals plugged in right now
+pub fn connected_pedals() -> Vec<PedalDevice> {
+    let api = match HidApi::new() {
+        Ok(api) => api,
+        Err(e) => {
+            warn!("Failed to enumerate HID devices: {}", e);
+            return Vec::new();
+        }
+    };
+    api.device_list()
+        .filter_map(|info| {
+            let name = is_pedal(info.vendor_id(), info.product_id())?;
+            Some(PedalDevice {
+                name: info.product_string().unwrap_or(name).to_string(),
+                vendor_id: info.vendor_id(),
+                product_id: info.product_id(),
+            })
+        })
+        .collect()
+}
+
+static START_LISTENER: Once = Once::new();
+
+/// Start reading pedals, once something is bound to one. Pedals can be
+/// plugged in and out while it runs.
+pub fn ensure_pedal_listener() {
+    START_LISTENER.call_once(|| {
+        std::thread::spawn(|| {
+            let mut api = match HidApi::new() {
+                Ok(api) => api,
+                Err(e) => {
+                    error!("Can't read foot pedals: {}", e);
+                    return;
+                }
+            };
+            info!("Listening for foot pedals");
+            loop {
+                match open_pedal(&mut api) {
+                    Some(device) => read_pedal(&device),
+                    None => std::thread::sleep(SCAN_INTERVAL),
+                }
+            }
+        });
+    });
+}
+
+fn open_pedal(api: &mut HidApi) -> Option<HidDevice> {
+    if let Err(e) = api.refresh_devices() {
+        debug!("Failed to refresh HID devices: {}", e);
+        return None;
+    }
+    let info = api
+        .device_list()
+        .find(|info| is_pedal(info.vendor_id(), info.product_id()).is_some())?;
+    match info.open_device(api) {
+        Ok(device) => {
+            info!(
+                "Opened foot pedal {:04x}:{:04x}",
+                info.vendor_id(),
+                info.product_id()
+            );
+            Some(device)
+        }
+        Err(e) => {
+            // Usually another dictation app has it open
+            warn!("Failed to open foot pedal: {}", e);
+            None
+        }
+    }
+}
+
+/// Turn reports into pedal events until the device goes away
+fn read_pedal(device: &HidDevice) {
+    let mut held: u64 = 0;
+    let mut report = [0u8; 8];
+    loop {
+        let len = match device.read_timeout(&mut report, READ_TIMEOUT_MS) {
+            Ok(0) => continue,
+            Ok(len) => len,
+            Err(e) => {
+                info!("Foot pedal disconnected: {}", e);
+                release_all(held);
+                return;
+            }
+        };
+        let mut bits = [0u8; 8];
+        bits[..len].copy_from_slice(&report[..len]);
+        let state = u64::from_le_bytes(bits);
+        publish_changes(held, state);
+        held = state;
+    }
+}
+
+fn publish_changes(before: u64, after: u64) {
+    let changed = before ^ after;
+    for bit in (0..64).filter(|bit| changed & (1 << bit) != 0) {
+        shortcut_capture::publish(KeyEvent {
+            code: format!("{}{}", PEDAL_PREFIX, bit + 1),
+            pressed: after & (1 << bit) != 0,
+            at: Instant::now(),
+        });
+    }
+}
+
+/// Don't leave a push-to-talk recording running when the pedal is unplugged
+fn release_all(held: u64) {
+    publish_changes(held, 0);
+}
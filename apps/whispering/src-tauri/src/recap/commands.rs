@@ -0,0 +1,32 @@
+use super::{Recap, RecapConfig, RecapPeriod, RecapScheduler};
+use crate::error::Result;
+use tauri::State;
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_recap_config(scheduler: State<'_, RecapScheduler>) -> Result<RecapConfig> {
+    Ok(scheduler.config())
+}
+
+#[tauri::command]
+pub async fn set_recap_config(
+    config: RecapConfig,
+    scheduler: State<'_, RecapScheduler>,
+) -> Result<()> {
+    debug!("Updating recap config: {:?}", config);
+    scheduler.set_config(config)
+}
+
+/// Produce a recap right away, e.g. from a "preview" button in settings
+#[tauri::command]
+pub async fn generate_recap(
+    period: RecapPeriod,
+    scheduler: State<'_, RecapScheduler>,
+    app_handle: tauri::AppHandle,
+) -> Result<Recap> {
+    let config = RecapConfig {
+        period,
+        ..scheduler.config()
+    };
+    super::deliver(&app_handle, &config)
+}
@@ -0,0 +1,335 @@
+//! Periodic recaps of dictation activity, built on the stats module.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::history::HistoryStore;
+use crate::notifications::notify;
+use crate::stats::{self, Stats, StatsRange};
+use crate::telemetry::{self, Feature};
+use crate::timestamps::Timestamps;
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+pub const RECAP_FILE: &str = "recap.json";
+
+/// How often the scheduler checks whether a recap is due
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecapPeriod {
+    Daily,
+    Weekly,
+}
+
+impl RecapPeriod {
+    fn days(&self) -> i64 {
+        match self {
+            RecapPeriod::Daily => 1,
+            RecapPeriod::Weekly => 7,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            RecapPeriod::Daily => "today",
+            RecapPeriod::Weekly => "this week",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecapConfig {
+    pub enabled: bool,
+    pub period: RecapPeriod,
+    /// Local hour (0-23) the recap is delivered at
+    pub hour: u32,
+    /// Day of a weekly recap, 0 = Monday
+    pub weekday: u32,
+    pub notify: bool,
+    /// Also write a markdown report
+    pub write_report: bool,
+    /// Where reports go; defaults to `recaps` in the app data directory
+    pub report_dir: Option<String>,
+}
+
+impl Default for RecapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            period: RecapPeriod::Weekly,
+            hour: 18,
+            weekday: 4,
+            notify: true,
+            write_report: false,
+            report_dir: None,
+        }
+    }
+}
+
+/// A generated recap
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recap {
+    pub period: RecapPeriod,
+    /// One-line summary shown in the notification
+    pub message: String,
+    pub stats: Stats,
+    /// Path of the markdown report, if one was written
+    pub report_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredRecap {
+    config: RecapConfig,
+    /// Milliseconds since the Unix epoch
+    last_sent: Option<i64>,
+}
+
+pub struct RecapScheduler {
+    path: PathBuf,
+    state: Mutex<StoredRecap>,
+}
+
+impl RecapScheduler {
+    pub fn open(path: &Path) -> Self {
+        let state = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                StoredRecap::default()
+            }),
+            Err(_) => StoredRecap::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn config(&self) -> RecapConfig {
+        self.state
+            .lock()
+            .map(|state| state.config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: RecapConfig) -> Result<()> {
+        if config.hour > 23 || config.weekday > 6 {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid recap schedule: hour {}, weekday {}",
+                config.hour, config.weekday
+            )));
+        }
+        self.modify(|state| {
+            // Count from now so enabling doesn't immediately send a recap
+            if config.enabled && !state.config.enabled {
+                state.last_sent = Some(Local::now().timestamp_millis());
+            }
+            state.config = config;
+        })
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut StoredRecap)) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock recap state: {}", e)))?;
+        f(&mut state);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*state)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize recap state: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+
+    /// Check once a minute whether a recap is due and deliver it
+    pub fn start(&self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                let scheduler = app.state::<RecapScheduler>();
+                if !scheduler.is_due(Local::now()) {
+                    continue;
+                }
+                let config = scheduler.config();
+                match deliver(&app, &config) {
+                    Ok(recap) => info!("Delivered {:?} recap: {}", config.period, recap.message),
+                    Err(e) => error!("Failed to deliver recap: {}", e),
+                }
+                // Mark as sent even on failure so a broken report dir doesn't retry every minute
+                if let Err(e) = scheduler
+                    .modify(|state| state.last_sent = Some(Local::now().timestamp_millis()))
+                {
+                    error!("Failed to save recap state: {}", e);
+                }
+            }
+        });
+    }
+
+    fn is_due(&self, now: DateTime<Local>) -> bool {
+        let Ok(state) = self.state.lock() else {
+            return false;
+        };
+        if !state.config.enabled {
+            return false;
+        }
+        let scheduled = latest_scheduled(&state.config, now);
+        debug!("Latest scheduled recap: {:?}", scheduled);
+        match (scheduled, state.last_sent) {
+            (Some(scheduled), Some(last_sent)) => last_sent < scheduled.timestamp_millis(),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// The most recent time at or before `now` a recap was scheduled for
+fn latest_scheduled(config: &RecapConfig, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let today = now.date_naive().and_hms_opt(config.hour, 0, 0)?;
+    let mut candidate = Local.from_local_datetime(&today).earliest()?;
+    if config.period == RecapPeriod::Weekly {
+        let days_back = (now.weekday().num_days_from_monday() + 7 - config.weekday) % 7;
+        candidate -= Duration::days(days_back as i64);
+    }
+    if candidate > now {
+        candidate -= Duration::days(config.period.days());
+    }
+    Some(candidate)
+}
+
+/// Compute a recap for the period ending now and deliver it as configured
+pub fn deliver(app: &AppHandle, config: &RecapConfig) -> Result<Recap> {
+    telemetry::record(app, Feature::Recap);
+    let now = Local::now();
+    let range = StatsRange {
+        from: Some((now - Duration::days(config.period.days())).timestamp_millis()),
+        to: Some(now.timestamp_millis()),
+    };
+    let stats = stats::compute(&app.state::<HistoryStore>(), &range)?;
+    let message = summary_line(config.period, &stats);
+
+    let report_path = if config.write_report {
+        let dir = match &config.report_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => app
+                .path()
+                .app_data_dir()
+                .map_err(|e| {
+                    AppError::Internal(format!("Failed to resolve app data directory: {}", e))
+                })?
+                .join("recaps"),
+        };
+        std::fs::create_dir_all(&dir)?;
+        let date = Timestamps::current(app).format(now.timestamp_millis(), "%Y-%m-%d")?;
+        let path = dir.join(format!("whispering-recap-{}.md", date));
+        std::fs::write(&path, report(config.period, &message, &stats))?;
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    if config.notify {
+        notify(app, &message);
+    }
+
+    Ok(Recap {
+        period: config.period,
+        message,
+        stats,
+        report_path,
+    })
+}
+
+fn thousands(value: usize) -> String {
+    let digits = value.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn duration_label(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn summary_line(period: RecapPeriod, stats: &Stats) -> String {
+    let mut message = format!(
+        "You dictated {} words {}",
+        thousands(stats.words),
+        period.label()
+    );
+    if stats.time_saved_seconds >= 60.0 {
+        let _ = write!(
+            message,
+            ", saving about {} of typing",
+            duration_label(stats.time_saved_seconds)
+        );
+    }
+    message
+}
+
+fn report(period: RecapPeriod, message: &str, stats: &Stats) -> String {
+    let title = match period {
+        RecapPeriod::Daily => "Daily recap",
+        RecapPeriod::Weekly => "Weekly recap",
+    };
+    let mut report = format!("# {}\n\n{}.\n\n", title, message);
+    let _ = writeln!(report, "- Recordings: {}", stats.entries);
+    let _ = writeln!(report, "- Audio: {}", duration_label(stats.audio_seconds));
+    let _ = writeln!(
+        report,
+        "- Average speed: {:.0} words per minute",
+        stats.average_words_per_minute
+    );
+
+    if !stats.words_per_day.is_empty() {
+        report.push_str(
+            "\n## Words per day\n\n| Day | Words | Recordings |\n| --- | ---: | ---: |\n",
+        );
+        for day in &stats.words_per_day {
+            let _ = writeln!(
+                report,
+                "| {} | {} | {} |",
+                day.date,
+                thousands(day.words),
+                day.entries
+            );
+        }
+    }
+    if !stats.top_apps.is_empty() {
+        report.push_str("\n## Top apps\n\n");
+        for app in &stats.top_apps {
+            let _ = writeln!(report, "- {}: {} words", app.name, thousands(app.words));
+        }
+    }
+    if !stats.providers.is_empty() {
+        report.push_str("\n## Providers\n\n");
+        for provider in &stats.providers {
+            let _ = writeln!(
+                report,
+                "- {}: {} recordings",
+                provider.name, provider.entries
+            );
+        }
+    }
+    report
+}
@@ -0,0 +1,51 @@
+//! Audio host APIs besides the platform default, for pro audio interfaces.
+//!
+//! ASIO (Windows) and JACK (Linux) are compiled in only with the `asio` and
+//! `jack` cargo features, since both need native SDKs or libraries at build
+//! time. PipeWire is reached through its JACK compatibility layer.
+
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioBackend {
+    /// WASAPI, CoreAudio or ALSA
+    #[default]
+    Default,
+    Asio,
+    Jack,
+}
+
+impl AudioBackend {
+    /// The cpal host for this backend, if this build includes it
+    fn host_id(self) -> Option<cpal::HostId> {
+        match self {
+            AudioBackend::Default => Some(cpal::default_host().id()),
+            #[cfg(all(target_os = "windows", feature = "asio"))]
+            AudioBackend::Asio => Some(cpal::HostId::Asio),
+            #[cfg(all(target_os = "linux", feature = "jack"))]
+            AudioBackend::Jack => Some(cpal::HostId::Jack),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    pub fn host(self) -> Result<cpal::Host> {
+        let id = self.host_id().ok_or_else(|| {
+            AppError::DeviceNotFound(format!("This build doesn't include the {:?} audio backend", self))
+        })?;
+        cpal::host_from_id(id).map_err(|e| {
+            AppError::DeviceNotFound(format!("The {:?} audio backend is unavailable: {}", self, e))
+        })
+    }
+
+    /// Backends this build includes whose drivers or server are present
+    pub fn available() -> Vec<AudioBackend> {
+        let hosts = cpal::available_hosts();
+        [AudioBackend::Default, AudioBackend::Asio, AudioBackend::Jack]
+            .into_iter()
+            .filter(|backend| backend.host_id().is_some_and(|id| hosts.contains(&id)))
+            .collect()
+    }
+}
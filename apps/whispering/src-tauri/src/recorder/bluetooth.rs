@@ -0,0 +1,72 @@
+//! Spotting Bluetooth headset microphones before they're opened.
+//!
+//! Recording from a Bluetooth headset switches it from A2DP to the hands-free
+//! profile (HFP), which drops both the mic and whatever is playing to
+//! phone-call quality. When asked to, another microphone (usually the
+//! built-in one) is recorded instead so the headset keeps A2DP for playback;
+//! otherwise `recorder://bluetooth-headset` lets the UI explain the drop.
+//!
+//! Headsets are recognized by name and by offering nothing above 24 kHz,
+//! which wired and USB microphones practically always do. Through ALSA on
+//! Linux, devices are only "default" or "pulse", so nothing is detected.
+
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::Device;
+use serde::Serialize;
+
+/// Highest rate hands-free profiles offer (mSBC wideband is 16 kHz)
+const HFP_MAX_SAMPLE_RATE: u32 = 24_000;
+
+/// Name fragments of hands-free inputs across platforms
+const HEADSET_MARKERS: &[&str] = &[
+    "hands-free",
+    "handsfree",
+    "hfp",
+    "hsp",
+    "bluetooth",
+    "bluez",
+    "airpods",
+];
+
+/// Payload of `recorder://bluetooth-headset`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BluetoothHeadset {
+    /// The headset microphone that was selected
+    pub device: String,
+    /// A microphone that wouldn't affect playback, if there is one
+    pub alternative: Option<String>,
+    /// Whether the recording comes from `alternative` instead
+    pub switched: bool,
+}
+
+fn has_headset_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    HEADSET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+fn is_narrowband(device: &Device) -> bool {
+    device
+        .supported_input_configs()
+        .map(|configs| {
+            let mut max_rates = configs.map(|config| config.max_sample_rate().0).peekable();
+            max_rates.peek().is_some() && max_rates.all(|rate| rate <= HFP_MAX_SAMPLE_RATE)
+        })
+        .unwrap_or(false)
+}
+
+pub fn is_headset_mic(device: &Device) -> bool {
+    device.name().is_ok_and(|name| has_headset_name(&name)) || is_narrowband(device)
+}
+
+/// The first input on `host` that isn't a headset microphone, preferring
+/// the system default
+pub fn alternative_mic(host: &cpal::Host) -> Option<String> {
+    let default = host.default_input_device();
+    default
+        .into_iter()
+        .chain(host.input_devices().ok().into_iter().flatten())
+        .find(|device| !is_headset_mic(device))
+        .and_then(|device| device.name().ok())
+}
@@ -1,32 +1,131 @@
-use crate::recorder::recorder::{AudioRecording, RecorderState, Result};
+use crate::app_state::{AppState, StateEvent};
+use crate::error::AppError;
+use crate::events::{AppEvent, AudioDropped, BluetoothHeadsetSelected, SettingsChanged};
+use crate::file_names::{FileNameContext, SavedRecording};
+use crate::focus;
+use crate::history::now_millis;
+use crate::jobs::pending::{PendingJobs, PendingSource};
+use crate::recorder::backend::AudioBackend;
+use crate::recorder::disk_space;
+use crate::recorder::input::{DeviceInputConfig, InputChannel, InputConfigs};
+use crate::recorder::priority::{DevicePriorities, DevicePriority};
+use crate::recorder::recorder::{AudioRecording, DroppedAudio, RecorderState, Result};
+use crate::recorder::spill::CaptureMemory;
+use crate::settings::{NativeSettings, SettingsStore};
 use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::State;
-use tracing::{debug, info};
+use tauri::{AppHandle, Listener, Manager, State};
+use tracing::{debug, info, warn};
 
 /// Application state containing the recorder
 pub struct AppData {
     pub recorder: Mutex<RecorderState>,
+    /// The last recording written to a file, until `name_saved_recording`
+    /// names it after its transcript
+    pub last_saved: Mutex<Option<SavedRecording>>,
 }
 
 impl AppData {
     pub fn new() -> Self {
         Self {
             recorder: Mutex::new(RecorderState::new()),
+            last_saved: Mutex::new(None),
         }
     }
+
+    /// Close the parked stream as soon as warm start is turned off, rather
+    /// than after the next recording, and apply pre-roll and backend changes
+    pub fn follow_settings(&self, app: AppHandle) {
+        let handle = app.clone();
+        app.listen_any(SettingsChanged::NAME, move |event| {
+            let Ok(settings) = serde_json::from_str::<NativeSettings>(event.payload()) else {
+                return;
+            };
+            let data = handle.state::<AppData>();
+            let Ok(mut recorder) = data.recorder.lock() else {
+                return;
+            };
+            recorder.set_backend(settings.audio_backend);
+            recorder.set_pre_roll(pre_roll_ms(&settings));
+            if settings.warm_start {
+                return;
+            }
+            if let Err(e) = recorder.release_parked() {
+                warn!("Failed to release audio stream: {}", e);
+            }
+        });
+    }
+}
+
+/// Whether sessions should leave their stream parked for the next one
+fn keep_warm(settings: &SettingsStore) -> bool {
+    settings.get().warm_start
+}
+
+/// Pre-roll only works on a parked stream, so it's off without warm start
+fn pre_roll_ms(settings: &NativeSettings) -> u32 {
+    if settings.warm_start {
+        settings.pre_roll_ms
+    } else {
+        0
+    }
+}
+
+/// The most preferred connected device, or `device_identifier` if no listed
+/// device is connected (see `priority`)
+pub(crate) fn apply_priority(
+    recorder: &RecorderState,
+    priorities: &DevicePriorities,
+    device_identifier: String,
+) -> String {
+    let priority = priorities.config();
+    if !priority.enabled {
+        return device_identifier;
+    }
+    match recorder.enumerate_devices() {
+        Ok(available) => priority.choose(&available).unwrap_or(device_identifier),
+        Err(e) => {
+            warn!("Failed to list devices for the priority list: {}", e);
+            device_identifier
+        }
+    }
+}
+
+/// The device to open for `device_identifier`, reporting Bluetooth headsets
+fn choose_device(
+    app: &AppHandle,
+    recorder: &mut RecorderState,
+    device_identifier: String,
+    settings: &SettingsStore,
+) -> String {
+    let (device, headset) =
+        recorder.choose_device(device_identifier, settings.get().avoid_bluetooth_mic);
+    if let Some(headset) = headset {
+        let _ = BluetoothHeadsetSelected::emit(app, &headset);
+    }
+    device
 }
 
 #[tauri::command]
-pub async fn enumerate_recording_devices(state: State<'_, AppData>) -> Result<Vec<String>> {
+pub async fn enumerate_recording_devices(
+    state: State<'_, AppData>,
+    settings: State<'_, SettingsStore>,
+) -> Result<Vec<String>> {
     debug!("Enumerating recording devices");
-    let recorder = state
+    let mut recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    recorder.set_backend(settings.get().audio_backend);
     recorder.enumerate_devices()
 }
 
+/// Audio backends this build includes and this machine can use
+#[tauri::command]
+pub async fn list_audio_backends() -> Result<Vec<AudioBackend>> {
+    Ok(AudioBackend::available())
+}
+
 #[tauri::command]
 pub async fn init_recording_session(
     device_identifier: String,
@@ -34,7 +133,10 @@ pub async fn init_recording_session(
     output_folder: String,
     sample_rate: Option<u32>,
     state: State<'_, AppData>,
-    _app_handle: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+    inputs: State<'_, InputConfigs>,
+    priorities: State<'_, DevicePriorities>,
+    app_handle: tauri::AppHandle,
 ) -> Result<()> {
     info!(
         "Initializing recording session: device={}, id={}, folder={}, sample_rate={:?}",
@@ -47,60 +149,185 @@ pub async fn init_recording_session(
     // Create the directory if it doesn't exist
     if !recordings_dir.exists() {
         std::fs::create_dir_all(&recordings_dir)
-            .map_err(|e| format!("Failed to create output folder: {}", e))?;
+            .map_err(|e| AppError::Internal(format!("Failed to create output folder: {}", e)))?;
     }
     
     // Validate it's a directory (not a file)
     if !recordings_dir.is_dir() {
-        return Err(format!("Output path is not a directory: {:?}", recordings_dir));
+        return Err(AppError::InvalidInput(format!(
+            "Output path is not a directory: {:?}",
+            recordings_dir
+        )));
     }
 
+    // A recording that runs the disk full ends up cut off mid-sample
+    let min_free = disk_space::threshold_bytes(&settings.get());
+    disk_space::ensure_space(&recordings_dir, min_free)?;
+
     // Initialize the session with optional sample rate
     let mut recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.init_session(device_identifier, recordings_dir, recording_id, sample_rate)
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    recorder.set_backend(settings.get().audio_backend);
+    recorder.set_pre_roll(pre_roll_ms(&settings.get()));
+    let device_identifier = apply_priority(&recorder, &priorities, device_identifier);
+    let device_identifier = choose_device(&app_handle, &mut recorder, device_identifier, &settings);
+    let input = inputs.get(&device_identifier);
+    recorder.init_session(device_identifier, recordings_dir, recording_id, sample_rate, input)
+}
+
+/// Open a device ahead of time so the first recording starts instantly; the
+/// frontend calls this when the selected device changes
+#[tauri::command]
+pub async fn prewarm_recording_device(
+    device_identifier: String,
+    sample_rate: Option<u32>,
+    state: State<'_, AppData>,
+    settings: State<'_, SettingsStore>,
+    inputs: State<'_, InputConfigs>,
+    priorities: State<'_, DevicePriorities>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    if !keep_warm(&settings) {
+        return Ok(());
+    }
+    debug!("Pre-warming recording device {}", device_identifier);
+    let mut recorder = state
+        .recorder
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    recorder.set_backend(settings.get().audio_backend);
+    recorder.set_pre_roll(pre_roll_ms(&settings.get()));
+    let device_identifier = apply_priority(&recorder, &priorities, device_identifier);
+    let device_identifier = choose_device(&app_handle, &mut recorder, device_identifier, &settings);
+    let input = inputs.get(&device_identifier);
+    recorder.prewarm(device_identifier, sample_rate, input)
 }
 
 #[tauri::command]
-pub async fn start_recording(state: State<'_, AppData>) -> Result<()> {
+pub async fn start_recording(
+    state: State<'_, AppData>,
+    app_state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
     info!("Starting recording");
     let mut recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.start_recording()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    recorder.start_recording()?;
+    app_state.record(StateEvent::RecordingStarted {
+        recording_id: recorder.get_current_recording_id(),
+    });
+    if let Some(path) = recorder.recording_path() {
+        disk_space::watch(app_handle, path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Carry on a recording paused for lack of disk space, once there's room
+#[tauri::command]
+pub async fn resume_recording(
+    state: State<'_, AppData>,
+    settings: State<'_, SettingsStore>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    let mut recorder = state
+        .recorder
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    let Some(path) = recorder.recording_path().map(PathBuf::from) else {
+        return Err(AppError::NoSession("No recording to resume".to_string()));
+    };
+    if recorder.is_capturing() {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        disk_space::ensure_space(dir, disk_space::threshold_bytes(&settings.get()))?;
+    }
+    info!("Resuming recording");
+    recorder.start_recording()?;
+    disk_space::watch(app_handle, path);
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn stop_recording(state: State<'_, AppData>) -> Result<AudioRecording> {
+pub async fn stop_recording(
+    state: State<'_, AppData>,
+    app_state: State<'_, AppState>,
+    pending: State<'_, PendingJobs>,
+    app_handle: tauri::AppHandle,
+) -> Result<AudioRecording> {
     info!("Stopping recording");
     let mut recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.stop_recording()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    let recording = recorder.stop_recording()?;
+    app_state.record(StateEvent::RecordingStopped);
+    if let Some(path) = recording.file_path.as_deref() {
+        if let Err(e) = pending.enqueue(path, PendingSource::Recording) {
+            warn!("Failed to queue {} for transcription: {}", path, e);
+        }
+        if recording.dropped_seconds > 0.0 {
+            let _ = AudioDropped::emit(
+                &app_handle,
+                &DroppedAudio {
+                    path: path.to_string(),
+                    seconds: recording.dropped_seconds,
+                },
+            );
+        }
+    }
+    if let Some(path) = recording.file_path.as_deref().map(PathBuf::from) {
+        let context = FileNameContext {
+            created_at: now_millis() - (recording.duration_seconds * 1000.0) as i64,
+            app: focus::focused_app(),
+            id: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Ok(mut last_saved) = state.last_saved.lock() {
+            *last_saved = Some(SavedRecording { path, context });
+        }
+    }
+    Ok(recording)
 }
 
 #[tauri::command]
-pub async fn cancel_recording(state: State<'_, AppData>) -> Result<()> {
+pub async fn cancel_recording(
+    state: State<'_, AppData>,
+    app_state: State<'_, AppState>,
+    settings: State<'_, SettingsStore>,
+) -> Result<()> {
     info!("Cancelling recording");
     let mut recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.cancel_recording()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    recorder.cancel_recording(keep_warm(&settings))?;
+    app_state.record(StateEvent::RecordingCancelled);
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn close_recording_session(state: State<'_, AppData>) -> Result<()> {
+pub async fn close_recording_session(
+    state: State<'_, AppData>,
+    settings: State<'_, SettingsStore>,
+) -> Result<()> {
     info!("Closing recording session");
     let mut recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.close_session()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    if keep_warm(&settings) {
+        recorder.park_session()
+    } else {
+        recorder.close_session()
+    }
 }
 
 #[tauri::command]
@@ -109,6 +336,63 @@ pub async fn get_current_recording_id(state: State<'_, AppData>) -> Result<Optio
     let recorder = state
         .recorder
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
     Ok(recorder.get_current_recording_id())
 }
+
+/// Audio held in memory and spilled to disk by live subscribers, for diagnostics
+#[tauri::command]
+pub async fn get_capture_memory(state: State<'_, AppData>) -> Result<CaptureMemory> {
+    let recorder = state
+        .recorder
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+    Ok(recorder.memory_usage())
+}
+
+/// Input settings of a device, the defaults if none were set
+#[tauri::command]
+pub async fn get_input_config(
+    device: String,
+    inputs: State<'_, InputConfigs>,
+) -> Result<DeviceInputConfig> {
+    Ok(inputs.get(&device).unwrap_or_default())
+}
+
+/// Record one channel of `device`, or a mix of all, stored at `sample_rate`.
+/// Takes effect from the next session on that device.
+#[tauri::command]
+pub async fn set_input_config(
+    device: String,
+    channel: InputChannel,
+    sample_rate: Option<u32>,
+    inputs: State<'_, InputConfigs>,
+) -> Result<()> {
+    info!(
+        "Setting input config: device={}, channel={:?}, sample_rate={:?}",
+        device, channel, sample_rate
+    );
+    inputs.set(
+        &device,
+        DeviceInputConfig {
+            channel,
+            sample_rate,
+        },
+    )
+}
+
+#[tauri::command]
+pub async fn get_device_priority(
+    priorities: State<'_, DevicePriorities>,
+) -> Result<DevicePriority> {
+    Ok(priorities.config())
+}
+
+/// Set the microphones to record from in order of preference
+#[tauri::command]
+pub async fn set_device_priority(
+    priority: DevicePriority,
+    priorities: State<'_, DevicePriorities>,
+) -> Result<()> {
+    priorities.set_config(priority)
+}
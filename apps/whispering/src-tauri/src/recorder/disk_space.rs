@@ -0,0 +1,141 @@
+//! Keeping recordings from running the disk full.
+//!
+//! A WAV file written until the disk fills up is cut off mid-sample, with a
+//! header claiming audio that isn't there. So a recording only starts with
+//! at least `minFreeDiskMb` free in its folder, and while it runs the free
+//! space is checked every few seconds. Once it drops below that, capture is
+//! paused with the file left valid, and the user is warned; the recording
+//! carries on with `resume_recording` once there's room again.
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, DiskSpaceLow};
+use crate::i18n;
+use crate::notifications::notify;
+use crate::recorder::AppData;
+use crate::settings::{NativeSettings, SettingsStore};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use sysinfo::Disks;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Payload of `recorder://disk-space-low`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpace {
+    /// The recording's file
+    pub path: String,
+    pub available_bytes: u64,
+    pub threshold_bytes: u64,
+}
+
+/// The free space the settings ask for, in bytes; 0 turns the guard off
+pub fn threshold_bytes(settings: &NativeSettings) -> u64 {
+    u64::from(settings.min_free_disk_mb) * 1024 * 1024
+}
+
+/// Free bytes on the disk holding `path`, or `None` if that can't be told
+pub fn available_bytes(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Fail if `dir` has less than `threshold` bytes free. Disks whose free
+/// space can't be read are let through.
+pub fn ensure_space(dir: &Path, threshold: u64) -> Result<()> {
+    if threshold == 0 {
+        return Ok(());
+    }
+    match available_bytes(dir) {
+        Some(available) if available < threshold => Err(AppError::DiskFull(format!(
+            "{} free in {}, and recording needs {}",
+            format_bytes(available),
+            dir.display(),
+            format_bytes(threshold)
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `bytes` in MB below a gigabyte, otherwise in GB
+pub fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb < 1024.0 {
+        format!("{:.0} MB", mb)
+    } else {
+        format!("{:.1} GB", mb / 1024.0)
+    }
+}
+
+/// Check free space every few seconds while the session captures into
+/// `path`, pausing it when space runs low. Stops once the recording is
+/// stopped, paused or replaced by another.
+pub fn watch(app: AppHandle, path: PathBuf) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        if !is_capturing_into(&app, &path) {
+            return;
+        }
+        let threshold = app
+            .try_state::<SettingsStore>()
+            .map(|settings| threshold_bytes(&settings.get()))
+            .unwrap_or_default();
+        let Some(available) = path.parent().and_then(available_bytes) else {
+            continue;
+        };
+        if threshold == 0 || available >= threshold {
+            continue;
+        }
+
+        let data = app.state::<AppData>();
+        let Ok(mut recorder) = data.recorder.lock() else {
+            return;
+        };
+        // It may have been stopped while the disks were read
+        if recorder.recording_path() != Some(path.as_path()) || !recorder.is_capturing() {
+            return;
+        }
+        if let Err(e) = recorder.pause_capture() {
+            warn!("Failed to pause recording on low disk space: {}", e);
+            return;
+        }
+        drop(recorder);
+
+        info!("Paused recording with {} free", format_bytes(available));
+        let _ = DiskSpaceLow::emit(
+            &app,
+            &DiskSpace {
+                path: path.to_string_lossy().to_string(),
+                available_bytes: available,
+                threshold_bytes: threshold,
+            },
+        );
+        notify(
+            &app,
+            &i18n::t_args(
+                "notify-disk-space-low",
+                &[("available", &format_bytes(available))],
+            ),
+        );
+        return;
+    });
+}
+
+fn is_capturing_into(app: &AppHandle, path: &Path) -> bool {
+    let data = app.state::<AppData>();
+    data.recorder
+        .lock()
+        .map(|recorder| recorder.is_capturing() && recorder.recording_path() == Some(path))
+        .unwrap_or(false)
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+/// Most buffers kept for reuse; blocks beyond them, held by a subscriber
+/// that fell behind, are allocated and freed as before
+const MAX_POOLED: usize = 64;
+
+/// Sample buffers reused from one stream callback to the next
+///
+/// A block of captured audio is shared by the recording file's queue and
+/// every subscriber, and lives until the last of them is done with it. The
+/// pool keeps the buffers it hands out and fills again any that nobody else
+/// holds any more, so once warmed up the callback doesn't allocate.
+#[derive(Debug, Default)]
+pub struct FramePool {
+    buffers: Vec<Arc<Vec<f32>>>,
+    /// Where to look for a free buffer first; the oldest handed out is the
+    /// likeliest to be released
+    next: usize,
+}
+
+impl FramePool {
+    /// A shared buffer holding `samples`
+    pub fn fill(&mut self, samples: &[f32]) -> Arc<Vec<f32>> {
+        for _ in 0..self.buffers.len() {
+            let index = self.next % self.buffers.len();
+            self.next = index + 1;
+            if let Some(buffer) = Arc::get_mut(&mut self.buffers[index]) {
+                buffer.clear();
+                buffer.extend_from_slice(samples);
+                return self.buffers[index].clone();
+            }
+        }
+        let buffer = Arc::new(samples.to_vec());
+        if self.buffers.len() < MAX_POOLED {
+            self.buffers.push(buffer.clone());
+        }
+        buffer
+    }
+}
@@ -0,0 +1,204 @@
+//! Per-device input settings: which channel to record and at what rate.
+//!
+//! Some USB interfaces put the microphone on one side of a stereo pair, and
+//! many only really run at 44.1 or 48 kHz while advertising lower rates,
+//! which garbles audio opened at 16 kHz. A configured device is therefore
+//! opened at its own default format; the chosen channel is picked out and
+//! resampled in the capture pipeline instead.
+
+use crate::error::{AppError, Result};
+use crate::recorder::resample::LinearResampler;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+pub const INPUT_FILE: &str = "input.json";
+
+/// Sample rates a recording can be stored at
+pub const SAMPLE_RATE_RANGE: RangeInclusive<u32> = 8_000..=192_000;
+
+/// Which channel of a multichannel device to record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputChannel {
+    /// Average of all channels
+    #[default]
+    Mix,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInputConfig {
+    #[serde(default)]
+    pub channel: InputChannel,
+    /// Rate recordings are stored at; the session's requested rate if unset
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+}
+
+/// Input settings by device name
+pub struct InputConfigs {
+    path: PathBuf,
+    configs: Mutex<HashMap<String, DeviceInputConfig>>,
+}
+
+impl InputConfigs {
+    pub fn open(path: &Path) -> Self {
+        let configs = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            configs: Mutex::new(configs),
+        }
+    }
+
+    pub fn get(&self, device: &str) -> Option<DeviceInputConfig> {
+        self.configs
+            .lock()
+            .ok()
+            .and_then(|configs| configs.get(device).cloned())
+    }
+
+    /// Store the settings of `device`; the defaults remove them, returning
+    /// the device to automatic format selection
+    pub fn set(&self, device: &str, config: DeviceInputConfig) -> Result<()> {
+        if let Some(rate) = config.sample_rate {
+            if !SAMPLE_RATE_RANGE.contains(&rate) {
+                return Err(AppError::InvalidInput(format!(
+                    "Sample rate must be between {} and {} Hz",
+                    SAMPLE_RATE_RANGE.start(),
+                    SAMPLE_RATE_RANGE.end()
+                )));
+            }
+        }
+        let mut configs = self
+            .configs
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock input settings: {}", e)))?;
+        if config == DeviceInputConfig::default() {
+            configs.remove(device);
+        } else {
+            configs.insert(device.to_string(), config);
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*configs).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize input settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+/// Turns the device's interleaved audio into what gets recorded: the chosen
+/// channel (or all of them), at the recording's sample rate
+pub struct InputConverter {
+    channels: usize,
+    /// `None` keeps every channel
+    channel: Option<InputChannel>,
+    /// One per output channel; empty when the rates already match
+    resamplers: Vec<LinearResampler>,
+    /// Per output channel before and after resampling, reused between
+    /// blocks so converting doesn't allocate
+    selected: Vec<Vec<f32>>,
+    resampled: Vec<Vec<f32>>,
+}
+
+impl InputConverter {
+    /// A converter from `channels` at `source_rate`, or `None` if the audio
+    /// can be recorded as it comes
+    pub fn new(
+        channels: u16,
+        channel: Option<InputChannel>,
+        source_rate: u32,
+        target_rate: u32,
+    ) -> Option<Self> {
+        let channel = channel.filter(|_| channels > 1);
+        if channel.is_none() && source_rate == target_rate {
+            return None;
+        }
+        let output_channels = if channel.is_some() { 1 } else { channels };
+        let resamplers = if source_rate == target_rate {
+            Vec::new()
+        } else {
+            (0..output_channels)
+                .map(|_| LinearResampler::new(source_rate, target_rate))
+                .collect()
+        };
+        Some(Self {
+            channels: channels.max(1) as usize,
+            channel,
+            resamplers,
+            selected: vec![Vec::new(); output_channels as usize],
+            resampled: vec![Vec::new(); output_channels as usize],
+        })
+    }
+
+    pub fn output_channels(&self) -> u16 {
+        if self.channel.is_some() {
+            1
+        } else {
+            self.channels as u16
+        }
+    }
+
+    /// Convert a block of interleaved device audio, appending it to `output`
+    pub fn process_into(&mut self, interleaved: &[f32], output: &mut Vec<f32>) {
+        for channel in self.selected.iter_mut() {
+            channel.clear();
+        }
+        let frames = interleaved.chunks(self.channels);
+        match self.channel {
+            Some(InputChannel::Mix) => self.selected[0]
+                .extend(frames.map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)),
+            Some(InputChannel::Left) => self.selected[0].extend(frames.map(|frame| frame[0])),
+            Some(InputChannel::Right) => self.selected[0]
+                .extend(frames.map(|frame| frame.get(1).copied().unwrap_or(frame[0]))),
+            None => {
+                for frame in frames {
+                    for (channel, &sample) in self.selected.iter_mut().zip(frame) {
+                        channel.push(sample);
+                    }
+                }
+            }
+        }
+        let channels = if self.resamplers.is_empty() {
+            &self.selected
+        } else {
+            for ((samples, resampler), resampled) in self
+                .selected
+                .iter()
+                .zip(self.resamplers.iter_mut())
+                .zip(self.resampled.iter_mut())
+            {
+                resampled.clear();
+                resampler.process_into(samples, resampled);
+            }
+            &self.resampled
+        };
+        interleave_into(channels, output);
+    }
+}
+
+fn interleave_into(channels: &[Vec<f32>], output: &mut Vec<f32>) {
+    if let [channel] = channels {
+        output.extend_from_slice(channel);
+        return;
+    }
+    let frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+    output.reserve(frames * channels.len());
+    for i in 0..frames {
+        output.extend(channels.iter().map(|channel| channel[i]));
+    }
+}
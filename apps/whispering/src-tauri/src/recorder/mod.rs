@@ -1,11 +1,22 @@
+pub mod backend;
+pub mod bluetooth;
 pub mod commands;
+pub mod disk_space;
+mod frame_pool;
+pub mod input;
+mod pre_roll;
+pub mod priority;
 pub mod recorder;
+pub mod resample;
+pub mod spill;
 pub mod wav_writer;
 
 // Export everything from commands for easy access
 pub use commands::{
-    cancel_recording, close_recording_session, enumerate_recording_devices,
-    get_current_recording_id, init_recording_session, start_recording, stop_recording, AppData,
+    cancel_recording, close_recording_session, enumerate_recording_devices, get_capture_memory,
+    get_current_recording_id, get_device_priority, get_input_config, init_recording_session,
+    list_audio_backends, prewarm_recording_device, resume_recording, set_device_priority,
+    set_input_config, start_recording, stop_recording, AppData,
 };
 
 // Export key types from recorder
@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+
+/// Shortest and longest pre-roll accepted; 0 turns it off
+pub const PRE_ROLL_RANGE_MS: std::ops::RangeInclusive<u32> = 500..=2000;
+
+/// Circular buffer of the most recent audio captured while armed but not
+/// recording, so words spoken just before the shortcut aren't lost
+///
+/// Holds interleaved f32 samples, the format the WAV writer stores.
+#[derive(Debug, Default)]
+pub struct PreRoll {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl PreRoll {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Samples needed to hold `ms` of audio
+    pub fn capacity_for(ms: u32, sample_rate: u32, channels: u16) -> usize {
+        (ms as u64 * sample_rate as u64 / 1000) as usize * channels as usize
+    }
+
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let excess = self.samples.len().saturating_sub(capacity);
+        self.samples.drain(..excess);
+    }
+
+    pub fn push<T: Copy>(&mut self, data: &[T], to_f32: impl Fn(T) -> f32) {
+        if self.capacity == 0 {
+            return;
+        }
+        // Only the tail of an oversized block can fit
+        let data = &data[data.len().saturating_sub(self.capacity)..];
+        let excess = (self.samples.len() + data.len()).saturating_sub(self.capacity);
+        self.samples.drain(..excess);
+        self.samples.extend(data.iter().map(|&s| to_f32(s)));
+    }
+
+    /// Everything buffered, oldest first, leaving the buffer empty
+    pub fn take(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+}
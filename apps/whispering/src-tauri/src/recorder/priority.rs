@@ -0,0 +1,145 @@
+//! An ordered list of preferred microphones ("Shure MV7 if present, else
+//! AirPods, else built-in"), so docking and undocking doesn't leave the app
+//! pointing at a device that's gone.
+//!
+//! The list is evaluated at each recording start and whenever devices come
+//! or go. cpal has no hot-plug notifications, so devices are polled while
+//! the list is enabled; a change of preferred device closes any stream
+//! parked on the old one and is announced on `recorder://preferred-device`.
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, PreferredDeviceChanged};
+use crate::recorder::commands::AppData;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+pub const DEVICE_PRIORITY_FILE: &str = "device_priority.json";
+
+/// How often devices are listed to notice hot-plugging
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DevicePriority {
+    pub enabled: bool,
+    /// Most preferred first. Each matches devices whose name contains it,
+    /// ignoring case, so "MV7" finds "Microphone (Shure MV7)".
+    pub devices: Vec<String>,
+}
+
+impl DevicePriority {
+    /// The most preferred of `available` device names, if any is listed
+    pub fn choose(&self, available: &[String]) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        self.devices.iter().find_map(|wanted| {
+            let wanted = wanted.trim().to_lowercase();
+            if wanted.is_empty() {
+                return None;
+            }
+            available
+                .iter()
+                .find(|name| name.to_lowercase().contains(&wanted))
+                .cloned()
+        })
+    }
+}
+
+/// Payload of `recorder://preferred-device`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferredDevice {
+    /// `None` when no listed device is connected
+    pub device: Option<String>,
+}
+
+pub struct DevicePriorities {
+    path: PathBuf,
+    config: Mutex<DevicePriority>,
+    /// Preferred device as of the last poll
+    current: Mutex<Option<String>>,
+}
+
+impl DevicePriorities {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                DevicePriority::default()
+            }),
+            Err(_) => DevicePriority::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            current: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> DevicePriority {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: DevicePriority) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize device priority: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock device priority: {}", e)))? =
+            config;
+        Ok(())
+    }
+
+    /// Poll for hot-plugged devices for as long as the app runs
+    pub fn watch(app: AppHandle) {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let priorities = app.state::<DevicePriorities>();
+            let config = priorities.config();
+            if !config.enabled {
+                continue;
+            }
+            let data = app.state::<AppData>();
+            let Ok(mut recorder) = data.recorder.lock() else {
+                continue;
+            };
+            let available = match recorder.enumerate_devices() {
+                Ok(available) => available,
+                Err(e) => {
+                    debug!("Failed to list devices: {}", e);
+                    continue;
+                }
+            };
+            let preferred = config.choose(&available);
+            let Ok(mut current) = priorities.current.lock() else {
+                continue;
+            };
+            if *current == preferred {
+                continue;
+            }
+            info!("Preferred recording device is now {:?}", preferred);
+            *current = preferred.clone();
+            // A recording in progress keeps its device; parked streams are
+            // reopened on the new one at the next session
+            if let Err(e) = recorder.release_parked() {
+                warn!("Failed to release audio stream: {}", e);
+            }
+            drop(recorder);
+            let _ = PreferredDeviceChanged::emit(&app, &PreferredDevice { device: preferred });
+        });
+    }
+}
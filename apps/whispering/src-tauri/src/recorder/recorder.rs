@@ -1,15 +1,26 @@
+use crate::error::AppError;
+use crate::recorder::backend::AudioBackend;
+use crate::recorder::bluetooth::{self, BluetoothHeadset};
+use crate::recorder::frame_pool::FramePool;
+use crate::recorder::input::{DeviceInputConfig, InputConverter};
+use crate::recorder::pre_roll::{PreRoll, PRE_ROLL_RANGE_MS};
+use crate::recorder::spill::{
+    CaptureMemory, FrameQueue, FrameReceiver, MemoryBudget, DEFAULT_MEMORY_LIMIT,
+    DEFAULT_SPILL_LIMIT, WRITE_QUEUE_LIMIT,
+};
 use crate::recorder::wav_writer::WavWriter;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, SampleFormat, Stream};
 use serde::Serialize;
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-/// Simple result type using String for errors
-pub type Result<T> = std::result::Result<T, String>;
+pub use crate::error::Result;
 
 /// Audio recording metadata - returned to frontend
 #[derive(Debug, Clone, Serialize)]
@@ -20,85 +31,454 @@ pub struct AudioRecording {
     pub channels: u16,
     pub duration_seconds: f32,
     pub file_path: Option<String>, // Path to the WAV file
+    /// Audio missing from the file because it couldn't be written fast
+    /// enough; 0 normally
+    pub dropped_seconds: f32,
 }
 
+/// Payload of `recorder://audio-dropped`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedAudio {
+    /// The recording's file
+    pub path: String,
+    pub seconds: f32,
+}
+
+/// Device identifier that records what the default output device plays
+///
+/// Only Windows (WASAPI) can capture an output device directly. On macOS a
+/// virtual device such as BlackHole is needed, and on Linux PulseAudio and
+/// PipeWire already list "Monitor of ..." sources as regular input devices.
+pub const LOOPBACK_DEVICE: &str = "loopback";
+
+/// Block of captured audio, interleaved as recorded
+///
+/// The recording file's queue and every subscriber get the same shared
+/// samples, so fanning a block out doesn't copy it. Subscribers that want
+/// one channel downmix with [`AudioFrame::mono`], off the audio thread.
+#[derive(Debug, Clone)]
+pub struct AudioFrame {
+    pub samples: Arc<Vec<f32>>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl AudioFrame {
+    /// The samples averaged down to one channel
+    pub fn mono(&self) -> Cow<'_, [f32]> {
+        let channels = self.channels.max(1) as usize;
+        if channels == 1 {
+            return Cow::Borrowed(self.samples.as_slice());
+        }
+        Cow::Owned(
+            self.samples
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+                .collect(),
+        )
+    }
+}
+
+/// Receivers of live audio; closed ones are dropped on the next frame
+type FrameSubscribers = Arc<Mutex<Vec<Arc<FrameQueue>>>>;
+
+/// WAV writer shared with the writer thread; empty while the stream is
+/// parked between recordings
+type WriterSlot = Arc<Mutex<Option<WavWriter>>>;
+
+/// The first error writing the recording file hit, reported when it stops
+type WriteFailure = Arc<Mutex<Option<std::io::Error>>>;
+
+/// Pre-roll shared with the stream callback. Its lock also guards switching
+/// into recording, so no audio falls between the buffer and the file.
+type PreRollSlot = Arc<Mutex<PreRoll>>;
+
 /// Simple recorder commands for worker thread communication
 #[derive(Debug)]
 enum RecorderCmd {
-    Start(mpsc::Sender<()>), // Response channel to confirm command processed
-    Stop(mpsc::Sender<()>),  // Response channel to confirm command processed
+    /// Response channel to confirm command processed, or why the stream
+    /// couldn't be resumed
+    Start(mpsc::Sender<std::result::Result<(), String>>),
+    Stop(mpsc::Sender<()>), // Response channel to confirm command processed
+    /// Pause the stream but keep the device open, for a quick restart
+    Pause,
+    /// Run the stream without recording, filling the pre-roll
+    Arm,
     Shutdown,
 }
 
+/// What a stream was opened with, to tell whether a parked one can be reused
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StreamKey {
+    backend: AudioBackend,
+    device_name: String,
+    preferred_sample_rate: Option<u32>,
+    input: Option<DeviceInputConfig>,
+}
+
 /// Simplified recorder state
+///
+/// Opening a device takes 200–500 ms on most systems, long enough to clip
+/// the first word. With warm start, ending a session only parks the stream
+/// (paused, device still open) and the next session on the same device
+/// resumes it within milliseconds.
 pub struct RecorderState {
     cmd_tx: Option<mpsc::Sender<RecorderCmd>>,
     worker_handle: Option<JoinHandle<()>>,
-    writer: Option<Arc<Mutex<WavWriter>>>,
+    writer: WriterSlot,
+    /// Audio on its way from the stream callback to `writer`, kept within
+    /// `write_budget`
+    write_queue: Arc<FrameQueue>,
+    /// Fixed cap on `write_queue`, which is never spilled
+    write_budget: Arc<MemoryBudget>,
+    /// Thread writing `write_queue` to the file
+    write_handle: Option<JoinHandle<()>>,
+    write_failure: WriteFailure,
+    stream_key: Option<StreamKey>,
+    /// Host API devices are listed and opened with
+    backend: AudioBackend,
+    /// Set by the stream's error callback, e.g. when the device is unplugged
+    stream_failed: Arc<AtomicBool>,
+    pre_roll: PreRollSlot,
+    /// How much audio from before `start_recording` to keep; 0 for none
+    pre_roll_ms: u32,
     is_recording: Arc<AtomicBool>,
     sample_rate: u32,
     channels: u16,
     file_path: Option<PathBuf>,
+    frame_subscribers: FrameSubscribers,
+    /// Caps the audio queued for subscribers in RAM (see `spill`)
+    memory_budget: Arc<MemoryBudget>,
+    /// Headset check per selected device, as reported (see `bluetooth`);
+    /// `None` for devices that aren't headsets
+    headsets: HashMap<String, Option<BluetoothHeadset>>,
 }
 
 impl RecorderState {
     pub fn new() -> Self {
+        let memory_budget = Arc::new(MemoryBudget::new(DEFAULT_MEMORY_LIMIT, DEFAULT_SPILL_LIMIT));
+        let write_budget = Arc::new(MemoryBudget::new(WRITE_QUEUE_LIMIT, 0));
         Self {
             cmd_tx: None,
             worker_handle: None,
-            writer: None,
+            writer: Arc::new(Mutex::new(None)),
+            write_queue: FrameQueue::new(write_budget.clone()),
+            write_budget,
+            write_handle: None,
+            write_failure: Arc::new(Mutex::new(None)),
+            stream_key: None,
+            backend: AudioBackend::default(),
+            stream_failed: Arc::new(AtomicBool::new(false)),
+            pre_roll: Arc::new(Mutex::new(PreRoll::default())),
+            pre_roll_ms: 0,
             is_recording: Arc::new(AtomicBool::new(false)),
             sample_rate: 0,
             channels: 0,
             file_path: None,
+            frame_subscribers: Arc::new(Mutex::new(Vec::new())),
+            memory_budget,
+            headsets: HashMap::new(),
+        }
+    }
+
+    /// Receive captured audio as it arrives, for as long as the receiver lives
+    ///
+    /// Frames are only delivered while recording, as recorded; see
+    /// [`AudioFrame::mono`] for one channel. Subscriptions survive
+    /// session changes, so a subscriber can outlive a single recording. A
+    /// subscriber that falls behind has its backlog spilled to disk by the
+    /// writer thread rather than held in memory, and loses new frames once
+    /// that's full too.
+    pub fn subscribe_frames(&self) -> FrameReceiver {
+        let queue = FrameQueue::new(self.memory_budget.clone());
+        if let Ok(mut subscribers) = self.frame_subscribers.lock() {
+            subscribers.push(queue.clone());
+        }
+        FrameReceiver::new(queue)
+    }
+
+    /// How much captured audio is held in memory and spilled to disk
+    pub fn memory_usage(&self) -> CaptureMemory {
+        let subscribers = self
+            .frame_subscribers
+            .lock()
+            .map(|subscribers| subscribers.iter().filter(|q| !q.is_closed()).count())
+            .unwrap_or(0);
+        let pre_roll_bytes =
+            PreRoll::capacity_for(self.pre_roll_ms, self.sample_rate, self.channels)
+                * std::mem::size_of::<f32>();
+        CaptureMemory {
+            memory_bytes: self.memory_budget.used(),
+            spilled_bytes: self.memory_budget.spilled(),
+            limit_bytes: self.memory_budget.limit(),
+            spill_limit_bytes: self.memory_budget.spill_limit(),
+            dropped_frames: self.memory_budget.dropped_frames(),
+            dropped_bytes: self.memory_budget.dropped_bytes(),
+            file_queue_bytes: self.write_budget.used(),
+            file_dropped_bytes: self.write_budget.dropped_bytes(),
+            pre_roll_bytes,
+            subscribers,
         }
     }
 
     /// List available recording devices by name
     pub fn enumerate_devices(&self) -> Result<Vec<String>> {
-        let host = cpal::default_host();
+        let host = self.backend.host()?;
+        let loopback = cfg!(target_os = "windows") && self.backend == AudioBackend::Default;
         let devices = host
             .input_devices()
-            .map_err(|e| format!("Failed to get input devices: {}", e))?
+            .map_err(|e| AppError::Internal(format!("Failed to get input devices: {}", e)))?
             .filter_map(|device| device.name().ok())
+            .chain(loopback.then(|| LOOPBACK_DEVICE.to_string()))
             .collect();
 
         Ok(devices)
     }
 
     /// Initialize recording session - creates stream and WAV writer
+    ///
+    /// A parked stream on the same device is resumed instead of reopened.
     pub fn init_session(
         &mut self,
         device_name: String,
         output_folder: PathBuf,
         recording_id: String,
         preferred_sample_rate: Option<u32>,
+        input: Option<DeviceInputConfig>,
     ) -> Result<()> {
-        // Clean up any existing session
-        self.close_session()?;
+        let key = StreamKey {
+            backend: self.backend,
+            device_name,
+            preferred_sample_rate,
+            input,
+        };
+        if self.can_reuse(&key) {
+            // End whatever session is still open, keeping the stream
+            self.park_session()?;
+            debug!("Reusing warm audio stream for {}", key.device_name);
+        } else {
+            // Clean up any existing session
+            self.close_session()?;
+            self.open_stream(key)?;
+        }
 
         // Create file path
         let file_path = output_folder.join(format!("{}.wav", recording_id));
 
+        // Create WAV writer
+        let writer = WavWriter::new(file_path.clone(), self.sample_rate, self.channels)
+            .map_err(|e| AppError::Internal(format!("Failed to create WAV file: {}", e)))?;
+        *self
+            .writer
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock writer: {}", e)))? =
+            Some(writer);
+        if let Ok(mut failure) = self.write_failure.lock() {
+            *failure = None;
+        }
+        self.write_queue.take_dropped_bytes();
+        self.file_path = Some(file_path);
+
+        info!(
+            "Recording session initialized: {} Hz, {} channels, file: {:?}",
+            self.sample_rate, self.channels, self.file_path
+        );
+
+        Ok(())
+    }
+
+    /// Open a device ahead of the first recording and park its stream, so
+    /// even that recording starts without delay. Does nothing mid-session.
+    pub fn prewarm(
+        &mut self,
+        device_name: String,
+        preferred_sample_rate: Option<u32>,
+        input: Option<DeviceInputConfig>,
+    ) -> Result<()> {
+        let key = StreamKey {
+            backend: self.backend,
+            device_name,
+            preferred_sample_rate,
+            input,
+        };
+        if self.file_path.is_some() || self.can_reuse(&key) {
+            return Ok(());
+        }
+        self.close_session()?;
+        self.open_stream(key)?;
+        self.park_session()?;
+        info!("Audio stream pre-warmed: {} Hz, {} channels", self.sample_rate, self.channels);
+        Ok(())
+    }
+
+    /// Keep the last `ms` of audio from before each recording starts, taken
+    /// from the parked stream. Needs warm start, since otherwise nothing is
+    /// listening before the shortcut is pressed; while armed the microphone
+    /// stays active rather than paused.
+    pub fn set_pre_roll(&mut self, ms: u32) {
+        let ms = if ms == 0 {
+            0
+        } else {
+            ms.clamp(*PRE_ROLL_RANGE_MS.start(), *PRE_ROLL_RANGE_MS.end())
+        };
+        if ms == self.pre_roll_ms {
+            return;
+        }
+        self.pre_roll_ms = ms;
+        if let Ok(mut pre_roll) = self.pre_roll.lock() {
+            pre_roll.resize(PreRoll::capacity_for(ms, self.sample_rate, self.channels));
+        }
+        // A parked stream starts or stops listening to match
+        if let (Some(tx), None) = (&self.cmd_tx, &self.file_path) {
+            let _ = tx.send(if ms > 0 { RecorderCmd::Arm } else { RecorderCmd::Pause });
+        }
+    }
+
+    /// Switch host API, closing a parked stream opened with the old one
+    /// (ASIO drivers often allow a single client). Takes effect from the
+    /// next session.
+    pub fn set_backend(&mut self, backend: AudioBackend) {
+        if backend == self.backend {
+            return;
+        }
+        info!("Switching audio backend to {:?}", backend);
+        self.backend = backend;
+        self.headsets.clear();
+        if let Err(e) = self.release_parked() {
+            error!("Failed to release audio stream: {}", e);
+        }
+    }
+
+    /// The device to record from when `device_name` is selected, steering
+    /// away from a Bluetooth headset microphone if `avoid_headset`. The
+    /// headset is reported the first time it's checked, so the UI can
+    /// explain the quality drop once rather than on every recording.
+    pub fn choose_device(
+        &mut self,
+        device_name: String,
+        avoid_headset: bool,
+    ) -> (String, Option<BluetoothHeadset>) {
+        let mut first_check = false;
+        if !self.headsets.contains_key(&device_name) {
+            first_check = true;
+            let headset = self.check_headset(&device_name, avoid_headset);
+            self.headsets.insert(device_name.clone(), headset);
+        }
+        let Some(Some(headset)) = self.headsets.get_mut(&device_name) else {
+            return (device_name, None);
+        };
+        // The setting may have changed since the check
+        let switched = avoid_headset && headset.alternative.is_some();
+        let changed = headset.switched != switched;
+        headset.switched = switched;
+        let report = (first_check || changed).then(|| headset.clone());
+        let device = match &headset.alternative {
+            Some(alternative) if switched => alternative.clone(),
+            _ => device_name,
+        };
+        (device, report)
+    }
+
+    fn check_headset(&self, device_name: &str, avoid_headset: bool) -> Option<BluetoothHeadset> {
+        if is_loopback(device_name) {
+            return None;
+        }
+        let host = self.backend.host().ok()?;
+        let device = find_device(&host, device_name).ok()?;
+        if !bluetooth::is_headset_mic(&device) {
+            return None;
+        }
+        let alternative = bluetooth::alternative_mic(&host);
+        info!(
+            "{} is a Bluetooth headset microphone; alternative: {:?}",
+            device_name, alternative
+        );
+        Some(BluetoothHeadset {
+            device: device.name().unwrap_or_else(|_| device_name.to_string()),
+            switched: avoid_headset && alternative.is_some(),
+            alternative,
+        })
+    }
+
+    fn can_reuse(&self, key: &StreamKey) -> bool {
+        self.cmd_tx.is_some()
+            && self.stream_key.as_ref() == Some(key)
+            && !self.stream_failed.load(Ordering::Relaxed)
+    }
+
+    /// Open the device and start the worker thread that owns its stream
+    fn open_stream(&mut self, key: StreamKey) -> Result<()> {
+        let device_name = &key.device_name;
+        let preferred_sample_rate = key.preferred_sample_rate;
         // Find the device
-        let host = cpal::default_host();
-        let device = find_device(&host, &device_name)?;
+        let host = key.backend.host()?;
+        let device = find_device(&host, device_name)?;
 
-        // Get optimal config for voice with optional preferred sample rate
-        let config = get_optimal_config(&device, preferred_sample_rate)?;
+        // Get optimal config for voice with optional preferred sample rate;
+        // loopback has to use the output device's own format, and configured
+        // devices run at their default one (see `input`)
+        let target_sample_rate = key
+            .input
+            .as_ref()
+            .and_then(|input| input.sample_rate)
+            .or(preferred_sample_rate)
+            .unwrap_or(16000);
+        let (config, converter) = if is_loopback(device_name) {
+            let config = device
+                .default_output_config()
+                .map_err(|e| default_config_error(e, "output"))?;
+            if !SUPPORTED_FORMATS.contains(&config.sample_format()) {
+                return Err(AppError::UnsupportedDevice(format!(
+                    "Output format {:?} can't be recorded",
+                    config.sample_format()
+                )));
+            }
+            (config, None)
+        } else {
+            let config = match &key.input {
+                Some(_) => native_config(&device, target_sample_rate)?,
+                None => get_optimal_config(&device, target_sample_rate)?,
+            };
+            let converter = InputConverter::new(
+                config.channels(),
+                key.input.as_ref().map(|input| input.channel),
+                config.sample_rate().0,
+                target_sample_rate,
+            );
+            (config, converter)
+        };
         let sample_format = config.sample_format();
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels();
+        let device_sample_rate = config.sample_rate().0;
+        let device_channels = config.channels();
 
-        // Create WAV writer
-        let writer = WavWriter::new(file_path.clone(), sample_rate, channels)
-            .map_err(|e| format!("Failed to create WAV file: {}", e))?;
-        let writer = Arc::new(Mutex::new(writer));
+        // What gets recorded, after channel selection and resampling
+        let (sample_rate, channels) = match &converter {
+            Some(converter) => (target_sample_rate, converter.output_channels()),
+            None => (device_sample_rate, device_channels),
+        };
+        if converter.is_some() {
+            info!(
+                "Converting {} Hz, {} channels from {} to {} Hz, {} channels",
+                device_sample_rate, device_channels, device_name, sample_rate, channels
+            );
+        }
+
+        // Fresh writer slot, filled in per session, and the queue feeding it
+        let writer: WriterSlot = Arc::new(Mutex::new(None));
+        let write_queue = FrameQueue::new(self.write_budget.clone());
+        let pre_roll: PreRollSlot = Arc::new(Mutex::new(PreRoll::new(PreRoll::capacity_for(
+            self.pre_roll_ms,
+            sample_rate,
+            channels,
+        ))));
+        self.stream_failed = Arc::new(AtomicBool::new(false));
+        let stream_failed = self.stream_failed.clone();
 
         // Create stream config
         let stream_config = cpal::StreamConfig {
-            channels,
-            sample_rate: cpal::SampleRate(sample_rate),
+            channels: device_channels,
+            sample_rate: cpal::SampleRate(device_sample_rate),
             buffer_size: cpal::BufferSize::Default,
         };
 
@@ -109,9 +489,24 @@ impl RecorderState {
         // Create command channel for worker thread
         let (cmd_tx, cmd_rx) = mpsc::channel();
 
+        // Worker reports whether the device could actually be opened
+        let (ready_tx, ready_rx) = mpsc::channel::<std::result::Result<(), String>>();
+
         // Clone for the worker thread
-        let writer_clone = writer.clone();
-        let is_recording_clone = is_recording.clone();
+        let worker_pre_roll = pre_roll.clone();
+        let worker_writer = writer.clone();
+        let capture = Capture {
+            is_recording: is_recording.clone(),
+            write_queue: write_queue.clone(),
+            sample_rate,
+            pre_roll: pre_roll.clone(),
+            channels,
+            pool: FramePool::default(),
+            subscribers: self.frame_subscribers.clone(),
+            converter,
+            input: Vec::new(),
+            converted: Vec::new(),
+        };
 
         // Create the worker thread that owns the stream
         let worker = thread::spawn(move || {
@@ -120,12 +515,13 @@ impl RecorderState {
                 &device,
                 &stream_config,
                 sample_format,
-                is_recording_clone,
-                writer_clone,
+                capture,
+                stream_failed,
             ) {
                 Ok(s) => s,
                 Err(e) => {
                     error!("Failed to build stream: {}", e);
+                    let _ = ready_tx.send(Err(e.to_string()));
                     return;
                 }
             };
@@ -133,25 +529,45 @@ impl RecorderState {
             // Start the stream
             if let Err(e) = stream.play() {
                 error!("Failed to start stream: {}", e);
+                let _ = ready_tx.send(Err(format!("Failed to start stream: {}", e)));
                 return;
             }
 
             info!("Audio stream started successfully");
+            let _ = ready_tx.send(Ok(()));
 
             // Keep thread alive by waiting for commands
             // This blocks but is responsive - no sleeping!
             loop {
                 match cmd_rx.recv() {
                     Ok(RecorderCmd::Start(reply_tx)) => {
-                        is_recording.store(true, Ordering::Relaxed);
+                        // Resumes a parked stream; a no-op if it's running
+                        if let Err(e) = stream.play() {
+                            error!("Failed to resume stream: {}", e);
+                            let _ = reply_tx.send(Err(format!("Failed to resume stream: {}", e)));
+                            continue;
+                        }
+                        start_with_pre_roll(&worker_pre_roll, &worker_writer, &is_recording);
                         info!("Recording started");
-                        let _ = reply_tx.send(()); // Confirm command processed
+                        let _ = reply_tx.send(Ok(())); // Confirm command processed
                     }
                     Ok(RecorderCmd::Stop(reply_tx)) => {
                         is_recording.store(false, Ordering::Relaxed);
                         info!("Recording stopped");
                         let _ = reply_tx.send(()); // Confirm command processed
                     }
+                    Ok(RecorderCmd::Pause) => {
+                        // Backends that can't pause keep running; the
+                        // callback discards audio while not recording
+                        if let Err(e) = stream.pause() {
+                            debug!("Can't pause stream, leaving it running: {}", e);
+                        }
+                    }
+                    Ok(RecorderCmd::Arm) => {
+                        if let Err(e) = stream.play() {
+                            error!("Failed to arm stream: {}", e);
+                        }
+                    }
                     Ok(RecorderCmd::Shutdown) | Err(_) => {
                         info!("Shutting down audio worker");
                         break;
@@ -161,18 +577,45 @@ impl RecorderState {
             // Stream automatically drops here
         });
 
+        // Surface device failures here instead of on the first start command
+        match ready_rx.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                let _ = worker.join();
+                return Err(AppError::DeviceBusy(e));
+            }
+            Err(_) => {
+                let _ = worker.join();
+                return Err(AppError::DeviceBusy("Audio worker exited unexpectedly".to_string()));
+            }
+        }
+
+        let write_handle = {
+            let queue = write_queue.clone();
+            let writer = writer.clone();
+            let failure = self.write_failure.clone();
+            let subscribers = self.frame_subscribers.clone();
+            let budget = self.memory_budget.clone();
+            thread::spawn(move || {
+                while tauri::async_runtime::block_on(queue.wait()) {
+                    write_queued(&queue, &writer, &failure);
+                    if budget.should_spill() {
+                        spill_backlogs(&subscribers);
+                    }
+                }
+            })
+        };
+
         // Store everything
         self.cmd_tx = Some(cmd_tx);
         self.worker_handle = Some(worker);
-        self.writer = Some(writer);
+        self.writer = writer;
+        self.write_queue = write_queue;
+        self.write_handle = Some(write_handle);
+        self.pre_roll = pre_roll;
+        self.stream_key = Some(key);
         self.sample_rate = sample_rate;
         self.channels = channels;
-        self.file_path = Some(file_path);
-
-        info!(
-            "Recording session initialized: {} Hz, {} channels, file: {:?}",
-            sample_rate, channels, self.file_path
-        );
 
         Ok(())
     }
@@ -182,12 +625,16 @@ impl RecorderState {
         if let Some(tx) = &self.cmd_tx {
             let (reply_tx, reply_rx) = mpsc::channel();
             tx.send(RecorderCmd::Start(reply_tx))
-                .map_err(|e| format!("Failed to send start command: {}", e))?;
+                .map_err(|e| AppError::Internal(format!("Failed to send start command: {}", e)))?;
             // Wait for worker thread to confirm the command was processed
-            reply_rx.recv()
-                .map_err(|e| format!("Failed to receive start confirmation: {}", e))?;
+            reply_rx
+                .recv()
+                .map_err(|e| {
+                    AppError::Internal(format!("Failed to receive start confirmation: {}", e))
+                })?
+                .map_err(AppError::DeviceBusy)?;
         } else {
-            return Err("No recording session initialized".to_string());
+            return Err(AppError::NoSession("No recording session initialized".to_string()));
         }
         Ok(())
     }
@@ -198,23 +645,28 @@ impl RecorderState {
         if let Some(tx) = &self.cmd_tx {
             let (reply_tx, reply_rx) = mpsc::channel();
             tx.send(RecorderCmd::Stop(reply_tx))
-                .map_err(|e| format!("Failed to send stop command: {}", e))?;
+                .map_err(|e| AppError::Internal(format!("Failed to send stop command: {}", e)))?;
             // Wait for worker thread to confirm the command was processed
-            reply_rx.recv()
-                .map_err(|e| format!("Failed to receive stop confirmation: {}", e))?;
+            reply_rx.recv().map_err(|e| {
+                AppError::Internal(format!("Failed to receive stop confirmation: {}", e))
+            })?;
         }
 
         // Finalize the WAV file and get metadata
-        let (sample_rate, channels, duration) = if let Some(writer) = &self.writer {
-            let mut w = writer
-                .lock()
-                .map_err(|e| format!("Failed to lock writer: {}", e))?;
+        self.flush_writes();
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock writer: {}", e)))?;
+        let (sample_rate, channels, duration) = if let Some(w) = writer.as_mut() {
             w.finalize()
-                .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+                .map_err(|e| AppError::Internal(format!("Failed to finalize WAV: {}", e)))?;
             w.get_metadata()
         } else {
             (self.sample_rate, self.channels, 0.0)
         };
+        drop(writer);
+        self.take_write_failure()?;
 
         let file_path = self
             .file_path
@@ -223,17 +675,32 @@ impl RecorderState {
 
         info!("Recording stopped: {:.2}s, file: {:?}", duration, file_path);
 
+        // Blocks are queued as recorded, every channel interleaved
+        let dropped_bytes = self.write_queue.take_dropped_bytes();
+        let bytes_per_second = (sample_rate as f32 * channels as f32 * 4.0).max(1.0);
+        let dropped_seconds = dropped_bytes as f32 / bytes_per_second;
+        if dropped_bytes > 0 {
+            warn!(
+                "Recording is missing {:.2}s of audio the disk couldn't keep up with",
+                dropped_seconds
+            );
+        }
+
         Ok(AudioRecording {
             audio_data: Vec::new(), // Empty for file-based recording
             sample_rate,
             channels,
             duration_seconds: duration,
             file_path,
+            dropped_seconds,
         })
     }
 
     /// Cancel recording - stop and delete the file
-    pub fn cancel_recording(&mut self) -> Result<()> {
+    ///
+    /// With `keep_warm`, the stream is parked for the next session rather
+    /// than closed.
+    pub fn cancel_recording(&mut self, keep_warm: bool) -> Result<()> {
         // Send stop command
         if let Some(tx) = &self.cmd_tx {
             let (reply_tx, reply_rx) = mpsc::channel();
@@ -248,11 +715,52 @@ impl RecorderState {
         }
 
         // Clear the session
-        self.close_session()?;
+        if keep_warm {
+            self.park_session()
+        } else {
+            self.close_session()
+        }
+    }
+
+    /// End the session but keep the stream open and paused, so the next
+    /// session on the same device starts without reopening it
+    pub fn park_session(&mut self) -> Result<()> {
+        let Some(tx) = &self.cmd_tx else {
+            return Ok(());
+        };
+        let (reply_tx, reply_rx) = mpsc::channel();
+        if tx.send(RecorderCmd::Stop(reply_tx)).is_ok() {
+            let _ = reply_rx.recv();
+        }
+        // With pre-roll the stream keeps running to fill the buffer
+        let _ = tx.send(if self.pre_roll_ms > 0 {
+            RecorderCmd::Arm
+        } else {
+            RecorderCmd::Pause
+        });
+
+        self.flush_writes();
+        if let Ok(mut writer) = self.writer.lock() {
+            if let Some(mut w) = writer.take() {
+                let _ = w.finalize(); // Ignore errors during cleanup
+            }
+        }
+        self.file_path = None;
 
+        debug!("Recording session parked");
         Ok(())
     }
 
+    /// Close a parked stream, e.g. after warm start was turned off. A session
+    /// in progress is left alone.
+    pub fn release_parked(&mut self) -> Result<()> {
+        if self.cmd_tx.is_none() || self.file_path.is_some() {
+            return Ok(());
+        }
+        info!("Releasing parked audio stream");
+        self.close_session()
+    }
+
     /// Close the recording session
     pub fn close_session(&mut self) -> Result<()> {
         // Send shutdown command to worker thread
@@ -265,14 +773,21 @@ impl RecorderState {
             let _ = handle.join();
         }
 
+        // The stream is gone, so the writer thread gets nothing new
+        self.write_queue.finish();
+        if let Some(handle) = self.write_handle.take() {
+            let _ = handle.join();
+        }
+
         // Finalize and drop the writer
-        if let Some(writer) = self.writer.take() {
-            if let Ok(mut w) = writer.lock() {
+        if let Ok(mut writer) = self.writer.lock() {
+            if let Some(mut w) = writer.take() {
                 let _ = w.finalize(); // Ignore errors during cleanup
             }
         }
 
         // Clear state
+        self.stream_key = None;
         self.file_path = None;
         self.sample_rate = 0;
         self.channels = 0;
@@ -281,6 +796,65 @@ impl RecorderState {
         Ok(())
     }
 
+    /// Stop capturing without ending the session, with the file's header
+    /// brought up to date so it's valid as it is. `start_recording` carries
+    /// on writing to the same file.
+    pub fn pause_capture(&mut self) -> Result<()> {
+        let Some(tx) = &self.cmd_tx else {
+            return Err(AppError::NoSession("No recording session initialized".to_string()));
+        };
+        let (reply_tx, reply_rx) = mpsc::channel();
+        tx.send(RecorderCmd::Stop(reply_tx))
+            .map_err(|e| AppError::Internal(format!("Failed to send stop command: {}", e)))?;
+        reply_rx.recv().map_err(|e| {
+            AppError::Internal(format!("Failed to receive stop confirmation: {}", e))
+        })?;
+        let _ = tx.send(RecorderCmd::Pause);
+
+        // Audio from the pause shouldn't be written as pre-roll on resuming
+        if let Ok(mut pre_roll) = self.pre_roll.lock() {
+            pre_roll.take();
+        }
+        self.flush_writes();
+        if let Some(w) = self
+            .writer
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock writer: {}", e)))?
+            .as_mut()
+        {
+            w.finalize()
+                .map_err(|e| AppError::Internal(format!("Failed to update WAV header: {}", e)))?;
+        }
+        self.take_write_failure()?;
+        info!("Recording paused");
+        Ok(())
+    }
+
+    /// Write out the audio still queued for the file, so it's complete before
+    /// its header is finalized
+    fn flush_writes(&self) {
+        write_queued(&self.write_queue, &self.writer, &self.write_failure);
+    }
+
+    /// Report an error writing the file since the session started
+    fn take_write_failure(&self) -> Result<()> {
+        let failure = self.write_failure.lock().ok().and_then(|mut f| f.take());
+        match failure {
+            Some(e) => Err(AppError::Io(e)),
+            None => Ok(()),
+        }
+    }
+
+    /// The file the current session records into, while there is one
+    pub fn recording_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Whether audio is being written, as opposed to parked or paused
+    pub fn is_capturing(&self) -> bool {
+        self.is_recording.load(Ordering::Acquire)
+    }
+
     /// Get current recording ID if actively recording
     pub fn get_current_recording_id(&self) -> Option<String> {
         if self.is_recording.load(Ordering::Acquire) {
@@ -295,17 +869,37 @@ impl RecorderState {
     }
 }
 
+fn is_loopback(device_name: &str) -> bool {
+    device_name.eq_ignore_ascii_case(LOOPBACK_DEVICE)
+}
+
 /// Find a recording device by name
 fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
+    if is_loopback(device_name) {
+        if !cfg!(target_os = "windows") {
+            return Err(AppError::DeviceNotFound(
+                "System audio capture needs a loopback device on this platform".to_string(),
+            ));
+        }
+        // WASAPI captures an output device when it's opened as an input stream
+        return host
+            .default_output_device()
+            .ok_or_else(|| AppError::DeviceNotFound("No default output device available".to_string()));
+    }
+
+
     // Handle "default" device
     if device_name.to_lowercase() == "default" {
         return host
             .default_input_device()
-            .ok_or_else(|| "No default input device available".to_string());
+            .ok_or_else(|| AppError::DeviceNotFound("No default input device available".to_string()));
     }
 
     // Find specific device
-    let devices: Vec<_> = host.input_devices().map_err(|e| e.to_string())?.collect();
+    let devices: Vec<_> = host
+        .input_devices()
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .collect();
 
     for device in devices {
         if let Ok(name) = device.name() {
@@ -315,35 +909,74 @@ fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
         }
     }
 
-    Err(format!("Device '{}' not found", device_name))
+    Err(AppError::DeviceNotFound(format!("Device '{}' not found", device_name)))
+}
+
+/// Formats the stream callback can convert
+const SUPPORTED_FORMATS: [SampleFormat; 3] = [SampleFormat::F32, SampleFormat::I16, SampleFormat::U16];
+
+/// The device's own default format, which devices that misreport their
+/// supported rates handle reliably
+fn native_config(device: &Device, target_sample_rate: u32) -> Result<cpal::SupportedStreamConfig> {
+    match device.default_input_config() {
+        Ok(config) if SUPPORTED_FORMATS.contains(&config.sample_format()) => Ok(config),
+        Ok(config) => {
+            debug!(
+                "Default input format {:?} unsupported, choosing another",
+                config.sample_format()
+            );
+            get_optimal_config(device, target_sample_rate)
+        }
+        Err(e) => Err(default_config_error(e, "input")),
+    }
+}
+
+/// A device that's gone is reported as such; any other failure to read its
+/// formats means it can't be used, not that it's busy
+fn default_config_error(e: cpal::DefaultStreamConfigError, direction: &str) -> AppError {
+    match e {
+        cpal::DefaultStreamConfigError::DeviceNotAvailable => {
+            AppError::DeviceNotFound("The device is no longer available".to_string())
+        }
+        e => AppError::UnsupportedDevice(format!("No {} configuration: {}", direction, e)),
+    }
 }
 
 /// Get optimal configuration for voice recording
+///
+/// Devices that can't run at `target_sample_rate` (often ones that only
+/// offer 44.1 or 48 kHz) are opened in their default format and resampled
+/// by the stream callback.
 fn get_optimal_config(
     device: &Device,
-    preferred_sample_rate: Option<u32>,
+    target_sample_rate: u32,
 ) -> Result<cpal::SupportedStreamConfig> {
-    // Use preferred sample rate or default to 16kHz for voice
-    let target_sample_rate = preferred_sample_rate.unwrap_or(16000);
-
     let configs: Vec<_> = device
         .supported_input_configs()
-        .map_err(|e| e.to_string())?
+        .map_err(|e| match e {
+            cpal::SupportedStreamConfigsError::DeviceNotAvailable => {
+                AppError::DeviceNotFound("The device is no longer available".to_string())
+            }
+            e => AppError::UnsupportedDevice(format!("Can't list input configurations: {}", e)),
+        })?
         .collect();
 
     if configs.is_empty() {
-        return Err("No supported input configurations".to_string());
+        return Err(AppError::UnsupportedDevice(
+            "No supported input configurations".to_string(),
+        ));
     }
 
     // Filter for supported sample formats only
-    let supported_formats = [SampleFormat::F32, SampleFormat::I16, SampleFormat::U16];
     let compatible_configs: Vec<_> = configs
         .iter()
-        .filter(|config| supported_formats.contains(&config.sample_format()))
+        .filter(|config| SUPPORTED_FORMATS.contains(&config.sample_format()))
         .collect();
 
     if compatible_configs.is_empty() {
-        return Err("No configurations with supported sample formats (F32, I16, U16)".to_string());
+        return Err(AppError::UnsupportedDevice(
+            "No configurations with supported sample formats (F32, I16, U16)".to_string(),
+        ));
     }
 
     // Try to find mono config with target sample rate and supported format
@@ -366,47 +999,76 @@ fn get_optimal_config(
         }
     }
 
-    // If target rate not supported, try to find closest rate
-    let mut best_config = None;
-    let mut best_diff = u32::MAX;
+    // Target rate not supported; resample from the device's default format
+    if let Ok(config) = device.default_input_config() {
+        if SUPPORTED_FORMATS.contains(&config.sample_format()) {
+            return Ok(config);
+        }
+    }
 
-    for config in &compatible_configs {
-        // Prefer mono
-        if config.channels() == 1 {
-            let min_rate = config.min_sample_rate().0;
-            let max_rate = config.max_sample_rate().0;
+    // Otherwise any compatible config at its highest rate
+    let config = compatible_configs[0];
+    Ok(config.with_sample_rate(config.max_sample_rate()))
+}
 
-            // Find closest supported rate
-            let closest_rate = if target_sample_rate < min_rate {
-                min_rate
-            } else if target_sample_rate > max_rate {
-                max_rate
-            } else {
-                target_sample_rate
-            };
+/// What the stream callback does with each block of captured audio
+///
+/// The callback runs every few milliseconds for the whole recording, so it
+/// works in buffers kept from one block to the next, and hands blocks for
+/// the file to the writer thread rather than waiting on the disk.
+struct Capture {
+    is_recording: Arc<AtomicBool>,
+    /// Recorded blocks for the file
+    write_queue: Arc<FrameQueue>,
+    sample_rate: u32,
+    channels: u16,
+    pre_roll: PreRollSlot,
+    /// Buffers for the blocks the file and subscribers share
+    pool: FramePool,
+    subscribers: FrameSubscribers,
+    /// Picks the input channel and resamples; `None` records audio as it comes
+    converter: Option<InputConverter>,
+    /// The block as f32, and after conversion
+    input: Vec<f32>,
+    converted: Vec<f32>,
+}
 
-            let diff = (closest_rate as i32 - target_sample_rate as i32).abs() as u32;
-            if diff < best_diff {
-                best_diff = diff;
-                best_config = Some(config.with_sample_rate(cpal::SampleRate(closest_rate)));
+impl Capture {
+    fn handle<T: Copy>(&mut self, data: &[T], to_f32: impl Fn(T) -> f32) {
+        self.input.clear();
+        self.input.extend(data.iter().map(|&s| to_f32(s)));
+        // Converted even while not recording, so the resampler stays in step
+        let samples: &[f32] = match self.converter.as_mut() {
+            Some(converter) => {
+                self.converted.clear();
+                converter.process_into(&self.input, &mut self.converted);
+                &self.converted
             }
+            None => &self.input,
+        };
+        // Holding the pre-roll lock keeps the switch into recording from
+        // landing between the check and the write
+        let Ok(mut buffered) = self.pre_roll.lock() else {
+            return;
+        };
+        if !self.is_recording.load(Ordering::Relaxed) {
+            buffered.push(samples, |s| s);
+            return;
         }
-    }
-
-    // If still no best config, take any compatible config
-    if best_config.is_none() && !compatible_configs.is_empty() {
-        let config = compatible_configs[0];
-        let min_rate = config.min_sample_rate().0;
-        let max_rate = config.max_sample_rate().0;
-        let rate = if min_rate <= target_sample_rate && max_rate >= target_sample_rate {
-            target_sample_rate
-        } else {
-            min_rate // Use minimum rate as fallback
+        drop(buffered);
+        let frame = AudioFrame {
+            samples: self.pool.fill(samples),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
         };
-        best_config = Some(config.with_sample_rate(cpal::SampleRate(rate)));
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|queue| !queue.is_closed());
+            for queue in subscribers.iter() {
+                queue.push(frame.clone());
+            }
+        }
+        self.write_queue.push(frame);
     }
-
-    best_config.ok_or_else(|| "Failed to find suitable audio configuration".to_string())
 }
 
 /// Build input stream for any supported sample format
@@ -414,60 +1076,108 @@ fn build_input_stream(
     device: &Device,
     config: &cpal::StreamConfig,
     sample_format: SampleFormat,
-    is_recording: Arc<AtomicBool>,
-    writer: Arc<Mutex<WavWriter>>,
+    mut capture: Capture,
+    stream_failed: Arc<AtomicBool>,
 ) -> Result<Stream> {
-    let err_fn = |err| error!("Audio stream error: {}", err);
+    let err_fn = move |err| {
+        error!("Audio stream error: {}", err);
+        stream_failed.store(true, Ordering::Relaxed);
+    };
 
     let stream = match sample_format {
         SampleFormat::F32 => device
             .build_input_stream(
                 config,
-                move |data: &[f32], _: &_| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer.lock() {
-                            let _ = w.write_samples_f32(data);
-                        }
-                    }
-                },
+                move |data: &[f32], _: &_| capture.handle(data, |s| s),
                 err_fn,
                 None,
             )
-            .map_err(|e| format!("Failed to build F32 stream: {}", e))?,
+            .map_err(|e| AppError::DeviceBusy(format!("Failed to build F32 stream: {}", e)))?,
         SampleFormat::I16 => device
             .build_input_stream(
                 config,
-                move |data: &[i16], _: &_| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer.lock() {
-                            let _ = w.write_samples_i16(data);
-                        }
-                    }
-                },
+                move |data: &[i16], _: &_| capture.handle(data, |s| s as f32 / i16::MAX as f32),
                 err_fn,
                 None,
             )
-            .map_err(|e| format!("Failed to build I16 stream: {}", e))?,
+            .map_err(|e| AppError::DeviceBusy(format!("Failed to build I16 stream: {}", e)))?,
         SampleFormat::U16 => device
             .build_input_stream(
                 config,
                 move |data: &[u16], _: &_| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer.lock() {
-                            let _ = w.write_samples_u16(data);
-                        }
-                    }
+                    capture.handle(data, |s| (s as f32 - 32768.0) / 32768.0)
                 },
                 err_fn,
                 None,
             )
-            .map_err(|e| format!("Failed to build U16 stream: {}", e))?,
-        _ => return Err(format!("Unsupported sample format: {:?}", sample_format)),
+            .map_err(|e| AppError::DeviceBusy(format!("Failed to build U16 stream: {}", e)))?,
+        _ => {
+            return Err(AppError::UnsupportedDevice(format!(
+                "Unsupported sample format: {:?}",
+                sample_format
+            )))
+        }
     };
 
     Ok(stream)
 }
 
+/// Write the queued audio to the file. Called by the writer thread and
+/// before the file is finalized; taking frames under the writer's lock keeps
+/// them in order whoever writes them. After a failed write the rest of the
+/// queue is discarded, and the failure kept for `stop_recording` to report.
+fn write_queued(queue: &FrameQueue, writer: &WriterSlot, failure: &WriteFailure) {
+    let Ok(mut slot) = writer.lock() else {
+        return;
+    };
+    while let Some(frame) = queue.try_pop() {
+        let Some(w) = slot.as_mut() else {
+            continue;
+        };
+        let Ok(mut failure) = failure.lock() else {
+            continue;
+        };
+        if failure.is_some() {
+            continue;
+        }
+        if let Err(e) = w.write_samples_f32(&frame.samples) {
+            error!("Failed to write recording: {}", e);
+            *failure = Some(e);
+        }
+    }
+}
+
+/// Spill the subscribers' backlogs, largest first, until they're back under
+/// the memory budget's spilling point
+fn spill_backlogs(subscribers: &FrameSubscribers) {
+    // Not holding the lock the callback publishes under while on the disk
+    let Ok(mut queues) = subscribers.lock().map(|queues| queues.clone()) else {
+        return;
+    };
+    queues.sort_by_cached_key(|queue| std::cmp::Reverse(queue.backlog_bytes()));
+    for queue in queues {
+        queue.spill_excess();
+    }
+}
+
+/// Switch the stream into recording, writing the buffered pre-roll first
+fn start_with_pre_roll(pre_roll: &PreRollSlot, writer: &WriterSlot, is_recording: &AtomicBool) {
+    let Ok(mut buffered) = pre_roll.lock() else {
+        is_recording.store(true, Ordering::Relaxed);
+        return;
+    };
+    let samples = buffered.take();
+    if !samples.is_empty() {
+        if let Ok(mut slot) = writer.lock() {
+            if let Some(w) = slot.as_mut() {
+                let _ = w.write_samples_f32(&samples);
+            }
+        }
+        debug!("Wrote {} pre-roll samples", samples.len());
+    }
+    is_recording.store(true, Ordering::Relaxed);
+}
+
 impl Drop for RecorderState {
     fn drop(&mut self) {
         let _ = self.close_session();
@@ -0,0 +1,90 @@
+use wide::f32x8;
+
+/// Output samples interpolated together in one SIMD batch
+const LANES: usize = 8;
+
+/// Sample rate conversion for live audio, one channel at a time
+///
+/// Linear interpolation is plenty for speech recognition and, unlike the
+/// sinc resampler used for files, works on arbitrarily sized blocks. The
+/// interpolation runs eight output samples at a time in SIMD registers, and
+/// `process_into` appends to a caller-owned buffer so the capture callback
+/// doesn't allocate.
+pub struct LinearResampler {
+    /// Input samples per output sample
+    step: f64,
+    /// Position of the next output sample relative to the start of the next block
+    position: f64,
+    /// Last sample of the previous block, for interpolating across blocks
+    previous: f32,
+}
+
+impl LinearResampler {
+    pub fn new(source_rate: u32, target_rate: u32) -> Self {
+        Self {
+            step: source_rate as f64 / target_rate.max(1) as f64,
+            position: 0.0,
+            previous: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut output = Vec::with_capacity(self.output_len(samples.len()));
+        self.process_into(samples, &mut output);
+        output
+    }
+
+    /// Upper bound on the output for `input_len` samples, for sizing buffers
+    pub fn output_len(&self, input_len: usize) -> usize {
+        (input_len as f64 / self.step) as usize + 1
+    }
+
+    /// Resample `samples`, appending the result to `output`
+    pub fn process_into(&mut self, samples: &[f32], output: &mut Vec<f32>) {
+        if samples.is_empty() {
+            return;
+        }
+        let previous = self.previous;
+        let last = samples.len() - 1;
+        let sample_at = |i: isize| {
+            if i < 0 {
+                previous
+            } else {
+                samples[(i as usize).min(last)]
+            }
+        };
+        let last = last as f64;
+        output.reserve(self.output_len(samples.len()));
+
+        let mut position = self.position;
+        let mut lower = [0f32; LANES];
+        let mut upper = [0f32; LANES];
+        let mut fraction = [0f32; LANES];
+        // Whole batches whose last output still falls inside this block
+        while position + self.step * (LANES - 1) as f64 <= last {
+            for lane in 0..LANES {
+                let lane_position = position + self.step * lane as f64;
+                let index = lane_position.floor();
+                fraction[lane] = (lane_position - index) as f32;
+                lower[lane] = sample_at(index as isize);
+                upper[lane] = sample_at(index as isize + 1);
+            }
+            let lower = f32x8::from(lower);
+            let interpolated = lower + (f32x8::from(upper) - lower) * f32x8::from(fraction);
+            output.extend_from_slice(&interpolated.to_array());
+            position += self.step * LANES as f64;
+        }
+        // The remainder one at a time
+        while position <= last {
+            let index = position.floor();
+            let fraction = (position - index) as f32;
+            let index = index as isize;
+            let lower = sample_at(index);
+            output.push(lower + (sample_at(index + 1) - lower) * fraction);
+            position += self.step;
+        }
+
+        self.position = position - samples.len() as f64;
+        self.previous = samples[samples.len() - 1];
+    }
+}
@@ -0,0 +1,468 @@
+//! Frame queues that stay within a memory budget.
+//!
+//! Captured audio fans out to subscribers (e.g. streaming transcription)
+//! through these queues, and reaches the recording file through one too, so
+//! a slow disk never blocks the audio callback. The callback only ever
+//! appends to a queue in memory; it never waits on a file.
+//!
+//! A subscriber that falls behind, say while its connection is being
+//! re-established, would otherwise hold the whole backlog in RAM; over an
+//! hour-long recording that's gigabytes. Once the subscribers' queues
+//! together hold more than half their budget, the writer thread moves the
+//! oldest frames of the largest backlog to a temp file, to be read back in
+//! order later. Spill files are bounded too. When the budget is full, because
+//! the spill files are or the disk can't keep up, the callback drops new
+//! frames instead: the backlog stays in order, at the cost of a gap in what
+//! the subscriber receives.
+//!
+//! The recording file's own queue has a fixed size and is never spilled,
+//! since that would wait on the same disk. Audio it drops is missing from
+//! the file, so it's counted per recording and reported when it stops.
+
+use super::recorder::AudioFrame;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tempfile::TempPath;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+/// Audio held in RAM across the subscribers' queues. The writer thread
+/// spills once they hold half of it, and new frames are dropped at the limit.
+pub const DEFAULT_MEMORY_LIMIT: usize = 32 * 1024 * 1024;
+
+/// Audio held in spill files across all frame queues before dropping frames;
+/// about two hours of 16 kHz mono
+pub const DEFAULT_SPILL_LIMIT: u64 = 512 * 1024 * 1024;
+
+/// Audio waiting for the recording file before new blocks are dropped;
+/// about 40 seconds of 48 kHz stereo
+pub const WRITE_QUEUE_LIMIT: usize = 16 * 1024 * 1024;
+
+/// Current audio memory use, for diagnostics
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureMemory {
+    /// Bytes of audio queued in RAM
+    pub memory_bytes: usize,
+    /// Bytes of audio waiting in spill files
+    pub spilled_bytes: u64,
+    pub limit_bytes: usize,
+    pub spill_limit_bytes: u64,
+    /// Frames dropped because the subscribers' backlogs were full
+    pub dropped_frames: u64,
+    pub dropped_bytes: u64,
+    /// Bytes of audio waiting to be written to the recording file
+    pub file_queue_bytes: usize,
+    /// Bytes of audio missing from recording files because their queue was
+    /// full
+    pub file_dropped_bytes: u64,
+    /// Bytes held by the pre-roll buffer, which has a fixed size
+    pub pre_roll_bytes: usize,
+    pub subscribers: usize,
+}
+
+/// Memory shared by every queue
+#[derive(Debug)]
+pub struct MemoryBudget {
+    used: AtomicUsize,
+    spilled: AtomicU64,
+    limit: usize,
+    spill_limit: u64,
+    dropped_frames: AtomicU64,
+    dropped_bytes: AtomicU64,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: usize, spill_limit: u64) -> Self {
+        Self {
+            used: AtomicUsize::new(0),
+            spilled: AtomicU64::new(0),
+            limit,
+            spill_limit,
+            dropped_frames: AtomicU64::new(0),
+            dropped_bytes: AtomicU64::new(0),
+        }
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    pub fn spilled(&self) -> u64 {
+        self.spilled.load(Ordering::Relaxed)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn spill_limit(&self) -> u64 {
+        self.spill_limit
+    }
+
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Whether queues hold enough to start spilling, leaving the other half
+    /// of the budget for the callback while the disk catches up
+    pub fn should_spill(&self) -> bool {
+        self.spill_limit > 0 && self.used() > self.limit / 2
+    }
+}
+
+fn frame_bytes(frame: &AudioFrame) -> usize {
+    frame.samples.len() * std::mem::size_of::<f32>()
+}
+
+/// Frames moved out of memory, oldest first
+struct Spill {
+    writer: BufWriter<File>,
+    reader: BufReader<File>,
+    unread: usize,
+    bytes: u64,
+    /// Removes the file when dropped, after the handles above are closed
+    path: TempPath,
+}
+
+impl Spill {
+    fn create() -> io::Result<Self> {
+        let file = tempfile::Builder::new()
+            .prefix("frames-")
+            .suffix(".bin")
+            .tempfile_in(crate::session::temp_dir()?)?;
+        let reader = BufReader::new(file.reopen()?);
+        let (file, path) = file.into_parts();
+        debug!("Spilling audio frames to {:?}", path);
+        Ok(Self {
+            writer: BufWriter::new(file),
+            reader,
+            unread: 0,
+            bytes: 0,
+            path,
+        })
+    }
+
+    fn write(&mut self, frame: &AudioFrame) -> io::Result<()> {
+        self.writer.write_all(&frame.sample_rate.to_le_bytes())?;
+        self.writer
+            .write_all(&u32::from(frame.channels).to_le_bytes())?;
+        self.writer
+            .write_all(&(frame.samples.len() as u32).to_le_bytes())?;
+        for sample in frame.samples.iter() {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.unread += 1;
+        self.bytes += frame_bytes(frame) as u64;
+        Ok(())
+    }
+
+    fn read(&mut self) -> io::Result<AudioFrame> {
+        self.writer.flush()?;
+        let mut word = [0u8; 4];
+        self.reader.read_exact(&mut word)?;
+        let sample_rate = u32::from_le_bytes(word);
+        self.reader.read_exact(&mut word)?;
+        let channels = u32::from_le_bytes(word) as u16;
+        self.reader.read_exact(&mut word)?;
+        let len = u32::from_le_bytes(word) as usize;
+        let mut bytes = vec![0u8; len * 4];
+        self.reader.read_exact(&mut bytes)?;
+        let samples = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let frame = AudioFrame {
+            samples: Arc::new(samples),
+            sample_rate,
+            channels,
+        };
+        self.unread -= 1;
+        self.bytes -= frame_bytes(&frame) as u64;
+        Ok(frame)
+    }
+}
+
+/// Frames still in RAM, the only part of a queue the callback touches
+#[derive(Default)]
+struct Memory {
+    frames: VecDeque<AudioFrame>,
+    bytes: usize,
+    /// Frames dropped since the backlog last drained
+    dropping: u64,
+}
+
+/// One subscriber's frames, shared between the stream callback and the receiver
+///
+/// Frames leave the front of `memory` only with `spill` locked, taken before
+/// `memory`, so the spiller's view of the oldest frame stays valid while it
+/// writes it out without holding up the callback.
+pub struct FrameQueue {
+    memory: Mutex<Memory>,
+    spill: Mutex<Option<Spill>>,
+    budget: Arc<MemoryBudget>,
+    notify: Notify,
+    closed: AtomicBool,
+    /// Nothing more will be pushed (see `finish`)
+    finished: AtomicBool,
+    /// Bytes dropped from this queue since `take_dropped_bytes`
+    dropped_bytes: AtomicU64,
+    /// A spill file couldn't be created, already warned about
+    spill_failed: AtomicBool,
+}
+
+impl FrameQueue {
+    pub fn new(budget: Arc<MemoryBudget>) -> Arc<Self> {
+        Arc::new(Self {
+            memory: Mutex::new(Memory::default()),
+            spill: Mutex::new(None),
+            budget,
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            dropped_bytes: AtomicU64::new(0),
+            spill_failed: AtomicBool::new(false),
+        })
+    }
+
+    /// Whether the receiver is gone
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Queue a frame from the stream callback, or drop it if the budget is
+    /// full. Never touches the disk.
+    pub fn push(&self, frame: AudioFrame) {
+        let Ok(mut memory) = self.memory.lock() else {
+            return;
+        };
+        if self.is_closed() {
+            return;
+        }
+        let bytes = frame_bytes(&frame);
+        if self.budget.used() + bytes > self.budget.limit {
+            if memory.dropping == 0 {
+                warn!("Audio frame backlog is full, dropping new frames");
+            }
+            memory.dropping += 1;
+            self.count_dropped(1, bytes as u64);
+            return;
+        }
+        memory.bytes += bytes;
+        memory.frames.push_back(frame);
+        self.budget.used.fetch_add(bytes, Ordering::Relaxed);
+        drop(memory);
+        self.notify.notify_one();
+    }
+
+    fn count_dropped(&self, frames: u64, bytes: u64) {
+        self.budget
+            .dropped_frames
+            .fetch_add(frames, Ordering::Relaxed);
+        self.budget
+            .dropped_bytes
+            .fetch_add(bytes, Ordering::Relaxed);
+        self.dropped_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Bytes of audio this queue dropped since the last call
+    pub fn take_dropped_bytes(&self) -> u64 {
+        self.dropped_bytes.swap(0, Ordering::Relaxed)
+    }
+
+    /// Bytes of audio waiting in RAM
+    pub fn backlog_bytes(&self) -> usize {
+        self.memory.lock().map(|memory| memory.bytes).unwrap_or(0)
+    }
+
+    /// Move the oldest frames to the spill file until the budget is back
+    /// under its spilling point. Waits on the disk, so it's for the writer
+    /// thread, never the callback.
+    pub fn spill_excess(&self) {
+        let Ok(mut spill) = self.spill.lock() else {
+            return;
+        };
+        while self.budget.should_spill() {
+            let Some(frame) = self
+                .memory
+                .lock()
+                .ok()
+                .and_then(|memory| memory.frames.front().cloned())
+            else {
+                break;
+            };
+            let bytes = frame_bytes(&frame);
+            if self.budget.spilled() + bytes as u64 > self.budget.spill_limit {
+                // Left in memory; the callback drops new frames once the
+                // budget is full
+                break;
+            }
+            if spill.is_none() {
+                match Spill::create() {
+                    Ok(file) => *spill = Some(file),
+                    Err(e) => {
+                        if !self.spill_failed.swap(true, Ordering::Relaxed) {
+                            warn!("Failed to create audio spill file: {}", e);
+                        }
+                        break;
+                    }
+                }
+            }
+            let file = spill.as_mut().expect("spill file was just created");
+            if let Err(e) = file.write(&frame) {
+                // The file may end in half a record, so nothing after it can
+                // be read back; the frame itself is still in memory
+                warn!("Failed to spill audio frames: {}", e);
+                self.discard_spill(&mut spill);
+                break;
+            }
+            self.spill_failed.store(false, Ordering::Relaxed);
+            let Ok(mut memory) = self.memory.lock() else {
+                break;
+            };
+            memory.frames.pop_front();
+            memory.bytes -= bytes;
+            self.budget.used.fetch_sub(bytes, Ordering::Relaxed);
+            self.budget
+                .spilled
+                .fetch_add(bytes as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Give up the spill file, counting the frames still in it as dropped
+    fn discard_spill(&self, spill: &mut Option<Spill>) {
+        let Some(spill) = spill.take() else {
+            return;
+        };
+        if spill.unread > 0 {
+            warn!("Lost {} spilled audio frames", spill.unread);
+        }
+        self.budget
+            .spilled
+            .fetch_sub(spill.bytes, Ordering::Relaxed);
+        self.count_dropped(spill.unread as u64, spill.bytes);
+    }
+
+    /// Take the oldest frame without waiting, for a consumer that drains the
+    /// queue itself rather than through a [`FrameReceiver`]
+    pub fn try_pop(&self) -> Option<AudioFrame> {
+        self.pop()
+    }
+
+    /// Wait until a frame is queued; `false` once `finish` was called and
+    /// the queue is empty
+    pub async fn wait(&self) -> bool {
+        loop {
+            if self.has_frames() {
+                return true;
+            }
+            if self.finished.load(Ordering::Acquire) {
+                return false;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// No more frames will be pushed; wakes the consumer to drain the rest
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    fn has_frames(&self) -> bool {
+        let spilled = self
+            .spill
+            .lock()
+            .is_ok_and(|spill| spill.as_ref().is_some_and(|spill| spill.unread > 0));
+        spilled
+            || self
+                .memory
+                .lock()
+                .is_ok_and(|memory| !memory.frames.is_empty())
+    }
+
+    fn pop(&self) -> Option<AudioFrame> {
+        let mut spill = self.spill.lock().ok()?;
+        // Spilled frames are older than everything still in memory
+        if let Some(file) = spill.as_mut().filter(|file| file.unread > 0) {
+            match file.read() {
+                Ok(frame) => {
+                    self.budget
+                        .spilled
+                        .fetch_sub(frame_bytes(&frame) as u64, Ordering::Relaxed);
+                    if file.unread == 0 {
+                        *spill = None;
+                    }
+                    return Some(frame);
+                }
+                Err(e) => {
+                    warn!("Failed to read spilled audio frames: {}", e);
+                    self.discard_spill(&mut spill);
+                }
+            }
+        }
+        let mut memory = self.memory.lock().ok()?;
+        let frame = memory.frames.pop_front()?;
+        if memory.dropping > 0 && memory.frames.is_empty() {
+            debug!(
+                "Audio frame backlog drained after {} dropped frames",
+                memory.dropping
+            );
+            memory.dropping = 0;
+        }
+        let bytes = frame_bytes(&frame);
+        memory.bytes -= bytes;
+        self.budget.used.fetch_sub(bytes, Ordering::Relaxed);
+        Some(frame)
+    }
+}
+
+/// Receiving end of a [`FrameQueue`]; frames stop being queued once dropped
+pub struct FrameReceiver {
+    queue: Arc<FrameQueue>,
+}
+
+impl FrameReceiver {
+    pub fn new(queue: Arc<FrameQueue>) -> Self {
+        Self { queue }
+    }
+
+    /// The next frame, waiting for one if needed. Safe to use in `select!`:
+    /// frames stay queued until returned.
+    pub async fn recv(&mut self) -> Option<AudioFrame> {
+        loop {
+            if let Some(frame) = self.queue.pop() {
+                return Some(frame);
+            }
+            self.queue.notify.notified().await;
+        }
+    }
+}
+
+impl Drop for FrameReceiver {
+    fn drop(&mut self) {
+        let Ok(mut spill) = self.queue.spill.lock() else {
+            return;
+        };
+        let Ok(mut memory) = self.queue.memory.lock() else {
+            return;
+        };
+        self.queue.closed.store(true, Ordering::Relaxed);
+        // Give the queued frames' memory back to the budget
+        let budget = &self.queue.budget;
+        budget.used.fetch_sub(memory.bytes, Ordering::Relaxed);
+        if let Some(spill) = spill.take() {
+            budget.spilled.fetch_sub(spill.bytes, Ordering::Relaxed);
+        }
+        memory.frames.clear();
+        memory.bytes = 0;
+    }
+}
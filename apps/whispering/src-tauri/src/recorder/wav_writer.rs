@@ -17,6 +17,8 @@ pub struct WavWriter {
     samples_written: u64,
     last_header_update: Instant,
     file_path: PathBuf,
+    /// Encoded samples, reused so each block is a single write
+    bytes: Vec<u8>,
 }
 
 impl WavWriter {
@@ -73,15 +75,19 @@ impl WavWriter {
             samples_written: 0,
             last_header_update: Instant::now(),
             file_path,
+            bytes: Vec::new(),
         })
     }
 
     /// Write f32 samples to the WAV file
     pub fn write_samples_f32(&mut self, samples: &[f32]) -> io::Result<()> {
         // Write samples as little-endian f32
+        self.bytes.clear();
+        self.bytes.reserve(samples.len() * 4);
         for sample in samples {
-            self.writer.write_all(&sample.to_le_bytes())?;
+            self.bytes.extend_from_slice(&sample.to_le_bytes());
         }
+        self.writer.write_all(&self.bytes)?;
 
         self.samples_written += samples.len() as u64;
 
@@ -0,0 +1,18 @@
+use super::{ReminderConfig, Reminders};
+use crate::error::Result;
+use tauri::State;
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_reminder_config(reminders: State<'_, Reminders>) -> Result<ReminderConfig> {
+    Ok(reminders.config())
+}
+
+#[tauri::command]
+pub async fn set_reminder_config(
+    config: ReminderConfig,
+    reminders: State<'_, Reminders>,
+) -> Result<()> {
+    debug!("Updating reminders: {:?}", config);
+    reminders.set_config(config)
+}
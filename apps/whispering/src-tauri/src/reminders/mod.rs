@@ -0,0 +1,219 @@
+//! Optional reminders: a break nudge after a long stretch of dictation and
+//! scheduled daily reminders (e.g. an evening journal prompt).
+
+pub mod commands;
+
+use crate::app_state::{AppState, AppStatus};
+use crate::error::{AppError, Result};
+use crate::notifications::notify;
+use chrono::{DateTime, Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+pub const REMINDERS_FILE: &str = "reminders.json";
+
+/// How often reminders are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BreakReminder {
+    pub enabled: bool,
+    /// Remind after this many minutes of dictation without a break
+    pub work_minutes: u32,
+    /// A pause in recording at least this long counts as a break
+    pub break_minutes: u32,
+}
+
+impl Default for BreakReminder {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            work_minutes: 20,
+            break_minutes: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyReminder {
+    /// Local time, 24-hour clock
+    pub hour: u32,
+    pub minute: u32,
+    /// Days to remind on, 0 = Monday; empty means every day
+    #[serde(default)]
+    pub weekdays: Vec<u32>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReminderConfig {
+    pub break_reminder: BreakReminder,
+    pub daily: Vec<DailyReminder>,
+}
+
+/// Tracks the current stretch of dictation for the break reminder
+#[derive(Default)]
+struct Activity {
+    /// When the current stretch started
+    started: Option<Instant>,
+    /// Last time a recording was in progress
+    last_recording: Option<Instant>,
+}
+
+pub struct Reminders {
+    path: PathBuf,
+    config: Mutex<ReminderConfig>,
+    activity: Mutex<Activity>,
+}
+
+impl Reminders {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                ReminderConfig::default()
+            }),
+            Err(_) => ReminderConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            activity: Mutex::new(Activity::default()),
+        }
+    }
+
+    pub fn config(&self) -> ReminderConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: ReminderConfig) -> Result<()> {
+        if let Some(reminder) = config
+            .daily
+            .iter()
+            .find(|r| r.hour > 23 || r.minute > 59 || r.weekdays.iter().any(|d| *d > 6))
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid reminder time {:02}:{:02} on {:?}",
+                reminder.hour, reminder.minute, reminder.weekdays
+            )));
+        }
+        if config.break_reminder.work_minutes == 0 {
+            return Err(AppError::InvalidInput(
+                "Break reminder interval must be at least a minute".to_string(),
+            ));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize reminders: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock reminders: {}", e)))? = config;
+        Ok(())
+    }
+
+    /// Check reminders in the background for the rest of the app's life
+    pub fn start(&self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut last_check = Local::now();
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                let now = Local::now();
+                let reminders = app.state::<Reminders>();
+                let config = reminders.config();
+                let recording = matches!(
+                    app.state::<AppState>().current(),
+                    AppStatus::Recording { .. }
+                );
+
+                if config.break_reminder.enabled
+                    && reminders.break_due(&config.break_reminder, recording)
+                {
+                    notify(
+                        &app,
+                        &format!(
+                            "You've been dictating for {} minutes — take a break",
+                            config.break_reminder.work_minutes
+                        ),
+                    );
+                }
+                for reminder in &config.daily {
+                    if is_between(reminder, last_check, now) {
+                        debug!(
+                            "Daily reminder at {:02}:{:02}",
+                            reminder.hour, reminder.minute
+                        );
+                        notify(&app, &reminder.message);
+                    }
+                }
+                last_check = now;
+            }
+        });
+    }
+
+    /// Update the current stretch of dictation and report whether it's time
+    /// for a break, starting a new stretch if so
+    fn break_due(&self, config: &BreakReminder, recording: bool) -> bool {
+        let Ok(mut activity) = self.activity.lock() else {
+            return false;
+        };
+        let now = Instant::now();
+        let break_length = Duration::from_secs(config.break_minutes as u64 * 60);
+        let rested = match activity.last_recording {
+            Some(last) => now.duration_since(last) >= break_length,
+            None => true,
+        };
+
+        if recording {
+            if rested {
+                activity.started = Some(now);
+            }
+            activity.last_recording = Some(now);
+        } else if rested {
+            activity.started = None;
+        }
+
+        let work = Duration::from_secs(config.work_minutes as u64 * 60);
+        match activity.started {
+            Some(started) if now.duration_since(started) >= work => {
+                activity.started = None;
+                activity.last_recording = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether a daily reminder's time falls in `(after, until]`
+fn is_between(reminder: &DailyReminder, after: DateTime<Local>, until: DateTime<Local>) -> bool {
+    // Both days, in case the check interval spans midnight
+    let mut days = vec![after.date_naive(), until.date_naive()];
+    days.dedup();
+    days.into_iter().any(|day| {
+        let Some(time) = day
+            .and_hms_opt(reminder.hour, reminder.minute, 0)
+            .and_then(|time| Local.from_local_datetime(&time).earliest())
+        else {
+            return false;
+        };
+        let weekday = time.weekday().num_days_from_monday();
+        (reminder.weekdays.is_empty() || reminder.weekdays.contains(&weekday))
+            && time > after
+            && time <= until
+    })
+}
@@ -0,0 +1,50 @@
+use crate::error::Result;
+use crate::inbox::{PairedDeviceInfo, PairingInfo};
+use crate::remote::{RemoteConfig, RemoteServer, RemoteStatus};
+use tauri::State;
+use tracing::info;
+
+#[tauri::command]
+pub async fn start_remote_server(
+    config: RemoteConfig,
+    remote: State<'_, RemoteServer>,
+    app_handle: tauri::AppHandle,
+) -> Result<RemoteStatus> {
+    info!("Starting remote control server on port {}", config.port);
+    remote.start(app_handle, config)
+}
+
+#[tauri::command]
+pub async fn stop_remote_server(remote: State<'_, RemoteServer>) -> Result<()> {
+    info!("Stopping remote control server");
+    remote.stop()
+}
+
+#[tauri::command]
+pub async fn get_remote_status(remote: State<'_, RemoteServer>) -> Result<RemoteStatus> {
+    remote.status()
+}
+
+#[tauri::command]
+pub async fn start_remote_pairing(remote: State<'_, RemoteServer>) -> Result<PairingInfo> {
+    info!("Starting remote control pairing");
+    remote.begin_pairing()
+}
+
+#[tauri::command]
+pub async fn list_remote_devices(
+    remote: State<'_, RemoteServer>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<PairedDeviceInfo>> {
+    remote.paired_devices(&app_handle)
+}
+
+#[tauri::command]
+pub async fn revoke_remote_device(
+    device_id: String,
+    remote: State<'_, RemoteServer>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Revoking remote control device {}", device_id);
+    remote.revoke_device(&app_handle, &device_id)
+}
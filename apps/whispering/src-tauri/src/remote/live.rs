@@ -0,0 +1,154 @@
+//! What a paired phone shows as the live transcript view.
+//!
+//! The feed is a snapshot with a sequence number that goes up on every
+//! change. Phones long-poll `GET /live?since=<seq>`, which answers as soon
+//! as there's something newer, or with the same snapshot once the wait
+//! times out.
+
+use crate::app_state::{AppState, AppStatus};
+use crate::events::{AppEvent, StreamingFinal, StreamingInterim};
+use crate::ipc::{IpcMessage, IpcServer};
+use serde::Serialize;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Listener, Manager};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSnapshot {
+    pub seq: u64,
+    pub status: AppStatus,
+    /// Live transcription the provider won't revise, this recording
+    pub committed: String,
+    /// Its best guess at what follows, replaced as it goes
+    pub interim: String,
+    /// The last finished transcript
+    pub last_transcript: Option<String>,
+}
+
+pub struct LiveFeed {
+    snapshot: Mutex<LiveSnapshot>,
+    changed: Condvar,
+}
+
+impl LiveFeed {
+    pub fn new() -> Self {
+        Self {
+            snapshot: Mutex::new(LiveSnapshot {
+                seq: 0,
+                status: AppStatus::Idle,
+                committed: String::new(),
+                interim: String::new(),
+                last_transcript: None,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    pub fn snapshot(&self) -> LiveSnapshot {
+        self.snapshot
+            .lock()
+            .map(|snapshot| snapshot.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    /// Change the snapshot and wake the phones waiting on it
+    pub fn update(&self, f: impl FnOnce(&mut LiveSnapshot)) {
+        let Ok(mut snapshot) = self.snapshot.lock() else {
+            return;
+        };
+        f(&mut snapshot);
+        snapshot.seq += 1;
+        self.changed.notify_all();
+    }
+
+    /// The first snapshot newer than `since`, or the current one once
+    /// `timeout` has passed
+    pub fn wait(&self, since: u64, timeout: Duration) -> LiveSnapshot {
+        let deadline = Instant::now() + timeout;
+        let Ok(mut snapshot) = self.snapshot.lock() else {
+            return self.snapshot();
+        };
+        while snapshot.seq <= since {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            snapshot = match self.changed.wait_timeout(snapshot, left) {
+                Ok((snapshot, _)) => snapshot,
+                Err(e) => e.into_inner().0,
+            };
+        }
+        snapshot.clone()
+    }
+
+    /// Keep the feed up to date with the app for as long as it runs
+    pub fn follow(self: &Arc<Self>, app: &AppHandle) {
+        let feed = self.clone();
+        let mut states = app.state::<AppState>().subscribe();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let status = states.borrow_and_update().clone();
+                feed.update(|snapshot| {
+                    // A new recording starts a new live transcript
+                    if matches!(status, AppStatus::Recording { .. })
+                        && !matches!(snapshot.status, AppStatus::Recording { .. })
+                    {
+                        snapshot.committed.clear();
+                        snapshot.interim.clear();
+                    }
+                    snapshot.status = status;
+                });
+                if states.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let feed = self.clone();
+        app.listen_any(StreamingInterim::NAME, move |event| {
+            if let Some(text) = transcript_text(event.payload()) {
+                feed.update(|snapshot| snapshot.interim = text);
+            }
+        });
+        let feed = self.clone();
+        app.listen_any(StreamingFinal::NAME, move |event| {
+            if let Some(text) = transcript_text(event.payload()) {
+                feed.update(|snapshot| {
+                    if !snapshot.committed.is_empty() {
+                        snapshot.committed.push(' ');
+                    }
+                    snapshot.committed.push_str(text.trim());
+                    snapshot.interim.clear();
+                });
+            }
+        });
+
+        // Transcripts the frontend publishes once it has delivered them
+        let ipc = app.state::<IpcServer>();
+        let transcripts = ipc.subscribe();
+        let last = ipc.last_transcript();
+        self.update(|snapshot| snapshot.last_transcript = last);
+        let feed = self.clone();
+        thread::spawn(move || {
+            for message in transcripts {
+                if let IpcMessage::Transcript { text } = message {
+                    feed.update(|snapshot| snapshot.last_transcript = text);
+                }
+            }
+        });
+    }
+}
+
+impl Default for LiveFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The text of a `StreamingTranscript` payload
+fn transcript_text(payload: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(payload).ok()?;
+    value.get("text")?.as_str().map(str::to_string)
+}
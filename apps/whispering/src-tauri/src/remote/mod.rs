@@ -0,0 +1,438 @@
+//! Remote control from a phone on the same network.
+//!
+//! A paired phone works as a record button and shows the transcript as it's
+//! dictated. Pairing works like the inbox's: the desktop shows a QR code
+//! with its address, a one-time code and its certificate's fingerprint;
+//! the phone pins the certificate and trades the code for a bearer token.
+//! Phones that can't scan find the desktop over mDNS instead (the TXT record
+//! carries the fingerprint) and have the code typed in. Only devices in the
+//! allowlist, which the user can revoke from, get past `/pair`.
+//!
+//! The server is HTTPS on its own port, with its own certificate and
+//! allowlist in the `remote` data directory, so pairing a phone as an inbox
+//! doesn't let it start recordings.
+//!
+//! - `POST /pair` with `{ code, deviceName }` returns `{ deviceId, token }`
+//! - `GET /status` returns the app status
+//! - `POST /record` with `{ action: "start" | "stop" | "toggle" }` drives
+//!   recording as the CLI does, through the UI's recorder when it's open.
+//!   It answers with the live snapshot once the recorder has started or
+//!   stopped; stopping waits for the transcript.
+//! - `GET /live?since=<seq>` long-polls the live transcript (see [`live`])
+
+pub mod commands;
+pub mod live;
+
+use crate::app_state::AppState;
+use crate::discovery::{self, Advertisement};
+use crate::error::AppError;
+use crate::events::{AppEvent, RemoteDevicePaired};
+use crate::inbox::pairing::{load_or_create_identity, DeviceRegistry};
+use crate::inbox::{local_ip, PairedDeviceInfo, PairingInfo};
+use crate::ipc::server::forward;
+use crate::ipc::IpcMessage;
+use live::LiveFeed;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Once};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server, SslConfig};
+use tracing::{error, info, warn};
+
+pub use crate::error::Result;
+
+/// Default port for the remote control server, next to the inbox's
+pub const DEFAULT_REMOTE_PORT: u16 = 47822;
+
+/// Largest JSON body accepted
+const MAX_JSON_BYTES: u64 = 64 * 1024;
+
+/// Longest a `/live` request is held open waiting for a change
+const LIVE_POLL_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// Remote control server settings - provided by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteConfig {
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Advertise the server over mDNS
+    #[serde(default = "default_discoverable")]
+    pub discoverable: bool,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_REMOTE_PORT
+}
+
+fn default_discoverable() -> bool {
+    true
+}
+
+/// Current state of the remote control server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub address: Option<String>,
+    pub fingerprint: Option<String>,
+    /// Whether phones can find it over mDNS
+    pub discoverable: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum RecordAction {
+    Start,
+    Stop,
+    Toggle,
+}
+
+impl RecordAction {
+    /// The command the CLI sends for the same thing
+    fn command(self) -> &'static str {
+        match self {
+            RecordAction::Start => "startRecording",
+            RecordAction::Stop => "stopRecording",
+            RecordAction::Toggle => "toggleRecording",
+        }
+    }
+}
+
+struct RunningServer {
+    server: Arc<Server>,
+    handle: JoinHandle<()>,
+    port: u16,
+    fingerprint: String,
+    devices: Arc<Mutex<DeviceRegistry>>,
+    advertisement: Option<Advertisement>,
+}
+
+/// Local HTTPS endpoint that lets paired phones start and stop recordings
+pub struct RemoteServer {
+    running: Mutex<Option<RunningServer>>,
+    feed: Arc<LiveFeed>,
+    following: Once,
+}
+
+impl RemoteServer {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+            feed: Arc::new(LiveFeed::new()),
+            following: Once::new(),
+        }
+    }
+
+    pub fn start(&self, app: AppHandle, config: RemoteConfig) -> Result<RemoteStatus> {
+        // Restart with the new configuration if already running
+        self.stop()?;
+        self.following.call_once(|| self.feed.follow(&app));
+
+        let dir = remote_dir(&app)?;
+        let identity = load_or_create_identity(&dir)?;
+        let devices = Arc::new(Mutex::new(DeviceRegistry::load(&dir)?));
+
+        let server = Server::https(
+            ("0.0.0.0", config.port),
+            SslConfig {
+                certificate: identity.cert_pem,
+                private_key: identity.key_pem,
+            },
+        )
+        .map_err(|e| {
+            AppError::AddressInUse(format!(
+                "Failed to start remote control server on port {}: {}",
+                config.port, e
+            ))
+        })?;
+        let server = Arc::new(server);
+        let port = config.port;
+
+        let advertisement = if config.discoverable {
+            let (instance, txt) = advertised_as(&identity.fingerprint);
+            Advertisement::start(&instance, port, &txt)
+                .map_err(|e| warn!("Remote control won't be discoverable: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        let handle = {
+            let server = server.clone();
+            let devices = devices.clone();
+            let feed = self.feed.clone();
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    // `/live` holds its request open, so each gets a thread
+                    let app = app.clone();
+                    let devices = devices.clone();
+                    let feed = feed.clone();
+                    thread::spawn(move || handle_request(&app, &devices, &feed, request));
+                }
+                info!("Remote control server stopped");
+            })
+        };
+
+        let mut running = self.running.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock remote control server: {}", e))
+        })?;
+        *running = Some(RunningServer {
+            server,
+            handle,
+            port,
+            fingerprint: identity.fingerprint,
+            devices,
+            advertisement,
+        });
+
+        info!("Remote control server listening on port {}", port);
+        Ok(status_of(running.as_ref()))
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        let running = self
+            .running
+            .lock()
+            .map_err(|e| {
+                AppError::Internal(format!("Failed to lock remote control server: {}", e))
+            })?
+            .take();
+
+        if let Some(running) = running {
+            drop(running.advertisement);
+            running.server.unblock();
+            let _ = running.handle.join();
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<RemoteStatus> {
+        let running = self.running.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock remote control server: {}", e))
+        })?;
+        Ok(status_of(running.as_ref()))
+    }
+
+    /// Open a pairing window and return the QR code for the UI
+    pub fn begin_pairing(&self) -> Result<PairingInfo> {
+        let running = self.running.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock remote control server: {}", e))
+        })?;
+        let running = running.as_ref().ok_or_else(|| {
+            AppError::NotRunning("Remote control server is not running".to_string())
+        })?;
+
+        let address = local_ip()
+            .map(|ip| ip.to_string())
+            .ok_or_else(|| AppError::Network("No local network address available".to_string()))?;
+
+        let mut devices = running
+            .devices
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock paired devices: {}", e)))?;
+        let pending = devices.begin_pairing();
+
+        let uri = format!(
+            "whispering://remote?host={}&port={}&code={}&fp={}",
+            address, running.port, pending.code, running.fingerprint
+        );
+        let qr_svg = qrcode::QrCode::new(uri.as_bytes())
+            .map_err(|e| AppError::Internal(format!("Failed to create QR code: {}", e)))?
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(256, 256)
+            .build();
+
+        Ok(PairingInfo {
+            uri,
+            code: pending.code.clone(),
+            expires_at: pending.expires_at,
+            qr_svg,
+        })
+    }
+
+    pub fn paired_devices(&self, app: &AppHandle) -> Result<Vec<PairedDeviceInfo>> {
+        self.with_registry(app, |registry| Ok(registry.devices()))
+    }
+
+    pub fn revoke_device(&self, app: &AppHandle, device_id: &str) -> Result<()> {
+        self.with_registry(app, |registry| registry.revoke(device_id))
+    }
+
+    /// Run against the live registry when serving, otherwise against the file on disk
+    fn with_registry<T>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&mut DeviceRegistry) -> Result<T>,
+    ) -> Result<T> {
+        let running = self.running.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock remote control server: {}", e))
+        })?;
+        match running.as_ref() {
+            Some(running) => {
+                let mut registry = running.devices.lock().map_err(|e| {
+                    AppError::Internal(format!("Failed to lock paired devices: {}", e))
+                })?;
+                f(&mut registry)
+            }
+            None => f(&mut DeviceRegistry::load(&remote_dir(app)?)?),
+        }
+    }
+}
+
+impl Default for RemoteServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Instance name and TXT record the remote is advertised with over mDNS
+pub fn advertised_as(fingerprint: &str) -> (String, [(&'static str, &str); 3]) {
+    let instance = format!("{} remote", discovery::host_label());
+    let txt = [("caps", "remote"), ("fp", fingerprint), ("v", "1")];
+    (instance, txt)
+}
+
+fn status_of(running: Option<&RunningServer>) -> RemoteStatus {
+    match running {
+        Some(running) => RemoteStatus {
+            running: true,
+            port: Some(running.port),
+            address: local_ip().map(|ip| ip.to_string()),
+            fingerprint: Some(running.fingerprint.clone()),
+            discoverable: running.advertisement.is_some(),
+        },
+        None => RemoteStatus {
+            running: false,
+            port: None,
+            address: None,
+            fingerprint: None,
+            discoverable: false,
+        },
+    }
+}
+
+fn remote_dir(app: &AppHandle) -> Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve app data directory: {}", e)))?
+        .join("remote");
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        AppError::Internal(format!("Failed to create remote control directory: {}", e))
+    })?;
+    Ok(dir)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairRequest {
+    code: String,
+    device_name: String,
+}
+
+#[derive(Deserialize)]
+struct RecordRequest {
+    action: RecordAction,
+}
+
+type Handled = std::result::Result<(u16, serde_json::Value), (u16, String)>;
+
+fn handle_request(
+    app: &AppHandle,
+    devices: &Mutex<DeviceRegistry>,
+    feed: &LiveFeed,
+    mut request: Request,
+) {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+
+    let result: Handled = match (&method, path) {
+        (Method::Post, "/pair") => read_json::<PairRequest>(&mut request)
+            .and_then(|pair| {
+                let mut registry = devices.lock().map_err(|e| (500, e.to_string()))?;
+                registry
+                    .complete_pairing(&pair.code, &pair.device_name)
+                    .map_err(|e| (403, e.to_string()))
+            })
+            .map(|(device, token)| {
+                let _ = RemoteDevicePaired::emit(app, &device);
+                (
+                    200,
+                    serde_json::json!({ "deviceId": device.id, "token": token }),
+                )
+            }),
+        (Method::Get, "/status") => authenticate(devices, &request).map(|_| {
+            let status = app.state::<AppState>().current();
+            (200, serde_json::json!({ "status": status }))
+        }),
+        (Method::Post, "/record") => authenticate(devices, &request).and_then(|device| {
+            let record = read_json::<RecordRequest>(&mut request)?;
+            info!("{:?} requested by '{}'", record.action, device.name);
+            match forward(app, record.action.command()) {
+                IpcMessage::Error { message } => Err((500, message)),
+                _ => Ok((200, serde_json::json!(feed.snapshot()))),
+            }
+        }),
+        (Method::Get, "/live") => authenticate(devices, &request).map(|_| {
+            let since = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("since="))
+                .and_then(|since| since.parse().ok())
+                .unwrap_or(0);
+            let snapshot = feed.wait(since, LIVE_POLL_TIMEOUT);
+            (200, serde_json::json!(snapshot))
+        }),
+        _ => Err((404, "Not found".to_string())),
+    };
+
+    let (status, body) = match result {
+        Ok((status, body)) => (status, body),
+        Err((status, message)) => {
+            warn!("Remote request {} {} failed: {}", method, path, message);
+            (status, serde_json::json!({ "error": message }))
+        }
+    };
+
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+    if let Err(e) = request.respond(response) {
+        error!("Failed to respond to remote request: {}", e);
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(
+    request: &mut Request,
+) -> std::result::Result<T, (u16, String)> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_JSON_BYTES)
+        .read_to_end(&mut body)
+        .map_err(|e| (400, format!("Failed to read request: {}", e)))?;
+    serde_json::from_slice(&body).map_err(|e| (400, format!("Invalid request: {}", e)))
+}
+
+fn authenticate(
+    devices: &Mutex<DeviceRegistry>,
+    request: &Request,
+) -> std::result::Result<PairedDeviceInfo, (u16, String)> {
+    let token = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+        .ok_or_else(|| (401, "Missing bearer token".to_string()))?;
+
+    devices
+        .lock()
+        .map_err(|e| (500, e.to_string()))?
+        .authenticate(token)
+        .ok_or_else(|| (401, "Unknown device".to_string()))
+}
@@ -0,0 +1,42 @@
+use super::{RecordingCheck, RecordingHealth};
+use crate::error::{AppError, Result};
+use crate::history::HistoryStore;
+use std::path::Path;
+use tauri::State;
+use tracing::info;
+
+/// Check the audio of every history entry, flagging the ones that can't be
+/// played with the `damaged-audio` tag
+#[tauri::command]
+pub async fn check_recordings(history: State<'_, HistoryStore>) -> Result<Vec<RecordingCheck>> {
+    super::check_history(&history)
+}
+
+/// Fix the header of an entry's WAV file after a crash or a full disk, so
+/// the audio that made it to disk plays and transcribes again
+#[tauri::command]
+pub async fn repair_recording(
+    id: String,
+    history: State<'_, HistoryStore>,
+) -> Result<RecordingCheck> {
+    let entry = history.get(&id)?;
+    let audio_path = entry
+        .audio_path
+        .clone()
+        .ok_or_else(|| AppError::InvalidInput(format!("History entry {} has no audio", id)))?;
+
+    let file = super::repair_file(Path::new(&audio_path))?;
+    if file.health == RecordingHealth::Ok {
+        if let Some(duration) = file.duration_seconds {
+            info!("Repaired recording {} ({:.1}s)", id, duration);
+            // Also drops the waveform cached from the damaged file
+            history.update_audio(&id, &audio_path, duration)?;
+        }
+    }
+    super::flag(&history, &entry, file.health)?;
+    Ok(RecordingCheck {
+        id,
+        audio_path,
+        file,
+    })
+}
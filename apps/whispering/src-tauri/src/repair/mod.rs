@@ -0,0 +1,308 @@
+//! Finding and fixing recordings left damaged by a crash or a full disk.
+//!
+//! The recorder writes WAV files progressively, updating the header's sizes
+//! every so often, so a crash leaves a file whose header undercounts its
+//! audio, or still has the placeholder sizes. A disk filling up can cut the
+//! audio off mid-frame. Both are repairable by rewriting the sizes from what
+//! is actually on disk. Files with no audio at all, or that aren't WAV
+//! despite their name, are beyond repair; their history entries are tagged
+//! `damaged-audio` so they can be found and cleaned up.
+//!
+//! Compressed recordings are only checked for being present and non-empty.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::history::{HistoryEntry, HistoryStore};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Tag given to history entries whose audio can't be recovered
+pub const DAMAGED_TAG: &str = "damaged-audio";
+
+/// What was found in a recording's file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingHealth {
+    Ok,
+    /// The file is gone
+    Missing,
+    /// The file holds no audio
+    Empty,
+    /// The header doesn't match the audio; `repair_recording` can fix it
+    Repairable,
+    /// Not a readable WAV file
+    Unrecoverable,
+}
+
+/// The outcome of checking one file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCheck {
+    pub health: RecordingHealth,
+    /// What's wrong, when something is
+    pub problem: Option<String>,
+    /// Length of the audio on disk, for WAV files with any
+    pub duration_seconds: Option<f32>,
+}
+
+/// The outcome of checking a history entry's recording
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingCheck {
+    pub id: String,
+    pub audio_path: String,
+    #[serde(flatten)]
+    pub file: FileCheck,
+}
+
+/// Where a WAV file's sizes are, and what they should say
+struct WavLayout {
+    riff_size: u32,
+    data_size_pos: u64,
+    declared_data: u32,
+    /// Whole frames of audio after the data chunk's header
+    data_bytes: u64,
+    /// Whether bytes the header doesn't count follow the audio, as opposed
+    /// to another chunk
+    unaccounted: bool,
+    file_len: u64,
+    bytes_per_second: u64,
+}
+
+impl FileCheck {
+    fn new(
+        health: RecordingHealth,
+        problem: Option<String>,
+        duration_seconds: Option<f32>,
+    ) -> Self {
+        Self {
+            health,
+            problem,
+            duration_seconds,
+        }
+    }
+
+    fn broken(health: RecordingHealth, problem: impl Into<String>) -> Self {
+        Self::new(health, Some(problem.into()), None)
+    }
+}
+
+/// Check a recording's file without changing it
+pub fn check_file(path: &Path) -> FileCheck {
+    let len = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return FileCheck::broken(RecordingHealth::Missing, "The file is missing"),
+    };
+    if len == 0 {
+        return FileCheck::broken(RecordingHealth::Empty, "The file is empty");
+    }
+    let is_wav = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+    let layout = match read_layout(path, len) {
+        Ok(Some(layout)) => layout,
+        Ok(None) if !is_wav => return FileCheck::new(RecordingHealth::Ok, None, None),
+        Ok(None) => return FileCheck::broken(RecordingHealth::Unrecoverable, "Not a WAV file"),
+        Err(problem) => return FileCheck::broken(RecordingHealth::Unrecoverable, problem),
+    };
+
+    let duration = Some(layout.data_bytes as f32 / layout.bytes_per_second as f32);
+    if layout.data_bytes == 0 {
+        return FileCheck::broken(RecordingHealth::Empty, "The recording has no audio");
+    }
+    let expected_riff = layout.file_len - 8;
+    if u64::from(layout.declared_data) > layout.data_bytes
+        || u64::from(layout.riff_size) > expected_riff
+        || layout.unaccounted
+    {
+        return FileCheck::new(
+            RecordingHealth::Repairable,
+            Some(format!(
+                "The header counts {} bytes of audio, but {} are on disk",
+                layout.declared_data, layout.data_bytes
+            )),
+            duration,
+        );
+    }
+    FileCheck::new(RecordingHealth::Ok, None, duration)
+}
+
+/// Rewrite a WAV file's sizes to cover the audio on disk, dropping a
+/// trailing partial frame. Files that are fine are left alone; ones beyond
+/// repair are reported as they are.
+pub fn repair_file(path: &Path) -> Result<FileCheck> {
+    let check = check_file(path);
+    if check.health != RecordingHealth::Repairable {
+        return Ok(check);
+    }
+    let len = std::fs::metadata(path)?.len();
+    let layout = read_layout(path, len)
+        .map_err(AppError::InvalidInput)?
+        .ok_or_else(|| AppError::InvalidInput(format!("{} isn't a WAV file", path.display())))?;
+
+    // Sizes are 32 bits; anything past 4 GiB can't be described
+    let data_end = layout.data_size_pos + 4;
+    let data_bytes = layout.data_bytes.min(u64::from(u32::MAX) - data_end);
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    if layout.unaccounted {
+        file.set_len(data_end + data_bytes)?;
+    }
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&((data_end + data_bytes - 8) as u32).to_le_bytes())?;
+    file.seek(SeekFrom::Start(layout.data_size_pos))?;
+    file.write_all(&(data_bytes as u32).to_le_bytes())?;
+    file.sync_all()?;
+    info!("Rewrote the header of {}", path.display());
+    Ok(check_file(path))
+}
+
+/// Read the RIFF header and walk the chunks up to the audio. `None` for
+/// files that aren't RIFF WAVE at all; an error describes a broken one.
+fn read_layout(path: &Path, file_len: u64) -> std::result::Result<Option<WavLayout>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Can't open the file: {}", e))?;
+    let mut header = [0u8; 12];
+    if file_len < 12 || file.read_exact(&mut header).is_err() {
+        return Ok(None);
+    }
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Ok(None);
+    }
+    let riff_size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+    let mut block_align = None;
+    let mut bytes_per_second = 0;
+    let mut pos = 12u64;
+    loop {
+        let mut chunk = [0u8; 8];
+        if pos + 8 > file_len || file.read_exact(&mut chunk).is_err() {
+            return Err("The file ends before its audio starts".to_string());
+        }
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+        match &chunk[0..4] {
+            b"fmt " => {
+                let mut fmt = [0u8; 16];
+                file.read_exact(&mut fmt)
+                    .map_err(|_| "The format header is cut off".to_string())?;
+                let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]);
+                let align = u16::from_le_bytes([fmt[12], fmt[13]]);
+                if sample_rate == 0 || align == 0 {
+                    return Err("The format header is invalid".to_string());
+                }
+                block_align = Some(u64::from(align));
+                bytes_per_second = u64::from(sample_rate) * u64::from(align);
+            }
+            b"data" => {
+                let block_align =
+                    block_align.ok_or_else(|| "The audio has no format header".to_string())?;
+                let data_start = pos + 8;
+                let on_disk = file_len - data_start;
+                let declared = u64::from(size);
+                // A declared size short of what's on disk is fine if
+                // another chunk follows the audio
+                let unaccounted = declared < on_disk
+                    && !next_is_chunk(&mut file, data_start + declared + declared % 2, file_len);
+                let data_bytes = if unaccounted || declared > on_disk {
+                    on_disk - on_disk % block_align
+                } else {
+                    declared
+                };
+                return Ok(Some(WavLayout {
+                    riff_size,
+                    data_size_pos: pos + 4,
+                    declared_data: size,
+                    data_bytes,
+                    unaccounted: unaccounted || (declared > on_disk && on_disk % block_align != 0),
+                    file_len,
+                    bytes_per_second,
+                }));
+            }
+            _ => {}
+        }
+        pos += 8 + u64::from(size) + u64::from(size) % 2;
+        file.seek(SeekFrom::Start(pos))
+            .map_err(|e| format!("Can't read the file: {}", e))?;
+    }
+}
+
+/// Whether a chunk that fits in the file starts at `pos`
+fn next_is_chunk(file: &mut File, pos: u64, file_len: u64) -> bool {
+    let mut header = [0u8; 8];
+    if file.seek(SeekFrom::Start(pos)).is_err() || file.read_exact(&mut header).is_err() {
+        return false;
+    }
+    let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    header[0..4]
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || *b == b' ')
+        && pos + 8 + u64::from(size) <= file_len
+}
+
+/// Check every history entry with audio, tagging the ones beyond repair
+pub fn check_history(history: &HistoryStore) -> Result<Vec<RecordingCheck>> {
+    let mut checks = Vec::new();
+    for entry in history.list(None)? {
+        let Some(audio_path) = entry.audio_path.clone() else {
+            continue;
+        };
+        let file = check_file(Path::new(&audio_path));
+        flag(history, &entry, file.health)?;
+        checks.push(RecordingCheck {
+            id: entry.id,
+            audio_path,
+            file,
+        });
+    }
+    Ok(checks)
+}
+
+/// Tag or untag an entry as having unrecoverable audio
+fn flag(history: &HistoryStore, entry: &HistoryEntry, health: RecordingHealth) -> Result<()> {
+    // A missing file may just be on a drive that isn't mounted
+    if health == RecordingHealth::Missing {
+        return Ok(());
+    }
+    let damaged = matches!(
+        health,
+        RecordingHealth::Empty | RecordingHealth::Unrecoverable
+    );
+    let tagged = entry.tags.iter().any(|tag| tag == DAMAGED_TAG);
+    if damaged == tagged {
+        return Ok(());
+    }
+    let mut tags: Vec<String> = entry
+        .tags
+        .iter()
+        .filter(|tag| *tag != DAMAGED_TAG)
+        .cloned()
+        .collect();
+    if damaged {
+        tags.push(DAMAGED_TAG.to_string());
+    }
+    history.set_tags(&entry.id, &tags)?;
+    Ok(())
+}
+
+/// Check the history's recordings once in the background at startup, so
+/// damage from a crash in the last session is found early
+pub fn check_on_startup(app: AppHandle) {
+    std::thread::spawn(move || {
+        let history = app.state::<HistoryStore>();
+        match check_history(&history) {
+            Ok(checks) => {
+                let damaged = checks
+                    .iter()
+                    .filter(|check| check.file.health != RecordingHealth::Ok)
+                    .count();
+                if damaged > 0 {
+                    warn!("{} of {} recordings need attention", damaged, checks.len());
+                }
+            }
+            Err(e) => warn!("Failed to check recordings: {}", e),
+        }
+    });
+}
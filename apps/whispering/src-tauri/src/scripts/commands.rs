@@ -0,0 +1,41 @@
+use super::{ScriptConfig, ScriptHook, ScriptRun, Scripts};
+use crate::error::{AppError, Result};
+use crate::focus::focused_app;
+use crate::transforms::TransformContext;
+use tauri::State;
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_script_config(scripts: State<'_, Scripts>) -> Result<ScriptConfig> {
+    Ok(scripts.config())
+}
+
+/// Save the hooks, returning them with ids assigned to new ones
+#[tauri::command]
+pub async fn set_script_config(
+    config: ScriptConfig,
+    scripts: State<'_, Scripts>,
+) -> Result<ScriptConfig> {
+    debug!("Saving {} script hooks", config.hooks.len());
+    scripts.set_config(config)
+}
+
+/// Run a hook, saved or not, on `text` and show what it printed, whatever
+/// its mode
+#[tauri::command]
+pub async fn test_script_hook(
+    hook: ScriptHook,
+    text: String,
+    context: Option<TransformContext>,
+) -> Result<ScriptRun> {
+    tauri::async_runtime::spawn_blocking(move || {
+        super::run(
+            &hook,
+            &text,
+            &context.unwrap_or_default(),
+            focused_app().as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Script hook task failed: {}", e)))?
+}
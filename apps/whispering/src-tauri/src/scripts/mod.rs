@@ -0,0 +1,435 @@
+//! Script hooks: the user's own programs, run on each new transcript.
+//!
+//! A lighter alternative to plugins. Each hook is a program started directly
+//! (no shell) with the transcript on stdin and what's known about it in the
+//! environment:
+//!
+//! - `WHISPERING_HOOK`: the hook's name
+//! - `WHISPERING_LANGUAGE`: the transcript's language code, if known
+//! - `WHISPERING_PROFILE`: the active profile, if any
+//! - `WHISPERING_FOCUSED_APP`: the app being dictated into, if known
+//!
+//! In `replace` mode the program's stdout becomes the transcript, provided it
+//! exits successfully within its timeout and prints something; otherwise the
+//! transcript is kept. `observe` hooks run in the background and their output
+//! is ignored, for logging or forwarding transcripts elsewhere.
+//!
+//! Hooks run wherever transforms are applied, after transform plugins and
+//! before profanity filtering, in the order they're listed.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::focus::focused_app;
+use crate::transforms::{TransformContext, TransformStage};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+pub const SCRIPTS_FILE: &str = "scripts.json";
+
+/// Replace hooks hold up the pipeline, so they get at most this long
+const MAX_TIMEOUT_SECS: u64 = 60;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Variables a hook keeps when its environment is cleared
+const KEPT_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "SYSTEMROOT",
+    "USERPROFILE",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptMode {
+    /// Stdout replaces the transcript
+    Replace,
+    /// Runs in the background; stdout is ignored
+    Observe,
+}
+
+/// Limits on what a hook's process sees
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Sandbox {
+    /// Start from an empty environment apart from `PATH`, `HOME` and the
+    /// like, so API keys in the app's environment don't leak to scripts
+    pub clear_env: bool,
+    /// Where the program runs; an empty scratch folder if unset
+    pub working_dir: Option<PathBuf>,
+    /// Output beyond this is discarded, and a replace hook that exceeds it
+    /// keeps the transcript
+    pub max_output_bytes: usize,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self {
+            clear_env: true,
+            working_dir: None,
+            max_output_bytes: 1 << 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptHook {
+    /// Assigned when the hook is saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Path to the program, or its name to find on `PATH`
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub mode: ScriptMode,
+    /// The program is killed after this long
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub sandbox: Sandbox,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+impl ScriptHook {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "Script hook name can't be empty".to_string(),
+            ));
+        }
+        if self.program.trim().is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "Script hook {} has no program",
+                self.name
+            )));
+        }
+        if !(1..=MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Err(AppError::InvalidInput(format!(
+                "Script hook timeout must be between 1 and {} seconds",
+                MAX_TIMEOUT_SECS
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScriptConfig {
+    /// In the order they run
+    pub hooks: Vec<ScriptHook>,
+}
+
+/// What one run of a hook produced
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRun {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` if the program was killed or died from a signal
+    pub code: Option<i32>,
+    pub timed_out: bool,
+    /// Stdout went past `max_output_bytes` and was cut short
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+impl ScriptRun {
+    /// The new transcript from a replace hook, if it produced one
+    fn replacement(&self) -> Option<String> {
+        if self.timed_out || self.truncated || self.code != Some(0) {
+            return None;
+        }
+        let text = self.stdout.strip_suffix('\n').unwrap_or(&self.stdout);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        (!text.trim().is_empty()).then(|| text.to_string())
+    }
+}
+
+/// Runs enabled hooks over transcripts, registered with `Transforms`
+struct HookStage {
+    config: Arc<Mutex<ScriptConfig>>,
+}
+
+impl TransformStage for HookStage {
+    fn apply(&self, mut text: String, context: &TransformContext) -> String {
+        let hooks: Vec<ScriptHook> = self
+            .config
+            .lock()
+            .map(|config| {
+                config
+                    .hooks
+                    .iter()
+                    .filter(|hook| hook.enabled)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        if hooks.is_empty() {
+            return text;
+        }
+        let app = focused_app();
+        for hook in hooks {
+            match hook.mode {
+                ScriptMode::Replace => match run(&hook, &text, context, app.as_deref()) {
+                    Ok(output) => match output.replacement() {
+                        Some(replacement) => text = replacement,
+                        None => warn!(
+                            "Script hook {} kept the transcript (exit code {:?}, timed out: {})",
+                            hook.name, output.code, output.timed_out
+                        ),
+                    },
+                    Err(e) => warn!("Skipping script hook {}: {}", hook.name, e),
+                },
+                ScriptMode::Observe => {
+                    let text = text.clone();
+                    let context = context.clone();
+                    let app = app.clone();
+                    thread::spawn(move || match run(&hook, &text, &context, app.as_deref()) {
+                        Ok(output) if output.code != Some(0) => warn!(
+                            "Script hook {} failed (exit code {:?}): {}",
+                            hook.name,
+                            output.code,
+                            output.stderr.trim()
+                        ),
+                        Ok(_) => debug!("Script hook {} ran", hook.name),
+                        Err(e) => warn!("Script hook {} failed: {}", hook.name, e),
+                    });
+                }
+            }
+        }
+        text
+    }
+}
+
+pub struct Scripts {
+    path: PathBuf,
+    config: Arc<Mutex<ScriptConfig>>,
+}
+
+impl Scripts {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                ScriptConfig::default()
+            }),
+            Err(_) => ScriptConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Arc::new(Mutex::new(config)),
+        }
+    }
+
+    /// The stage that runs enabled hooks, for `Transforms::add_stage`
+    pub fn transform_stage(&self) -> Arc<dyn TransformStage> {
+        Arc::new(HookStage {
+            config: self.config.clone(),
+        })
+    }
+
+    pub fn config(&self) -> ScriptConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, mut config: ScriptConfig) -> Result<ScriptConfig> {
+        for hook in &mut config.hooks {
+            hook.validate()?;
+            if hook.id.is_empty() {
+                hook.id = uuid::Uuid::new_v4().to_string();
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize script hooks: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock script hooks: {}", e)))? =
+            config.clone();
+        Ok(config)
+    }
+}
+
+/// Run `hook` with `text` on stdin, waiting at most its timeout
+pub fn run(
+    hook: &ScriptHook,
+    text: &str,
+    context: &TransformContext,
+    focused_app: Option<&str>,
+) -> Result<ScriptRun> {
+    hook.validate()?;
+    let working_dir = match &hook.sandbox.working_dir {
+        Some(dir) => dir.clone(),
+        None => {
+            let scratch = crate::session::temp_dir()?.join("scripts");
+            std::fs::create_dir_all(&scratch)?;
+            scratch
+        }
+    };
+
+    let mut command = Command::new(hook.program.trim());
+    command
+        .args(&hook.args)
+        .current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if hook.sandbox.clear_env {
+        command.env_clear();
+        for key in KEPT_ENV {
+            if let Some(value) = std::env::var_os(key) {
+                command.env(key, value);
+            }
+        }
+    }
+    command
+        .env("WHISPERING_HOOK", &hook.name)
+        .env(
+            "WHISPERING_LANGUAGE",
+            context.language.as_deref().unwrap_or_default(),
+        )
+        .env(
+            "WHISPERING_PROFILE",
+            context.profile.as_deref().unwrap_or_default(),
+        )
+        .env("WHISPERING_FOCUSED_APP", focused_app.unwrap_or_default());
+    #[cfg(target_os = "windows")]
+    command.creation_flags(crate::command::CREATE_NO_WINDOW);
+
+    let started = Instant::now();
+    let mut child = command
+        .spawn()
+        .map_err(|e| AppError::Internal(format!("Failed to start {}: {}", hook.program, e)))?;
+
+    // Feed stdin and drain the pipes on their own threads, so a program that
+    // writes before it finishes reading can't deadlock against us
+    let input = text.as_bytes().to_vec();
+    let stdin = child.stdin.take();
+    thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(&input);
+        }
+    });
+    let limit = hook.sandbox.max_output_bytes;
+    let stdout = drain(child.stdout.take(), limit);
+    let stderr = drain(child.stderr.take(), limit);
+
+    let deadline = started + Duration::from_secs(hook.timeout_secs);
+    let code = wait(&mut child, deadline)?;
+    // Anything the program started may still hold the pipes open after it
+    // exits, so the readers get until the same deadline
+    let (stdout, truncated) = stdout.collect(deadline);
+    let (stderr, _) = stderr.collect(deadline);
+    let duration_ms = started.elapsed().as_millis() as u64;
+    Ok(ScriptRun {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        timed_out: code.is_none(),
+        code: code.flatten(),
+        truncated,
+        duration_ms,
+    })
+}
+
+/// Wait for `child` to exit until `deadline`, then kill it
+///
+/// Returns `None` if it was killed, otherwise its exit code, itself `None`
+/// when it died from a signal.
+fn wait(child: &mut Child, deadline: Instant) -> Result<Option<Option<i32>>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status.code()));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Output read so far from one of the program's pipes
+struct Drain {
+    output: Arc<Mutex<Vec<u8>>>,
+    /// Whether anything was discarded, sent once the pipe closes
+    done: mpsc::Receiver<bool>,
+}
+
+impl Drain {
+    /// The output read by `deadline`, and whether it's incomplete: over the
+    /// limit, or still being written when the deadline passed. The reader
+    /// is left behind in that case rather than waited on.
+    fn collect(self, deadline: Instant) -> (Vec<u8>, bool) {
+        let truncated = self
+            .done
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            .unwrap_or(true);
+        let output = self
+            .output
+            .lock()
+            .map(|mut output| std::mem::take(&mut *output))
+            .unwrap_or_default();
+        (output, truncated)
+    }
+}
+
+/// Read up to `limit` bytes from `pipe`, discarding the rest so the program
+/// never blocks on a full pipe
+fn drain<R: Read + Send + 'static>(pipe: Option<R>, limit: usize) -> Drain {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let (done_tx, done) = mpsc::channel();
+    let shared = output.clone();
+    thread::spawn(move || {
+        let mut discarded = false;
+        if let Some(mut pipe) = pipe {
+            let mut chunk = [0u8; 8192];
+            loop {
+                let read = match pipe.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(read) => read,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                };
+                let Ok(mut output) = shared.lock() else {
+                    break;
+                };
+                let kept = limit.saturating_sub(output.len()).min(read);
+                output.extend_from_slice(&chunk[..kept]);
+                discarded |= kept < read;
+            }
+        }
+        let _ = done_tx.send(discarded);
+    });
+    Drain { output, done }
+}
@@ -0,0 +1,321 @@
+//! Sharing the machine with other users, or with other logins of the same
+//! user.
+//!
+//! History, settings and models live in the user's own app data directory,
+//! so users never see each other's. What belongs to one running instance is
+//! scoped to the login session too, so a console and a remote desktop login
+//! of the same user, or several users switching on one PC, can't pick up
+//! each other's files:
+//!
+//! - the IPC endpoint file, so the CLI and browser extension talk to the
+//!   instance in their own session ([`runtime_dir`])
+//! - scratch files. The system temp directory is shared between users on
+//!   Linux, where names in it can be guessed and claimed first, so they go
+//!   in a directory only the user can open ([`temp_dir`])
+//! - the single-instance lock. Launching Whispering again hands over to the
+//!   instance in the same session over IPC ([`hand_off`]). The single-instance
+//!   plugin backs that up where its lock is per session (a named mutex on
+//!   Windows, a socket in the per-user temp directory on macOS), but not on
+//!   Linux, where it claims a name on the user's D-Bus bus that every
+//!   session of theirs shares.
+//!
+//! While the app runs, the session is watched ([`watch`]). When it stops
+//! being the one at the screen (another user switches in, or a remote
+//! session is disconnected) a recording in progress is stopped and queued
+//! for transcription, rather than left listening to whoever sits down next.
+//! The frontend offers to transcribe it once the session is back, on
+//! `session://changed`.
+
+use crate::app_state::{AppState, StateEvent};
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, SessionChanged};
+use crate::ipc::{IpcClient, IpcCommand, IpcMessage, APP_IDENTIFIER};
+use crate::jobs::pending::{PendingJobs, PendingSource};
+use crate::recorder::AppData;
+use serde::Serialize;
+use std::io;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The login session this process runs in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub user: String,
+    /// The OS's id for the login; `None` where a user only has one (macOS)
+    pub id: Option<String>,
+}
+
+impl Session {
+    pub fn current() -> Self {
+        let user = ["USER", "USERNAME", "LOGNAME"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|user| !user.is_empty()))
+            .unwrap_or_else(|| "user".to_string());
+        Self {
+            user,
+            id: session_id(),
+        }
+    }
+
+    /// A name for the session that's safe in a file name, e.g. `alice-3`
+    pub fn scope(&self) -> String {
+        let name = match &self.id {
+            Some(id) => format!("{}-{}", self.user, id),
+            None => self.user.clone(),
+        };
+        name.chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' => c,
+                _ => '_',
+            })
+            .collect()
+    }
+}
+
+/// Payload of `session://changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionChange {
+    /// Whether the session is the one at the screen
+    pub active: bool,
+    /// Recordings stopped while it wasn't, on becoming active again
+    pub stopped_recordings: Vec<String>,
+}
+
+/// [`Session::scope`] of this process, worked out once
+pub fn scope() -> &'static str {
+    static SCOPE: OnceLock<String> = OnceLock::new();
+    SCOPE.get_or_init(|| Session::current().scope())
+}
+
+/// Directory for files that belong to this session's instance
+pub fn runtime_dir() -> Result<PathBuf> {
+    dirs::data_local_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER).join("sessions").join(scope()))
+        .ok_or_else(|| AppError::Internal("Failed to resolve local data directory".to_string()))
+}
+
+/// This session's own directory for scratch files, created on first use
+///
+/// It lives in the user's runtime directory (`$XDG_RUNTIME_DIR`) where there
+/// is one, else their cache directory, and only its owner can open it.
+pub fn temp_dir() -> io::Result<PathBuf> {
+    let base = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "No private directory for temp files",
+            )
+        })?;
+    let dir = base.join(APP_IDENTIFIER).join("tmp").join(scope());
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+/// Show the instance already running in this session, if there is one,
+/// returning whether it took over
+pub fn hand_off() -> bool {
+    let response = IpcClient::connect().and_then(|mut client| client.request(IpcCommand::ShowUi));
+    match response {
+        Ok(IpcMessage::Ok) => {
+            info!("Whispering is already running in this session");
+            true
+        }
+        Ok(_) | Err(_) => false,
+    }
+}
+
+/// Stop recording whenever the session stops being the one at the screen
+pub fn watch(app: AppHandle) {
+    thread::spawn(move || {
+        let mut was_active = true;
+        let mut stopped = Vec::new();
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+            let Some(active) = is_active() else {
+                continue;
+            };
+            if active == was_active {
+                continue;
+            }
+            was_active = active;
+            info!(
+                "Session {} is {}",
+                scope(),
+                if active { "active" } else { "inactive" }
+            );
+            if !active {
+                match stop_recording(&app) {
+                    Ok(Some(path)) => stopped.push(path),
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to stop recording on switching away: {}", e),
+                }
+            }
+            let change = SessionChange {
+                active,
+                stopped_recordings: if active {
+                    std::mem::take(&mut stopped)
+                } else {
+                    Vec::new()
+                },
+            };
+            if let Err(e) = SessionChanged::emit(&app, &change) {
+                warn!("Failed to announce session change: {}", e);
+            }
+        }
+    });
+}
+
+/// Stop the recording in progress, if any, queueing its file
+fn stop_recording(app: &AppHandle) -> Result<Option<String>> {
+    let data = app.state::<AppData>();
+    let recording = {
+        let mut recorder = data
+            .recorder
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?;
+        if recorder.get_current_recording_id().is_none() {
+            return Ok(None);
+        }
+        let recording = recorder.stop_recording()?;
+        recorder.close_session()?;
+        recording
+    };
+    let app_state = app.state::<AppState>();
+    app_state.record(StateEvent::RecordingStopped);
+    app_state.record(StateEvent::Completed);
+    let Some(path) = recording.file_path else {
+        return Ok(None);
+    };
+    info!("Stopped recording {} on switching away", path);
+    app.state::<PendingJobs>()
+        .enqueue(&path, PendingSource::Recording)?;
+    Ok(Some(path))
+}
+
+#[cfg(target_os = "windows")]
+fn session_id() -> Option<String> {
+    use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+
+    let mut id = 0u32;
+    // Safety: `id` outlives the call, which only writes to it
+    let ok = unsafe { ProcessIdToSessionId(std::process::id(), &mut id) };
+    (ok != 0).then(|| id.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn session_id() -> Option<String> {
+    std::env::var("XDG_SESSION_ID")
+        .ok()
+        .filter(|id| !id.is_empty())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn session_id() -> Option<String> {
+    None
+}
+
+/// Whether this session is the one at the screen, or `None` if that can't
+/// be told
+///
+/// A locked screen still counts as active; only another session taking
+/// over the console, or a remote one being disconnected, doesn't.
+#[cfg(target_os = "windows")]
+fn is_active() -> Option<bool> {
+    use windows_sys::Win32::System::RemoteDesktop::{
+        WTSActive, WTSConnectState, WTSFreeMemory, WTSQuerySessionInformationW,
+        WTS_CONNECTSTATE_CLASS, WTS_CURRENT_SERVER_HANDLE, WTS_CURRENT_SESSION,
+    };
+
+    let mut buffer = std::ptr::null_mut();
+    let mut len = 0u32;
+    // Safety: on success the buffer holds a WTS_CONNECTSTATE_CLASS, which is
+    // read before it's freed with WTSFreeMemory
+    unsafe {
+        let ok = WTSQuerySessionInformationW(
+            WTS_CURRENT_SERVER_HANDLE,
+            WTS_CURRENT_SESSION,
+            WTSConnectState,
+            &mut buffer,
+            &mut len,
+        );
+        if ok == 0 || buffer.is_null() {
+            return None;
+        }
+        let state = *(buffer as *const WTS_CONNECTSTATE_CLASS);
+        WTSFreeMemory(buffer as _);
+        Some(state == WTSActive)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_active() -> Option<bool> {
+    static CONNECTION: OnceLock<Option<zbus::blocking::Connection>> = OnceLock::new();
+    let connection = CONNECTION
+        .get_or_init(|| {
+            zbus::blocking::Connection::system()
+                .map_err(|e| warn!("Failed to connect to the system bus: {}", e))
+                .ok()
+        })
+        .as_ref()?;
+    // `auto` is the caller's session, or the user's graphical one for
+    // processes started outside a session (e.g. by a user service)
+    let query = || -> zbus::Result<bool> {
+        let reply = connection.call_method(
+            Some("org.freedesktop.login1"),
+            "/org/freedesktop/login1/session/auto",
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &("org.freedesktop.login1.Session", "Active"),
+        )?;
+        let value: zbus::zvariant::OwnedValue = reply.body().deserialize()?;
+        Ok(bool::try_from(value)?)
+    };
+    query()
+        .map_err(|e| tracing::debug!("Failed to ask logind if the session is active: {}", e))
+        .ok()
+}
+
+#[cfg(target_os = "macos")]
+fn is_active() -> Option<bool> {
+    use core_foundation_sys::base::CFRelease;
+    use core_foundation_sys::dictionary::{CFDictionaryGetValue, CFDictionaryRef};
+    use core_foundation_sys::number::{CFBooleanGetValue, CFBooleanRef};
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
+    }
+
+    // Safety: the dictionary is released once, after the value borrowed
+    // from it has been read
+    unsafe {
+        let session = CGSessionCopyCurrentDictionary();
+        if session.is_null() {
+            return None;
+        }
+        let key = crate::focus::cf_string("kCGSSessionOnConsoleKey");
+        let value = CFDictionaryGetValue(session, key as _) as CFBooleanRef;
+        let on_console = (!value.is_null()).then(|| CFBooleanGetValue(value));
+        CFRelease(key as _);
+        CFRelease(session as _);
+        on_console
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn is_active() -> Option<bool> {
+    None
+}
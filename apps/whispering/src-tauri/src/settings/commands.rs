@@ -0,0 +1,28 @@
+use crate::error::Result;
+use crate::settings::policy::Policy;
+use crate::settings::{NativeSettings, SettingsStore};
+use tauri::{AppHandle, Runtime, State};
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_native_settings(settings: State<'_, SettingsStore>) -> Result<NativeSettings> {
+    Ok(settings.get())
+}
+
+/// Called by the frontend whenever one of the mirrored settings changes
+#[tauri::command]
+pub async fn set_native_settings<R: Runtime>(
+    settings: NativeSettings,
+    store: State<'_, SettingsStore>,
+    app_handle: AppHandle<R>,
+) -> Result<NativeSettings> {
+    debug!("Updating native settings: {:?}", settings);
+    store.set(&app_handle, settings)
+}
+
+/// The admin policy in effect, so the UI can lock managed settings and hide
+/// what's disallowed
+#[tauri::command]
+pub async fn get_policy(store: State<'_, SettingsStore>) -> Result<Policy> {
+    Ok(store.policy().clone())
+}
@@ -0,0 +1,279 @@
+//! Reading and writing the settings file.
+//!
+//! Settings are stored as TOML so they can be edited by hand. Writes go
+//! through `toml_edit`, which keeps the user's comments and formatting and
+//! only touches values that changed. Global shortcuts and providers go in
+//! `[hotkeys]` and `[providers]` sections. Files ending in `.json` (the
+//! format used before TOML) are still read and written as JSON.
+
+use super::NativeSettings;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// Comment written above each key when a new TOML file is created
+const KEY_COMMENTS: &[(&str, &str)] = &[
+    (
+        "closeToTray",
+        "Hide the window instead of quitting when it's closed",
+    ),
+    (
+        "startMinimized",
+        "Launch with the window hidden, leaving only the tray icon",
+    ),
+    (
+        "soundFeedback",
+        "Play sounds on recording and transcription events",
+    ),
+    (
+        "autoPaste",
+        "Paste the transcript at the cursor once it's ready",
+    ),
+    (
+        "privacyMode",
+        "Block every network request so audio never leaves this machine",
+    ),
+    (
+        "detectMeetings",
+        "Offer to transcribe when Zoom, Teams, Meet, etc. start a meeting",
+    ),
+    (
+        "meetingAppsAllowed",
+        "Meeting apps transcribed without asking, e.g. [\"Zoom\"]",
+    ),
+    ("meetingAppsDenied", "Meeting apps never asked about"),
+    (
+        "warmStart",
+        "Keep the microphone open between recordings so capture starts instantly",
+    ),
+    (
+        "preRollMs",
+        "Milliseconds of audio kept from before each recording starts (0, or 500-2000)",
+    ),
+    (
+        "audioBackend",
+        "Audio host API: \"default\", \"asio\" (Windows) or \"jack\" (Linux)",
+    ),
+    (
+        "avoidBluetoothMic",
+        "Record from another microphone instead of a Bluetooth headset's, keeping its audio quality",
+    ),
+    (
+        "processingTimeoutSecs",
+        "Cancel a transcription still processing after this many seconds (0 to wait forever)",
+    ),
+    (
+        "screenReaderAnnouncements",
+        "Have screen readers announce when recording starts and stops and text is pasted",
+    ),
+    (
+        "trayTheme",
+        "Tray icons: \"classic\", \"highContrast\", \"monochrome\" or \"custom\"",
+    ),
+    (
+        "trayThemeFolder",
+        "Folder with idle.png, recording.png and processing.png for the custom tray theme",
+    ),
+    (
+        "customTrayIconIdle",
+        "PNG shown in the tray while idle, replacing the theme's (16-1024 pixels a side)",
+    ),
+    (
+        "customTrayIconRecording",
+        "PNG shown in the tray while recording",
+    ),
+    (
+        "customTrayIconProcessing",
+        "PNG shown in the tray while transcribing",
+    ),
+    (
+        "customStartSound",
+        "MP3, WAV, Ogg or FLAC file played when recording starts (up to 5 MiB)",
+    ),
+    ("customStopSound", "Sound file played when recording stops"),
+    (
+        "language",
+        "Language of the tray menu and notifications: \"en\", \"de\", \"es\", \"fr\", or \"\" for the system's",
+    ),
+    (
+        "timeZone",
+        "Time zone for exported and reported times, e.g. \"Europe/Berlin\" or \"UTC\"; \"\" for the system's",
+    ),
+    (
+        "timestampStyle",
+        "How exports write dates: \"iso\" (2024-05-01) or \"locale\" (the system's order)",
+    ),
+    (
+        "fileNameTemplate",
+        "Name for saved recordings and exports, e.g. \"{{date}}_{{app}}_{{first_words}}\"; \"\" for the built-in names",
+    ),
+    (
+        "minFreeDiskMb",
+        "Free disk space (MB) recordings keep; they pause below it. 0 to turn off",
+    ),
+    (
+        "hotkeys",
+        "Global shortcuts by command, e.g. toggleManualRecording = \"CommandOrControl+Shift+;\"; \"\" for none",
+    ),
+    (
+        "providers",
+        "Services used by the app; transcription is e.g. \"whispercpp\", \"openai\" or \"groq\"",
+    ),
+];
+
+const HEADER: &str = "\
+# Whispering settings
+#
+# Safe to edit by hand: changes are applied while Whispering is running.
+# Keys that are missing use their default value.
+";
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+pub fn parse(path: &Path, contents: &str) -> Result<NativeSettings, String> {
+    if is_json(path) {
+        serde_json::from_str(contents).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    }
+}
+
+/// Render `settings` for writing to `path`, preserving the comments and
+/// layout of `existing` (the file's current contents) where possible
+pub fn render(
+    path: &Path,
+    existing: Option<&str>,
+    settings: &NativeSettings,
+) -> Result<String, String> {
+    if is_json(path) {
+        return serde_json::to_string_pretty(settings).map_err(|e| e.to_string());
+    }
+
+    let values = match serde_json::to_value(settings).map_err(|e| e.to_string())? {
+        serde_json::Value::Object(values) => values,
+        _ => return Err("Settings must serialize to a table".to_string()),
+    };
+
+    let (mut document, mut header) = match existing.map(str::parse::<DocumentMut>) {
+        Some(Ok(document)) => (document, ""),
+        // A broken or missing file is replaced by a fresh, commented one
+        _ => (DocumentMut::new(), HEADER),
+    };
+
+    for (key, json) in values {
+        if let serde_json::Value::Object(entries) = json {
+            render_section(&mut document, &key, entries);
+            continue;
+        }
+        let Some(value) = to_toml(&json) else {
+            continue;
+        };
+        if !set_value(document.as_table_mut(), &key, value, &json) {
+            continue;
+        }
+        let comment = comment_for(&key)
+            .map(|comment| format!("\n# {}\n", comment))
+            .unwrap_or_default();
+        if let Some(mut inserted) = document.key_mut(&key) {
+            inserted
+                .leaf_decor_mut()
+                .set_prefix(format!("{}{}", header, comment));
+        }
+        header = "";
+    }
+
+    Ok(document.to_string())
+}
+
+fn comment_for(key: &str) -> Option<&'static str> {
+    KEY_COMMENTS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, comment)| *comment)
+}
+
+/// Set `key` unless it already holds `json`, keeping any trailing comment on
+/// the line; returns whether the key is new
+fn set_value(table: &mut Table, key: &str, mut value: Value, json: &serde_json::Value) -> bool {
+    match table.get_mut(key) {
+        Some(item) => {
+            if let Some(current) = item.as_value() {
+                if same(current, json) {
+                    return false;
+                }
+                *value.decor_mut() = current.decor().clone();
+            }
+            *item = Item::Value(value);
+            false
+        }
+        None => {
+            table.insert(key, Item::Value(value));
+            true
+        }
+    }
+}
+
+/// Write a nested setting (`hotkeys`, `providers`) as a `[section]`, dropping
+/// keys the app no longer has so the file mirrors what's applied
+fn render_section(
+    document: &mut DocumentMut,
+    key: &str,
+    entries: serde_json::Map<String, serde_json::Value>,
+) {
+    if !document.get(key).is_some_and(Item::is_table) {
+        let mut table = Table::new();
+        if let Some(comment) = comment_for(key) {
+            table.decor_mut().set_prefix(format!("\n# {}\n", comment));
+        }
+        document.insert(key, Item::Table(table));
+    }
+    let Some(table) = document.get_mut(key).and_then(Item::as_table_mut) else {
+        return;
+    };
+
+    let stale: Vec<String> = table
+        .iter()
+        .map(|(k, _)| k.to_string())
+        .filter(|k| !entries.contains_key(k))
+        .collect();
+    for k in stale {
+        table.remove(&k);
+    }
+    for (k, json) in entries {
+        if let Some(value) = to_toml(&json) {
+            set_value(table, &k, value, &json);
+        }
+    }
+}
+
+fn to_toml(value: &serde_json::Value) -> Option<Value> {
+    match value {
+        serde_json::Value::Bool(b) => Some(Value::from(*b)),
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .map(Value::from)
+            .or_else(|| n.as_f64().map(Value::from)),
+        serde_json::Value::String(s) => Some(Value::from(s.as_str())),
+        serde_json::Value::Array(items) => Some(Value::Array(
+            items.iter().filter_map(to_toml).collect(),
+        )),
+        _ => None,
+    }
+}
+
+fn same(current: &Value, value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Bool(b) => current.as_bool() == Some(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => current.as_integer() == Some(i),
+            None => current.as_float() == n.as_f64(),
+        },
+        serde_json::Value::String(s) => current.as_str() == Some(s.as_str()),
+        serde_json::Value::Array(items) => current.as_array().is_some_and(|array| {
+            array.len() == items.len() && array.iter().zip(items).all(|(c, v)| same(c, v))
+        }),
+        _ => false,
+    }
+}
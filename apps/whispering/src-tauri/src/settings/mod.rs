@@ -0,0 +1,375 @@
+//! Settings the Rust side needs to act on by itself.
+//!
+//! The frontend owns the full settings object (persisted in localStorage);
+//! it mirrors the handful of keys listed here so they're available before
+//! the webview has loaded and while the window is hidden.
+//!
+//! The settings file is TOML (see `file`), can be moved with `--config`, and
+//! is watched, so edits made outside the app (dotfile managers, sync tools)
+//! are applied live and announced with `settings://reloaded`.
+
+pub mod commands;
+mod file;
+pub mod policy;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, SettingsChanged, SettingsReloaded};
+use crate::recorder::backend::AudioBackend;
+use crate::timestamps::TimestampStyle;
+use crate::tray::TrayTheme;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use policy::Policy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::{debug, error, info, warn};
+
+/// Editors often write a file in several steps; wait for them to finish
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// JSON file used before settings moved to TOML, migrated on first start
+const LEGACY_SETTINGS_FILE: &str = "native_settings.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NativeSettings {
+    /// Hide the window instead of quitting when it's closed
+    pub close_to_tray: bool,
+    /// Launch with the window hidden, leaving only the tray icon
+    pub start_minimized: bool,
+    /// Play sounds on recording and transcription events
+    pub sound_feedback: bool,
+    /// Paste the transcript at the cursor once it's ready
+    pub auto_paste: bool,
+    /// Block every request that would leave the machine (see `privacy`)
+    pub privacy_mode: bool,
+    /// Offer to transcribe when a conferencing app starts a meeting (see `meetings`)
+    pub detect_meetings: bool,
+    /// Meeting apps transcribed without asking
+    pub meeting_apps_allowed: Vec<String>,
+    /// Meeting apps never asked about
+    pub meeting_apps_denied: Vec<String>,
+    /// Keep the microphone open (paused) between recordings so capture
+    /// starts instantly; off saves a little battery
+    pub warm_start: bool,
+    /// Audio kept from just before a recording starts, in milliseconds (0
+    /// for none); needs warm start
+    pub pre_roll_ms: u32,
+    /// Host API for recording devices; ASIO and JACK need builds with the
+    /// matching cargo feature
+    pub audio_backend: AudioBackend,
+    /// Record from another microphone when a Bluetooth headset's is
+    /// selected, so the headset keeps full quality for playback
+    pub avoid_bluetooth_mic: bool,
+    /// Give up on a transcription still processing after this many
+    /// seconds (0 to wait forever; see `watchdog`)
+    pub processing_timeout_secs: u32,
+    /// Have screen readers announce recording, transcription and pasting
+    /// (see `accessibility`)
+    pub screen_reader_announcements: bool,
+    /// Icon set for the tray (see `tray::theme`)
+    pub tray_theme: TrayTheme,
+    /// Folder with `idle.png`, `recording.png` and `processing.png` for the
+    /// custom tray theme
+    pub tray_theme_folder: String,
+    /// PNGs used for the tray icon in each state instead of the theme's
+    /// (see `custom_assets`); empty for the theme's own
+    pub custom_tray_icon_idle: String,
+    pub custom_tray_icon_recording: String,
+    pub custom_tray_icon_processing: String,
+    /// Sounds played when recording starts and stops instead of the
+    /// built-in ones; empty for the built-in ones
+    pub custom_start_sound: String,
+    pub custom_stop_sound: String,
+    /// Language of tray menus, tooltips and notifications, e.g. `de`; empty
+    /// to follow the system (see `i18n`)
+    pub language: String,
+    /// Zone times are shown in, e.g. `Europe/Berlin` or `UTC`; empty for
+    /// the system's (see `timestamps`)
+    pub time_zone: String,
+    /// How exports and reports write dates
+    pub timestamp_style: TimestampStyle,
+    /// Names saved recordings, exports and watched-folder transcripts, e.g.
+    /// `{{date}}_{{app}}_{{first_words}}`; empty for the built-in names (see
+    /// `file_names`)
+    pub file_name_template: String,
+    /// Free space, in MB, a recording's folder must keep; recordings won't
+    /// start below it and pause when they reach it. 0 turns the check off
+    pub min_free_disk_mb: u32,
+    /// Global shortcuts by frontend command id, e.g. `toggleManualRecording =
+    /// "CommandOrControl+Shift+;"`; empty leaves the command unbound. The
+    /// frontend registers them, so edits to the file take effect right away
+    pub hotkeys: BTreeMap<String, String>,
+    /// Services the frontend sends audio and text to
+    pub providers: ProviderSettings,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderSettings {
+    /// Transcription service id, e.g. `whispercpp`, `openai` or `groq`;
+    /// empty until the frontend first reports its choice
+    pub transcription: String,
+}
+
+impl Default for NativeSettings {
+    fn default() -> Self {
+        Self {
+            close_to_tray: false,
+            start_minimized: false,
+            sound_feedback: true,
+            auto_paste: true,
+            privacy_mode: false,
+            detect_meetings: false,
+            meeting_apps_allowed: Vec::new(),
+            meeting_apps_denied: Vec::new(),
+            warm_start: true,
+            pre_roll_ms: 0,
+            audio_backend: AudioBackend::default(),
+            avoid_bluetooth_mic: false,
+            processing_timeout_secs: 120,
+            screen_reader_announcements: true,
+            tray_theme: TrayTheme::default(),
+            tray_theme_folder: String::new(),
+            custom_tray_icon_idle: String::new(),
+            custom_tray_icon_recording: String::new(),
+            custom_tray_icon_processing: String::new(),
+            custom_start_sound: String::new(),
+            custom_stop_sound: String::new(),
+            language: String::new(),
+            time_zone: String::new(),
+            timestamp_style: TimestampStyle::default(),
+            file_name_template: String::new(),
+            min_free_disk_mb: 500,
+            hotkeys: BTreeMap::new(),
+            providers: ProviderSettings::default(),
+        }
+    }
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    policy: Policy,
+    settings: Mutex<NativeSettings>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl SettingsStore {
+    /// Load settings from disk, falling back to defaults if the file is
+    /// missing or unreadable
+    pub fn open(path: &Path, policy: Policy) -> Self {
+        let settings = match std::fs::read_to_string(path) {
+            Ok(contents) => file::parse(path, &contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                NativeSettings::default()
+            }),
+            Err(_) => Self::migrate_legacy(path).unwrap_or_default(),
+        };
+        let settings = enforce(&policy, settings);
+        debug!("Loaded native settings from {:?}: {:?}", path, settings);
+        Self {
+            path: path.to_path_buf(),
+            policy,
+            settings: Mutex::new(settings),
+            watcher: Mutex::new(None),
+        }
+    }
+
+    /// Carry over settings from the JSON file next to `path`, if there is one
+    fn migrate_legacy(path: &Path) -> Option<NativeSettings> {
+        let legacy = path.with_file_name(LEGACY_SETTINGS_FILE);
+        if legacy == path {
+            return None;
+        }
+        let settings: NativeSettings =
+            serde_json::from_str(&std::fs::read_to_string(&legacy).ok()?).ok()?;
+        match file::render(path, None, &settings) {
+            Ok(contents) => match std::fs::write(path, contents) {
+                Ok(()) => {
+                    info!("Migrated {:?} to {:?}", legacy, path);
+                    let _ = std::fs::remove_file(&legacy);
+                }
+                Err(e) => warn!("Failed to write {:?}: {}", path, e),
+            },
+            Err(e) => warn!("Failed to migrate {:?}: {}", legacy, e),
+        }
+        Some(settings)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    pub fn get(&self) -> NativeSettings {
+        self.settings
+            .lock()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the settings, persist them and notify the frontend if anything changed
+    ///
+    /// Keys managed by the policy keep their forced values.
+    pub fn set<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        settings: NativeSettings,
+    ) -> Result<NativeSettings> {
+        let settings = enforce(&self.policy, settings);
+        {
+            let mut current = self
+                .settings
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock settings: {}", e)))?;
+            if *current == settings {
+                return Ok(settings);
+            }
+            *current = settings.clone();
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let existing = std::fs::read_to_string(&self.path).ok();
+        let contents = file::render(&self.path, existing.as_deref(), &settings)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize settings: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+
+        let _ = SettingsChanged::emit(app, &settings);
+        Ok(settings)
+    }
+
+    /// Apply a change to the current settings, as done by the tray menu toggles
+    pub fn update<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        f: impl FnOnce(&mut NativeSettings),
+    ) -> Result<NativeSettings> {
+        let mut settings = self.get();
+        f(&mut settings);
+        self.set(app, settings)
+    }
+
+    /// Whether closing the window should hide it instead of exiting
+    ///
+    /// Only honoured when a tray icon is actually visible, otherwise the user
+    /// would have no way to bring the window back.
+    pub fn should_hide_to_tray(&self, tray_supported: bool) -> bool {
+        tray_supported && self.get().close_to_tray
+    }
+
+    /// Watch the settings file and apply external edits as they happen
+    ///
+    /// The parent directory is watched rather than the file itself, since
+    /// many editors save by writing a new file and renaming it over the old.
+    pub fn watch(&self, app: AppHandle) -> Result<()> {
+        let Some(dir) = self.path.parent() else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(dir)?;
+
+        let (event_tx, event_rx) = mpsc::channel::<()>();
+        let file_name = self.path.file_name().map(|name| name.to_os_string());
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                Ok(event) => {
+                    let ours = event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == file_name.as_deref());
+                    if ours && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                        let _ = event_tx.send(());
+                    }
+                }
+                Err(e) => error!("Settings watcher error: {}", e),
+            })
+            .map_err(|e| AppError::Internal(format!("Failed to create settings watcher: {}", e)))?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::Internal(format!("Failed to watch {:?}: {}", dir, e)))?;
+
+        thread::spawn(move || {
+            while event_rx.recv().is_ok() {
+                // Collapse a burst of events into one reload
+                while event_rx.recv_timeout(RELOAD_DEBOUNCE).is_ok() {}
+                if let Err(e) = app.state::<SettingsStore>().reload(&app) {
+                    warn!("Failed to reload settings: {}", e);
+                }
+            }
+        });
+
+        *self
+            .watcher
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock settings watcher: {}", e)))? =
+            Some(watcher);
+        info!("Watching {:?} for external changes", self.path);
+        Ok(())
+    }
+
+    /// Re-read the settings file, applying it if it differs from what's loaded
+    ///
+    /// Our own writes land here too; they match the loaded settings and are
+    /// ignored. An invalid file is reported and the current settings kept.
+    fn reload(&self, app: &AppHandle) -> Result<()> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            // Deleted, or mid-rename; the next event will bring it back
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let settings = file::parse(&self.path, &contents).map_err(|e| {
+            AppError::InvalidInput(format!("Invalid settings in {:?}: {}", self.path, e))
+        })?;
+        let settings = enforce(&self.policy, settings);
+
+        {
+            let mut current = self
+                .settings
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock settings: {}", e)))?;
+            if *current == settings {
+                return Ok(());
+            }
+            *current = settings.clone();
+        }
+
+        info!("Settings reloaded from {:?}", self.path);
+        let _ = SettingsChanged::emit(app, &settings);
+        let _ = SettingsReloaded::emit(app, &settings);
+        Ok(())
+    }
+}
+
+/// Override settings with the values forced by the policy
+fn enforce(policy: &Policy, mut settings: NativeSettings) -> NativeSettings {
+    if policy.local_only {
+        settings.privacy_mode = true;
+    }
+    // One key at a time, so a value of the wrong type only loses that key
+    for (key, forced) in &policy.settings {
+        let Ok(serde_json::Value::Object(mut values)) = serde_json::to_value(&settings) else {
+            break;
+        };
+        // Keys the frontend owns are applied there
+        let Some(value) = values.get_mut(key) else {
+            continue;
+        };
+        *value = forced.clone();
+        match serde_json::from_value(serde_json::Value::Object(values)) {
+            Ok(updated) => settings = updated,
+            Err(e) => warn!("Ignoring policy setting {} of the wrong type: {}", key, e),
+        }
+    }
+    settings
+}
@@ -0,0 +1,139 @@
+//! Admin-provisioned policy that forces and locks settings.
+//!
+//! The policy file is TOML in a system-wide location users can't write to:
+//!
+//! ```toml
+//! # Only local transcription engines may be used
+//! local_only = true
+//! # Keep nothing on disk after transcription
+//! disable_history = true
+//! # Mask profanity in every transcript ("keep", "mask" or "remove")
+//! profanity = "mask"
+//!
+//! # Forced values, keyed like the app's settings
+//! [settings]
+//! closeToTray = true
+//! "transcription.selectedTranscriptionService" = "whispercpp"
+//! ```
+//!
+//! Native settings keys are enforced here; the rest are reported to the
+//! frontend, which owns those settings and applies them.
+
+use crate::transforms::ProfanityLevel;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// File name of the policy inside the system policy directory
+const POLICY_FILE: &str = "policy.toml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Policy {
+    /// Where the policy was loaded from, `None` if there isn't one
+    #[serde(skip_deserializing)]
+    pub source: Option<String>,
+    /// Disallow cloud transcription and LLM providers
+    #[serde(default, alias = "local_only")]
+    pub local_only: bool,
+    /// Don't keep transcripts or audio in the history database
+    #[serde(default, alias = "disable_history")]
+    pub disable_history: bool,
+    /// Profanity filtering for every transcript, over the user's choice
+    #[serde(default)]
+    pub profanity: Option<ProfanityLevel>,
+    /// Settings forced to these values, which the user can't change
+    #[serde(default)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Policy {
+    /// Keys of settings managed by the policy
+    pub fn managed_keys(&self) -> Vec<String> {
+        self.settings.keys().cloned().collect()
+    }
+
+    pub fn is_managed(&self, key: &str) -> bool {
+        self.settings.contains_key(key)
+    }
+}
+
+/// System-wide policy location for this platform
+///
+/// Debug builds can point elsewhere with `WHISPERING_POLICY_FILE`; release
+/// builds ignore it, or any user could swap in a policy of their own.
+fn policy_path() -> Option<PathBuf> {
+    #[cfg(debug_assertions)]
+    if let Ok(path) = std::env::var("WHISPERING_POLICY_FILE") {
+        return Some(PathBuf::from(path));
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var("ProgramData")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join("Whispering").join(POLICY_FILE))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Some(PathBuf::from("/Library/Application Support/Whispering").join(POLICY_FILE))
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        Some(PathBuf::from("/etc/whispering").join(POLICY_FILE))
+    }
+}
+
+/// Load the policy, or an empty one if none is installed
+pub fn load() -> Policy {
+    policy_path()
+        .map(|path| load_from(&path))
+        .unwrap_or_default()
+}
+
+/// Load the policy at `path`, or an empty one if there's no file there
+///
+/// A policy that exists but can't be read or parsed fails closed: it's
+/// logged and replaced by the strictest one, local only and without history,
+/// so an admin's typo doesn't lift their restrictions. Refusing to start
+/// instead would lock users out.
+pub fn load_from(path: &Path) -> Policy {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Policy::default(),
+        Err(e) => {
+            warn!(
+                "Failed to read policy {:?}, restricting everything: {}",
+                path, e
+            );
+            return strictest(path);
+        }
+    };
+    match toml::from_str::<Policy>(&contents) {
+        Ok(mut policy) => {
+            policy.source = Some(path.to_string_lossy().to_string());
+            info!(
+                "Loaded policy from {:?}: local_only={}, disable_history={}, profanity={:?}, managed={:?}",
+                path,
+                policy.local_only,
+                policy.disable_history,
+                policy.profanity,
+                policy.managed_keys()
+            );
+            policy
+        }
+        Err(e) => {
+            warn!("Invalid policy {:?}, restricting everything: {}", path, e);
+            strictest(path)
+        }
+    }
+}
+
+/// Stand-in for a policy that couldn't be loaded
+fn strictest(path: &Path) -> Policy {
+    Policy {
+        source: Some(path.to_string_lossy().to_string()),
+        local_only: true,
+        disable_history: true,
+        ..Default::default()
+    }
+}
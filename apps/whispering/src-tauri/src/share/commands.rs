@@ -0,0 +1,26 @@
+use super::{Share, ShareConfig, ShareExpiry, Sharing};
+use crate::error::Result;
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_share_config(sharing: State<'_, Sharing>) -> Result<ShareConfig> {
+    Ok(sharing.config())
+}
+
+#[tauri::command]
+pub async fn set_share_config(config: ShareConfig, sharing: State<'_, Sharing>) -> Result<()> {
+    debug!("Updating share service: {}", config.service_url);
+    sharing.set_config(config)
+}
+
+/// Encrypt a transcript and upload it, returning a link that carries the key
+#[tauri::command]
+pub async fn create_share(
+    id: String,
+    expiry: ShareExpiry,
+    sharing: State<'_, Sharing>,
+    app: AppHandle,
+) -> Result<Share> {
+    sharing.create(&app, &id, expiry).await
+}
@@ -0,0 +1,259 @@
+//! End-to-end encrypted share links for transcripts.
+//!
+//! Transcripts are uploaded to a PrivateBin instance (privatebin.net or a
+//! self-hosted one) in its v2 format: encrypted here with a random key that
+//! only ever appears in the link's `#fragment`, which browsers don't send to
+//! the server. Anyone with the link can read the transcript in PrivateBin's
+//! own page; the server only ever stores ciphertext.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::history::HistoryStore;
+use crate::privacy;
+use aes_gcm::aead::consts::U16;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::aes::Aes256;
+use aes_gcm::AesGcm;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+pub const SHARE_FILE: &str = "share.json";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// PrivateBin v2 encryption parameters
+const KDF_ITERATIONS: u32 = 100_000;
+const KEY_BITS: u32 = 256;
+const TAG_BITS: u32 = 128;
+
+/// PrivateBin uses 16-byte IVs rather than GCM's usual 12
+type Cipher = AesGcm<Aes256, U16>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShareConfig {
+    /// PrivateBin instance links point to
+    pub service_url: String,
+}
+
+impl Default for ShareConfig {
+    fn default() -> Self {
+        Self {
+            service_url: "https://privatebin.net".to_string(),
+        }
+    }
+}
+
+/// How long a share stays up; PrivateBin instances may cap this
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareExpiry {
+    FiveMinutes,
+    OneHour,
+    OneDay,
+    OneWeek,
+    OneMonth,
+    /// Deleted as soon as it has been opened once
+    BurnAfterReading,
+}
+
+impl ShareExpiry {
+    /// PrivateBin's name for the expiry, and whether it burns after reading
+    fn as_privatebin(&self) -> (&'static str, bool) {
+        match self {
+            ShareExpiry::FiveMinutes => ("5min", false),
+            ShareExpiry::OneHour => ("1hour", false),
+            ShareExpiry::OneDay => ("1day", false),
+            ShareExpiry::OneWeek => ("1week", false),
+            ShareExpiry::OneMonth => ("1month", false),
+            ShareExpiry::BurnAfterReading => ("1week", true),
+        }
+    }
+}
+
+/// Result of `create_share`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Share {
+    /// Link to hand out, with the key in its fragment
+    pub url: String,
+    /// Link that deletes the share early
+    pub delete_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrivateBinResponse {
+    status: i64,
+    id: Option<String>,
+    deletetoken: Option<String>,
+    message: Option<String>,
+}
+
+pub struct Sharing {
+    path: PathBuf,
+    config: Mutex<ShareConfig>,
+}
+
+impl Sharing {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                ShareConfig::default()
+            }),
+            Err(_) => ShareConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+        }
+    }
+
+    pub fn config(&self) -> ShareConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: ShareConfig) -> Result<()> {
+        if !config.service_url.starts_with("https://") && !config.service_url.starts_with("http://")
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Share service URL must start with https:// or http://, got {}",
+                config.service_url
+            )));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize share settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock share settings: {}", e)))? =
+            config;
+        Ok(())
+    }
+
+    /// Encrypt the transcript of history entry `id` and upload it
+    pub async fn create(&self, app: &AppHandle, id: &str, expiry: ShareExpiry) -> Result<Share> {
+        let entry = app.state::<HistoryStore>().get(id)?;
+        if entry.transcript.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "This entry has no transcript to share".to_string(),
+            ));
+        }
+        let base = self.config().service_url.trim_end_matches('/').to_string();
+        privacy::ensure_allowed(app, &base)?;
+
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        let body = encrypt_paste(&entry.transcript, &key, expiry)?;
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+        let response = client
+            .post(format!("{}/", base))
+            .header("X-Requested-With", "JSONHttpRequest")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(AppError::Network(format!(
+                "Share service returned {}",
+                response.status()
+            )));
+        }
+        let reply: PrivateBinResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Network(format!("Unexpected share service reply: {}", e)))?;
+        let (0, Some(paste_id), Some(delete_token)) = (reply.status, reply.id, reply.deletetoken)
+        else {
+            return Err(AppError::Network(format!(
+                "Share service refused the transcript: {}",
+                reply.message.unwrap_or_default()
+            )));
+        };
+
+        info!("Shared history entry {}", id);
+        Ok(Share {
+            url: format!("{}/?{}#{}", base, paste_id, bs58::encode(key).into_string()),
+            delete_url: format!(
+                "{}/?pasteid={}&deletetoken={}",
+                base, paste_id, delete_token
+            ),
+        })
+    }
+}
+
+/// Build a PrivateBin v2 paste holding `text`, encrypted with `key`
+fn encrypt_paste(text: &str, key: &[u8; 32], expiry: ShareExpiry) -> Result<Value> {
+    let mut iv = [0u8; 16];
+    let mut salt = [0u8; 8];
+    OsRng.fill_bytes(&mut iv);
+    OsRng.fill_bytes(&mut salt);
+
+    let mut derived = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(key, &salt, KDF_ITERATIONS, &mut derived);
+
+    let (expire, burn_after_reading) = expiry.as_privatebin();
+    let adata = json!([
+        [
+            BASE64.encode(iv),
+            BASE64.encode(salt),
+            KDF_ITERATIONS,
+            KEY_BITS,
+            TAG_BITS,
+            "aes",
+            "gcm",
+            "none"
+        ],
+        "plaintext",
+        0,
+        u8::from(burn_after_reading)
+    ]);
+    // The authenticated data is the exact JSON the viewer will re-serialize
+    let aad = serde_json::to_string(&adata)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize share metadata: {}", e)))?;
+    let plaintext = serde_json::to_string(&json!({ "paste": text }))
+        .map_err(|e| AppError::Internal(format!("Failed to serialize share: {}", e)))?;
+
+    let cipher = Cipher::new_from_slice(&derived)
+        .map_err(|e| AppError::Internal(format!("Failed to set up share encryption: {}", e)))?;
+    let ciphertext = cipher
+        .encrypt(
+            aes_gcm::Nonce::<U16>::from_slice(&iv),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|e| AppError::Internal(format!("Failed to encrypt share: {}", e)))?;
+
+    Ok(json!({
+        "v": 2,
+        "adata": adata,
+        "ct": BASE64.encode(ciphertext),
+        "meta": { "expire": expire },
+    }))
+}
@@ -0,0 +1,381 @@
+//! Capture the next key combination pressed anywhere on the system.
+//!
+//! Shortcut strings typed by hand go wrong easily on non-QWERTY layouts: the
+//! character printed on a key isn't what the OS reports for it. Instead we
+//! listen for the physical key and describe it by its position on a US
+//! layout, which is what global-hotkey resolves accelerators against.
+//!
+//! The same listener feeds triggers global-hotkey can't express (see
+//! `hotkeys`) through [`subscribe_keys`].
+
+use crate::error::{AppError, Result};
+use crate::raw_input;
+use rdev::{EventType, Key};
+use serde::Serialize;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info};
+
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+
+/// A captured key combination
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedShortcut {
+    /// Accelerator string accepted by the global shortcut plugin, e.g. `Control+Shift+;`
+    pub accelerator: String,
+    /// Modifiers held, in canonical order
+    pub modifiers: Vec<String>,
+    /// Layout-independent key code (W3C `KeyboardEvent.code`), e.g. `Semicolon`
+    pub code: String,
+    /// Platform key code, for keys without a standard name
+    pub raw_code: Option<u32>,
+}
+
+/// A physical key, mouse button or pedal going down or up
+#[derive(Debug, Clone)]
+pub struct KeyEvent {
+    /// Layout-independent key code, e.g. `ControlRight`, or a `raw_input`
+    /// name such as `Mouse4`
+    pub code: String,
+    pub pressed: bool,
+    pub at: Instant,
+}
+
+lazy_static::lazy_static! {
+    /// Where the listener delivers the next combination, while a capture is pending
+    static ref PENDING_CAPTURE: Mutex<Option<Sender<Option<CapturedShortcut>>>> = Mutex::new(None);
+    /// Set if the global listener couldn't start (e.g. Wayland, missing permissions)
+    static ref LISTENER_ERROR: Mutex<Option<String>> = Mutex::new(None);
+    /// Receivers of every key event, outside of a pending capture
+    static ref KEY_SUBSCRIBERS: Mutex<Vec<Sender<KeyEvent>>> = Mutex::new(Vec::new());
+}
+
+static START_LISTENER: Once = Once::new();
+
+/// rdev's listener can't be stopped, so a single one is started on first
+/// use and only acts while a capture is pending or something subscribed
+pub fn ensure_listener() {
+    START_LISTENER.call_once(|| {
+        std::thread::spawn(|| {
+            info!("Starting shortcut capture listener");
+            let mut modifiers = Modifiers::default();
+            if let Err(e) =
+                rdev::listen(move |event| handle_event(&mut modifiers, event.event_type))
+            {
+                error!("Shortcut capture listener failed: {:?}", e);
+                if let Ok(mut listener_error) = LISTENER_ERROR.lock() {
+                    *listener_error = Some(format!("{:?}", e));
+                }
+                // Wake any pending capture so it reports the failure
+                if let Ok(mut pending) = PENDING_CAPTURE.lock() {
+                    pending.take();
+                }
+            }
+        });
+    });
+}
+
+#[derive(Default)]
+struct Modifiers {
+    control: bool,
+    alt: bool,
+    alt_gr: bool,
+    shift: bool,
+    meta: bool,
+}
+
+impl Modifiers {
+    /// Update modifier state, returning true if `key` is a modifier
+    fn track(&mut self, key: Key, pressed: bool) -> bool {
+        let flag = match key {
+            Key::ControlLeft | Key::ControlRight => &mut self.control,
+            Key::Alt => &mut self.alt,
+            Key::AltGr => &mut self.alt_gr,
+            Key::ShiftLeft | Key::ShiftRight => &mut self.shift,
+            Key::MetaLeft | Key::MetaRight => &mut self.meta,
+            _ => return false,
+        };
+        *flag = pressed;
+        true
+    }
+
+    fn names(&self) -> Vec<String> {
+        let meta = if cfg!(target_os = "macos") {
+            "Command"
+        } else {
+            "Super"
+        };
+        [
+            (self.control, "Control"),
+            (self.alt, "Alt"),
+            (self.alt_gr, "AltGr"),
+            (self.shift, "Shift"),
+            (self.meta, meta),
+        ]
+        .into_iter()
+        .filter(|(held, _)| *held)
+        .map(|(_, name)| name.to_string())
+        .collect()
+    }
+}
+
+fn handle_event(modifiers: &mut Modifiers, event: EventType) {
+    let (key, pressed) = match event {
+        EventType::KeyPress(key) => (key, true),
+        EventType::KeyRelease(key) => (key, false),
+        EventType::ButtonPress(button) | EventType::ButtonRelease(button) => {
+            let Some(code) = raw_input::button_code(button) else {
+                return;
+            };
+            if PENDING_CAPTURE
+                .lock()
+                .is_ok_and(|pending| pending.is_none())
+            {
+                publish(KeyEvent {
+                    code: code.to_string(),
+                    pressed: matches!(event, EventType::ButtonPress(_)),
+                    at: Instant::now(),
+                });
+            }
+            return;
+        }
+        _ => return,
+    };
+    let is_modifier = modifiers.track(key, pressed);
+
+    let Ok(mut pending) = PENDING_CAPTURE.lock() else {
+        return;
+    };
+    let Some(tx) = pending.take() else {
+        drop(pending);
+        publish(KeyEvent {
+            code: key_code(key),
+            pressed,
+            at: Instant::now(),
+        });
+        return;
+    };
+    if is_modifier || !pressed {
+        *pending = Some(tx);
+        return;
+    }
+
+    // Escape on its own cancels the capture
+    let names = modifiers.names();
+    if key == Key::Escape && names.is_empty() {
+        debug!("Shortcut capture cancelled");
+        let _ = tx.send(None);
+        return;
+    }
+
+    let (code, accelerator_key, raw_code) = match key_names(key) {
+        Some((code, accelerator_key)) => (code.to_string(), accelerator_key.to_string(), None),
+        None => {
+            let raw = match key {
+                Key::Unknown(raw) => Some(raw),
+                _ => None,
+            };
+            let name = key_code(key);
+            (name.clone(), name, raw)
+        }
+    };
+
+    let mut parts = names.clone();
+    parts.push(accelerator_key);
+    let shortcut = CapturedShortcut {
+        accelerator: parts.join("+"),
+        modifiers: names,
+        code,
+        raw_code,
+    };
+    debug!("Captured shortcut {:?}", shortcut);
+    let _ = tx.send(Some(shortcut));
+}
+
+/// Deliver an event to every subscriber; also used for inputs read outside
+/// this listener, such as foot pedals
+pub fn publish(event: KeyEvent) {
+    if let Ok(mut subscribers) = KEY_SUBSCRIBERS.lock() {
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// Receive every key press and release from now on, starting the listener
+/// if needed. Presses that complete a shortcut capture aren't delivered.
+pub fn subscribe_keys() -> Receiver<KeyEvent> {
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut subscribers) = KEY_SUBSCRIBERS.lock() {
+        subscribers.push(tx);
+    }
+    rx
+}
+
+/// Why the listener isn't running, if it failed to start
+pub fn listener_error() -> Option<String> {
+    LISTENER_ERROR.lock().ok().and_then(|e| e.clone())
+}
+
+/// W3C code for a physical key, including modifiers; keys without a
+/// standard name are described by rdev's name for them
+pub fn key_code(key: Key) -> String {
+    let code = match key {
+        Key::ControlLeft => "ControlLeft",
+        Key::ControlRight => "ControlRight",
+        Key::Alt => "AltLeft",
+        Key::AltGr => "AltRight",
+        Key::ShiftLeft => "ShiftLeft",
+        Key::ShiftRight => "ShiftRight",
+        Key::MetaLeft => "MetaLeft",
+        Key::MetaRight => "MetaRight",
+        _ => match key_names(key).map(|(code, _)| code) {
+            Some(code) => code,
+            None => match raw_input::media_key_code(key) {
+                Some(code) => code,
+                None => return format!("{:?}", key),
+            },
+        },
+    };
+    code.to_string()
+}
+
+/// W3C code and accelerator key for a physical key
+fn key_names(key: Key) -> Option<(&'static str, &'static str)> {
+    macro_rules! letters {
+        ($($variant:ident => $letter:literal),* $(,)?) => {
+            match key {
+                $(Key::$variant => return Some((stringify!($variant), $letter)),)*
+                _ => {}
+            }
+        };
+    }
+    letters!(
+        KeyA => "A", KeyB => "B", KeyC => "C", KeyD => "D", KeyE => "E", KeyF => "F",
+        KeyG => "G", KeyH => "H", KeyI => "I", KeyJ => "J", KeyK => "K", KeyL => "L",
+        KeyM => "M", KeyN => "N", KeyO => "O", KeyP => "P", KeyQ => "Q", KeyR => "R",
+        KeyS => "S", KeyT => "T", KeyU => "U", KeyV => "V", KeyW => "W", KeyX => "X",
+        KeyY => "Y", KeyZ => "Z",
+    );
+
+    Some(match key {
+        Key::Num0 => ("Digit0", "0"),
+        Key::Num1 => ("Digit1", "1"),
+        Key::Num2 => ("Digit2", "2"),
+        Key::Num3 => ("Digit3", "3"),
+        Key::Num4 => ("Digit4", "4"),
+        Key::Num5 => ("Digit5", "5"),
+        Key::Num6 => ("Digit6", "6"),
+        Key::Num7 => ("Digit7", "7"),
+        Key::Num8 => ("Digit8", "8"),
+        Key::Num9 => ("Digit9", "9"),
+        Key::F1 => ("F1", "F1"),
+        Key::F2 => ("F2", "F2"),
+        Key::F3 => ("F3", "F3"),
+        Key::F4 => ("F4", "F4"),
+        Key::F5 => ("F5", "F5"),
+        Key::F6 => ("F6", "F6"),
+        Key::F7 => ("F7", "F7"),
+        Key::F8 => ("F8", "F8"),
+        Key::F9 => ("F9", "F9"),
+        Key::F10 => ("F10", "F10"),
+        Key::F11 => ("F11", "F11"),
+        Key::F12 => ("F12", "F12"),
+        Key::BackQuote => ("Backquote", "`"),
+        Key::Minus => ("Minus", "-"),
+        Key::Equal => ("Equal", "="),
+        Key::LeftBracket => ("BracketLeft", "["),
+        Key::RightBracket => ("BracketRight", "]"),
+        Key::BackSlash => ("Backslash", "\\"),
+        Key::IntlBackslash => ("IntlBackslash", "\\"),
+        Key::SemiColon => ("Semicolon", ";"),
+        Key::Quote => ("Quote", "'"),
+        Key::Comma => ("Comma", ","),
+        Key::Dot => ("Period", "."),
+        Key::Slash => ("Slash", "/"),
+        Key::Space => ("Space", "Space"),
+        Key::Tab => ("Tab", "Tab"),
+        Key::Return => ("Enter", "Return"),
+        Key::Backspace => ("Backspace", "Backspace"),
+        Key::Delete => ("Delete", "Delete"),
+        Key::Insert => ("Insert", "Insert"),
+        Key::Escape => ("Escape", "Escape"),
+        Key::CapsLock => ("CapsLock", "Capslock"),
+        Key::NumLock => ("NumLock", "Numlock"),
+        Key::ScrollLock => ("ScrollLock", "Scrolllock"),
+        Key::PrintScreen => ("PrintScreen", "PrintScreen"),
+        Key::Home => ("Home", "Home"),
+        Key::End => ("End", "End"),
+        Key::PageUp => ("PageUp", "PageUp"),
+        Key::PageDown => ("PageDown", "PageDown"),
+        Key::UpArrow => ("ArrowUp", "Up"),
+        Key::DownArrow => ("ArrowDown", "Down"),
+        Key::LeftArrow => ("ArrowLeft", "Left"),
+        Key::RightArrow => ("ArrowRight", "Right"),
+        Key::Kp0 => ("Numpad0", "num0"),
+        Key::Kp1 => ("Numpad1", "num1"),
+        Key::Kp2 => ("Numpad2", "num2"),
+        Key::Kp3 => ("Numpad3", "num3"),
+        Key::Kp4 => ("Numpad4", "num4"),
+        Key::Kp5 => ("Numpad5", "num5"),
+        Key::Kp6 => ("Numpad6", "num6"),
+        Key::Kp7 => ("Numpad7", "num7"),
+        Key::Kp8 => ("Numpad8", "num8"),
+        Key::Kp9 => ("Numpad9", "num9"),
+        Key::KpDelete => ("NumpadDecimal", "numdec"),
+        Key::KpPlus => ("NumpadAdd", "numadd"),
+        Key::KpMinus => ("NumpadSubtract", "numsub"),
+        Key::KpMultiply => ("NumpadMultiply", "nummult"),
+        Key::KpDivide => ("NumpadDivide", "numdiv"),
+        Key::KpReturn => ("NumpadEnter", "Return"),
+        _ => return None,
+    })
+}
+
+/// Wait for the next key combination pressed anywhere on the system
+///
+/// Returns `None` if the user pressed Escape or nothing was pressed before
+/// the timeout.
+#[tauri::command]
+pub async fn capture_next_shortcut(timeout_ms: Option<u64>) -> Result<Option<CapturedShortcut>> {
+    ensure_listener();
+
+    if let Some(e) = listener_error() {
+        return Err(AppError::PermissionDenied(format!(
+            "Can't listen for key presses: {}",
+            e
+        )));
+    }
+
+    let (tx, rx) = mpsc::channel();
+    {
+        let mut pending = PENDING_CAPTURE
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock shortcut capture: {}", e)))?;
+        // A newer capture replaces an older one; the older one sees a disconnect
+        *pending = Some(tx);
+    }
+
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    let result = tokio::task::spawn_blocking(move || rx.recv_timeout(timeout))
+        .await
+        .map_err(|e| AppError::Internal(format!("Shortcut capture task failed: {}", e)))?;
+
+    match result {
+        Ok(shortcut) => Ok(shortcut),
+        Err(RecvTimeoutError::Timeout) => {
+            if let Ok(mut pending) = PENDING_CAPTURE.lock() {
+                pending.take();
+            }
+            debug!("Shortcut capture timed out");
+            Ok(None)
+        }
+        Err(RecvTimeoutError::Disconnected) => match listener_error() {
+            Some(e) => Err(AppError::PermissionDenied(format!(
+                "Can't listen for key presses: {}",
+                e
+            ))),
+            None => Ok(None),
+        },
+    }
+}
@@ -0,0 +1,13 @@
+use super::{Stats, StatsRange};
+use crate::error::Result;
+use crate::history::HistoryStore;
+use tauri::State;
+
+/// Usage statistics for the dashboard, over all history when `range` is omitted
+#[tauri::command]
+pub async fn get_stats(
+    range: Option<StatsRange>,
+    history: State<'_, HistoryStore>,
+) -> Result<Stats> {
+    Ok(super::compute(&history, &range.unwrap_or_default())?)
+}
@@ -0,0 +1,152 @@
+pub mod commands;
+
+use crate::history::{HistoryEntry, HistoryFilter, HistoryStore, Result};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Typing speed used to estimate time saved by dictating
+pub const TYPING_WORDS_PER_MINUTE: f64 = 40.0;
+
+/// How many apps `top_apps` lists
+const TOP_APPS: usize = 10;
+
+/// Time range to compute statistics over, in milliseconds since the Unix epoch
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatsRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyWords {
+    /// Local date, `YYYY-MM-DD`
+    pub date: String,
+    pub words: usize,
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageCount {
+    pub name: String,
+    pub entries: usize,
+    pub words: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub entries: usize,
+    pub words: usize,
+    pub audio_seconds: f64,
+    /// Oldest day first, only days with at least one entry
+    pub words_per_day: Vec<DailyWords>,
+    /// Words per minute of audio, over entries with a known duration
+    pub average_words_per_minute: f64,
+    /// Typing time for the same words at `TYPING_WORDS_PER_MINUTE`, minus the
+    /// time spent speaking
+    pub time_saved_seconds: f64,
+    /// Most-used target apps, most entries first
+    pub top_apps: Vec<UsageCount>,
+    /// Entries per transcription provider, most entries first
+    pub providers: Vec<UsageCount>,
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+fn local_date(millis: i64) -> String {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|time| time.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn sorted_counts(counts: HashMap<String, UsageCount>) -> Vec<UsageCount> {
+    let mut counts: Vec<UsageCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.entries.cmp(&a.entries).then_with(|| a.name.cmp(&b.name)));
+    counts
+}
+
+fn count(counts: &mut HashMap<String, UsageCount>, name: &str, words: usize) {
+    let usage = counts
+        .entry(name.to_string())
+        .or_insert_with(|| UsageCount {
+            name: name.to_string(),
+            entries: 0,
+            words: 0,
+        });
+    usage.entries += 1;
+    usage.words += words;
+}
+
+/// Compute dashboard statistics from the history store
+pub fn compute(history: &HistoryStore, range: &StatsRange) -> Result<Stats> {
+    let filter = HistoryFilter {
+        from: range.from,
+        to: range.to,
+        ..Default::default()
+    };
+    Ok(summarize(&history.list_filtered(&filter, None)?))
+}
+
+fn summarize(entries: &[HistoryEntry]) -> Stats {
+    let mut words = 0;
+    let mut audio_seconds = 0.0;
+    let mut timed_words = 0;
+    let mut per_day: BTreeMap<String, DailyWords> = BTreeMap::new();
+    let mut apps = HashMap::new();
+    let mut providers = HashMap::new();
+
+    for entry in entries {
+        let entry_words = word_count(&entry.transcript);
+        words += entry_words;
+        if entry.duration_seconds > 0.0 {
+            audio_seconds += entry.duration_seconds as f64;
+            timed_words += entry_words;
+        }
+
+        let date = local_date(entry.created_at);
+        let day = per_day.entry(date.clone()).or_insert_with(|| DailyWords {
+            date,
+            words: 0,
+            entries: 0,
+        });
+        day.words += entry_words;
+        day.entries += 1;
+
+        if let Some(app) = &entry.target_app {
+            count(&mut apps, app, entry_words);
+        }
+        count(
+            &mut providers,
+            entry.provider.as_deref().unwrap_or("unknown"),
+            entry_words,
+        );
+    }
+
+    let average_words_per_minute = if audio_seconds > 0.0 {
+        timed_words as f64 / (audio_seconds / 60.0)
+    } else {
+        0.0
+    };
+    let typing_seconds = timed_words as f64 / TYPING_WORDS_PER_MINUTE * 60.0;
+    let mut top_apps = sorted_counts(apps);
+    top_apps.truncate(TOP_APPS);
+
+    Stats {
+        entries: entries.len(),
+        words,
+        audio_seconds,
+        words_per_day: per_day.into_values().collect(),
+        average_words_per_minute,
+        time_saved_seconds: (typing_seconds - audio_seconds).max(0.0),
+        top_apps,
+        providers: sorted_counts(providers),
+    }
+}
@@ -0,0 +1,26 @@
+use super::{DeviceSync, SyncConfig, SyncSummary};
+use crate::error::{AppError, Result};
+use tauri::{AppHandle, Manager, State};
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_sync_config(sync: State<'_, DeviceSync>) -> Result<SyncConfig> {
+    Ok(sync.config())
+}
+
+#[tauri::command]
+pub async fn set_sync_config(config: SyncConfig, sync: State<'_, DeviceSync>) -> Result<()> {
+    debug!(
+        "Updating sync: enabled={}, folder={:?}",
+        config.enabled, config.folder
+    );
+    sync.set_config(config)
+}
+
+/// Merge the other devices' changes and publish this one's right away
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncSummary> {
+    tokio::task::spawn_blocking(move || app.state::<DeviceSync>().sync_now(&app))
+        .await
+        .map_err(|e| AppError::Internal(format!("Sync task failed: {}", e)))?
+}
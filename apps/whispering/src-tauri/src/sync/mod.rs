@@ -0,0 +1,498 @@
+//! Keeping history and settings the same across a user's desktops.
+//!
+//! Each device writes everything it has to `<device id>.json` in a folder the
+//! user already syncs (Dropbox, Syncthing, iCloud Drive, ...), and reads the
+//! files the other devices left there. Entries are merged last-write-wins on
+//! their `updated_at`, deletions included (see `history::sync`), and settings
+//! as a whole. When both sides changed the same thing since the last merge
+//! the newer one still wins, and `sync://conflict` says what was overwritten.
+//!
+//! Each device only ever writes its own file, so the sync tool never has to
+//! merge anything itself. The backups destination isn't used as a transport:
+//! this machine only keeps the public half of its key (see `backup`), so it
+//! couldn't read another device's data from there.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, SyncConflicted, Synced};
+use crate::history::{HistoryStore, LocalCopy, SyncedEntry, Tombstone};
+use crate::settings::{NativeSettings, SettingsStore};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+pub const SYNC_FILE: &str = "sync.json";
+
+/// How often the sync folder is checked
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncConfig {
+    pub enabled: bool,
+    /// Folder shared between the devices
+    pub folder: Option<String>,
+    /// Shown to the other devices, e.g. in conflict notices
+    pub device_name: String,
+}
+
+/// What a device writes to the sync folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceFile {
+    device_id: String,
+    device_name: String,
+    /// Milliseconds since the Unix epoch
+    exported_at: i64,
+    entries: Vec<SyncedEntry>,
+    tombstones: Vec<Tombstone>,
+    settings: Option<SyncedSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedSettings {
+    settings: NativeSettings,
+    updated_at: i64,
+}
+
+/// Which copy a conflict was settled in favour of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictWinner {
+    Local,
+    Remote,
+}
+
+/// Payload of `sync://conflict`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    /// The entry both devices changed; `None` for settings
+    pub entry_id: Option<String>,
+    /// The other device's name
+    pub device: String,
+    pub kept: ConflictWinner,
+}
+
+/// Result of a sync
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+    pub devices: usize,
+    /// Entries added or updated from other devices
+    pub updated: usize,
+    pub deleted: usize,
+    pub settings_updated: bool,
+    pub conflicts: usize,
+}
+
+/// What's known about another device
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Peer {
+    name: String,
+    /// `exported_at` of the file last merged from it
+    exported_at: i64,
+    /// When it was last merged, on this device's clock
+    merged_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredSync {
+    config: SyncConfig,
+    device_id: String,
+    /// Settings as last written to or taken from the sync folder, to notice
+    /// local changes
+    settings: Option<NativeSettings>,
+    settings_updated_at: i64,
+    peers: HashMap<String, Peer>,
+    /// Digest of what was last written, to skip rewriting an unchanged file
+    export_digest: String,
+}
+
+pub struct DeviceSync {
+    path: PathBuf,
+    state: Mutex<StoredSync>,
+    running: AtomicBool,
+}
+
+impl DeviceSync {
+    pub fn open(path: &Path) -> Self {
+        let mut state: StoredSync = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                StoredSync::default()
+            }),
+            Err(_) => StoredSync::default(),
+        };
+        if state.device_id.is_empty() {
+            state.device_id = uuid::Uuid::new_v4().to_string();
+        }
+        Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(state),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    pub fn config(&self) -> SyncConfig {
+        self.state
+            .lock()
+            .map(|state| state.config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: SyncConfig) -> Result<()> {
+        if config.enabled {
+            match &config.folder {
+                Some(folder) if Path::new(folder).is_dir() => {}
+                Some(folder) => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Sync folder is not a directory: {}",
+                        folder
+                    )))
+                }
+                None => {
+                    return Err(AppError::InvalidInput(
+                        "Choose a sync folder first".to_string(),
+                    ))
+                }
+            }
+        }
+        self.modify(|state| {
+            // Another folder may hold other devices' files from any time
+            if config.folder != state.config.folder {
+                state.peers.clear();
+            }
+            state.config = config;
+        })
+    }
+
+    /// Merge the other devices' files, then write this device's
+    pub fn sync_now(&self, app: &AppHandle) -> Result<SyncSummary> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(AppError::InvalidInput(
+                "A sync is already running".to_string(),
+            ));
+        }
+        let result = self.run(app);
+        self.running.store(false, Ordering::SeqCst);
+        result
+    }
+
+    fn run(&self, app: &AppHandle) -> Result<SyncSummary> {
+        let (config, device_id) = {
+            let state = self
+                .state
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock sync state: {}", e)))?;
+            (state.config.clone(), state.device_id.clone())
+        };
+        let folder = PathBuf::from(
+            config
+                .folder
+                .ok_or_else(|| AppError::InvalidInput("Choose a sync folder first".to_string()))?,
+        );
+        let history = app.state::<HistoryStore>();
+        let settings = app.state::<SettingsStore>();
+        let now = Local::now().timestamp_millis();
+
+        // Notice settings changed on this device since the last sync
+        let current = settings.get();
+        self.modify(|state| {
+            if state.settings.as_ref() != Some(&current) {
+                // A device joining for the first time takes the others'
+                // settings rather than pushing its defaults onto them
+                state.settings_updated_at = if state.settings.is_some() { now } else { 0 };
+                state.settings = Some(current.clone());
+            }
+        })?;
+
+        let mut summary = SyncSummary::default();
+        for file in read_device_files(&folder, &device_id)? {
+            summary.devices += 1;
+            let peer = self
+                .state
+                .lock()
+                .ok()
+                .and_then(|state| state.peers.get(&file.device_id).cloned())
+                .unwrap_or_default();
+            if file.exported_at <= peer.exported_at {
+                continue;
+            }
+            debug!("Merging sync file from {}", file.device_name);
+            self.merge(
+                app,
+                &history,
+                &settings,
+                &file,
+                peer.merged_at,
+                &mut summary,
+            )?;
+            self.modify(|state| {
+                state.peers.insert(
+                    file.device_id.clone(),
+                    Peer {
+                        name: file.device_name.clone(),
+                        exported_at: file.exported_at,
+                        merged_at: now,
+                    },
+                );
+            })?;
+        }
+
+        self.export(&folder, &history)?;
+        if summary.updated > 0 || summary.deleted > 0 || summary.settings_updated {
+            info!(
+                "Synced from {} devices: {} updated, {} deleted, {} conflicts",
+                summary.devices, summary.updated, summary.deleted, summary.conflicts
+            );
+            let _ = Synced::emit(app, &summary);
+        }
+        Ok(summary)
+    }
+
+    /// Apply whatever in `file` is newer than what's here
+    fn merge(
+        &self,
+        app: &AppHandle,
+        history: &HistoryStore,
+        settings: &SettingsStore,
+        file: &DeviceFile,
+        last_merged: i64,
+        summary: &mut SyncSummary,
+    ) -> Result<()> {
+        let conflict = |entry_id: Option<&str>, kept: ConflictWinner| {
+            let _ = SyncConflicted::emit(
+                app,
+                &SyncConflict {
+                    entry_id: entry_id.map(str::to_string),
+                    device: file.device_name.clone(),
+                    kept,
+                },
+            );
+        };
+
+        for remote in &file.entries {
+            let id = remote.entry.id.as_str();
+            let local_changed_at = match history.local_copy(id)? {
+                LocalCopy::Missing => None,
+                LocalCopy::Present(local) => {
+                    if serde_json::to_value(&local.entry).ok()
+                        == serde_json::to_value(&remote.entry).ok()
+                    {
+                        continue;
+                    }
+                    Some(local.updated_at)
+                }
+                LocalCopy::Deleted(deleted_at) => Some(deleted_at),
+            };
+            let remote_wins = local_changed_at.is_none_or(|local| remote.updated_at > local);
+            if let Some(local) = local_changed_at {
+                if local > last_merged && remote.updated_at > last_merged {
+                    summary.conflicts += 1;
+                    conflict(
+                        Some(id),
+                        if remote_wins {
+                            ConflictWinner::Remote
+                        } else {
+                            ConflictWinner::Local
+                        },
+                    );
+                }
+            }
+            if remote_wins {
+                history.apply_synced_entry(remote)?;
+                summary.updated += 1;
+            }
+        }
+
+        for tombstone in &file.tombstones {
+            match history.local_copy(&tombstone.id)? {
+                LocalCopy::Present(local) => {
+                    if local.updated_at > last_merged && tombstone.deleted_at > last_merged {
+                        summary.conflicts += 1;
+                        conflict(
+                            Some(&tombstone.id),
+                            if tombstone.deleted_at > local.updated_at {
+                                ConflictWinner::Remote
+                            } else {
+                                ConflictWinner::Local
+                            },
+                        );
+                    }
+                    if tombstone.deleted_at > local.updated_at {
+                        history.apply_tombstone(tombstone)?;
+                        summary.deleted += 1;
+                    }
+                }
+                LocalCopy::Deleted(_) => {}
+                // Nothing to delete, but remember it so older copies elsewhere
+                // don't bring it back
+                LocalCopy::Missing => history.apply_tombstone(tombstone)?,
+            }
+        }
+
+        if let Some(remote) = &file.settings {
+            let (local, local_updated_at) = self
+                .state
+                .lock()
+                .map(|state| (state.settings.clone(), state.settings_updated_at))
+                .map_err(|e| AppError::Internal(format!("Failed to lock sync state: {}", e)))?;
+            if local.as_ref() != Some(&remote.settings) {
+                let remote_wins = remote.updated_at > local_updated_at;
+                if local_updated_at > last_merged && remote.updated_at > last_merged {
+                    summary.conflicts += 1;
+                    conflict(
+                        None,
+                        if remote_wins {
+                            ConflictWinner::Remote
+                        } else {
+                            ConflictWinner::Local
+                        },
+                    );
+                }
+                if remote_wins {
+                    // Policy-managed keys keep their forced values here
+                    let applied = settings.set(app, remote.settings.clone())?;
+                    self.modify(|state| {
+                        state.settings = Some(applied);
+                        state.settings_updated_at = remote.updated_at;
+                    })?;
+                    summary.settings_updated = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write this device's file if anything changed, replacing it in one
+    /// step so the sync tool never picks up half of it
+    fn export(&self, folder: &Path, history: &HistoryStore) -> Result<()> {
+        let (entries, tombstones) = history.sync_snapshot()?;
+        let (mut file, previous_digest) = {
+            let state = self
+                .state
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock sync state: {}", e)))?;
+            let file = DeviceFile {
+                device_id: state.device_id.clone(),
+                device_name: device_name(&state.config),
+                exported_at: 0,
+                entries,
+                tombstones,
+                settings: state.settings.clone().map(|settings| SyncedSettings {
+                    settings,
+                    updated_at: state.settings_updated_at,
+                }),
+            };
+            (file, state.export_digest.clone())
+        };
+        let serialize = |file: &DeviceFile| {
+            serde_json::to_vec(file)
+                .map_err(|e| AppError::Internal(format!("Failed to serialize sync file: {}", e)))
+        };
+        let digest = Sha256::digest(serialize(&file)?)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        let path = folder.join(format!("{}.json", file.device_id));
+        if digest == previous_digest && path.exists() {
+            return Ok(());
+        }
+
+        file.exported_at = Local::now().timestamp_millis();
+        let partial = folder.join(format!(".{}.json.partial", file.device_id));
+        std::fs::write(&partial, serialize(&file)?)
+            .map_err(|e| AppError::Internal(format!("Failed to write sync file: {}", e)))?;
+        std::fs::rename(&partial, &path)
+            .map_err(|e| AppError::Internal(format!("Failed to write sync file: {}", e)))?;
+        self.modify(|state| state.export_digest = digest)
+    }
+
+    /// Sync once a minute while enabled
+    pub fn start(&self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(SYNC_INTERVAL).await;
+                if !app.state::<DeviceSync>().config().enabled {
+                    continue;
+                }
+                let handle = app.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    handle.state::<DeviceSync>().sync_now(&handle)
+                })
+                .await;
+                match result {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => error!("Sync failed: {}", e),
+                    Err(e) => error!("Sync task failed: {}", e),
+                }
+            }
+        });
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut StoredSync)) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock sync state: {}", e)))?;
+        f(&mut state);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*state)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize sync state: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+fn device_name(config: &SyncConfig) -> String {
+    if !config.device_name.trim().is_empty() {
+        return config.device_name.trim().to_string();
+    }
+    sysinfo::System::host_name().unwrap_or_else(|| "Another device".to_string())
+}
+
+/// The other devices' files in `folder`; unreadable ones are skipped, since
+/// the sync tool may be halfway through updating them
+fn read_device_files(folder: &Path, own_id: &str) -> Result<Vec<DeviceFile>> {
+    let entries = std::fs::read_dir(folder).map_err(|e| {
+        AppError::Internal(format!(
+            "Failed to read sync folder {}: {}",
+            folder.display(),
+            e
+        ))
+    })?;
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json")
+            || path.file_stem().and_then(|stem| stem.to_str()) == Some(own_id)
+        {
+            continue;
+        }
+        let parsed = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                serde_json::from_slice::<DeviceFile>(&contents).map_err(|e| e.to_string())
+            });
+        match parsed {
+            Ok(file) if file.device_id != own_id => files.push(file),
+            Ok(_) => {}
+            Err(e) => warn!("Skipping sync file {:?}: {}", path, e),
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,24 @@
+use super::{Feature, PendingTelemetry, Telemetry};
+use crate::error::Result;
+use tauri::{AppHandle, State};
+
+/// Exactly what the next telemetry submission would contain
+#[tauri::command]
+pub async fn get_pending_telemetry(
+    telemetry: State<'_, Telemetry>,
+    app_handle: AppHandle,
+) -> Result<PendingTelemetry> {
+    Ok(telemetry.pending(&app_handle))
+}
+
+#[tauri::command]
+pub async fn set_telemetry_enabled(enabled: bool, telemetry: State<'_, Telemetry>) -> Result<()> {
+    telemetry.set_enabled(enabled)
+}
+
+/// Count a feature used from the frontend
+#[tauri::command]
+pub async fn record_feature_usage(feature: Feature, telemetry: State<'_, Telemetry>) -> Result<()> {
+    telemetry.record(feature);
+    Ok(())
+}
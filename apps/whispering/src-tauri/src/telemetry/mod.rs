@@ -0,0 +1,219 @@
+//! Opt-in usage telemetry, aggregated on this machine.
+//!
+//! Features report that they were used and only a count is kept. Once a day
+//! the counters are sent as a single event and reset. Nothing about what was
+//! dictated is ever recorded: the only data is the feature name, how often
+//! it was used, and the app version and OS. `get_pending_telemetry` returns
+//! exactly the event that would be sent next.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_aptabase::EventTracker;
+use tracing::{debug, error, warn};
+
+pub const TELEMETRY_FILE: &str = "telemetry.json";
+
+/// Name of the event the counters are submitted as
+const EVENT_NAME: &str = "feature_usage";
+
+/// How often counters are submitted
+const SUBMIT_INTERVAL_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// How often the submitter checks whether a submission is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Features whose usage is counted
+///
+/// A closed set, so free-form strings (and anything typed by the user)
+/// can't end up in telemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Feature {
+    Recording,
+    LocalTranscription,
+    CloudTranscription,
+    Retranscription,
+    TranscriptEdit,
+    Transformation,
+    WatchedFolder,
+    Inbox,
+    Playback,
+    AudioEditing,
+    BulkDelete,
+    BulkExport,
+    BulkReprocess,
+    Recap,
+    Tags,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredTelemetry {
+    enabled: bool,
+    counters: BTreeMap<Feature, u64>,
+    /// Start of the current aggregation period, milliseconds since the Unix epoch
+    since: Option<i64>,
+}
+
+/// The event that will be submitted at the end of the current period
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTelemetry {
+    pub enabled: bool,
+    /// Milliseconds since the Unix epoch
+    pub since: Option<i64>,
+    /// When the counters will be submitted, if telemetry is on
+    pub submit_after: Option<i64>,
+    pub event: String,
+    pub app_version: String,
+    pub os: String,
+    pub counters: BTreeMap<Feature, u64>,
+}
+
+pub struct Telemetry {
+    path: PathBuf,
+    state: Mutex<StoredTelemetry>,
+}
+
+impl Telemetry {
+    pub fn open(path: &Path) -> Self {
+        let state = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                StoredTelemetry::default()
+            }),
+            Err(_) => StoredTelemetry::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().is_ok_and(|state| state.enabled)
+    }
+
+    /// Turn telemetry on or off; turning it off discards anything unsent
+    pub fn set_enabled(&self, enabled: bool) -> Result<()> {
+        self.modify(|state| {
+            if enabled && !state.enabled {
+                state.since = Some(Local::now().timestamp_millis());
+            }
+            if !enabled {
+                state.counters.clear();
+                state.since = None;
+            }
+            state.enabled = enabled;
+        })
+    }
+
+    /// Count one use of `feature`; does nothing unless telemetry is on
+    pub fn record(&self, feature: Feature) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Err(e) = self.modify(|state| *state.counters.entry(feature).or_default() += 1) {
+            warn!("Failed to record {:?} usage: {}", feature, e);
+        }
+    }
+
+    pub fn pending(&self, app: &AppHandle) -> PendingTelemetry {
+        let state = self
+            .state
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default();
+        PendingTelemetry {
+            enabled: state.enabled,
+            since: state.since,
+            submit_after: state
+                .since
+                .filter(|_| state.enabled)
+                .map(|since| since + SUBMIT_INTERVAL_MS),
+            event: EVENT_NAME.to_string(),
+            app_version: app.package_info().version.to_string(),
+            os: std::env::consts::OS.to_string(),
+            counters: state.counters,
+        }
+    }
+
+    /// Submit the counters once a day through the analytics plugin
+    ///
+    /// `submit` is false in builds without an analytics key; usage is then
+    /// still aggregated (and inspectable) but never leaves the machine.
+    pub fn start(&self, app: AppHandle, submit: bool) {
+        if !submit {
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                let telemetry = app.state::<Telemetry>();
+                if let Err(e) = telemetry.submit_if_due(&app) {
+                    error!("Failed to submit telemetry: {}", e);
+                }
+            }
+        });
+    }
+
+    fn submit_if_due(&self, app: &AppHandle) -> Result<()> {
+        let pending = self.pending(app);
+        let now = Local::now().timestamp_millis();
+        if !pending.submit_after.is_some_and(|after| now >= after) {
+            return Ok(());
+        }
+        if crate::privacy::is_enabled(app) {
+            debug!("Privacy mode is on, not submitting telemetry");
+            return Ok(());
+        }
+        if !pending.counters.is_empty() {
+            let props = serde_json::json!({
+                "appVersion": pending.app_version,
+                "os": pending.os,
+                "counters": pending.counters,
+            });
+            app.track_event(EVENT_NAME, Some(props)).map_err(|e| {
+                AppError::Internal(format!("Failed to track telemetry event: {}", e))
+            })?;
+            debug!(
+                "Submitted telemetry for {} features",
+                pending.counters.len()
+            );
+        }
+        self.modify(|state| {
+            state.counters.clear();
+            state.since = Some(now);
+        })
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut StoredTelemetry)) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock telemetry: {}", e)))?;
+        f(&mut state);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*state)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize telemetry: {}", e)))?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+/// Count one use of `feature`, for call sites that only have an `AppHandle`
+pub fn record(app: &AppHandle, feature: Feature) {
+    if let Some(telemetry) = app.try_state::<Telemetry>() {
+        telemetry.record(feature);
+    }
+}
@@ -0,0 +1,179 @@
+//! Showing stored times to people.
+//!
+//! History and everything else keep times as UTC milliseconds. Exports,
+//! report filenames and templates show them in the `timeZone` setting (the
+//! system's when empty, `UTC`, or an IANA name like `Europe/Berlin`) and in
+//! the `timestampStyle`: ISO 8601, which reads the same everywhere, or the
+//! numeric date order of the system locale (`05/01/2024` in the US,
+//! `01.05.2024` in Germany).
+//!
+//! Filenames ignore the style and always use year-month-day, so they sort
+//! by date and nobody mistakes the month for the day.
+
+use crate::error::{AppError, Result};
+use crate::settings::{NativeSettings, SettingsStore};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::warn;
+
+const ISO_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const FILENAME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// How dates and times are written for people to read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimestampStyle {
+    /// `2024-05-01 14:30:05`
+    #[default]
+    Iso,
+    /// The system locale's date order and clock
+    Locale,
+}
+
+/// The time zone times are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    /// Parse a `timeZone` setting: empty for the system's zone, otherwise
+    /// `UTC` or an IANA name
+    pub fn parse(time_zone: &str) -> Result<Self> {
+        match time_zone.trim() {
+            "" => Ok(Zone::Local),
+            name if name.eq_ignore_ascii_case("utc") => Ok(Zone::Named(Tz::UTC)),
+            name => name.parse::<Tz>().map(Zone::Named).map_err(|_| {
+                AppError::InvalidInput(format!(
+                    "Unknown time zone {:?}; use an IANA name like Europe/Berlin",
+                    name
+                ))
+            }),
+        }
+    }
+
+    fn at(self, millis: i64) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Zone::Local => Local
+                .timestamp_millis_opt(millis)
+                .single()
+                .map(|time| time.fixed_offset()),
+            Zone::Named(tz) => tz
+                .timestamp_millis_opt(millis)
+                .single()
+                .map(|time| time.fixed_offset()),
+        }
+    }
+}
+
+/// Formats UTC milliseconds in the configured zone and style
+#[derive(Debug, Clone)]
+pub struct Timestamps {
+    zone: Zone,
+    style: TimestampStyle,
+    /// BCP 47 tag the locale style follows, e.g. `en-US`
+    locale: String,
+}
+
+impl Timestamps {
+    pub fn new(zone: Zone, style: TimestampStyle, locale: &str) -> Self {
+        Self {
+            zone,
+            style,
+            locale: locale.to_string(),
+        }
+    }
+
+    /// The zone and style in `settings`, with the system locale. An unknown
+    /// zone falls back to the system's.
+    pub fn from_settings(settings: &NativeSettings) -> Self {
+        let zone = Zone::parse(&settings.time_zone).unwrap_or_else(|e| {
+            warn!("{}", e);
+            Zone::Local
+        });
+        let locale = tauri_plugin_os::locale().unwrap_or_default();
+        Self::new(zone, settings.timestamp_style, &locale)
+    }
+
+    /// The current settings' formatting, or ISO in the system's zone
+    /// before settings have loaded
+    pub fn current<R: Runtime>(app: &AppHandle<R>) -> Self {
+        match app.try_state::<SettingsStore>() {
+            Some(settings) => Self::from_settings(&settings.get()),
+            None => Self::new(Zone::Local, TimestampStyle::Iso, ""),
+        }
+    }
+
+    /// `millis` in the configured zone
+    pub fn at(&self, millis: i64) -> Option<DateTime<FixedOffset>> {
+        self.zone.at(millis)
+    }
+
+    /// `millis` written in the configured style
+    pub fn display(&self, millis: i64) -> String {
+        let pattern = match self.style {
+            TimestampStyle::Iso => ISO_FORMAT,
+            TimestampStyle::Locale => locale_format(&self.locale),
+        };
+        self.at(millis)
+            .map(|time| time.format(pattern).to_string())
+            .unwrap_or_default()
+    }
+
+    /// `millis` as RFC 3339 with the zone's offset, e.g.
+    /// `2024-05-01T14:30:05+02:00`, for files other programs read
+    pub fn rfc3339(&self, millis: i64) -> String {
+        self.at(millis)
+            .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .unwrap_or_default()
+    }
+
+    /// `millis` for use in a filename, e.g. `2024-05-01_14-30-05`
+    pub fn filename(&self, millis: i64) -> String {
+        self.at(millis)
+            .map(|time| time.format(FILENAME_FORMAT).to_string())
+            .unwrap_or_default()
+    }
+
+    /// `millis` formatted with a strftime `pattern`, e.g. from a template
+    pub fn format(&self, millis: i64, pattern: &str) -> Result<String> {
+        let items: Vec<Item> = StrftimeItems::new(pattern).collect();
+        if items.iter().any(|item| matches!(item, Item::Error)) {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid date pattern: {}",
+                pattern
+            )));
+        }
+        let time = self
+            .at(millis)
+            .ok_or_else(|| AppError::InvalidInput(format!("Invalid timestamp: {}", millis)))?;
+        Ok(time.format_with_items(items.into_iter()).to_string())
+    }
+}
+
+/// Numeric date and time in the order `locale` uses
+fn locale_format(locale: &str) -> &'static str {
+    let mut parts = locale.split(['-', '_', '.']);
+    let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let region = parts
+        .find(|part| part.len() == 2)
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    match (language.as_str(), region.as_str()) {
+        // Month first, with a 12-hour clock
+        (_, "US" | "PH" | "FM" | "MH") | ("en", "") => "%m/%d/%Y %I:%M:%S %p",
+        // Year first
+        ("ja" | "zh" | "ko" | "hu" | "lt" | "sv" | "mn", _) | (_, "CA") => ISO_FORMAT,
+        // Day first with dots
+        ("de" | "ru" | "pl" | "cs" | "sk" | "fi" | "nb" | "no" | "da" | "tr" | "uk" | "ro", _) => {
+            "%d.%m.%Y %H:%M:%S"
+        }
+        ("", _) => ISO_FORMAT,
+        // Day first, as most of the world writes it
+        _ => "%d/%m/%Y %H:%M:%S",
+    }
+}
@@ -0,0 +1,234 @@
+//! Run one clip through several providers to help pick a default.
+//!
+//! The frontend owns provider settings and API keys, so it passes the
+//! targets to compare: local models, and anything speaking the OpenAI
+//! transcription API (OpenAI, Groq, self-hosted servers). Targets run one
+//! after another so local models don't compete for the CPU and skew each
+//! other's latency.
+
+use super::compare::{compare, TranscriptComparison};
+use super::remote::{transcribe_remote, RemoteServer};
+use super::{decode_audio, transcribe_local, LocalEngine, ModelManager, DECODED_SAMPLE_RATE};
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Something to transcribe the clip with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BenchmarkTarget {
+    #[serde(rename_all = "camelCase")]
+    Local {
+        engine: LocalEngine,
+        model_path: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Remote {
+        server: RemoteServer,
+        model: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+}
+
+impl BenchmarkTarget {
+    fn name(&self) -> String {
+        match self {
+            BenchmarkTarget::Local {
+                engine, model_path, ..
+            } => {
+                let model = Path::new(model_path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| model_path.clone());
+                format!("{} ({})", engine.as_str(), model)
+            }
+            BenchmarkTarget::Remote { server, model, .. } => {
+                format!("{} ({})", host(&server.base_url), model)
+            }
+        }
+    }
+
+    /// Estimated price in US dollars, if it's a known paid service
+    fn cost_usd(&self, audio_seconds: f32) -> Option<f64> {
+        match self {
+            BenchmarkTarget::Local { .. } => Some(0.0),
+            BenchmarkTarget::Remote { server, model, .. } => {
+                let per_minute = price_per_minute(&host(&server.base_url), model)?;
+                Some(per_minute * audio_seconds as f64 / 60.0)
+            }
+        }
+    }
+}
+
+/// List prices per minute of audio; estimates only, providers bill by their
+/// own rounding rules
+fn price_per_minute(host: &str, model: &str) -> Option<f64> {
+    match (host, model) {
+        ("api.openai.com", "whisper-1" | "gpt-4o-transcribe") => Some(0.006),
+        ("api.openai.com", "gpt-4o-mini-transcribe") => Some(0.003),
+        ("api.groq.com", "whisper-large-v3") => Some(0.111 / 60.0),
+        ("api.groq.com", "whisper-large-v3-turbo") => Some(0.04 / 60.0),
+        ("api.groq.com", "distil-whisper-large-v3-en") => Some(0.02 / 60.0),
+        _ => None,
+    }
+}
+
+fn host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// How one target did
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub target: BenchmarkTarget,
+    pub latency_ms: u64,
+    /// Processing time divided by audio length; below 1 is faster than real time
+    pub realtime_factor: f32,
+    /// Estimated price in US dollars; absent for services without known pricing
+    pub cost_usd: Option<f64>,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    /// Word error rate and alignment against the baseline, if this run
+    /// produced text
+    pub comparison: Option<TranscriptComparison>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub audio_seconds: f32,
+    /// What results are compared with: the reference transcript if one was
+    /// given, otherwise the first successful result
+    pub baseline: Option<String>,
+    pub results: Vec<BenchmarkResult>,
+}
+
+async fn run_target(
+    app: &AppHandle,
+    target: &BenchmarkTarget,
+    audio_data: Vec<u8>,
+    file_name: &str,
+) -> Result<String> {
+    match target.clone() {
+        BenchmarkTarget::Local {
+            engine,
+            model_path,
+            language,
+        } => {
+            let app = app.clone();
+            tokio::task::spawn_blocking(move || {
+                let model_manager = app.state::<ModelManager>();
+                transcribe_local(&model_manager, engine, audio_data, &model_path, language)
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("Benchmark run panicked: {}", e)))?
+            .map(|transcript| transcript.text)
+            .map_err(AppError::from)
+        }
+        BenchmarkTarget::Remote {
+            server,
+            model,
+            language,
+        } => transcribe_remote(app, &server, audio_data, file_name, &model, language).await,
+    }
+}
+
+/// Transcribe the clip at `audio_path` with every target and compare them
+pub async fn benchmark(
+    app: &AppHandle,
+    audio_path: &Path,
+    targets: &[BenchmarkTarget],
+    reference: Option<String>,
+) -> Result<BenchmarkReport> {
+    if targets.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Choose at least one provider to benchmark".to_string(),
+        ));
+    }
+    let audio_data = std::fs::read(audio_path)?;
+    let file_name = audio_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio.wav".to_string());
+    let audio_seconds = {
+        let audio_data = audio_data.clone();
+        tokio::task::spawn_blocking(move || decode_audio(audio_data))
+            .await
+            .map_err(|e| AppError::Internal(format!("Decoding audio panicked: {}", e)))??
+            .len() as f32
+            / DECODED_SAMPLE_RATE as f32
+    };
+    info!(
+        "Benchmarking {} providers on {:?} ({:.1}s)",
+        targets.len(),
+        audio_path,
+        audio_seconds
+    );
+
+    let mut results = Vec::new();
+    for target in targets {
+        let started = Instant::now();
+        let outcome = run_target(app, target, audio_data.clone(), &file_name).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        if let Err(e) = &outcome {
+            warn!("Benchmark run with {} failed: {}", target.name(), e);
+        }
+        let (text, error) = match outcome {
+            Ok(text) => (Some(text), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        results.push(BenchmarkResult {
+            name: target.name(),
+            target: target.clone(),
+            latency_ms,
+            realtime_factor: if audio_seconds > 0.0 {
+                latency_ms as f32 / 1000.0 / audio_seconds
+            } else {
+                0.0
+            },
+            cost_usd: target.cost_usd(audio_seconds),
+            text,
+            error,
+            comparison: None,
+        });
+    }
+
+    let baseline = reference
+        .filter(|reference| !reference.trim().is_empty())
+        .or_else(|| results.iter().find_map(|result| result.text.clone()));
+    if let Some(baseline) = &baseline {
+        for result in &mut results {
+            result.comparison = result.text.as_deref().map(|text| compare(text, baseline));
+        }
+    }
+
+    Ok(BenchmarkReport {
+        audio_seconds,
+        baseline,
+        results,
+    })
+}
+
+/// Transcribe a sample clip with each target and report latency, estimated
+/// cost and word error rate. Pass `reference` (the known correct text) to
+/// measure against it; otherwise results are compared with the first one.
+#[tauri::command]
+pub async fn benchmark_providers(
+    audio_path: String,
+    targets: Vec<BenchmarkTarget>,
+    reference: Option<String>,
+    app_handle: AppHandle,
+) -> Result<BenchmarkReport> {
+    benchmark(&app_handle, Path::new(&audio_path), &targets, reference).await
+}
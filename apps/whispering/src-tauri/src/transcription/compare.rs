@@ -0,0 +1,150 @@
+//! Word error rate between a transcript and a known correct one.
+//!
+//! Words are compared case-insensitively with surrounding punctuation
+//! removed, so "Hello," and "hello" match; the alignment keeps the words as
+//! written for display.
+
+use crate::error::Result;
+use serde::Serialize;
+
+/// One step of the alignment from the reference to the hypothesis
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum AlignedWord {
+    Correct {
+        word: String,
+    },
+    /// The hypothesis has `hypothesis` where the reference has `reference`
+    Substitution {
+        reference: String,
+        hypothesis: String,
+    },
+    /// Only in the hypothesis
+    Insertion {
+        word: String,
+    },
+    /// Missing from the hypothesis
+    Deletion {
+        word: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptComparison {
+    /// (substitutions + deletions + insertions) / reference words; can
+    /// exceed 1 when the hypothesis has many extra words
+    pub wer: f32,
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    pub reference_words: usize,
+    pub hypothesis_words: usize,
+    /// Reference order, showing how each word was (mis)recognized
+    pub alignment: Vec<AlignedWord>,
+}
+
+/// Lowercase with leading and trailing punctuation removed
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Words to compare: the original spelling and its normalized form. Words
+/// that are only punctuation (e.g. a lone "-") don't count.
+fn words(text: &str) -> Vec<(&str, String)> {
+    text.split_whitespace()
+        .map(|word| (word, normalize(word)))
+        .filter(|(_, normalized)| !normalized.is_empty())
+        .collect()
+}
+
+/// Align `hypothesis` to `reference` with the fewest edits and count them
+pub fn compare(hypothesis: &str, reference: &str) -> TranscriptComparison {
+    let reference = words(reference);
+    let hypothesis = words(hypothesis);
+    let (n, m) = (reference.len(), hypothesis.len());
+
+    // cost[i][j] is the edit distance between reference[..i] and hypothesis[..j]
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution =
+                cost[i - 1][j - 1] + usize::from(reference[i - 1].1 != hypothesis[j - 1].1);
+            cost[i][j] = substitution.min(cost[i - 1][j] + 1).min(cost[i][j - 1] + 1);
+        }
+    }
+
+    // Walk back from the end, preferring matches and substitutions so errors
+    // line up with the words they replaced
+    let mut alignment = Vec::new();
+    let (mut substitutions, mut deletions, mut insertions) = (0, 0, 0);
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let same = reference[i - 1].1 == hypothesis[j - 1].1;
+            if cost[i][j] == cost[i - 1][j - 1] + usize::from(!same) {
+                alignment.push(if same {
+                    AlignedWord::Correct {
+                        word: hypothesis[j - 1].0.to_string(),
+                    }
+                } else {
+                    substitutions += 1;
+                    AlignedWord::Substitution {
+                        reference: reference[i - 1].0.to_string(),
+                        hypothesis: hypothesis[j - 1].0.to_string(),
+                    }
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && cost[i][j] == cost[i - 1][j] + 1 {
+            deletions += 1;
+            alignment.push(AlignedWord::Deletion {
+                word: reference[i - 1].0.to_string(),
+            });
+            i -= 1;
+        } else {
+            insertions += 1;
+            alignment.push(AlignedWord::Insertion {
+                word: hypothesis[j - 1].0.to_string(),
+            });
+            j -= 1;
+        }
+    }
+    alignment.reverse();
+
+    let errors = substitutions + deletions + insertions;
+    let wer = match n {
+        0 if errors == 0 => 0.0,
+        0 => 1.0,
+        _ => errors as f32 / n as f32,
+    };
+    TranscriptComparison {
+        wer,
+        substitutions,
+        deletions,
+        insertions,
+        reference_words: n,
+        hypothesis_words: m,
+        alignment,
+    }
+}
+
+/// Word error rate of `hypothesis` against the correct `reference`, with an
+/// alignment showing each substitution, deletion and insertion
+#[tauri::command]
+pub async fn compare_transcripts(
+    hypothesis: String,
+    reference: String,
+) -> Result<TranscriptComparison> {
+    Ok(compare(&hypothesis, &reference))
+}
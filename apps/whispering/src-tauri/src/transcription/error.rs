@@ -18,4 +18,7 @@ pub enum TranscriptionError {
 
     #[error("Transcription error: {message}")]
     TranscriptionError { message: String },
+
+    #[error("Cancelled: {message}")]
+    Cancelled { message: String },
 }
\ No newline at end of file
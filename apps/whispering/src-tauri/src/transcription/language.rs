@@ -0,0 +1,126 @@
+//! Which language a transcript is in, for when the language was left on
+//! auto-detect.
+//!
+//! The local engines don't report the language they settled on, so it's
+//! worked out from the transcript text. Short utterances give little to go
+//! on, which is when auto-detect most often picks the wrong language; the
+//! confidence lets the UI offer `retranscribe_with_language` for those.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use whatlang::Lang;
+
+/// Below this confidence the UI should treat the language as a guess
+pub const RELIABLE_CONFIDENCE: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code where there is one (`en`, `de`), as the engines take
+    /// it, otherwise ISO 639-3
+    pub code: String,
+    /// From 0 to 1
+    pub confidence: f32,
+}
+
+impl DetectedLanguage {
+    pub fn is_reliable(&self) -> bool {
+        self.confidence >= RELIABLE_CONFIDENCE
+    }
+}
+
+/// Whether `language` asks for auto-detection rather than naming a language
+pub fn is_auto(language: Option<&str>) -> bool {
+    matches!(language, None | Some("") | Some("auto"))
+}
+
+/// ISO 639-1 code of `lang`, for the languages Whisper knows by one
+fn two_letter_code(lang: Lang) -> Option<&'static str> {
+    Some(match lang {
+        Lang::Afr => "af",
+        Lang::Ara => "ar",
+        Lang::Aze => "az",
+        Lang::Bel => "be",
+        Lang::Ben => "bn",
+        Lang::Bul => "bg",
+        Lang::Cat => "ca",
+        Lang::Ces => "cs",
+        Lang::Cmn => "zh",
+        Lang::Dan => "da",
+        Lang::Deu => "de",
+        Lang::Ell => "el",
+        Lang::Eng => "en",
+        Lang::Epo => "eo",
+        Lang::Est => "et",
+        Lang::Fin => "fi",
+        Lang::Fra => "fr",
+        Lang::Guj => "gu",
+        Lang::Heb => "he",
+        Lang::Hin => "hi",
+        Lang::Hrv => "hr",
+        Lang::Hun => "hu",
+        Lang::Hye => "hy",
+        Lang::Ind => "id",
+        Lang::Ita => "it",
+        Lang::Jav => "jw",
+        Lang::Jpn => "ja",
+        Lang::Kan => "kn",
+        Lang::Kat => "ka",
+        Lang::Khm => "km",
+        Lang::Kor => "ko",
+        Lang::Lat => "la",
+        Lang::Lav => "lv",
+        Lang::Lit => "lt",
+        Lang::Mal => "ml",
+        Lang::Mar => "mr",
+        Lang::Mkd => "mk",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Nld => "nl",
+        Lang::Nob => "no",
+        Lang::Pan => "pa",
+        Lang::Pes => "fa",
+        Lang::Pol => "pl",
+        Lang::Por => "pt",
+        Lang::Ron => "ro",
+        Lang::Rus => "ru",
+        Lang::Sin => "si",
+        Lang::Slk => "sk",
+        Lang::Slv => "sl",
+        Lang::Sna => "sn",
+        Lang::Spa => "es",
+        Lang::Srp => "sr",
+        Lang::Swe => "sv",
+        Lang::Tam => "ta",
+        Lang::Tel => "te",
+        Lang::Tgl => "tl",
+        Lang::Tha => "th",
+        Lang::Tuk => "tk",
+        Lang::Tur => "tr",
+        Lang::Ukr => "uk",
+        Lang::Urd => "ur",
+        Lang::Uzb => "uz",
+        Lang::Vie => "vi",
+        Lang::Yid => "yi",
+        _ => return None,
+    })
+}
+
+/// Guess the language of `text`, or `None` if there's no text to go on
+pub fn detect(text: &str) -> Option<DetectedLanguage> {
+    let info = whatlang::detect(text)?;
+    let code = two_letter_code(info.lang())
+        .map(str::to_string)
+        .unwrap_or_else(|| info.lang().code().to_string());
+    Some(DetectedLanguage {
+        code,
+        confidence: info.confidence() as f32,
+    })
+}
+
+/// Guess the language of a transcript made elsewhere, e.g. by a cloud
+/// provider from the frontend
+#[tauri::command]
+pub async fn detect_language(text: String) -> Result<Option<DetectedLanguage>> {
+    Ok(detect(&text))
+}
@@ -1,12 +1,27 @@
+pub mod benchmark;
+pub mod compare;
 mod error;
+pub mod language;
+#[cfg(feature = "local-inference")]
 mod model_manager;
+pub mod remote;
+pub mod streaming;
 
-use error::TranscriptionError;
+pub use error::TranscriptionError;
+pub use language::DetectedLanguage;
+#[cfg(feature = "local-inference")]
 pub use model_manager::ModelManager;
+use crate::jobs::{self, Jobs};
+use crate::punctuation::Punctuator;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "local-inference")]
 use std::path::PathBuf;
 use std::io::Write;
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+#[cfg(feature = "local-inference")]
 use transcribe_rs::{
-    TranscriptionEngine,
+    TranscriptionEngine, TranscriptionResult,
     engines::{
         whisper::{WhisperEngine, WhisperInferenceParams},
         parakeet::{ParakeetInferenceParams, TimestampGranularity},
@@ -14,6 +29,19 @@ use transcribe_rs::{
 };
 use rubato::{Resampler, SincFixedIn, SincInterpolationType, SincInterpolationParameters, WindowFunction};
 
+/// Stand-in for builds without the `local-inference` feature, so subsystems
+/// that transcribe in Rust still compile and fail with a clear error
+#[cfg(not(feature = "local-inference"))]
+#[derive(Default)]
+pub struct ModelManager;
+
+#[cfg(not(feature = "local-inference"))]
+impl ModelManager {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
 /// Check if audio is already in whisper-compatible format (16kHz, mono, 16-bit PCM)
 fn is_valid_wav_format(audio_data: &[u8]) -> bool {
     let cursor = std::io::Cursor::new(audio_data);
@@ -409,27 +437,195 @@ fn extract_samples_from_wav(wav_data: Vec<u8>) -> Result<Vec<f32>, Transcription
     Ok(samples)
 }
 
-#[tauri::command]
-pub async fn transcribe_audio_whisper(
+/// Decode any supported audio file to 16kHz mono samples
+///
+/// Uses the same conversion tiers as transcription, so anything that can be
+/// transcribed can also be analysed (waveforms, playback, metrics).
+pub fn decode_audio(audio_data: Vec<u8>) -> Result<Vec<f32>, TranscriptionError> {
+    extract_samples_from_wav(convert_audio_for_whisper(audio_data)?)
+}
+
+/// Sample rate of audio returned by `decode_audio`
+pub const DECODED_SAMPLE_RATE: u32 = 16000;
+
+/// Local engine used for transcriptions that are started from Rust
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocalEngine {
+    Whisper,
+    Parakeet,
+}
+
+impl LocalEngine {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocalEngine::Whisper => "whisper",
+            LocalEngine::Parakeet => "parakeet",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "whisper" => Some(LocalEngine::Whisper),
+            "parakeet" => Some(LocalEngine::Parakeet),
+            _ => None,
+        }
+    }
+}
+
+/// A timed piece of a transcript, used for subtitle sidecar files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Transcript produced by one of the local engines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalTranscript {
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+    pub duration_seconds: f32,
+    /// Worked out from the text when no language was asked for
+    pub language: Option<DetectedLanguage>,
+}
+
+impl LocalTranscript {
+    /// Language to store with the transcript and how sure detection was of
+    /// it; the `requested` language, with no confidence, if one was given
+    pub fn language_for_history(&self, requested: Option<&str>) -> (Option<String>, Option<f32>) {
+        match &self.language {
+            Some(detected) => (Some(detected.code.clone()), Some(detected.confidence)),
+            None if language::is_auto(requested) => (None, None),
+            None => (requested.map(str::to_string), None),
+        }
+    }
+}
+
+/// Transcribe raw audio bytes with a local engine.
+///
+/// This is the shared path behind the `transcribe_audio_*` commands and any
+/// Rust-side subsystem (such as the folder watcher) that needs a transcript
+/// without a round trip through the webview.
+pub fn transcribe_local(
+    model_manager: &ModelManager,
+    engine: LocalEngine,
     audio_data: Vec<u8>,
-    model_path: String,
+    model_path: &str,
+    language: Option<String>,
+) -> Result<LocalTranscript, TranscriptionError> {
+    transcribe_local_cancellable(
+        model_manager,
+        engine,
+        audio_data,
+        model_path,
+        language,
+        &CancellationToken::new(),
+    )
+}
+
+/// [`transcribe_local`] that stops between stages once `token` is cancelled
+pub fn transcribe_local_cancellable(
+    model_manager: &ModelManager,
+    engine: LocalEngine,
+    audio_data: Vec<u8>,
+    model_path: &str,
     language: Option<String>,
-    model_manager: tauri::State<'_, ModelManager>,
-) -> Result<String, TranscriptionError> {
-    // Convert audio to 16kHz mono format that whisper requires
+    token: &CancellationToken,
+) -> Result<LocalTranscript, TranscriptionError> {
+    jobs::check(token)?;
+    // Convert audio to 16kHz mono format that the local engines require
     let wav_data = convert_audio_for_whisper(audio_data)?;
+    jobs::check(token)?;
 
     // Extract samples from WAV
     let samples = extract_samples_from_wav(wav_data)?;
+    jobs::check(token)?;
+    let duration_seconds = samples.len() as f32 / 16000.0;
 
     // Return early if audio is empty
     if samples.is_empty() {
-        return Ok(String::new());
+        return Ok(LocalTranscript {
+            text: String::new(),
+            segments: Vec::new(),
+            duration_seconds,
+            language: None,
+        });
     }
 
+    let detect_language = language::is_auto(language.as_deref());
+
+    let (text, segments) = run_engine(model_manager, engine, samples, model_path, language)?;
+    jobs::check(token)?;
+
+    Ok(LocalTranscript {
+        language: if detect_language {
+            language::detect(&text)
+        } else {
+            None
+        },
+        text,
+        segments,
+        duration_seconds,
+    })
+}
+
+/// Run `engine` on 16 kHz mono samples, returning the text and its segments
+#[cfg(feature = "local-inference")]
+fn run_engine(
+    model_manager: &ModelManager,
+    engine: LocalEngine,
+    samples: Vec<f32>,
+    model_path: &str,
+    language: Option<String>,
+) -> Result<(String, Vec<TranscriptSegment>), TranscriptionError> {
+    let result = match engine {
+        LocalEngine::Whisper => run_whisper(model_manager, samples, model_path, language)?,
+        LocalEngine::Parakeet => run_parakeet(model_manager, samples, model_path)?,
+    };
+
+    let segments = result
+        .segments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            start: segment.start,
+            end: segment.end,
+            text: segment.text.trim().to_string(),
+        })
+        .collect();
+    Ok((result.text.trim().to_string(), segments))
+}
+
+#[cfg(not(feature = "local-inference"))]
+fn run_engine(
+    _model_manager: &ModelManager,
+    engine: LocalEngine,
+    _samples: Vec<f32>,
+    _model_path: &str,
+    _language: Option<String>,
+) -> Result<(String, Vec<TranscriptSegment>), TranscriptionError> {
+    Err(TranscriptionError::ModelLoadError {
+        message: format!(
+            "This build doesn't include local transcription, so the {} engine can't run",
+            engine.as_str()
+        ),
+    })
+}
+
+#[cfg(feature = "local-inference")]
+fn run_whisper(
+    model_manager: &ModelManager,
+    samples: Vec<f32>,
+    model_path: &str,
+    language: Option<String>,
+) -> Result<TranscriptionResult, TranscriptionError> {
     // Get or load the model using the persistent model manager
     let engine_arc = model_manager
-        .get_or_load_whisper(PathBuf::from(&model_path))
+        .get_or_load_whisper(PathBuf::from(model_path))
         .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
 
     // Configure inference parameters
@@ -444,52 +640,37 @@ pub async fn transcribe_audio_whisper(
     params.no_speech_thold = 0.2;
 
     // Run transcription with the persistent engine
-    let result = {
-        let mut engine_guard = engine_arc.lock().unwrap();
-        let engine = engine_guard.as_mut().ok_or_else(|| {
-            TranscriptionError::ModelLoadError {
-                message: "Model failed to load".to_string(),
-            }
-        })?;
-
-        // Extract the WhisperEngine from the enum
-        let whisper_engine = match engine {
-            model_manager::Engine::Whisper(e) => e,
-            _ => return Err(TranscriptionError::ModelLoadError {
-                message: "Expected Whisper engine but got different type".to_string(),
-            }),
-        };
+    let mut engine_guard = engine_arc.lock().unwrap();
+    let engine = engine_guard.as_mut().ok_or_else(|| {
+        TranscriptionError::ModelLoadError {
+            message: "Model failed to load".to_string(),
+        }
+    })?;
 
-        whisper_engine
-            .transcribe_samples(samples, Some(params))
-            .map_err(|e| TranscriptionError::TranscriptionError {
-                message: e.to_string(),
-            })?
+    // Extract the WhisperEngine from the enum
+    let whisper_engine = match engine {
+        model_manager::Engine::Whisper(e) => e,
+        _ => return Err(TranscriptionError::ModelLoadError {
+            message: "Expected Whisper engine but got different type".to_string(),
+        }),
     };
 
-    Ok(result.text.trim().to_string())
+    whisper_engine
+        .transcribe_samples(samples, Some(params))
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: e.to_string(),
+        })
 }
 
-#[tauri::command]
-pub async fn transcribe_audio_parakeet(
-    audio_data: Vec<u8>,
-    model_path: String,
-    model_manager: tauri::State<'_, ModelManager>,
-) -> Result<String, TranscriptionError> {
-    // Convert audio to 16kHz mono format
-    let wav_data = convert_audio_for_whisper(audio_data)?;
-
-    // Extract samples from WAV
-    let samples = extract_samples_from_wav(wav_data)?;
-
-    // Return early if audio is empty
-    if samples.is_empty() {
-        return Ok(String::new());
-    }
-
+#[cfg(feature = "local-inference")]
+fn run_parakeet(
+    model_manager: &ModelManager,
+    samples: Vec<f32>,
+    model_path: &str,
+) -> Result<TranscriptionResult, TranscriptionError> {
     // Get or load the model using the persistent model manager
     let engine_arc = model_manager
-        .get_or_load_parakeet(PathBuf::from(&model_path))
+        .get_or_load_parakeet(PathBuf::from(model_path))
         .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
 
     let params = ParakeetInferenceParams {
@@ -498,28 +679,108 @@ pub async fn transcribe_audio_parakeet(
     };
 
     // Run transcription with the persistent engine
-    let result = {
-        let mut engine_guard = engine_arc.lock().unwrap();
-        let engine = engine_guard.as_mut().ok_or_else(|| {
-            TranscriptionError::ModelLoadError {
-                message: "Model failed to load".to_string(),
-            }
-        })?;
-
-        // Extract the ParakeetEngine from the enum
-        let parakeet_engine = match engine {
-            model_manager::Engine::Parakeet(e) => e,
-            _ => return Err(TranscriptionError::ModelLoadError {
-                message: "Expected Parakeet engine but got different type".to_string(),
-            }),
-        };
+    let mut engine_guard = engine_arc.lock().unwrap();
+    let engine = engine_guard.as_mut().ok_or_else(|| {
+        TranscriptionError::ModelLoadError {
+            message: "Model failed to load".to_string(),
+        }
+    })?;
 
-        parakeet_engine
-            .transcribe_samples(samples, Some(params))
-            .map_err(|e| TranscriptionError::TranscriptionError {
-                message: e.to_string(),
-            })?
+    // Extract the ParakeetEngine from the enum
+    let parakeet_engine = match engine {
+        model_manager::Engine::Parakeet(e) => e,
+        _ => return Err(TranscriptionError::ModelLoadError {
+            message: "Expected Parakeet engine but got different type".to_string(),
+        }),
     };
 
-    Ok(result.text.trim().to_string())
-}
\ No newline at end of file
+    parakeet_engine
+        .transcribe_samples(samples, Some(params))
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: e.to_string(),
+        })
+}
+
+/// Run a local transcription on a blocking thread as job `job_id`
+///
+/// Returns as soon as the job is cancelled; see [`crate::jobs`] for what
+/// happens to the work already under way.
+async fn transcribe_local_job(
+    app: &AppHandle,
+    engine: LocalEngine,
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    job_id: Option<String>,
+) -> crate::error::Result<String> {
+    if !cfg!(feature = "local-inference") {
+        return Err(crate::build_features::missing(
+            "local-inference",
+            "Local transcription",
+        ));
+    }
+
+    let jobs = app.state::<Jobs>();
+    let job = jobs.start(job_id);
+    let token = job.token().clone();
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn_blocking(move || {
+        let model_manager = handle.state::<ModelManager>();
+        transcribe_local_cancellable(
+            &model_manager,
+            engine,
+            audio_data,
+            &model_path,
+            language,
+            &token,
+        )
+    });
+    let transcript = job
+        .run(async {
+            task.await
+                .map_err(|e| {
+                    crate::error::AppError::Internal(format!("Transcription task failed: {}", e))
+                })?
+                .map_err(Into::into)
+        })
+        .await?;
+    jobs::check(job.token())?;
+    Ok(app.state::<Punctuator>().apply(transcript.text))
+}
+
+#[tauri::command]
+pub async fn transcribe_audio_whisper(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> crate::error::Result<String> {
+    transcribe_local_job(
+        &app_handle,
+        LocalEngine::Whisper,
+        audio_data,
+        model_path,
+        language,
+        job_id,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn transcribe_audio_parakeet(
+    audio_data: Vec<u8>,
+    model_path: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> crate::error::Result<String> {
+    transcribe_local_job(
+        &app_handle,
+        LocalEngine::Parakeet,
+        audio_data,
+        model_path,
+        None,
+        job_id,
+    )
+    .await
+}
@@ -0,0 +1,374 @@
+//! OpenAI-compatible transcription servers (faster-whisper-server, Speaches,
+//! LocalAI, vLLM, ...), usually running on a home server or another machine
+//! on the LAN.
+//!
+//! Only the parts of the API every implementation shares are used:
+//! `GET /v1/models` and `POST /v1/audio/transcriptions`. Speaker labels
+//! (`diarized_json`) are only asked for when the caller wants them, since
+//! few servers besides OpenAI's own support them.
+
+use crate::error::{AppError, Result};
+use crate::jobs::Jobs;
+use crate::privacy;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+use tracing::{debug, info};
+
+/// Requests to the server give up after this long
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Connectivity checks give up sooner so the settings page stays responsive
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many requests `measure_remote_latency` makes when not told
+const DEFAULT_LATENCY_SAMPLES: u32 = 5;
+
+/// Where the server is and how to authenticate with it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteServer {
+    /// e.g. `http://192.168.1.20:8000`, with or without a trailing `/v1`
+    pub base_url: String,
+    /// Sent as a bearer token if set
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+impl RemoteServer {
+    pub(crate) fn url(&self, path: &str) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+        format!("{}/v1/{}", base, path)
+    }
+
+    pub(crate) fn request(
+        &self,
+        client: &reqwest::Client,
+        method: reqwest::Method,
+        url: &str,
+    ) -> reqwest::RequestBuilder {
+        let request = client.request(method, url);
+        match self.api_key.as_deref().filter(|key| !key.is_empty()) {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+}
+
+/// Result of `test_remote_server`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTest {
+    pub reachable: bool,
+    /// HTTP status of the models request, if the server answered
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub models: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Result of `measure_remote_latency`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyReport {
+    pub samples: u32,
+    pub failed: u32,
+    pub min_ms: Option<u64>,
+    pub average_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelInfo>,
+}
+
+#[derive(Deserialize)]
+struct ModelInfo {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// A stretch of speech by one speaker, from `transcribe_remote_diarized`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiarizedSegment {
+    /// Label the server gave the speaker, e.g. `A`
+    pub speaker: String,
+    /// Seconds from the start of the audio
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+struct DiarizedResponse {
+    segments: Vec<DiarizedSegment>,
+}
+
+pub(crate) fn client(timeout: Duration) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Turn a non-success response into the matching error
+pub(crate) async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("Server returned {}: {}", status, body.trim());
+    Err(match status.as_u16() {
+        401 | 403 => AppError::InvalidApiKey(message),
+        _ => AppError::Network(message),
+    })
+}
+
+pub(crate) fn network_error(e: reqwest::Error) -> AppError {
+    AppError::Network(e.to_string())
+}
+
+/// Model ids the server offers
+pub async fn list_models(app: &AppHandle, server: &RemoteServer) -> Result<Vec<String>> {
+    fetch_models(app, server, &client(PROBE_TIMEOUT)?).await
+}
+
+async fn fetch_models(
+    app: &AppHandle,
+    server: &RemoteServer,
+    client: &reqwest::Client,
+) -> Result<Vec<String>> {
+    let url = server.url("models");
+    privacy::ensure_allowed(app, &url)?;
+    let response = server
+        .request(client, reqwest::Method::GET, &url)
+        .send()
+        .await
+        .map_err(network_error)?;
+    let models: ModelList = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Unexpected models response: {}", e)))?;
+    Ok(models.data.into_iter().map(|model| model.id).collect())
+}
+
+/// Transcribe audio (any format the server accepts) with `model`
+pub async fn transcribe_remote(
+    app: &AppHandle,
+    server: &RemoteServer,
+    audio_data: Vec<u8>,
+    file_name: &str,
+    model: &str,
+    language: Option<String>,
+) -> Result<String> {
+    let url = server.url("audio/transcriptions");
+    privacy::ensure_allowed(app, &url)?;
+    debug!(
+        "Transcribing {} bytes with {} at {}",
+        audio_data.len(),
+        model,
+        url
+    );
+
+    let file = reqwest::multipart::Part::bytes(audio_data).file_name(file_name.to_string());
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text("model", model.to_string())
+        .text("response_format", "json");
+    if let Some(language) = language.filter(|l| !l.is_empty() && l != "auto") {
+        form = form.text("language", language);
+    }
+
+    let response = server
+        .request(&client(REQUEST_TIMEOUT)?, reqwest::Method::POST, &url)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(network_error)?;
+    let transcript: TranscriptionResponse = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Unexpected transcription response: {}", e)))?;
+    Ok(transcript.text.trim().to_string())
+}
+
+/// Transcribe audio with speaker labels, using the `diarized_json` response
+/// format of OpenAI's diarizing models (`gpt-4o-transcribe-diarize`)
+pub async fn transcribe_remote_diarized(
+    app: &AppHandle,
+    server: &RemoteServer,
+    audio_data: Vec<u8>,
+    file_name: &str,
+    model: &str,
+    language: Option<String>,
+) -> Result<Vec<DiarizedSegment>> {
+    let url = server.url("audio/transcriptions");
+    privacy::ensure_allowed(app, &url)?;
+    debug!("Transcribing {} bytes with speakers with {} at {}", audio_data.len(), model, url);
+
+    let file = reqwest::multipart::Part::bytes(audio_data).file_name(file_name.to_string());
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text("model", model.to_string())
+        .text("response_format", "diarized_json")
+        .text("chunking_strategy", "auto");
+    if let Some(language) = language.filter(|l| !l.is_empty() && l != "auto") {
+        form = form.text("language", language);
+    }
+
+    let response = server
+        .request(&client(REQUEST_TIMEOUT)?, reqwest::Method::POST, &url)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(network_error)?;
+    let diarized: DiarizedResponse = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Unexpected diarized response: {}", e)))?;
+    Ok(diarized.segments)
+}
+
+/// Check that the server is reachable, accepts our credentials and lists models
+pub async fn test_connection(app: &AppHandle, server: &RemoteServer) -> Result<ConnectionTest> {
+    let url = server.url("models");
+    privacy::ensure_allowed(app, &url)?;
+
+    let started = Instant::now();
+    let response = match server
+        .request(&client(PROBE_TIMEOUT)?, reqwest::Method::GET, &url)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(ConnectionTest {
+                reachable: false,
+                status: None,
+                latency_ms: None,
+                models: Vec::new(),
+                error: Some(e.to_string()),
+            })
+        }
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+
+    let (models, error) = match check_status(response).await {
+        Ok(response) => match response.json::<ModelList>().await {
+            Ok(list) => (list.data.into_iter().map(|model| model.id).collect(), None),
+            Err(e) => (
+                Vec::new(),
+                Some(format!("Unexpected models response: {}", e)),
+            ),
+        },
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    Ok(ConnectionTest {
+        reachable: true,
+        status: Some(status),
+        latency_ms: Some(latency_ms),
+        models,
+        error,
+    })
+}
+
+/// Round-trip time of `samples` lightweight requests to the server
+pub async fn measure_latency(
+    app: &AppHandle,
+    server: &RemoteServer,
+    samples: u32,
+) -> Result<LatencyReport> {
+    let client = client(PROBE_TIMEOUT)?;
+    let url = server.url("models");
+    privacy::ensure_allowed(app, &url)?;
+
+    let mut timings = Vec::new();
+    let mut failed = 0;
+    for _ in 0..samples {
+        let started = Instant::now();
+        let response = server
+            .request(&client, reqwest::Method::GET, &url)
+            .send()
+            .await;
+        match response {
+            // Any answer counts; only the round trip is being measured
+            Ok(_) => timings.push(started.elapsed().as_millis() as u64),
+            Err(e) => {
+                debug!("Latency probe to {} failed: {}", url, e);
+                failed += 1;
+            }
+        }
+    }
+
+    let report = LatencyReport {
+        samples,
+        failed,
+        min_ms: timings.iter().min().copied(),
+        average_ms: (!timings.is_empty())
+            .then(|| timings.iter().sum::<u64>() / timings.len() as u64),
+        max_ms: timings.iter().max().copied(),
+    };
+    info!("Latency to {}: {:?}", server.base_url, report);
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn list_remote_models(
+    server: RemoteServer,
+    app_handle: AppHandle,
+) -> Result<Vec<String>> {
+    list_models(&app_handle, &server).await
+}
+
+#[tauri::command]
+pub async fn test_remote_server(
+    server: RemoteServer,
+    app_handle: AppHandle,
+) -> Result<ConnectionTest> {
+    test_connection(&app_handle, &server).await
+}
+
+#[tauri::command]
+pub async fn measure_remote_latency(
+    server: RemoteServer,
+    samples: Option<u32>,
+    app_handle: AppHandle,
+) -> Result<LatencyReport> {
+    let samples = samples.unwrap_or(DEFAULT_LATENCY_SAMPLES).clamp(1, 50);
+    measure_latency(&app_handle, &server, samples).await
+}
+
+#[tauri::command]
+pub async fn transcribe_audio_remote(
+    audio_data: Vec<u8>,
+    server: RemoteServer,
+    model: String,
+    language: Option<String>,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+    jobs: State<'_, Jobs>,
+) -> Result<String> {
+    // Cancelling drops the request, closing the connection mid-upload if need be
+    let job = jobs.start(job_id);
+    job.run(transcribe_remote(
+        &app_handle,
+        &server,
+        audio_data,
+        "recording.wav",
+        &model,
+        language,
+    ))
+    .await
+}
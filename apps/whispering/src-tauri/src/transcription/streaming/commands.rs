@@ -0,0 +1,45 @@
+use crate::error::{AppError, Result};
+use crate::recorder::commands::AppData;
+use crate::transcription::streaming::{
+    KeywordAlerts, KeywordConfig, StreamingConfig, StreamingTranscriber,
+};
+use tauri::{AppHandle, State};
+
+/// Stream audio to a realtime provider while recording
+///
+/// Results arrive as `streaming://interim` and `streaming://final` events.
+/// The session keeps running across recordings until `stop_streaming`.
+#[tauri::command]
+pub async fn start_streaming(
+    config: StreamingConfig,
+    app_handle: AppHandle,
+    recorder: State<'_, AppData>,
+    streaming: State<'_, StreamingTranscriber>,
+) -> Result<()> {
+    let frames = recorder
+        .recorder
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock recorder: {}", e)))?
+        .subscribe_frames();
+    streaming.start(app_handle, config, frames).await
+}
+
+#[tauri::command]
+pub async fn stop_streaming(streaming: State<'_, StreamingTranscriber>) -> Result<()> {
+    streaming.stop().await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_keyword_alerts(alerts: State<'_, KeywordAlerts>) -> Result<KeywordConfig> {
+    Ok(alerts.config())
+}
+
+/// Set the phrases to listen for in the live transcript
+#[tauri::command]
+pub async fn set_keyword_alerts(
+    config: KeywordConfig,
+    alerts: State<'_, KeywordAlerts>,
+) -> Result<()> {
+    alerts.set_config(config)
+}
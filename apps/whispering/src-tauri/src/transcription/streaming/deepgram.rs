@@ -0,0 +1,123 @@
+//! Deepgram live streaming (`wss://api.deepgram.com/v1/listen`).
+
+use super::{StreamingProvider, StreamingResult};
+use crate::error::{AppError, Result};
+use serde::Deserialize;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+const DEFAULT_MODEL: &str = "nova-3";
+const SAMPLE_RATE: u32 = 16000;
+
+pub struct DeepgramLive {
+    api_key: String,
+    model: String,
+    language: Option<String>,
+}
+
+impl DeepgramLive {
+    pub fn new(api_key: String, model: Option<String>, language: Option<String>) -> Self {
+        Self {
+            api_key,
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            language,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ServerMessage {
+    Results {
+        channel: Channel,
+        #[serde(default)]
+        is_final: bool,
+    },
+    Error {
+        #[serde(default)]
+        description: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct Channel {
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Deserialize)]
+struct Alternative {
+    transcript: String,
+}
+
+impl StreamingProvider for DeepgramLive {
+    fn name(&self) -> &'static str {
+        "Deepgram"
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn url(&self) -> String {
+        let mut url = format!(
+            "wss://api.deepgram.com/v1/listen?model={}&encoding=linear16&sample_rate={}&channels=1&interim_results=true&smart_format=true",
+            self.model, SAMPLE_RATE
+        );
+        match &self.language {
+            Some(language) => url.push_str(&format!("&language={}", language)),
+            None => url.push_str("&detect_language=true"),
+        }
+        url
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![("Authorization", format!("Token {}", self.api_key))]
+    }
+
+    fn audio_message(&self, pcm: &[i16]) -> Message {
+        Message::Binary(pcm.iter().flat_map(|s| s.to_le_bytes()).collect())
+    }
+
+    fn keep_alive_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"type":"KeepAlive"}"#.to_string()))
+    }
+
+    fn finish_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"type":"CloseStream"}"#.to_string()))
+    }
+
+    fn parse(&mut self, text: &str) -> Result<Vec<StreamingResult>> {
+        let message: ServerMessage = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Ignoring unexpected Deepgram message: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+        match message {
+            ServerMessage::Results { channel, is_final } => {
+                let transcript = channel
+                    .alternatives
+                    .into_iter()
+                    .next()
+                    .map(|alternative| alternative.transcript)
+                    .unwrap_or_default();
+                if transcript.is_empty() {
+                    return Ok(Vec::new());
+                }
+                Ok(vec![if is_final {
+                    StreamingResult::Final(transcript)
+                } else {
+                    StreamingResult::Interim(transcript)
+                }])
+            }
+            ServerMessage::Error { description } => Err(AppError::InvalidInput(format!(
+                "Deepgram error: {}",
+                description
+            ))),
+            ServerMessage::Other => Ok(Vec::new()),
+        }
+    }
+}
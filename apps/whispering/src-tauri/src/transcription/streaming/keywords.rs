@@ -0,0 +1,223 @@
+//! Alerts for watched words and phrases in the live transcript, e.g. your
+//! name in a meeting you're only half listening to.
+//!
+//! Only final results are checked, so a phrase the provider revises away
+//! doesn't fire. Each match emits `streaming://keyword` and, per watch,
+//! shows a notification and/or posts to a webhook. A phrase that just fired
+//! stays quiet for the cooldown, so a discussion about it doesn't turn into
+//! a stream of alerts.
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, KeywordHeard};
+use crate::i18n;
+use crate::notifications::notify;
+use crate::privacy;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+pub const KEYWORDS_FILE: &str = "keywords.json";
+
+/// Webhook requests give up after this long
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordWatch {
+    /// Matched case-insensitively as whole words
+    pub phrase: String,
+    #[serde(default = "default_true")]
+    pub notify: bool,
+    /// Receives a JSON `KeywordMatch` by POST
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+impl KeywordWatch {
+    fn compile(&self) -> Result<Regex> {
+        let words: Vec<String> = self.phrase.split_whitespace().map(regex::escape).collect();
+        if words.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Watched phrase can't be empty".to_string(),
+            ));
+        }
+        RegexBuilder::new(&format!(r"\b{}\b", words.join(r"\s+")))
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid phrase {:?}: {}", self.phrase, e)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct KeywordConfig {
+    pub enabled: bool,
+    pub watches: Vec<KeywordWatch>,
+    /// Seconds a phrase stays quiet after firing
+    pub cooldown_seconds: u32,
+}
+
+impl Default for KeywordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            watches: Vec::new(),
+            cooldown_seconds: 60,
+        }
+    }
+}
+
+/// Payload of `streaming://keyword` and of webhook requests
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordMatch {
+    pub phrase: String,
+    /// The final result the phrase was heard in
+    pub text: String,
+    pub provider: &'static str,
+    /// Milliseconds since the Unix epoch
+    pub at: i64,
+}
+
+pub struct KeywordAlerts {
+    path: PathBuf,
+    config: Mutex<KeywordConfig>,
+    /// When each phrase last fired
+    fired: Mutex<HashMap<String, Instant>>,
+}
+
+impl KeywordAlerts {
+    pub fn open(path: &Path) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                KeywordConfig::default()
+            }),
+            Err(_) => KeywordConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            fired: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> KeywordConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: KeywordConfig) -> Result<()> {
+        for watch in &config.watches {
+            watch.compile()?;
+            if let Some(url) = watch.webhook_url.as_deref().filter(|url| !url.is_empty()) {
+                reqwest::Url::parse(url).map_err(|e| {
+                    AppError::InvalidInput(format!("Invalid webhook URL {:?}: {}", url, e))
+                })?;
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize keyword alerts: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self
+            .config
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock keyword alerts: {}", e)))? =
+            config;
+        Ok(())
+    }
+
+    /// Fire the alerts of every watched phrase in `text`, a final result
+    pub fn check(&self, app: &AppHandle, provider: &'static str, text: &str) {
+        let config = self.config();
+        if !config.enabled {
+            return;
+        }
+        let cooldown = Duration::from_secs(config.cooldown_seconds.into());
+        for watch in &config.watches {
+            let matched = match watch.compile() {
+                Ok(regex) => regex.is_match(text),
+                Err(e) => {
+                    warn!("Skipping keyword watch: {}", e);
+                    false
+                }
+            };
+            if !matched || !self.start_cooldown(&watch.phrase, cooldown) {
+                continue;
+            }
+
+            info!("Heard watched phrase {:?}", watch.phrase);
+            let found = KeywordMatch {
+                phrase: watch.phrase.clone(),
+                text: text.to_string(),
+                provider,
+                at: crate::history::now_millis(),
+            };
+            let _ = KeywordHeard::emit(app, &found);
+            if watch.notify {
+                let message =
+                    i18n::t_args("notify-keyword-heard", &[("phrase", &watch.phrase), ("text", text)]);
+                notify(app, &message);
+            }
+            if let Some(url) = watch.webhook_url.clone().filter(|url| !url.is_empty()) {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = post_webhook(&app, &url, &found).await {
+                        warn!("Keyword webhook to {} failed: {}", url, e);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Whether `phrase` is out of its cooldown, starting a new one if so
+    fn start_cooldown(&self, phrase: &str, cooldown: Duration) -> bool {
+        let Ok(mut fired) = self.fired.lock() else {
+            return true;
+        };
+        let key = phrase.to_lowercase();
+        if fired
+            .get(&key)
+            .is_some_and(|last| last.elapsed() < cooldown)
+        {
+            return false;
+        }
+        fired.insert(key, Instant::now());
+        true
+    }
+}
+
+async fn post_webhook(app: &AppHandle, url: &str, found: &KeywordMatch) -> Result<()> {
+    privacy::ensure_allowed(app, url)?;
+    let response = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?
+        .post(url)
+        .json(found)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(AppError::Network(format!(
+            "Webhook returned {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,512 @@
+//! Live transcription over WebSocket.
+//!
+//! Instead of uploading a finished recording, a streaming session subscribes
+//! to the recorder's captured frames, pushes them to the provider as PCM16
+//! while recording, and emits results as they arrive:
+//!
+//! - `streaming://interim`: best guess so far, replaced by later results
+//! - `streaming://final`: text the provider won't revise
+//! - `streaming://status`: connection state changes
+//! - `streaming://keyword`: a watched phrase was heard (see `keywords`)
+//!
+//! Dropped connections are reopened with backoff. Audio captured while
+//! reconnecting stays queued, spilling to disk past the memory budget, and is
+//! sent once the new connection is up.
+
+pub mod commands;
+mod deepgram;
+mod keywords;
+mod openai;
+
+pub use deepgram::DeepgramLive;
+pub use keywords::{KeywordAlerts, KeywordConfig, KeywordMatch, KeywordWatch, KEYWORDS_FILE};
+pub use openai::OpenAiRealtime;
+
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, StreamingFinal, StreamingInterim, StreamingStatusChanged};
+use crate::privacy;
+use crate::recorder::recorder::AudioFrame;
+use crate::recorder::resample::LinearResampler;
+use crate::recorder::spill::FrameReceiver;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn};
+
+/// Opening the WebSocket gives up after this long
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Providers close idle connections, so something is sent at least this often
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// After stopping, how long to wait for the provider's last results
+const FINISH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Consecutive failed connection attempts before giving up
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A result from a streaming provider
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamingResult {
+    Interim(String),
+    Final(String),
+}
+
+/// A realtime transcription API reached over WebSocket
+///
+/// Providers only describe the protocol; connecting, sending audio,
+/// keep-alives and reconnecting are handled by the session.
+pub trait StreamingProvider: Send {
+    fn name(&self) -> &'static str;
+
+    /// Sample rate the provider expects mono PCM16 audio at
+    fn sample_rate(&self) -> u32;
+
+    fn url(&self) -> String;
+
+    /// Extra handshake headers, usually authentication
+    fn headers(&self) -> Vec<(&'static str, String)>;
+
+    /// Messages sent right after (re)connecting to configure the session
+    fn session_messages(&self) -> Vec<Message> {
+        Vec::new()
+    }
+
+    fn audio_message(&self, pcm: &[i16]) -> Message;
+
+    /// Sent when no audio has gone out for a while, if the provider needs it
+    fn keep_alive_message(&self) -> Option<Message> {
+        None
+    }
+
+    /// Asks the provider to flush its last results before the socket closes
+    fn finish_message(&self) -> Option<Message> {
+        None
+    }
+
+    /// Results in a text message from the provider
+    fn parse(&mut self, text: &str) -> Result<Vec<StreamingResult>>;
+}
+
+/// Which provider `start_streaming` should use
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamingProviderKind {
+    Deepgram,
+    OpenAi,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingConfig {
+    pub provider: StreamingProviderKind,
+    pub api_key: String,
+    /// Provider default if unset
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Language code, or `auto`/unset to let the provider detect it
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+impl StreamingConfig {
+    fn language(&self) -> Option<String> {
+        self.language
+            .clone()
+            .filter(|language| !language.is_empty() && language != "auto")
+    }
+
+    fn into_provider(self) -> Result<Box<dyn StreamingProvider>> {
+        if self.api_key.trim().is_empty() {
+            return Err(AppError::InvalidApiKey(
+                "An API key is required for streaming transcription".to_string(),
+            ));
+        }
+        let language = self.language();
+        let model = self.model.filter(|model| !model.is_empty());
+        Ok(match self.provider {
+            StreamingProviderKind::Deepgram => {
+                Box::new(DeepgramLive::new(self.api_key, model, language))
+            }
+            StreamingProviderKind::OpenAi => Box::new(OpenAiRealtime::new(
+                self.api_key,
+                model,
+                language,
+                self.prompt,
+            )),
+        })
+    }
+}
+
+/// Payload of `streaming://interim` and `streaming://final`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingTranscript {
+    pub provider: &'static str,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Reconnecting,
+    Stopped,
+    Failed,
+}
+
+/// Payload of `streaming://status`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingStatus {
+    pub provider: &'static str,
+    pub state: ConnectionState,
+    /// Reconnection attempt, 0 for the first connection
+    pub attempt: u32,
+    pub error: Option<String>,
+}
+
+struct ActiveStream {
+    stop: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+/// The live transcription session, if one is running
+pub struct StreamingTranscriber {
+    active: Mutex<Option<ActiveStream>>,
+}
+
+impl StreamingTranscriber {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(None),
+        }
+    }
+
+    /// Start streaming `frames` to the configured provider, replacing any running session
+    pub async fn start(
+        &self,
+        app: AppHandle,
+        config: StreamingConfig,
+        frames: FrameReceiver,
+    ) -> Result<()> {
+        let provider = config.into_provider()?;
+        privacy::ensure_allowed(&app, &provider.url())?;
+        self.stop().await;
+
+        info!("Starting {} streaming transcription", provider.name());
+        let (stop, stop_rx) = watch::channel(false);
+        let task = tauri::async_runtime::spawn(run(app, provider, frames, stop_rx));
+        *self
+            .active
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock streaming state: {}", e)))? =
+            Some(ActiveStream { stop, task });
+        Ok(())
+    }
+
+    /// Stop the running session, waiting for the provider's last results
+    pub async fn stop(&self) {
+        let active = self.active.lock().ok().and_then(|mut active| active.take());
+        if let Some(active) = active {
+            let _ = active.stop.send(true);
+            let _ = active.task.await;
+        }
+    }
+}
+
+fn emit_status(
+    app: &AppHandle,
+    provider: &'static str,
+    state: ConnectionState,
+    attempt: u32,
+    error: Option<String>,
+) {
+    let _ = StreamingStatusChanged::emit(
+        app,
+        &StreamingStatus {
+            provider,
+            state,
+            attempt,
+            error,
+        },
+    );
+}
+
+/// Session loop: connect, stream until stopped, reconnect on network failures
+async fn run(
+    app: AppHandle,
+    mut provider: Box<dyn StreamingProvider>,
+    mut frames: FrameReceiver,
+    mut stop: watch::Receiver<bool>,
+) {
+    let name = provider.name();
+    let mut resampler = FrameResampler::new(provider.sample_rate());
+    let mut attempt = 0;
+
+    let error = loop {
+        let state = if attempt == 0 {
+            ConnectionState::Connecting
+        } else {
+            ConnectionState::Reconnecting
+        };
+        emit_status(&app, name, state, attempt, None);
+
+        let outcome = match connect(&app, provider.as_ref()).await {
+            Ok(socket) => {
+                attempt = 0;
+                emit_status(&app, name, ConnectionState::Connected, attempt, None);
+                stream(
+                    &app,
+                    provider.as_mut(),
+                    socket,
+                    &mut resampler,
+                    &mut frames,
+                    &mut stop,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(()) => break None,
+            Err(e @ AppError::Network(_)) if attempt < MAX_RECONNECT_ATTEMPTS => {
+                attempt += 1;
+                let backoff = backoff(attempt);
+                warn!(
+                    "{} streaming connection lost ({}), reconnecting in {:?}",
+                    name, e, backoff
+                );
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = stop.wait_for(|stopped| *stopped) => break None,
+                }
+            }
+            Err(e) => break Some(e.to_string()),
+        }
+    };
+
+    match error {
+        Some(error) => {
+            warn!("{} streaming transcription failed: {}", name, error);
+            emit_status(&app, name, ConnectionState::Failed, attempt, Some(error));
+        }
+        None => {
+            info!("{} streaming transcription stopped", name);
+            emit_status(&app, name, ConnectionState::Stopped, attempt, None);
+        }
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF)
+}
+
+async fn connect(app: &AppHandle, provider: &dyn StreamingProvider) -> Result<Socket> {
+    let url = provider.url();
+    privacy::ensure_allowed(app, &url)?;
+    debug!("Connecting to {}", url);
+
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid streaming URL {}: {}", url, e)))?;
+    for (name, value) in provider.headers() {
+        let value = HeaderValue::from_str(&value)
+            .map_err(|_| AppError::InvalidInput(format!("Invalid {} header", name)))?;
+        request.headers_mut().insert(name, value);
+    }
+
+    let (socket, _) =
+        tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(request))
+            .await
+            .map_err(|_| AppError::Network(format!("Timed out connecting to {}", url)))?
+            .map_err(handshake_error)?;
+    Ok(socket)
+}
+
+fn handshake_error(e: tungstenite::Error) -> AppError {
+    match e {
+        tungstenite::Error::Http(response) => {
+            let status = response.status();
+            let body = response
+                .body()
+                .as_deref()
+                .map(String::from_utf8_lossy)
+                .unwrap_or_default();
+            let message = format!("Server returned {}: {}", status, body.trim());
+            match status.as_u16() {
+                401 | 403 => AppError::InvalidApiKey(message),
+                400..=499 => AppError::InvalidInput(message),
+                _ => AppError::Network(message),
+            }
+        }
+        other => network_error(other),
+    }
+}
+
+fn network_error(e: tungstenite::Error) -> AppError {
+    AppError::Network(e.to_string())
+}
+
+/// Pump audio out and results in until stopped or the connection drops
+async fn stream(
+    app: &AppHandle,
+    provider: &mut dyn StreamingProvider,
+    socket: Socket,
+    resampler: &mut FrameResampler,
+    frames: &mut FrameReceiver,
+    stop: &mut watch::Receiver<bool>,
+) -> Result<()> {
+    let (mut sink, mut source) = socket.split();
+    for message in provider.session_messages() {
+        sink.send(message).await.map_err(network_error)?;
+    }
+
+    let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+    keep_alive.reset();
+
+    loop {
+        tokio::select! {
+            _ = stop.wait_for(|stopped| *stopped) => break,
+            frame = frames.recv() => {
+                // The recorder went away along with our subscription
+                let Some(frame) = frame else { break };
+                let pcm = resampler.process(&frame);
+                if !pcm.is_empty() {
+                    sink.send(provider.audio_message(&pcm))
+                        .await
+                        .map_err(network_error)?;
+                    keep_alive.reset();
+                }
+            }
+            _ = keep_alive.tick() => {
+                if let Some(message) = provider.keep_alive_message() {
+                    sink.send(message).await.map_err(network_error)?;
+                }
+            }
+            message = source.next() => match message {
+                Some(Ok(Message::Text(text))) => handle_message(app, provider, &text)?,
+                Some(Ok(Message::Close(frame))) => {
+                    return Err(AppError::Network(format!(
+                        "Connection closed by {}: {}",
+                        provider.name(),
+                        frame.map(|f| f.reason.to_string()).unwrap_or_default()
+                    )));
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(network_error(e)),
+                None => {
+                    return Err(AppError::Network(format!(
+                        "Connection to {} closed",
+                        provider.name()
+                    )))
+                }
+            },
+        }
+    }
+
+    // Let the provider flush what it has buffered before closing
+    if let Some(message) = provider.finish_message() {
+        if sink.send(message).await.is_ok() {
+            let drain = async {
+                while let Some(Ok(message)) = source.next().await {
+                    match message {
+                        Message::Text(text) => handle_message(app, provider, &text)?,
+                        Message::Close(_) => break,
+                        _ => {}
+                    }
+                }
+                Ok::<_, AppError>(())
+            };
+            let drained = tokio::time::timeout(FINISH_TIMEOUT, drain).await;
+            if let Ok(Err(e)) = drained {
+                warn!(
+                    "{} reported an error while finishing: {}",
+                    provider.name(),
+                    e
+                );
+            }
+        }
+    }
+    let _ = sink.close().await;
+    Ok(())
+}
+
+fn handle_message(app: &AppHandle, provider: &mut dyn StreamingProvider, text: &str) -> Result<()> {
+    for result in provider.parse(text)? {
+        let _ = match result {
+            StreamingResult::Interim(text) => StreamingInterim::emit(
+                app,
+                &StreamingTranscript {
+                    provider: provider.name(),
+                    text,
+                },
+            ),
+            StreamingResult::Final(text) => {
+                app.state::<KeywordAlerts>()
+                    .check(app, provider.name(), &text);
+                StreamingFinal::emit(
+                    app,
+                    &StreamingTranscript {
+                        provider: provider.name(),
+                        text,
+                    },
+                )
+            }
+        };
+    }
+    Ok(())
+}
+
+/// Converts captured frames to PCM16 at the provider's sample rate
+struct FrameResampler {
+    target_rate: u32,
+    source_rate: u32,
+    resampler: LinearResampler,
+}
+
+impl FrameResampler {
+    fn new(target_rate: u32) -> Self {
+        Self {
+            target_rate,
+            source_rate: 0,
+            resampler: LinearResampler::new(target_rate, target_rate),
+        }
+    }
+
+    fn process(&mut self, frame: &AudioFrame) -> Vec<i16> {
+        if frame.samples.is_empty() || frame.sample_rate == 0 {
+            return Vec::new();
+        }
+        if frame.sample_rate != self.source_rate {
+            self.source_rate = frame.sample_rate;
+            self.resampler = LinearResampler::new(frame.sample_rate, self.target_rate);
+        }
+        self.resampler
+            .process(&frame.mono())
+            .into_iter()
+            .map(|value| (value.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .collect()
+    }
+}
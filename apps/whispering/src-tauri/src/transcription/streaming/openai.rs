@@ -0,0 +1,155 @@
+//! OpenAI Realtime API in transcription-only mode
+//! (`wss://api.openai.com/v1/realtime?intent=transcription`).
+//!
+//! Audio is appended to the input buffer and server-side voice activity
+//! detection commits it at pauses; each committed turn streams back as
+//! deltas followed by a completed transcript.
+
+use super::{StreamingProvider, StreamingResult};
+use crate::error::{AppError, Result};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+const DEFAULT_MODEL: &str = "gpt-4o-transcribe";
+const SAMPLE_RATE: u32 = 24000;
+
+pub struct OpenAiRealtime {
+    api_key: String,
+    model: String,
+    language: Option<String>,
+    prompt: Option<String>,
+    /// Deltas received so far for each turn that hasn't completed
+    partial: HashMap<String, String>,
+}
+
+impl OpenAiRealtime {
+    pub fn new(
+        api_key: String,
+        model: Option<String>,
+        language: Option<String>,
+        prompt: Option<String>,
+    ) -> Self {
+        Self {
+            api_key,
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            language,
+            prompt: prompt.filter(|prompt| !prompt.is_empty()),
+            partial: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ServerEvent {
+    #[serde(rename = "conversation.item.input_audio_transcription.delta")]
+    Delta { item_id: String, delta: String },
+    #[serde(rename = "conversation.item.input_audio_transcription.completed")]
+    Completed { item_id: String, transcript: String },
+    #[serde(rename = "error")]
+    Error { error: ErrorDetail },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetail {
+    message: String,
+}
+
+impl StreamingProvider for OpenAiRealtime {
+    fn name(&self) -> &'static str {
+        "OpenAI"
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn url(&self) -> String {
+        "wss://api.openai.com/v1/realtime?intent=transcription".to_string()
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Authorization", format!("Bearer {}", self.api_key)),
+            ("OpenAI-Beta", "realtime=v1".to_string()),
+        ]
+    }
+
+    fn session_messages(&self) -> Vec<Message> {
+        let mut transcription = json!({ "model": self.model });
+        if let Some(language) = &self.language {
+            transcription["language"] = json!(language);
+        }
+        if let Some(prompt) = &self.prompt {
+            transcription["prompt"] = json!(prompt);
+        }
+        let update = json!({
+            "type": "transcription_session.update",
+            "session": {
+                "input_audio_format": "pcm16",
+                "input_audio_transcription": transcription,
+                "turn_detection": { "type": "server_vad" },
+            },
+        });
+        vec![Message::Text(update.to_string())]
+    }
+
+    fn audio_message(&self, pcm: &[i16]) -> Message {
+        let bytes: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let append = json!({
+            "type": "input_audio_buffer.append",
+            "audio": base64::engine::general_purpose::STANDARD.encode(bytes),
+        });
+        Message::Text(append.to_string())
+    }
+
+    fn finish_message(&self) -> Option<Message> {
+        // Transcribe whatever is still buffered instead of waiting for a pause
+        Some(Message::Text(
+            json!({ "type": "input_audio_buffer.commit" }).to_string(),
+        ))
+    }
+
+    fn parse(&mut self, text: &str) -> Result<Vec<StreamingResult>> {
+        let event: ServerEvent = match serde_json::from_str(text) {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("Ignoring unexpected OpenAI message: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+        match event {
+            ServerEvent::Delta { item_id, delta } => {
+                let partial = self.partial.entry(item_id).or_default();
+                partial.push_str(&delta);
+                Ok(vec![StreamingResult::Interim(partial.clone())])
+            }
+            ServerEvent::Completed {
+                item_id,
+                transcript,
+            } => {
+                self.partial.remove(&item_id);
+                let transcript = transcript.trim().to_string();
+                if transcript.is_empty() {
+                    return Ok(Vec::new());
+                }
+                Ok(vec![StreamingResult::Final(transcript)])
+            }
+            // Committing an empty buffer on stop is harmless
+            ServerEvent::Error { error } if error.message.contains("buffer too small") => {
+                Ok(Vec::new())
+            }
+            ServerEvent::Error { error } => Err(AppError::InvalidInput(format!(
+                "OpenAI error: {}",
+                error.message
+            ))),
+            ServerEvent::Other => Ok(Vec::new()),
+        }
+    }
+}
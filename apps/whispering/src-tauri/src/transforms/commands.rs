@@ -0,0 +1,87 @@
+use super::{
+    FillerPreview, ReplacementRule, RuleTest, TransformConfig, TransformContext, Transforms,
+};
+use crate::error::{AppError, Result};
+use tauri::{AppHandle, Manager, State};
+use tracing::debug;
+
+#[tauri::command]
+pub async fn get_transform_config(transforms: State<'_, Transforms>) -> Result<TransformConfig> {
+    Ok(transforms.config())
+}
+
+#[tauri::command]
+pub async fn set_transform_config(
+    config: TransformConfig,
+    transforms: State<'_, Transforms>,
+) -> Result<()> {
+    debug!("Saving transform settings");
+    transforms.set_config(config)
+}
+
+/// Run a new transcript through the enabled built-in transforms
+///
+/// On a blocking thread, since script hooks among the stages can take up to
+/// their timeout.
+#[tauri::command]
+pub async fn apply_transforms(
+    text: String,
+    context: Option<TransformContext>,
+    app_handle: AppHandle,
+) -> Result<String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app_handle
+            .state::<Transforms>()
+            .apply(&text, &context.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Transform task failed: {}", e)))
+}
+
+/// Show which words filler removal would drop, so it can be tried on real
+/// transcripts before turning it on
+#[tauri::command]
+pub async fn preview_filler_removal(
+    text: String,
+    context: Option<TransformContext>,
+    transforms: State<'_, Transforms>,
+) -> Result<FillerPreview> {
+    Ok(transforms.preview_fillers(&text, &context.unwrap_or_default()))
+}
+
+/// Add a regex replacement rule after the existing ones; returns it with its
+/// new id
+#[tauri::command]
+pub async fn add_rule(
+    rule: ReplacementRule,
+    transforms: State<'_, Transforms>,
+) -> Result<ReplacementRule> {
+    debug!("Adding replacement rule {:?}", rule.pattern);
+    transforms.add_rule(rule)
+}
+
+#[tauri::command]
+pub async fn update_rule(rule: ReplacementRule, transforms: State<'_, Transforms>) -> Result<()> {
+    transforms.update_rule(rule)
+}
+
+#[tauri::command]
+pub async fn remove_rule(id: String, transforms: State<'_, Transforms>) -> Result<()> {
+    transforms.remove_rule(&id)
+}
+
+/// Try a rule, saved or not, on `input`, or on its own test strings if no
+/// input is given
+#[tauri::command]
+pub async fn test_rule(rule: ReplacementRule, input: Option<String>) -> Result<Vec<RuleTest>> {
+    rule.test(input.as_slice())
+}
+
+/// Set the order rules run in; `ids` must list every rule once
+#[tauri::command]
+pub async fn reorder_rules(
+    ids: Vec<String>,
+    transforms: State<'_, Transforms>,
+) -> Result<Vec<ReplacementRule>> {
+    transforms.reorder_rules(&ids)
+}
@@ -0,0 +1,213 @@
+//! Removing filler words ("um", "uh") and accidental repeats ("the the").
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Repeats that are usually intended ("we had had enough")
+const INTENDED_REPEATS: &[&str] = &["had", "that"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FillerConfig {
+    pub enabled: bool,
+    /// Collapse a word said twice in a row into one
+    pub remove_repeats: bool,
+    /// Filler words and phrases by language code. An entry ending in a comma
+    /// only matches where the transcript has one, which tells "so, like,
+    /// yeah" apart from "I like it".
+    pub fillers: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for FillerConfig {
+    fn default() -> Self {
+        let lists: [(&str, &[&str]); 4] = [
+            (
+                "en",
+                &[
+                    "um",
+                    "umm",
+                    "uh",
+                    "uhm",
+                    "er",
+                    "erm",
+                    "ah",
+                    "hmm",
+                    "mm",
+                    "like,",
+                    "you know,",
+                ],
+            ),
+            ("de", &["äh", "ähm", "öh", "öhm", "hm"]),
+            ("fr", &["euh", "ben,", "bah,"]),
+            ("es", &["eh", "em", "este,", "o sea,"]),
+        ];
+        Self {
+            enabled: false,
+            remove_repeats: true,
+            fillers: lists
+                .into_iter()
+                .map(|(language, words)| {
+                    (
+                        language.to_string(),
+                        words.iter().map(|word| word.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A run of the original text, marked if the filter would drop it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewSegment {
+    pub text: String,
+    pub removed: bool,
+}
+
+/// Result of `preview_filler_removal`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillerPreview {
+    /// The text with fillers removed
+    pub result: String,
+    /// The original text split into kept and removed runs, for highlighting
+    pub segments: Vec<PreviewSegment>,
+}
+
+/// A filler entry split into words; `comma` if it must be followed by one
+struct Pattern {
+    words: Vec<String>,
+    comma: bool,
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+impl FillerConfig {
+    /// Patterns for `language` (e.g. `en` or `en-US`), longest first so
+    /// phrases win over their first word
+    fn patterns(&self, language: Option<&str>) -> Vec<Pattern> {
+        let language = language
+            .and_then(|language| language.split(['-', '_']).next())
+            .filter(|language| !language.is_empty() && *language != "auto")
+            .unwrap_or("en")
+            .to_lowercase();
+        let mut patterns: Vec<Pattern> = self
+            .fillers
+            .get(&language)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.trim();
+                let words: Vec<String> = entry.split_whitespace().map(normalize).collect();
+                if words.is_empty() || words.iter().any(|word| word.is_empty()) {
+                    return None;
+                }
+                Some(Pattern {
+                    words,
+                    comma: entry.ends_with(','),
+                })
+            })
+            .collect();
+        patterns.sort_by(|a, b| b.words.len().cmp(&a.words.len()));
+        patterns
+    }
+
+    /// Mark which words of `words` to drop
+    fn removals(&self, words: &[&str], language: Option<&str>) -> Vec<bool> {
+        let patterns = self.patterns(language);
+        let normalized: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+        let mut removed = vec![false; words.len()];
+
+        let mut i = 0;
+        while i < words.len() {
+            let matched = patterns.iter().find(|pattern| {
+                let end = i + pattern.words.len();
+                end <= words.len()
+                    && normalized[i..end] == pattern.words[..]
+                    && (!pattern.comma || words[end - 1].ends_with(','))
+            });
+            match matched {
+                Some(pattern) => {
+                    removed[i..i + pattern.words.len()].fill(true);
+                    i += pattern.words.len();
+                }
+                None => i += 1,
+            }
+        }
+
+        if self.remove_repeats {
+            let mut previous: Option<usize> = None;
+            for i in 0..words.len() {
+                if removed[i] || normalized[i].is_empty() {
+                    continue;
+                }
+                if let Some(p) = previous {
+                    // Only a bare repeat; "end. End" is two sentences
+                    let bare = !words[p].ends_with(|c: char| c.is_ascii_punctuation());
+                    if bare
+                        && normalized[p] == normalized[i]
+                        && !INTENDED_REPEATS.contains(&normalized[i].as_str())
+                    {
+                        removed[p] = true;
+                    }
+                }
+                previous = Some(i);
+            }
+        }
+        removed
+    }
+
+    /// Split `text` into kept and removed runs and build the cleaned text
+    pub fn preview(&self, text: &str, language: Option<&str>) -> FillerPreview {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let removed = self.removals(&words, language);
+
+        let mut segments: Vec<PreviewSegment> = Vec::new();
+        for (word, &removed) in words.iter().zip(&removed) {
+            match segments.last_mut() {
+                Some(segment) if segment.removed == removed => {
+                    segment.text.push(' ');
+                    segment.text.push_str(word);
+                }
+                _ => segments.push(PreviewSegment {
+                    text: word.to_string(),
+                    removed,
+                }),
+            }
+        }
+
+        let mut kept: Vec<String> = Vec::new();
+        let mut capitalize_next = false;
+        for (i, (word, &removed)) in words.iter().zip(&removed).enumerate() {
+            let sentence_start =
+                i == 0 || words[i - 1].ends_with(['.', '?', '!']) || capitalize_next;
+            if removed {
+                // "Um, so we" becomes "So we"
+                capitalize_next = sentence_start;
+                continue;
+            }
+            let mut word = word.to_string();
+            if capitalize_next {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    word = first.to_uppercase().chain(chars).collect();
+                }
+            }
+            capitalize_next = false;
+            kept.push(word);
+        }
+
+        FillerPreview {
+            result: kept.join(" "),
+            segments,
+        }
+    }
+
+    pub fn apply(&self, text: &str, language: Option<&str>) -> String {
+        self.preview(text, language).result
+    }
+}
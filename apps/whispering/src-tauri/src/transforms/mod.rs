@@ -0,0 +1,202 @@
+//! Built-in transforms applied to every transcript before it's stored or
+//! pasted.
+//!
+//! Unlike the user's transformations (LLM prompts, find-and-replace steps),
+//! these are cleanups that run locally and fast enough to always be on:
+//! regex replacement rules, filler removal, number formatting, any stages
+//! registered with [`Transforms::add_stage`] (transform plugins, script
+//! hooks and automation scripts) and the profanity filter, in that order.
+//! The frontend sends each new transcript through `apply_transforms`, and
+//! transcripts made in Rust (watched folders, the inbox, re-transcription)
+//! go through `Transforms::apply` directly. Stages that are turned off pass
+//! text through untouched.
+
+pub mod commands;
+mod fillers;
+mod numbers;
+mod profanity;
+mod rules;
+
+pub use fillers::{FillerConfig, FillerPreview, PreviewSegment};
+pub use numbers::NumberConfig;
+pub use profanity::{ProfanityConfig, ProfanityLevel};
+pub use rules::{ReplacementRule, RuleTest};
+
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+pub const TRANSFORMS_FILE: &str = "transforms.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TransformConfig {
+    /// The user's regex replacements, in the order they run
+    pub rules: Vec<ReplacementRule>,
+    pub fillers: FillerConfig,
+    pub numbers: NumberConfig,
+    pub profanity: ProfanityConfig,
+}
+
+/// What a transcript is being transformed for
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TransformContext {
+    /// Language code of the transcript, e.g. `en` or `de-AT`
+    pub language: Option<String>,
+    /// Active profile, for stages that can be turned off per profile
+    pub profile: Option<String>,
+}
+
+/// A stage contributed from outside the built-in set
+///
+/// Stages run on whatever thread applies transforms, including async
+/// commands, so they must not block for long.
+pub trait TransformStage: Send + Sync {
+    fn apply(&self, text: String, context: &TransformContext) -> String;
+}
+
+pub struct Transforms {
+    path: PathBuf,
+    config: Mutex<TransformConfig>,
+    /// Profanity level forced by the admin policy, over the user's choice
+    forced_profanity: Option<ProfanityLevel>,
+    stages: Mutex<Vec<Arc<dyn TransformStage>>>,
+}
+
+impl Transforms {
+    pub fn open(path: &Path, forced_profanity: Option<ProfanityLevel>) -> Self {
+        let config = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                TransformConfig::default()
+            }),
+            Err(_) => TransformConfig::default(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            config: Mutex::new(config),
+            forced_profanity,
+            stages: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Run `stage` after the built-in cleanups, before profanity filtering
+    pub fn add_stage(&self, stage: Arc<dyn TransformStage>) {
+        if let Ok(mut stages) = self.stages.lock() {
+            stages.push(stage);
+        }
+    }
+
+    pub fn config(&self) -> TransformConfig {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, mut config: TransformConfig) -> Result<()> {
+        for rule in &mut config.rules {
+            rule.compile()?;
+            if rule.id.is_empty() {
+                rule.id = uuid::Uuid::new_v4().to_string();
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&config).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize transform settings: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        *self.config.lock().map_err(|e| {
+            AppError::Internal(format!("Failed to lock transform settings: {}", e))
+        })? = config;
+        Ok(())
+    }
+
+    /// Validate `rule` and add it after the existing rules
+    pub fn add_rule(&self, mut rule: ReplacementRule) -> Result<ReplacementRule> {
+        rule.compile()?;
+        rule.id = uuid::Uuid::new_v4().to_string();
+        let mut config = self.config();
+        config.rules.push(rule.clone());
+        self.set_config(config)?;
+        Ok(rule)
+    }
+
+    /// Replace the rule with `rule.id`, keeping its place in the order
+    pub fn update_rule(&self, rule: ReplacementRule) -> Result<()> {
+        let mut config = self.config();
+        let existing = config
+            .rules
+            .iter_mut()
+            .find(|existing| existing.id == rule.id)
+            .ok_or_else(|| AppError::InvalidInput(format!("No rule with id {}", rule.id)))?;
+        *existing = rule;
+        self.set_config(config)
+    }
+
+    pub fn remove_rule(&self, id: &str) -> Result<()> {
+        let mut config = self.config();
+        config.rules.retain(|rule| rule.id != id);
+        self.set_config(config)
+    }
+
+    /// Put the rules in the order of `ids`, which must list each rule once
+    pub fn reorder_rules(&self, ids: &[String]) -> Result<Vec<ReplacementRule>> {
+        let mut config = self.config();
+        let mut rules = Vec::with_capacity(ids.len());
+        for id in ids {
+            let index = config
+                .rules
+                .iter()
+                .position(|rule| &rule.id == id)
+                .ok_or_else(|| AppError::InvalidInput(format!("No rule with id {}", id)))?;
+            rules.push(config.rules.remove(index));
+        }
+        if !config.rules.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "The new order leaves out {} rules",
+                config.rules.len()
+            )));
+        }
+        config.rules = rules.clone();
+        self.set_config(config)?;
+        Ok(rules)
+    }
+
+    /// Run every enabled stage over `text`. Profanity filtering goes last so
+    /// nothing after it can reintroduce a filtered word.
+    pub fn apply(&self, text: &str, context: &TransformContext) -> String {
+        let config = self.config();
+        let language = context.language.as_deref();
+        let mut text = rules::apply(&config.rules, text);
+        if config.fillers.enabled {
+            text = config.fillers.apply(&text, language);
+        }
+        if config.numbers.enabled_for(context.profile.as_deref()) {
+            text = numbers::normalize(&text, language);
+        }
+        let stages = self
+            .stages
+            .lock()
+            .map(|stages| stages.clone())
+            .unwrap_or_default();
+        for stage in stages {
+            text = stage.apply(text, context);
+        }
+        let level = self.forced_profanity.unwrap_or(config.profanity.level);
+        text = config.profanity.apply(&text, level);
+        text
+    }
+
+    /// What filler removal would do to `text`, whether or not it's enabled
+    pub fn preview_fillers(&self, text: &str, context: &TransformContext) -> FillerPreview {
+        self.config()
+            .fillers
+            .preview(text, context.language.as_deref())
+    }
+}
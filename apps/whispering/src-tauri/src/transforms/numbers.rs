@@ -0,0 +1,814 @@
+//! Spoken numbers, amounts and dates to digits: "twenty three dollars"
+//! becomes "$23" and "march fifth" becomes "March 5".
+//!
+//! English and German are supported; other languages pass through
+//! unchanged. Small numbers on their own stay words ("three ideas", "drei
+//! Ideen") as style guides recommend, while amounts, percentages and dates
+//! always convert.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NumberConfig {
+    pub enabled: bool,
+    /// Overrides `enabled` for the named profiles, e.g. to keep numbers
+    /// spelled out in a "prose" profile
+    pub profiles: BTreeMap<String, bool>,
+}
+
+impl NumberConfig {
+    pub fn enabled_for(&self, profile: Option<&str>) -> bool {
+        profile
+            .and_then(|profile| self.profiles.get(profile).copied())
+            .unwrap_or(self.enabled)
+    }
+}
+
+const EN_UNITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const EN_TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const EN_TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const EN_SCALES: [(&str, u64); 3] = [
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+];
+const EN_ORDINALS: [&str; 19] = [
+    "first",
+    "second",
+    "third",
+    "fourth",
+    "fifth",
+    "sixth",
+    "seventh",
+    "eighth",
+    "ninth",
+    "tenth",
+    "eleventh",
+    "twelfth",
+    "thirteenth",
+    "fourteenth",
+    "fifteenth",
+    "sixteenth",
+    "seventeenth",
+    "eighteenth",
+    "nineteenth",
+];
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const DE_SMALL: [&str; 20] = [
+    "null",
+    "eins",
+    "zwei",
+    "drei",
+    "vier",
+    "fünf",
+    "sechs",
+    "sieben",
+    "acht",
+    "neun",
+    "zehn",
+    "elf",
+    "zwölf",
+    "dreizehn",
+    "vierzehn",
+    "fünfzehn",
+    "sechzehn",
+    "siebzehn",
+    "achtzehn",
+    "neunzehn",
+];
+const DE_TENS: [&str; 8] = [
+    "zwanzig", "dreißig", "vierzig", "fünfzig", "sechzig", "siebzig", "achtzig", "neunzig",
+];
+const DE_SCALES: [(&str, u64); 4] = [
+    ("million", 1_000_000),
+    ("millionen", 1_000_000),
+    ("milliarde", 1_000_000_000),
+    ("milliarden", 1_000_000_000),
+];
+const DE_MONTHS: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+
+fn position(words: &[&str], word: &str) -> Option<u64> {
+    words
+        .iter()
+        .position(|candidate| *candidate == word)
+        .map(|i| i as u64)
+}
+
+/// A word of the transcript with the punctuation around it split off
+struct Token<'a> {
+    lead: &'a str,
+    core: &'a str,
+    trail: &'a str,
+    /// Byte range of `core` in the transcript
+    range: Range<usize>,
+    /// `core` lowercased
+    word: String,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    text.split_whitespace()
+        .map(|original| {
+            let rest = original.trim_start_matches(|c: char| !c.is_alphanumeric());
+            let core = rest.trim_end_matches(|c: char| !c.is_alphanumeric());
+            let lead = &original[..original.len() - rest.len()];
+            // `original` is a slice of `text`, so this is its offset there
+            let start = original.as_ptr() as usize - text.as_ptr() as usize + lead.len();
+            Token {
+                lead,
+                core,
+                trail: &rest[core.len()..],
+                range: start..start + core.len(),
+                word: core.to_lowercase(),
+            }
+        })
+        .collect()
+}
+
+/// Whether `tokens[k]` continues the phrase before it, i.e. there's no
+/// punctuation between them
+fn joined(tokens: &[Token], k: usize) -> bool {
+    k > 0 && k < tokens.len() && tokens[k - 1].trail.is_empty() && tokens[k].lead.is_empty()
+}
+
+/// A phrase found at some position, ending before token `end`
+struct Match {
+    end: usize,
+    /// The replacement, or `None` to keep the tokens as they are
+    text: Option<String>,
+}
+
+impl Match {
+    fn replace(text: String, end: usize) -> Option<Self> {
+        Some(Self {
+            end,
+            text: Some(text),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Locale {
+    English,
+    German,
+}
+
+impl Locale {
+    fn from_language(language: Option<&str>) -> Option<Self> {
+        let language = language
+            .and_then(|language| language.split(['-', '_']).next())
+            .unwrap_or("")
+            .to_lowercase();
+        match language.as_str() {
+            "" | "auto" | "en" => Some(Locale::English),
+            "de" => Some(Locale::German),
+            _ => None,
+        }
+    }
+
+    /// Numbers below this stay words unless they're an amount or date
+    fn spell_below(self) -> u64 {
+        match self {
+            Locale::English => 10,
+            // Duden writes out numbers up to twelve
+            Locale::German => 13,
+        }
+    }
+
+    /// Digits with thousands grouped the local way for long numbers
+    /// ("12,500", "12.500"); four-digit numbers stay ungrouped so years read
+    /// naturally, except in amounts
+    fn format(self, value: u64, amount: bool) -> String {
+        let digits = value.to_string();
+        if value < 1_000 || (value < 10_000 && !amount) {
+            return digits;
+        }
+        let separator = match self {
+            Locale::English => ',',
+            Locale::German => '.',
+        };
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(separator);
+            }
+            grouped.push(c);
+        }
+        grouped
+    }
+
+    fn find(self, tokens: &[Token], i: usize) -> Option<Match> {
+        match self {
+            Locale::English => english(tokens, i),
+            Locale::German => german(tokens, i),
+        }
+    }
+}
+
+/// Rewrite spoken numbers, amounts and dates in `text` as digits. Only the
+/// phrases replaced change; spacing, line breaks and punctuation around them
+/// are kept.
+pub fn normalize(text: &str, language: Option<&str>) -> String {
+    let Some(locale) = Locale::from_language(language) else {
+        return text.to_string();
+    };
+    let tokens = tokenize(text);
+    let mut normalized = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        match locale.find(&tokens, i) {
+            Some(Match {
+                end,
+                text: Some(replacement),
+            }) => {
+                normalized.push_str(&text[copied..tokens[i].range.start]);
+                normalized.push_str(&replacement);
+                copied = tokens[end - 1].range.end;
+                i = end;
+            }
+            Some(Match { end, text: None }) => i = end,
+            None => i += 1,
+        }
+    }
+    normalized.push_str(&text[copied..]);
+    normalized
+}
+
+/// End of the run of number words starting at `i`
+fn number_run_end(tokens: &[Token], i: usize, is_number: impl Fn(&str) -> bool) -> usize {
+    let mut k = i;
+    while k < tokens.len() && (k == i || joined(tokens, k)) && is_number(&tokens[k].word) {
+        k += 1;
+    }
+    k
+}
+
+// English
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Last {
+    #[default]
+    Start,
+    Zero,
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+    And,
+    A,
+}
+
+/// Reads an English cardinal one word at a time, rejecting words that
+/// can't continue it ("three two")
+#[derive(Debug, Clone, Copy, Default)]
+struct EnglishCardinal {
+    /// Sum of finished thousands, millions, ...
+    total: u64,
+    /// The part below the last scale word
+    current: u64,
+    /// Last scale word used, so "thousand million" isn't read as a number
+    scale: u64,
+    last: Last,
+}
+
+impl EnglishCardinal {
+    fn push(&mut self, word: &str) -> bool {
+        let open = matches!(
+            self.last,
+            Last::Start | Last::Hundred | Last::Scale | Last::And
+        );
+        if let Some(n) = position(&EN_UNITS, word) {
+            if n == 0 {
+                if self.last != Last::Start {
+                    return false;
+                }
+                self.last = Last::Zero;
+                return true;
+            }
+            if !open && self.last != Last::Tens {
+                return false;
+            }
+            self.current += n;
+            self.last = Last::Unit;
+        } else if let Some(n) = position(&EN_TEENS, word) {
+            if !open {
+                return false;
+            }
+            self.current += 10 + n;
+            self.last = Last::Teen;
+        } else if let Some(n) = position(&EN_TENS, word) {
+            if !open {
+                return false;
+            }
+            self.current += (n + 2) * 10;
+            self.last = Last::Tens;
+        } else if word == "hundred" {
+            if !matches!(self.last, Last::Unit | Last::Teen | Last::A) {
+                return false;
+            }
+            self.current *= 100;
+            self.last = Last::Hundred;
+        } else if let Some(&(_, scale)) = EN_SCALES.iter().find(|(name, _)| *name == word) {
+            let counted = matches!(
+                self.last,
+                Last::Unit | Last::Teen | Last::Tens | Last::Hundred | Last::A
+            );
+            if !counted || (self.scale != 0 && scale >= self.scale) {
+                return false;
+            }
+            self.total += self.current * scale;
+            self.current = 0;
+            self.scale = scale;
+            self.last = Last::Scale;
+        } else if word == "and" {
+            if !matches!(self.last, Last::Hundred | Last::Scale) {
+                return false;
+            }
+            self.last = Last::And;
+        } else if word == "a" {
+            if self.last != Last::Start {
+                return false;
+            }
+            self.current = 1;
+            self.last = Last::A;
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn complete(&self) -> bool {
+        !matches!(self.last, Last::Start | Last::And | Last::A)
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+fn is_english_number(word: &str) -> bool {
+    word.split('-').all(|part| {
+        EN_UNITS.contains(&part)
+            || EN_TEENS.contains(&part)
+            || EN_TENS.contains(&part)
+            || part == "hundred"
+            || EN_SCALES.iter().any(|(name, _)| *name == part)
+    })
+}
+
+/// The number at `i`, where it ends, and whether it was spoken rather than
+/// already digits
+fn english_cardinal(tokens: &[Token], i: usize) -> Option<(u64, usize, bool)> {
+    if let Ok(value) = tokens[i].word.parse::<u64>() {
+        return Some((value, i + 1, false));
+    }
+    let mut state = EnglishCardinal::default();
+    let mut found = None;
+    let mut k = i;
+    while k < tokens.len() && (k == i || joined(tokens, k)) {
+        let mut next = state;
+        if !tokens[k].word.split('-').all(|part| next.push(part)) {
+            break;
+        }
+        state = next;
+        k += 1;
+        if state.complete() {
+            found = Some((state.value(), k, true));
+        }
+    }
+    found
+}
+
+fn english_ordinal(word: &str) -> Option<u64> {
+    if let Some(n) = position(&EN_ORDINALS, word) {
+        return Some(n + 1);
+    }
+    match word {
+        "twentieth" => return Some(20),
+        "thirtieth" => return Some(30),
+        _ => {}
+    }
+    ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .and_then(|digits| digits.parse().ok())
+}
+
+/// A day of the month at `k`: "fifth", "twenty fifth", "twenty-fifth", "5th"
+fn english_day(tokens: &[Token], k: usize) -> Option<(u64, usize)> {
+    let parts: Vec<&str> = tokens[k].word.split('-').collect();
+    let tens = |word: &str| position(&EN_TENS, word).map(|n| (n + 2) * 10);
+    let below_ten = |word: &str| english_ordinal(word).filter(|n| *n < 10);
+    let (day, end) = match parts[..] {
+        [word] => match english_ordinal(word) {
+            Some(day) => (day, k + 1),
+            None if joined(tokens, k + 1) => (tens(word)? + below_ten(&tokens[k + 1].word)?, k + 2),
+            None => return None,
+        },
+        [ten, unit] => (tens(ten)? + below_ten(unit)?, k + 1),
+        _ => return None,
+    };
+    (1..=31).contains(&day).then_some((day, end))
+}
+
+fn english_month(token: &Token) -> Option<&'static str> {
+    let month = EN_MONTHS
+        .iter()
+        .find(|month| month.eq_ignore_ascii_case(&token.word))?;
+    // "I may first check" isn't a date; transcribers capitalize the month
+    if *month == "May" && !token.core.starts_with('M') {
+        return None;
+    }
+    Some(month)
+}
+
+fn english(tokens: &[Token], i: usize) -> Option<Match> {
+    // "march fifth", "march the fifth"
+    if let Some(month) = english_month(&tokens[i]) {
+        let mut k = i + 1;
+        if joined(tokens, k) && tokens[k].word == "the" {
+            k += 1;
+        }
+        if joined(tokens, k) {
+            if let Some((day, end)) = english_day(tokens, k) {
+                return Match::replace(format!("{} {}", month, day), end);
+            }
+        }
+    }
+    // "the fifth of march"
+    if tokens[i].word == "the" && joined(tokens, i + 1) {
+        if let Some((day, k)) = english_day(tokens, i + 1) {
+            if joined(tokens, k) && tokens[k].word == "of" && joined(tokens, k + 1) {
+                if let Some(month) = english_month(&tokens[k + 1]) {
+                    return Match::replace(format!("{} {}", month, day), k + 2);
+                }
+            }
+        }
+    }
+
+    let (value, end, spoken) = english_cardinal(tokens, i)?;
+    let locale = Locale::English;
+    let next = |k: usize| joined(tokens, k).then(|| tokens[k].word.as_str());
+
+    let symbol = match next(end) {
+        Some("dollar" | "dollars" | "buck" | "bucks") => Some("$"),
+        Some("euro" | "euros") => Some("€"),
+        _ => None,
+    };
+    if let Some(symbol) = symbol {
+        let amount = locale.format(value, true);
+        // "and fifty cents"
+        if next(end + 1) == Some("and") && joined(tokens, end + 2) {
+            if let Some((cents, k, _)) = english_cardinal(tokens, end + 2) {
+                if cents < 100 && matches!(next(k), Some("cent" | "cents")) {
+                    return Match::replace(format!("{}{}.{:02}", symbol, amount, cents), k + 1);
+                }
+            }
+        }
+        return Match::replace(format!("{}{}", symbol, amount), end + 1);
+    }
+    match (next(end), next(end + 1)) {
+        (Some("percent"), _) => {
+            return Match::replace(format!("{}%", locale.format(value, false)), end + 1)
+        }
+        (Some("per"), Some("cent")) => {
+            return Match::replace(format!("{}%", locale.format(value, false)), end + 2)
+        }
+        _ => {}
+    }
+
+    if !spoken {
+        return None;
+    }
+    // Part of a run we can't read as one number, like "twenty twenty four";
+    // leave all of it alone rather than splitting it up
+    let run_end = number_run_end(tokens, i, is_english_number);
+    if end < run_end {
+        return Some(Match {
+            end: run_end,
+            text: None,
+        });
+    }
+    (value >= locale.spell_below()).then(|| Match {
+        end,
+        text: Some(locale.format(value, false)),
+    })
+}
+
+// German
+
+fn german_unit(word: &str) -> Option<u64> {
+    match word {
+        "ein" | "eins" | "eine" => Some(1),
+        _ => position(&DE_SMALL[2..10], word).map(|n| n + 2),
+    }
+}
+
+fn german_below_hundred(word: &str) -> Option<u64> {
+    if let Some(n) = german_unit(word).or_else(|| position(&DE_SMALL, word)) {
+        return Some(n);
+    }
+    if let Some(n) = position(&DE_TENS, word) {
+        return Some((n + 2) * 10);
+    }
+    // "dreiundzwanzig"
+    let (unit, tens) = word.split_once("und")?;
+    let unit = match unit {
+        "eins" | "eine" => return None,
+        _ => german_unit(unit)?,
+    };
+    Some(unit + (position(&DE_TENS, tens)? + 2) * 10)
+}
+
+fn german_below_thousand(word: &str) -> Option<u64> {
+    let Some((hundreds, rest)) = word.split_once("hundert") else {
+        return german_below_hundred(word);
+    };
+    let hundreds = match hundreds {
+        "" => 1,
+        _ => german_unit(hundreds)?,
+    };
+    let rest = match rest.strip_prefix("und").unwrap_or(rest) {
+        "" => 0,
+        rest => german_below_hundred(rest)?,
+    };
+    Some(hundreds * 100 + rest)
+}
+
+/// A number written as one word, like "zweitausenddreihundertvierzig"
+fn german_below_million(word: &str) -> Option<u64> {
+    let Some((thousands, rest)) = word.split_once("tausend") else {
+        return german_below_thousand(word);
+    };
+    let thousands = match thousands {
+        "" => 1,
+        _ => german_below_thousand(thousands)?,
+    };
+    let rest = match rest.strip_prefix("und").unwrap_or(rest) {
+        "" => 0,
+        rest => german_below_thousand(rest)?,
+    };
+    Some(thousands * 1_000 + rest)
+}
+
+fn german_scale(word: &str) -> Option<u64> {
+    DE_SCALES
+        .iter()
+        .find(|(name, _)| *name == word)
+        .map(|&(_, scale)| scale)
+}
+
+fn is_german_number(word: &str) -> bool {
+    german_below_million(word).is_some() || german_scale(word).is_some()
+}
+
+/// The number at `i`, where it ends, and whether it was spoken rather than
+/// already digits. Millions and above are separate words ("zwei Millionen
+/// dreihunderttausend").
+fn german_cardinal(tokens: &[Token], i: usize) -> Option<(u64, usize, bool)> {
+    if let Ok(value) = tokens[i].word.parse::<u64>() {
+        return Some((value, i + 1, false));
+    }
+    let mut total = 0;
+    let mut limit = u64::MAX;
+    let mut k = i;
+    while k < tokens.len() && (k == i || joined(tokens, k)) {
+        let Some(value) = german_below_million(&tokens[k].word) else {
+            break;
+        };
+        let scale = joined(tokens, k + 1)
+            .then(|| german_scale(&tokens[k + 1].word))
+            .flatten();
+        match scale {
+            Some(scale) if scale < limit => {
+                total += value * scale;
+                limit = scale;
+                k += 2;
+            }
+            _ => {
+                total += value;
+                k += 1;
+                break;
+            }
+        }
+    }
+    (k > i).then_some((total, k, true))
+}
+
+/// A day of the month like "fünfte", "fünften" or "einundzwanzigsten"
+fn german_ordinal(word: &str) -> Option<u64> {
+    let stem = ["en", "er", "es", "em", "e"]
+        .iter()
+        .find_map(|ending| word.strip_suffix(ending))?;
+    let day = match stem {
+        "erst" => 1,
+        "dritt" => 3,
+        "siebt" | "siebent" => 7,
+        "acht" => 8,
+        _ => stem
+            .strip_suffix("st")
+            .and_then(german_below_hundred)
+            .filter(|n| *n >= 20)
+            .or_else(|| {
+                stem.strip_suffix('t')
+                    .and_then(german_below_hundred)
+                    .filter(|n| (2..20).contains(n))
+            })?,
+    };
+    (1..=31).contains(&day).then_some(day)
+}
+
+fn german_month(word: &str) -> Option<&'static str> {
+    if word == "jänner" {
+        return Some(DE_MONTHS[0]);
+    }
+    DE_MONTHS
+        .iter()
+        .find(|month| month.to_lowercase() == word)
+        .copied()
+}
+
+fn german(tokens: &[Token], i: usize) -> Option<Match> {
+    // "fünfter März", "am fünften März"
+    if let Some(day) = german_ordinal(&tokens[i].word) {
+        if joined(tokens, i + 1) {
+            if let Some(month) = german_month(&tokens[i + 1].word) {
+                return Match::replace(format!("{}. {}", day, month), i + 2);
+            }
+        }
+    }
+
+    let (value, end, spoken) = german_cardinal(tokens, i)?;
+    let locale = Locale::German;
+    let next = |k: usize| joined(tokens, k).then(|| tokens[k].word.as_str());
+
+    let symbol = match next(end) {
+        Some("euro") => Some("€"),
+        Some("dollar") => Some("$"),
+        Some("prozent") => Some("%"),
+        _ => None,
+    };
+    if let Some(symbol) = symbol {
+        let amount = locale.format(value, symbol != "%");
+        // "und fünfzig Cent"
+        if symbol != "%" && next(end + 1) == Some("und") && joined(tokens, end + 2) {
+            if let Some((cents, k, _)) = german_cardinal(tokens, end + 2) {
+                if cents < 100 && next(k) == Some("cent") {
+                    return Match::replace(format!("{},{:02} {}", amount, cents, symbol), k + 1);
+                }
+            }
+        }
+        return Match::replace(format!("{} {}", amount, symbol), end + 1);
+    }
+
+    if !spoken {
+        return None;
+    }
+    let run_end = number_run_end(tokens, i, is_german_number);
+    if end < run_end {
+        return Some(Match {
+            end: run_end,
+            text: None,
+        });
+    }
+    (value >= locale.spell_below()).then(|| Match {
+        end,
+        text: Some(locale.format(value, false)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+
+    fn english(text: &str) -> String {
+        normalize(text, Some("en"))
+    }
+
+    fn german(text: &str) -> String {
+        normalize(text, Some("de"))
+    }
+
+    #[test]
+    fn english_amounts() {
+        assert_eq!(english("twenty three dollars"), "$23");
+        assert_eq!(
+            english("It costs twenty three dollars and fifty cents."),
+            "It costs $23.50."
+        );
+        assert_eq!(english("about twenty five percent"), "about 25%");
+        assert_eq!(
+            english("twelve thousand five hundred people"),
+            "12,500 people"
+        );
+    }
+
+    #[test]
+    fn english_dates_and_ordinals() {
+        assert_eq!(
+            english("Let's meet on march fifth."),
+            "Let's meet on March 5."
+        );
+        assert_eq!(english("the twenty-first of May"), "May 21");
+        assert_eq!(english("due march 5th"), "due March 5");
+    }
+
+    #[test]
+    fn english_leaves_the_rest_alone() {
+        assert_eq!(english("I have three ideas"), "I have three ideas");
+        assert_eq!(
+            english("back in twenty twenty four"),
+            "back in twenty twenty four"
+        );
+        assert_eq!(
+            normalize("vingt-trois euros", Some("fr")),
+            "vingt-trois euros"
+        );
+        assert_eq!(
+            english("  two  spaces\nand a line  "),
+            "  two  spaces\nand a line  "
+        );
+    }
+
+    #[test]
+    fn english_keeps_whitespace_around_replacements() {
+        assert_eq!(
+            english("Line one:\n  twenty five percent\tdone  "),
+            "Line one:\n  25%\tdone  "
+        );
+    }
+
+    #[test]
+    fn german_amounts() {
+        assert_eq!(
+            german("Das kostet dreiundzwanzig Euro und fünfzig Cent."),
+            "Das kostet 23,50 €."
+        );
+        assert_eq!(german("zwanzig Prozent"), "20 %");
+        assert_eq!(
+            german("zweitausenddreihundertvierzig Besucher"),
+            "2340 Besucher"
+        );
+    }
+
+    #[test]
+    fn german_dates_and_ordinals() {
+        assert_eq!(
+            german("Wir treffen uns am fünften März."),
+            "Wir treffen uns am 5. März."
+        );
+        assert_eq!(german("einundzwanzigster Juni"), "21. Juni");
+    }
+
+    #[test]
+    fn german_leaves_the_rest_alone() {
+        assert_eq!(german("Ich habe drei Ideen"), "Ich habe drei Ideen");
+        assert_eq!(german("Summe:\n\tzwölf Euro"), "Summe:\n\t12 €");
+    }
+}
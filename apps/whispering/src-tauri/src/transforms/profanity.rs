@@ -0,0 +1,119 @@
+//! Masking or removing profanity, e.g. for workplace deployments.
+//!
+//! Words match whole, so "class" never trips over its middle; a list entry
+//! ending in `*` also matches longer forms ("fuck*" covers "fucking").
+
+use serde::{Deserialize, Serialize};
+
+/// Words filtered out of the box, in English and German
+const DEFAULT_WORDS: &[&str] = &[
+    "arse",
+    "ass",
+    "asshole*",
+    "bastard*",
+    "bitch*",
+    "bollocks",
+    "bullshit*",
+    "cock",
+    "cocks",
+    "cunt*",
+    "dick",
+    "dickhead*",
+    "fuck*",
+    "motherfuck*",
+    "piss*",
+    "prick",
+    "pricks",
+    "shit*",
+    "slut*",
+    "twat*",
+    "wank*",
+    "whore*",
+    "arschloch*",
+    "fick*",
+    "fotze*",
+    "hure*",
+    "scheiß*",
+    "scheiss*",
+    "wichser*",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProfanityLevel {
+    /// Leave transcripts as they are
+    #[default]
+    Keep,
+    /// Keep the first letter and star the rest: "f***"
+    Mask,
+    /// Drop the word entirely
+    Remove,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfanityConfig {
+    pub level: ProfanityLevel,
+    /// Filtered on top of the built-in list
+    pub words: Vec<String>,
+    /// Words to let through even though a list matches them, in the same
+    /// format
+    pub allowed: Vec<String>,
+}
+
+fn matches(entry: &str, word: &str) -> bool {
+    match entry.strip_suffix('*') {
+        Some(prefix) => word.starts_with(prefix),
+        None => word == entry,
+    }
+}
+
+impl ProfanityConfig {
+    fn is_profane(&self, word: &str) -> bool {
+        let listed = |entry: &str| matches(&entry.trim().to_lowercase(), word);
+        let profane = DEFAULT_WORDS.iter().any(|entry| listed(entry))
+            || self.words.iter().any(|entry| listed(entry));
+        profane && !self.allowed.iter().any(|entry| listed(entry))
+    }
+
+    /// Filter `text` at `level`, which the policy may force over the
+    /// configured one
+    pub fn apply(&self, text: &str, level: ProfanityLevel) -> String {
+        if level == ProfanityLevel::Keep {
+            return text.to_string();
+        }
+        let mut words: Vec<String> = Vec::new();
+        let mut changed = false;
+        for original in text.split_whitespace() {
+            let rest = original.trim_start_matches(|c: char| !c.is_alphanumeric());
+            let core = rest.trim_end_matches(|c: char| !c.is_alphanumeric());
+            if core.is_empty() || !self.is_profane(&core.to_lowercase()) {
+                words.push(original.to_string());
+                continue;
+            }
+            changed = true;
+            let lead = &original[..original.len() - rest.len()];
+            let trail = &rest[core.len()..];
+            match level {
+                ProfanityLevel::Mask => {
+                    let mut chars = core.chars();
+                    let first = chars.next().map(String::from).unwrap_or_default();
+                    let stars = "*".repeat(chars.count());
+                    words.push(format!("{}{}{}{}", lead, first, stars, trail));
+                }
+                // Hand the punctuation to the word before, so a removed word
+                // doesn't take the end of the sentence with it
+                ProfanityLevel::Remove | ProfanityLevel::Keep => {
+                    if let Some(previous) = words.last_mut() {
+                        previous.push_str(trail);
+                    }
+                }
+            }
+        }
+        if changed {
+            words.join(" ")
+        } else {
+            text.to_string()
+        }
+    }
+}
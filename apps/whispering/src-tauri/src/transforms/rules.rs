@@ -0,0 +1,95 @@
+//! User-defined regex replacements, such as `\bbtw\b` → "by the way".
+//!
+//! Rules run in order, each on the previous one's output, so a later rule
+//! can build on an earlier one. Replacements use the `regex` crate's syntax:
+//! `$1` or `${name}` for capture groups and `$$` for a literal dollar sign.
+
+use crate::error::{AppError, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementRule {
+    /// Assigned by `add_rule`
+    #[serde(default)]
+    pub id: String,
+    pub pattern: String,
+    pub replacement: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Sample inputs kept with the rule, to check it still does what's
+    /// intended after editing
+    #[serde(default)]
+    pub tests: Vec<String>,
+}
+
+impl ReplacementRule {
+    pub fn compile(&self) -> Result<Regex> {
+        if self.pattern.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Rule pattern can't be empty".to_string(),
+            ));
+        }
+        RegexBuilder::new(&self.pattern)
+            .case_insensitive(self.case_insensitive)
+            .build()
+            .map_err(|e| {
+                AppError::InvalidInput(format!("Invalid pattern {:?}: {}", self.pattern, e))
+            })
+    }
+
+    /// Run the rule over each of `inputs`, or its own test strings if none
+    /// are given
+    pub fn test(&self, inputs: &[String]) -> Result<Vec<RuleTest>> {
+        let regex = self.compile()?;
+        let inputs = if inputs.is_empty() {
+            &self.tests
+        } else {
+            inputs
+        };
+        Ok(inputs
+            .iter()
+            .map(|input| RuleTest {
+                input: input.clone(),
+                output: regex
+                    .replace_all(input, self.replacement.as_str())
+                    .into_owned(),
+                matches: regex.find_iter(input).count(),
+            })
+            .collect())
+    }
+}
+
+/// What a rule does to one input, from `test_rule`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTest {
+    pub input: String,
+    pub output: String,
+    pub matches: usize,
+}
+
+/// Run the enabled `rules` over `text` in order. Rules that no longer
+/// compile are skipped rather than failing the whole transcript.
+pub fn apply(rules: &[ReplacementRule], text: &str) -> String {
+    let mut text = text.to_string();
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        match rule.compile() {
+            Ok(regex) => {
+                text = regex
+                    .replace_all(&text, rule.replacement.as_str())
+                    .into_owned();
+            }
+            Err(e) => warn!("Skipping replacement rule {}: {}", rule.id, e),
+        }
+    }
+    text
+}
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use tracing::debug;
+
+/// Which implementation is drawing the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayBackend {
+    /// Tauri's built-in tray, created by the frontend
+    Native,
+    /// Our own StatusNotifierItem, registered directly over D-Bus (Linux only)
+    StatusNotifier,
+    /// No tray can be shown in this environment
+    Unavailable,
+}
+
+/// Result of probing the desktop environment for a usable tray
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayCapability {
+    pub supported: bool,
+    pub backend: TrayBackend,
+    /// Why the tray is (or isn't) available, for display in settings
+    pub reason: String,
+    /// What the user can do to get a tray, when there isn't one
+    pub fallback_advice: Option<String>,
+}
+
+impl TrayCapability {
+    pub fn available(backend: TrayBackend, reason: impl Into<String>) -> Self {
+        Self {
+            supported: true,
+            backend,
+            reason: reason.into(),
+            fallback_advice: None,
+        }
+    }
+
+    pub fn unavailable(reason: impl Into<String>, advice: impl Into<String>) -> Self {
+        Self {
+            supported: false,
+            backend: TrayBackend::Unavailable,
+            reason: reason.into(),
+            fallback_advice: Some(advice.into()),
+        }
+    }
+}
+
+/// Probe the current environment and pick a tray implementation
+///
+/// Tauri's Linux tray goes through libappindicator, which is loaded at runtime
+/// and silently shows nothing when the library is missing. KDE and GNOME (with
+/// the AppIndicator extension) both host StatusNotifierItems natively, so in
+/// that case we register one ourselves instead.
+#[cfg(target_os = "linux")]
+pub fn probe() -> TrayCapability {
+    if let Ok(value) = std::env::var("WHISPERING_TRAY_BACKEND") {
+        match value.as_str() {
+            "native" => {
+                return TrayCapability::available(
+                    TrayBackend::Native,
+                    "Forced by WHISPERING_TRAY_BACKEND",
+                )
+            }
+            "sni" => {
+                return TrayCapability::available(
+                    TrayBackend::StatusNotifier,
+                    "Forced by WHISPERING_TRAY_BACKEND",
+                )
+            }
+            _ => {}
+        }
+    }
+
+    let has_host = has_status_notifier_host();
+    let has_appindicator = has_appindicator_library();
+    debug!(
+        "Tray probe: status notifier host = {}, appindicator = {}",
+        has_host, has_appindicator
+    );
+
+    match (has_host, has_appindicator) {
+        (false, _) => TrayCapability::unavailable(
+            "No StatusNotifier host is running on the session bus",
+            "On GNOME, install the \"AppIndicator and KStatusNotifierItem Support\" \
+             extension. Other desktops need a system tray applet or panel that \
+             supports StatusNotifierItem. Global shortcuts keep working without a tray.",
+        ),
+        (true, true) => TrayCapability::available(
+            TrayBackend::Native,
+            "StatusNotifier host and libappindicator are available",
+        ),
+        (true, false) => TrayCapability::available(
+            TrayBackend::StatusNotifier,
+            "libappindicator is not installed; using a built-in StatusNotifierItem",
+        ),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn probe() -> TrayCapability {
+    if has_gui_session() {
+        TrayCapability::available(TrayBackend::Native, "Menu bar is available")
+    } else {
+        TrayCapability::unavailable(
+            "Not running in a logged-in GUI session, so there is no menu bar",
+            "Launch Whispering from the desktop rather than over SSH or as a background service.",
+        )
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn probe() -> TrayCapability {
+    if has_shell_tray() {
+        TrayCapability::available(
+            TrayBackend::Native,
+            "Windows notification area is available",
+        )
+    } else {
+        TrayCapability::unavailable(
+            "The Windows shell (Explorer) taskbar is not running",
+            "Restart Explorer or use the main window; the tray icon appears once the taskbar is back.",
+        )
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn probe() -> TrayCapability {
+    TrayCapability::unavailable(
+        "System tray is not supported on this platform",
+        "Use the main window to control recording.",
+    )
+}
+
+/// Whether a StatusNotifierWatcher (the tray host) owns its name on the session bus
+#[cfg(target_os = "linux")]
+pub fn has_status_notifier_host() -> bool {
+    let probe = || -> zbus::Result<bool> {
+        let connection = zbus::blocking::Connection::session()?;
+        let proxy = zbus::blocking::fdo::DBusProxy::new(&connection)?;
+        let name = zbus::names::BusName::try_from("org.kde.StatusNotifierWatcher")?;
+        Ok(proxy.name_has_owner(name)?)
+    };
+    match probe() {
+        Ok(has_owner) => has_owner,
+        Err(e) => {
+            debug!("Failed to query session bus for a tray host: {}", e);
+            false
+        }
+    }
+}
+
+/// Whether one of the libraries Tauri's tray loads at runtime is installed
+#[cfg(target_os = "linux")]
+pub fn has_appindicator_library() -> bool {
+    ["libayatana-appindicator3.so.1", "libappindicator3.so.1"]
+        .iter()
+        .any(|name| {
+            // Safety: these libraries have no initialisers with side effects;
+            // the handle is dropped immediately after the probe.
+            unsafe { libloading::Library::new(name) }.is_ok()
+        })
+}
+
+/// Whether we're attached to a window server session (and so have a menu bar)
+#[cfg(target_os = "macos")]
+fn has_gui_session() -> bool {
+    use core_foundation_sys::base::CFRelease;
+    use core_foundation_sys::dictionary::CFDictionaryRef;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
+    }
+
+    // Returns NULL when the process isn't running inside a login session
+    let session = unsafe { CGSessionCopyCurrentDictionary() };
+    if session.is_null() {
+        return false;
+    }
+    unsafe { CFRelease(session as _) };
+    true
+}
+
+/// Whether Explorer's taskbar (which hosts the notification area) exists
+#[cfg(target_os = "windows")]
+fn has_shell_tray() -> bool {
+    use windows_sys::Win32::UI::WindowsAndMessaging::FindWindowW;
+
+    let class: Vec<u16> = "Shell_TrayWnd\0".encode_utf16().collect();
+    let hwnd = unsafe { FindWindowW(class.as_ptr(), std::ptr::null()) };
+    !hwnd.is_null()
+}
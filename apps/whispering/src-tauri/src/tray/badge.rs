@@ -0,0 +1,105 @@
+use image::{Rgba, RgbaImage};
+
+/// Badge fill colour (system-notification red)
+const BADGE_COLOR: Rgba<u8> = Rgba([230, 57, 53, 255]);
+const TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// 3x5 bitmap glyphs for the badge label, one row per byte (low 3 bits)
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+fn glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => return None,
+    })
+}
+
+/// Label shown in the badge; large counts collapse to "9+" to stay legible
+pub fn badge_label(count: u32) -> String {
+    if count > 9 {
+        "9+".to_string()
+    } else {
+        count.to_string()
+    }
+}
+
+/// Draw a numbered badge in the top-right corner of a tray icon
+pub fn draw_badge(icon: &mut RgbaImage, count: u32) {
+    if count == 0 {
+        return;
+    }
+
+    let (width, height) = icon.dimensions();
+    let size = width.min(height);
+    let diameter = (size as f32 * 0.6).max(6.0);
+    let radius = diameter / 2.0;
+    let center_x = width as f32 - radius;
+    let center_y = radius;
+
+    // Filled circle with a one pixel anti-aliased edge
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let coverage = (radius - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                blend(icon.get_pixel_mut(x, y), BADGE_COLOR, coverage);
+            }
+        }
+    }
+
+    let label = badge_label(count);
+    let glyph_count = label.chars().count() as u32;
+    let text_columns = glyph_count * GLYPH_WIDTH + glyph_count.saturating_sub(1);
+    let scale = ((diameter * 0.6 / text_columns as f32)
+        .min(diameter * 0.55 / GLYPH_HEIGHT as f32)
+        .floor() as u32)
+        .max(1);
+
+    let text_width = text_columns * scale;
+    let text_height = GLYPH_HEIGHT * scale;
+    let origin_x = (center_x - text_width as f32 / 2.0).round().max(0.0) as u32;
+    let origin_y = (center_y - text_height as f32 / 2.0).round().max(0.0) as u32;
+
+    for (i, c) in label.chars().enumerate() {
+        let Some(rows) = glyph(c) else { continue };
+        let glyph_x = origin_x + i as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        let x = glyph_x + col * scale + sx;
+                        let y = origin_y + row as u32 * scale + sy;
+                        if x < width && y < height {
+                            icon.put_pixel(x, y, TEXT_COLOR);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Source-over blend of `color` onto `pixel` with the given coverage
+pub(super) fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>, coverage: f32) {
+    let alpha = coverage * color[3] as f32 / 255.0;
+    for channel in 0..3 {
+        pixel[channel] =
+            (color[channel] as f32 * alpha + pixel[channel] as f32 * (1.0 - alpha)).round() as u8;
+    }
+    pixel[3] = (255.0 * alpha + pixel[3] as f32 * (1.0 - alpha)).round() as u8;
+}
@@ -0,0 +1,52 @@
+use crate::error::Result;
+use crate::tray::{TrayBackend, TrayCapability, TrayIconKind, TrayInfo, TrayManager};
+use tauri::{AppHandle, Runtime, State};
+use tracing::debug;
+
+/// Overlay a numbered badge on the tray icon (e.g. queued offline
+/// transcriptions or unread results). Passing 0 removes the badge.
+#[tauri::command]
+pub async fn set_tray_badge<R: Runtime>(
+    count: u32,
+    tray: State<'_, TrayManager>,
+    app_handle: AppHandle<R>,
+) -> Result<()> {
+    debug!("Setting tray badge to {}", count);
+    tray.set_badge(&app_handle, count)
+}
+
+/// Show the icon for `icon` in the current theme; for recorders the backend
+/// doesn't track itself, like the browser's
+#[tauri::command]
+pub async fn set_tray_icon<R: Runtime>(
+    icon: TrayIconKind,
+    tray: State<'_, TrayManager>,
+    app_handle: AppHandle<R>,
+) -> Result<()> {
+    debug!("Setting tray icon to {:?}", icon);
+    tray.set_icon_kind(&app_handle, icon)
+}
+
+/// The tray icon as drawn in the current theme, with its state and badge,
+/// for the frontend to create its tray with
+#[tauri::command]
+pub async fn get_tray_info<R: Runtime>(
+    tray: State<'_, TrayManager>,
+    app_handle: AppHandle<R>,
+) -> Result<TrayInfo> {
+    tray.info(&app_handle)
+}
+
+/// Whether a tray icon can be shown in this environment, with the reason and
+/// what the user can do about it when it can't
+#[tauri::command]
+pub async fn is_tray_supported(tray: State<'_, TrayManager>) -> Result<TrayCapability> {
+    Ok(tray.capability())
+}
+
+/// Which tray implementation is active; the frontend only creates Tauri's
+/// tray when this is `native`
+#[tauri::command]
+pub async fn get_tray_backend(tray: State<'_, TrayManager>) -> Result<TrayBackend> {
+    Ok(tray.backend())
+}
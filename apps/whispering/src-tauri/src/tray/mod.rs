@@ -0,0 +1,441 @@
+pub mod backend;
+pub mod badge;
+pub mod commands;
+#[cfg(target_os = "linux")]
+pub mod sni;
+pub mod theme;
+
+use crate::app_state::{AppState, AppStatus};
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, PresetApplied, PresetsChanged, SettingsChanged};
+use crate::i18n;
+use crate::notifications::notify;
+use crate::settings::SettingsStore;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Listener, Manager, Runtime, Window};
+use tracing::{debug, info, warn};
+
+pub use backend::{TrayBackend, TrayCapability};
+pub use theme::{IconTheme, TrayTheme};
+
+/// Must match `TRAY_ID` in the frontend tray service, which creates the tray
+pub const TRAY_ID: &str = "whispering-tray";
+
+const IDLE_ICON: &[u8] = include_bytes!("../../recorder-state-icons/studio_microphone.png");
+const RECORDING_ICON: &[u8] = include_bytes!("../../recorder-state-icons/red_large_square.png");
+const PROCESSING_ICON: &[u8] =
+    include_bytes!("../../recorder-state-icons/arrows_counterclockwise.png");
+
+/// Base icon shown in the tray before any overlay is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayIconKind {
+    Idle,
+    Recording,
+    Processing,
+}
+
+impl TrayIconKind {
+    fn bytes(&self) -> &'static [u8] {
+        match self {
+            TrayIconKind::Idle => IDLE_ICON,
+            TrayIconKind::Recording => RECORDING_ICON,
+            TrayIconKind::Processing => PROCESSING_ICON,
+        }
+    }
+
+    /// File name, without `.png`, of this state's icon in a custom theme
+    fn name(&self) -> &'static str {
+        match self {
+            TrayIconKind::Idle => "idle",
+            TrayIconKind::Recording => "recording",
+            TrayIconKind::Processing => "processing",
+        }
+    }
+
+    fn tooltip(&self) -> String {
+        i18n::t(match self {
+            TrayIconKind::Idle => "tooltip-idle",
+            TrayIconKind::Recording => "tooltip-recording",
+            TrayIconKind::Processing => "tooltip-processing",
+        })
+    }
+}
+
+impl From<&AppStatus> for TrayIconKind {
+    fn from(status: &AppStatus) -> Self {
+        match status {
+            AppStatus::Recording { .. } => TrayIconKind::Recording,
+            AppStatus::Processing | AppStatus::Injecting => TrayIconKind::Processing,
+            AppStatus::Idle | AppStatus::Error { .. } => TrayIconKind::Idle,
+        }
+    }
+}
+
+/// What the tray icon shows, kept even while there's no tray to draw it on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrayDisplay {
+    pub icon: TrayIconKind,
+    pub badge: u32,
+}
+
+/// The tray icon as it's drawn now, for trays created outside Rust
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayInfo {
+    pub backend: TrayBackend,
+    pub theme: TrayTheme,
+    pub icon: TrayIconKind,
+    pub badge: u32,
+    pub tooltip: String,
+    /// Whether macOS should recolour the icon to match the menu bar
+    pub template: bool,
+    /// RGBA pixels of the themed icon, badge included
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Renders the tray icon from the app state plus any overlays
+pub struct TrayManager {
+    display: Mutex<TrayDisplay>,
+    capability: Mutex<Option<TrayCapability>>,
+    #[cfg(target_os = "linux")]
+    sni: Mutex<Option<sni::SniHandle>>,
+    /// Whether the "still running in the tray" notice was shown this session
+    hide_notice_shown: AtomicBool,
+}
+
+impl TrayManager {
+    pub fn new() -> Self {
+        Self::with_capability(None)
+    }
+
+    /// A manager that reports `capability` instead of probing the desktop,
+    /// e.g. to exercise the tray commands without an OS tray
+    pub fn with_capability(capability: Option<TrayCapability>) -> Self {
+        Self {
+            display: Mutex::new(TrayDisplay {
+                icon: TrayIconKind::Idle,
+                badge: 0,
+            }),
+            capability: Mutex::new(capability),
+            #[cfg(target_os = "linux")]
+            sni: Mutex::new(None),
+            hide_notice_shown: AtomicBool::new(false),
+        }
+    }
+
+    /// Probe the environment and, if Tauri's tray won't show, start our own
+    pub fn start(&self, app: &AppHandle) -> Result<TrayCapability> {
+        let capability = backend::probe();
+        let detected = capability.backend;
+        info!("Using tray backend: {:?} ({})", detected, capability.reason);
+
+        #[cfg(target_os = "linux")]
+        if detected == TrayBackend::StatusNotifier {
+            let display = self
+                .display
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock tray display: {}", e)))?;
+            let (rgba, width, height) =
+                render_rgba(&IconTheme::current(app), display.icon, display.badge)?;
+            let handle =
+                sni::SniHandle::spawn(app.clone(), &rgba, width, height, &display.icon.tooltip());
+            *self
+                .sni
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Failed to lock tray handle: {}", e)))? =
+                Some(handle);
+
+            // Keep the menu checkmarks in sync when settings or the preset
+            // change elsewhere
+            for event in [
+                SettingsChanged::NAME,
+                PresetApplied::NAME,
+                PresetsChanged::NAME,
+            ] {
+                let app_handle = app.clone();
+                app.listen_any(event, move |_| {
+                    let tray = app_handle.state::<TrayManager>();
+                    if let Ok(sni) = tray.sni.lock() {
+                        if let Some(handle) = sni.as_ref() {
+                            handle.refresh_menu();
+                        }
+                    }
+                });
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = app;
+
+        *self
+            .capability
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock tray capability: {}", e)))? =
+            Some(capability.clone());
+        Ok(capability)
+    }
+
+    /// Create the tray from Rust when startup defers the webview
+    ///
+    /// Usually the frontend creates the tray, but without a webview there's
+    /// nothing to create it. This one only offers showing the window and
+    /// quitting; the frontend installs its full menu once the window exists.
+    pub fn create_native(&self, app: &AppHandle) -> Result<()> {
+        if self.backend() != TrayBackend::Native || app.tray_by_id(TRAY_ID).is_some() {
+            return Ok(());
+        }
+        let menu = native_menu(app)?;
+        let display = self
+            .display
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock tray display: {}", e)))?;
+        let theme = IconTheme::current(app);
+        let (rgba, width, height) = render_rgba(&theme, display.icon, display.badge)?;
+        TrayIconBuilder::with_id(TRAY_ID)
+            .icon(Image::new_owned(rgba, width, height))
+            .icon_as_template(theme.is_template())
+            .tooltip(display.icon.tooltip())
+            .menu(&menu)
+            .show_menu_on_left_click(false)
+            .on_menu_event(|app, event| match event.id().as_ref() {
+                "tray:show" => show_window(app),
+                "tray:quit" => app.exit(0),
+                _ => {}
+            })
+            .on_tray_icon_event(|tray, event| {
+                if let TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } = event
+                {
+                    show_window(tray.app_handle());
+                }
+            })
+            .build(app)
+            .map_err(|e| AppError::Internal(format!("Failed to create tray: {}", e)))?;
+        info!("Created tray before the window");
+        Ok(())
+    }
+
+    /// What the startup probe found, or a fresh probe if the tray wasn't started
+    pub fn capability(&self) -> TrayCapability {
+        match self.capability.lock().ok().and_then(|c| c.clone()) {
+            Some(capability) => capability,
+            None => backend::probe(),
+        }
+    }
+
+    pub fn backend(&self) -> TrayBackend {
+        self.capability().backend
+    }
+
+    /// What the icon shows, or will show once the tray exists
+    pub fn display(&self) -> TrayDisplay {
+        self.display
+            .lock()
+            .map(|display| *display)
+            .unwrap_or(TrayDisplay {
+                icon: TrayIconKind::Idle,
+                badge: 0,
+            })
+    }
+
+    /// The current icon resolved through the theme in the settings
+    pub fn info<R: Runtime>(&self, app: &AppHandle<R>) -> Result<TrayInfo> {
+        let display = self.display();
+        let theme = IconTheme::current(app);
+        let (rgba, width, height) = render_rgba(&theme, display.icon, display.badge)?;
+        Ok(TrayInfo {
+            backend: self.backend(),
+            theme: theme.theme(),
+            icon: display.icon,
+            badge: display.badge,
+            tooltip: display.icon.tooltip().to_string(),
+            template: theme.is_template(),
+            rgba,
+            width,
+            height,
+        })
+    }
+
+    /// Handle a close request on `window`, returning true if it was hidden to
+    /// the tray instead and the close should be prevented
+    pub fn hide_on_close(&self, window: &Window) -> bool {
+        let app = window.app_handle();
+        let hide = app
+            .try_state::<SettingsStore>()
+            .is_some_and(|settings| settings.should_hide_to_tray(self.capability().supported));
+        if !hide {
+            return false;
+        }
+
+        if let Err(e) = window.hide() {
+            warn!("Failed to hide window to tray: {}", e);
+            return false;
+        }
+        debug!("Window hidden to tray");
+
+        if !self.hide_notice_shown.swap(true, Ordering::Relaxed) {
+            notify(app, &i18n::t("notify-still-in-tray"));
+        }
+        true
+    }
+
+    pub fn shutdown(&self) {
+        #[cfg(target_os = "linux")]
+        if let Ok(mut sni) = self.sni.lock() {
+            if let Some(handle) = sni.take() {
+                handle.shutdown();
+            }
+        }
+    }
+
+    /// Keep the base icon in sync with the recording lifecycle
+    pub fn follow_app_state<R: Runtime>(&self, app: AppHandle<R>) {
+        let mut rx = app.state::<AppState>().subscribe();
+        tauri::async_runtime::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let icon = TrayIconKind::from(&*rx.borrow_and_update());
+                let tray = app.state::<TrayManager>();
+                if let Err(e) = tray.set_icon_kind(&app, icon) {
+                    warn!("Failed to update tray icon: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Redraw the icon whenever the settings change, in case the theme did,
+    /// and once now for the theme just loaded
+    pub fn follow_settings<R: Runtime>(&self, app: AppHandle<R>) {
+        let handle = app.clone();
+        app.listen_any(SettingsChanged::NAME, move |_| {
+            let tray = handle.state::<TrayManager>();
+            if let Err(e) = tray.update(&handle, |_| {}) {
+                warn!("Failed to redraw tray icon: {}", e);
+            }
+        });
+        if let Err(e) = self.update(&app, |_| {}) {
+            warn!("Failed to redraw tray icon: {}", e);
+        }
+    }
+
+    /// Redraw the tray in the current language
+    ///
+    /// The tooltip and the StatusNotifierItem menu are rebuilt here. The menu
+    /// of a native tray is too while Rust's bare one is showing; once the
+    /// window exists the frontend owns it and relabels it on
+    /// `locale://changed`.
+    pub fn relabel(&self, app: &AppHandle) -> Result<()> {
+        self.update(app, |_| {})?;
+        #[cfg(target_os = "linux")]
+        if let Some(handle) = self
+            .sni
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock tray handle: {}", e)))?
+            .as_ref()
+        {
+            handle.refresh_menu();
+        }
+        if app.get_webview_window("main").is_none() {
+            if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                tray.set_menu(Some(native_menu(app)?))
+                    .map_err(|e| AppError::Internal(format!("Failed to set tray menu: {}", e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_icon_kind<R: Runtime>(&self, app: &AppHandle<R>, icon: TrayIconKind) -> Result<()> {
+        self.update(app, |display| display.icon = icon)
+    }
+
+    /// Show a count (queued or unread items) on the tray icon; 0 clears it
+    pub fn set_badge<R: Runtime>(&self, app: &AppHandle<R>, count: u32) -> Result<()> {
+        self.update(app, |display| display.badge = count)
+    }
+
+    /// Change what the icon shows and redraw it
+    ///
+    /// Without a tray yet the change is only stored; whoever creates the tray
+    /// draws it from [`display`](Self::display).
+    fn update<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        f: impl FnOnce(&mut TrayDisplay),
+    ) -> Result<()> {
+        let mut display = self
+            .display
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock tray display: {}", e)))?;
+        f(&mut display);
+        let theme = IconTheme::current(app);
+
+        #[cfg(target_os = "linux")]
+        if let Some(handle) = self
+            .sni
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock tray handle: {}", e)))?
+            .as_ref()
+        {
+            let (rgba, width, height) = render_rgba(&theme, display.icon, display.badge)?;
+            handle.set_icon(&rgba, width, height, &display.icon.tooltip());
+            return Ok(());
+        }
+
+        // The frontend creates the tray; until it exists there's nothing to draw on
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            debug!("Tray not created yet, deferring icon update");
+            return Ok(());
+        };
+
+        let (rgba, width, height) = render_rgba(&theme, display.icon, display.badge)?;
+        tray.set_icon(Some(Image::new_owned(rgba, width, height)))
+            .map_err(|e| AppError::Internal(format!("Failed to set tray icon: {}", e)))?;
+        tray.set_icon_as_template(theme.is_template())
+            .map_err(|e| AppError::Internal(format!("Failed to set tray icon: {}", e)))?;
+        tray.set_tooltip(Some(display.icon.tooltip()))
+            .map_err(|e| AppError::Internal(format!("Failed to set tray tooltip: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// The menu of the tray Rust creates before the window exists
+fn native_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>> {
+    let show = MenuItem::with_id(
+        app,
+        "tray:show",
+        i18n::t("tray-show-window"),
+        true,
+        None::<&str>,
+    )
+    .map_err(|e| AppError::Internal(format!("Failed to create tray menu: {}", e)))?;
+    let quit = MenuItem::with_id(app, "tray:quit", i18n::t("tray-quit"), true, None::<&str>)
+        .map_err(|e| AppError::Internal(format!("Failed to create tray menu: {}", e)))?;
+    Menu::with_items(app, &[&show, &quit])
+        .map_err(|e| AppError::Internal(format!("Failed to create tray menu: {}", e)))
+}
+
+fn show_window(app: &AppHandle) {
+    if let Err(e) = crate::daemon::show_ui(app) {
+        warn!("Failed to show main window: {}", e);
+    }
+}
+
+/// Resolve the base icon through `theme` and composite overlays onto it,
+/// returning RGBA pixels
+fn render_rgba(theme: &IconTheme, kind: TrayIconKind, badge: u32) -> Result<(Vec<u8>, u32, u32)> {
+    let mut icon = theme.icon(kind)?;
+
+    badge::draw_badge(&mut icon, badge);
+
+    let (width, height) = icon.dimensions();
+    Ok((icon.into_raw(), width, height))
+}
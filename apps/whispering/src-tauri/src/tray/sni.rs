@@ -0,0 +1,224 @@
+//! StatusNotifierItem tray for Linux desktops where Tauri's tray doesn't show.
+//!
+//! Mirrors the frontend tray menu (show, hide, settings toggles, recording
+//! presets, quit) and is
+//! drawn from the same composited icon as the native tray.
+
+use crate::events::{AppEvent, TrayNavigate};
+use crate::i18n;
+use crate::presets::Presets;
+use crate::settings::{NativeSettings, SettingsStore};
+use ksni::menu::{CheckmarkItem, StandardItem, SubMenu};
+use ksni::{Icon, MenuItem, ToolTip, TrayService};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+pub struct SniTray {
+    app: AppHandle,
+    icon: Icon,
+    tooltip: String,
+}
+
+impl SniTray {
+    fn show_window(&self) {
+        if let Err(e) = crate::daemon::show_ui(&self.app) {
+            warn!("Failed to show main window: {}", e);
+        }
+    }
+
+    fn toggle_setting(&self, f: fn(&mut NativeSettings)) {
+        // Menu callbacks run while ksni holds the tray lock, and the resulting
+        // `settings://changed` event refreshes this menu, so apply it off-thread
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Some(settings) = app.try_state::<SettingsStore>() {
+                if let Err(e) = settings.update(&app, f) {
+                    warn!("Failed to update setting from tray: {}", e);
+                }
+            }
+        });
+    }
+
+    fn apply_preset(&self, name: String) {
+        // Off-thread for the same reason as `toggle_setting`
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Some(presets) = app.try_state::<Presets>() {
+                if let Err(e) = presets.apply(&app, &name) {
+                    warn!("Failed to apply preset from tray: {}", e);
+                }
+            }
+        });
+    }
+
+    fn hide_window(&self) {
+        if let Some(window) = self.app.get_webview_window("main") {
+            if let Err(e) = window.hide() {
+                warn!("Failed to hide main window: {}", e);
+            }
+        }
+    }
+}
+
+impl ksni::Tray for SniTray {
+    fn id(&self) -> String {
+        super::TRAY_ID.to_string()
+    }
+
+    fn title(&self) -> String {
+        "Whispering".to_string()
+    }
+
+    fn icon_pixmap(&self) -> Vec<Icon> {
+        vec![self.icon.clone()]
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: self.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let settings = self
+            .app
+            .try_state::<SettingsStore>()
+            .map(|store| store.get())
+            .unwrap_or_default();
+        let toggle = |label: String, checked: bool, f: fn(&mut NativeSettings)| -> MenuItem<Self> {
+            CheckmarkItem {
+                label,
+                checked,
+                activate: Box::new(move |tray: &mut Self| tray.toggle_setting(f)),
+                ..Default::default()
+            }
+            .into()
+        };
+        let presets = self
+            .app
+            .try_state::<Presets>()
+            .map(|presets| presets.list())
+            .unwrap_or_default();
+        let preset_items = presets
+            .presets
+            .into_iter()
+            .map(|preset| {
+                let checked = presets.active.as_deref() == Some(preset.name.as_str());
+                let name = preset.name.clone();
+                CheckmarkItem {
+                    label: preset.name,
+                    checked,
+                    activate: Box::new(move |tray: &mut Self| tray.apply_preset(name.clone())),
+                    ..Default::default()
+                }
+                .into()
+            })
+            .collect();
+
+        vec![
+            StandardItem {
+                label: i18n::t("tray-show-window"),
+                activate: Box::new(|tray: &mut Self| tray.show_window()),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: i18n::t("tray-hide-window"),
+                activate: Box::new(|tray: &mut Self| tray.hide_window()),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: i18n::t("tray-settings"),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = TrayNavigate::emit(&tray.app, "/settings");
+                    tray.show_window();
+                }),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            toggle(i18n::t("tray-close-to-tray"), settings.close_to_tray, |s| {
+                s.close_to_tray = !s.close_to_tray
+            }),
+            toggle(
+                i18n::t("tray-start-minimized"),
+                settings.start_minimized,
+                |s| s.start_minimized = !s.start_minimized,
+            ),
+            toggle(
+                i18n::t("tray-sound-feedback"),
+                settings.sound_feedback,
+                |s| s.sound_feedback = !s.sound_feedback,
+            ),
+            toggle(i18n::t("tray-auto-paste"), settings.auto_paste, |s| {
+                s.auto_paste = !s.auto_paste
+            }),
+            SubMenu {
+                label: i18n::t("tray-recording-preset"),
+                submenu: preset_items,
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: i18n::t("tray-quit"),
+                activate: Box::new(|tray: &mut Self| tray.app.exit(0)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
+
+/// Handle to a running StatusNotifierItem
+pub struct SniHandle {
+    handle: ksni::Handle<SniTray>,
+}
+
+impl SniHandle {
+    /// Register the item on the session bus; the service runs on its own thread
+    pub fn spawn(app: AppHandle, rgba: &[u8], width: u32, height: u32, tooltip: &str) -> Self {
+        let service = TrayService::new(SniTray {
+            app,
+            icon: to_argb_icon(rgba, width, height),
+            tooltip: tooltip.to_string(),
+        });
+        let handle = service.handle();
+        service.spawn();
+        info!("Registered StatusNotifierItem tray");
+        Self { handle }
+    }
+
+    pub fn set_icon(&self, rgba: &[u8], width: u32, height: u32, tooltip: &str) {
+        let icon = to_argb_icon(rgba, width, height);
+        let tooltip = tooltip.to_string();
+        self.handle.update(move |tray| {
+            tray.icon = icon;
+            tray.tooltip = tooltip;
+        });
+    }
+
+    /// Rebuild the menu so the checkmarks reflect the current settings
+    pub fn refresh_menu(&self) {
+        self.handle.update(|_| {});
+    }
+
+    pub fn shutdown(&self) {
+        self.handle.shutdown();
+    }
+}
+
+/// StatusNotifierItem pixmaps are ARGB32 in network byte order
+fn to_argb_icon(rgba: &[u8], width: u32, height: u32) -> Icon {
+    let data = rgba
+        .chunks_exact(4)
+        .flat_map(|px| [px[3], px[0], px[1], px[2]])
+        .collect();
+    Icon {
+        width: width as i32,
+        height: height as i32,
+        data,
+    }
+}
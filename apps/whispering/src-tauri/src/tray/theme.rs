@@ -0,0 +1,179 @@
+//! Tray icon themes.
+//!
+//! The classic icons tell the states apart mostly by colour, which doesn't
+//! work for everyone. The drawn themes use a different shape per state
+//! instead (a ring when idle, a filled disc while recording, three dots while
+//! transcribing), so they read the same with any kind of colour vision:
+//!
+//! - high contrast: yellow shapes with a black outline, visible on light and
+//!   dark panels alike
+//! - monochrome: plain shapes; on macOS a template image the menu bar tints
+//!   to match, elsewhere white for the usual dark panels
+//! - custom: `idle.png`, `recording.png` and `processing.png` from a folder
+//!   of the user's choosing, falling back to classic for any that's missing
+//!
+//! Whatever the theme, a custom icon set for a single state replaces the
+//! theme's icon for it (see `custom_assets`).
+
+use super::TrayIconKind;
+use crate::custom_assets;
+use crate::error::{AppError, Result};
+use crate::settings::{NativeSettings, SettingsStore};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::warn;
+
+/// Side of the drawn icons in pixels
+const SIZE: u32 = 64;
+
+const HIGH_CONTRAST_FILL: Rgba<u8> = Rgba([255, 221, 0, 255]);
+const HIGH_CONTRAST_OUTLINE: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const OUTLINE_WIDTH: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayTheme {
+    /// The original microphone, red square and arrows
+    #[default]
+    Classic,
+    HighContrast,
+    Monochrome,
+    /// Icons from `trayThemeFolder`
+    Custom,
+}
+
+/// A theme and whatever it needs to draw its icons
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IconTheme {
+    theme: TrayTheme,
+    folder: Option<PathBuf>,
+    /// Icons for single states, replacing the theme's
+    overrides: Vec<(TrayIconKind, PathBuf)>,
+}
+
+impl IconTheme {
+    pub fn from_settings(settings: &NativeSettings) -> Self {
+        let path = |setting: &str| {
+            let setting = setting.trim();
+            (!setting.is_empty()).then(|| PathBuf::from(setting))
+        };
+        let overrides = [
+            (TrayIconKind::Idle, &settings.custom_tray_icon_idle),
+            (
+                TrayIconKind::Recording,
+                &settings.custom_tray_icon_recording,
+            ),
+            (
+                TrayIconKind::Processing,
+                &settings.custom_tray_icon_processing,
+            ),
+        ];
+        Self {
+            theme: settings.tray_theme,
+            folder: path(&settings.tray_theme_folder),
+            overrides: overrides
+                .into_iter()
+                .filter_map(|(kind, setting)| Some((kind, path(setting)?)))
+                .collect(),
+        }
+    }
+
+    /// The theme chosen in the settings, or classic before they're loaded
+    pub fn current<R: Runtime>(app: &AppHandle<R>) -> Self {
+        app.try_state::<SettingsStore>()
+            .map(|settings| Self::from_settings(&settings.get()))
+            .unwrap_or_default()
+    }
+
+    pub fn theme(&self) -> TrayTheme {
+        self.theme
+    }
+
+    /// Whether macOS should recolour the icon to match the menu bar
+    pub fn is_template(&self) -> bool {
+        self.theme == TrayTheme::Monochrome && cfg!(target_os = "macos")
+    }
+
+    /// The base icon for `kind`, before any badge
+    pub fn icon(&self, kind: TrayIconKind) -> Result<RgbaImage> {
+        if let Some((_, path)) = self.overrides.iter().find(|(k, _)| *k == kind) {
+            match custom_assets::load_icon(path) {
+                Ok(icon) => return Ok(icon),
+                Err(e) => warn!("Using the theme's tray icon: {}", e),
+            }
+        }
+        match self.theme {
+            TrayTheme::Classic => classic(kind),
+            TrayTheme::HighContrast => {
+                Ok(draw(kind, HIGH_CONTRAST_FILL, Some(HIGH_CONTRAST_OUTLINE)))
+            }
+            TrayTheme::Monochrome => {
+                let fill = if cfg!(target_os = "macos") {
+                    Rgba([0, 0, 0, 255])
+                } else {
+                    Rgba([255, 255, 255, 255])
+                };
+                Ok(draw(kind, fill, None))
+            }
+            TrayTheme::Custom => match self.custom(kind) {
+                Ok(icon) => Ok(icon),
+                Err(e) => {
+                    warn!("Using the classic tray icon: {}", e);
+                    classic(kind)
+                }
+            },
+        }
+    }
+
+    fn custom(&self, kind: TrayIconKind) -> Result<RgbaImage> {
+        let folder = self.folder.as_ref().ok_or_else(|| {
+            AppError::InvalidInput("No folder is set for the custom tray theme".to_string())
+        })?;
+        custom_assets::load_icon(&folder.join(format!("{}.png", kind.name())))
+    }
+}
+
+fn classic(kind: TrayIconKind) -> Result<RgbaImage> {
+    Ok(image::load_from_memory(kind.bytes())
+        .map_err(|e| AppError::Internal(format!("Failed to decode tray icon: {}", e)))?
+        .to_rgba8())
+}
+
+/// Draw the shape for `kind`, optionally outlined so it stands out on any
+/// background
+fn draw(kind: TrayIconKind, fill: Rgba<u8>, outline: Option<Rgba<u8>>) -> RgbaImage {
+    let mut icon = RgbaImage::new(SIZE, SIZE);
+    let center = SIZE as f32 / 2.0;
+    // Signed distance from the shape's edge, negative inside
+    let distance = |x: f32, y: f32| -> f32 {
+        let from_center = |cx: f32| ((x - cx).powi(2) + (y - center).powi(2)).sqrt();
+        match kind {
+            TrayIconKind::Idle => (from_center(center) - 20.0).abs() - 4.5,
+            TrayIconKind::Recording => from_center(center) - 24.0,
+            TrayIconKind::Processing => [14.0, 32.0, 50.0]
+                .iter()
+                .map(|&cx| from_center(cx) - 7.5)
+                .fold(f32::INFINITY, f32::min),
+        }
+    };
+
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let d = distance(x as f32 + 0.5, y as f32 + 0.5);
+            let pixel = icon.get_pixel_mut(x, y);
+            if let Some(outline) = outline {
+                let coverage = (0.5 - (d - OUTLINE_WIDTH)).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    super::badge::blend(pixel, outline, coverage);
+                }
+            }
+            let coverage = (0.5 - d).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                super::badge::blend(pixel, fill, coverage);
+            }
+        }
+    }
+    icon
+}
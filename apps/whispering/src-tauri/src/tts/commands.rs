@@ -0,0 +1,32 @@
+use super::{Speaker, VoiceInfo};
+use crate::error::{AppError, Result};
+use tauri::State;
+
+/// Read `text` aloud, interrupting anything already being read. `rate` is a
+/// multiple of normal speed, 1.0 if not given.
+#[tauri::command]
+pub async fn speak(
+    text: String,
+    voice: Option<String>,
+    rate: Option<f32>,
+    speaker: State<'_, Speaker>,
+) -> Result<()> {
+    let rate = rate.unwrap_or(1.0);
+    if !(0.25..=4.0).contains(&rate) {
+        return Err(AppError::InvalidInput(
+            "Speech rate must be between 0.25 and 4".to_string(),
+        ));
+    }
+    speaker.speak(text, voice, rate)
+}
+
+#[tauri::command]
+pub async fn stop_speaking(speaker: State<'_, Speaker>) -> Result<()> {
+    speaker.stop()
+}
+
+/// Voices installed on the system, for picking one in settings
+#[tauri::command]
+pub async fn list_voices(speaker: State<'_, Speaker>) -> Result<Vec<VoiceInfo>> {
+    speaker.voices()
+}
@@ -0,0 +1,172 @@
+//! Reading transcripts aloud with the system's voices, so dictation can be
+//! checked without looking at the screen.
+//!
+//! Uses the platform speech engine through the `tts` crate (SAPI/WinRT on
+//! Windows, AVFoundation on macOS, Speech Dispatcher on Linux). Engines
+//! aren't `Send` everywhere, so like playback a dedicated thread owns one
+//! and is driven through a channel.
+
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use ::tts::Tts;
+use serde::Serialize;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use tracing::{info, warn};
+
+/// A voice the system can speak with
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceInfo {
+    pub id: String,
+    pub name: String,
+    /// BCP 47 language tag, e.g. `en-US`
+    pub language: String,
+}
+
+enum SpeechCommand {
+    Speak {
+        text: String,
+        voice: Option<String>,
+        rate: f32,
+    },
+    Stop,
+    Voices(Sender<Result<Vec<VoiceInfo>>>),
+}
+
+/// Speaks text through the system speech engine, started on first use
+pub struct Speaker {
+    tx: Mutex<Option<Sender<SpeechCommand>>>,
+}
+
+impl Speaker {
+    pub fn new() -> Self {
+        Self {
+            tx: Mutex::new(None),
+        }
+    }
+
+    /// Speak `text`, interrupting anything already being spoken. `voice` is
+    /// an id from `voices` (the system default if `None` or not found), and
+    /// `rate` a multiple of the voice's normal speed.
+    pub fn speak(&self, text: String, voice: Option<String>, rate: f32) -> Result<()> {
+        self.send(SpeechCommand::Speak { text, voice, rate })
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        self.send(SpeechCommand::Stop)
+    }
+
+    pub fn voices(&self) -> Result<Vec<VoiceInfo>> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.send(SpeechCommand::Voices(reply_tx))?;
+        reply_rx.recv().map_err(|_| {
+            AppError::Internal("Speech thread stopped before listing voices".to_string())
+        })?
+    }
+
+    fn send(&self, command: SpeechCommand) -> Result<()> {
+        let mut tx = self
+            .tx
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock speech engine: {}", e)))?;
+        if tx.is_none() {
+            *tx = Some(spawn_speaker()?);
+        }
+        let sender = tx.as_ref().expect("speech thread was just started");
+        if let Err(mpsc::SendError(command)) = sender.send(command) {
+            warn!("Speech thread stopped, restarting");
+            let sender = spawn_speaker()?;
+            sender
+                .send(command)
+                .map_err(|_| AppError::Internal("Speech thread is not running".to_string()))?;
+            *tx = Some(sender);
+        }
+        Ok(())
+    }
+}
+
+fn spawn_speaker() -> Result<Sender<SpeechCommand>> {
+    let (tx, rx) = mpsc::channel::<SpeechCommand>();
+    let (ready_tx, ready_rx) = mpsc::channel::<std::result::Result<(), String>>();
+
+    thread::spawn(move || {
+        let mut tts = match Tts::default() {
+            Ok(tts) => {
+                let _ = ready_tx.send(Ok(()));
+                tts
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(format!("No speech engine available: {}", e)));
+                return;
+            }
+        };
+
+        for command in rx {
+            match command {
+                SpeechCommand::Speak { text, voice, rate } => {
+                    if let Err(e) = speak(&mut tts, &text, voice.as_deref(), rate) {
+                        warn!("Failed to speak: {}", e);
+                    }
+                }
+                SpeechCommand::Stop => {
+                    if let Err(e) = tts.stop() {
+                        warn!("Failed to stop speaking: {}", e);
+                    }
+                }
+                SpeechCommand::Voices(reply) => {
+                    let _ = reply.send(voices(&tts));
+                }
+            }
+        }
+    });
+
+    ready_rx
+        .recv()
+        .map_err(|_| AppError::Internal("Speech thread exited during startup".to_string()))?
+        .map_err(AppError::Unsupported)?;
+    info!("Speech engine started");
+    Ok(tx)
+}
+
+fn speak(
+    tts: &mut Tts,
+    text: &str,
+    voice: Option<&str>,
+    rate: f32,
+) -> std::result::Result<(), ::tts::Error> {
+    let features = tts.supported_features();
+    if features.voice {
+        if let Some(id) = voice {
+            match tts.voices()?.into_iter().find(|v| v.id() == id) {
+                Some(voice) => tts.set_voice(&voice)?,
+                None => warn!("Voice {} not found, using the default", id),
+            }
+        }
+    }
+    if features.rate {
+        let rate = (tts.normal_rate() * rate).clamp(tts.min_rate(), tts.max_rate());
+        tts.set_rate(rate)?;
+    }
+    tts.speak(text, true)?;
+    Ok(())
+}
+
+fn voices(tts: &Tts) -> Result<Vec<VoiceInfo>> {
+    if !tts.supported_features().voice {
+        return Ok(Vec::new());
+    }
+    let voices = tts
+        .voices()
+        .map_err(|e| AppError::Internal(format!("Failed to list voices: {}", e)))?;
+    Ok(voices
+        .into_iter()
+        .map(|voice| VoiceInfo {
+            id: voice.id(),
+            name: voice.name(),
+            language: voice.language().to_string(),
+        })
+        .collect())
+}
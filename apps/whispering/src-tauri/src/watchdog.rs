@@ -0,0 +1,74 @@
+//! Watchdog for a pipeline stuck in Processing.
+//!
+//! A provider request that never returns (a connection dropped mid-upload
+//! that the HTTP client doesn't notice) leaves the app in Processing, with
+//! the tray icon spinning until restart. Once Processing or Injecting has
+//! lasted `processing_timeout_secs`, running jobs are cancelled, the user is
+//! told what to check, and the state goes back to Idle so the next recording
+//! can start. The frontend abandons the provider requests it has in flight
+//! on `watchdog://timeout`.
+
+use crate::app_state::{AppState, AppStatus, StateEvent};
+use crate::events::{AppEvent, WatchdogTimeout};
+use crate::i18n;
+use crate::jobs::Jobs;
+use crate::notifications::notify;
+use crate::settings::SettingsStore;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::warn;
+
+fn is_busy(status: &AppStatus) -> bool {
+    matches!(status, AppStatus::Processing | AppStatus::Injecting)
+}
+
+/// Timeout from settings, `None` if turned off
+fn timeout<R: Runtime>(app: &AppHandle<R>) -> Option<Duration> {
+    let secs = app
+        .try_state::<SettingsStore>()
+        .map(|settings| settings.get().processing_timeout_secs)
+        .unwrap_or_default();
+    (secs > 0).then(|| Duration::from_secs(secs as u64))
+}
+
+/// Watch the app state for as long as the app runs
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    let mut rx = app.state::<AppState>().subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let busy = is_busy(&rx.borrow_and_update());
+            let limit = if busy { timeout(&app) } else { None };
+            let Some(limit) = limit else {
+                if rx.changed().await.is_err() {
+                    break;
+                }
+                continue;
+            };
+            // Each stage gets the full timeout; any change restarts it
+            tokio::select! {
+                changed = rx.changed() => if changed.is_err() { break },
+                _ = tokio::time::sleep(limit) => {
+                    if is_busy(&rx.borrow()) {
+                        cancel(&app, limit);
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn cancel<R: Runtime>(app: &AppHandle<R>, limit: Duration) {
+    let secs = limit.as_secs();
+    warn!("Still processing after {}s, cancelling the job", secs);
+    // Stops any transcription still running in Rust, not just the UI state
+    app.state::<Jobs>().cancel_all();
+    let _ = WatchdogTimeout::emit(app, &secs);
+    let message = i18n::t_args(
+        "notify-processing-timeout",
+        &[("seconds", &secs.to_string())],
+    );
+    notify(app, &message);
+    let state = app.state::<AppState>();
+    state.record(StateEvent::Failed { message });
+    state.record(StateEvent::Reset);
+}
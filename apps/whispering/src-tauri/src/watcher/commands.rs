@@ -0,0 +1,19 @@
+use crate::error::Result;
+use crate::watcher::{FolderWatcher, WatchedFolder};
+use tauri::State;
+use tracing::info;
+
+#[tauri::command]
+pub async fn set_watched_folders(
+    folders: Vec<WatchedFolder>,
+    watcher: State<'_, FolderWatcher>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Configuring {} watched folder(s)", folders.len());
+    watcher.configure(app_handle, folders)
+}
+
+#[tauri::command]
+pub async fn get_watched_folders(watcher: State<'_, FolderWatcher>) -> Result<Vec<WatchedFolder>> {
+    Ok(watcher.folders())
+}
@@ -0,0 +1,419 @@
+pub mod commands;
+
+use crate::error::AppError;
+use crate::events::{AppEvent, WatcherFailed, WatcherTranscribed};
+use crate::file_names::{self, FileNameContext};
+use crate::history::{HistoryEntry, HistorySource, HistoryStore, LatencyStage, StageTiming};
+use crate::punctuation::Punctuator;
+use crate::settings::SettingsStore;
+use crate::telemetry::{self, Feature};
+use crate::timestamps::Timestamps;
+use crate::transcription::{transcribe_local, LocalEngine, LocalTranscript, ModelManager, TranscriptSegment};
+use crate::transforms::{TransformContext, Transforms};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+pub use crate::error::Result;
+
+pub const WATCHED_FOLDERS_FILE: &str = "watched-folders.json";
+
+/// Audio file extensions picked up by the watcher
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "aac", "ogg", "opus", "flac", "webm", "mp4"];
+
+/// How long a file must stay untouched before it is considered fully written
+const SETTLE_DELAY: Duration = Duration::from_secs(2);
+
+/// Configuration for a single watched directory - provided by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedFolder {
+    pub path: String,
+    pub engine: LocalEngine,
+    pub model_path: String,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub recursive: bool,
+    #[serde(default = "default_true")]
+    pub write_txt: bool,
+    #[serde(default)]
+    pub write_srt: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Payload of the `watcher://error` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherErrorPayload {
+    pub path: String,
+    pub message: String,
+}
+
+/// Watches configured folders and transcribes new audio files automatically
+///
+/// The folders are kept on disk and watched again on the next launch. Files
+/// already in a folder when watching starts are transcribed too, unless
+/// history has them, so nothing dropped in while the app was closed is
+/// missed.
+pub struct FolderWatcher {
+    path: PathBuf,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    folders: Arc<Mutex<Vec<WatchedFolder>>>,
+}
+
+impl FolderWatcher {
+    pub fn open(path: &Path) -> Self {
+        let folders = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {:?}: {}", path, e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            watcher: Mutex::new(None),
+            folders: Arc::new(Mutex::new(folders)),
+        }
+    }
+
+    pub fn folders(&self) -> Vec<WatchedFolder> {
+        self.folders.lock().map(|f| f.clone()).unwrap_or_default()
+    }
+
+    /// Watch the folders saved by the last launch. Ones that are gone (e.g.
+    /// on an unplugged drive) are skipped but kept for next time.
+    pub fn start(&self, app: AppHandle) -> Result<()> {
+        let folders = self.folders();
+        let present = folders
+            .iter()
+            .filter(|folder| {
+                let present = Path::new(&folder.path).is_dir();
+                if !present {
+                    warn!("Not watching {}: it's not a directory", folder.path);
+                }
+                present
+            })
+            .cloned()
+            .collect();
+        self.watch(app, present)
+    }
+
+    /// Replace the watched folder set, restarting the underlying watcher
+    pub fn configure(&self, app: AppHandle, folders: Vec<WatchedFolder>) -> Result<()> {
+        for folder in &folders {
+            if !Path::new(&folder.path).is_dir() {
+                return Err(AppError::InvalidInput(format!(
+                    "Watched path is not a directory: {}",
+                    folder.path
+                )));
+            }
+        }
+
+        *self
+            .folders
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock watched folders: {}", e)))? =
+            folders.clone();
+        self.save(&folders)?;
+        self.watch(app, folders)
+    }
+
+    fn save(&self, folders: &[WatchedFolder]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(folders).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize watched folders: {}", e))
+        })?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+
+    fn watch(&self, app: AppHandle, folders: Vec<WatchedFolder>) -> Result<()> {
+        let mut watcher_guard = self
+            .watcher
+            .lock()
+            .map_err(|e| AppError::Internal(format!("Failed to lock watcher: {}", e)))?;
+
+        // Dropping the old watcher closes its channel, which ends the old worker thread
+        watcher_guard.take();
+
+        if folders.is_empty() {
+            info!("Folder watcher stopped: no folders configured");
+            return Ok(());
+        }
+
+        let (event_tx, event_rx) = mpsc::channel::<PathBuf>();
+        let existing_tx = event_tx.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths {
+                        let _ = event_tx.send(path);
+                    }
+                }
+            }
+            Err(e) => error!("Folder watcher error: {}", e),
+        })
+        .map_err(|e| AppError::Internal(format!("Failed to create folder watcher: {}", e)))?;
+
+        for folder in &folders {
+            let mode = if folder.recursive {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            watcher.watch(Path::new(&folder.path), mode).map_err(|e| {
+                AppError::Internal(format!("Failed to watch {}: {}", folder.path, e))
+            })?;
+            info!("Watching folder {} (recursive: {})", folder.path, folder.recursive);
+        }
+
+        // Files already there go through the same queue; ones in history are skipped
+        for folder in &folders {
+            for path in audio_files(Path::new(&folder.path), folder.recursive) {
+                let _ = existing_tx.send(path);
+            }
+        }
+        drop(existing_tx);
+
+        let folders = self.folders.clone();
+        thread::spawn(move || run_worker(app, folders, event_rx));
+
+        *watcher_guard = Some(watcher);
+        Ok(())
+    }
+}
+
+/// Collect file events, wait for each file to settle, then transcribe it
+fn run_worker(app: AppHandle, folders: Arc<Mutex<Vec<WatchedFolder>>>, event_rx: mpsc::Receiver<PathBuf>) {
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+
+    loop {
+        match event_rx.recv_timeout(Duration::from_millis(500)) {
+            Ok(path) => {
+                if is_audio_file(&path) {
+                    pending.insert(path, Instant::now());
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                debug!("Folder watcher worker shutting down");
+                break;
+            }
+        }
+
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, last_event)| last_event.elapsed() >= SETTLE_DELAY)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in settled {
+            pending.remove(&path);
+
+            let folder = folders.lock().ok().and_then(|folders| {
+                folders
+                    .iter()
+                    .find(|folder| path.starts_with(&folder.path))
+                    .cloned()
+            });
+
+            if let Some(folder) = folder {
+                if let Err(e) = process_file(&app, &folder, &path) {
+                    error!("Failed to transcribe watched file {:?}: {}", path, e);
+                    let _ = WatcherFailed::emit(
+                        &app,
+                        &WatcherErrorPayload {
+                            path: path.to_string_lossy().to_string(),
+                            message: e.to_string(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Audio files in `dir`, and its subfolders if `recursive`
+fn audio_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            if recursive {
+                files.extend(audio_files(&path, true));
+            }
+        } else if is_audio_file(&path) {
+            files.push(path);
+        }
+    }
+    files
+}
+
+pub(crate) fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Transcribe a single settled file, write sidecars and record it in history
+fn process_file(app: &AppHandle, folder: &WatchedFolder, path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Ok(());
+    }
+
+    let source_path = path.to_string_lossy().to_string();
+    let history = app.state::<HistoryStore>();
+    if history.contains_source_path(&source_path)? {
+        debug!("Skipping already transcribed file {:?}", path);
+        return Ok(());
+    }
+
+    let audio_data = std::fs::read(path)
+        .map_err(|e| AppError::Internal(format!("Failed to read audio file: {}", e)))?;
+    if audio_data.is_empty() {
+        warn!("Skipping empty audio file {:?}", path);
+        return Ok(());
+    }
+
+    info!("Transcribing watched file {:?}", path);
+    let model_manager = app.state::<ModelManager>();
+    let started = Instant::now();
+    let mut transcript = transcribe_local(
+        &model_manager,
+        folder.engine,
+        audio_data,
+        &folder.model_path,
+        folder.language.clone(),
+    )?;
+    transcript.text = app.state::<Punctuator>().apply(transcript.text);
+    transcript.text = app.state::<Transforms>().apply(
+        &transcript.text,
+        &TransformContext {
+            language: folder.language.clone(),
+            profile: None,
+        },
+    );
+
+    let mut entry = HistoryEntry::new(HistorySource::WatchedFolder, transcript.text.clone());
+    write_sidecars(app, folder, path, &entry, &transcript)?;
+
+    entry.source_path = Some(source_path.clone());
+    entry.audio_path = Some(source_path);
+    entry.duration_seconds = transcript.duration_seconds;
+    entry.provider = Some(folder.engine.as_str().to_string());
+    (entry.language, entry.language_confidence) =
+        transcript.language_for_history(folder.language.as_deref());
+    history.insert(&entry)?;
+    history.record_latency(&entry.id, &[StageTiming::since(LatencyStage::Provider, started)])?;
+    telemetry::record(app, Feature::WatchedFolder);
+
+    let _ = WatcherTranscribed::emit(app, &entry);
+    Ok(())
+}
+
+/// Write the transcript next to the audio, named like it, or by the file
+/// name template if one is set
+fn write_sidecars(
+    app: &AppHandle,
+    folder: &WatchedFolder,
+    path: &Path,
+    entry: &HistoryEntry,
+    transcript: &LocalTranscript,
+) -> Result<()> {
+    if !folder.write_txt && !folder.write_srt {
+        return Ok(());
+    }
+
+    let context = FileNameContext {
+        created_at: entry.created_at,
+        app: None,
+        text: transcript.text.clone(),
+        id: entry.id.clone(),
+        name: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let template = app.state::<SettingsStore>().get().file_name_template;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = match file_names::render(&template, &context, &Timestamps::current(app)) {
+        Ok(stem) => stem.map(|stem| file_names::unique_stem(dir, &stem, &["txt", "srt"])),
+        Err(e) => {
+            warn!("Naming sidecars after the audio instead: {}", e);
+            None
+        }
+    };
+    let sidecar = |extension: &str| match &stem {
+        Some(stem) => dir.join(format!("{}.{}", stem, extension)),
+        None => path.with_extension(extension),
+    };
+
+    if folder.write_txt {
+        std::fs::write(sidecar("txt"), &transcript.text)
+            .map_err(|e| AppError::Internal(format!("Failed to write .txt sidecar: {}", e)))?;
+    }
+
+    if folder.write_srt {
+        std::fs::write(sidecar("srt"), format_srt(transcript))
+            .map_err(|e| AppError::Internal(format!("Failed to write .srt sidecar: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Render a transcript as SubRip subtitles
+///
+/// Falls back to a single cue spanning the whole clip when the engine did not
+/// return segment timestamps.
+pub fn format_srt(transcript: &LocalTranscript) -> String {
+    let fallback;
+    let segments = if transcript.segments.is_empty() {
+        fallback = [TranscriptSegment {
+            start: 0.0,
+            end: transcript.duration_seconds,
+            text: transcript.text.clone(),
+        }];
+        &fallback[..]
+    } else {
+        &transcript.segments[..]
+    };
+
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                format_srt_timestamp(segment.start),
+                format_srt_timestamp(segment.end),
+                segment.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_srt_timestamp(seconds: f32) -> String {
+    let total_millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_millis / 3_600_000;
+    let minutes = (total_millis % 3_600_000) / 60_000;
+    let secs = (total_millis % 60_000) / 1000;
+    let millis = total_millis % 1000;
+    format!("{:02}:{:02}:{:02},{:03}", hours, minutes, secs, millis)
+}
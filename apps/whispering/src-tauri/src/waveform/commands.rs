@@ -0,0 +1,16 @@
+use crate::error::Result;
+use crate::history::HistoryStore;
+use crate::waveform::{waveform_for_entry, Waveform};
+use tauri::State;
+use tracing::debug;
+
+/// Peak data for drawing a history entry's waveform with `buckets` bars
+#[tauri::command]
+pub async fn get_waveform(
+    id: String,
+    buckets: u32,
+    history: State<'_, HistoryStore>,
+) -> Result<Waveform> {
+    debug!("Getting waveform for {} ({} buckets)", id, buckets);
+    waveform_for_entry(&history, &id, buckets as usize)
+}
@@ -0,0 +1,73 @@
+pub mod commands;
+
+use crate::error::{AppError, Result};
+use crate::history::HistoryStore;
+use crate::transcription::decode_audio;
+use serde::Serialize;
+use tracing::debug;
+
+/// Number of peaks stored per recording; requests for fewer buckets are
+/// downsampled from this, so audio only has to be decoded once
+pub const STORED_RESOLUTION: usize = 2048;
+
+/// Peak amplitudes for drawing a recording's waveform
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Waveform {
+    /// Peak amplitude per bucket, from 0.0 (silence) to 1.0 (full scale)
+    pub peaks: Vec<f32>,
+}
+
+/// Get the waveform of a history entry's audio, computing and caching it on first use
+pub fn waveform_for_entry(history: &HistoryStore, id: &str, buckets: usize) -> Result<Waveform> {
+    let stored = match history.waveform_peaks(id)? {
+        Some(stored) => stored,
+        None => {
+            let entry = history.get(id)?;
+            let audio_path = entry.audio_path.ok_or_else(|| {
+                AppError::InvalidInput(format!("History entry {} has no audio", id))
+            })?;
+            debug!("Computing waveform for {} from {}", id, audio_path);
+
+            let audio = std::fs::read(&audio_path)?;
+            let samples = decode_audio(audio)?;
+            let stored = quantize(&compute_peaks(&samples, STORED_RESOLUTION));
+            history.set_waveform_peaks(id, &stored)?;
+            stored
+        }
+    };
+
+    let peaks: Vec<f32> = stored.iter().map(|&p| p as f32 / 255.0).collect();
+    Ok(Waveform {
+        peaks: downsample(&peaks, buckets),
+    })
+}
+
+/// Maximum absolute amplitude in each of `buckets` equal slices of `samples`
+pub fn compute_peaks(samples: &[f32], buckets: usize) -> Vec<f32> {
+    if samples.is_empty() || buckets == 0 {
+        return vec![0.0; buckets];
+    }
+    (0..buckets)
+        .map(|i| {
+            let start = i * samples.len() / buckets;
+            let end = ((i + 1) * samples.len() / buckets).max(start + 1);
+            samples[start..end.min(samples.len())]
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs()))
+                .min(1.0)
+        })
+        .collect()
+}
+
+/// Reduce peaks to `buckets` values, keeping the maximum of each group
+fn downsample(peaks: &[f32], buckets: usize) -> Vec<f32> {
+    if buckets >= peaks.len() {
+        return peaks.to_vec();
+    }
+    compute_peaks(peaks, buckets)
+}
+
+fn quantize(peaks: &[f32]) -> Vec<u8> {
+    peaks.iter().map(|p| (p * 255.0).round() as u8).collect()
+}
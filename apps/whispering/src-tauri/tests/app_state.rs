@@ -0,0 +1,169 @@
+//! The recording state machine, driven through its commands
+
+mod common;
+
+use common::{events, invoke, next_event, window};
+use serde_json::{json, Value};
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::{App, Manager};
+use whispering_lib::app_state::{get_app_state, report_app_state, AppState, AppStatus, StateEvent};
+use whispering_lib::events::{AppEvent, StateChanged};
+
+fn app() -> App<MockRuntime> {
+    let app = mock_builder()
+        .manage(AppState::new())
+        .invoke_handler(tauri::generate_handler![get_app_state, report_app_state])
+        .build(mock_context(noop_assets()))
+        .expect("failed to build the mock app");
+    app.state::<AppState>()
+        .forward_to_frontend(app.handle().clone());
+    app
+}
+
+fn report(window: &tauri::WebviewWindow<MockRuntime>, event: Value) -> Result<Value, Value> {
+    invoke(window, "report_app_state", json!({ "event": event }))
+}
+
+#[test]
+fn starts_idle() {
+    let app = app();
+    let window = window(&app);
+    let status: Value = invoke(&window, "get_app_state", json!({})).unwrap();
+    assert_eq!(status, json!({ "status": "idle" }));
+}
+
+#[test]
+fn runs_a_full_recording_cycle() {
+    let app = app();
+    let window = window(&app);
+
+    let steps = [
+        (
+            json!({ "type": "recordingStarted", "recordingId": "abc" }),
+            json!({ "status": "recording", "recordingId": "abc" }),
+        ),
+        (
+            json!({ "type": "recordingStopped" }),
+            json!({ "status": "processing" }),
+        ),
+        (
+            json!({ "type": "injectionStarted" }),
+            json!({ "status": "injecting" }),
+        ),
+        (json!({ "type": "completed" }), json!({ "status": "idle" })),
+    ];
+    for (event, expected) in steps {
+        assert_eq!(report(&window, event).unwrap(), expected);
+    }
+}
+
+#[test]
+fn rejects_invalid_transitions_without_changing_state() {
+    let app = app();
+    let window = window(&app);
+
+    let error = report(&window, json!({ "type": "recordingStopped" })).unwrap_err();
+    assert_eq!(error["name"], "InvalidTransition");
+    assert_eq!(error["code"], "INVALID_STATE");
+
+    let status: Value = invoke(&window, "get_app_state", json!({})).unwrap();
+    assert_eq!(status, json!({ "status": "idle" }));
+}
+
+#[test]
+fn failures_and_resets_are_always_allowed() {
+    let app = app();
+    let window = window(&app);
+
+    let failed = report(
+        &window,
+        json!({ "type": "failed", "message": "no microphone" }),
+    );
+    assert_eq!(
+        failed.unwrap(),
+        json!({ "status": "error", "message": "no microphone" })
+    );
+    let reset = report(&window, json!({ "type": "reset" }));
+    assert_eq!(reset.unwrap(), json!({ "status": "idle" }));
+}
+
+#[test]
+fn forwards_changes_to_the_frontend() {
+    let app = app();
+    let window = window(&app);
+    let changes = events(&app, StateChanged::NAME);
+
+    report(
+        &window,
+        json!({ "type": "recordingStarted", "recordingId": null }),
+    )
+    .unwrap();
+    assert_eq!(
+        next_event(&changes),
+        json!({ "status": "recording", "recordingId": null })
+    );
+}
+
+fn every_status() -> Vec<AppStatus> {
+    vec![
+        AppStatus::Idle,
+        AppStatus::Recording { recording_id: None },
+        AppStatus::Processing,
+        AppStatus::Injecting,
+        AppStatus::Error {
+            message: "no microphone".to_string(),
+        },
+    ]
+}
+
+#[test]
+fn every_state_can_fail_and_reset() {
+    let failed = StateEvent::Failed {
+        message: "timed out".to_string(),
+    };
+    for status in every_status() {
+        assert!(
+            matches!(status.next(&failed), Some(AppStatus::Error { .. })),
+            "{:?} can't fail",
+            status
+        );
+        assert_eq!(status.next(&StateEvent::Reset), Some(AppStatus::Idle));
+    }
+}
+
+#[test]
+fn recordings_only_start_when_nothing_else_runs() {
+    let started = StateEvent::RecordingStarted { recording_id: None };
+    for status in every_status() {
+        let allowed = matches!(status, AppStatus::Idle | AppStatus::Error { .. });
+        assert_eq!(status.next(&started).is_some(), allowed, "{:?}", status);
+    }
+}
+
+#[test]
+fn only_a_running_pipeline_completes() {
+    for status in every_status() {
+        let allowed = matches!(status, AppStatus::Processing | AppStatus::Injecting);
+        assert_eq!(
+            status.next(&StateEvent::Completed),
+            allowed.then_some(AppStatus::Idle),
+            "{:?}",
+            status
+        );
+    }
+}
+
+#[test]
+fn a_transcript_left_on_the_clipboard_settles_for_the_next_recording() {
+    // Nothing is typed, so the frontend reports the completion itself
+    let state = AppState::new();
+    state
+        .transition(StateEvent::RecordingStarted { recording_id: None })
+        .unwrap();
+    state.transition(StateEvent::RecordingStopped).unwrap();
+    assert_eq!(state.current(), AppStatus::Processing);
+    state.transition(StateEvent::Completed).unwrap();
+    assert!(state
+        .transition(StateEvent::RecordingStarted { recording_id: None })
+        .is_ok());
+}
@@ -0,0 +1,67 @@
+//! Writing the full data archive
+
+use std::fs::File;
+use whispering_lib::bulk::Archive;
+use whispering_lib::history::{HistoryEntry, HistorySource};
+
+#[test]
+fn writes_audio_text_and_a_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let audio = dir.path().join("recording.wav");
+    std::fs::write(&audio, b"RIFF audio").unwrap();
+    let mut entry = HistoryEntry::new(HistorySource::Recording, "Hello".to_string());
+    entry.audio_path = Some(audio.display().to_string());
+    let silent = HistoryEntry::new(HistorySource::Inbox, "No audio".to_string());
+
+    let path = dir.path().join("takeout.zip");
+    let archive = Archive::create(&path).unwrap();
+    archive.add_audio(&entry).unwrap();
+    archive.add_audio(&silent).unwrap();
+    archive
+        .add_text("transcripts/history.md", "# Whispering history\n")
+        .unwrap();
+    assert_eq!(archive.finish("1.0.0", 2).unwrap(), path);
+    assert!(!dir.path().join("takeout.zip.part").exists());
+
+    let mut zip = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+    let audio_name = format!("audio/{}.wav", entry.id);
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            audio_name.as_str(),
+            "manifest.json",
+            "transcripts/history.md"
+        ]
+    );
+
+    let manifest: serde_json::Value =
+        serde_json::from_reader(zip.by_name("manifest.json").unwrap()).unwrap();
+    assert_eq!(manifest["entries"], 2);
+    assert_eq!(manifest["audio"][&entry.id], audio_name.as_str());
+}
+
+#[test]
+fn removes_an_unfinished_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("takeout.zip");
+    let archive = Archive::create(&path).unwrap();
+    archive
+        .add_text("transcripts/history.md", "partial")
+        .unwrap();
+    drop(archive);
+
+    assert!(!path.exists());
+    assert!(!dir.path().join("takeout.zip.part").exists());
+}
+
+#[test]
+fn fails_on_missing_audio() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = Archive::create(&dir.path().join("takeout.zip")).unwrap();
+    let mut entry = HistoryEntry::new(HistorySource::Recording, "Gone".to_string());
+    entry.audio_path = Some(dir.path().join("gone.wav").display().to_string());
+
+    assert!(archive.add_audio(&entry).is_err());
+}
@@ -0,0 +1,84 @@
+//! CLI recording commands, relayed to the frontend over IPC and answered
+//! once it has run them
+#![cfg(unix)]
+
+mod common;
+
+use common::{invoke, window};
+use serde_json::{json, Value};
+use std::thread;
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::{App, Listener, Manager};
+use whispering_lib::app_state::{report_app_state, AppState, AppStatus};
+use whispering_lib::cli;
+use whispering_lib::events::{AppEvent, IpcCommandReceived};
+use whispering_lib::ipc::commands::finish_ipc_command;
+use whispering_lib::ipc::IpcServer;
+
+fn app() -> App<MockRuntime> {
+    let app = mock_builder()
+        .manage(AppState::new())
+        .manage(IpcServer::new())
+        .invoke_handler(tauri::generate_handler![
+            report_app_state,
+            finish_ipc_command
+        ])
+        .build(mock_context(noop_assets()))
+        .expect("failed to build the mock app");
+    app.state::<IpcServer>()
+        .start(app.handle().clone())
+        .expect("failed to start the IPC server");
+    app
+}
+
+fn whispering(command: &str) -> Option<i32> {
+    cli::run(&["whispering".to_string(), command.to_string()])
+}
+
+#[test]
+fn recording_commands_wait_for_the_frontend() {
+    // The endpoint file goes in the data directory; keep it away from a
+    // Whispering that's running for real
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", home.path());
+    std::env::set_var("XDG_DATA_HOME", home.path().join("data"));
+
+    let app = app();
+    // The UI is open, so commands go to its recorder
+    let window = window(&app);
+
+    // Stands in for the frontend's `handleIpcCommands`: the recorder
+    // reports starting as `start_recording` does, and stopping fails
+    app.listen_any(IpcCommandReceived::NAME, move |event| {
+        let payload: Value = serde_json::from_str(event.payload()).unwrap();
+        let window = window.clone();
+        thread::spawn(move || {
+            let error = match payload["command"].as_str() {
+                Some("startRecording") => {
+                    let started = json!({ "type": "recordingStarted", "recordingId": "cli" });
+                    invoke::<Value>(&window, "report_app_state", json!({ "event": started }))
+                        .unwrap();
+                    None
+                }
+                _ => Some("Microphone unplugged"),
+            };
+            invoke::<()>(
+                &window,
+                "finish_ipc_command",
+                json!({ "id": payload["id"], "error": error }),
+            )
+            .unwrap();
+        });
+    });
+
+    assert_eq!(whispering("start"), Some(0));
+    assert_eq!(
+        app.state::<AppState>().current(),
+        AppStatus::Recording {
+            recording_id: Some("cli".to_string())
+        }
+    );
+
+    // The frontend's error makes it back to the exit code
+    assert_eq!(whispering("stop"), Some(1));
+}
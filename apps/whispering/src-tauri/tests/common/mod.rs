@@ -0,0 +1,70 @@
+//! A Tauri app on the mock runtime, and a way to call its commands the way
+//! the frontend does, through IPC with JSON arguments.
+
+#![allow(dead_code)]
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::ipc::{CallbackFn, InvokeBody};
+use tauri::test::{get_ipc_response, MockRuntime, INVOKE_KEY};
+use tauri::webview::InvokeRequest;
+use tauri::{App, Listener, WebviewWindow, WebviewWindowBuilder};
+
+/// How long to wait for events and background tasks before failing
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A window to invoke commands from
+pub fn window(app: &App<MockRuntime>) -> WebviewWindow<MockRuntime> {
+    WebviewWindowBuilder::new(app, "main", Default::default())
+        .build()
+        .expect("failed to create the mock window")
+}
+
+/// Invoke `cmd` with `args`, returning its result or the serialized error
+pub fn invoke<T: DeserializeOwned>(
+    window: &WebviewWindow<MockRuntime>,
+    cmd: &str,
+    args: Value,
+) -> Result<T, Value> {
+    let response = get_ipc_response(
+        window,
+        InvokeRequest {
+            cmd: cmd.into(),
+            callback: CallbackFn(0),
+            error: CallbackFn(1),
+            url: "http://tauri.localhost".parse().unwrap(),
+            body: InvokeBody::Json(args),
+            headers: Default::default(),
+            invoke_key: INVOKE_KEY.to_string(),
+        },
+    )?;
+    Ok(response
+        .deserialize()
+        .expect("command returned an unexpected type"))
+}
+
+/// Collect the payloads of `event` as they're emitted
+pub fn events(app: &App<MockRuntime>, event: &str) -> mpsc::Receiver<Value> {
+    let (tx, rx) = mpsc::channel();
+    app.listen_any(event.to_string(), move |event| {
+        let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+        let _ = tx.send(payload);
+    });
+    rx
+}
+
+/// Wait for the next payload, failing the test after [`TIMEOUT`]
+pub fn next_event(rx: &mpsc::Receiver<Value>) -> Value {
+    rx.recv_timeout(TIMEOUT).expect("event wasn't emitted")
+}
+
+/// Poll `condition` until it holds, for effects of background tasks
+pub fn eventually(mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + TIMEOUT;
+    while !condition() {
+        assert!(Instant::now() < deadline, "condition didn't hold in time");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,63 @@
+//! Checks on user-supplied tray icons and sounds
+
+use image::RgbaImage;
+use std::path::{Path, PathBuf};
+use whispering_lib::custom_assets::{check, load_icon, load_sound};
+use whispering_lib::settings::NativeSettings;
+
+fn png(dir: &Path, name: &str, size: u32) -> PathBuf {
+    let path = dir.join(name);
+    RgbaImage::new(size, size).save(&path).unwrap();
+    path
+}
+
+#[test]
+fn accepts_a_png_icon_and_scales_large_ones_down() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let icon = load_icon(&png(dir.path(), "small.png", 32)).unwrap();
+    assert_eq!(icon.dimensions(), (32, 32));
+    let icon = load_icon(&png(dir.path(), "large.png", 512)).unwrap();
+    assert_eq!(icon.dimensions(), (256, 256));
+}
+
+#[test]
+fn rejects_icons_of_the_wrong_size_or_format() {
+    let dir = tempfile::tempdir().unwrap();
+
+    assert!(load_icon(&png(dir.path(), "tiny.png", 8)).is_err());
+    let text = dir.path().join("icon.png");
+    std::fs::write(&text, "not an image").unwrap();
+    assert!(load_icon(&text).is_err());
+    assert!(load_icon(&dir.path().join("missing.png")).is_err());
+}
+
+#[test]
+fn recognises_sounds_by_their_contents() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let wav = dir.path().join("start.wav");
+    std::fs::write(&wav, b"RIFF\x24\x00\x00\x00WAVEfmt ").unwrap();
+    assert!(load_sound(&wav).is_ok());
+
+    let renamed = dir.path().join("stop.mp3");
+    std::fs::write(&renamed, "plain text").unwrap();
+    assert!(load_sound(&renamed).is_err());
+}
+
+#[test]
+fn reports_only_files_that_are_set() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = NativeSettings {
+        custom_tray_icon_idle: png(dir.path(), "idle.png", 32).display().to_string(),
+        custom_stop_sound: dir.path().join("missing.wav").display().to_string(),
+        ..Default::default()
+    };
+
+    let checks = check(&settings);
+    assert_eq!(checks.len(), 2);
+    assert_eq!(checks[0].setting, "customTrayIconIdle");
+    assert_eq!(checks[0].error, None);
+    assert_eq!(checks[1].setting, "customStopSound");
+    assert!(checks[1].error.is_some());
+}
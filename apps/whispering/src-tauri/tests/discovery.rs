@@ -0,0 +1,42 @@
+//! Naming the services advertised over mDNS
+#![cfg(feature = "http-api")]
+
+use whispering_lib::discovery::{host_label, service_info, SERVICE_TYPE};
+use whispering_lib::{inbox, remote};
+
+#[test]
+fn host_label_is_a_dns_label() {
+    let label = host_label();
+    assert!(!label.is_empty());
+    assert!(!label.starts_with('-') && !label.ends_with('-'));
+    assert!(label
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'));
+}
+
+#[test]
+fn inbox_is_advertised_with_its_fingerprint() {
+    let (instance, txt) = inbox::advertised_as("ab:cd");
+    let service = service_info(&instance, inbox::DEFAULT_INBOX_PORT, &txt).unwrap();
+
+    assert_eq!(service.get_port(), inbox::DEFAULT_INBOX_PORT);
+    assert_eq!(
+        service.get_fullname(),
+        format!("{} inbox.{}", host_label(), SERVICE_TYPE)
+    );
+    assert_eq!(service.get_property_val_str("caps"), Some("inbox"));
+    assert_eq!(service.get_property_val_str("fp"), Some("ab:cd"));
+    assert_eq!(service.get_property_val_str("v"), Some("1"));
+}
+
+#[test]
+fn remote_is_told_apart_from_the_inbox() {
+    let (instance, txt) = remote::advertised_as("ab:cd");
+    let service = service_info(&instance, remote::DEFAULT_REMOTE_PORT, &txt).unwrap();
+
+    assert_eq!(
+        service.get_fullname(),
+        format!("{} remote.{}", host_label(), SERVICE_TYPE)
+    );
+    assert_eq!(service.get_property_val_str("caps"), Some("remote"));
+}
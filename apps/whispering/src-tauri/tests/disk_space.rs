@@ -0,0 +1,31 @@
+//! Free-space checks before and during recording
+
+use whispering_lib::recorder::disk_space::{ensure_space, format_bytes, threshold_bytes};
+use whispering_lib::settings::NativeSettings;
+
+#[test]
+fn threshold_follows_the_setting() {
+    let settings = NativeSettings {
+        min_free_disk_mb: 2,
+        ..Default::default()
+    };
+    assert_eq!(threshold_bytes(&settings), 2 * 1024 * 1024);
+    assert_eq!(NativeSettings::default().min_free_disk_mb, 500);
+}
+
+#[test]
+fn refuses_to_record_onto_a_full_disk() {
+    let dir = tempfile::tempdir().unwrap();
+
+    assert!(ensure_space(dir.path(), 0).is_ok());
+    assert!(ensure_space(dir.path(), 1).is_ok());
+    let err = ensure_space(dir.path(), u64::MAX).unwrap_err();
+    assert_eq!(err.code(), "DISK_FULL");
+}
+
+#[test]
+fn formats_sizes_for_warnings() {
+    assert_eq!(format_bytes(0), "0 MB");
+    assert_eq!(format_bytes(300 * 1024 * 1024), "300 MB");
+    assert_eq!(format_bytes(3 * 1024 * 1024 * 1024 / 2), "1.5 GB");
+}
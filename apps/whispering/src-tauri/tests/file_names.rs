@@ -0,0 +1,79 @@
+//! Naming saved files from a template
+
+use chrono_tz::Tz;
+use whispering_lib::file_names::{
+    first_words, rename, render, unique_path, validate, FileNameContext,
+};
+use whispering_lib::timestamps::{TimestampStyle, Timestamps, Zone};
+
+fn utc() -> Timestamps {
+    Timestamps::new(Zone::Named(Tz::UTC), TimestampStyle::Iso, "")
+}
+
+fn context() -> FileNameContext {
+    FileNameContext {
+        // 2024-01-05 13:30:05 UTC
+        created_at: 1_704_461_405_000,
+        app: Some("Slack".to_string()),
+        text: "Hello, world! This is a test of naming.".to_string(),
+        id: "abc123".to_string(),
+        name: "interview".to_string(),
+    }
+}
+
+#[test]
+fn fills_in_placeholders() {
+    let stem = render("{{date}}_{{app}}_{{first_words}}", &context(), &utc()).unwrap();
+    assert_eq!(
+        stem.as_deref(),
+        Some("2024-01-05_Slack_Hello-world-This-is-a")
+    );
+    assert_eq!(render("  ", &context(), &utc()).unwrap(), None);
+    assert_eq!(first_words("  one  two, three ", 2), "one-two");
+}
+
+#[test]
+fn rejects_unknown_placeholders() {
+    assert!(validate("{{date}}-{{nope}}").is_err());
+    assert!(validate("{{date").is_err());
+    assert!(validate("recording-{{id}}").is_ok());
+}
+
+#[test]
+fn keeps_values_from_escaping_the_folder() {
+    let context = FileNameContext {
+        app: Some("../../etc/passwd".to_string()),
+        ..context()
+    };
+    let stem = render("{{app}}", &context, &utc()).unwrap().unwrap();
+    assert!(!stem.contains('/'));
+    assert!(!stem.starts_with('.'));
+}
+
+#[test]
+fn numbers_names_that_are_taken() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.wav"), b"").unwrap();
+    std::fs::write(dir.path().join("notes (2).wav"), b"").unwrap();
+
+    assert_eq!(
+        unique_path(dir.path(), "notes", "wav"),
+        dir.path().join("notes (3).wav")
+    );
+    assert_eq!(
+        unique_path(dir.path(), "notes", "txt"),
+        dir.path().join("notes.txt")
+    );
+}
+
+#[test]
+fn renames_a_saved_recording() {
+    let dir = tempfile::tempdir().unwrap();
+    let saved = dir.path().join("abc123.wav");
+    std::fs::write(&saved, b"RIFF").unwrap();
+
+    let renamed = rename(&saved, "{{id}} {{first_words}}", &context(), &utc()).unwrap();
+    assert_eq!(renamed, dir.path().join("abc123 Hello-world-This-is-a.wav"));
+    assert!(renamed.is_file());
+    assert!(!saved.exists());
+}
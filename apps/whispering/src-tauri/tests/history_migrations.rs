@@ -0,0 +1,82 @@
+//! Upgrading the history database, with a copy made first
+
+use rusqlite::Connection;
+use whispering_lib::history::{
+    HistoryEntry, HistorySource, HistoryStore, BACKUP_DIR, HISTORY_DB_FILE, SCHEMA_VERSION,
+};
+
+#[test]
+fn creates_a_new_database_at_the_latest_version_without_a_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = HistoryStore::open(&dir.path().join(HISTORY_DB_FILE)).unwrap();
+
+    let info = history.info().unwrap();
+    assert_eq!(info.schema_version, SCHEMA_VERSION);
+    assert_eq!(info.latest_version, SCHEMA_VERSION);
+    assert!(info.backups.is_empty());
+    assert!(!dir.path().join(BACKUP_DIR).exists());
+}
+
+#[test]
+fn backs_up_an_unversioned_database_before_upgrading_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(HISTORY_DB_FILE);
+    // The first release's table, before any columns were added
+    Connection::open(&path)
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE history (
+                id TEXT PRIMARY KEY,
+                created_at INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                source_path TEXT,
+                audio_path TEXT,
+                transcript TEXT NOT NULL,
+                duration_seconds REAL NOT NULL DEFAULT 0
+            );
+            INSERT INTO history (id, created_at, source, transcript)
+                VALUES ('old', 1, 'recording', 'kept');",
+        )
+        .unwrap();
+
+    let history = HistoryStore::open(&path).unwrap();
+    assert_eq!(history.get("old").unwrap().transcript, "kept");
+    let info = history.info().unwrap();
+    assert_eq!(info.schema_version, SCHEMA_VERSION);
+    assert_eq!(info.backups.len(), 1);
+
+    // The copy is the database as it was
+    let backup = Connection::open(&info.backups[0]).unwrap();
+    let version: u32 = backup
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(version, 0);
+
+    // Opening it again has nothing to upgrade
+    drop(history);
+    let history = HistoryStore::open(&path).unwrap();
+    assert_eq!(history.info().unwrap().backups.len(), 1);
+}
+
+#[test]
+fn reports_row_counts() {
+    let history = HistoryStore::open_in_memory().unwrap();
+    for text in ["one", "two"] {
+        history
+            .insert(&HistoryEntry::new(
+                HistorySource::Recording,
+                text.to_string(),
+            ))
+            .unwrap();
+    }
+
+    let info = history.info().unwrap();
+    assert_eq!(info.path, None);
+    assert_eq!(info.size_bytes, 0);
+    let rows = info
+        .tables
+        .iter()
+        .find(|table| table.name == "history")
+        .map(|table| table.rows);
+    assert_eq!(rows, Some(2));
+}
@@ -0,0 +1,92 @@
+//! Transcripts made in the app, mirrored into the history database
+
+use whispering_lib::history::{
+    HistoryEntry, HistorySource, HistoryStore, InjectionOutcome, TagContext, Tombstone,
+};
+use whispering_lib::stats::{compute, StatsRange};
+
+fn recording(id: &str, transcript: &str) -> HistoryEntry {
+    let mut entry = HistoryEntry::new(HistorySource::Recording, transcript.to_string());
+    entry.id = id.to_string();
+    entry
+}
+
+#[test]
+fn saving_again_updates_the_transcript_and_keeps_the_rest() {
+    let history = HistoryStore::open_in_memory().unwrap();
+    history.save_recording(&recording("abc", "first")).unwrap();
+    history.set_tags("abc", &["work".to_string()]).unwrap();
+
+    let mut named = recording("abc", "first, edited");
+    named.audio_path = Some("/recordings/standup.wav".to_string());
+    named.duration_seconds = 12.5;
+    history.save_recording(&named).unwrap();
+    // A later edit doesn't know the audio, which stays
+    history.save_recording(&recording("abc", "final")).unwrap();
+
+    let entry = history.get("abc").unwrap();
+    assert_eq!(entry.transcript, "final");
+    assert_eq!(entry.audio_path.as_deref(), Some("/recordings/standup.wav"));
+    assert_eq!(entry.duration_seconds, 12.5);
+    assert_eq!(entry.tags, ["work"]);
+    assert_eq!(history.list(None).unwrap().len(), 1);
+}
+
+#[test]
+fn recordings_count_towards_providers_and_apps() {
+    let history = HistoryStore::open_in_memory().unwrap();
+    let mut first = recording("abc", "send the report today");
+    first.provider = Some("OpenAI".to_string());
+    assert!(history.save_recording(&first).unwrap());
+    let context = TagContext {
+        app: Some("Slack".to_string()),
+        profile: None,
+    };
+    history.apply_auto_tags("abc", &context).unwrap();
+
+    // Editing later, after switching services, isn't a new transcription
+    let mut edited = recording("abc", "send the report tomorrow");
+    edited.provider = Some("Groq".to_string());
+    assert!(!history.save_recording(&edited).unwrap());
+
+    let stats = compute(&history, &StatsRange::default()).unwrap();
+    assert_eq!(stats.providers.len(), 1);
+    assert_eq!(stats.providers[0].name, "OpenAI");
+    assert_eq!(stats.top_apps.len(), 1);
+    assert_eq!(stats.top_apps[0].name, "Slack");
+    assert_eq!(stats.top_apps[0].words, 4);
+}
+
+#[test]
+fn deleting_a_recording_drops_how_it_was_injected() {
+    let history = HistoryStore::open_in_memory().unwrap();
+    history.save_recording(&recording("abc", "hello")).unwrap();
+    history
+        .record_injection("abc", Some("Notes"), "paste", InjectionOutcome::Verified)
+        .unwrap();
+
+    history.delete("abc").unwrap();
+
+    assert!(history.injection_record("abc").unwrap().is_none());
+}
+
+#[test]
+fn a_deletion_from_another_device_drops_everything_derived_from_the_entry() {
+    let history = HistoryStore::open_in_memory().unwrap();
+    history.save_recording(&recording("abc", "hello")).unwrap();
+    history.edit_transcript("abc", "hello there").unwrap();
+    history
+        .record_injection("abc", None, "paste", InjectionOutcome::Unverified)
+        .unwrap();
+
+    history
+        .apply_tombstone(&Tombstone {
+            id: "abc".to_string(),
+            deleted_at: 1,
+        })
+        .unwrap();
+
+    assert!(history.get("abc").is_err());
+    assert!(history.list_versions("abc").unwrap().is_empty());
+    assert!(history.injection_record("abc").unwrap().is_none());
+}
@@ -0,0 +1,27 @@
+//! Choosing a translation and falling back to English
+
+use whispering_lib::i18n::{negotiate, t, t_args, SUPPORTED_LOCALES};
+
+#[test]
+fn negotiates_by_primary_language() {
+    assert_eq!(negotiate("de-AT"), Some("de"));
+    assert_eq!(negotiate("fr_CA.UTF-8"), Some("fr"));
+    assert_eq!(negotiate("ES"), Some("es"));
+    assert_eq!(negotiate("ja-JP"), None);
+}
+
+#[test]
+fn every_translation_parses_and_has_the_tray_labels() {
+    for (locale, source) in SUPPORTED_LOCALES {
+        let resource = fluent_bundle::FluentResource::try_new(source.to_string());
+        assert!(resource.is_ok(), "{} has syntax errors", locale);
+        assert!(source.contains("tray-quit ="), "{} lacks tray-quit", locale);
+    }
+}
+
+#[test]
+fn fills_in_arguments_and_falls_back_to_the_id() {
+    let text = t_args("notify-processing-timeout", &[("seconds", "60")]);
+    assert!(text.contains("60"));
+    assert_eq!(t("no-such-message"), "no-such-message");
+}
@@ -0,0 +1,109 @@
+//! Importing history from other dictation tools
+
+use std::path::Path;
+use whispering_lib::history::{HistorySource, HistoryStore};
+use whispering_lib::import::{import, ImportFormat};
+
+fn write(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn imports_superwhisper_recordings_with_their_audio() {
+    let dir = tempfile::tempdir().unwrap();
+    let recording = dir.path().join("1714557600");
+    write(
+        &recording.join("meta.json"),
+        r#"{"result": "Hello from superwhisper", "datetime": "2024-05-01T10:00:00Z", "duration": 2500, "modelName": "Ultra"}"#,
+    );
+    write(&recording.join("output.wav"), "RIFF");
+    // Not a recording
+    write(&dir.path().join("notes/readme.txt"), "ignored");
+
+    let history = HistoryStore::open_in_memory().unwrap();
+    let report = import(&history, dir.path(), ImportFormat::Superwhisper, false).unwrap();
+    assert_eq!((report.found, report.imported), (1, 1));
+
+    let entry = &history.list(None).unwrap()[0];
+    assert_eq!(entry.transcript, "Hello from superwhisper");
+    assert_eq!(entry.source, HistorySource::Import);
+    assert_eq!(entry.created_at, 1_714_557_600_000);
+    assert_eq!(entry.duration_seconds, 2.5);
+    assert_eq!(entry.provider.as_deref(), Some("Ultra"));
+    assert!(entry.audio_path.as_deref().unwrap().ends_with("output.wav"));
+}
+
+#[test]
+fn reads_subtitles_exported_by_macwhisper_and_aiko() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        &dir.path().join("Standup.srt"),
+        "1\n00:00:00,000 --> 00:00:02,000\nGood morning\n\n2\n00:00:02,000 --> 00:01:04,500\neveryone.\n",
+    );
+    write(
+        &dir.path().join("Memo.vtt"),
+        "WEBVTT\n\n00:00.000 --> 00:03.000\nBuy milk.\n",
+    );
+
+    let history = HistoryStore::open_in_memory().unwrap();
+    let report = import(&history, dir.path(), ImportFormat::Aiko, false).unwrap();
+    assert_eq!(report.imported, 2);
+
+    let standup = report
+        .preview
+        .iter()
+        .find(|entry| entry.title.as_deref() == Some("Standup"))
+        .unwrap();
+    assert_eq!(standup.transcript, "Good morning everyone.");
+    assert_eq!(standup.duration_seconds, 64.5);
+    assert_eq!(standup.provider.as_deref(), Some("Aiko"));
+}
+
+#[test]
+fn maps_csv_and_json_columns_by_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("history.csv");
+    write(
+        &csv,
+        "Date,Text,Duration,Tags\n2024-05-01 09:30:00,\"First, with a comma\",12,work;idea\n2024-05-02,Second,,\n",
+    );
+    let json = dir.path().join("history.json");
+    write(
+        &json,
+        r#"{"entries": [{"id": "a", "transcript": "From JSON", "createdAt": 1714557600000, "favorite": true}, {"id": "b"}]}"#,
+    );
+
+    let history = HistoryStore::open_in_memory().unwrap();
+    let report = import(&history, &csv, ImportFormat::Csv, false).unwrap();
+    assert_eq!(report.imported, 2);
+    let first = &report.preview[0];
+    assert_eq!(first.transcript, "First, with a comma");
+    assert_eq!(first.tags, vec!["work", "idea"]);
+    assert_eq!(first.duration_seconds, 12.0);
+
+    let report = import(&history, &json, ImportFormat::Json, false).unwrap();
+    assert_eq!((report.found, report.imported), (2, 1));
+    assert_eq!(report.problems.len(), 1);
+    assert!(report.preview[0].favorite);
+    assert_eq!(report.preview[0].created_at, 1_714_557_600_000);
+}
+
+#[test]
+fn dry_runs_store_nothing_and_reimports_skip_what_exists() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("note.txt");
+    write(&file, "Just a note");
+    let history = HistoryStore::open_in_memory().unwrap();
+
+    let report = import(&history, &file, ImportFormat::MacWhisper, true).unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.imported, 1);
+    assert_eq!(report.preview[0].transcript, "Just a note");
+    assert!(history.list(None).unwrap().is_empty());
+
+    import(&history, &file, ImportFormat::MacWhisper, false).unwrap();
+    let report = import(&history, &file, ImportFormat::MacWhisper, false).unwrap();
+    assert_eq!((report.imported, report.duplicates), (0, 1));
+    assert_eq!(history.list(None).unwrap().len(), 1);
+}
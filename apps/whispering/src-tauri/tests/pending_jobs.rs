@@ -0,0 +1,84 @@
+//! The on-disk queue of files waiting to be transcribed
+
+use std::path::Path;
+use whispering_lib::jobs::pending::{PendingJobs, PendingSource, MAX_RESUMES, PENDING_FILE};
+
+fn audio(dir: &Path, name: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, b"RIFF").unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn finished_jobs_are_not_interrupted() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join(PENDING_FILE);
+    let done = audio(dir.path(), "done.wav");
+    let crashed = audio(dir.path(), "crashed.wav");
+
+    let pending = PendingJobs::open(&file);
+    pending.enqueue(&done, PendingSource::Recording).unwrap();
+    pending.enqueue(&crashed, PendingSource::File).unwrap();
+    pending.enqueue(&crashed, PendingSource::File).unwrap();
+    assert!(pending.finish(&done).unwrap());
+    assert!(!pending.finish(&done).unwrap());
+    // Jobs queued during this launch weren't interrupted
+    assert!(pending.take_interrupted().unwrap().resume.is_empty());
+
+    let interrupted = PendingJobs::open(&file).take_interrupted().unwrap();
+    let paths: Vec<_> = interrupted.resume.iter().map(|job| &job.path).collect();
+    assert_eq!(paths, [&crashed]);
+    assert_eq!(interrupted.resume[0].source, PendingSource::File);
+    assert!(interrupted.retry.is_empty());
+}
+
+#[test]
+fn interrupted_jobs_are_handed_out_once_per_launch() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join(PENDING_FILE);
+    let path = audio(dir.path(), "recording.wav");
+    PendingJobs::open(&file)
+        .enqueue(&path, PendingSource::Daemon)
+        .unwrap();
+
+    let pending = PendingJobs::open(&file);
+    assert_eq!(pending.take_interrupted().unwrap().resume.len(), 1);
+    let again = pending.take_interrupted().unwrap();
+    assert!(again.resume.is_empty() && again.retry.is_empty());
+    // Still queued until the frontend finishes it
+    assert_eq!(pending.list().len(), 1);
+}
+
+#[test]
+fn jobs_that_keep_failing_to_resume_are_only_offered_for_retry() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join(PENDING_FILE);
+    let path = audio(dir.path(), "crashes.wav");
+    PendingJobs::open(&file)
+        .enqueue(&path, PendingSource::Recording)
+        .unwrap();
+
+    for resumes in 1..=MAX_RESUMES {
+        let interrupted = PendingJobs::open(&file).take_interrupted().unwrap();
+        assert_eq!(interrupted.resume[0].resumes, resumes);
+    }
+    let interrupted = PendingJobs::open(&file).take_interrupted().unwrap();
+    assert!(interrupted.resume.is_empty());
+    assert_eq!(interrupted.retry.len(), 1);
+}
+
+#[test]
+fn drops_jobs_whose_file_is_gone() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join(PENDING_FILE);
+    let path = audio(dir.path(), "deleted.wav");
+    PendingJobs::open(&file)
+        .enqueue(&path, PendingSource::Recording)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let pending = PendingJobs::open(&file);
+    let interrupted = pending.take_interrupted().unwrap();
+    assert!(interrupted.resume.is_empty() && interrupted.retry.is_empty());
+    assert!(pending.list().is_empty());
+}
@@ -0,0 +1,46 @@
+//! The live transcript feed paired phones long-poll
+#![cfg(feature = "http-api")]
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use whispering_lib::remote::live::LiveFeed;
+
+#[test]
+fn answers_at_once_when_there_is_something_newer() {
+    let feed = LiveFeed::new();
+    feed.update(|snapshot| snapshot.interim = "hello".to_string());
+
+    let snapshot = feed.wait(0, Duration::from_secs(5));
+    assert_eq!(snapshot.seq, 1);
+    assert_eq!(snapshot.interim, "hello");
+}
+
+#[test]
+fn waits_for_the_next_change() {
+    let feed = Arc::new(LiveFeed::new());
+    let updater = {
+        let feed = feed.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            feed.update(|snapshot| snapshot.committed = "first words".to_string());
+        })
+    };
+
+    let snapshot = feed.wait(0, Duration::from_secs(5));
+    updater.join().unwrap();
+    assert_eq!(snapshot.seq, 1);
+    assert_eq!(snapshot.committed, "first words");
+}
+
+#[test]
+fn gives_up_with_the_same_snapshot() {
+    let feed = LiveFeed::new();
+    feed.update(|snapshot| snapshot.last_transcript = Some("done".to_string()));
+
+    let started = Instant::now();
+    let snapshot = feed.wait(1, Duration::from_millis(50));
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    assert_eq!(snapshot.seq, 1);
+    assert_eq!(snapshot.last_transcript.as_deref(), Some("done"));
+}
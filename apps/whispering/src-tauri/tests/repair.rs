@@ -0,0 +1,101 @@
+//! Finding and repairing damaged WAV recordings
+
+use std::path::{Path, PathBuf};
+use whispering_lib::history::{HistoryEntry, HistorySource, HistoryStore};
+use whispering_lib::repair::{
+    check_file, check_history, repair_file, RecordingHealth, DAMAGED_TAG,
+};
+
+const SAMPLE_RATE: u32 = 16_000;
+
+/// A mono float WAV with `frames` frames, whose header claims `declared`
+/// bytes of audio
+fn wav(dir: &Path, name: &str, frames: u32, declared: u32) -> PathBuf {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + declared).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&3u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 4).to_le_bytes());
+    bytes.extend_from_slice(&4u16.to_le_bytes());
+    bytes.extend_from_slice(&32u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&declared.to_le_bytes());
+    for _ in 0..frames {
+        bytes.extend_from_slice(&0.25f32.to_le_bytes());
+    }
+    let path = dir.join(name);
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn accepts_a_complete_recording() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = wav(dir.path(), "ok.wav", SAMPLE_RATE, SAMPLE_RATE * 4);
+
+    let check = check_file(&path);
+    assert_eq!(check.health, RecordingHealth::Ok);
+    assert_eq!(check.duration_seconds, Some(1.0));
+}
+
+#[test]
+fn repairs_the_header_of_an_interrupted_recording() {
+    let dir = tempfile::tempdir().unwrap();
+    // The writer crashed before its first header update
+    let path = wav(dir.path(), "crashed.wav", SAMPLE_RATE * 2, 0);
+    assert_eq!(check_file(&path).health, RecordingHealth::Repairable);
+
+    let check = repair_file(&path).unwrap();
+    assert_eq!(check.health, RecordingHealth::Ok);
+    assert_eq!(check.duration_seconds, Some(2.0));
+    let decoded = hound::WavReader::open(&path).unwrap();
+    assert_eq!(decoded.duration(), SAMPLE_RATE * 2);
+}
+
+#[test]
+fn drops_a_partial_frame_cut_off_by_a_full_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = wav(dir.path(), "cut.wav", SAMPLE_RATE, u32::MAX);
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes.extend_from_slice(&[0, 0]);
+    std::fs::write(&path, &bytes).unwrap();
+
+    let check = repair_file(&path).unwrap();
+    assert_eq!(check.health, RecordingHealth::Ok);
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().len(),
+        44 + u64::from(SAMPLE_RATE) * 4
+    );
+}
+
+#[test]
+fn flags_recordings_beyond_repair() {
+    let dir = tempfile::tempdir().unwrap();
+    let empty = wav(dir.path(), "empty.wav", 0, 0);
+    let garbage = dir.path().join("garbage.wav");
+    std::fs::write(&garbage, "not audio").unwrap();
+
+    assert_eq!(check_file(&empty).health, RecordingHealth::Empty);
+    assert_eq!(check_file(&garbage).health, RecordingHealth::Unrecoverable);
+    assert_eq!(
+        check_file(&dir.path().join("gone.wav")).health,
+        RecordingHealth::Missing
+    );
+    assert_eq!(
+        repair_file(&garbage).unwrap().health,
+        RecordingHealth::Unrecoverable
+    );
+
+    let history = HistoryStore::open_in_memory().unwrap();
+    let mut entry = HistoryEntry::new(HistorySource::Recording, "hello".to_string());
+    entry.audio_path = Some(garbage.display().to_string());
+    history.insert(&entry).unwrap();
+
+    let checks = check_history(&history).unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(history.get(&entry.id).unwrap().tags, vec![DAMAGED_TAG]);
+}
@@ -0,0 +1,46 @@
+//! Scoping per-instance files to the login session
+
+use whispering_lib::ipc::endpoint_file;
+use whispering_lib::session::{runtime_dir, scope, temp_dir, Session};
+
+#[test]
+fn scope_names_the_user_and_login() {
+    let session = Session {
+        user: "alice".to_string(),
+        id: Some("3".to_string()),
+    };
+    assert_eq!(session.scope(), "alice-3");
+
+    let session = Session {
+        user: "alice".to_string(),
+        id: None,
+    };
+    assert_eq!(session.scope(), "alice");
+}
+
+#[test]
+fn scope_is_safe_in_a_file_name() {
+    let session = Session {
+        user: r"OFFICE\Jo Smith".to_string(),
+        id: Some("c/2".to_string()),
+    };
+    assert_eq!(session.scope(), "OFFICE_Jo_Smith-c_2");
+}
+
+#[test]
+fn per_instance_files_live_under_the_session() {
+    assert!(runtime_dir().unwrap().ends_with(scope()));
+    assert!(endpoint_file().unwrap().starts_with(runtime_dir().unwrap()));
+    assert!(temp_dir().unwrap().ends_with(scope()));
+}
+
+#[cfg(unix)]
+#[test]
+fn temp_dir_is_private_to_the_user() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = temp_dir().unwrap();
+    assert!(!dir.starts_with(std::env::temp_dir()));
+    let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+}
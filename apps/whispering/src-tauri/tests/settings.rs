@@ -0,0 +1,186 @@
+//! Native settings commands: persistence, change events and policy enforcement
+
+mod common;
+
+use common::{events, invoke, next_event, window};
+use serde_json::{json, Value};
+use std::path::Path;
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::App;
+use whispering_lib::events::{AppEvent, SettingsChanged};
+use whispering_lib::settings::commands::{get_native_settings, get_policy, set_native_settings};
+use whispering_lib::settings::policy::{self, Policy};
+use whispering_lib::settings::{NativeSettings, SettingsStore, SETTINGS_FILE};
+
+fn app(path: &Path, policy: Policy) -> App<MockRuntime> {
+    mock_builder()
+        .manage(SettingsStore::open(path, policy))
+        .invoke_handler(tauri::generate_handler![
+            get_native_settings,
+            set_native_settings,
+            get_policy
+        ])
+        .build(mock_context(noop_assets()))
+        .expect("failed to build the mock app")
+}
+
+fn settings(window: &tauri::WebviewWindow<MockRuntime>) -> Value {
+    invoke(window, "get_native_settings", json!({})).unwrap()
+}
+
+#[test]
+fn defaults_without_a_settings_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = app(&dir.path().join(SETTINGS_FILE), Policy::default());
+    let window = window(&app);
+
+    let expected = serde_json::to_value(NativeSettings::default()).unwrap();
+    assert_eq!(settings(&window), expected);
+    assert!(!dir.path().join(SETTINGS_FILE).exists());
+}
+
+#[test]
+fn saved_settings_are_persisted_and_announced() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(SETTINGS_FILE);
+    let app = app(&path, Policy::default());
+    let window = window(&app);
+    let changes = events(&app, SettingsChanged::NAME);
+
+    let mut changed = settings(&window);
+    changed["closeToTray"] = json!(true);
+    changed["processingTimeoutSecs"] = json!(30);
+    let saved: Value = invoke(
+        &window,
+        "set_native_settings",
+        json!({ "settings": changed }),
+    )
+    .unwrap();
+    assert_eq!(saved, changed);
+    assert_eq!(next_event(&changes), changed);
+
+    let reopened = SettingsStore::open(&path, Policy::default()).get();
+    assert_eq!(serde_json::to_value(reopened).unwrap(), changed);
+}
+
+#[test]
+fn unchanged_settings_are_not_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(SETTINGS_FILE);
+    let app = app(&path, Policy::default());
+    let window = window(&app);
+
+    let current = settings(&window);
+    invoke::<Value>(
+        &window,
+        "set_native_settings",
+        json!({ "settings": current }),
+    )
+    .unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn policy_values_win_over_saved_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut policy = Policy {
+        local_only: true,
+        ..Default::default()
+    };
+    policy
+        .settings
+        .insert("closeToTray".to_string(), json!(false));
+    let app = app(&dir.path().join(SETTINGS_FILE), policy);
+    let window = window(&app);
+
+    let mut changed = settings(&window);
+    changed["privacyMode"] = json!(false);
+    changed["closeToTray"] = json!(true);
+    changed["soundFeedback"] = json!(!changed["soundFeedback"].as_bool().unwrap());
+    let saved: Value = invoke(
+        &window,
+        "set_native_settings",
+        json!({ "settings": changed }),
+    )
+    .unwrap();
+
+    assert_eq!(saved["privacyMode"], true);
+    assert_eq!(saved["closeToTray"], false);
+    assert_eq!(saved["soundFeedback"], changed["soundFeedback"]);
+
+    let policy: Value = invoke(&window, "get_policy", json!({})).unwrap();
+    assert_eq!(policy["localOnly"], true);
+}
+
+#[test]
+fn a_policy_value_of_the_wrong_type_only_loses_its_own_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut policy = Policy::default();
+    policy
+        .settings
+        .insert("closeToTray".to_string(), json!("yes"));
+    policy
+        .settings
+        .insert("soundFeedback".to_string(), json!(false));
+    let path = dir.path().join(SETTINGS_FILE);
+
+    let settings = SettingsStore::open(&path, policy).get();
+
+    assert!(!settings.sound_feedback);
+    assert!(!settings.close_to_tray);
+}
+
+#[test]
+fn an_unreadable_policy_fails_closed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("policy.toml");
+    assert!(!policy::load_from(&path).local_only);
+
+    std::fs::write(&path, "local_only = maybe\n").unwrap();
+    let policy = policy::load_from(&path);
+
+    assert!(policy.local_only);
+    assert!(policy.disable_history);
+    assert!(policy.source.is_some());
+}
+
+#[test]
+fn hotkeys_and_providers_are_sections_of_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(SETTINGS_FILE);
+    let app = app(&path, Policy::default());
+    let window = window(&app);
+
+    let mut changed = settings(&window);
+    changed["hotkeys"] = json!({
+        "toggleManualRecording": "CommandOrControl+Shift+;",
+        "cancelManualRecording": "",
+    });
+    changed["providers"]["transcription"] = json!("groq");
+    invoke::<Value>(
+        &window,
+        "set_native_settings",
+        json!({ "settings": changed }),
+    )
+    .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("[hotkeys]"));
+    assert!(contents.contains("toggleManualRecording = \"CommandOrControl+Shift+;\""));
+    assert!(contents.contains("[providers]"));
+    assert!(contents.contains("transcription = \"groq\""));
+    let reopened = SettingsStore::open(&path, Policy::default()).get();
+    assert_eq!(serde_json::to_value(reopened).unwrap(), changed);
+
+    // Commands that are gone drop out of the file
+    changed["hotkeys"] = json!({ "toggleManualRecording": "F9" });
+    invoke::<Value>(
+        &window,
+        "set_native_settings",
+        json!({ "settings": changed }),
+    )
+    .unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("toggleManualRecording = \"F9\""));
+    assert!(!contents.contains("cancelManualRecording"));
+}
@@ -0,0 +1,46 @@
+//! Stored UTC times shown in the configured zone and style
+
+use chrono_tz::Tz;
+use whispering_lib::timestamps::{TimestampStyle, Timestamps, Zone};
+
+/// 2024-01-05 13:30:05 UTC
+const MILLIS: i64 = 1_704_461_405_000;
+
+#[test]
+fn parses_time_zones() {
+    assert_eq!(Zone::parse("").unwrap(), Zone::Local);
+    assert_eq!(Zone::parse("utc").unwrap(), Zone::Named(Tz::UTC));
+    assert_eq!(
+        Zone::parse(" Europe/Berlin ").unwrap(),
+        Zone::Named(Tz::Europe__Berlin)
+    );
+    assert!(Zone::parse("Mars/Olympus").is_err());
+}
+
+#[test]
+fn writes_times_in_the_configured_zone() {
+    let berlin = Timestamps::new(
+        Zone::Named(Tz::Europe__Berlin),
+        TimestampStyle::Iso,
+        "de-DE",
+    );
+    assert_eq!(berlin.display(MILLIS), "2024-01-05 14:30:05");
+    assert_eq!(berlin.rfc3339(MILLIS), "2024-01-05T14:30:05+01:00");
+    assert_eq!(berlin.format(MILLIS, "%d %B").unwrap(), "05 January");
+    assert!(berlin.format(MILLIS, "%Q").is_err());
+}
+
+#[test]
+fn follows_the_locale_date_order_except_in_filenames() {
+    let zone = Zone::Named(Tz::UTC);
+    let us = Timestamps::new(zone, TimestampStyle::Locale, "en-US");
+    let germany = Timestamps::new(zone, TimestampStyle::Locale, "de-DE");
+    let britain = Timestamps::new(zone, TimestampStyle::Locale, "en-GB");
+
+    assert_eq!(us.display(MILLIS), "01/05/2024 01:30:05 PM");
+    assert_eq!(germany.display(MILLIS), "05.01.2024 13:30:05");
+    assert_eq!(britain.display(MILLIS), "05/01/2024 13:30:05");
+    for timestamps in [us, germany, britain] {
+        assert_eq!(timestamps.filename(MILLIS), "2024-01-05_13-30-05");
+    }
+}
@@ -0,0 +1,137 @@
+//! Tray commands without an OS tray, as on desktops that can't show one and
+//! before the frontend has created it
+
+mod common;
+
+use common::{eventually, invoke, window};
+use serde_json::{json, Value};
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::{App, Manager};
+use whispering_lib::app_state::{AppState, StateEvent};
+use whispering_lib::settings::NativeSettings;
+use whispering_lib::tray::commands::{
+    get_tray_backend, get_tray_info, is_tray_supported, set_tray_badge, set_tray_icon,
+};
+use whispering_lib::tray::{
+    IconTheme, TrayBackend, TrayCapability, TrayIconKind, TrayManager, TrayTheme,
+};
+
+fn app(capability: TrayCapability) -> App<MockRuntime> {
+    mock_builder()
+        .manage(AppState::new())
+        .manage(TrayManager::with_capability(Some(capability)))
+        .invoke_handler(tauri::generate_handler![
+            set_tray_badge,
+            set_tray_icon,
+            is_tray_supported,
+            get_tray_backend,
+            get_tray_info
+        ])
+        .build(mock_context(noop_assets()))
+        .expect("failed to build the mock app")
+}
+
+fn native() -> TrayCapability {
+    TrayCapability::available(TrayBackend::Native, "test")
+}
+
+#[test]
+fn badge_is_kept_until_the_tray_exists() {
+    let app = app(native());
+    let window = window(&app);
+
+    let result: Result<Value, Value> = invoke(&window, "set_tray_badge", json!({ "count": 3 }));
+    assert_eq!(result, Ok(Value::Null));
+    assert_eq!(app.state::<TrayManager>().display().badge, 3);
+
+    invoke::<Value>(&window, "set_tray_badge", json!({ "count": 0 })).unwrap();
+    assert_eq!(app.state::<TrayManager>().display().badge, 0);
+}
+
+#[test]
+fn icon_follows_the_app_state_without_a_tray() {
+    let app = app(native());
+    let tray = app.state::<TrayManager>();
+    tray.follow_app_state(app.handle().clone());
+
+    let state = app.state::<AppState>();
+    state.record(StateEvent::RecordingStarted { recording_id: None });
+    eventually(|| tray.display().icon == TrayIconKind::Recording);
+
+    state.record(StateEvent::RecordingStopped);
+    eventually(|| tray.display().icon == TrayIconKind::Processing);
+
+    state.record(StateEvent::Completed);
+    eventually(|| tray.display().icon == TrayIconKind::Idle);
+}
+
+#[test]
+fn info_has_the_icon_drawn_for_the_current_state() {
+    let app = app(native());
+    let window = window(&app);
+
+    invoke::<Value>(&window, "set_tray_icon", json!({ "icon": "recording" })).unwrap();
+    let info: Value = invoke(&window, "get_tray_info", json!({})).unwrap();
+    assert_eq!(info["icon"], "recording");
+    assert_eq!(info["theme"], "classic");
+    assert_eq!(info["tooltip"], "Whispering - Recording");
+    let pixels = info["width"].as_u64().unwrap() * info["height"].as_u64().unwrap();
+    assert_eq!(info["rgba"].as_array().unwrap().len() as u64, pixels * 4);
+}
+
+#[test]
+fn drawn_themes_use_a_shape_per_state() {
+    let settings = NativeSettings {
+        tray_theme: TrayTheme::HighContrast,
+        ..Default::default()
+    };
+    let theme = IconTheme::from_settings(&settings);
+    let idle = theme.icon(TrayIconKind::Idle).unwrap();
+    let recording = theme.icon(TrayIconKind::Recording).unwrap();
+    let processing = theme.icon(TrayIconKind::Processing).unwrap();
+    assert_ne!(idle, recording);
+    assert_ne!(recording, processing);
+    // The idle ring is hollow, the recording disc isn't
+    assert_eq!(idle.get_pixel(32, 32)[3], 0);
+    assert_eq!(recording.get_pixel(32, 32)[3], 255);
+}
+
+#[test]
+fn custom_theme_falls_back_to_classic() {
+    let settings = NativeSettings {
+        tray_theme: TrayTheme::Custom,
+        tray_theme_folder: "/nonexistent/tray-icons".to_string(),
+        ..Default::default()
+    };
+    let custom = IconTheme::from_settings(&settings).icon(TrayIconKind::Idle);
+    let classic = IconTheme::default().icon(TrayIconKind::Idle);
+    assert_eq!(custom.unwrap(), classic.unwrap());
+}
+
+#[test]
+fn reports_the_detected_backend() {
+    let app = app(native());
+    let window = window(&app);
+
+    let backend: String = invoke(&window, "get_tray_backend", json!({})).unwrap();
+    assert_eq!(backend, "native");
+    let capability: Value = invoke(&window, "is_tray_supported", json!({})).unwrap();
+    assert_eq!(capability["supported"], true);
+}
+
+#[test]
+fn explains_why_there_is_no_tray() {
+    let app = app(TrayCapability::unavailable(
+        "No status notifier host",
+        "Install the AppIndicator extension",
+    ));
+    let window = window(&app);
+
+    let capability: Value = invoke(&window, "is_tray_supported", json!({})).unwrap();
+    assert_eq!(capability["supported"], false);
+    assert_eq!(capability["backend"], "unavailable");
+    assert_eq!(
+        capability["fallbackAdvice"],
+        "Install the AppIndicator extension"
+    );
+}
@@ -0,0 +1,59 @@
+//! The watchdog stopping a pipeline stuck in Processing
+
+mod common;
+
+use common::{events, eventually, next_event, TIMEOUT};
+use serde_json::json;
+use tauri::test::{mock_builder, mock_context, noop_assets};
+use tauri::Manager;
+use whispering_lib::app_state::{AppState, AppStatus, StateEvent};
+use whispering_lib::error::{AppError, Result};
+use whispering_lib::jobs::Jobs;
+use whispering_lib::settings::policy::Policy;
+use whispering_lib::settings::SettingsStore;
+use whispering_lib::transcription::TranscriptionError;
+use whispering_lib::watchdog;
+
+#[test]
+fn a_stuck_transcription_is_cancelled_and_the_state_reset() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = SettingsStore::open(&dir.path().join("settings.toml"), Policy::default());
+    // The watchdog tells the user with a notification
+    let app = mock_builder()
+        .plugin(tauri_plugin_notification::init())
+        .manage(AppState::new())
+        .manage(Jobs::new())
+        .manage(settings)
+        .build(mock_context(noop_assets()))
+        .expect("failed to build the mock app");
+    app.state::<SettingsStore>()
+        .update(app.handle(), |settings| {
+            settings.processing_timeout_secs = 1
+        })
+        .unwrap();
+    let timeouts = events(&app, "watchdog://timeout");
+    watchdog::start(app.handle().clone());
+
+    let state = app.state::<AppState>();
+    state.record(StateEvent::RecordingStarted { recording_id: None });
+    state.record(StateEvent::RecordingStopped);
+    assert_eq!(state.current(), AppStatus::Processing);
+
+    // A provider request that never returns
+    let jobs = app.state::<Jobs>();
+    let job = jobs.start(None);
+    let result: Result<()> = tauri::async_runtime::block_on(async {
+        tokio::time::timeout(TIMEOUT, job.run(std::future::pending()))
+            .await
+            .expect("the watchdog didn't cancel the job")
+    });
+    assert!(matches!(
+        result,
+        Err(AppError::Transcription(
+            TranscriptionError::Cancelled { .. }
+        ))
+    ));
+
+    assert_eq!(next_event(&timeouts), json!(1));
+    eventually(|| state.current() == AppStatus::Idle);
+}
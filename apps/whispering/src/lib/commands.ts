@@ -57,6 +57,24 @@ export const commands = [
 		on: 'Pressed',
 		callback: () => rpc.commands.toggleVadRecording.execute(undefined),
 	},
+	{
+		id: 'transcribeClipboardFile',
+		title: 'Transcribe audio file on clipboard',
+		on: 'Pressed',
+		callback: () => rpc.commands.transcribeAudioFile.execute({}),
+	},
+	{
+		id: 'toggleVoiceNote',
+		title: 'Record voice note (no transcription)',
+		on: 'Pressed',
+		callback: () => rpc.commands.toggleVoiceNote.execute(undefined),
+	},
+	{
+		id: 'readBackLastTranscript',
+		title: 'Read back last transcript',
+		on: 'Pressed',
+		callback: () => rpc.commands.readBackLastTranscript.execute(undefined),
+	},
 ] as const satisfies SatisfiedCommand[];
 
 export type Command = (typeof commands)[number];
@@ -1,13 +1,17 @@
 // API Key components
 
 export { default as AnthropicApiKeyInput } from './api-key-inputs/AnthropicApiKeyInput.svelte';
+export { default as AssemblyAIApiKeyInput } from './api-key-inputs/AssemblyAIApiKeyInput.svelte';
+export { default as AzureSpeechApiKeyInput } from './api-key-inputs/AzureSpeechApiKeyInput.svelte';
 export { default as DeepgramApiKeyInput } from './api-key-inputs/DeepgramApiKeyInput.svelte';
 export { default as ElevenLabsApiKeyInput } from './api-key-inputs/ElevenLabsApiKeyInput.svelte';
 export { default as GoogleApiKeyInput } from './api-key-inputs/GoogleApiKeyInput.svelte';
+export { default as GoogleSpeechApiKeyInput } from './api-key-inputs/GoogleSpeechApiKeyInput.svelte';
 export { default as GroqApiKeyInput } from './api-key-inputs/GroqApiKeyInput.svelte';
 export { default as MistralApiKeyInput } from './api-key-inputs/MistralApiKeyInput.svelte';
 export { default as OpenAiApiKeyInput } from './api-key-inputs/OpenAiApiKeyInput.svelte';
 export { default as OpenRouterApiKeyInput } from './api-key-inputs/OpenRouterApiKeyInput.svelte';
+export { default as SpeechmaticsApiKeyInput } from './api-key-inputs/SpeechmaticsApiKeyInput.svelte';
 // Shared components
 export { default as CompressionBody } from './CompressionBody.svelte';
 export { default as CompressionSelector } from './selectors/CompressionSelector.svelte';
@@ -1,4 +1,6 @@
 export { ALWAYS_ON_TOP_OPTIONS, ALWAYS_ON_TOP_VALUES } from './always-on-top';
+export { APP_LANGUAGE_OPTIONS, APP_LANGUAGE_VALUES } from './languages';
+export { TRAY_THEME_OPTIONS, TRAY_THEME_VALUES } from './tray-themes';
 
 export {
 	recorderStateToIcons,
@@ -0,0 +1,17 @@
+/**
+ * Languages the tray, notifications and error hints are translated into;
+ * see src-tauri/locales. 'system' follows the operating system's language.
+ */
+
+export const APP_LANGUAGE_VALUES = ['system', 'en', 'de', 'es', 'fr'] as const;
+
+export const APP_LANGUAGE_OPTIONS = [
+	{ value: 'system', label: 'System language' },
+	{ value: 'en', label: 'English' },
+	{ value: 'de', label: 'Deutsch' },
+	{ value: 'es', label: 'Español' },
+	{ value: 'fr', label: 'Français' },
+] as const satisfies {
+	value: (typeof APP_LANGUAGE_VALUES)[number];
+	label: string;
+}[];
@@ -0,0 +1,20 @@
+/**
+ * Tray icon sets; see src-tauri/src/tray/theme.rs
+ */
+
+export const TRAY_THEME_VALUES = [
+	'classic',
+	'highContrast',
+	'monochrome',
+	'custom',
+] as const;
+
+export const TRAY_THEME_OPTIONS = [
+	{ value: 'classic', label: 'Classic' },
+	{ value: 'highContrast', label: 'High contrast' },
+	{ value: 'monochrome', label: 'Monochrome' },
+	{ value: 'custom', label: 'Custom folder' },
+] as const satisfies {
+	value: (typeof TRAY_THEME_VALUES)[number];
+	label: string;
+}[];
@@ -0,0 +1,122 @@
+// Generated by src-tauri/build.rs from src-tauri/src/events/catalog.rs.
+// Do not edit; add events to the catalog instead.
+
+import type {
+	AppStatus,
+	BackupStatus,
+	BluetoothHeadset,
+	BulkFinished,
+	BulkProgress,
+	CorrectionSuggestion,
+	DiskSpace,
+	DroppedAudio,
+	HistoryEntry,
+	InboxFailure,
+	InjectionReport,
+	IpcCommand,
+	KeywordMatch,
+	LatencyBreakdown,
+	MeetingStart,
+	NativeSettings,
+	PairedDevice,
+	PlaybackStatus,
+	PreferredDevice,
+	RecordingPreset,
+	SessionChange,
+	StreamingStatus,
+	StreamingTranscript,
+	SummaryProgress,
+	SyncConflict,
+	SyncSummary,
+	TranscribeFile,
+	TriggerFired,
+	WatcherError,
+} from './payloads';
+
+/** Payload of each event the backend emits, by event name */
+export type EventPayloads = {
+	/** The app state machine moved to a new status */
+	'state://changed': AppStatus;
+	/** Text for the main window's live region to have the screen reader speak, on platforms without a native announcement API */
+	'accessibility://announce': string;
+	/** A backup started, finished or failed */
+	'backup://status': BackupStatus;
+	/** A bulk job finished another entry */
+	'bulk://progress': BulkProgress;
+	/** A bulk job ran to completion or was cancelled */
+	'bulk://finished': BulkFinished;
+	/** An audio file should be transcribed to the clipboard */
+	'clipboard-audio://transcribe': TranscribeFile;
+	/** The same correction was made often enough to suggest automating it */
+	'corrections://suggested': CorrectionSuggestion;
+	/** A double-tap, key sequence, mouse button or pedal trigger fired */
+	'hotkeys://trigger': TriggerFired;
+	/** A phone finished pairing with the inbox */
+	'inbox://paired': PairedDevice;
+	/** A paired device uploaded a recording */
+	'inbox://received': HistoryEntry;
+	/** An uploaded recording was transcribed */
+	'inbox://transcribed': HistoryEntry;
+	/** An uploaded recording couldn't be transcribed and waits in the inbox */
+	'inbox://transcription-failed': InboxFailure;
+	/** The target app runs as administrator; carries its name if known */
+	'injection://elevated': string | null;
+	/** Text went into the target app, or fell back to the clipboard */
+	'injection://result': InjectionReport;
+	/** The CLI or another instance asked for a frontend command */
+	'ipc://command': IpcCommand;
+	/** Stage timings were stored for an entry */
+	'latency://recorded': LatencyBreakdown;
+	/** The language of tray menus, tooltips and notifications changed */
+	'locale://changed': string;
+	/** The user agreed to transcribe a detected meeting */
+	'meetings://start': MeetingStart;
+	/** A meeting summary moved to its next stage */
+	'meetings://summary-progress': SummaryProgress;
+	/** Playback stopped or reached the end */
+	'playback://ended': PlaybackStatus;
+	/** Periodic position update while playing */
+	'playback://position': PlaybackStatus;
+	/** A recording preset was applied from the tray, CLI or settings */
+	'presets://applied': RecordingPreset;
+	/** Presets were saved or deleted */
+	'presets://changed': null;
+	/** A recording's file is missing audio the disk couldn't keep up with */
+	'recorder://audio-dropped': DroppedAudio;
+	/** The selected microphone belongs to a Bluetooth headset */
+	'recorder://bluetooth-headset': BluetoothHeadset;
+	/** A recording was paused because its disk is nearly full */
+	'recorder://disk-space-low': DiskSpace;
+	/** The device priority list now prefers another device */
+	'recorder://preferred-device': PreferredDevice;
+	/** A phone finished pairing as a remote control */
+	'remote://paired': PairedDevice;
+	/** The login session became, or stopped being, the one at the screen */
+	'session://changed': SessionChange;
+	/** Native settings changed, from the frontend, tray or settings file */
+	'settings://changed': NativeSettings;
+	/** The settings file was edited outside the app and reloaded */
+	'settings://reloaded': NativeSettings;
+	/** Live transcription's best guess so far, replaced by later results */
+	'streaming://interim': StreamingTranscript;
+	/** Live transcription text the provider won't revise */
+	'streaming://final': StreamingTranscript;
+	/** A watched phrase was heard during live transcription */
+	'streaming://keyword': KeywordMatch;
+	/** The live transcription connection changed state */
+	'streaming://status': StreamingStatus;
+	/** Syncing overwrote a change made on this device or another */
+	'sync://conflict': SyncConflict;
+	/** Changes from other devices were merged */
+	'sync://synced': SyncSummary;
+	/** The tray asked the main window to open a page */
+	'tray://navigate': string;
+	/** The watchdog cancelled a stuck job; carries the timeout in seconds */
+	'watchdog://timeout': number;
+	/** A file in a watched folder couldn't be transcribed */
+	'watcher://error': WatcherError;
+	/** A file in a watched folder was transcribed */
+	'watcher://transcribed': HistoryEntry;
+};
+
+export type EventName = keyof EventPayloads;
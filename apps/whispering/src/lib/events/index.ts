@@ -0,0 +1,20 @@
+import {
+	type EventCallback,
+	listen as listenToTauri,
+	type UnlistenFn,
+} from '@tauri-apps/api/event';
+import type { EventName, EventPayloads } from './catalog.generated';
+
+export type { EventName, EventPayloads } from './catalog.generated';
+export * from './payloads';
+
+/**
+ * Listen to an event emitted by src-tauri, with the payload typed from the
+ * event catalog so a renamed or reshaped event fails to type-check.
+ */
+export function listen<E extends EventName>(
+	event: E,
+	handler: EventCallback<EventPayloads[E]>,
+): Promise<UnlistenFn> {
+	return listenToTauri<EventPayloads[E]>(event, handler);
+}
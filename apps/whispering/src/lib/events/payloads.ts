@@ -0,0 +1,249 @@
+/**
+ * Payloads of the events in src-tauri/src/events/catalog.rs, matching the
+ * Rust types named there. Event names and which payload goes with which
+ * event are generated into `catalog.generated.ts`; only the shapes live here.
+ */
+
+import type { Settings } from '$lib/settings';
+
+export type { LatencyBreakdown } from '$lib/utils/latency';
+
+export type AppStatus =
+	| { status: 'idle' }
+	| { status: 'recording'; recordingId: string | null }
+	| { status: 'processing' }
+	| { status: 'injecting' }
+	| { status: 'error'; message: string };
+
+export type BackupStatus = {
+	ready: boolean;
+	running: boolean;
+	lastRunAt: number | null;
+	lastSuccessAt: number | null;
+	lastError: string | null;
+	audioFiles: number;
+};
+
+type BulkJobKind = 'delete' | 'export' | 'reprocess' | 'archive';
+
+export type BulkProgress = {
+	jobId: string;
+	kind: BulkJobKind;
+	done: number;
+	failed: number;
+	total: number;
+};
+
+export type BulkFinished = {
+	jobId: string;
+	kind: BulkJobKind;
+	succeeded: number;
+	failed: number;
+	cancelled: boolean;
+	error: string | null;
+};
+
+export type TranscribeFile = {
+	path: string;
+};
+
+export type CorrectionSuggestion = {
+	id: string;
+	from: string;
+	to: string;
+	entryIds: string[];
+	lastSeen: number;
+	status: 'learning' | 'suggested' | 'accepted' | 'dismissed';
+	kind:
+		| { type: 'vocabulary'; term: string }
+		| {
+				type: 'rule';
+				rule: {
+					id: string;
+					pattern: string;
+					replacement: string;
+					caseInsensitive: boolean;
+					enabled: boolean;
+				};
+		  };
+};
+
+export type TriggerFired = {
+	command: string;
+	state: 'Pressed' | 'Released';
+};
+
+export type PairedDevice = {
+	id: string;
+	name: string;
+	pairedAt: number;
+};
+
+export type InboxFailure = {
+	entryId: string;
+	message: string;
+};
+
+export type HistoryEntry = {
+	id: string;
+	createdAt: number;
+	source: 'recording' | 'watchedFolder' | 'inbox' | 'import';
+	sourcePath: string | null;
+	audioPath: string | null;
+	transcript: string;
+	durationSeconds: number;
+	tags: string[];
+	favorite: boolean;
+	provider: string | null;
+	targetApp: string | null;
+	title: string | null;
+	attendees: string[];
+	language: string | null;
+	languageConfidence: number | null;
+};
+
+export type InjectionReport = {
+	app: string | null;
+	method: 'paste' | 'type' | 'native';
+	outcome: 'verified' | 'unverified' | 'fellBack';
+	elevated: boolean;
+};
+
+export type IpcCommand = {
+	/** Passed back to `finish_ipc_command` */
+	id: number;
+	command: string;
+};
+
+export type MeetingStart = {
+	app: string;
+	deviceIdentifier: string | null;
+};
+
+export type SummaryProgress = {
+	audioPath: string;
+	stage: 'transcribing' | 'summarizing' | 'saving';
+};
+
+export type PlaybackStatus = {
+	id: string;
+	positionSeconds: number;
+	durationSeconds: number;
+	playing: boolean;
+};
+
+export type RecordingPreset = {
+	name: string;
+	sampleRate: 16000 | 44100 | 48000;
+	encoding: 'wav' | 'compressed';
+	compressionOptions: string | null;
+	preRollMs: number;
+	vad: boolean;
+};
+
+export type BluetoothHeadset = {
+	device: string;
+	alternative: string | null;
+	switched: boolean;
+};
+
+export type DiskSpace = {
+	/** The recording's file */
+	path: string;
+	availableBytes: number;
+	thresholdBytes: number;
+};
+
+export type DroppedAudio = {
+	/** The recording's file */
+	path: string;
+	seconds: number;
+};
+
+export type SessionChange = {
+	/** Whether the session is the one at the screen */
+	active: boolean;
+	/** Recordings stopped while it wasn't, on becoming active again */
+	stoppedRecordings: string[];
+};
+
+export type PreferredDevice = {
+	device: string | null;
+};
+
+/** Settings Rust acts on while the window is closed; see src-tauri/src/settings */
+export type NativeSettings = {
+	closeToTray: boolean;
+	startMinimized: boolean;
+	soundFeedback: boolean;
+	autoPaste: boolean;
+	privacyMode: boolean;
+	detectMeetings: boolean;
+	meetingAppsAllowed: string[];
+	meetingAppsDenied: string[];
+	warmStart: boolean;
+	preRollMs: number;
+	audioBackend: Settings['recording.cpal.backend'];
+	avoidBluetoothMic: boolean;
+	processingTimeoutSecs: number;
+	screenReaderAnnouncements: boolean;
+	trayTheme: Settings['system.trayTheme'];
+	trayThemeFolder: string;
+	customTrayIconIdle: string;
+	customTrayIconRecording: string;
+	customTrayIconProcessing: string;
+	customStartSound: string;
+	customStopSound: string;
+	/** Empty to follow the system's language */
+	language: string;
+	/** Empty for the system's zone */
+	timeZone: string;
+	timestampStyle: Settings['system.timestampStyle'];
+	fileNameTemplate: string;
+	/** 0 turns the free-space check off */
+	minFreeDiskMb: number;
+	/** Global shortcut of each command by id; '' for none */
+	hotkeys: Record<string, string>;
+	providers: {
+		/** Transcription service id, e.g. 'whispercpp' */
+		transcription: string;
+	};
+};
+
+export type StreamingTranscript = {
+	provider: string;
+	text: string;
+};
+
+export type KeywordMatch = {
+	phrase: string;
+	text: string;
+	provider: string;
+	at: number;
+};
+
+export type StreamingStatus = {
+	provider: string;
+	state: 'connecting' | 'connected' | 'reconnecting' | 'stopped' | 'failed';
+	attempt: number;
+	error: string | null;
+};
+
+export type SyncConflict = {
+	entryId: string | null;
+	device: string;
+	kept: 'local' | 'remote';
+};
+
+export type SyncSummary = {
+	devices: number;
+	updated: number;
+	deleted: number;
+	settingsUpdated: boolean;
+	conflicts: number;
+};
+
+export type WatcherError = {
+	path: string;
+	message: string;
+};
@@ -1,8 +1,19 @@
 import { nanoid } from 'nanoid/non-secure';
+import { extractErrorMessage } from 'wellcrafted/error';
 import { Err, Ok } from 'wellcrafted/result';
 import { fromTaggedError } from '$lib/result';
+import * as services from '$lib/services';
 import { DbServiceErr } from '$lib/services/db';
 import { settings } from '$lib/stores/settings.svelte';
+import { settleAppState } from '$lib/utils/app-state';
+import { getClipboardAudioPath } from '$lib/utils/clipboard-audio';
+import { reportLatency, type StageTiming, timeStage } from '$lib/utils/latency';
+import { speak } from '$lib/utils/tts';
+import { nameSavedRecording } from '$lib/utils/file-names';
+import { saveToHistory } from '$lib/utils/history';
+import { publishTranscript } from '$lib/utils/ipc';
+import { finishPendingJob } from '$lib/utils/pending-jobs';
+import { saveVoiceNoteFile } from '$lib/utils/voice-notes';
 import { rpc } from './';
 import { defineMutation } from './_client';
 import { delivery } from './delivery';
@@ -18,6 +29,10 @@ import { vadRecorder } from './vad-recorder';
 // Track manual recording start time for duration calculation
 let manualRecordingStartTime: number | null = null;
 
+// Whether the current manual recording was started as a voice note, which is
+// saved without transcribing
+let voiceNoteInProgress = false;
+
 // Internal mutations for manual recording
 const startManualRecording = defineMutation({
 	mutationKey: ['commands', 'startManualRecording'] as const,
@@ -102,8 +117,12 @@ const stopManualRecording = defineMutation({
 			title: '⏸️ Stopping recording...',
 			description: 'Finalizing your audio capture...',
 		});
-		const { data: blob, error: stopRecordingError } =
-			await recorder.stopRecording.execute({ toastId });
+		const timings: StageTiming[] = [];
+		const { data: blob, error: stopRecordingError } = await timeStage(
+			timings,
+			'captureStop',
+			() => recorder.stopRecording.execute({ toastId }),
+		);
 		if (stopRecordingError) {
 			notify.error.execute({ id: toastId, ...stopRecordingError });
 			return Err(stopRecordingError);
@@ -129,11 +148,22 @@ const stopManualRecording = defineMutation({
 			duration,
 		});
 
+		if (voiceNoteInProgress) {
+			voiceNoteInProgress = false;
+			// Kept without transcribing, so there's nothing to resume
+			if (window.__TAURI_INTERNALS__) await finishPendingJob();
+			await saveVoiceNote({ blob, toastId });
+			if (window.__TAURI_INTERNALS__) await settleAppState({ type: 'completed' });
+			return Ok(undefined);
+		}
+
 		await processRecordingPipeline({
 			blob,
 			toastId,
 			completionTitle: '✨ Recording Complete!',
 			completionDescription: 'Recording saved and session closed successfully',
+			timings,
+			nameSavedFile: true,
 		});
 
 		return Ok(undefined);
@@ -294,10 +324,31 @@ export const commands = {
 		},
 	}),
 
+	// Record a voice note, saved to history (and the voice note folder, if
+	// set) without being transcribed
+	toggleVoiceNote: defineMutation({
+		mutationKey: ['commands', 'toggleVoiceNote'] as const,
+		resultMutationFn: async () => {
+			const { data: recorderState, error: getRecorderStateError } =
+				await recorder.getRecorderState.fetch();
+			if (getRecorderStateError) {
+				notify.error.execute(getRecorderStateError);
+				return Err(getRecorderStateError);
+			}
+			if (recorderState === 'RECORDING') {
+				return await stopManualRecording.execute(undefined);
+			}
+			const result = await startManualRecording.execute(undefined);
+			if (!result.error) voiceNoteInProgress = true;
+			return result;
+		},
+	}),
+
 	// Cancel manual recording
 	cancelManualRecording: defineMutation({
 		mutationKey: ['commands', 'cancelManualRecording'] as const,
 		resultMutationFn: async () => {
+			voiceNoteInProgress = false;
 			const toastId = nanoid();
 			notify.loading.execute({
 				id: toastId,
@@ -412,8 +463,174 @@ export const commands = {
 			});
 		},
 	}),
+
+	// Transcribe an audio file named on the clipboard (or passed by
+	// `whispering transcribe <file>`) and copy the result to the clipboard
+	transcribeAudioFile: defineMutation({
+		mutationKey: ['commands', 'transcribeAudioFile'] as const,
+		resultMutationFn: async ({ path }: { path?: string }) => {
+			const toastId = nanoid();
+			if (!window.__TAURI_INTERNALS__) {
+				notify.error.execute({
+					id: toastId,
+					title: '❌ Desktop app only',
+					description:
+						'Transcribing a file from the clipboard needs the desktop app.',
+				});
+				return Ok(undefined);
+			}
+
+			let filePath = path;
+			if (!filePath) {
+				const { data, error: pathError } = await getClipboardAudioPath();
+				if (pathError) {
+					notify.error.execute({
+						id: toastId,
+						title: '❌ No audio file on the clipboard',
+						description: pathError.message,
+						action: { type: 'more-details', error: pathError },
+					});
+					return Ok(undefined);
+				}
+				filePath = data;
+			}
+
+			const { data: file, error: readError } =
+				await services.fs.pathToFile(filePath);
+			if (readError) {
+				notify.error.execute({
+					id: toastId,
+					title: '❌ Failed to read audio file',
+					description: readError.message,
+					action: { type: 'more-details', error: readError },
+				});
+				return Ok(undefined);
+			}
+
+			rpc.analytics.logEvent.execute({
+				type: 'file_uploaded',
+				blob_size: file.size,
+			});
+
+			await processRecordingPipeline({
+				blob: file,
+				toastId,
+				completionTitle: '📋 Transcribing audio file',
+				completionDescription: file.name,
+				clipboardOnly: true,
+				audioPath: filePath,
+			});
+			return Ok(undefined);
+		},
+	}),
+
+	// Read the latest transcript aloud to check it without looking
+	readBackLastTranscript: defineMutation({
+		mutationKey: ['commands', 'readBackLastTranscript'] as const,
+		resultMutationFn: async () => {
+			if (!window.__TAURI_INTERNALS__) {
+				notify.error.execute({
+					title: '❌ Desktop app only',
+					description: 'Reading transcripts aloud needs the desktop app.',
+				});
+				return Ok(undefined);
+			}
+
+			const { data: latestRecording, error: getLatestError } =
+				await recordings.getLatestRecording.fetch();
+			if (getLatestError) {
+				notify.error.execute({
+					title: '❌ Failed to get the latest transcript',
+					description: getLatestError.message,
+					action: { type: 'more-details', error: getLatestError },
+				});
+				return Ok(undefined);
+			}
+
+			const text = latestRecording?.transcribedText.trim();
+			if (!text) {
+				notify.info.execute({
+					title: '🔈 Nothing to read back',
+					description: 'There is no transcript yet.',
+				});
+				return Ok(undefined);
+			}
+
+			try {
+				await speak(text);
+			} catch (error) {
+				notify.error.execute({
+					title: '❌ Failed to read back the transcript',
+					description: extractErrorMessage(error),
+				});
+			}
+			return Ok(undefined);
+		},
+	}),
 };
 
+/**
+ * Saves a voice note: the recording goes into history untranscribed, and is
+ * also written to the voice note folder when one is set. No transcription
+ * service is called.
+ */
+async function saveVoiceNote({ blob, toastId }: { blob: Blob; toastId: string }) {
+	const recordedAt = new Date();
+	const now = recordedAt.toISOString();
+	const { error: createRecordingError } =
+		await recordings.createRecording.execute({
+			id: nanoid(),
+			title: 'Voice note',
+			subtitle: '',
+			createdAt: now,
+			updatedAt: now,
+			timestamp: now,
+			transcribedText: '',
+			blob,
+			transcriptionStatus: 'UNPROCESSED',
+		});
+	if (createRecordingError) {
+		notify.error.execute({
+			id: toastId,
+			title: '❌ Your voice note could not be saved to the database.',
+			description: createRecordingError.message,
+			action: { type: 'more-details', error: createRecordingError },
+		});
+		return;
+	}
+
+	const folder = settings.value['voiceNotes.folder'];
+	if (!folder || !window.__TAURI_INTERNALS__) {
+		notify.success.execute({
+			id: toastId,
+			title: '🗒️ Voice note saved',
+			description: 'Saved to your recordings without transcribing.',
+		});
+		return;
+	}
+
+	const { data: path, error: saveFileError } = await saveVoiceNoteFile({
+		folder,
+		blob,
+		recordedAt,
+		timeZone: settings.value['system.timeZone'],
+	});
+	if (saveFileError) {
+		notify.warning.execute({
+			id: toastId,
+			title: '⚠️ Voice note saved to your recordings only',
+			description: saveFileError.message,
+			action: { type: 'more-details', error: saveFileError },
+		});
+		return;
+	}
+	notify.success.execute({
+		id: toastId,
+		title: '🗒️ Voice note saved',
+		description: path,
+	});
+}
+
 /**
  * Processes a recording through the full pipeline: save → transcribe → transform
  *
@@ -423,17 +640,35 @@ export const commands = {
  * 3. Shows completion toast
  * 4. Executes transcription flow
  * 5. Applies transformation if one is selected
+ *
+ * Stage timings are reported for the latency breakdown along the way;
+ * `timings` carries any measured before the recording was created.
  */
 async function processRecordingPipeline({
 	blob,
 	toastId,
 	completionTitle,
 	completionDescription,
+	timings = [],
+	clipboardOnly = false,
+	nameSavedFile = false,
+	audioPath,
 }: {
 	blob: Blob;
 	toastId: string;
 	completionTitle: string;
 	completionDescription: string;
+	timings?: StageTiming[];
+	/** Put results on the clipboard only, whatever the delivery settings */
+	clipboardOnly?: boolean;
+	/** Rename the file the desktop recorder saved after the transcript */
+	nameSavedFile?: boolean;
+	/**
+	 * The file being transcribed, taken off the queue of interrupted
+	 * transcriptions once done; the desktop recorder's file when
+	 * `nameSavedFile` is set
+	 */
+	audioPath?: string;
 }) {
 	const now = new Date().toISOString();
 	const newRecordingId = nanoid();
@@ -459,6 +694,11 @@ async function processRecordingPipeline({
 			description: createRecordingError.message,
 			action: { type: 'more-details', error: createRecordingError },
 		});
+		if (window.__TAURI_INTERNALS__)
+			await settleAppState({
+				type: 'failed',
+				message: createRecordingError.message,
+			});
 		return;
 	}
 
@@ -478,7 +718,20 @@ async function processRecordingPipeline({
 	const { data: transcribedText, error: transcribeError } =
 		await transcription.transcribeRecording.execute(createdRecording);
 
+	// Finished either way: a failure is reported below, so it's not one to
+	// resume after a crash. Before naming, which moves the saved file.
+	if ((audioPath || nameSavedFile) && window.__TAURI_INTERNALS__) {
+		const { error: finishError } = await finishPendingJob(audioPath);
+		if (finishError)
+			console.warn('Failed to finish the pending transcription:', finishError);
+	}
+
 	if (transcribeError) {
+		if (window.__TAURI_INTERNALS__)
+			await settleAppState({
+				type: 'failed',
+				message: transcribeError.message,
+			});
 		if (transcribeError.name === 'WhisperingError') {
 			notify.error.execute({ id: transcribeToastId, ...transcribeError });
 			return;
@@ -494,10 +747,42 @@ async function processRecordingPipeline({
 
 	sound.playSoundIfEnabled.execute('transcriptionComplete');
 
-	await delivery.deliverTranscriptionResult.execute({
-		text: transcribedText,
-		toastId: transcribeToastId,
-	});
+	if (window.__TAURI_INTERNALS__) {
+		const { error: publishError } = await publishTranscript(transcribedText);
+		if (publishError)
+			console.warn('Failed to publish the transcript:', publishError);
+	}
+
+	let savedPath = audioPath;
+	if (nameSavedFile && window.__TAURI_INTERNALS__) {
+		const { data: namedPath, error: nameFileError } =
+			await nameSavedRecording(transcribedText);
+		if (nameFileError)
+			console.warn('Failed to name the saved recording:', nameFileError);
+		savedPath = namedPath ?? savedPath;
+	}
+
+	// Transcribing saved it to history already; now its audio is known
+	if (savedPath && window.__TAURI_INTERNALS__) {
+		const { error: historyError } = await saveToHistory(
+			{ ...createdRecording, transcribedText },
+			{ audioPath: savedPath },
+		);
+		if (historyError)
+			console.warn('Failed to save the audio to history:', historyError);
+	}
+
+	await timeStage(timings, 'inject', () =>
+		delivery.deliverTranscriptionResult.execute({
+			text: transcribedText,
+			toastId: transcribeToastId,
+			entryId: createdRecording.id,
+			clipboardOnly,
+		}),
+	);
+	reportLatency(createdRecording.id, timings);
+	// Typing the text settles it already; the clipboard alone doesn't
+	if (window.__TAURI_INTERNALS__) await settleAppState({ type: 'completed' });
 
 	// Determine if we need to chain to transformation
 	const transformationId =
@@ -544,11 +829,16 @@ async function processRecordingPipeline({
 		description:
 			'Applying your selected transformation to the transcribed text...',
 	});
-	const { data: transformationRun, error: transformError } =
-		await transformer.transformRecording.execute({
-			recordingId: createdRecording.id,
-			transformation,
-		});
+	const transformTimings: StageTiming[] = [];
+	const { data: transformationRun, error: transformError } = await timeStage(
+		transformTimings,
+		'postProcess',
+		() =>
+			transformer.transformRecording.execute({
+				recordingId: createdRecording.id,
+				transformation,
+			}),
+	);
 	if (transformError) {
 		notify.error.execute({ id: transformToastId, ...transformError });
 		return;
@@ -566,8 +856,20 @@ async function processRecordingPipeline({
 
 	sound.playSoundIfEnabled.execute('transformationComplete');
 
-	await delivery.deliverTransformationResult.execute({
-		text: transformationRun.output,
-		toastId: transformToastId,
-	});
+	await timeStage(transformTimings, 'inject', () =>
+		delivery.deliverTransformationResult.execute({
+			text: transformationRun.output,
+			toastId: transformToastId,
+			entryId: createdRecording.id,
+			clipboardOnly,
+		}),
+	);
+	// Both results were delivered, so inject covers the two of them
+	const injectMs = [...timings, ...transformTimings]
+		.filter(({ stage }) => stage === 'inject')
+		.reduce((total, { ms }) => total + ms, 0);
+	reportLatency(createdRecording.id, [
+		...transformTimings.filter(({ stage }) => stage !== 'inject'),
+		{ stage: 'inject', ms: injectMs },
+	]);
 }
@@ -3,6 +3,13 @@ import { WHISPERING_RECORDINGS_PATHNAME } from '$lib/constants/app';
 import type { WhisperingError } from '$lib/result';
 import type { TextServiceError } from '$lib/services/text';
 import { settings } from '$lib/stores/settings.svelte';
+import { getBuildFeatures } from '$lib/utils/build-features';
+import {
+	appendToJournal,
+	commitJournal,
+	type JournalError,
+} from '$lib/utils/journal';
+import { deliverToPlugins } from '$lib/utils/plugins';
 import { defineMutation } from './_client';
 import { rpc } from './index';
 
@@ -19,9 +26,15 @@ export const delivery = {
 	 * The user's preferences are read from:
 	 * - `transcription.copyToClipboardOnSuccess` - Whether to auto-copy
 	 * - `transcription.writeToCursorOnSuccess` - Whether to auto-write to cursor
+	 * - `journal.pathPattern` - File to also append the text to, if any
+	 * - `journal.gitCommit` - Whether to commit that file to git afterwards
+	 *
+	 * Enabled destination plugins get the text too, on desktop builds with plugins.
 	 *
 	 * @param text - The transcribed text to deliver
 	 * @param toastId - Unique ID for toast notifications to prevent duplicates
+	 * @param entryId - The recording the text came from, for storing how it was injected
+	 * @param clipboardOnly - Copy to the clipboard and skip writing to the cursor, regardless of settings
 	 * @returns Result with no meaningful data (fire-and-forget operation)
 	 *
 	 * @example
@@ -38,9 +51,13 @@ export const delivery = {
 		resultMutationFn: async ({
 			text,
 			toastId,
+			entryId,
+			clipboardOnly = false,
 		}: {
 			text: string;
 			toastId: string;
+			entryId?: string;
+			clipboardOnly?: boolean;
 		}) => {
 			// Track what operations succeeded
 			let copied = false;
@@ -105,6 +122,15 @@ export const delivery = {
 				}
 			};
 
+			// Warns that appending to the journal failed
+			const warnJournalFailed = (error: JournalError) => {
+				rpc.notify.warning.execute({
+					title: "Couldn't add to journal",
+					description: error.message,
+					action: { type: 'more-details', error },
+				});
+			};
+
 			// Show appropriate success notification based on what succeeded
 			const showSuccessNotification = () => {
 				if (copied && written) {
@@ -153,7 +179,10 @@ export const delivery = {
 			// Main delivery flow - operations are independent
 
 			// Check if user wants to copy to clipboard
-			if (settings.value['transcription.copyToClipboardOnSuccess']) {
+			if (
+				clipboardOnly ||
+				settings.value['transcription.copyToClipboardOnSuccess']
+			) {
 				const { error: copyError } = await rpc.text.copyToClipboard.execute({
 					text,
 				});
@@ -165,9 +194,13 @@ export const delivery = {
 			}
 
 			// Check if user wants to write to cursor (independent of copy)
-			if (settings.value['transcription.writeToCursorOnSuccess']) {
+			if (
+				!clipboardOnly &&
+				settings.value['transcription.writeToCursorOnSuccess']
+			) {
 				const { error: writeError } = await rpc.text.writeToCursor.execute({
 					text,
+					entryId,
 				});
 				if (!writeError) {
 					written = true;
@@ -176,6 +209,40 @@ export const delivery = {
 				}
 			}
 
+			// Append to the journal file, if one is set (independent of the above)
+			const journalPattern = settings.value['journal.pathPattern'];
+			if (window.__TAURI_INTERNALS__ && journalPattern) {
+				const { data: journalPath, error: journalError } =
+					await appendToJournal({ pathPattern: journalPattern, text });
+				if (journalError) {
+					warnJournalFailed(journalError);
+				} else if (settings.value['journal.gitCommit']) {
+					const { error: commitError } = await commitJournal({
+						path: journalPath,
+						text,
+						messageTemplate: settings.value['journal.commitMessage'],
+					});
+					if (commitError) warnJournalFailed(commitError);
+				}
+			}
+
+			// Hand the text to destination plugins (independent of the above)
+			if (window.__TAURI_INTERNALS__ && (await getBuildFeatures()).plugins) {
+				const { data: deliveries, error: pluginError } =
+					await deliverToPlugins({ text, entryId });
+				const failures = pluginError
+					? [pluginError.message]
+					: deliveries
+							.filter((delivery) => delivery.error)
+							.map((delivery) => `${delivery.id}: ${delivery.error}`);
+				if (failures.length > 0) {
+					rpc.notify.warning.execute({
+						title: "Couldn't send to plugins",
+						description: failures.join('\n'),
+					});
+				}
+			}
+
 			// Show appropriate notification
 			showSuccessNotification();
 
@@ -198,6 +265,8 @@ export const delivery = {
 	 *
 	 * @param text - The transformed text to deliver
 	 * @param toastId - Unique ID for toast notifications to prevent duplicates
+	 * @param entryId - The recording the text came from, for storing how it was injected
+	 * @param clipboardOnly - Copy to the clipboard and skip writing to the cursor, regardless of settings
 	 * @returns Result with no meaningful data (fire-and-forget operation)
 	 *
 	 * @example
@@ -214,9 +283,13 @@ export const delivery = {
 		resultMutationFn: async ({
 			text,
 			toastId,
+			entryId,
+			clipboardOnly = false,
 		}: {
 			text: string;
 			toastId: string;
+			entryId?: string;
+			clipboardOnly?: boolean;
 		}) => {
 			// Track what operations succeeded
 			let copied = false;
@@ -329,7 +402,10 @@ export const delivery = {
 			// Main delivery flow - operations are independent
 
 			// Check if user wants to copy to clipboard
-			if (settings.value['transformation.copyToClipboardOnSuccess']) {
+			if (
+				clipboardOnly ||
+				settings.value['transformation.copyToClipboardOnSuccess']
+			) {
 				const { error: copyError } = await rpc.text.copyToClipboard.execute({
 					text,
 				});
@@ -341,9 +417,13 @@ export const delivery = {
 			}
 
 			// Check if user wants to write to cursor (independent of copy)
-			if (settings.value['transformation.writeToCursorOnSuccess']) {
+			if (
+				!clipboardOnly &&
+				settings.value['transformation.writeToCursorOnSuccess']
+			) {
 				const { error: writeError } = await rpc.text.writeToCursor.execute({
 					text,
+					entryId,
 				});
 				if (!writeError) {
 					written = true;
@@ -3,6 +3,10 @@ import { WhisperingErr, type WhisperingError } from '$lib/result';
 import * as services from '$lib/services';
 import type { Recording } from '$lib/services/db';
 import type { DownloadServiceError } from '$lib/services/download';
+import {
+	buildSpeakerExport,
+	type SpeakerExportFormat,
+} from '$lib/services/transcription/speakers';
 import { defineMutation } from './_client';
 
 export const download = {
@@ -24,4 +28,26 @@ export const download = {
 			});
 		},
 	}),
+
+	exportSpeakers: defineMutation({
+		mutationKey: ['download', 'exportSpeakers'] as const,
+		resultMutationFn: async ({
+			recording,
+			format,
+		}: {
+			recording: Recording;
+			format: SpeakerExportFormat;
+		}): Promise<Result<void, WhisperingError | DownloadServiceError>> => {
+			const files = buildSpeakerExport(recording, format);
+			if (!files.length) {
+				return WhisperingErr({
+					title: '⚠️ No speakers found',
+					description:
+						'This recording was transcribed without speaker labels. Use a provider with diarization to export per speaker.',
+				});
+			}
+
+			return await services.download.downloadTextFiles({ files });
+		},
+	}),
 };
@@ -2,12 +2,16 @@ import type { Accessor } from '@tanstack/svelte-query';
 import { Err, Ok } from 'wellcrafted/result';
 import * as services from '$lib/services';
 import type { Recording } from '$lib/services/db';
+import { getSpeakerStats } from '$lib/services/transcription/speakers';
+import { deleteFromHistory, saveToHistory } from '$lib/utils/history';
 import { defineMutation, defineQuery, queryClient } from './_client';
 
 const recordingKeys = {
 	all: ['recordings'] as const,
 	latest: ['recordings', 'latest'] as const,
 	byId: (id: Accessor<string>) => [...recordingKeys.all, id()] as const,
+	speakerStats: (id: Accessor<string>) =>
+		[...recordingKeys.all, id(), 'speakerStats'] as const,
 };
 
 export const recordings = {
@@ -43,6 +47,19 @@ export const recordings = {
 				queryClient.getQueryState(recordingKeys.all)?.dataUpdatedAt,
 		}),
 
+	/** Talk time per speaker, for recordings transcribed with diarization */
+	getSpeakerStats: (id: Accessor<string>) =>
+		defineQuery({
+			queryKey: recordingKeys.speakerStats(id),
+			resultQueryFn: async () => {
+				const { data: recording, error } = await services.db.getRecordingById(
+					id(),
+				);
+				if (error) return Err(error);
+				return Ok(getSpeakerStats(recording?.insights?.utterances ?? []));
+			},
+		}),
+
 	createRecording: defineMutation({
 		mutationKey: ['recordings', 'createRecording'] as const,
 		resultMutationFn: async (recording: Recording) => {
@@ -74,6 +91,19 @@ export const recordings = {
 			const { data, error } = await services.db.updateRecording(recording);
 			if (error) return Err(error);
 
+			// Transcripts, and edits to them, are kept in desktop history too
+			if (
+				window.__TAURI_INTERNALS__ &&
+				recording.transcriptionStatus === 'DONE'
+			) {
+				const { error: historyError } = await saveToHistory(recording);
+				if (historyError)
+					console.warn(
+						'Failed to save the transcript to history:',
+						historyError,
+					);
+			}
+
 			queryClient.setQueryData<Recording[]>(recordingKeys.all, (oldData) => {
 				if (!oldData) return [recording];
 				return oldData.map((item) =>
@@ -97,6 +127,7 @@ export const recordings = {
 		resultMutationFn: async (recording: Recording) => {
 			const { error } = await services.db.deleteRecording(recording);
 			if (error) return Err(error);
+			if (window.__TAURI_INTERNALS__) await deleteFromHistory([recording.id]);
 
 			queryClient.setQueryData<Recording[]>(recordingKeys.all, (oldData) => {
 				if (!oldData) return [];
@@ -118,6 +149,8 @@ export const recordings = {
 		resultMutationFn: async (recordings: Recording[]) => {
 			const { error } = await services.db.deleteRecordings(recordings);
 			if (error) return Err(error);
+			if (window.__TAURI_INTERNALS__)
+				await deleteFromHistory(recordings.map((r) => r.id));
 
 			queryClient.setQueryData<Recording[]>(recordingKeys.all, (oldData) => {
 				if (!oldData) return [];
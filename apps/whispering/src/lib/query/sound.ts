@@ -3,8 +3,22 @@ import type { WhisperingSoundNames } from '$lib/constants/sounds';
 import * as services from '$lib/services';
 import type { PlaySoundServiceError } from '$lib/services/sound';
 import { settings } from '$lib/stores/settings.svelte';
+import type { SoundSlot } from '$lib/utils/custom-assets';
 import { defineMutation } from './_client';
 
+/** Built-in sounds the user can replace with their own files */
+const CUSTOM_SOUND_SLOTS: Partial<Record<WhisperingSoundNames, SoundSlot>> = {
+	'manual-start': 'start',
+	'vad-start': 'start',
+	'manual-stop': 'stop',
+	'vad-stop': 'stop',
+};
+
+const CUSTOM_SOUND_SETTINGS = {
+	start: 'sound.customStart',
+	stop: 'sound.customStop',
+} as const satisfies Record<SoundSlot, string>;
+
 const soundKeys = {
 	all: ['sound'] as const,
 	playSoundIfEnabled: ['sound', 'playSoundIfEnabled'] as const,
@@ -16,9 +30,23 @@ export const sound = {
 		resultMutationFn: async (
 			soundName: WhisperingSoundNames,
 		): Promise<Result<void, PlaySoundServiceError>> => {
-			if (!settings.value[`sound.playOn.${soundName}`]) {
+			if (
+				!settings.value['sound.enabled'] ||
+				!settings.value[`sound.playOn.${soundName}`]
+			) {
 				return Ok(undefined);
 			}
+			const slot = CUSTOM_SOUND_SLOTS[soundName];
+			const customPath = slot && settings.value[CUSTOM_SOUND_SETTINGS[slot]];
+			if (slot && customPath) {
+				const { error } = await services.sound.playCustomSound(
+					slot,
+					customPath,
+				);
+				if (!error) return Ok(undefined);
+				// Missing or invalid files fall back to the built-in sound
+				console.warn('Failed to play custom sound:', error);
+			}
 			return await services.sound.playSound(soundName);
 		},
 	}),
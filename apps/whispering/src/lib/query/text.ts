@@ -9,13 +9,19 @@ export const text = {
 	}),
 	writeToCursor: defineMutation({
 		mutationKey: ['text', 'writeToCursor'],
-		resultMutationFn: async ({ text }: { text: string }) => {
+		resultMutationFn: async ({
+			text,
+			entryId,
+		}: {
+			text: string;
+			entryId?: string;
+		}) => {
 			// writeToCursor handles everything internally:
 			// 1. Saves current clipboard
 			// 2. Writes text to clipboard
 			// 3. Simulates paste
 			// 4. Restores original clipboard
-			return await services.text.writeToCursor(text);
+			return await services.text.writeToCursor(text, { entryId });
 		},
 	}),
 };
@@ -5,8 +5,10 @@ import {
 	WhisperingWarningErr,
 } from '$lib/result';
 import * as services from '$lib/services';
-import type { Recording } from '$lib/services/db';
+import type { Recording, TranscriptInsights } from '$lib/services/db';
 import { settings } from '$lib/stores/settings.svelte';
+import { reportLatency, type StageTiming, timeStage } from '$lib/utils/latency';
+import { applyTransforms } from '$lib/utils/transforms';
 import { rpc } from './';
 import { defineMutation, queryClient } from './_client';
 import { notify } from './notify';
@@ -16,6 +18,12 @@ const transcriptionKeys = {
 	isTranscribing: ['transcription', 'isTranscribing'] as const,
 } as const;
 
+/**
+ * Aborted when the watchdog gives up on a stuck transcription (see
+ * src-tauri/src/watchdog.rs), then replaced for the transcriptions after it
+ */
+let stuckTranscriptions = new AbortController();
+
 export const transcription = {
 	isCurrentlyTranscribing() {
 		return (
@@ -24,6 +32,14 @@ export const transcription = {
 			}) > 0
 		);
 	},
+	/**
+	 * Give up on the transcriptions in flight: they fail as cancelled right
+	 * away, and whatever their provider returns later is ignored
+	 */
+	cancelInFlight() {
+		stuckTranscriptions.abort();
+		stuckTranscriptions = new AbortController();
+	},
 	transcribeRecording: defineMutation({
 		mutationKey: transcriptionKeys.isTranscribing,
 		resultMutationFn: async (
@@ -51,7 +67,7 @@ export const transcription = {
 					},
 				});
 			}
-			const { data: transcribedText, error: transcribeError } =
+			const { data: transcript, error: transcribeError } =
 				await transcribeBlob(recording.blob);
 			if (transcribeError) {
 				const { error: setRecordingTranscribingError } =
@@ -72,11 +88,16 @@ export const transcription = {
 				}
 				return Err(transcribeError);
 			}
+			reportLatency(recording.id, transcript.timings);
+			const transcribedText = await applyTransforms(transcript.text, {
+				language: settings.value['transcription.outputLanguage'],
+			});
 
 			const { error: setRecordingTranscribedTextError } =
 				await recordings.updateRecording.execute({
 					...recording,
 					transcribedText,
+					insights: transcript.insights,
 					transcriptionStatus: 'DONE',
 				});
 			if (setRecordingTranscribedTextError) {
@@ -116,9 +137,15 @@ export const transcription = {
 
 async function transcribeBlob(
 	blob: Blob,
-): Promise<Result<string, WhisperingError>> {
+): Promise<
+	Result<
+		{ text: string; insights?: TranscriptInsights; timings: StageTiming[] },
+		WhisperingError
+	>
+> {
 	const selectedService =
 		settings.value['transcription.selectedTranscriptionService'];
+	const { signal } = stuckTranscriptions;
 
 	// Log transcription request
 	const startTime = Date.now();
@@ -127,14 +154,21 @@ async function transcribeBlob(
 		provider: selectedService,
 	});
 
+	// Stage timings for the latency breakdown
+	const timings: StageTiming[] = [];
+
 	// Compress audio if enabled, else pass through original blob
 	let audioToTranscribe = blob;
 	if (settings.value['transcription.compressionEnabled']) {
-		const { data: compressedBlob, error: compressionError } =
-			await services.ffmpeg.compressAudioBlob(
-				blob,
-				settings.value['transcription.compressionOptions'],
-			);
+		const { data: compressedBlob, error: compressionError } = await timeStage(
+			timings,
+			'encode',
+			() =>
+				services.ffmpeg.compressAudioBlob(
+					blob,
+					settings.value['transcription.compressionOptions'],
+				),
+		);
 
 		if (compressionError) {
 			// Notify user of compression failure but continue with original blob
@@ -167,8 +201,13 @@ async function transcribeBlob(
 		}
 	}
 
-	const transcriptionResult: Result<string, WhisperingError> =
-		await (async () => {
+	// Set by providers that return more than text
+	let insights: TranscriptInsights | undefined;
+
+	// Cloud providers upload and transcribe in one request, so both count
+	// as the provider stage
+	const transcribing: Promise<Result<string, WhisperingError>> =
+		timeStage(timings, 'provider', async () => {
 			switch (selectedService) {
 				case 'OpenAI':
 					return await services.transcriptions.openai.transcribe(
@@ -201,6 +240,7 @@ async function transcribeBlob(
 							temperature: settings.value['transcription.temperature'],
 							modelId: settings.value['transcription.speaches.modelId'],
 							baseUrl: settings.value['transcription.speaches.baseUrl'],
+							apiKey: settings.value['transcription.speaches.apiKey'],
 						},
 					);
 				case 'ElevenLabs':
@@ -236,6 +276,74 @@ async function transcribeBlob(
 							modelName: settings.value['transcription.mistral.model'],
 						},
 					);
+				case 'Azure':
+					return await services.transcriptions.azure.transcribe(
+						audioToTranscribe,
+						{
+							outputLanguage: settings.value['transcription.outputLanguage'],
+							prompt: settings.value['transcription.prompt'],
+							temperature: settings.value['transcription.temperature'],
+							apiKey: settings.value['apiKeys.azureSpeech'],
+							modelName: settings.value['transcription.azure.model'],
+							region: settings.value['transcription.azure.region'],
+							endpoint: settings.value['transcription.azure.endpoint'],
+						},
+					);
+				case 'Google':
+					return await services.transcriptions.google.transcribe(
+						audioToTranscribe,
+						{
+							outputLanguage: settings.value['transcription.outputLanguage'],
+							prompt: settings.value['transcription.prompt'],
+							temperature: settings.value['transcription.temperature'],
+							apiKey: settings.value['apiKeys.googleSpeech'],
+							modelName: settings.value['transcription.google.model'],
+							endpoint: settings.value['transcription.google.endpoint'],
+						},
+					);
+				case 'AssemblyAI': {
+					const { data, error } =
+						await services.transcriptions.assemblyai.transcribe(
+							audioToTranscribe,
+							{
+								outputLanguage: settings.value['transcription.outputLanguage'],
+								prompt: settings.value['transcription.prompt'],
+								temperature: settings.value['transcription.temperature'],
+								apiKey: settings.value['apiKeys.assemblyai'],
+								modelName: settings.value['transcription.assemblyai.model'],
+								autoChapters:
+									settings.value['transcription.assemblyai.autoChapters'],
+								entityDetection:
+									settings.value['transcription.assemblyai.entityDetection'],
+								speakerLabels:
+									settings.value['transcription.assemblyai.speakerLabels'],
+							},
+						);
+					if (error) return Err(error);
+					insights = data.insights;
+					return Ok(data.text);
+				}
+				case 'Speechmatics': {
+					const { data, error } =
+						await services.transcriptions.speechmatics.transcribe(
+							audioToTranscribe,
+							{
+								outputLanguage: settings.value['transcription.outputLanguage'],
+								prompt: settings.value['transcription.prompt'],
+								temperature: settings.value['transcription.temperature'],
+								apiKey: settings.value['apiKeys.speechmatics'],
+								modelName: settings.value['transcription.speechmatics.model'],
+								autoChapters:
+									settings.value['transcription.speechmatics.autoChapters'],
+								entities: settings.value['transcription.speechmatics.entities'],
+								diarization:
+									settings.value['transcription.speechmatics.diarization'],
+							},
+						);
+					if (error) return Err(error);
+					insights = data.insights;
+					return Ok(data.text);
+				}
 				case 'whispercpp': {
 					// Pure Rust audio conversion now handles most formats without FFmpeg
 					// Only compressed formats (MP3, M4A) require FFmpeg, which will be
@@ -263,7 +371,8 @@ async function transcribeBlob(
 						description: 'Please select a transcription service in settings.',
 					});
 			}
-		})();
+		});
+	const transcriptionResult = await unlessCancelled(signal, transcribing);
 
 	// Log transcription result
 	const duration = Date.now() - startTime;
@@ -282,5 +391,28 @@ async function transcribeBlob(
 		});
 	}
 
-	return transcriptionResult;
+	if (transcriptionResult.error) return Err(transcriptionResult.error);
+	return Ok({ text: transcriptionResult.data, insights, timings });
+}
+
+/** `transcribing`'s result, or a cancellation once `signal` is aborted */
+function unlessCancelled<T>(
+	signal: AbortSignal,
+	transcribing: Promise<Result<T, WhisperingError>>,
+): Promise<Result<T, WhisperingError>> {
+	const cancelled = () =>
+		WhisperingErr({
+			title: '⏱️ Transcription cancelled',
+			description:
+				'The transcription took too long and was stopped. Check your connection or try another provider.',
+		});
+	if (signal.aborted) return Promise.resolve(cancelled());
+	return new Promise((resolve) => {
+		const onAbort = () => resolve(cancelled());
+		signal.addEventListener('abort', onAbort, { once: true });
+		transcribing.then((result) => {
+			signal.removeEventListener('abort', onAbort);
+			resolve(result);
+		});
+	});
 }
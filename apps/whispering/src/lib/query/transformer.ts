@@ -19,6 +19,7 @@ import {
 	interpolateTemplate,
 	type TemplateString,
 } from '$lib/utils/template';
+import { formatTimestamp, timestampOptions } from '$lib/utils/timestamps';
 import { defineMutation, queryClient } from './_client';
 import { transformationRunKeys } from './transformation-runs';
 import { transformationsKeys } from './transformations';
@@ -174,13 +175,18 @@ async function handleStep({
 
 		case 'prompt_transform': {
 			const provider = step['prompt_transform.inference.provider'];
+			// The current time, written as the settings say
+			const now = formatTimestamp(
+				new Date(),
+				timestampOptions(settings.value),
+			);
 			const systemPrompt = interpolateTemplate(
 				asTemplateString(step['prompt_transform.systemPromptTemplate']),
-				{ input },
+				{ input, now },
 			);
 			const userPrompt = interpolateTemplate(
 				asTemplateString(step['prompt_transform.userPromptTemplate']),
-				{ input },
+				{ input, now },
 			);
 
 			switch (provider) {
@@ -12,6 +12,7 @@ export type {
 	TransformationRunFailed,
 	TransformationStep,
 	TransformationStepRun,
+	TranscriptInsights,
 } from './models';
 export {
 	generateDefaultTransformation,
@@ -6,6 +6,7 @@ export type {
 	RecordingsDbSchemaV3,
 	RecordingsDbSchemaV4,
 	RecordingsDbSchemaV5,
+	TranscriptInsights,
 } from './recordings';
 export type {
 	TransformationRun,
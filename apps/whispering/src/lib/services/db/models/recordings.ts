@@ -15,6 +15,37 @@ export type Recording = {
 	 * 4. If the transcription fails, it is marked as 'FAILED'
 	 */
 	transcriptionStatus: 'UNPROCESSED' | 'TRANSCRIBING' | 'DONE' | 'FAILED';
+	/**
+	 * Extra analysis returned by providers that support it (e.g. AssemblyAI,
+	 * Speechmatics). Undefined for providers that only return text.
+	 */
+	insights?: TranscriptInsights;
+};
+
+/**
+ * Optional analysis of a transcript. Times are in seconds from the start of
+ * the recording; each field is only present if the provider returned it.
+ */
+export type TranscriptInsights = {
+	chapters?: {
+		start: number;
+		end: number;
+		headline: string;
+		summary: string;
+	}[];
+	entities?: {
+		type: string;
+		text: string;
+		start?: number;
+		end?: number;
+	}[];
+	/** Speaker turns, from diarization */
+	utterances?: {
+		speaker: string;
+		start: number;
+		end: number;
+		text: string;
+	}[];
 };
 
 export type RecordingsDbSchemaV5 = {
@@ -1,5 +1,6 @@
-import { save } from '@tauri-apps/plugin-dialog';
-import { writeFile } from '@tauri-apps/plugin-fs';
+import { join } from '@tauri-apps/api/path';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import { writeFile, writeTextFile } from '@tauri-apps/plugin-fs';
 import { Err, Ok, tryAsync } from 'wellcrafted/result';
 import { getExtensionFromAudioBlob } from '$lib/services/_utils';
 import type { DownloadService } from '.';
@@ -48,5 +49,41 @@ export function createDownloadServiceDesktop(): DownloadService {
 			if (writeError) return Err(writeError);
 			return Ok(undefined);
 		},
+		downloadTextFiles: async ({ files }) => {
+			const { data: folder, error: openError } = await tryAsync({
+				try: () => open({ directory: true, title: 'Export to folder' }),
+				catch: (error) =>
+					DownloadServiceErr({
+						message:
+							'There was an error choosing a folder to export to. Please try again.',
+						context: { files: files.map((file) => file.name) },
+						cause: error,
+					}),
+			});
+			if (openError) return Err(openError);
+			if (folder === null) {
+				return DownloadServiceErr({
+					message: 'Please choose a folder to export to.',
+					context: { files: files.map((file) => file.name) },
+					cause: undefined,
+				});
+			}
+			const { error: writeError } = await tryAsync({
+				try: async () => {
+					for (const file of files) {
+						await writeTextFile(await join(folder, file.name), file.text);
+					}
+				},
+				catch: (error) =>
+					DownloadServiceErr({
+						message:
+							'There was an error writing the exported files using the Tauri Filesystem API. Please try again.',
+						context: { files: files.map((file) => file.name), folder },
+						cause: error,
+					}),
+			});
+			if (writeError) return Err(writeError);
+			return Ok(undefined);
+		},
 	};
 }
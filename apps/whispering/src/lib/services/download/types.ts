@@ -11,4 +11,8 @@ export type DownloadService = {
 		name: string;
 		blob: Blob;
 	}) => Promise<Result<void, DownloadServiceError>>;
+	/** Save several text files together, e.g. one per speaker */
+	downloadTextFiles: (args: {
+		files: { name: string; text: string }[];
+	}) => Promise<Result<void, DownloadServiceError>>;
 };
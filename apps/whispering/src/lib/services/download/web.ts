@@ -25,5 +25,29 @@ export function createDownloadServiceWeb(): DownloadService {
 						cause: error,
 					}),
 			}),
+		downloadTextFiles: ({ files }) =>
+			tryAsync({
+				try: async () => {
+					for (const { name, text } of files) {
+						const url = URL.createObjectURL(
+							new File([text], name, { type: 'text/plain' }),
+						);
+						const a = document.createElement('a');
+						a.href = url;
+						a.download = name;
+						document.body.appendChild(a);
+						a.click();
+						document.body.removeChild(a);
+						URL.revokeObjectURL(url);
+					}
+				},
+				catch: (error) =>
+					DownloadServiceErr({
+						message:
+							'There was an error saving the exported files in your browser. Please try again.',
+						context: { files: files.map((file) => file.name) },
+						cause: error,
+					}),
+			}),
 	};
 }
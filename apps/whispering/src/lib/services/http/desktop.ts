@@ -1,50 +1,70 @@
+import { invoke } from '@tauri-apps/api/core';
 import { fetch } from '@tauri-apps/plugin-http';
 import { extractErrorMessage } from 'wellcrafted/error';
 import { Err, tryAsync } from 'wellcrafted/result';
+import type { z } from 'zod';
 import type { HttpService } from '.';
 import { ConnectionErr, ParseErr, ResponseErr } from './types';
 
 export function createHttpServiceDesktop(): HttpService {
-	return {
-		async post({ body, url, schema, headers }) {
-			const { data: response, error: responseError } = await tryAsync({
-				try: () =>
-					fetch(url, {
-						method: 'POST',
-						body,
-						headers: headers,
-					}),
-				catch: (error) =>
-					ConnectionErr({
-						message: 'Failed to establish connection',
-						context: { url, body, headers },
-						cause: error,
-					}),
+	async function request<TSchema extends z.ZodTypeAny>({
+		method,
+		body,
+		url,
+		schema,
+		headers,
+	}: {
+		method: 'GET' | 'POST';
+		body?: BodyInit | FormData;
+		url: string;
+		schema: TSchema;
+		headers?: Record<string, string>;
+	}) {
+		const { data: response, error: responseError } = await tryAsync({
+			try: async () => {
+				// Refused by the Rust side while privacy mode is on
+				await invoke('check_network_request', { url });
+				return fetch(url, {
+					method,
+					body,
+					headers: headers,
+				});
+			},
+			catch: (error) =>
+				ConnectionErr({
+					message: 'Failed to establish connection',
+					context: { url, body, headers },
+					cause: error,
+				}),
+		});
+		if (responseError) return Err(responseError);
+
+		if (!response.ok) {
+			return ResponseErr({
+				status: response.status,
+				message: extractErrorMessage(await response.json()),
+				context: { url, body, headers },
+				cause: responseError,
 			});
-			if (responseError) return Err(responseError);
+		}
 
-			if (!response.ok) {
-				return ResponseErr({
-					status: response.status,
-					message: extractErrorMessage(await response.json()),
+		const parseResult = await tryAsync({
+			try: async () => {
+				const json = await response.json();
+				return schema.parse(json) as z.infer<TSchema>;
+			},
+			catch: (error) =>
+				ParseErr({
+					message: 'Failed to parse response',
 					context: { url, body, headers },
-					cause: responseError,
-				});
-			}
+					cause: error,
+				}),
+		});
+		return parseResult;
+	}
 
-			const parseResult = await tryAsync({
-				try: async () => {
-					const json = await response.json();
-					return schema.parse(json);
-				},
-				catch: (error) =>
-					ParseErr({
-						message: 'Failed to parse response',
-						context: { url, body, headers },
-						cause: error,
-					}),
-			});
-			return parseResult;
-		},
+	return {
+		post: (config) => request({ method: 'POST', ...config }),
+		get: (config) => request({ method: 'GET', ...config }),
 	};
 }
@@ -80,4 +80,14 @@ export type HttpService = {
 		schema: TSchema;
 		headers?: Record<string, string>;
 	}) => Promise<Result<z.infer<TSchema>, HttpServiceError>>;
+
+	/**
+	 * Makes a GET request with the same parsing and error handling as `post`.
+	 * Used to poll asynchronous transcription jobs.
+	 */
+	get: <TSchema extends z.ZodTypeAny>(config: {
+		url: string;
+		schema: TSchema;
+		headers?: Record<string, string>;
+	}) => Promise<Result<z.infer<TSchema>, HttpServiceError>>;
 };
@@ -5,6 +5,7 @@ import type {
 	CancelRecordingResult,
 	WhisperingRecordingState,
 } from '$lib/constants/audio';
+import { asAppError, describeAppError } from '$lib/utils/app-error';
 import type { Device, DeviceAcquisitionOutcome } from '../types';
 import { asDeviceIdentifier } from '../types';
 import type {
@@ -22,6 +23,8 @@ type AudioRecording = {
 	channels: number;
 	durationSeconds: number;
 	filePath?: string;
+	/** Audio missing from the file because the disk fell behind */
+	droppedSeconds: number;
 };
 
 /**
@@ -186,11 +189,14 @@ export function createCpalRecorderService(): RecorderService {
 			);
 			if (initRecordingSessionError)
 				return RecorderServiceErr({
-					message:
+					message: describeAppError(
+						initRecordingSessionError.error,
 						'We encountered an issue while setting up your recording session. This could be because your microphone is being used by another app, your microphone permissions are denied, or the selected recording device is disconnected',
+					),
 					context: {
 						selectedDeviceId,
 						deviceIdentifier,
+						code: asAppError(initRecordingSessionError.error)?.code,
 					},
 					cause: initRecordingSessionError,
 				});
@@ -204,9 +210,15 @@ export function createCpalRecorderService(): RecorderService {
 				await invoke<void>('start_recording');
 			if (startRecordingError)
 				return RecorderServiceErr({
-					message:
+					message: describeAppError(
+						startRecordingError.error,
 						'Unable to start recording. Please check your microphone and try again.',
-					context: { deviceIdentifier, deviceOutcome },
+					),
+					context: {
+						deviceIdentifier,
+						deviceOutcome,
+						code: asAppError(startRecordingError.error)?.code,
+					},
 					cause: startRecordingError,
 				});
 
@@ -228,8 +240,14 @@ export function createCpalRecorderService(): RecorderService {
 				await invoke<AudioRecording>('stop_recording');
 			if (stopRecordingError) {
 				return RecorderServiceErr({
-					message: 'Unable to save your recording. Please try again.',
-					context: { operation: 'stopRecording' },
+					message: describeAppError(
+						stopRecordingError.error,
+						'Unable to save your recording. Please try again.',
+					),
+					context: {
+						operation: 'stopRecording',
+						code: asAppError(stopRecordingError.error)?.code,
+					},
 					cause: stopRecordingError,
 				});
 			}
@@ -325,6 +343,8 @@ export function createCpalRecorderService(): RecorderService {
 			// If there's a file path, delete the file using Tauri FS plugin
 			if (audioRecording?.filePath) {
 				const { filePath } = audioRecording;
+				// Stopping queued it for transcription; it's not to be resumed
+				await invoke<boolean>('finish_pending_job', { path: filePath });
 				const { error: removeError } = await tryAsync({
 					try: () => remove(filePath),
 					catch: (error) =>
@@ -1,9 +1,13 @@
 import { tryAsync } from 'wellcrafted/result';
+import { loadCustomSound } from '$lib/utils/custom-assets';
 import type { PlaySoundService } from '.';
 import { audioElements } from './assets';
 import { PlaySoundServiceErr } from './types';
 
 export function createPlaySoundServiceDesktop(): PlaySoundService {
+	/** Loaded custom sounds, by the path they were loaded from */
+	const customAudioElements = new Map<string, HTMLAudioElement>();
+
 	return {
 		playSound: async (soundName) =>
 			tryAsync({
@@ -17,5 +21,24 @@ export function createPlaySoundServiceDesktop(): PlaySoundService {
 						cause: error,
 					}),
 			}),
+		playCustomSound: async (slot, path) =>
+			tryAsync({
+				try: async () => {
+					let audio = customAudioElements.get(path);
+					if (!audio) {
+						const { data: blob, error } = await loadCustomSound(slot);
+						if (error) throw error;
+						audio = new Audio(URL.createObjectURL(blob));
+						customAudioElements.set(path, audio);
+					}
+					await audio.play();
+				},
+				catch: (error) =>
+					PlaySoundServiceErr({
+						message: 'Failed to play custom sound',
+						context: { slot, path },
+						cause: error,
+					}),
+			}),
 	};
 }
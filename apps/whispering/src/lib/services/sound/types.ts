@@ -1,6 +1,7 @@
 import { createTaggedError } from 'wellcrafted/error';
 import type { Result } from 'wellcrafted/result';
 import type { WhisperingSoundNames } from '$lib/constants/sounds';
+import type { SoundSlot } from '$lib/utils/custom-assets';
 
 export const { PlaySoundServiceError, PlaySoundServiceErr } = createTaggedError(
 	'PlaySoundServiceError',
@@ -11,4 +12,13 @@ export type PlaySoundService = {
 	playSound: (
 		soundName: WhisperingSoundNames,
 	) => Promise<Result<void, PlaySoundServiceError>>;
+	/**
+	 * Play the user's own file for `slot`, set in the settings as `path`.
+	 * Fails if the file doesn't pass the checks, so the caller can fall back
+	 * to the built-in sound.
+	 */
+	playCustomSound: (
+		slot: SoundSlot,
+		path: string,
+	) => Promise<Result<void, PlaySoundServiceError>>;
 };
@@ -2,6 +2,7 @@ import { Ok } from 'wellcrafted/result';
 // import { extension } from '@repo/extension';
 import type { PlaySoundService } from '.';
 import { audioElements } from './assets';
+import { PlaySoundServiceErr } from './types';
 
 export function createPlaySoundServiceWeb(): PlaySoundService {
 	return {
@@ -24,5 +25,12 @@ export function createPlaySoundServiceWeb(): PlaySoundService {
 			// }
 			return Ok(undefined);
 		},
+		// Custom sounds are read from disk, which only the desktop app can do
+		playCustomSound: async (slot, path) =>
+			PlaySoundServiceErr({
+				message: 'Custom sounds are only available in the desktop app',
+				context: { slot, path },
+				cause: undefined,
+			}),
 	};
 }
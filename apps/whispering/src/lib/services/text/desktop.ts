@@ -20,9 +20,14 @@ export function createTextServiceDesktop(): TextService {
 					}),
 			}),
 
-		writeToCursor: async (text) =>
+		writeToCursor: async (text, options) =>
 			tryAsync({
-				try: () => invoke<void>('write_text', { text }),
+				try: async () => {
+					await invoke('write_text', {
+						text,
+						entryId: options?.entryId ?? null,
+					});
+				},
 				catch: (error) =>
 					TextServiceErr({
 						message:
@@ -24,9 +24,16 @@ export type TextService = {
 	 * 3. Simulates paste (Cmd+V on macOS, Ctrl+V elsewhere)
 	 * 4. Restores the original clipboard
 	 *
+	 * On desktop the text is checked after it goes in where the target app
+	 * allows. If the app rejected it, the text is left on the clipboard and a
+	 * notification says so.
+	 *
 	 * @param text The text to write at the cursor position.
+	 * @param options.entryId The recording the text came from, so the outcome
+	 * is stored with its history entry.
 	 */
 	writeToCursor: (
 		text: string,
+		options?: { entryId?: string },
 	) => MaybePromise<Result<void, TextServiceError | WhisperingError>>;
 };
@@ -0,0 +1,291 @@
+import { Ok, type Result } from 'wellcrafted/result';
+import { z } from 'zod';
+import { WhisperingErr, type WhisperingError } from '$lib/result';
+import type { TranscriptInsights } from '$lib/services/db';
+import type { HttpService, HttpServiceError } from '$lib/services/http';
+import { HttpServiceLive } from '$lib/services/http';
+import type { Settings } from '$lib/settings';
+
+export const ASSEMBLYAI_TRANSCRIPTION_MODELS = [
+	{
+		name: 'best',
+		description:
+			"AssemblyAI's most accurate model. Supports auto chapters, entity detection and speaker labels.",
+		cost: '$0.37/hour',
+	},
+	{
+		name: 'nano',
+		description:
+			'Lower-cost model with broad language support, for when accuracy matters less than price.',
+		cost: '$0.12/hour',
+	},
+] as const satisfies {
+	name: string;
+	description: string;
+	cost: string;
+}[];
+
+export type AssemblyAIModel = (typeof ASSEMBLYAI_TRANSCRIPTION_MODELS)[number];
+
+const API_BASE_URL = 'https://api.assemblyai.com/v2';
+
+const POLL_INTERVAL_MS = 1000;
+
+/** Give up on a transcript that hasn't finished after this long */
+const POLL_TIMEOUT_MS = 10 * 60 * 1000;
+
+const uploadResponseSchema = z.object({ upload_url: z.string() });
+
+// AssemblyAI reports times in milliseconds
+const transcriptSchema = z.object({
+	id: z.string(),
+	status: z.enum(['queued', 'processing', 'completed', 'error']),
+	text: z.string().nullish(),
+	error: z.string().nullish(),
+	chapters: z
+		.array(
+			z.object({
+				start: z.number(),
+				end: z.number(),
+				headline: z.string(),
+				summary: z.string(),
+			}),
+		)
+		.nullish(),
+	entities: z
+		.array(
+			z.object({
+				entity_type: z.string(),
+				text: z.string(),
+				start: z.number(),
+				end: z.number(),
+			}),
+		)
+		.nullish(),
+	utterances: z
+		.array(
+			z.object({
+				speaker: z.string(),
+				start: z.number(),
+				end: z.number(),
+				text: z.string(),
+			}),
+		)
+		.nullish(),
+});
+
+type AssemblyAITranscript = z.infer<typeof transcriptSchema>;
+
+function toInsights(transcript: AssemblyAITranscript): TranscriptInsights {
+	return {
+		chapters: transcript.chapters?.map((chapter) => ({
+			start: chapter.start / 1000,
+			end: chapter.end / 1000,
+			headline: chapter.headline,
+			summary: chapter.summary,
+		})),
+		entities: transcript.entities?.map((entity) => ({
+			type: entity.entity_type,
+			text: entity.text,
+			start: entity.start / 1000,
+			end: entity.end / 1000,
+		})),
+		utterances: transcript.utterances?.map((utterance) => ({
+			speaker: `Speaker ${utterance.speaker}`,
+			start: utterance.start / 1000,
+			end: utterance.end / 1000,
+			text: utterance.text,
+		})),
+	};
+}
+
+function toWhisperingError(error: HttpServiceError) {
+	switch (error.name) {
+		case 'ConnectionError':
+			return WhisperingErr({
+				title: '🌐 Connection Issue',
+				description:
+					'Unable to connect to AssemblyAI. Please check your internet connection.',
+				action: { type: 'more-details', error: error.cause },
+			});
+		case 'ResponseError': {
+			const { status, message } = error;
+			if (status === 401) {
+				return WhisperingErr({
+					title: '🔑 Authentication Failed',
+					description:
+						'Your AssemblyAI API key is invalid or expired. Please update your API key in settings.',
+					action: {
+						type: 'link',
+						label: 'Update API key',
+						href: '/settings/transcription',
+					},
+				});
+			}
+			if (status === 429) {
+				return WhisperingErr({
+					title: '⏱️ Rate Limit Reached',
+					description: 'Too many requests. Please wait before trying again.',
+					action: { type: 'more-details', error: error.cause },
+				});
+			}
+			if (status >= 500) {
+				return WhisperingErr({
+					title: '🔧 Service Unavailable',
+					description: `AssemblyAI is temporarily unavailable (Error ${status}). Please try again later.`,
+					action: { type: 'more-details', error: error.cause },
+				});
+			}
+			return WhisperingErr({
+				title: '❌ Transcription Failed',
+				description:
+					message ||
+					'An unexpected error occurred during transcription. Please try again.',
+				action: { type: 'more-details', error: error.cause },
+			});
+		}
+		case 'ParseError':
+			return WhisperingErr({
+				title: '🔍 Response Error',
+				description:
+					'Received an unexpected response from AssemblyAI. Please try again.',
+				action: { type: 'more-details', error: error.cause },
+			});
+		default:
+			return WhisperingErr({
+				title: '❓ Unexpected Error',
+				description:
+					'An unexpected error occurred during transcription. Please try again.',
+				action: { type: 'more-details', error },
+			});
+	}
+}
+
+export function createAssemblyAITranscriptionService({
+	HttpService,
+}: {
+	HttpService: HttpService;
+}) {
+	return {
+		/**
+		 * Uploads the audio, starts a transcript with the enabled features and
+		 * polls until AssemblyAI finishes it.
+		 */
+		async transcribe(
+			audioBlob: Blob,
+			options: {
+				prompt: string;
+				temperature: string;
+				outputLanguage: Settings['transcription.outputLanguage'];
+				apiKey: string;
+				modelName: (string & {}) | AssemblyAIModel['name'];
+				autoChapters: boolean;
+				entityDetection: boolean;
+				speakerLabels: boolean;
+			},
+		): Promise<
+			Result<{ text: string; insights: TranscriptInsights }, WhisperingError>
+		> {
+			// Pre-validation: Check API key
+			if (!options.apiKey) {
+				return WhisperingErr({
+					title: '🔑 API Key Required',
+					description:
+						'Please enter your AssemblyAI API key in settings to use AssemblyAI transcription.',
+					action: {
+						type: 'link',
+						label: 'Add API key',
+						href: '/settings/transcription',
+					},
+				});
+			}
+
+			const headers = { Authorization: options.apiKey };
+
+			const { data: upload, error: uploadError } = await HttpService.post({
+				url: `${API_BASE_URL}/upload`,
+				body: audioBlob,
+				headers: { ...headers, 'Content-Type': 'application/octet-stream' },
+				schema: uploadResponseSchema,
+			});
+			if (uploadError) return toWhisperingError(uploadError);
+
+			const { data: created, error: createError } = await HttpService.post({
+				url: `${API_BASE_URL}/transcript`,
+				body: JSON.stringify({
+					audio_url: upload.upload_url,
+					speech_model: options.modelName,
+					...(options.outputLanguage === 'auto'
+						? { language_detection: true }
+						: { language_code: options.outputLanguage }),
+					...(options.prompt && {
+						word_boost: options.prompt
+							.split(',')
+							.map((word) => word.trim())
+							.filter(Boolean),
+					}),
+					auto_chapters: options.autoChapters,
+					entity_detection: options.entityDetection,
+					speaker_labels: options.speakerLabels,
+				}),
+				headers: { ...headers, 'Content-Type': 'application/json' },
+				schema: transcriptSchema,
+			});
+			if (createError) return toWhisperingError(createError);
+
+			let transcript = created;
+			const deadline = Date.now() + POLL_TIMEOUT_MS;
+			while (
+				transcript.status === 'queued' ||
+				transcript.status === 'processing'
+			) {
+				if (Date.now() > deadline) {
+					return WhisperingErr({
+						title: '⏱️ Transcription Timed Out',
+						description:
+							'AssemblyAI is taking unusually long to finish this transcript. Please try again later.',
+					});
+				}
+				await new Promise((resolve) =>
+					setTimeout(resolve, POLL_INTERVAL_MS),
+				);
+				const { data: polled, error: pollError } = await HttpService.get({
+					url: `${API_BASE_URL}/transcript/${transcript.id}`,
+					headers,
+					schema: transcriptSchema,
+				});
+				if (pollError) return toWhisperingError(pollError);
+				transcript = polled;
+			}
+
+			if (transcript.status === 'error') {
+				return WhisperingErr({
+					title: '❌ Transcription Failed',
+					description:
+						transcript.error ??
+						'AssemblyAI could not transcribe this audio. Please try again.',
+				});
+			}
+
+			const text = transcript.text?.trim();
+			if (!text) {
+				return WhisperingErr({
+					title: '📝 No Transcription Found',
+					description:
+						'No speech was detected in the audio file. Please check your audio and try again.',
+				});
+			}
+
+			return Ok({ text, insights: toInsights(transcript) });
+		},
+	};
+}
+
+export type AssemblyAITranscriptionService = ReturnType<
+	typeof createAssemblyAITranscriptionService
+>;
+
+export const AssemblyAITranscriptionServiceLive =
+	createAssemblyAITranscriptionService({
+		HttpService: HttpServiceLive,
+	});
@@ -0,0 +1,262 @@
+import { Ok, type Result } from 'wellcrafted/result';
+import { z } from 'zod';
+import { WhisperingErr, type WhisperingError } from '$lib/result';
+import { getExtensionFromAudioBlob } from '$lib/services/_utils';
+import type { HttpService } from '$lib/services/http';
+import { HttpServiceLive } from '$lib/services/http';
+import type { Settings } from '$lib/settings';
+import { toSpeechLocale } from './locale';
+
+export const AZURE_TRANSCRIPTION_MODELS = [
+	{
+		name: 'fast',
+		description:
+			'Azure AI Speech fast transcription. Returns results faster than real time with automatic punctuation and multi-language detection.',
+		cost: '$0.36/hour',
+	},
+] as const satisfies {
+	name: string;
+	description: string;
+	cost: string;
+}[];
+
+export type AzureModel = (typeof AZURE_TRANSCRIPTION_MODELS)[number];
+
+const MAX_FILE_SIZE_MB = 300 as const;
+
+const API_VERSION = '2024-11-15' as const;
+
+// Schema for Azure fast transcription response
+const azureResponseSchema = z.object({
+	combinedPhrases: z.array(z.object({ text: z.string() })),
+});
+
+/**
+ * Base URL of the Speech resource. A custom endpoint (private endpoint,
+ * sovereign cloud, or custom domain) takes precedence over the region.
+ */
+function getAzureBaseUrl({
+	region,
+	endpoint,
+}: {
+	region: string;
+	endpoint: string;
+}): string {
+	if (endpoint) return endpoint.replace(/\/+$/, '');
+	return `https://${region}.api.cognitive.microsoft.com`;
+}
+
+export function createAzureTranscriptionService({
+	HttpService,
+}: {
+	HttpService: HttpService;
+}) {
+	return {
+		async transcribe(
+			audioBlob: Blob,
+			options: {
+				prompt: string;
+				temperature: string;
+				outputLanguage: Settings['transcription.outputLanguage'];
+				apiKey: string;
+				modelName: (string & {}) | AzureModel['name'];
+				region: string;
+				endpoint: string;
+			},
+		): Promise<Result<string, WhisperingError>> {
+			// Pre-validation: Check API key
+			if (!options.apiKey) {
+				return WhisperingErr({
+					title: '🔑 API Key Required',
+					description:
+						'Please enter your Azure Speech resource key in settings to use Azure transcription.',
+					action: {
+						type: 'link',
+						label: 'Add API key',
+						href: '/settings/transcription',
+					},
+				});
+			}
+
+			if (!options.region && !options.endpoint) {
+				return WhisperingErr({
+					title: '🌍 Region Required',
+					description:
+						'Please enter the region of your Azure Speech resource (e.g. eastus) or a custom endpoint.',
+					action: {
+						type: 'link',
+						label: 'Configure Azure',
+						href: '/settings/transcription',
+					},
+				});
+			}
+
+			// Validate file size
+			const blobSizeInMb = audioBlob.size / (1024 * 1024);
+			if (blobSizeInMb > MAX_FILE_SIZE_MB) {
+				return WhisperingErr({
+					title: `The file size (${blobSizeInMb}MB) is too large`,
+					description: `Please upload a file smaller than ${MAX_FILE_SIZE_MB}MB.`,
+				});
+			}
+
+			const locale = toSpeechLocale(options.outputLanguage);
+			const definition = {
+				// Without locales, Azure identifies the spoken language itself
+				...(locale && { locales: [locale] }),
+				...(options.prompt && {
+					phraseList: {
+						phrases: options.prompt
+							.split(',')
+							.map((phrase) => phrase.trim())
+							.filter(Boolean),
+					},
+				}),
+			};
+
+			const formData = new FormData();
+			formData.append(
+				'audio',
+				new File(
+					[audioBlob],
+					`recording.${getExtensionFromAudioBlob(audioBlob)}`,
+					{ type: audioBlob.type },
+				),
+			);
+			formData.append('definition', JSON.stringify(definition));
+
+			const { data: azureResponse, error: postError } =
+				await HttpService.post({
+					url: `${getAzureBaseUrl(options)}/speechtotext/transcriptions:transcribe?api-version=${API_VERSION}`,
+					body: formData,
+					headers: {
+						'Ocp-Apim-Subscription-Key': options.apiKey,
+					},
+					schema: azureResponseSchema,
+				});
+
+			if (postError) {
+				switch (postError.name) {
+					case 'ConnectionError': {
+						return WhisperingErr({
+							title: '🌐 Connection Issue',
+							description:
+								'Unable to connect to Azure Speech. Please check your internet connection and region or endpoint settings.',
+							action: { type: 'more-details', error: postError.cause },
+						});
+					}
+
+					case 'ResponseError': {
+						const { status, message } = postError;
+
+						if (status === 400) {
+							return WhisperingErr({
+								title: '❌ Bad Request',
+								description:
+									message ||
+									'Invalid request parameters. Please check your audio file and language settings.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status === 401) {
+							return WhisperingErr({
+								title: '🔑 Authentication Failed',
+								description:
+									'Your Azure Speech key is invalid, or does not belong to a resource in this region. Please check your key and region in settings.',
+								action: {
+									type: 'link',
+									label: 'Update API key',
+									href: '/settings/transcription',
+								},
+							});
+						}
+
+						if (status === 403) {
+							return WhisperingErr({
+								title: '⛔ Access Denied',
+								description:
+									message ||
+									'Your Azure Speech resource does not allow this request. Check its pricing tier and network restrictions.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status === 413) {
+							return WhisperingErr({
+								title: '📦 Audio File Too Large',
+								description:
+									'Your audio file exceeds the maximum size limit. Try splitting it into smaller segments.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status === 429) {
+							return WhisperingErr({
+								title: '⏱️ Rate Limit Reached',
+								description:
+									'Too many requests for your Azure Speech resource. Please wait before trying again.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status && status >= 500) {
+							return WhisperingErr({
+								title: '🔧 Service Unavailable',
+								description: `Azure Speech is temporarily unavailable (Error ${status}). Please try again later.`,
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						return WhisperingErr({
+							title: '❌ Transcription Failed',
+							description:
+								message ||
+								'An unexpected error occurred during transcription. Please try again.',
+							action: { type: 'more-details', error: postError.cause },
+						});
+					}
+
+					case 'ParseError':
+						return WhisperingErr({
+							title: '🔍 Response Error',
+							description:
+								'Received an unexpected response from Azure Speech. Please try again.',
+							action: { type: 'more-details', error: postError.cause },
+						});
+
+					default:
+						return WhisperingErr({
+							title: '❓ Unexpected Error',
+							description:
+								'An unexpected error occurred during transcription. Please try again.',
+							action: { type: 'more-details', error: postError },
+						});
+				}
+			}
+
+			const transcript = azureResponse.combinedPhrases
+				.map((phrase) => phrase.text)
+				.join(' ')
+				.trim();
+
+			if (!transcript) {
+				return WhisperingErr({
+					title: '📝 No Transcription Found',
+					description:
+						'No speech was detected in the audio file. Please check your audio and try again.',
+				});
+			}
+
+			return Ok(transcript);
+		},
+	};
+}
+
+export type AzureTranscriptionService = ReturnType<
+	typeof createAzureTranscriptionService
+>;
+
+export const AzureTranscriptionServiceLive = createAzureTranscriptionService({
+	HttpService: HttpServiceLive,
+});
@@ -0,0 +1,427 @@
+import { Err, Ok, type Result, tryAsync } from 'wellcrafted/result';
+import { z } from 'zod';
+import { WhisperingErr, type WhisperingError } from '$lib/result';
+import { getExtensionFromAudioBlob } from '$lib/services/_utils';
+import type { HttpService } from '$lib/services/http';
+import { HttpServiceLive } from '$lib/services/http';
+import type { Settings } from '$lib/settings';
+import { toSpeechLocale } from './locale';
+
+export const GOOGLE_TRANSCRIPTION_MODELS = [
+	{
+		name: 'latest_long',
+		description:
+			'Best for long-form dictation and any content that is not a short command. Recommended for most users.',
+		cost: '$0.016/minute',
+	},
+	{
+		name: 'latest_short',
+		description:
+			'Optimized for short utterances such as commands or single phrases of a few seconds.',
+		cost: '$0.016/minute',
+	},
+	{
+		name: 'default',
+		description:
+			'General-purpose model for audio that does not fit the other models.',
+		cost: '$0.024/minute',
+	},
+] as const satisfies {
+	name: string;
+	description: string;
+	cost: string;
+}[];
+
+export type GoogleModel = (typeof GOOGLE_TRANSCRIPTION_MODELS)[number];
+
+/** Synchronous recognition accepts at most ~1 minute / 10MB of audio */
+const MAX_FILE_SIZE_MB = 10 as const;
+
+const CLOUD_PLATFORM_SCOPE = 'https://www.googleapis.com/auth/cloud-platform';
+
+// Schema for Google Speech-to-Text recognize response
+const googleResponseSchema = z.object({
+	// Omitted entirely when no speech was recognized
+	results: z
+		.array(
+			z.object({
+				alternatives: z.array(z.object({ transcript: z.string() })).optional(),
+			}),
+		)
+		.optional(),
+});
+
+const serviceAccountSchema = z.object({
+	type: z.literal('service_account'),
+	client_email: z.string(),
+	private_key: z.string(),
+	token_uri: z.string().default('https://oauth2.googleapis.com/token'),
+});
+
+type ServiceAccount = z.infer<typeof serviceAccountSchema>;
+
+const tokenResponseSchema = z.object({
+	access_token: z.string(),
+	expires_in: z.number(),
+});
+
+/**
+ * Picks the RecognitionConfig encoding for a recorded blob. WAV and FLAC
+ * carry their own header, so Google reads the format from the file.
+ */
+function getGoogleEncoding(
+	audioBlob: Blob,
+): { encoding: string; sampleRateHertz: number } | undefined {
+	switch (getExtensionFromAudioBlob(audioBlob)) {
+		case 'webm':
+			return { encoding: 'WEBM_OPUS', sampleRateHertz: 48000 };
+		case 'ogg':
+			return { encoding: 'OGG_OPUS', sampleRateHertz: 48000 };
+		case 'mp3':
+			return { encoding: 'MP3', sampleRateHertz: 44100 };
+		default:
+			return undefined;
+	}
+}
+
+function base64Url(bytes: ArrayBuffer | string): string {
+	const binary =
+		typeof bytes === 'string'
+			? bytes
+			: String.fromCharCode(...new Uint8Array(bytes));
+	return btoa(binary)
+		.replace(/\+/g, '-')
+		.replace(/\//g, '_')
+		.replace(/=+$/, '');
+}
+
+async function blobToBase64(blob: Blob): Promise<string> {
+	const bytes = new Uint8Array(await blob.arrayBuffer());
+	let binary = '';
+	// Chunked to stay under the argument limit of String.fromCharCode
+	for (let i = 0; i < bytes.length; i += 0x8000) {
+		binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+	}
+	return btoa(binary);
+}
+
+/** Signs a service account JWT assertion for the OAuth token exchange */
+async function createAssertion(account: ServiceAccount): Promise<string> {
+	const pem = account.private_key
+		.replace(/-----(BEGIN|END) PRIVATE KEY-----/g, '')
+		.replace(/\s+/g, '');
+	const der = Uint8Array.from(atob(pem), (char) => char.charCodeAt(0));
+	const key = await crypto.subtle.importKey(
+		'pkcs8',
+		der,
+		{ name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-256' },
+		false,
+		['sign'],
+	);
+
+	const now = Math.floor(Date.now() / 1000);
+	const unsigned = [
+		base64Url(JSON.stringify({ alg: 'RS256', typ: 'JWT' })),
+		base64Url(
+			JSON.stringify({
+				iss: account.client_email,
+				scope: CLOUD_PLATFORM_SCOPE,
+				aud: account.token_uri,
+				iat: now,
+				exp: now + 3600,
+			}),
+		),
+	].join('.');
+	const signature = await crypto.subtle.sign(
+		'RSASSA-PKCS1-v1_5',
+		key,
+		new TextEncoder().encode(unsigned),
+	);
+	return `${unsigned}.${base64Url(signature)}`;
+}
+
+/**
+ * Parses the credential setting, which holds either a plain API key or the
+ * JSON key file of a service account.
+ */
+function parseCredential(
+	credential: string,
+):
+	| { type: 'apiKey'; apiKey: string }
+	| { type: 'serviceAccount'; account: ServiceAccount }
+	| undefined {
+	const trimmed = credential.trim();
+	if (!trimmed.startsWith('{')) return { type: 'apiKey', apiKey: trimmed };
+	try {
+		const account = serviceAccountSchema.safeParse(JSON.parse(trimmed));
+		return account.success
+			? { type: 'serviceAccount', account: account.data }
+			: undefined;
+	} catch {
+		return undefined;
+	}
+}
+
+export function createGoogleTranscriptionService({
+	HttpService,
+}: {
+	HttpService: HttpService;
+}) {
+	// Access tokens from service accounts, reused until shortly before expiry
+	const tokenCache = new Map<string, { token: string; expiresAt: number }>();
+
+	async function getAccessToken(
+		account: ServiceAccount,
+	): Promise<Result<string, WhisperingError>> {
+		const cached = tokenCache.get(account.client_email);
+		if (cached && cached.expiresAt > Date.now()) return Ok(cached.token);
+
+		const { data: assertion, error: signError } = await tryAsync({
+			try: () => createAssertion(account),
+			catch: (error) =>
+				WhisperingErr({
+					title: '🔑 Invalid Service Account Key',
+					description:
+						'The private key in your service account JSON could not be read. Please paste the key file exactly as downloaded from Google Cloud.',
+					action: { type: 'more-details', error },
+				}),
+		});
+		if (signError) return Err(signError);
+
+		const { data: tokenResponse, error: tokenError } =
+			await HttpService.post({
+				url: account.token_uri,
+				body: new URLSearchParams({
+					grant_type: 'urn:ietf:params:oauth:grant-type:jwt-bearer',
+					assertion,
+				}),
+				headers: { 'Content-Type': 'application/x-www-form-urlencoded' },
+				schema: tokenResponseSchema,
+			});
+		if (tokenError) {
+			return WhisperingErr({
+				title: '🔑 Authentication Failed',
+				description:
+					'Google rejected the service account. Make sure it is enabled and has access to the Speech-to-Text API.',
+				action: { type: 'more-details', error: tokenError },
+			});
+		}
+
+		tokenCache.set(account.client_email, {
+			token: tokenResponse.access_token,
+			expiresAt: Date.now() + (tokenResponse.expires_in - 60) * 1000,
+		});
+		return Ok(tokenResponse.access_token);
+	}
+
+	return {
+		async transcribe(
+			audioBlob: Blob,
+			options: {
+				prompt: string;
+				temperature: string;
+				outputLanguage: Settings['transcription.outputLanguage'];
+				apiKey: string;
+				modelName: (string & {}) | GoogleModel['name'];
+				endpoint: string;
+			},
+		): Promise<Result<string, WhisperingError>> {
+			// Pre-validation: Check credentials
+			if (!options.apiKey) {
+				return WhisperingErr({
+					title: '🔑 API Key Required',
+					description:
+						'Please enter a Google Cloud API key or service account JSON in settings to use Google Speech-to-Text.',
+					action: {
+						type: 'link',
+						label: 'Add API key',
+						href: '/settings/transcription',
+					},
+				});
+			}
+
+			const credential = parseCredential(options.apiKey);
+			if (!credential) {
+				return WhisperingErr({
+					title: '🔑 Invalid Service Account Key',
+					description:
+						'The service account JSON could not be parsed. Please paste the key file exactly as downloaded from Google Cloud.',
+					action: {
+						type: 'link',
+						label: 'Update credentials',
+						href: '/settings/transcription',
+					},
+				});
+			}
+
+			// Validate file size
+			const blobSizeInMb = audioBlob.size / (1024 * 1024);
+			if (blobSizeInMb > MAX_FILE_SIZE_MB) {
+				return WhisperingErr({
+					title: `The file size (${blobSizeInMb}MB) is too large`,
+					description: `Google Speech-to-Text accepts up to ${MAX_FILE_SIZE_MB}MB (about one minute) per request. Please record a shorter clip.`,
+				});
+			}
+
+			const baseUrl = (
+				options.endpoint || 'https://speech.googleapis.com'
+			).replace(/\/+$/, '');
+			let url = `${baseUrl}/v1p1beta1/speech:recognize`;
+			const headers: Record<string, string> = {
+				'Content-Type': 'application/json',
+			};
+			if (credential.type === 'apiKey') {
+				url += `?key=${encodeURIComponent(credential.apiKey)}`;
+			} else {
+				const { data: token, error: tokenError } = await getAccessToken(
+					credential.account,
+				);
+				if (tokenError) return Err(tokenError);
+				headers.Authorization = `Bearer ${token}`;
+			}
+
+			const { data: googleResponse, error: postError } =
+				await HttpService.post({
+					url,
+					body: JSON.stringify({
+						config: {
+							...getGoogleEncoding(audioBlob),
+							// Google requires a language; auto detection isn't supported
+							languageCode: toSpeechLocale(options.outputLanguage) ?? 'en-US',
+							model: options.modelName,
+							enableAutomaticPunctuation: true,
+							...(options.prompt && {
+								speechContexts: [
+									{
+										phrases: options.prompt
+											.split(',')
+											.map((phrase) => phrase.trim())
+											.filter(Boolean),
+									},
+								],
+							}),
+						},
+						audio: { content: await blobToBase64(audioBlob) },
+					}),
+					headers,
+					schema: googleResponseSchema,
+				});
+
+			if (postError) {
+				switch (postError.name) {
+					case 'ConnectionError': {
+						return WhisperingErr({
+							title: '🌐 Connection Issue',
+							description:
+								'Unable to connect to Google Speech-to-Text. Please check your internet connection and endpoint setting.',
+							action: { type: 'more-details', error: postError.cause },
+						});
+					}
+
+					case 'ResponseError': {
+						const { status, message } = postError;
+
+						if (status === 400) {
+							return WhisperingErr({
+								title: '❌ Bad Request',
+								description:
+									message ||
+									'Invalid request parameters. Please check your audio file, model and language settings.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status === 401) {
+							return WhisperingErr({
+								title: '🔑 Authentication Failed',
+								description:
+									'Your Google Cloud credentials are invalid or expired. Please update them in settings.',
+								action: {
+									type: 'link',
+									label: 'Update API key',
+									href: '/settings/transcription',
+								},
+							});
+						}
+
+						if (status === 403) {
+							return WhisperingErr({
+								title: '⛔ Access Denied',
+								description:
+									message ||
+									'Make sure the Speech-to-Text API is enabled for your project and your key is allowed to use it.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status === 429) {
+							return WhisperingErr({
+								title: '⏱️ Rate Limit Reached',
+								description:
+									'Your project has exceeded its Speech-to-Text quota. Please wait before trying again.',
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						if (status && status >= 500) {
+							return WhisperingErr({
+								title: '🔧 Service Unavailable',
+								description: `Google Speech-to-Text is temporarily unavailable (Error ${status}). Please try again later.`,
+								action: { type: 'more-details', error: postError.cause },
+							});
+						}
+
+						return WhisperingErr({
+							title: '❌ Transcription Failed',
+							description:
+								message ||
+								'An unexpected error occurred during transcription. Please try again.',
+							action: { type: 'more-details', error: postError.cause },
+						});
+					}
+
+					case 'ParseError':
+						return WhisperingErr({
+							title: '🔍 Response Error',
+							description:
+								'Received an unexpected response from Google Speech-to-Text. Please try again.',
+							action: { type: 'more-details', error: postError.cause },
+						});
+
+					default:
+						return WhisperingErr({
+							title: '❓ Unexpected Error',
+							description:
+								'An unexpected error occurred during transcription. Please try again.',
+							action: { type: 'more-details', error: postError },
+						});
+				}
+			}
+
+			const transcript = (googleResponse.results ?? [])
+				.map((result) => result.alternatives?.at(0)?.transcript ?? '')
+				.join(' ')
+				.trim();
+
+			if (!transcript) {
+				return WhisperingErr({
+					title: '📝 No Transcription Found',
+					description:
+						'No speech was detected in the audio file. Please check your audio and try again.',
+				});
+			}
+
+			return Ok(transcript);
+		},
+	};
+}
+
+export type GoogleTranscriptionService = ReturnType<
+	typeof createGoogleTranscriptionService
+>;
+
+export const GoogleTranscriptionServiceLive = createGoogleTranscriptionService(
+	{
+		HttpService: HttpServiceLive,
+	},
+);
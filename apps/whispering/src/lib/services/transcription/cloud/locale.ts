@@ -0,0 +1,37 @@
+import type { Settings } from '$lib/settings';
+
+/**
+ * Region used for languages whose most common locale isn't simply the
+ * language code repeated (e.g. `en` → `en-US`, not `en-EN`).
+ */
+const DEFAULT_REGIONS: Record<string, string> = {
+	ar: 'SA',
+	cs: 'CZ',
+	da: 'DK',
+	el: 'GR',
+	en: 'US',
+	he: 'IL',
+	hi: 'IN',
+	ja: 'JP',
+	ko: 'KR',
+	ms: 'MY',
+	pt: 'BR',
+	sv: 'SE',
+	uk: 'UA',
+	vi: 'VN',
+	zh: 'CN',
+};
+
+/**
+ * Converts an output language setting (ISO 639-1, e.g. `de`) into the
+ * BCP-47 locale (e.g. `de-DE`) expected by Azure and Google.
+ *
+ * @returns The locale, or undefined when the language is detected automatically
+ */
+export function toSpeechLocale(
+	language: Settings['transcription.outputLanguage'],
+): string | undefined {
+	if (language === 'auto') return undefined;
+	const region = DEFAULT_REGIONS[language] ?? language.toUpperCase();
+	return `${language}-${region}`;
+}
@@ -0,0 +1,363 @@
+import { Ok, type Result } from 'wellcrafted/result';
+import { z } from 'zod';
+import { WhisperingErr, type WhisperingError } from '$lib/result';
+import { getExtensionFromAudioBlob } from '$lib/services/_utils';
+import type { TranscriptInsights } from '$lib/services/db';
+import type { HttpService, HttpServiceError } from '$lib/services/http';
+import { HttpServiceLive } from '$lib/services/http';
+import type { Settings } from '$lib/settings';
+
+export const SPEECHMATICS_TRANSCRIPTION_MODELS = [
+	{
+		name: 'enhanced',
+		description:
+			'Highest accuracy operating point. Recommended for dictation and any audio where accuracy matters most.',
+		cost: '$0.40/hour',
+	},
+	{
+		name: 'standard',
+		description:
+			'Faster, lower-cost operating point with slightly lower accuracy.',
+		cost: '$0.30/hour',
+	},
+] as const satisfies {
+	name: string;
+	description: string;
+	cost: string;
+}[];
+
+export type SpeechmaticsModel =
+	(typeof SPEECHMATICS_TRANSCRIPTION_MODELS)[number];
+
+const API_BASE_URL = 'https://asr.api.speechmatics.com/v2';
+
+const POLL_INTERVAL_MS = 1000;
+
+/** Give up on a job that hasn't finished after this long */
+const POLL_TIMEOUT_MS = 10 * 60 * 1000;
+
+const createJobResponseSchema = z.object({ id: z.string() });
+
+const jobStatusSchema = z.object({
+	job: z.object({
+		status: z.enum(['running', 'done', 'rejected', 'deleted', 'expired']),
+		errors: z.array(z.object({ message: z.string() })).optional(),
+	}),
+});
+
+// Speechmatics reports times in seconds
+const transcriptSchema = z.object({
+	results: z.array(
+		z.object({
+			type: z.enum(['word', 'punctuation', 'entity', 'speaker_change']),
+			start_time: z.number(),
+			end_time: z.number(),
+			attaches_to: z.string().optional(),
+			entity_class: z.string().optional(),
+			alternatives: z
+				.array(
+					z.object({
+						content: z.string(),
+						speaker: z.string().optional(),
+					}),
+				)
+				.optional(),
+		}),
+	),
+	chapters: z
+		.array(
+			z.object({
+				title: z.string(),
+				summary: z.string(),
+				start_time: z.number(),
+				end_time: z.number(),
+			}),
+		)
+		.optional(),
+});
+
+type SpeechmaticsTranscript = z.infer<typeof transcriptSchema>;
+
+/** Joins result items into text, attaching punctuation to the previous word */
+function joinItems(items: SpeechmaticsTranscript['results']): string {
+	return items
+		.reduce((text, item) => {
+			const content = item.alternatives?.at(0)?.content ?? '';
+			if (!content) return text;
+			return item.type === 'punctuation' || item.attaches_to === 'previous'
+				? `${text}${content}`
+				: `${text} ${content}`;
+		}, '')
+		.trim();
+}
+
+function toTranscript(transcript: SpeechmaticsTranscript): {
+	text: string;
+	insights: TranscriptInsights;
+} {
+	const items = transcript.results.filter(
+		(item) => item.type !== 'speaker_change',
+	);
+
+	// Consecutive items from the same speaker form one utterance
+	const utterances: NonNullable<TranscriptInsights['utterances']> = [];
+	let turn: SpeechmaticsTranscript['results'] = [];
+	const flush = () => {
+		const speaker = turn.find((item) => item.alternatives?.at(0)?.speaker)
+			?.alternatives?.[0]?.speaker;
+		if (turn.length && speaker) {
+			utterances.push({
+				speaker: `Speaker ${speaker.replace(/^S/, '')}`,
+				start: turn[0].start_time,
+				end: turn[turn.length - 1].end_time,
+				text: joinItems(turn),
+			});
+		}
+		turn = [];
+	};
+	for (const item of items) {
+		const speaker = item.alternatives?.at(0)?.speaker;
+		const current = turn.at(-1)?.alternatives?.at(0)?.speaker;
+		if (
+			item.type !== 'punctuation' &&
+			speaker &&
+			current &&
+			speaker !== current
+		) {
+			flush();
+		}
+		turn.push(item);
+	}
+	flush();
+
+	const entities = items
+		.filter((item) => item.type === 'entity' && item.entity_class)
+		.map((item) => ({
+			type: item.entity_class ?? '',
+			text: item.alternatives?.at(0)?.content ?? '',
+			start: item.start_time,
+			end: item.end_time,
+		}));
+
+	return {
+		text: joinItems(items),
+		insights: {
+			chapters: transcript.chapters?.map((chapter) => ({
+				start: chapter.start_time,
+				end: chapter.end_time,
+				headline: chapter.title,
+				summary: chapter.summary,
+			})),
+			entities: entities.length ? entities : undefined,
+			utterances: utterances.length ? utterances : undefined,
+		},
+	};
+}
+
+function toWhisperingError(error: HttpServiceError) {
+	switch (error.name) {
+		case 'ConnectionError':
+			return WhisperingErr({
+				title: '🌐 Connection Issue',
+				description:
+					'Unable to connect to Speechmatics. Please check your internet connection.',
+				action: { type: 'more-details', error: error.cause },
+			});
+		case 'ResponseError': {
+			const { status, message } = error;
+			if (status === 401) {
+				return WhisperingErr({
+					title: '🔑 Authentication Failed',
+					description:
+						'Your Speechmatics API key is invalid or expired. Please update your API key in settings.',
+					action: {
+						type: 'link',
+						label: 'Update API key',
+						href: '/settings/transcription',
+					},
+				});
+			}
+			if (status === 403) {
+				return WhisperingErr({
+					title: '⛔ Access Denied',
+					description:
+						message ||
+						'Your Speechmatics plan does not include one of the requested features.',
+					action: { type: 'more-details', error: error.cause },
+				});
+			}
+			if (status === 429) {
+				return WhisperingErr({
+					title: '⏱️ Rate Limit Reached',
+					description: 'Too many requests. Please wait before trying again.',
+					action: { type: 'more-details', error: error.cause },
+				});
+			}
+			if (status >= 500) {
+				return WhisperingErr({
+					title: '🔧 Service Unavailable',
+					description: `Speechmatics is temporarily unavailable (Error ${status}). Please try again later.`,
+					action: { type: 'more-details', error: error.cause },
+				});
+			}
+			return WhisperingErr({
+				title: '❌ Transcription Failed',
+				description:
+					message ||
+					'An unexpected error occurred during transcription. Please try again.',
+				action: { type: 'more-details', error: error.cause },
+			});
+		}
+		case 'ParseError':
+			return WhisperingErr({
+				title: '🔍 Response Error',
+				description:
+					'Received an unexpected response from Speechmatics. Please try again.',
+				action: { type: 'more-details', error: error.cause },
+			});
+		default:
+			return WhisperingErr({
+				title: '❓ Unexpected Error',
+				description:
+					'An unexpected error occurred during transcription. Please try again.',
+				action: { type: 'more-details', error },
+			});
+	}
+}
+
+export function createSpeechmaticsTranscriptionService({
+	HttpService,
+}: {
+	HttpService: HttpService;
+}) {
+	return {
+		/**
+		 * Submits a batch job with the enabled features and polls until
+		 * Speechmatics finishes it.
+		 */
+		async transcribe(
+			audioBlob: Blob,
+			options: {
+				prompt: string;
+				temperature: string;
+				outputLanguage: Settings['transcription.outputLanguage'];
+				apiKey: string;
+				modelName: (string & {}) | SpeechmaticsModel['name'];
+				autoChapters: boolean;
+				entities: boolean;
+				diarization: boolean;
+			},
+		): Promise<
+			Result<{ text: string; insights: TranscriptInsights }, WhisperingError>
+		> {
+			// Pre-validation: Check API key
+			if (!options.apiKey) {
+				return WhisperingErr({
+					title: '🔑 API Key Required',
+					description:
+						'Please enter your Speechmatics API key in settings to use Speechmatics transcription.',
+					action: {
+						type: 'link',
+						label: 'Add API key',
+						href: '/settings/transcription',
+					},
+				});
+			}
+
+			const headers = { Authorization: `Bearer ${options.apiKey}` };
+
+			const config = {
+				type: 'transcription',
+				transcription_config: {
+					language: options.outputLanguage,
+					operating_point: options.modelName,
+					enable_entities: options.entities,
+					...(options.diarization && { diarization: 'speaker' }),
+					...(options.prompt && {
+						additional_vocab: options.prompt
+							.split(',')
+							.map((content) => ({ content: content.trim() }))
+							.filter(({ content }) => content),
+					}),
+				},
+				...(options.autoChapters && { auto_chapters_config: {} }),
+			};
+
+			const formData = new FormData();
+			formData.append(
+				'data_file',
+				new File(
+					[audioBlob],
+					`recording.${getExtensionFromAudioBlob(audioBlob)}`,
+					{ type: audioBlob.type },
+				),
+			);
+			formData.append('config', JSON.stringify(config));
+
+			const { data: job, error: createError } = await HttpService.post({
+				url: `${API_BASE_URL}/jobs`,
+				body: formData,
+				headers,
+				schema: createJobResponseSchema,
+			});
+			if (createError) return toWhisperingError(createError);
+
+			const deadline = Date.now() + POLL_TIMEOUT_MS;
+			while (true) {
+				if (Date.now() > deadline) {
+					return WhisperingErr({
+						title: '⏱️ Transcription Timed Out',
+						description:
+							'Speechmatics is taking unusually long to finish this job. Please try again later.',
+					});
+				}
+				await new Promise((resolve) =>
+					setTimeout(resolve, POLL_INTERVAL_MS),
+				);
+				const { data: status, error: pollError } = await HttpService.get({
+					url: `${API_BASE_URL}/jobs/${job.id}`,
+					headers,
+					schema: jobStatusSchema,
+				});
+				if (pollError) return toWhisperingError(pollError);
+				if (status.job.status === 'done') break;
+				if (status.job.status !== 'running') {
+					return WhisperingErr({
+						title: '❌ Transcription Failed',
+						description:
+							status.job.errors?.at(0)?.message ??
+							`Speechmatics could not transcribe this audio (job ${status.job.status}).`,
+					});
+				}
+			}
+
+			const { data: transcript, error: transcriptError } =
+				await HttpService.get({
+					url: `${API_BASE_URL}/jobs/${job.id}/transcript?format=json-v2`,
+					headers,
+					schema: transcriptSchema,
+				});
+			if (transcriptError) return toWhisperingError(transcriptError);
+
+			const result = toTranscript(transcript);
+			if (!result.text) {
+				return WhisperingErr({
+					title: '📝 No Transcription Found',
+					description:
+						'No speech was detected in the audio file. Please check your audio and try again.',
+				});
+			}
+
+			return Ok(result);
+		},
+	};
+}
+
+export type SpeechmaticsTranscriptionService = ReturnType<
+	typeof createSpeechmaticsTranscriptionService
+>;
+
+export const SpeechmaticsTranscriptionServiceLive =
+	createSpeechmaticsTranscriptionService({
+		HttpService: HttpServiceLive,
+	});
@@ -1,11 +1,15 @@
 // Direct imports and re-exports from organized services
 
 // Cloud transcription services
+import { AssemblyAITranscriptionServiceLive } from './cloud/assemblyai';
+import { AzureTranscriptionServiceLive } from './cloud/azure';
 import { DeepgramTranscriptionServiceLive } from './cloud/deepgram';
 import { ElevenlabsTranscriptionServiceLive } from './cloud/elevenlabs';
+import { GoogleTranscriptionServiceLive } from './cloud/google';
 import { GroqTranscriptionServiceLive } from './cloud/groq';
 import { MistralTranscriptionServiceLive } from './cloud/mistral';
 import { OpenaiTranscriptionServiceLive } from './cloud/openai';
+import { SpeechmaticsTranscriptionServiceLive } from './cloud/speechmatics';
 import { ParakeetTranscriptionServiceLive } from './local/parakeet';
 // Local transcription services
 import { WhisperCppTranscriptionServiceLive } from './local/whispercpp';
@@ -21,5 +25,9 @@ export {
 	GroqTranscriptionServiceLive as groq,
 	MistralTranscriptionServiceLive as mistral,
 	OpenaiTranscriptionServiceLive as openai,
+	AzureTranscriptionServiceLive as azure,
+	GoogleTranscriptionServiceLive as google,
+	AssemblyAITranscriptionServiceLive as assemblyai,
+	SpeechmaticsTranscriptionServiceLive as speechmatics,
 	SpeachesTranscriptionServiceLive as speaches,
 };
@@ -0,0 +1,61 @@
+import type { TranscriptInsights } from '$lib/services/db';
+
+function formatTimestamp(seconds: number): string {
+	const minutes = Math.floor(seconds / 60);
+	const remainder = Math.floor(seconds % 60);
+	return `${minutes}:${remainder.toString().padStart(2, '0')}`;
+}
+
+/**
+ * Renders transcript insights as Markdown, for previewing and copying
+ * alongside the transcript.
+ *
+ * @returns The Markdown, or an empty string if there is nothing to show
+ */
+export function formatTranscriptInsights(insights: TranscriptInsights): string {
+	const sections: string[] = [];
+
+	if (insights.chapters?.length) {
+		sections.push(
+			[
+				'## Chapters',
+				...insights.chapters.map(
+					(chapter) =>
+						`- **${formatTimestamp(chapter.start)} ${chapter.headline}**${chapter.summary ? ` — ${chapter.summary}` : ''}`,
+				),
+			].join('\n'),
+		);
+	}
+
+	if (insights.utterances?.length) {
+		sections.push(
+			[
+				'## Speakers',
+				...insights.utterances.map(
+					(utterance) =>
+						`- [${formatTimestamp(utterance.start)}] **${utterance.speaker}:** ${utterance.text}`,
+				),
+			].join('\n'),
+		);
+	}
+
+	if (insights.entities?.length) {
+		// The same entity is usually mentioned more than once
+		const unique = new Map(
+			insights.entities.map((entity) => [
+				`${entity.type}:${entity.text.toLowerCase()}`,
+				entity,
+			]),
+		);
+		sections.push(
+			[
+				'## Entities',
+				...[...unique.values()].map(
+					(entity) => `- ${entity.text} (${entity.type.replace(/_/g, ' ')})`,
+				),
+			].join('\n'),
+		);
+	}
+
+	return sections.join('\n\n');
+}
@@ -3,7 +3,11 @@ import { exists, stat } from '@tauri-apps/plugin-fs';
 import { type } from 'arktype';
 import { extractErrorMessage } from 'wellcrafted/error';
 import { Ok, type Result, tryAsync } from 'wellcrafted/result';
-import { WhisperingErr, type WhisperingError } from '$lib/result';
+import {
+	WhisperingErr,
+	type WhisperingError,
+	WhisperingWarningErr,
+} from '$lib/result';
 import type { ParakeetModelConfig } from './types';
 
 /**
@@ -50,7 +54,7 @@ export const PARAKEET_MODELS: readonly ParakeetModelConfig[] = [
 ] as const;
 
 const ParakeetErrorType = type({
-	name: "'AudioReadError' | 'FfmpegNotFoundError' | 'ModelLoadError' | 'TranscriptionError'",
+	name: "'AudioReadError' | 'FfmpegNotFoundError' | 'ModelLoadError' | 'TranscriptionError' | 'Cancelled'",
 	message: 'string',
 });
 
@@ -58,7 +62,11 @@ export function createParakeetTranscriptionService() {
 	return {
 		async transcribe(
 			audioBlob: Blob,
-			options: { modelPath: string },
+			options: {
+				modelPath: string;
+				/** Lets `cancel_job` abort this transcription */
+				jobId?: string;
+			},
 		): Promise<Result<string, WhisperingError>> {
 			// Pre-validation
 			if (!options.modelPath) {
@@ -121,6 +129,7 @@ export function createParakeetTranscriptionService() {
 					invoke<string>('transcribe_audio_parakeet', {
 						audioData: audioData,
 						modelPath: options.modelPath,
+						jobId: options.jobId ?? null,
 					}),
 				catch: (unknownError) => {
 					const result = ParakeetErrorType(unknownError);
@@ -176,6 +185,12 @@ export function createParakeetTranscriptionService() {
 								},
 							});
 
+						case 'Cancelled':
+							return WhisperingWarningErr({
+								title: '⏹️ Transcription Cancelled',
+								description: error.message,
+							});
+
 						default:
 							return WhisperingErr({
 								title: '❌ Parakeet Error',
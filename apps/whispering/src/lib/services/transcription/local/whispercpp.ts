@@ -3,7 +3,11 @@ import { exists } from '@tauri-apps/plugin-fs';
 import { type } from 'arktype';
 import { extractErrorMessage } from 'wellcrafted/error';
 import { Ok, type Result, tryAsync } from 'wellcrafted/result';
-import { WhisperingErr, type WhisperingError } from '$lib/result';
+import {
+	WhisperingErr,
+	type WhisperingError,
+	WhisperingWarningErr,
+} from '$lib/result';
 import type { Settings } from '$lib/settings';
 import type { WhisperModelConfig } from './types';
 
@@ -63,7 +67,7 @@ export const WHISPER_MODELS: readonly WhisperModelConfig[] = [
 ] as const;
 
 const WhisperCppErrorType = type({
-	name: "'AudioReadError' | 'FfmpegNotFoundError' | 'GpuError' | 'ModelLoadError' | 'TranscriptionError'",
+	name: "'AudioReadError' | 'FfmpegNotFoundError' | 'GpuError' | 'ModelLoadError' | 'TranscriptionError' | 'Cancelled'",
 	message: 'string',
 });
 
@@ -74,6 +78,8 @@ export function createWhisperCppTranscriptionService() {
 			options: {
 				outputLanguage: Settings['transcription.outputLanguage'];
 				modelPath: string;
+				/** Lets `cancel_job` abort this transcription */
+				jobId?: string;
 			},
 		): Promise<Result<string, WhisperingError>> {
 			// Pre-validation
@@ -120,6 +126,7 @@ export function createWhisperCppTranscriptionService() {
 						modelPath: options.modelPath,
 						language:
 							options.outputLanguage === 'auto' ? null : options.outputLanguage,
+						jobId: options.jobId ?? null,
 					}),
 				catch: (unknownError) => {
 					const result = WhisperCppErrorType(unknownError);
@@ -186,6 +193,12 @@ export function createWhisperCppTranscriptionService() {
 								},
 							});
 
+						case 'Cancelled':
+							return WhisperingWarningErr({
+								title: '⏹️ Transcription Cancelled',
+								description: error.message,
+							});
+
 						default:
 							return WhisperingErr({
 								title: '❌ Whisper C++ Error',
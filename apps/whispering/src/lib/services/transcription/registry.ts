@@ -2,16 +2,25 @@
  * Transcription service configurations
  */
 
+import assemblyaiIcon from '$lib/constants/icons/assemblyai.svg?raw';
+import azureIcon from '$lib/constants/icons/azure.svg?raw';
 import deepgramIcon from '$lib/constants/icons/deepgram.svg?raw';
 import elevenlabsIcon from '$lib/constants/icons/elevenlabs.svg?raw';
 import ggmlIcon from '$lib/constants/icons/ggml.svg?raw';
+import googleCloudIcon from '$lib/constants/icons/google-cloud.svg?raw';
 // Import SVG icons as strings
 import groqIcon from '$lib/constants/icons/groq.svg?raw';
 import mistralIcon from '$lib/constants/icons/mistral.svg?raw';
 import nvidiaIcon from '$lib/constants/icons/nvidia.svg?raw';
 import openaiIcon from '$lib/constants/icons/openai.svg?raw';
 import speachesIcon from '$lib/constants/icons/speaches.svg?raw';
+import speechmaticsIcon from '$lib/constants/icons/speechmatics.svg?raw';
 import type { Settings } from '$lib/settings';
+import {
+	ASSEMBLYAI_TRANSCRIPTION_MODELS,
+	type AssemblyAIModel,
+} from './cloud/assemblyai';
+import { AZURE_TRANSCRIPTION_MODELS, type AzureModel } from './cloud/azure';
 import {
 	DEEPGRAM_TRANSCRIPTION_MODELS,
 	type DeepgramModel,
@@ -20,19 +29,31 @@ import {
 	ELEVENLABS_TRANSCRIPTION_MODELS,
 	type ElevenLabsModel,
 } from './cloud/elevenlabs';
+import {
+	GOOGLE_TRANSCRIPTION_MODELS,
+	type GoogleModel,
+} from './cloud/google';
 import { GROQ_MODELS, type GroqModel } from './cloud/groq';
 import {
 	MISTRAL_TRANSCRIPTION_MODELS,
 	type MistralModel,
 } from './cloud/mistral';
 import { OPENAI_TRANSCRIPTION_MODELS, type OpenAIModel } from './cloud/openai';
+import {
+	SPEECHMATICS_TRANSCRIPTION_MODELS,
+	type SpeechmaticsModel,
+} from './cloud/speechmatics';
 
 type TranscriptionModel =
 	| OpenAIModel
 	| GroqModel
 	| ElevenLabsModel
 	| DeepgramModel
-	| MistralModel;
+	| MistralModel
+	| AzureModel
+	| GoogleModel
+	| AssemblyAIModel
+	| SpeechmaticsModel;
 
 export const TRANSCRIPTION_SERVICE_IDS = [
 	'whispercpp',
@@ -42,6 +63,10 @@ export const TRANSCRIPTION_SERVICE_IDS = [
 	'ElevenLabs',
 	'Deepgram',
 	'Mistral',
+	'Azure',
+	'Google',
+	'AssemblyAI',
+	'Speechmatics',
 	'speaches',
 	// 'owhisper',
 ] as const;
@@ -160,6 +185,54 @@ export const TRANSCRIPTION_SERVICES = [
 		apiKeyField: 'apiKeys.mistral',
 		location: 'cloud',
 	},
+	{
+		id: 'Azure',
+		name: 'Azure Speech',
+		icon: azureIcon,
+		invertInDarkMode: false, // Azure has a colored logo
+		description: 'Microsoft Azure AI Speech service',
+		models: AZURE_TRANSCRIPTION_MODELS,
+		defaultModel: AZURE_TRANSCRIPTION_MODELS[0],
+		modelSettingKey: 'transcription.azure.model',
+		apiKeyField: 'apiKeys.azureSpeech',
+		location: 'cloud',
+	},
+	{
+		id: 'Google',
+		name: 'Google Speech-to-Text',
+		icon: googleCloudIcon,
+		invertInDarkMode: false, // Google Cloud has a colored logo
+		description: 'Google Cloud speech recognition',
+		models: GOOGLE_TRANSCRIPTION_MODELS,
+		defaultModel: GOOGLE_TRANSCRIPTION_MODELS[0],
+		modelSettingKey: 'transcription.google.model',
+		apiKeyField: 'apiKeys.googleSpeech',
+		location: 'cloud',
+	},
+	{
+		id: 'AssemblyAI',
+		name: 'AssemblyAI',
+		icon: assemblyaiIcon,
+		invertInDarkMode: false, // AssemblyAI has a colored logo
+		description: 'Transcription with chapters, entities and speakers',
+		models: ASSEMBLYAI_TRANSCRIPTION_MODELS,
+		defaultModel: ASSEMBLYAI_TRANSCRIPTION_MODELS[0],
+		modelSettingKey: 'transcription.assemblyai.model',
+		apiKeyField: 'apiKeys.assemblyai',
+		location: 'cloud',
+	},
+	{
+		id: 'Speechmatics',
+		name: 'Speechmatics',
+		icon: speechmaticsIcon,
+		invertInDarkMode: false, // Speechmatics has a colored logo
+		description: 'Accurate multilingual transcription with diarization',
+		models: SPEECHMATICS_TRANSCRIPTION_MODELS,
+		defaultModel: SPEECHMATICS_TRANSCRIPTION_MODELS[0],
+		modelSettingKey: 'transcription.speechmatics.model',
+		apiKeyField: 'apiKeys.speechmatics',
+		location: 'cloud',
+	},
 	// Self-hosted services
 	{
 		id: 'speaches',
@@ -186,4 +259,18 @@ export const TRANSCRIPTION_SERVICE_OPTIONS = TRANSCRIPTION_SERVICES.map(
 	}),
 );
 
+/**
+ * Services that may be offered to the user. Privacy mode hides cloud
+ * services, since using them would send audio off the machine.
+ */
+export function getAvailableTranscriptionServices({
+	privacyMode,
+}: {
+	privacyMode: boolean;
+}) {
+	return privacyMode
+		? TRANSCRIPTION_SERVICES.filter((service) => service.location !== 'cloud')
+		: TRANSCRIPTION_SERVICES;
+}
+
 export type TranscriptionService = (typeof TRANSCRIPTION_SERVICES)[number];
@@ -24,6 +24,7 @@ export function createSpeachesTranscriptionService({
 				outputLanguage: Settings['transcription.outputLanguage'];
 				modelId: string;
 				baseUrl: string;
+				apiKey?: string;
 			},
 		): Promise<Result<string, WhisperingError>> => {
 			const formData = new FormData();
@@ -45,9 +46,12 @@ export function createSpeachesTranscriptionService({
 
 			const { data: whisperApiResponse, error: postError } =
 				await HttpService.post({
-					url: `${options.baseUrl}/v1/audio/transcriptions`,
+					url: `${options.baseUrl.replace(/\/+$/, '').replace(/\/v1$/, '')}/v1/audio/transcriptions`,
 					body: formData,
-					headers: undefined, // No headers needed for Speaches
+					// Servers behind a reverse proxy often require a bearer token
+					headers: options.apiKey
+						? { Authorization: `Bearer ${options.apiKey}` }
+						: undefined,
 					schema: whisperApiResponseSchema,
 				});
 
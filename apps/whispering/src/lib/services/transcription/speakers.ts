@@ -0,0 +1,148 @@
+import type { Recording, TranscriptInsights } from '$lib/services/db';
+
+type Utterance = NonNullable<TranscriptInsights['utterances']>[number];
+
+export type SpeakerExportFormat = 'srt' | 'txt' | 'markdown';
+
+export type SpeakerStats = {
+	speaker: string;
+	/** Seconds the speaker was talking */
+	talkTime: number;
+	/** Fraction of all talk time, from 0 to 1 */
+	share: number;
+	turns: number;
+	words: number;
+};
+
+function formatTimestamp(seconds: number): string {
+	const minutes = Math.floor(seconds / 60);
+	const remainder = Math.floor(seconds % 60);
+	return `${minutes}:${remainder.toString().padStart(2, '0')}`;
+}
+
+function formatSrtTimestamp(seconds: number): string {
+	const totalMs = Math.max(0, Math.round(seconds * 1000));
+	const hours = Math.floor(totalMs / 3_600_000);
+	const minutes = Math.floor((totalMs % 3_600_000) / 60_000);
+	const secs = Math.floor((totalMs % 60_000) / 1000);
+	const ms = totalMs % 1000;
+	const pad = (value: number, length = 2) =>
+		value.toString().padStart(length, '0');
+	return `${pad(hours)}:${pad(minutes)}:${pad(secs)},${pad(ms, 3)}`;
+}
+
+/** Speakers in the order they first spoke */
+function speakersOf(utterances: Utterance[]): string[] {
+	return [...new Set(utterances.map((utterance) => utterance.speaker))];
+}
+
+/** A speaker label that's safe to use in a file name */
+function fileSafe(speaker: string): string {
+	return speaker.replace(/[^\p{L}\p{N}_-]+/gu, '_') || 'speaker';
+}
+
+/**
+ * Talk time, turns and word count per speaker of a diarized transcript,
+ * most talkative first.
+ */
+export function getSpeakerStats(utterances: Utterance[]): SpeakerStats[] {
+	const stats = new Map<string, SpeakerStats>();
+	for (const utterance of utterances) {
+		const entry = stats.get(utterance.speaker) ?? {
+			speaker: utterance.speaker,
+			talkTime: 0,
+			share: 0,
+			turns: 0,
+			words: 0,
+		};
+		entry.talkTime += Math.max(0, utterance.end - utterance.start);
+		entry.turns += 1;
+		entry.words += utterance.text.split(/\s+/).filter(Boolean).length;
+		stats.set(utterance.speaker, entry);
+	}
+	const total = [...stats.values()].reduce(
+		(sum, entry) => sum + entry.talkTime,
+		0,
+	);
+	return [...stats.values()]
+		.map((entry) => ({
+			...entry,
+			share: total > 0 ? entry.talkTime / total : 0,
+		}))
+		.toSorted((a, b) => b.talkTime - a.talkTime);
+}
+
+/** SubRip subtitles of one speaker's turns, keeping their original times */
+function formatSpeakerSrt(utterances: Utterance[]): string {
+	return utterances
+		.map(
+			(utterance, i) =>
+				`${i + 1}\n${formatSrtTimestamp(utterance.start)} --> ${formatSrtTimestamp(utterance.end)}\n${utterance.text.trim()}\n`,
+		)
+		.join('\n');
+}
+
+/**
+ * The whole conversation as Markdown, with a header whenever the speaker
+ * changes.
+ */
+function formatSpeakersMarkdown(
+	title: string,
+	utterances: Utterance[],
+): string {
+	const lines = [`# ${title}`];
+	let current: string | undefined;
+	for (const utterance of utterances) {
+		if (utterance.speaker !== current) {
+			current = utterance.speaker;
+			lines.push(
+				'',
+				`## ${utterance.speaker} (${formatTimestamp(utterance.start)})`,
+				'',
+			);
+		}
+		lines.push(utterance.text.trim());
+	}
+	return `${lines.join('\n')}\n`;
+}
+
+/**
+ * Files to export for a diarized recording: one SRT or text file per
+ * speaker, or a single Markdown file of the whole conversation.
+ *
+ * @returns No files if the recording has no speaker turns
+ */
+export function buildSpeakerExport(
+	recording: Recording,
+	format: SpeakerExportFormat,
+): { name: string; text: string }[] {
+	const utterances = (recording.insights?.utterances ?? []).toSorted(
+		(a, b) => a.start - b.start,
+	);
+	if (!utterances.length) return [];
+
+	const baseName = `whispering_recording_${recording.id}`;
+	if (format === 'markdown') {
+		return [
+			{
+				name: `${baseName}.md`,
+				text: formatSpeakersMarkdown(
+					recording.title || 'Recording',
+					utterances,
+				),
+			},
+		];
+	}
+	return speakersOf(utterances).map((speaker) => {
+		const turns = utterances.filter(
+			(utterance) => utterance.speaker === speaker,
+		);
+		return {
+			name: `${baseName}_${fileSafe(speaker)}.${format}`,
+			text:
+				format === 'srt'
+					? formatSpeakerSrt(turns)
+					: `${turns.map((utterance) => utterance.text.trim()).join('\n\n')}\n`,
+		};
+	});
+}
@@ -1,5 +1,12 @@
-import { Menu, MenuItem } from '@tauri-apps/api/menu';
-import { resolveResource } from '@tauri-apps/api/path';
+import { invoke } from '@tauri-apps/api/core';
+import {
+	CheckMenuItem,
+	Menu,
+	MenuItem,
+	PredefinedMenuItem,
+	Submenu,
+} from '@tauri-apps/api/menu';
+import { Image } from '@tauri-apps/api/image';
 import { TrayIcon } from '@tauri-apps/api/tray';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { exit } from '@tauri-apps/plugin-process';
@@ -9,9 +16,19 @@ import { type Err, Ok, tryAsync } from 'wellcrafted/result';
 import { goto } from '$app/navigation';
 // import { extension } from '@repo/extension';
 import type { WhisperingRecordingState } from '$lib/constants/audio';
+import { listen, type NativeSettings } from '$lib/events';
 
 const TRAY_ID = 'whispering-tray';
 
+/** The icon as Rust draws it in the current theme; see src-tauri/src/tray */
+type TrayInfo = {
+	rgba: number[];
+	width: number;
+	height: number;
+	template: boolean;
+	tooltip: string;
+};
+
 const { SetTrayIconServiceError, SetTrayIconServiceErr } = createTaggedError(
 	'SetTrayIconServiceError',
 );
@@ -46,9 +63,12 @@ export function createTrayIconDesktopService(): SetTrayIconService {
 		setTrayIcon: (recorderState: WhisperingRecordingState) =>
 			tryAsync({
 				try: async () => {
-					const iconPath = await getIconPath(recorderState);
-					const tray = await trayPromise;
-					return tray.setIcon(iconPath);
+					// Wait for the tray so the icon isn't drawn before it exists
+					await trayPromise;
+					// Rust draws the icon in the tray theme from the settings
+					await invoke('set_tray_icon', {
+						icon: recorderState === 'RECORDING' ? 'recording' : 'idle',
+					});
 				},
 				catch: (error) =>
 					SetTrayIconServiceErr({
@@ -61,46 +81,154 @@ export function createTrayIconDesktopService(): SetTrayIconService {
 }
 
 async function initTray() {
-	const existingTray = await TrayIcon.getById(TRAY_ID);
-	if (existingTray) return existingTray;
+	// On Linux desktops without libappindicator the backend registers its own
+	// StatusNotifierItem tray instead, so don't create a second one here
+	const backend = await invoke<'native' | 'statusNotifier' | 'unavailable'>(
+		'get_tray_backend',
+	);
+	if (backend !== 'native') {
+		await listen('tray://navigate', ({ payload }) => goto(payload));
+		return null;
+	}
+
+	// Labels are translated in Rust so both trays share them; see
+	// src-tauri/src/i18n. Items are relabelled when the language changes
+	type Labelled = { setText: (text: string) => Promise<void> };
+	let labels = await invoke<Record<string, string>>('get_tray_labels');
+	const labelled: [Labelled, string][] = [];
+	const label = <T extends Labelled>(item: T, id: string) => {
+		labelled.push([item, id]);
+		return item;
+	};
+	await listen('locale://changed', async () => {
+		labels = await invoke<Record<string, string>>('get_tray_labels');
+		for (const [item, id] of labelled) void item.setText(labels[id] ?? id);
+	});
+
+	// Settings toggles are stored natively so the StatusNotifierItem tray can
+	// share them; see syncNativeSettings for the link to the settings store
+	type ToggleKey =
+		| 'closeToTray'
+		| 'startMinimized'
+		| 'soundFeedback'
+		| 'autoPaste';
+	const nativeSettings = await invoke<NativeSettings>('get_native_settings');
+	const settingToggle = async (key: ToggleKey, labelId: string) => {
+		const item: CheckMenuItem = await CheckMenuItem.new({
+			id: key,
+			text: labels[labelId],
+			checked: nativeSettings[key],
+			action: async () => {
+				const current = await invoke<NativeSettings>('get_native_settings');
+				await invoke('set_native_settings', {
+					settings: { ...current, [key]: await item.isChecked() },
+				});
+			},
+		});
+		return label(item, labelId);
+	};
+	const toggles = [
+		await settingToggle('closeToTray', 'tray-close-to-tray'),
+		await settingToggle('startMinimized', 'tray-start-minimized'),
+		await settingToggle('soundFeedback', 'tray-sound-feedback'),
+		await settingToggle('autoPaste', 'tray-auto-paste'),
+	];
+	await listen('settings://changed', ({ payload }) => {
+		for (const toggle of toggles) {
+			void toggle.setChecked(payload[toggle.id as ToggleKey]);
+		}
+	});
+
+	// Recording presets; see src-tauri/src/presets
+	type PresetList = { presets: { name: string }[]; active: string | null };
+	const presetMenu = label(
+		await Submenu.new({ text: labels['tray-recording-preset'], items: [] }),
+		'tray-recording-preset',
+	);
+	const refreshPresets = async () => {
+		const list = await invoke<PresetList>('list_presets');
+		for (const item of await presetMenu.items()) await presetMenu.remove(item);
+		for (const preset of list.presets) {
+			await presetMenu.append(
+				await CheckMenuItem.new({
+					id: `preset:${preset.name}`,
+					text: preset.name,
+					checked: preset.name === list.active,
+					action: () => invoke('apply_preset', { name: preset.name }),
+				}),
+			);
+		}
+	};
+	await refreshPresets();
+	await listen('presets://applied', refreshPresets);
+	await listen('presets://changed', refreshPresets);
 
 	const trayMenu = await Menu.new({
 		items: [
 			// Window Controls Section
-			await MenuItem.new({
-				id: 'show',
-				text: 'Show Window',
-				action: () => getCurrentWindow().show(),
-			}),
+			label(
+				await MenuItem.new({
+					id: 'show',
+					text: labels['tray-show-window'],
+					action: () => getCurrentWindow().show(),
+				}),
+				'tray-show-window',
+			),
 
-			await MenuItem.new({
-				id: 'hide',
-				text: 'Hide Window',
-				action: () => getCurrentWindow().hide(),
-			}),
+			label(
+				await MenuItem.new({
+					id: 'hide',
+					text: labels['tray-hide-window'],
+					action: () => getCurrentWindow().hide(),
+				}),
+				'tray-hide-window',
+			),
 
 			// Settings Section
-			await MenuItem.new({
-				id: 'settings',
-				text: 'Settings',
-				action: () => {
-					goto('/settings');
-					return getCurrentWindow().show();
-				},
-			}),
+			label(
+				await MenuItem.new({
+					id: 'settings',
+					text: labels['tray-settings'],
+					action: () => {
+						goto('/settings');
+						return getCurrentWindow().show();
+					},
+				}),
+				'tray-settings',
+			),
+
+			// Quick Toggles Section
+			await PredefinedMenuItem.new({ item: 'Separator' }),
+			...toggles,
+			presetMenu,
+			await PredefinedMenuItem.new({ item: 'Separator' }),
 
 			// Quit Section
-			await MenuItem.new({
-				id: 'quit',
-				text: 'Quit',
-				action: () => void exit(0),
-			}),
+			label(
+				await MenuItem.new({
+					id: 'quit',
+					text: labels['tray-quit'],
+					action: () => void exit(0),
+				}),
+				'tray-quit',
+			),
 		],
 	});
 
+	// Rust creates a bare tray when startup skips the window (start minimized
+	// or daemon mode); take it over with the full menu
+	const existingTray = await TrayIcon.getById(TRAY_ID);
+	if (existingTray) {
+		await existingTray.setMenu(trayMenu);
+		return existingTray;
+	}
+
+	const info = await invoke<TrayInfo>('get_tray_info');
 	const tray = await TrayIcon.new({
 		id: TRAY_ID,
-		icon: await getIconPath('IDLE'),
+		icon: await Image.new(info.rgba, info.width, info.height),
+		iconAsTemplate: info.template,
+		tooltip: info.tooltip,
 		menu: trayMenu,
 		menuOnLeftClick: false,
 		action: (e) => {
@@ -119,14 +247,6 @@ async function initTray() {
 	return tray;
 }
 
-async function getIconPath(recorderState: WhisperingRecordingState) {
-	const iconPaths = {
-		IDLE: 'recorder-state-icons/studio_microphone.png',
-		RECORDING: 'recorder-state-icons/red_large_square.png',
-	} as const satisfies Record<WhisperingRecordingState, string>;
-	return await resolveResource(iconPaths[recorderState]);
-}
-
 export const TrayIconServiceLive = window.__TAURI_INTERNALS__
 	? createTrayIconDesktopService()
 	: createTrayIconWebService();
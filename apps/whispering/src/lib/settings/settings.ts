@@ -39,18 +39,26 @@ import {
 import { CommandOrAlt, CommandOrControl } from '$lib/constants/keyboard';
 import { SUPPORTED_LANGUAGES } from '$lib/constants/languages';
 import type { WhisperingSoundNames } from '$lib/constants/sounds';
-import { ALWAYS_ON_TOP_VALUES } from '$lib/constants/ui';
+import {
+	ALWAYS_ON_TOP_VALUES,
+	APP_LANGUAGE_VALUES,
+	TRAY_THEME_VALUES,
+} from '$lib/constants/ui';
 import {
 	FFMPEG_DEFAULT_COMPRESSION_OPTIONS,
 	FFMPEG_DEFAULT_GLOBAL_OPTIONS,
 	FFMPEG_DEFAULT_INPUT_OPTIONS,
 	FFMPEG_DEFAULT_OUTPUT_OPTIONS,
 } from '$lib/services/recorder/ffmpeg';
+import type { AssemblyAIModel } from '$lib/services/transcription/cloud/assemblyai';
+import type { AzureModel } from '$lib/services/transcription/cloud/azure';
 import type { DeepgramModel } from '$lib/services/transcription/cloud/deepgram';
 import type { ElevenLabsModel } from '$lib/services/transcription/cloud/elevenlabs';
+import type { GoogleModel } from '$lib/services/transcription/cloud/google';
 import type { GroqModel } from '$lib/services/transcription/cloud/groq';
 import type { MistralModel } from '$lib/services/transcription/cloud/mistral';
 import type { OpenAIModel } from '$lib/services/transcription/cloud/openai';
+import type { SpeechmaticsModel } from '$lib/services/transcription/cloud/speechmatics';
 import { TRANSCRIPTION_SERVICE_IDS } from '$lib/services/transcription/registry';
 import { asDeviceIdentifier } from '$lib/services/types';
 
@@ -94,6 +102,31 @@ export const settingsSchema = z.object({
 		`sound.playOn.${WhisperingSoundNames}`,
 		z.ZodDefault<ZodBoolean>
 	>),
+	// Master switch over the per-event toggles above, so muting (from the
+	// tray, say) keeps the user's choice of which events play a sound
+	'sound.enabled': z.boolean().default(true),
+	// Sound files played instead of the built-in start and stop sounds;
+	// empty for the built-in ones
+	'sound.customStart': z.string().default(''),
+	'sound.customStop': z.string().default(''),
+
+	// Reading transcripts aloud; a voice id from `list_voices`, null for the
+	// system default, and a multiple of normal speed
+	'tts.voice': z.string().nullable().default(null),
+	'tts.rate': z.number().min(0.25).max(4).default(1),
+
+	// Folder voice notes are also written to; null keeps them in recordings only
+	'voiceNotes.folder': z.string().nullable().default(null),
+
+	// File transcripts are appended to, with strftime fields such as
+	// `journal-%Y-%m.md` for rotation; null to not keep a journal
+	'journal.pathPattern': z.string().nullable().default(null),
+	// Commit the journal to git after each entry, with `{date}`, `{time}`,
+	// `{file}` and `{preview}` filled into the message
+	'journal.gitCommit': z.boolean().default(false),
+	'journal.commitMessage': z
+		.string()
+		.default('Dictation {date} {time}: {preview}'),
 
 	'transcription.copyToClipboardOnSuccess': z.boolean().default(true),
 	'transcription.writeToCursorOnSuccess': z.boolean().default(true),
@@ -101,6 +134,35 @@ export const settingsSchema = z.object({
 	'transformation.writeToCursorOnSuccess': z.boolean().default(false),
 
 	'system.alwaysOnTop': z.enum(ALWAYS_ON_TOP_VALUES).default('Never'),
+	'system.closeToTray': z.boolean().default(false),
+	'system.startMinimized': z.boolean().default(false),
+	// Block all network requests so audio never leaves the machine
+	'system.privacyMode': z.boolean().default(false),
+	// Offer to transcribe when a conferencing app starts a meeting
+	'system.detectMeetings': z.boolean().default(false),
+	// Meeting apps transcribed without asking / never asked about
+	'system.meetingAppsAllowed': z.array(z.string()).default([]),
+	'system.meetingAppsDenied': z.array(z.string()).default([]),
+	// Show where the time went after each transcription
+	'system.showLatencyHud': z.boolean().default(false),
+	// Have screen readers announce recording, transcription and pasting
+	'system.screenReaderAnnouncements': z.boolean().default(true),
+	// Tray icon set, and the folder of icons for the custom one
+	'system.trayTheme': z.enum(TRAY_THEME_VALUES).default('classic'),
+	'system.trayThemeFolder': z.string().default(''),
+	// PNGs replacing the tray icon for one state; empty for the theme's own
+	'system.customTrayIconIdle': z.string().default(''),
+	'system.customTrayIconRecording': z.string().default(''),
+	'system.customTrayIconProcessing': z.string().default(''),
+	// Language of the tray, notifications and error hints
+	'system.language': z.enum(APP_LANGUAGE_VALUES).default('system'),
+	// Zone and style for times shown, exported and put in filenames; times
+	// are stored as UTC. An empty zone is the system's
+	'system.timeZone': z.string().default(''),
+	'system.timestampStyle': z.enum(['iso', 'locale']).default('iso'),
+	// Names saved recordings, exports and watched-folder transcripts, e.g.
+	// '{{date}}_{{app}}_{{first_words}}'; empty for the built-in names
+	'system.fileNameTemplate': z.string().default(''),
 
 	'database.recordingRetentionStrategy': z
 		.enum(['keep-forever', 'limit-count'])
@@ -152,6 +214,16 @@ export const settingsSchema = z.object({
 	'recording.cpal.sampleRate': z
 		.enum(['16000', '44100', '48000'])
 		.default('16000'),
+	'recording.cpal.warmStart': z.boolean().default(true),
+	'recording.cpal.preRollMs': z
+		.enum(['0', '500', '1000', '1500', '2000'])
+		.default('0'),
+	'recording.cpal.backend': z.enum(['default', 'asio', 'jack']).default('default'),
+	'recording.cpal.avoidBluetoothMic': z.boolean().default(false),
+	// Free space recordings keep on their disk; they pause below it
+	'recording.cpal.minFreeDiskMb': z
+		.enum(['0', '100', '500', '1000', '5000'])
+		.default('500'),
 
 	// FFmpeg recording settings - split into three customizable parts
 	'recording.ffmpeg.globalOptions': z
@@ -171,6 +243,10 @@ export const settingsSchema = z.object({
 	'transcription.outputLanguage': z.enum(SUPPORTED_LANGUAGES).default('auto'),
 	'transcription.prompt': z.string().default(''),
 	'transcription.temperature': z.string().default('0.0'),
+	/** Seconds before a stuck transcription is cancelled; '0' waits forever */
+	'transcription.timeoutSeconds': z
+		.enum(['0', '60', '120', '300', '600'])
+		.default('120'),
 	// Audio compression settings
 	'transcription.compressionEnabled': z.boolean().default(false),
 	'transcription.compressionOptions': z
@@ -198,10 +274,40 @@ export const settingsSchema = z.object({
 		.string()
 		.transform((val) => val as (string & {}) | MistralModel['name'])
 		.default('voxtral-mini-latest' satisfies MistralModel['name']),
+	'transcription.azure.model': z
+		.string()
+		.transform((val) => val as (string & {}) | AzureModel['name'])
+		.default('fast' satisfies AzureModel['name']),
+	'transcription.azure.region': z.string().default('eastus'),
+	// Overrides the region, e.g. for private endpoints or sovereign clouds
+	'transcription.azure.endpoint': z.string().default(''),
+	'transcription.google.model': z
+		.string()
+		.transform((val) => val as (string & {}) | GoogleModel['name'])
+		.default('latest_long' satisfies GoogleModel['name']),
+	// Regional endpoints keep audio in a region, e.g. https://eu-speech.googleapis.com
+	'transcription.google.endpoint': z
+		.string()
+		.default('https://speech.googleapis.com'),
+	'transcription.assemblyai.model': z
+		.string()
+		.transform((val) => val as (string & {}) | AssemblyAIModel['name'])
+		.default('best' satisfies AssemblyAIModel['name']),
+	'transcription.assemblyai.autoChapters': z.boolean().default(false),
+	'transcription.assemblyai.entityDetection': z.boolean().default(false),
+	'transcription.assemblyai.speakerLabels': z.boolean().default(false),
+	'transcription.speechmatics.model': z
+		.string()
+		.transform((val) => val as (string & {}) | SpeechmaticsModel['name'])
+		.default('enhanced' satisfies SpeechmaticsModel['name']),
+	'transcription.speechmatics.autoChapters': z.boolean().default(false),
+	'transcription.speechmatics.entities': z.boolean().default(false),
+	'transcription.speechmatics.diarization': z.boolean().default(false),
 	'transcription.speaches.baseUrl': z.string().default('http://localhost:8000'),
 	'transcription.speaches.modelId': z
 		.string()
 		.default('Systran/faster-distil-whisper-small.en'),
+	'transcription.speaches.apiKey': z.string().default(''),
 	'transcription.whispercpp.modelPath': z.string().default(''),
 	'transcription.parakeet.modelPath': z.string().default(''),
 
@@ -222,8 +328,22 @@ export const settingsSchema = z.object({
 	'apiKeys.deepgram': z.string().default(''),
 	'apiKeys.elevenlabs': z.string().default(''),
 	'apiKeys.mistral': z.string().default(''),
+	'apiKeys.azureSpeech': z.string().default(''),
+	// A Google Cloud API key or the JSON key file of a service account
+	'apiKeys.googleSpeech': z.string().default(''),
+	'apiKeys.assemblyai': z.string().default(''),
+	'apiKeys.speechmatics': z.string().default(''),
 	'apiKeys.openrouter': z.string().default(''),
 
+	// Inbox for recordings pushed by paired phones; desktop builds with the
+	// HTTP API only
+	'inbox.enabled': z.boolean().default(false),
+	'inbox.port': z.number().int().min(1024).max(65535).default(47821),
+	// Advertise the inbox over mDNS so phones find it without the address
+	'inbox.discoverable': z.boolean().default(true),
+	// Transcribe uploads with the selected local model as they arrive
+	'inbox.transcribe': z.boolean().default(true),
+
 	// Analytics settings
 	'analytics.enabled': z.boolean().default(true),
 
@@ -236,6 +356,15 @@ export const settingsSchema = z.object({
 		'shortcuts.local.startVadRecording': z.string().nullable().default(null),
 		'shortcuts.local.stopVadRecording': z.string().nullable().default(null),
 		'shortcuts.local.pushToTalk': z.string().nullable().default('p'),
+		'shortcuts.local.transcribeClipboardFile': z
+			.string()
+			.nullable()
+			.default(null),
+		'shortcuts.local.readBackLastTranscript': z
+			.string()
+			.nullable()
+			.default(null),
+		'shortcuts.local.toggleVoiceNote': z.string().nullable().default(null),
 	} satisfies Record<
 		`shortcuts.local.${Command['id']}`,
 		z.ZodDefault<z.ZodNullable<ZodString>>
@@ -262,6 +391,15 @@ export const settingsSchema = z.object({
 			.string()
 			.nullable()
 			.default(`${CommandOrAlt}+Shift+D`),
+		'shortcuts.global.transcribeClipboardFile': z
+			.string()
+			.nullable()
+			.default(null),
+		'shortcuts.global.readBackLastTranscript': z
+			.string()
+			.nullable()
+			.default(null),
+		'shortcuts.global.toggleVoiceNote': z.string().nullable().default(null),
 	} satisfies Record<
 		`shortcuts.global.${Command['id']}`,
 		z.ZodDefault<z.ZodNullable<ZodString>>
@@ -0,0 +1,17 @@
+import type { InboxStatus } from '$lib/utils/inbox';
+
+let status = $state<InboxStatus | null>(null);
+
+/**
+ * What the inbox server reported when it last started or stopped. The
+ * layout keeps the server in step with the settings (see
+ * syncInboxServer.svelte.ts), and the settings page shows this.
+ */
+export const inbox = {
+	get status() {
+		return status;
+	},
+	set status(value: InboxStatus | null) {
+		status = value;
+	},
+};
@@ -0,0 +1,28 @@
+/**
+ * The error Rust commands fail with (src-tauri/src/error.rs). `code` is
+ * stable to match on; `recoveryHint` tells the user what to try, in the
+ * language of the tray and notifications.
+ */
+export type AppError = {
+	name: string;
+	code: string;
+	message: string;
+	recoveryHint: string | null;
+};
+
+export function asAppError(error: unknown): AppError | undefined {
+	if (typeof error !== 'object' || error === null) return undefined;
+	if (!('code' in error) || !('message' in error)) return undefined;
+	return error as AppError;
+}
+
+/**
+ * What went wrong in Rust and how to recover, for a toast, or `fallback`
+ * when the command didn't fail with an `AppError`
+ */
+export function describeAppError(error: unknown, fallback: string) {
+	const appError = asAppError(error);
+	if (!appError) return fallback;
+	if (!appError.recoveryHint) return appError.message;
+	return `${appError.message} ${appError.recoveryHint}`;
+}
@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { Err, Ok, tryAsync } from 'wellcrafted/result';
+import type { AppStatus } from '$lib/events';
+
+const { AppStateError, AppStateErr } = createTaggedError('AppStateError');
+export type AppStateError = ReturnType<typeof AppStateError>;
+
+/** Something that happened in the pipeline; see src-tauri/src/app_state.rs */
+export type StateEvent =
+	| { type: 'recordingStarted'; recordingId: string | null }
+	| { type: 'recordingStopped' }
+	| { type: 'recordingCancelled' }
+	| { type: 'injectionStarted' }
+	| { type: 'completed' }
+	| { type: 'failed'; message: string }
+	| { type: 'reset' };
+
+export function reportAppState(event: StateEvent) {
+	return tryAsync({
+		try: () => invoke<AppStatus>('report_app_state', { event }),
+		catch: (error) =>
+			AppStateErr({
+				message: extractErrorMessage(error),
+				context: { event },
+				cause: error,
+			}),
+	});
+}
+
+/**
+ * End the processing stage a stopped recording is in, once its transcript
+ * is delivered or has failed. Typing the text ends it in Rust already, so
+ * this only reports what happened when nothing was typed: the transcript
+ * only went to the clipboard, or transcription failed.
+ */
+export async function settleAppState(
+	outcome: { type: 'completed' } | { type: 'failed'; message: string },
+) {
+	const { data: status, error: getStatusError } = await tryAsync({
+		try: () => invoke<AppStatus>('get_app_state'),
+		catch: (error) =>
+			AppStateErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+	if (getStatusError) return Err(getStatusError);
+	if (status.status !== 'processing') return Ok(status);
+	return reportAppState(outcome);
+}
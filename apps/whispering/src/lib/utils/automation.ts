@@ -0,0 +1,70 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+import type { TransformContext } from './transforms';
+
+const { AutomationError, AutomationErr } =
+	createTaggedError('AutomationError');
+export type AutomationError = ReturnType<typeof AutomationError>;
+
+/**
+ * A Rhai script run on each new transcript; see src-tauri/src/automation
+ * for what scripts can see and do.
+ */
+export type AutomationScript = {
+	id: string;
+	name: string;
+	source: string;
+	enabled: boolean;
+};
+
+export type AutomationConfig = {
+	scripts: AutomationScript[];
+};
+
+/** What one run of a script did */
+export type AutomationRun = {
+	output: string;
+	/** Destination plugins the script asked for */
+	triggered: string[];
+	logs: string[];
+};
+
+function invokeAutomation<T>(command: string, args?: Record<string, unknown>) {
+	return tryAsync({
+		try: () => invoke<T>(command, args),
+		catch: (error) =>
+			AutomationErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+export function getAutomationConfig() {
+	return invokeAutomation<AutomationConfig>('get_automation_config');
+}
+
+/** Save the scripts; fails if any doesn't compile */
+export function setAutomationConfig(config: AutomationConfig) {
+	return invokeAutomation<AutomationConfig>('set_automation_config', {
+		config,
+	});
+}
+
+/** Run `source` on `text` without delivering to the destinations it triggers */
+export function testAutomationScript({
+	source,
+	text,
+	context,
+}: {
+	source: string;
+	text: string;
+	context?: TransformContext;
+}) {
+	return invokeAutomation<AutomationRun>('test_automation_script', {
+		source,
+		text,
+		context: context ?? null,
+	});
+}
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Optional subsystems compiled into the desktop app; see
+ * src-tauri/src/build_features.rs. Minimal and distro builds may leave some
+ * out, so anything depending on them should check first.
+ */
+export type BuildFeatures = {
+	localInference: boolean;
+	httpApi: boolean;
+	integrations: boolean;
+	overlay: boolean;
+	plugins: boolean;
+};
+
+/** What full builds include, assumed when the backend can't be asked */
+const FULL_BUILD: BuildFeatures = {
+	localInference: true,
+	httpApi: true,
+	integrations: true,
+	overlay: true,
+	plugins: true,
+};
+
+let features: Promise<BuildFeatures> | undefined;
+
+/** The features of the running build, fixed at compile time so asked once */
+export function getBuildFeatures(): Promise<BuildFeatures> {
+	features ??= invoke<BuildFeatures>('get_build_features').catch((error) => {
+		console.warn('Failed to read build features:', error);
+		return FULL_BUILD;
+	});
+	return features;
+}
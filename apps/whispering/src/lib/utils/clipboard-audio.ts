@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { ClipboardAudioError, ClipboardAudioErr } = createTaggedError(
+	'ClipboardAudioError',
+);
+export type ClipboardAudioError = ReturnType<typeof ClipboardAudioError>;
+
+/**
+ * Path of the audio file on the clipboard, whether copied in a file manager
+ * or pasted as a path. Desktop only; see src-tauri/src/clipboard_audio.
+ */
+export function getClipboardAudioPath() {
+	return tryAsync({
+		try: () => invoke<string>('get_clipboard_audio_path'),
+		catch: (error) =>
+			ClipboardAudioErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
@@ -0,0 +1,44 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { CustomAssetError, CustomAssetErr } =
+	createTaggedError('CustomAssetError');
+export type CustomAssetError = ReturnType<typeof CustomAssetError>;
+
+/** Which built-in sound a custom one replaces; see src-tauri/src/custom_assets */
+export type SoundSlot = 'start' | 'stop';
+
+/** How checking one custom icon or sound file went */
+export type AssetCheck = {
+	/** The native setting holding the path, e.g. `customStartSound` */
+	setting: string;
+	path: string;
+	/** Why the built-in asset is used instead, if it is */
+	error: string | null;
+};
+
+/** Check the custom icons and sounds set in the settings */
+export function checkCustomAssets() {
+	return tryAsync({
+		try: () => invoke<AssetCheck[]>('check_custom_assets'),
+		catch: (error) =>
+			CustomAssetErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+/** The custom sound for `sound`, once it passes the checks */
+export function loadCustomSound(sound: SoundSlot) {
+	return tryAsync({
+		try: async () =>
+			new Blob([await invoke<ArrayBuffer>('load_custom_sound', { sound })]),
+		catch: (error) =>
+			CustomAssetErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
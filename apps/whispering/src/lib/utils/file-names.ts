@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { FileNameError, FileNameErr } = createTaggedError('FileNameError');
+export type FileNameError = ReturnType<typeof FileNameError>;
+
+/** Placeholders a file name template can use; see src-tauri/src/file_names */
+export const FILE_NAME_PLACEHOLDERS = [
+	'date',
+	'time',
+	'datetime',
+	'app',
+	'first_words',
+	'id',
+	'name',
+] as const;
+
+/**
+ * The name `template` gives a sample recording, or null for an empty
+ * template; an error for unknown placeholders
+ */
+export function previewFileName(template: string) {
+	return tryAsync({
+		try: () => invoke<string | null>('preview_file_name', { template }),
+		catch: (error) =>
+			FileNameErr({
+				message: extractErrorMessage(error),
+				context: { template },
+				cause: error,
+			}),
+	});
+}
+
+/**
+ * Rename the recording the desktop recorder last saved after its
+ * transcript, as the file name template says. Returns its new path.
+ */
+export function nameSavedRecording(text: string) {
+	return tryAsync({
+		try: () => invoke<string | null>('name_saved_recording', { text }),
+		catch: (error) =>
+			FileNameErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
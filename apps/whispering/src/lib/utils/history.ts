@@ -0,0 +1,50 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+import type { Recording } from '$lib/services/db';
+import { settings } from '$lib/stores/settings.svelte';
+
+const { HistoryError, HistoryErr } = createTaggedError('HistoryError');
+export type HistoryError = ReturnType<typeof HistoryError>;
+
+/**
+ * Mirror a transcribed recording into the desktop history database
+ * (src-tauri/src/history), which search, tags, bulk actions and stats read.
+ * Saving again updates it, e.g. once the audio file's final path is known.
+ * The first save, as transcription finishes, records the service used and
+ * the app being dictated into.
+ */
+export function saveToHistory(
+	recording: Pick<Recording, 'id' | 'createdAt' | 'transcribedText'>,
+	{ audioPath }: { audioPath?: string } = {},
+) {
+	return tryAsync({
+		try: () =>
+			invoke<unknown>('save_recording_to_history', {
+				recording: {
+					id: recording.id,
+					createdAt: Date.parse(recording.createdAt),
+					transcript: recording.transcribedText,
+					audioPath: audioPath ?? null,
+					provider:
+						settings.value['transcription.selectedTranscriptionService'],
+				},
+			}),
+		catch: (error) =>
+			HistoryErr({
+				message: extractErrorMessage(error),
+				context: { id: recording.id },
+				cause: error,
+			}),
+	});
+}
+
+/**
+ * Remove deleted recordings from the desktop history database. Best effort:
+ * recordings from before history was mirrored aren't there.
+ */
+export async function deleteFromHistory(ids: string[]) {
+	await Promise.allSettled(
+		ids.map((id) => invoke('delete_history_entry', { id })),
+	);
+}
@@ -0,0 +1,112 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+import type { PairedDevice } from '$lib/events';
+import type { Settings } from '$lib/settings';
+
+const { InboxError, InboxErr } = createTaggedError('InboxError');
+export type InboxError = ReturnType<typeof InboxError>;
+
+export type InboxStatus = {
+	running: boolean;
+	port: number | null;
+	address: string | null;
+	fingerprint: string | null;
+	discoverable: boolean;
+};
+
+export type PairingInfo = {
+	uri: string;
+	code: string;
+	expiresAt: number;
+	/** QR code of `uri` rendered as an SVG document */
+	qrSvg: string;
+};
+
+type InboxTranscription = {
+	engine: 'whisper' | 'parakeet';
+	modelPath: string;
+	language: string | null;
+};
+
+/** Must match `InboxConfig` in src-tauri/src/inbox/mod.rs */
+export type InboxConfig = {
+	port: number;
+	discoverable: boolean;
+	transcription: InboxTranscription | null;
+};
+
+/**
+ * The local model uploads are transcribed with, or null when they should
+ * wait in the inbox: Rust only transcribes locally, so a cloud provider or a
+ * missing model leaves them for the user.
+ */
+function toInboxTranscription(value: Settings): InboxTranscription | null {
+	if (!value['inbox.transcribe']) return null;
+	const language =
+		value['transcription.outputLanguage'] === 'auto'
+			? null
+			: value['transcription.outputLanguage'];
+	switch (value['transcription.selectedTranscriptionService']) {
+		case 'whispercpp': {
+			const modelPath = value['transcription.whispercpp.modelPath'];
+			return modelPath ? { engine: 'whisper', modelPath, language } : null;
+		}
+		case 'parakeet': {
+			const modelPath = value['transcription.parakeet.modelPath'];
+			return modelPath ? { engine: 'parakeet', modelPath, language } : null;
+		}
+		default:
+			return null;
+	}
+}
+
+function invokeInbox<T>(command: string, args?: Record<string, unknown>) {
+	return tryAsync({
+		try: () => invoke<T>(command, args),
+		catch: (error) =>
+			InboxErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+/** The server configuration the inbox and transcription settings ask for */
+export function toInboxConfig(value: Settings): InboxConfig {
+	return {
+		port: value['inbox.port'],
+		discoverable: value['inbox.discoverable'],
+		transcription: toInboxTranscription(value),
+	};
+}
+
+/**
+ * (Re)start the inbox server. Desktop builds with the HTTP API only; see
+ * src-tauri/src/inbox.
+ */
+export function startInboxServer(config: InboxConfig) {
+	return invokeInbox<InboxStatus>('start_inbox_server', { config });
+}
+
+export function stopInboxServer() {
+	return invokeInbox<void>('stop_inbox_server');
+}
+
+export function getInboxStatus() {
+	return invokeInbox<InboxStatus>('get_inbox_status');
+}
+
+/** Open a pairing window; the phone scans the QR code before it expires */
+export function startInboxPairing() {
+	return invokeInbox<PairingInfo>('start_inbox_pairing');
+}
+
+export function listPairedDevices() {
+	return invokeInbox<PairedDevice[]>('list_paired_devices');
+}
+
+/** Forget a phone; it has to pair again to upload */
+export function revokePairedDevice(deviceId: string) {
+	return invokeInbox<void>('revoke_paired_device', { deviceId });
+}
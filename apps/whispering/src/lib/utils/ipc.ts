@@ -0,0 +1,38 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { IpcError, IpcErr } = createTaggedError('IpcError');
+export type IpcError = ReturnType<typeof IpcError>;
+
+/**
+ * Hand a finished transcript to helper processes: `whispering
+ * last-transcript` and subscribers such as the browser extension; see
+ * src-tauri/src/ipc
+ */
+export function publishTranscript(text: string) {
+	return tryAsync({
+		try: () => invoke<void>('publish_transcript', { text }),
+		catch: (error) =>
+			IpcErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+/**
+ * Tell the helper that sent a relayed recording command how it went; see
+ * `handleIpcCommands`
+ */
+export function finishIpcCommand(id: number, error?: string) {
+	return tryAsync({
+		try: () => invoke<void>('finish_ipc_command', { id, error: error ?? null }),
+		catch: (error) =>
+			IpcErr({
+				message: extractErrorMessage(error),
+				context: { id },
+				cause: error,
+			}),
+	});
+}
@@ -0,0 +1,53 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { JournalError, JournalErr } = createTaggedError('JournalError');
+export type JournalError = ReturnType<typeof JournalError>;
+
+/**
+ * Append `text` under a timestamp heading to the file `pathPattern` names
+ * right now; strftime fields like `journal-%Y-%m.md` start a new file each
+ * month. Desktop only; see src-tauri/src/integrations/journal.
+ */
+export function appendToJournal({
+	pathPattern,
+	text,
+}: {
+	pathPattern: string;
+	text: string;
+}) {
+	return tryAsync({
+		try: () => invoke<string>('append_to_journal', { pathPattern, text }),
+		catch: (error) =>
+			JournalErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+/**
+ * Commit the journal file at `path` to the git repository it's in, creating
+ * one in its folder if needed. `messageTemplate` may use `{date}`, `{time}`,
+ * `{file}` and `{preview}`.
+ */
+export function commitJournal({
+	path,
+	text,
+	messageTemplate,
+}: {
+	path: string;
+	text: string;
+	messageTemplate: string;
+}) {
+	return tryAsync({
+		try: () =>
+			invoke<string>('commit_journal', { path, text, messageTemplate }),
+		catch: (error) =>
+			JournalErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Pipeline stages, in order; see `LatencyStage` in src-tauri/src/history */
+export type LatencyStage =
+	| 'captureStop'
+	| 'encode'
+	| 'upload'
+	| 'provider'
+	| 'postProcess'
+	| 'inject';
+
+export type StageTiming = { stage: LatencyStage; ms: number };
+
+/** Payload of `latency://recorded` and result of `get_latency_breakdown` */
+export type LatencyBreakdown = {
+	entryId: string;
+	stages: StageTiming[];
+	totalMs: number;
+	slowest: LatencyStage | null;
+};
+
+/** Time an async stage, adding its duration to `timings` */
+export async function timeStage<T>(
+	timings: StageTiming[],
+	stage: LatencyStage,
+	run: () => Promise<T>,
+): Promise<T> {
+	const start = performance.now();
+	try {
+		return await run();
+	} finally {
+		timings.push({ stage, ms: Math.round(performance.now() - start) });
+	}
+}
+
+/**
+ * Store stage timings for a recording so `get_latency_breakdown` and the
+ * latency HUD can show them. Best effort: outside the desktop app, or if
+ * storing fails, the timings are dropped.
+ */
+export function reportLatency(recordingId: string, timings: StageTiming[]) {
+	if (!window.__TAURI_INTERNALS__ || timings.length === 0) return;
+	invoke('record_latency', { id: recordingId, stages: timings }).catch(
+		(error) => console.warn('Failed to record latency:', error),
+	);
+}
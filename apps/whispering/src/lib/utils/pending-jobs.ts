@@ -0,0 +1,52 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { PendingJobError, PendingJobErr } = createTaggedError('PendingJobError');
+export type PendingJobError = ReturnType<typeof PendingJobError>;
+
+/** An audio file queued for transcription; see src-tauri/src/jobs/pending.rs */
+export type PendingJob = {
+	path: string;
+	source: 'recording' | 'daemon' | 'file';
+	/** Unix milliseconds */
+	queuedAt: number;
+	/** Launches that have tried to resume it */
+	resumes: number;
+};
+
+/** Transcriptions a previous run didn't finish */
+export type InterruptedJobs = {
+	/** To transcribe again now */
+	resume: PendingJob[];
+	/** Resumed a few times without finishing, so only on request */
+	retry: PendingJob[];
+};
+
+/** Transcriptions interrupted before this launch; empty after the first call */
+export function takeInterruptedJobs() {
+	return tryAsync({
+		try: () => invoke<InterruptedJobs>('take_interrupted_jobs'),
+		catch: (error) =>
+			PendingJobErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+/**
+ * Take a file off the transcription queue once its transcript is saved or
+ * has failed; without a path, the recording the desktop recorder saved last
+ */
+export function finishPendingJob(path?: string) {
+	return tryAsync({
+		try: () => invoke<boolean>('finish_pending_job', { path: path ?? null }),
+		catch: (error) =>
+			PendingJobErr({
+				message: extractErrorMessage(error),
+				context: { path },
+				cause: error,
+			}),
+	});
+}
@@ -0,0 +1,67 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+
+const { PluginError, PluginErr } = createTaggedError('PluginError');
+export type PluginError = ReturnType<typeof PluginError>;
+
+/** An installed WebAssembly plugin; see src-tauri/src/plugins */
+export type PluginInfo = {
+	id: string;
+	name: string;
+	version: string;
+	description: string;
+	capabilities: { http: string[] };
+	kinds: ('transform' | 'destination')[];
+	enabled: boolean;
+	/** Why the plugin can't run, if it can't */
+	error: string | null;
+};
+
+/** How handing a transcript to one destination plugin went */
+export type PluginDelivery = {
+	id: string;
+	error: string | null;
+};
+
+function invokePlugins<T>(command: string, args?: Record<string, unknown>) {
+	return tryAsync({
+		try: () => invoke<T>(command, args),
+		catch: (error) =>
+			PluginErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+export function listPlugins() {
+	return invokePlugins<PluginInfo[]>('list_plugins');
+}
+
+/** Install the plugin in the folder `path`, or whose plugin.toml it is */
+export function installPlugin(path: string) {
+	return invokePlugins<PluginInfo>('install_plugin', { path });
+}
+
+export function enablePlugin(id: string) {
+	return invokePlugins<PluginInfo>('enable_plugin', { id });
+}
+
+export function disablePlugin(id: string) {
+	return invokePlugins<PluginInfo>('disable_plugin', { id });
+}
+
+/** Hand a delivered transcript to every enabled destination plugin */
+export function deliverToPlugins({
+	text,
+	entryId,
+}: {
+	text: string;
+	entryId?: string;
+}) {
+	return invokePlugins<PluginDelivery[]>('deliver_to_plugins', {
+		text,
+		entryId: entryId ?? null,
+	});
+}
@@ -0,0 +1,79 @@
+import { invoke } from '@tauri-apps/api/core';
+import { createTaggedError, extractErrorMessage } from 'wellcrafted/error';
+import { tryAsync } from 'wellcrafted/result';
+import type { TransformContext } from './transforms';
+
+const { ScriptHookError, ScriptHookErr } =
+	createTaggedError('ScriptHookError');
+export type ScriptHookError = ReturnType<typeof ScriptHookError>;
+
+/**
+ * A program run on each new transcript; see src-tauri/src/scripts. In
+ * `replace` mode its stdout becomes the transcript, in `observe` mode it's
+ * ignored.
+ */
+export type ScriptHook = {
+	id: string;
+	name: string;
+	program: string;
+	args: string[];
+	mode: 'replace' | 'observe';
+	timeoutSecs: number;
+	sandbox: {
+		clearEnv: boolean;
+		workingDir: string | null;
+		maxOutputBytes: number;
+	};
+	enabled: boolean;
+};
+
+export type ScriptConfig = {
+	hooks: ScriptHook[];
+};
+
+/** What one run of a hook produced */
+export type ScriptRun = {
+	stdout: string;
+	stderr: string;
+	code: number | null;
+	timedOut: boolean;
+	truncated: boolean;
+	durationMs: number;
+};
+
+function invokeScripts<T>(command: string, args?: Record<string, unknown>) {
+	return tryAsync({
+		try: () => invoke<T>(command, args),
+		catch: (error) =>
+			ScriptHookErr({
+				message: extractErrorMessage(error),
+				cause: error,
+			}),
+	});
+}
+
+export function getScriptConfig() {
+	return invokeScripts<ScriptConfig>('get_script_config');
+}
+
+/** Save the hooks; new ones come back with ids */
+export function setScriptConfig(config: ScriptConfig) {
+	return invokeScripts<ScriptConfig>('set_script_config', { config });
+}
+
+/** Run `hook` on `text` to see its output before saving or enabling it */
+export function testScriptHook({
+	hook,
+	text,
+	context,
+}: {
+	hook: ScriptHook;
+	text: string;
+	context?: TransformContext;
+}) {
+	return invokeScripts<ScriptRun>('test_script_hook', {
+		hook,
+		text,
+		context: context ?? null,
+	});
+}
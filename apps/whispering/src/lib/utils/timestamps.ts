@@ -0,0 +1,88 @@
+import type { Settings } from '$lib/settings';
+
+/**
+ * Times as people read them, in the zone and style from the settings; see
+ * src-tauri/src/timestamps for the same rules on the Rust side. Stored times
+ * stay UTC, only how they're shown changes.
+ */
+export type TimestampOptions = {
+	/** IANA name like 'Europe/Berlin', 'UTC', or '' for the system's zone */
+	timeZone: string;
+	style: Settings['system.timestampStyle'];
+};
+
+export function timestampOptions(value: Settings): TimestampOptions {
+	return {
+		timeZone: value['system.timeZone'],
+		style: value['system.timestampStyle'],
+	};
+}
+
+/** Whether `timeZone` is empty or a zone this system knows */
+export function isValidTimeZone(timeZone: string) {
+	return resolveZone(timeZone) !== undefined || !timeZone.trim();
+}
+
+/** The zone for Intl, or undefined for the system's */
+function resolveZone(timeZone: string) {
+	const zone = timeZone.trim();
+	if (!zone) return undefined;
+	try {
+		new Intl.DateTimeFormat('en-US', { timeZone: zone });
+		return zone;
+	} catch {
+		return undefined;
+	}
+}
+
+/** Zero-padded year-to-second fields of `date` in `timeZone` */
+function fieldsIn(date: Date, timeZone: string) {
+	const parts = new Intl.DateTimeFormat('en-US', {
+		timeZone: resolveZone(timeZone),
+		year: 'numeric',
+		month: '2-digit',
+		day: '2-digit',
+		hour: '2-digit',
+		minute: '2-digit',
+		second: '2-digit',
+		hourCycle: 'h23',
+	}).formatToParts(date);
+	const field = (type: Intl.DateTimeFormatPartTypes) =>
+		parts.find((part) => part.type === type)?.value ?? '00';
+	return {
+		year: field('year'),
+		month: field('month'),
+		day: field('day'),
+		hour: field('hour'),
+		minute: field('minute'),
+		second: field('second'),
+	};
+}
+
+/**
+ * `2024-05-01 14:30` in the ISO style, or the system locale's own way of
+ * writing dates (`May 1, 2024, 2:30 PM`, `01.05.2024, 14:30`)
+ */
+export function formatTimestamp(
+	date: Date,
+	{ timeZone, style }: TimestampOptions,
+) {
+	if (style === 'locale') {
+		return new Intl.DateTimeFormat(undefined, {
+			timeZone: resolveZone(timeZone),
+			dateStyle: 'medium',
+			timeStyle: 'short',
+		}).format(date);
+	}
+	const { year, month, day, hour, minute } = fieldsIn(date, timeZone);
+	return `${year}-${month}-${day} ${hour}:${minute}`;
+}
+
+/**
+ * `2024-05-01 14-30-05`, whatever the style, so files sort by date and the
+ * month is never taken for the day
+ */
+export function filenameTimestamp(date: Date, timeZone: string) {
+	const { year, month, day, hour, minute, second } = fieldsIn(date, timeZone);
+	return `${year}-${month}-${day} ${hour}-${minute}-${second}`;
+}
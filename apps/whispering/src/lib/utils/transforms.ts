@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Mirrors `TransformContext` in src-tauri/src/transforms */
+export type TransformContext = {
+	language?: string | null;
+	profile?: string | null;
+};
+
+/** Result of `preview_filler_removal` */
+export type FillerPreview = {
+	result: string;
+	segments: { text: string; removed: boolean }[];
+};
+
+/**
+ * Run a new transcript through the built-in transforms (filler removal and
+ * so on). Outside the desktop app, or if they fail, the text is returned
+ * unchanged.
+ */
+export async function applyTransforms(
+	text: string,
+	context: TransformContext,
+): Promise<string> {
+	if (!window.__TAURI_INTERNALS__) return text;
+	try {
+		return await invoke<string>('apply_transforms', { text, context });
+	} catch (error) {
+		console.warn('Failed to apply built-in transforms:', error);
+		return text;
+	}
+}
+
+/** Show what filler removal would drop from `text` without enabling it */
+export function previewFillerRemoval(
+	text: string,
+	context: TransformContext,
+): Promise<FillerPreview> {
+	return invoke<FillerPreview>('preview_filler_removal', { text, context });
+}
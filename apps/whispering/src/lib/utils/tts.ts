@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/core';
+import { settings } from '$lib/stores/settings.svelte';
+
+/** Mirrors `VoiceInfo` in src-tauri/src/tts */
+export type Voice = {
+	id: string;
+	name: string;
+	language: string;
+};
+
+/** Voices installed on the system; empty where the engine can't switch voices */
+export function listVoices(): Promise<Voice[]> {
+	return invoke<Voice[]>('list_voices');
+}
+
+/** Read `text` aloud with the voice and rate from settings (desktop only) */
+export function speak(text: string): Promise<void> {
+	return invoke('speak', {
+		text,
+		voice: settings.value['tts.voice'],
+		rate: settings.value['tts.rate'],
+	});
+}
@@ -0,0 +1,44 @@
+import { join } from '@tauri-apps/api/path';
+import { writeFile } from '@tauri-apps/plugin-fs';
+import { tryAsync } from 'wellcrafted/result';
+import { createTaggedError } from 'wellcrafted/error';
+import { getExtensionFromAudioBlob } from '$lib/services/_utils';
+import { filenameTimestamp } from '$lib/utils/timestamps';
+
+const { VoiceNoteError, VoiceNoteErr } = createTaggedError('VoiceNoteError');
+export type VoiceNoteError = ReturnType<typeof VoiceNoteError>;
+
+/**
+ * Write a voice note into `folder` as `Voice note 2024-05-01 14-30-05.webm`,
+ * named by when it was recorded in `timeZone` ('' for the system's). Returns
+ * the file's path.
+ */
+export function saveVoiceNoteFile({
+	folder,
+	blob,
+	recordedAt,
+	timeZone,
+}: {
+	folder: string;
+	blob: Blob;
+	recordedAt: Date;
+	timeZone: string;
+}) {
+	return tryAsync({
+		try: async () => {
+			const stamp = filenameTimestamp(recordedAt, timeZone);
+			const path = await join(
+				folder,
+				`Voice note ${stamp}.${getExtensionFromAudioBlob(blob)}`,
+			);
+			await writeFile(path, new Uint8Array(await blob.arrayBuffer()));
+			return path;
+		},
+		catch: (error) =>
+			VoiceNoteErr({
+				message: `Couldn't save the voice note to ${folder}`,
+				context: { folder },
+				cause: error,
+			}),
+	});
+}
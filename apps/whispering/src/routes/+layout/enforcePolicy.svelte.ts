@@ -0,0 +1,49 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Settings } from '$lib/settings';
+import { settings } from '$lib/stores/settings.svelte';
+import { TRANSCRIPTION_SERVICES } from '$lib/services/transcription/registry';
+
+/**
+ * Admin policy loaded by the Rust side from the system policy file. Must
+ * match `Policy` in src-tauri/src/settings/policy.rs.
+ */
+export type Policy = {
+	source: string | null;
+	localOnly: boolean;
+	disableHistory: boolean;
+	settings: Record<string, unknown>;
+};
+
+/**
+ * Keeps settings managed by the admin policy at their forced values, and
+ * switches away from cloud transcription when the policy is local-only.
+ */
+export function enforcePolicy() {
+	let policy = $state<Policy | null>(null);
+
+	invoke<Policy>('get_policy')
+		.then((value) => {
+			policy = value;
+		})
+		.catch((error) => console.error('Failed to load policy:', error));
+
+	$effect(() => {
+		if (!policy) return;
+		const updates: Partial<Settings> = {};
+		for (const [key, value] of Object.entries(policy.settings)) {
+			const current = settings.value[key as keyof Settings];
+			if (key in settings.value && current !== value)
+				(updates as Record<string, unknown>)[key] = value;
+		}
+		if (policy.localOnly) {
+			const selectedId =
+				settings.value['transcription.selectedTranscriptionService'];
+			const selected = TRANSCRIPTION_SERVICES.find(
+				(service) => service.id === selectedId,
+			);
+			if (selected?.location === 'cloud')
+				updates['transcription.selectedTranscriptionService'] = 'whispercpp';
+		}
+		if (Object.keys(updates).length > 0) settings.update(updates);
+	});
+}
@@ -0,0 +1,49 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Whether a request stays on this machine. Mirrors `is_local_url` in
+ * src-tauri/src/privacy.rs, which makes the actual decision.
+ */
+function isLocalUrl(input: RequestInfo | URL) {
+	const raw =
+		input instanceof Request ? input.url : input instanceof URL ? input.href : input;
+	let url: URL;
+	try {
+		url = new URL(raw, window.location.href);
+	} catch {
+		return true;
+	}
+	if (!['http:', 'https:', 'ws:', 'wss:'].includes(url.protocol)) return true;
+	const host = url.hostname.replace(/^\[|\]$/g, '').toLowerCase();
+	return (
+		host === 'localhost' ||
+		host.endsWith('.localhost') ||
+		host === '::1' ||
+		host.startsWith('127.')
+	);
+}
+
+/**
+ * Routes every webview `fetch` that would leave the machine through the Rust
+ * privacy check, so cloud SDKs (which call `fetch` directly) are blocked too
+ * while privacy mode is on. Local requests, including Tauri IPC, pass
+ * through untouched.
+ */
+export function enforcePrivacyMode() {
+	const originalFetch = window.fetch.bind(window);
+	window.fetch = async (input, init) => {
+		if (!isLocalUrl(input)) {
+			const url =
+				input instanceof Request
+					? input.url
+					: input instanceof URL
+						? input.href
+						: input;
+			await invoke('check_network_request', { url });
+		}
+		return originalFetch(input, init);
+	};
+	return () => {
+		window.fetch = originalFetch;
+	};
+}
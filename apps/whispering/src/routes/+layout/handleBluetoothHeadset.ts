@@ -0,0 +1,31 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Explains why audio sounds worse while recording from a Bluetooth headset:
+ * using its microphone switches it to the hands-free profile, which lowers
+ * the quality of both recording and playback.
+ */
+export function handleBluetoothHeadset() {
+	const unlisten = listen(
+		'recorder://bluetooth-headset',
+		({ payload }) => {
+			if (payload.switched) {
+				rpc.notify.info.execute({
+					title: `Recording from ${payload.alternative}`,
+					description: `Using ${payload.device}'s microphone would lower its audio quality, so another microphone is used instead.`,
+				});
+				return;
+			}
+			rpc.notify.warning.execute({
+				title: `${payload.device} is a Bluetooth headset`,
+				description: payload.alternative
+					? `Recording from it switches it to phone-call quality, for playback too. Select ${payload.alternative} or turn on "Avoid Bluetooth headset microphones" in recording settings to keep full quality.`
+					: 'Recording from it switches it to phone-call quality, for playback too.',
+			});
+		},
+	);
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api/core';
+import { extractErrorMessage } from 'wellcrafted/error';
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Warns when a recording was paused because its disk is nearly full, and
+ * offers to carry on once space has been freed. The audio recorded so far
+ * is kept in a valid file either way.
+ */
+export function handleDiskSpace() {
+	const unlisten = listen('recorder://disk-space-low', ({ payload }) => {
+		const available = Math.floor(payload.availableBytes / 1024 / 1024);
+		rpc.notify.warning.execute({
+			title: 'Recording paused: disk almost full',
+			description: `Only ${available} MB is left where recordings are saved. Free up some space and resume, or stop the recording to keep what was captured.`,
+			action: {
+				type: 'button',
+				label: 'Resume',
+				onClick: resume,
+			},
+		});
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
+
+function resume() {
+	return invoke('resume_recording').catch((error) => {
+		rpc.notify.error.execute({
+			title: 'Unable to resume recording',
+			description: extractErrorMessage(error),
+		});
+	});
+}
@@ -0,0 +1,19 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Warns when a recording's file came out with gaps because the disk
+ * couldn't keep up with the audio, so a garbled transcript isn't a mystery.
+ */
+export function handleDroppedAudio() {
+	const unlisten = listen('recorder://audio-dropped', ({ payload }) => {
+		const seconds = payload.seconds.toFixed(1);
+		rpc.notify.warning.execute({
+			title: 'Part of the recording is missing',
+			description: `About ${seconds}s of audio couldn't be saved because the disk fell behind. The rest of the recording was kept.`,
+		});
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
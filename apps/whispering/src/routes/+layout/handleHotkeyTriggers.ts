@@ -0,0 +1,24 @@
+import { commands } from '$lib/commands';
+import { listen } from '$lib/events';
+
+/**
+ * Runs the command bound to a double-tap, key sequence, mouse button or foot
+ * pedal. Rust matches these triggers itself, since the global shortcut
+ * plugin only knows single key combinations. Held buttons report both press
+ * and release, which each command filters like a regular shortcut.
+ */
+export function handleHotkeyTriggers() {
+	const unlisten = listen('hotkeys://trigger', ({ payload }) => {
+		const command = commands.find(({ id }) => id === payload.command);
+		if (!command) {
+			console.warn(`Unknown command for hotkey trigger: ${payload.command}`);
+			return;
+		}
+		if (command.on === 'Both' || command.on === payload.state) {
+			command.callback();
+		}
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
@@ -0,0 +1,18 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Tells the user when a recording a phone pushed to the inbox couldn't be
+ * transcribed. The audio is kept, so it waits in history for another try.
+ */
+export function handleInboxEvents() {
+	const unlisten = listen('inbox://transcription-failed', ({ payload }) => {
+		rpc.notify.error.execute({
+			title: "Couldn't transcribe a recording from your phone",
+			description: payload.message,
+		});
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
@@ -0,0 +1,43 @@
+import { rpc } from '$lib/query';
+import { takeInterruptedJobs } from '$lib/utils/pending-jobs';
+
+/**
+ * Picks up transcriptions the app quit or crashed in the middle of. They're
+ * resumed straight away, with the transcript put on the clipboard rather
+ * than pasted somewhere that's no longer expecting it; ones that have
+ * failed to finish a few launches running are only retried when asked, in
+ * case the file itself is what brings the app down. See
+ * src-tauri/src/jobs/pending.rs.
+ */
+export function handleInterruptedJobs() {
+	let cancelled = false;
+	takeInterruptedJobs().then(async ({ data, error }) => {
+		if (error) {
+			console.warn('Failed to check for interrupted transcriptions:', error);
+			return;
+		}
+		for (const job of data.resume) {
+			if (cancelled) return;
+			await rpc.commands.transcribeAudioFile.execute({ path: job.path });
+		}
+		if (data.retry.length === 0) return;
+		rpc.notify.warning.execute({
+			title: 'Transcriptions were interrupted',
+			description: `${data.retry.length} recording(s) didn't finish transcribing over several launches. Retry once you've checked the files, or they'll be offered again next time.`,
+			action: {
+				type: 'button',
+				label: 'Retry',
+				onClick: async () => {
+					for (const job of data.retry) {
+						await rpc.commands.transcribeAudioFile.execute({
+							path: job.path,
+						});
+					}
+				},
+			},
+		});
+	});
+	return () => {
+		cancelled = true;
+	};
+}
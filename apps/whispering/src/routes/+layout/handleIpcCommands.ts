@@ -0,0 +1,53 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+import { finishIpcCommand } from '$lib/utils/ipc';
+
+/**
+ * Runs recording commands from helper processes: `whispering toggle`, `start`
+ * and `stop`, the browser extension and paired phones. Rust relays them here
+ * since recording is driven by the frontend, and waits for the outcome to
+ * answer the helper; see src-tauri/src/ipc/server.rs.
+ *
+ * `start` and `stop` do nothing when already recording or not, so a
+ * launcher that fires them twice doesn't toggle by accident.
+ */
+export function handleIpcCommands() {
+	const unlisten = listen('ipc://command', async ({ payload }) => {
+		const error = await runCommand(payload.command);
+		const { error: finishError } = await finishIpcCommand(payload.id, error);
+		if (finishError)
+			console.warn('Failed to answer the IPC command:', finishError);
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
+
+/** Run `command`, returning why it failed if it did */
+async function runCommand(command: string): Promise<string | undefined> {
+	if (command === 'toggleRecording') {
+		const { error } =
+			await rpc.commands.toggleManualRecording.execute(undefined);
+		return error?.message;
+	}
+	const { data: recorderState, error: getRecorderStateError } =
+		await rpc.recorder.getRecorderState.fetch();
+	if (getRecorderStateError) return getRecorderStateError.message;
+	const recording = recorderState === 'RECORDING';
+	switch (command) {
+		case 'startRecording': {
+			if (recording) return;
+			const { error } =
+				await rpc.commands.startManualRecording.execute(undefined);
+			return error?.message;
+		}
+		case 'stopRecording': {
+			if (!recording) return;
+			const { error } =
+				await rpc.commands.stopManualRecording.execute(undefined);
+			return error?.message;
+		}
+		default:
+			return `Unknown command: ${command}`;
+	}
+}
@@ -0,0 +1,36 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+import { asDeviceIdentifier } from '$lib/services/types';
+import { settings } from '$lib/stores/settings.svelte';
+
+/**
+ * Starts recording when the user agrees to transcribe a detected meeting
+ * (or the app is on the always-transcribe list). Where Rust can capture
+ * system audio, the recording switches to it so both sides of the call are
+ * heard; elsewhere the selected microphone is used.
+ */
+export function handleMeetingStart() {
+	const unlisten = listen(
+		'meetings://start',
+		async ({ payload }) => {
+			if (payload.deviceIdentifier) {
+				settings.update({
+					'recording.method': 'cpal',
+					'recording.cpal.deviceId': asDeviceIdentifier(
+						payload.deviceIdentifier,
+					),
+				});
+			}
+			rpc.notify.info.execute({
+				title: `Transcribing ${payload.app} meeting`,
+				description: payload.deviceIdentifier
+					? 'Recording system audio until you stop the recording.'
+					: 'Recording from your microphone until you stop the recording.',
+			});
+			await rpc.commands.startManualRecording.execute(undefined);
+		},
+	);
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
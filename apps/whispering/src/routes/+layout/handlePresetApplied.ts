@@ -0,0 +1,34 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+import { settings } from '$lib/stores/settings.svelte';
+
+/**
+ * Applies the parts of a recording preset that live in frontend settings.
+ * Rust has already updated pre-roll and warm start; this covers sample
+ * rate, compression and whether recording is voice activated.
+ */
+export function handlePresetApplied() {
+	const unlisten = listen(
+		'presets://applied',
+		({ payload }) => {
+			settings.update({
+				'recording.mode': payload.vad ? 'vad' : 'manual',
+				'recording.cpal.sampleRate': String(payload.sampleRate) as
+					| '16000'
+					| '44100'
+					| '48000',
+				'transcription.compressionEnabled': payload.encoding === 'compressed',
+				...(payload.compressionOptions
+					? { 'transcription.compressionOptions': payload.compressionOptions }
+					: {}),
+			});
+			rpc.notify.info.execute({
+				title: `Recording preset: ${payload.name}`,
+				description: `${payload.sampleRate / 1000} kHz, ${payload.encoding === 'wav' ? 'uncompressed' : 'compressed'}${payload.vad ? ', voice activated' : ''}.`,
+			});
+		},
+	);
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
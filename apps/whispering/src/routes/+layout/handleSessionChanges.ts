@@ -0,0 +1,35 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+import { queryClient } from '$lib/query/_client';
+
+/**
+ * Catches up after switching users. Rust stops a recording when this login
+ * session stops being the one at the screen; once it's back, this offers to
+ * transcribe what was recorded. See src-tauri/src/session.
+ */
+export function handleSessionChanges() {
+	const unlisten = listen('session://changed', ({ payload }) => {
+		queryClient.invalidateQueries({
+			queryKey: rpc.recorder.getRecorderState.options().queryKey,
+		});
+		const paths = payload.stoppedRecordings;
+		if (!payload.active || paths.length === 0) return;
+		rpc.notify.info.execute({
+			title: 'Recording stopped while you were away',
+			description:
+				'Whispering stopped recording when another session took over the screen. What was recorded is kept.',
+			action: {
+				type: 'button',
+				label: 'Transcribe',
+				onClick: async () => {
+					for (const path of paths) {
+						await rpc.commands.transcribeAudioFile.execute({ path });
+					}
+				},
+			},
+		});
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
@@ -0,0 +1,22 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Tells the user when syncing with another desktop overwrote a change, since
+ * both devices edited the same transcript (or the settings) in between.
+ */
+export function handleSyncConflicts() {
+	const unlisten = listen('sync://conflict', ({ payload }) => {
+		const what = payload.entryId ? 'A transcript' : 'Settings';
+		rpc.notify.warning.execute({
+			title: `${what} changed here and on ${payload.device}`,
+			description:
+				payload.kept === 'remote'
+					? `Kept the newer version from ${payload.device}.`
+					: 'Kept the newer version from this device.',
+		});
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
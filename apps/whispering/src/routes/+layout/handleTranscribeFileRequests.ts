@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Transcribes files handed over by `whispering transcribe <file>`, e.g. from
+ * a file manager's "Open with" or a custom action, putting the transcript on
+ * the clipboard like the clipboard file hotkey does.
+ *
+ * Also transcribes what the daemon recorded while no window was open; see
+ * src-tauri/src/daemon.
+ */
+export function handleTranscribeFileRequests() {
+	const unlisten = listen(
+		'clipboard-audio://transcribe',
+		({ payload }) => {
+			rpc.commands.transcribeAudioFile.execute({ path: payload.path });
+		},
+	);
+	invoke<string[]>('take_daemon_recordings').then(async (paths) => {
+		for (const path of paths) {
+			await rpc.commands.transcribeAudioFile.execute({ path });
+		}
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
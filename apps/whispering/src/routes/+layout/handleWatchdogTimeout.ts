@@ -0,0 +1,17 @@
+import { listen } from '$lib/events';
+import { rpc } from '$lib/query';
+
+/**
+ * Stops the transcriptions the watchdog gave up on. Rust cancels its own
+ * jobs and resets the app state, but cloud providers are called from here,
+ * so their requests are abandoned here too; see src-tauri/src/watchdog.rs.
+ * The watchdog already told the user what happened.
+ */
+export function handleWatchdogTimeout() {
+	const unlisten = listen('watchdog://timeout', () => {
+		rpc.transcription.cancelInFlight();
+	});
+	return () => {
+		unlisten.then((fn) => fn());
+	};
+}
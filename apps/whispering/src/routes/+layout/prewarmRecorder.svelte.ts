@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api/core';
+import { onDestroy } from 'svelte';
+import { listen } from '$lib/events';
+import { settings } from '$lib/stores/settings.svelte';
+
+/**
+ * Opens the selected microphone ahead of the first recording, so warm start
+ * (see `RecorderState` in src-tauri/src/recorder) helps from the very first
+ * press of the shortcut. Rust ignores this mid-recording or with warm start
+ * off, and reopens the device when the selection changes, including when the
+ * device priority list picks another one after a hot-plug.
+ */
+export function prewarmRecorder() {
+	let preferredDeviceChanges = $state(0);
+	const unlisten = listen('recorder://preferred-device', () => {
+		preferredDeviceChanges++;
+	});
+	onDestroy(() => {
+		unlisten.then((fn) => fn());
+	});
+
+	$effect(() => {
+		void preferredDeviceChanges;
+		if (settings.value['recording.method'] !== 'cpal') return;
+		if (!settings.value['recording.cpal.warmStart']) return;
+		const deviceIdentifier = settings.value['recording.cpal.deviceId'];
+		if (!deviceIdentifier) return;
+		const sampleRate = Number.parseInt(
+			settings.value['recording.cpal.sampleRate'],
+			10,
+		);
+		invoke('prewarm_recording_device', { deviceIdentifier, sampleRate }).catch(
+			(error) => console.warn('Failed to pre-warm recording device:', error),
+		);
+	});
+}
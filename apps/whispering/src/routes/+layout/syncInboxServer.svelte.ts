@@ -0,0 +1,49 @@
+import { rpc } from '$lib/query';
+import { inbox } from '$lib/stores/inbox.svelte';
+import { settings } from '$lib/stores/settings.svelte';
+import { getBuildFeatures } from '$lib/utils/build-features';
+import {
+	type InboxConfig,
+	startInboxServer,
+	stopInboxServer,
+	toInboxConfig,
+} from '$lib/utils/inbox';
+
+/**
+ * Runs the inbox server while it's enabled, restarting it when the port,
+ * discoverability or the local model uploads are transcribed with changes.
+ * Builds without the HTTP API have no inbox, so nothing runs there.
+ */
+export function syncInboxServer() {
+	let httpApi = $state(false);
+	getBuildFeatures().then((features) => {
+		httpApi = features.httpApi;
+	});
+
+	// Serialized, so unrelated settings changes don't restart the server
+	const config = $derived(
+		settings.value['inbox.enabled']
+			? JSON.stringify(toInboxConfig(settings.value))
+			: null,
+	);
+
+	$effect(() => {
+		if (!httpApi) return;
+		if (config === null) {
+			stopInboxServer().then(() => {
+				inbox.status = null;
+			});
+			return;
+		}
+		const parsed = JSON.parse(config) as InboxConfig;
+		startInboxServer(parsed).then(({ data, error }) => {
+			inbox.status = data;
+			if (!error) return;
+			rpc.notify.error.execute({
+				title: 'Failed to start the inbox',
+				description: error.message,
+				action: { type: 'more-details', error },
+			});
+		});
+	});
+}
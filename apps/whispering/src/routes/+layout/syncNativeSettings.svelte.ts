@@ -0,0 +1,230 @@
+import { invoke } from '@tauri-apps/api/core';
+import { onDestroy } from 'svelte';
+import { toast } from 'svelte-sonner';
+import { type Command, commands } from '$lib/commands';
+import { APP_LANGUAGE_VALUES } from '$lib/constants/ui';
+import { listen, type NativeSettings } from '$lib/events';
+import { rpc } from '$lib/query';
+import type { Accelerator } from '$lib/services/global-shortcut-manager';
+import {
+	TRANSCRIPTION_SERVICE_IDS,
+	type TranscriptionServiceId,
+} from '$lib/services/transcription/registry';
+import type { Settings } from '$lib/settings';
+import { settings } from '$lib/stores/settings.svelte';
+
+function toNativeSettings(value: Settings): NativeSettings {
+	return {
+		closeToTray: value['system.closeToTray'],
+		startMinimized: value['system.startMinimized'],
+		soundFeedback: value['sound.enabled'],
+		autoPaste: value['transcription.writeToCursorOnSuccess'],
+		privacyMode: value['system.privacyMode'],
+		detectMeetings: value['system.detectMeetings'],
+		meetingAppsAllowed: value['system.meetingAppsAllowed'],
+		meetingAppsDenied: value['system.meetingAppsDenied'],
+		warmStart: value['recording.cpal.warmStart'],
+		preRollMs: Number(value['recording.cpal.preRollMs']),
+		audioBackend: value['recording.cpal.backend'],
+		avoidBluetoothMic: value['recording.cpal.avoidBluetoothMic'],
+		processingTimeoutSecs: Number(value['transcription.timeoutSeconds']),
+		screenReaderAnnouncements: value['system.screenReaderAnnouncements'],
+		trayTheme: value['system.trayTheme'],
+		trayThemeFolder: value['system.trayThemeFolder'],
+		customTrayIconIdle: value['system.customTrayIconIdle'],
+		customTrayIconRecording: value['system.customTrayIconRecording'],
+		customTrayIconProcessing: value['system.customTrayIconProcessing'],
+		customStartSound: value['sound.customStart'],
+		customStopSound: value['sound.customStop'],
+		language:
+			value['system.language'] === 'system' ? '' : value['system.language'],
+		timeZone: value['system.timeZone'],
+		timestampStyle: value['system.timestampStyle'],
+		fileNameTemplate: value['system.fileNameTemplate'],
+		minFreeDiskMb: Number(value['recording.cpal.minFreeDiskMb']),
+		hotkeys: Object.fromEntries(
+			commands.map((command) => [
+				command.id,
+				value[`shortcuts.global.${command.id}`] ?? '',
+			]),
+		),
+		providers: {
+			transcription: value['transcription.selectedTranscriptionService'],
+		},
+	};
+}
+
+function isPreRollMs(
+	value: string,
+): value is Settings['recording.cpal.preRollMs'] {
+	return ['0', '500', '1000', '1500', '2000'].includes(value);
+}
+
+function isMinFreeDiskMb(
+	value: string,
+): value is Settings['recording.cpal.minFreeDiskMb'] {
+	return ['0', '100', '500', '1000', '5000'].includes(value);
+}
+
+function isLanguage(value: string): value is Settings['system.language'] {
+	return (APP_LANGUAGE_VALUES as readonly string[]).includes(value);
+}
+
+function isTimeoutSeconds(
+	value: string,
+): value is Settings['transcription.timeoutSeconds'] {
+	return ['0', '60', '120', '300', '600'].includes(value);
+}
+
+function isTranscriptionService(value: string): value is TranscriptionServiceId {
+	return (TRANSCRIPTION_SERVICE_IDS as readonly string[]).includes(value);
+}
+
+/**
+ * Move `command`'s global shortcut from `previous` to `accelerator` after the
+ * settings file changed it, as the shortcut recorder does when one is typed
+ */
+async function rebindGlobalShortcut(
+	command: Command,
+	previous: string,
+	accelerator: string,
+) {
+	if (previous) {
+		await rpc.shortcuts.unregisterCommandGlobally.execute({
+			accelerator: previous as Accelerator,
+		});
+	}
+	if (!accelerator) return;
+	const { error } = await rpc.shortcuts.registerCommandGlobally.execute({
+		command,
+		accelerator: accelerator as Accelerator,
+	});
+	if (error) {
+		rpc.notify.error.execute({
+			title: `Failed to register the shortcut for "${command.title}"`,
+			description: `${accelerator} from the settings file is invalid or in use by another application.`,
+			action: { type: 'more-details', error },
+		});
+	}
+}
+
+function sameList(a: string[], b: string[]) {
+	return a.length === b.length && a.every((item, i) => item === b[i]);
+}
+
+/**
+ * Keeps the native settings mirror in sync in both directions: changes made
+ * in the app are pushed to Rust, and changes made from the tray menu or by
+ * editing the settings file are applied back to the settings store.
+ */
+export function syncNativeSettings() {
+	$effect(() => {
+		invoke('set_native_settings', {
+			settings: toNativeSettings(settings.value),
+		}).catch((error) => console.error('Failed to sync native settings:', error));
+	});
+
+	const unlisten = listen(
+		'settings://changed',
+		({ payload }) => {
+			const current = toNativeSettings(settings.value);
+			const updates: Partial<Settings> = {};
+			if (payload.closeToTray !== current.closeToTray)
+				updates['system.closeToTray'] = payload.closeToTray;
+			if (payload.startMinimized !== current.startMinimized)
+				updates['system.startMinimized'] = payload.startMinimized;
+			if (payload.autoPaste !== current.autoPaste)
+				updates['transcription.writeToCursorOnSuccess'] = payload.autoPaste;
+			if (payload.privacyMode !== current.privacyMode)
+				updates['system.privacyMode'] = payload.privacyMode;
+			if (payload.detectMeetings !== current.detectMeetings)
+				updates['system.detectMeetings'] = payload.detectMeetings;
+			if (!sameList(payload.meetingAppsAllowed, current.meetingAppsAllowed))
+				updates['system.meetingAppsAllowed'] = payload.meetingAppsAllowed;
+			if (!sameList(payload.meetingAppsDenied, current.meetingAppsDenied))
+				updates['system.meetingAppsDenied'] = payload.meetingAppsDenied;
+			if (payload.warmStart !== current.warmStart)
+				updates['recording.cpal.warmStart'] = payload.warmStart;
+			const preRollMs = String(payload.preRollMs);
+			if (payload.preRollMs !== current.preRollMs && isPreRollMs(preRollMs))
+				updates['recording.cpal.preRollMs'] = preRollMs;
+			if (payload.audioBackend !== current.audioBackend)
+				updates['recording.cpal.backend'] = payload.audioBackend;
+			if (payload.avoidBluetoothMic !== current.avoidBluetoothMic)
+				updates['recording.cpal.avoidBluetoothMic'] =
+					payload.avoidBluetoothMic;
+			const minFreeDiskMb = String(payload.minFreeDiskMb);
+			if (
+				payload.minFreeDiskMb !== current.minFreeDiskMb &&
+				isMinFreeDiskMb(minFreeDiskMb)
+			)
+				updates['recording.cpal.minFreeDiskMb'] = minFreeDiskMb;
+			const timeoutSeconds = String(payload.processingTimeoutSecs);
+			if (
+				payload.processingTimeoutSecs !== current.processingTimeoutSecs &&
+				isTimeoutSeconds(timeoutSeconds)
+			)
+				updates['transcription.timeoutSeconds'] = timeoutSeconds;
+			if (
+				payload.screenReaderAnnouncements !== current.screenReaderAnnouncements
+			)
+				updates['system.screenReaderAnnouncements'] =
+					payload.screenReaderAnnouncements;
+			if (payload.trayTheme !== current.trayTheme)
+				updates['system.trayTheme'] = payload.trayTheme;
+			if (payload.trayThemeFolder !== current.trayThemeFolder)
+				updates['system.trayThemeFolder'] = payload.trayThemeFolder;
+			if (payload.customTrayIconIdle !== current.customTrayIconIdle)
+				updates['system.customTrayIconIdle'] = payload.customTrayIconIdle;
+			if (payload.customTrayIconRecording !== current.customTrayIconRecording)
+				updates['system.customTrayIconRecording'] =
+					payload.customTrayIconRecording;
+			if (
+				payload.customTrayIconProcessing !== current.customTrayIconProcessing
+			)
+				updates['system.customTrayIconProcessing'] =
+					payload.customTrayIconProcessing;
+			if (payload.customStartSound !== current.customStartSound)
+				updates['sound.customStart'] = payload.customStartSound;
+			if (payload.customStopSound !== current.customStopSound)
+				updates['sound.customStop'] = payload.customStopSound;
+			if (payload.language !== current.language) {
+				const language = payload.language || 'system';
+				if (isLanguage(language)) updates['system.language'] = language;
+			}
+			if (payload.timeZone !== current.timeZone)
+				updates['system.timeZone'] = payload.timeZone;
+			if (payload.timestampStyle !== current.timestampStyle)
+				updates['system.timestampStyle'] = payload.timestampStyle;
+			if (payload.fileNameTemplate !== current.fileNameTemplate)
+				updates['system.fileNameTemplate'] = payload.fileNameTemplate;
+			if (payload.soundFeedback !== current.soundFeedback)
+				updates['sound.enabled'] = payload.soundFeedback;
+			for (const command of commands) {
+				const accelerator = payload.hotkeys[command.id];
+				const previous = current.hotkeys[command.id] ?? '';
+				if (accelerator === undefined || accelerator === previous) continue;
+				updates[`shortcuts.global.${command.id}`] = accelerator || null;
+				rebindGlobalShortcut(command, previous, accelerator);
+			}
+			const transcription = payload.providers.transcription;
+			if (
+				transcription !== current.providers.transcription &&
+				isTranscriptionService(transcription)
+			)
+				updates['transcription.selectedTranscriptionService'] = transcription;
+			if (Object.keys(updates).length > 0) settings.update(updates);
+		},
+	);
+
+	const unlistenReloaded = listen('settings://reloaded', () => {
+		toast.info('Settings reloaded', {
+			description: 'The settings file was changed outside Whispering.',
+		});
+	});
+
+	onDestroy(() => {
+		unlisten.then((fn) => fn());
+		unlistenReloaded.then((fn) => fn());
+	});
+}